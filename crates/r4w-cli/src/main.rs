@@ -16,12 +16,21 @@ use r4w_core::benchmark::{BenchmarkMetrics, BenchmarkReceiver, BenchmarkReport,
 use r4w_core::demodulation::Demodulator;
 use r4w_core::mesh::{LoRaMesh, LoRaMeshConfig, MeshPhy, ModemPreset, NodeId, Region};
 use r4w_core::modulation::Modulator;
+use r4w_core::observe::{Metrics, MetricsServer};
 use r4w_core::waveform::adsb::{AdsbMessage, CprDecoder};
 use r4w_core::waveform::ppm::PPM;
 use r4w_core::params::LoRaParams;
 use r4w_core::types::IQSample;
 use r4w_core::waveform::{CommonParams, WaveformFactory};
 use r4w_sim::{Channel, ChannelConfig, ChannelModel};
+use r4w_sim::impairments::{
+    signal_to_distortion_db, AdcConfig, ImpairmentConfig, Impairments, IqImbalance, PaConfig, PaModel,
+    PhaseNoiseConfig,
+};
+use r4w_sim::interference::InterfererConfig;
+use r4w_sim::monitor::StreamMonitor;
+use r4w_sim::scoring::PacketScenario;
+use r4w_core::propagation::{LinkBudget, PathLossModel};
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Write};
 use std::path::PathBuf;
@@ -69,13 +78,25 @@ enum Commands {
         /// Output format (f32, f64, i16)
         #[arg(long, default_value = "f32")]
         format: String,
+
+        /// Transmit on a HAL device instead of writing a file (e.g. "sim://snr=20")
+        #[arg(long)]
+        device: Option<String>,
     },
 
     /// Receive (demodulate) I/Q samples to a message
     Rx {
         /// Input file with I/Q samples
-        #[arg(short, long)]
-        input: PathBuf,
+        #[arg(short, long, required_unless_present = "device")]
+        input: Option<PathBuf>,
+
+        /// Capture from a HAL device instead of a file (e.g. "sim://snr=10,source=tx.iq")
+        #[arg(long, conflicts_with = "input")]
+        device: Option<String>,
+
+        /// Capture duration in milliseconds when reading from a device
+        #[arg(long, default_value = "500")]
+        duration: f64,
 
         /// Spreading factor (7-12)
         #[arg(long, default_value = "7")]
@@ -92,6 +113,10 @@ enum Commands {
         /// Input format (f32, f64, i16)
         #[arg(long, default_value = "f32")]
         format: String,
+
+        /// Serve Prometheus metrics on this port (kept up after decoding until Ctrl+C)
+        #[arg(long)]
+        metrics_port: Option<u16>,
     },
 
     /// Simulate a complete TX -> Channel -> RX pipeline
@@ -127,6 +152,12 @@ enum Commands {
         /// Save intermediate files
         #[arg(long)]
         save_samples: bool,
+
+        #[command(flatten)]
+        impairments: ImpairmentArgs,
+
+        #[command(flatten)]
+        interference: InterferenceArgs,
     },
 
     /// Generate test chirps for analysis
@@ -169,6 +200,72 @@ enum Commands {
         /// Payload length in bytes
         #[arg(long, default_value = "10")]
         payload_len: usize,
+
+        /// Show how a waveform would stream on a device instead
+        /// (e.g. --plan LoRa rtltcp://127.0.0.1:1234)
+        #[arg(long, num_args = 2, value_names = ["WAVEFORM", "DEVICE_URI"])]
+        plan: Option<Vec<String>>,
+
+        /// Waveform sample rate in Hz (with --plan)
+        #[arg(long, default_value = "125000")]
+        sample_rate: f64,
+
+        /// Carrier frequency in Hz (with --plan; default: the device's current frequency)
+        #[arg(long)]
+        freq: Option<f64>,
+
+        /// LO offset in Hz (with --plan; default: automatic, 0 disables)
+        #[arg(long, allow_negative_numbers = true)]
+        lo_offset: Option<f64>,
+
+        /// Device sample format: cf32, ci16, ci8, cu8 (with --plan; default: the device's native format)
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Compute a LoRa link budget, margin and range for a path loss model
+    #[command(allow_negative_numbers = true)]
+    LinkBudget {
+        /// Path loss model, e.g. "free-space", "two-ray:ht=10,hr=1.5",
+        /// "log-distance:n=2.8,sigma=6", "hata:hb=30,env=urban", "p1546:h1=50,loc=90"
+        #[arg(long, default_value = "free-space")]
+        model: String,
+
+        /// Link distance in meters
+        #[arg(short, long, default_value = "1000")]
+        distance: f64,
+
+        /// Carrier frequency in Hz
+        #[arg(short, long, default_value = "915e6")]
+        freq: f64,
+
+        /// Transmit power in dBm
+        #[arg(long, default_value = "14")]
+        tx_power: f64,
+
+        /// Transmit antenna gain in dBi
+        #[arg(long, default_value = "0")]
+        tx_gain: f64,
+
+        /// Receive antenna gain in dBi
+        #[arg(long, default_value = "0")]
+        rx_gain: f64,
+
+        /// Feedline loss at each end in dB
+        #[arg(long, default_value = "0")]
+        cable_loss: f64,
+
+        /// Additional losses (fade margin, body loss) in dB
+        #[arg(long, default_value = "0")]
+        other_loss: f64,
+
+        /// Spreading factor
+        #[arg(long, default_value = "7")]
+        sf: u8,
+
+        /// Bandwidth in kHz
+        #[arg(long, default_value = "125")]
+        bw: u32,
     },
 
     /// Analyze I/Q samples (spectrum, waterfall, statistics, peaks)
@@ -189,7 +286,7 @@ enum Commands {
         #[arg(long, default_value = "0")]
         samples: usize,
 
-        /// Analysis mode: basic, spectrum, waterfall, stats, peaks
+        /// Analysis mode: basic, spectrum, waterfall, stats, peaks, classify, detect, scd, quality, mask
         #[arg(long, default_value = "basic")]
         mode: String,
 
@@ -225,7 +322,7 @@ enum Commands {
         #[arg(long, default_value = "viridis")]
         colormap: String,
 
-        /// Peak detection threshold in dB above noise floor
+        /// Peak/emission detection threshold in dB above noise floor
         #[arg(long, default_value = "10")]
         threshold: f64,
 
@@ -236,6 +333,38 @@ enum Commands {
         /// Waterfall height (rows) for PNG output
         #[arg(long, default_value = "512")]
         height: usize,
+
+        /// Directory to extract each detection into as baseband SigMF (detect mode)
+        #[arg(long)]
+        extract: Option<PathBuf>,
+
+        /// Write detections back as annotations on the SigMF input (detect mode)
+        #[arg(long)]
+        annotate: bool,
+
+        /// Spectral correlation estimator: fam, ssca (scd mode)
+        #[arg(long, default_value = "fam")]
+        scd_method: String,
+
+        /// Number of frequency channels for spectral correlation (scd mode)
+        #[arg(long, default_value = "64")]
+        channels: usize,
+
+        /// Run the cyclostationary feature detector at this false alarm rate (scd mode)
+        #[arg(long)]
+        pfa: Option<f64>,
+
+        /// Reference waveform the capture was modulated with (quality mode, e.g. QPSK)
+        #[arg(long)]
+        waveform: Option<String>,
+
+        /// Spectrum mask: etsi-868, fcc-15.247, 802.11 or a YAML mask file (mask mode)
+        #[arg(long)]
+        mask: Option<String>,
+
+        /// Power in dBm corresponding to 0 dBFS, including antenna gain (mask mode)
+        #[arg(long, default_value = "0", allow_hyphen_values = true)]
+        ref_level: f64,
     },
 
     /// Simulate a waveform (AM, FM, OOK, FSK, PSK, QAM)
@@ -306,6 +435,10 @@ enum Commands {
         /// List available waveforms
         #[arg(long)]
         list: bool,
+
+        /// Serve Prometheus metrics on this port while running
+        #[arg(long)]
+        metrics_port: Option<u16>,
     },
 
     /// Generate and send test I/Q samples via UDP
@@ -355,6 +488,25 @@ enum Commands {
         repeat: bool,
     },
 
+    /// Serve a HAL device to rtl_tcp clients (gqrx, SDR++, rtltcp://)
+    RtlTcpServer {
+        /// Device to expose (e.g. "sim://snr=20", "file://capture.sigmf-meta")
+        #[arg(short, long, default_value = "sim://")]
+        device: String,
+
+        /// Address to listen on
+        #[arg(short, long, default_value = "0.0.0.0:1234")]
+        listen: String,
+
+        /// Initial sample rate in Hz (default: keep the device rate)
+        #[arg(short, long)]
+        sample_rate: Option<f64>,
+
+        /// Do not pace output to the sample rate
+        #[arg(long)]
+        no_pace: bool,
+    },
+
     /// Run as a remote agent daemon (for Raspberry Pi deployment)
     Agent {
         /// Port to listen on for control connections
@@ -364,6 +516,10 @@ enum Commands {
         /// Run in foreground (don't daemonize)
         #[arg(long)]
         foreground: bool,
+
+        /// Serve agent metrics on this port; receivers it starts serve theirs on the next port
+        #[arg(long)]
+        metrics_port: Option<u16>,
     },
 
     /// Connect to a remote agent
@@ -389,6 +545,12 @@ enum Commands {
         command: AdsbCommand,
     },
 
+    /// IEEE 802.15.4 / Zigbee frame commands
+    Zigbee {
+        #[command(subcommand)]
+        command: ZigbeeCommand,
+    },
+
     /// Generate shell completions
     Completions {
         /// Shell to generate completions for
@@ -415,6 +577,33 @@ enum Commands {
         #[arg(long, default_value = "2.5")]
         snr_step: f64,
 
+        /// Swept parameter (snr, jsr)
+        #[arg(long, default_value = "snr")]
+        sweep: String,
+
+        /// Fixed SNR in dB when sweeping JSR
+        #[arg(long, default_value = "30")]
+        snr: f64,
+
+        /// Minimum jammer-to-signal ratio in dB (JSR sweep)
+        #[arg(long, default_value = "-20")]
+        jsr_min: f64,
+
+        /// Maximum jammer-to-signal ratio in dB (JSR sweep)
+        #[arg(long, default_value = "10")]
+        jsr_max: f64,
+
+        /// JSR step in dB
+        #[arg(long, default_value = "2.5")]
+        jsr_step: f64,
+
+        /// Packet size in bytes for packet error rate
+        #[arg(long, default_value = "32")]
+        packet_bytes: usize,
+
+        #[command(flatten)]
+        interference: InterferenceArgs,
+
         /// Number of bits per test point
         #[arg(long, default_value = "10000")]
         bits: usize,
@@ -463,8 +652,12 @@ enum Commands {
         waveform: Option<String>,
 
         /// Generate test signal instead of recording from device
+        /// (tone, chirp, noise, or packets of --waveform with ground-truth annotations)
         #[arg(long)]
         generate: Option<String>,
+
+        #[command(flatten)]
+        packets: PacketArgs,
     },
 
     /// Playback I/Q samples from SigMF file
@@ -492,6 +685,10 @@ enum Commands {
         /// Apply waveform demodulation
         #[arg(long)]
         demodulate: Option<String>,
+
+        /// Serve Prometheus metrics on this port (kept up after playback until Ctrl+C)
+        #[arg(long)]
+        metrics_port: Option<u16>,
     },
 
     /// Convert between signal file formats
@@ -508,9 +705,11 @@ enum Commands {
         #[arg(long)]
         from: Option<String>,
 
-        /// Output format (sigmf, raw-cf32, raw-ci16)
-        #[arg(long, default_value = "sigmf")]
-        to: String,
+        /// Output format: sigmf, sigmf-archive, wav, gnuradio,
+        /// gnuradio-detached, hackrf, rtlsdr, raw-cf32, raw-ci16, raw-ci8, raw-cu8
+        /// (from the output extension if not specified, else sigmf)
+        #[arg(long)]
+        to: Option<String>,
 
         /// Sample rate (required for raw input)
         #[arg(long)]
@@ -519,21 +718,71 @@ enum Commands {
         /// Center frequency (optional)
         #[arg(long)]
         frequency: Option<f64>,
+
+        /// Check the input's SHA-512 checksum before converting
+        #[arg(long)]
+        verify: bool,
+    },
+
+    /// Score demodulator decodes against a recording's ground-truth annotations
+    Score {
+        /// Recording with ground-truth annotations (from `record --generate packets`)
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Demodulator (defaults to the annotated waveform)
+        #[arg(short, long)]
+        waveform: Option<String>,
+
+        /// Burst detection threshold above the noise floor in dB
+        #[arg(long, default_value = "1.5")]
+        threshold_db: f64,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Fail if the packet error rate exceeds this (0..1)
+        #[arg(long)]
+        max_per: Option<f64>,
+
+        /// Fail if the detection rate is below this (0..1)
+        #[arg(long)]
+        min_detection: Option<f64>,
+    },
+
+    /// Generate a labeled synthetic I/Q dataset for classifier training
+    #[command(allow_negative_numbers = true)]
+    Dataset {
+        /// Output directory
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Output format (npy, sigmf)
+        #[arg(short, long, default_value = "npy")]
+        format: String,
+
+        #[command(flatten)]
+        sweep: DatasetArgs,
     },
 
-    /// Display or serve Prometheus metrics
+    /// Display metrics of a running command started with --metrics-port
     Metrics {
         /// Output format (text, json, prometheus)
         #[arg(short, long, default_value = "text")]
         format: String,
 
-        /// Start HTTP server for Prometheus scraping
-        #[arg(long)]
-        serve: bool,
+        /// Host of the metrics endpoint
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
 
-        /// Port for metrics server
+        /// Port of the metrics endpoint
         #[arg(short, long, default_value = "9090")]
         port: u16,
+
+        /// Removed; serving moved to --metrics-port on rx, playback, benchmark and agent
+        #[arg(long, hide = true)]
+        serve: bool,
     },
 }
 
@@ -779,6 +1028,55 @@ enum AdsbCommand {
     },
 }
 
+#[derive(Subcommand)]
+enum ZigbeeCommand {
+    /// Decode 802.15.4 frames from I/Q sample file
+    File {
+        /// Input file with I/Q samples
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Sample rate in Hz
+        #[arg(short, long, default_value = "8000000")]
+        sample_rate: f64,
+
+        /// Write decoded frames to a PCAP file (link type 802.15.4 with FCS)
+        #[arg(long)]
+        pcap: Option<PathBuf>,
+
+        /// Show all frames (including FCS failures)
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Generate a test 802.15.4 signal (beacon, data frame and ACK)
+    Generate {
+        /// Output file for I/Q samples
+        #[arg(short, long, default_value = "zigbee_test.iq")]
+        output: PathBuf,
+
+        /// PAN identifier (hex)
+        #[arg(long, default_value = "1A62")]
+        pan_id: String,
+
+        /// Destination short address (hex)
+        #[arg(long, default_value = "0000")]
+        dst: String,
+
+        /// Source short address (hex)
+        #[arg(long, default_value = "0001")]
+        src: String,
+
+        /// Data frame payload
+        #[arg(short, long, default_value = "Hello Zigbee")]
+        payload: String,
+
+        /// Sample rate in Hz
+        #[arg(short, long, default_value = "8000000")]
+        sample_rate: f64,
+    },
+}
+
 fn validate_sf(sf: u8) -> Result<u8> {
     if (5..=12).contains(&sf) {
         Ok(sf)
@@ -804,6 +1102,277 @@ fn validate_cr(cr: u8) -> Result<u8> {
     }
 }
 
+/// RF front-end impairment options for channel simulation
+#[derive(clap::Args, Debug, Clone, Default)]
+struct ImpairmentArgs {
+    /// JSON file with a full impairment configuration (flags below override it)
+    #[arg(long)]
+    impairments: Option<PathBuf>,
+
+    /// Phase noise mask as offset:dBc/Hz pairs (e.g. "1e3:-80,1e4:-90,1e5:-110")
+    #[arg(long)]
+    phase_noise: Option<String>,
+
+    /// IQ gain imbalance in dB
+    #[arg(long)]
+    iq_gain_db: Option<f64>,
+
+    /// IQ phase imbalance in degrees
+    #[arg(long)]
+    iq_phase_deg: Option<f64>,
+
+    /// DC offset as "i,q"
+    #[arg(long)]
+    dc_offset: Option<String>,
+
+    /// PA model (rapp, saleh)
+    #[arg(long)]
+    pa: Option<String>,
+
+    /// PA input back-off in dB
+    #[arg(long, default_value = "6.0")]
+    pa_backoff: f64,
+
+    /// ADC resolution in bits
+    #[arg(long)]
+    adc_bits: Option<u32>,
+
+    /// ADC full-scale amplitude per I/Q component
+    #[arg(long, default_value = "1.5")]
+    adc_full_scale: f64,
+}
+
+impl ImpairmentArgs {
+    fn to_config(&self) -> Result<ImpairmentConfig> {
+        let mut config = match &self.impairments {
+            Some(path) => {
+                let text = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {:?}", path))?;
+                serde_json::from_str(&text).context("Invalid impairment configuration")?
+            }
+            None => ImpairmentConfig::default(),
+        };
+
+        if let Some(mask) = &self.phase_noise {
+            config.phase_noise = Some(PhaseNoiseConfig::parse(mask).map_err(anyhow::Error::msg)?);
+        }
+        if self.iq_gain_db.is_some() || self.iq_phase_deg.is_some() {
+            config.iq_imbalance = Some(IqImbalance {
+                gain_db: self.iq_gain_db.unwrap_or(0.0),
+                phase_deg: self.iq_phase_deg.unwrap_or(0.0),
+            });
+        }
+        if let Some(dc) = &self.dc_offset {
+            let (i, q) = dc
+                .split_once(',')
+                .ok_or_else(|| anyhow::anyhow!("DC offset must be \"i,q\""))?;
+            config.dc_offset = Some((i.trim().parse()?, q.trim().parse()?));
+        }
+        if let Some(pa) = &self.pa {
+            let model = match pa.to_lowercase().as_str() {
+                "rapp" => PaModel::rapp(1.0, 2.0),
+                "saleh" => PaModel::saleh(),
+                _ => anyhow::bail!("Unknown PA model: {}. Use rapp or saleh", pa),
+            };
+            config.pa = Some(PaConfig { model, input_backoff_db: self.pa_backoff });
+        }
+        if let Some(bits) = self.adc_bits {
+            config.adc = Some(AdcConfig { bits, full_scale: self.adc_full_scale });
+        }
+
+        config
+            .validate()
+            .map_err(|e| anyhow::anyhow!("Invalid impairment configuration: {}", e))?;
+        Ok(config)
+    }
+}
+
+/// Interference options for channel simulation
+#[derive(clap::Args, Debug, Clone, Default)]
+struct InterferenceArgs {
+    /// Add a jammer, e.g. "cw:jsr=0,freq=1e3", "chirp:start=-2e4,stop=2e4",
+    /// "noise:bw=1e4", "pulsed:width=1e-4,pri=1e-3", "follower:bw=5e3",
+    /// "cochannel:waveform=QPSK" (repeatable)
+    #[arg(long = "jammer")]
+    jammers: Vec<String>,
+
+    /// JSON file with a list of interferers (added before --jammer entries)
+    #[arg(long)]
+    interference: Option<PathBuf>,
+}
+
+impl InterferenceArgs {
+    fn to_config(&self) -> Result<Vec<InterfererConfig>> {
+        let mut interferers: Vec<InterfererConfig> = match &self.interference {
+            Some(path) => {
+                let text = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {:?}", path))?;
+                let interferers: Vec<InterfererConfig> =
+                    serde_json::from_str(&text).context("Invalid interference configuration")?;
+                for (i, interferer) in interferers.iter().enumerate() {
+                    interferer
+                        .validate()
+                        .map_err(|e| anyhow::anyhow!("Invalid interferer {} in {:?}: {}", i, path, e))?;
+                }
+                interferers
+            }
+            None => Vec::new(),
+        };
+        for spec in &self.jammers {
+            interferers.push(
+                InterfererConfig::parse(spec)
+                    .map_err(|e| anyhow::anyhow!("Invalid --jammer '{}': {}", spec, e))?,
+            );
+        }
+        Ok(interferers)
+    }
+}
+
+/// Ground-truth packet options for `record --generate packets`
+#[derive(clap::Args, Debug, Clone)]
+struct PacketArgs {
+    /// Number of packets to generate
+    #[arg(long, default_value = "10")]
+    packets: usize,
+
+    /// Payload length in bytes
+    #[arg(long, default_value = "16")]
+    payload_len: usize,
+
+    /// Per-sample SNR in dB (clean signal if not specified)
+    #[arg(long)]
+    snr: Option<f64>,
+
+    /// Mean gap between packets in samples (one packet length if not specified)
+    #[arg(long)]
+    gap: Option<usize>,
+
+    /// Random seed (random if not specified; recorded in the annotations)
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+impl PacketArgs {
+    fn to_scenario(&self, waveform: &str, sample_rate: f64) -> PacketScenario {
+        let mut scenario = PacketScenario::new(waveform, sample_rate)
+            .with_packets(self.packets)
+            .with_payload_len(self.payload_len)
+            .with_snr_db(self.snr)
+            .with_seed(self.seed.unwrap_or_else(rand::random));
+        if let Some(gap) = self.gap {
+            scenario = scenario.with_gap_samples(gap);
+        }
+        scenario
+    }
+}
+
+/// Sweep options for dataset generation
+#[derive(clap::Args, Debug, Clone)]
+struct DatasetArgs {
+    /// JSON dataset configuration (replaces the sweep flags below; --seed still applies)
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Waveforms to include (comma-separated, e.g., "BPSK,QPSK,16QAM")
+    #[arg(short, long, default_value = "BPSK,QPSK,8PSK,16QAM,64QAM,BFSK,4FSK,OOK,ASK,4ASK")]
+    waveforms: String,
+
+    /// Minimum SNR in dB
+    #[arg(long, default_value = "-10")]
+    snr_min: f64,
+
+    /// Maximum SNR in dB
+    #[arg(long, default_value = "20")]
+    snr_max: f64,
+
+    /// SNR step in dB
+    #[arg(long, default_value = "2")]
+    snr_step: f64,
+
+    /// Examples per waveform and SNR
+    #[arg(short = 'n', long, default_value = "100")]
+    examples: usize,
+
+    /// Samples per example
+    #[arg(short, long, default_value = "1024")]
+    length: usize,
+
+    /// Sample rate in Hz
+    #[arg(short, long, default_value = "48000")]
+    sample_rate: f64,
+
+    /// Maximum carrier frequency offset in Hz (uniform in +/-)
+    #[arg(long, default_value = "0")]
+    max_cfo: f64,
+
+    /// Fading models, one picked per example (comma-separated: none, rayleigh, epa, eva, etu)
+    #[arg(long, default_value = "none")]
+    fading: String,
+
+    /// Maximum Doppler frequency in Hz for fading models
+    #[arg(long, default_value = "10")]
+    doppler: f64,
+
+    /// Keep raw signal power instead of normalizing each example
+    #[arg(long)]
+    no_normalize: bool,
+
+    /// Random seed (random if not specified; recorded in the manifest)
+    #[arg(long)]
+    seed: Option<u64>,
+
+    #[command(flatten)]
+    impairments: ImpairmentArgs,
+}
+
+impl DatasetArgs {
+    fn to_config(&self) -> Result<r4w_sim::dataset::DatasetConfig> {
+        use r4w_sim::dataset::{DatasetConfig, Fading};
+
+        let mut config = match &self.config {
+            Some(path) => {
+                let text = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read dataset config {:?}", path))?;
+                serde_json::from_str(&text).with_context(|| format!("Invalid dataset config {:?}", path))?
+            }
+            None => {
+                if self.snr_step <= 0.0 || self.snr_max < self.snr_min {
+                    anyhow::bail!("Invalid SNR range {}..{} step {}", self.snr_min, self.snr_max, self.snr_step);
+                }
+                let steps = ((self.snr_max - self.snr_min) / self.snr_step + 1e-9).floor() as usize;
+                let fading = self
+                    .fading
+                    .split(',')
+                    .map(|name| {
+                        Fading::parse(name, self.doppler)
+                            .ok_or_else(|| anyhow::anyhow!("Unknown fading model: {}. Use none, rayleigh, epa, eva or etu", name))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let impairments = self.impairments.to_config()?;
+
+                DatasetConfig {
+                    waveforms: self.waveforms.split(',').map(|w| w.trim().to_string()).collect(),
+                    sample_rate: self.sample_rate,
+                    example_len: self.length,
+                    examples_per_point: self.examples,
+                    snr_db: (0..=steps).map(|i| self.snr_min + i as f64 * self.snr_step).collect(),
+                    max_cfo_hz: self.max_cfo,
+                    fading,
+                    impairments: if impairments.is_empty() { Vec::new() } else { vec![impairments] },
+                    normalize: !self.no_normalize,
+                    seed: 0,
+                }
+            }
+        };
+        config.seed = match (self.seed, &self.config) {
+            (Some(seed), _) => seed,
+            (None, Some(_)) => config.seed,
+            (None, None) => rand::random(),
+        };
+        Ok(config)
+    }
+}
+
 fn parse_channel_model(model: &str) -> Result<ChannelModel> {
     match model.to_lowercase().as_str() {
         "awgn" => Ok(ChannelModel::Awgn),
@@ -904,6 +1473,95 @@ fn read_samples_f32(path: &PathBuf) -> Result<Vec<IQSample>> {
     Ok(samples)
 }
 
+/// Open a HAL device by URI and negotiate a stream for `sample_rate`
+fn open_device(
+    uri: &str,
+    sample_rate: f64,
+) -> Result<(Box<dyn r4w_sim::SdrDeviceExt>, r4w_sim::hal::StreamAdapter)> {
+    let registry = r4w_sim::hal::create_default_registry();
+    let mut device = registry
+        .create(uri)
+        .map_err(|e| anyhow::anyhow!("Failed to open device '{}': {}", uri, e))?;
+    let adapter = r4w_sim::hal::StreamAdapter::negotiate(
+        device.as_mut(),
+        sample_rate,
+        &r4w_sim::hal::AdapterOptions::default(),
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to configure device: {}", e))?;
+    info!("Opened device: {}", device.name());
+    for line in adapter.plan().to_string().lines() {
+        info!("  {}", line);
+    }
+    Ok((device, adapter))
+}
+
+fn transmit_to_device(uri: &str, sample_rate: f64, samples: &[IQSample]) -> Result<()> {
+    let (mut device, mut adapter) = open_device(uri, sample_rate)?;
+    let mut samples = adapter.to_device(samples);
+    samples.extend(adapter.flush_to_device());
+    let mut stream = device
+        .create_tx_stream(r4w_sim::StreamConfig::default())
+        .map_err(|e| anyhow::anyhow!("Failed to create TX stream: {}", e))?;
+    stream.start().map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let mut sent = 0;
+    while sent < samples.len() {
+        let end = (sent + 4096).min(samples.len());
+        sent += stream
+            .write(&samples[sent..end], None, Duration::from_secs(1))
+            .map_err(|e| anyhow::anyhow!("TX write failed: {}", e))?;
+    }
+    stream.stop().map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let status = stream.status();
+    info!(
+        "Transmitted {} samples (underflows: {}, late: {})",
+        status.samples_processed, status.underflow_count, status.late_count
+    );
+    Ok(())
+}
+
+fn capture_from_device(uri: &str, sample_rate: f64, num_samples: usize, monitor: &StreamMonitor) -> Result<Vec<IQSample>> {
+    let (mut device, mut adapter) = open_device(uri, sample_rate)?;
+    let device_samples = (num_samples as f64 * adapter.plan().ratio()).ceil() as usize;
+    let mut stream = device
+        .create_rx_stream(r4w_sim::StreamConfig::default())
+        .map_err(|e| anyhow::anyhow!("Failed to create RX stream: {}", e))?;
+    stream.start().map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let mut buffer = vec![IQSample::new(0.0, 0.0); 4096];
+    let mut samples = Vec::with_capacity(num_samples);
+    let mut received = 0;
+    while received < device_samples {
+        let len = (device_samples - received).min(buffer.len());
+        let start = Instant::now();
+        let (n, _) = stream.read(&mut buffer[..len], Duration::from_secs(1)).map_err(|e| {
+            monitor.metrics().device_errors.inc();
+            anyhow::anyhow!("RX read failed: {}", e)
+        })?;
+        if n == 0 {
+            warn!("Device returned no samples; stopping capture");
+            break;
+        }
+        samples.extend(adapter.from_device(&buffer[..n]));
+        received += n;
+
+        let status = stream.status();
+        monitor.record_block(&buffer[..n], start.elapsed(), status.buffer_level);
+        monitor.record_stream_status(&status);
+    }
+    stream.stop().map_err(|e| anyhow::anyhow!("{}", e))?;
+    samples.extend(adapter.flush_from_device());
+    samples.truncate(num_samples);
+
+    let status = stream.status();
+    monitor.record_stream_status(&status);
+    if status.overflow_count > 0 {
+        warn!("{} RX overflows during capture", status.overflow_count);
+    }
+    Ok(samples)
+}
+
 fn cmd_tx(
     message: String,
     output: PathBuf,
@@ -911,6 +1569,7 @@ fn cmd_tx(
     bw: u32,
     cr: u8,
     _format: String,
+    device: Option<String>,
 ) -> Result<()> {
     validate_sf(sf)?;
     validate_cr(cr)?;
@@ -934,13 +1593,27 @@ fn cmd_tx(
         samples.len() as f64 / params.sample_rate * 1000.0
     );
 
-    write_samples_f32(&samples, &output)?;
-    info!("Wrote samples to {:?}", output);
+    if let Some(uri) = device {
+        transmit_to_device(&uri, params.sample_rate, &samples)?;
+    } else {
+        write_samples_f32(&samples, &output)?;
+        info!("Wrote samples to {:?}", output);
+    }
 
     Ok(())
 }
 
-fn cmd_rx(input: PathBuf, sf: u8, bw: u32, cr: u8, _format: String) -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+fn cmd_rx(
+    input: Option<PathBuf>,
+    device: Option<String>,
+    duration_ms: f64,
+    sf: u8,
+    bw: u32,
+    cr: u8,
+    _format: String,
+    metrics_port: Option<u16>,
+) -> Result<()> {
     validate_sf(sf)?;
     validate_cr(cr)?;
     let bw_hz = validate_bw(bw)?;
@@ -951,8 +1624,29 @@ fn cmd_rx(input: PathBuf, sf: u8, bw: u32, cr: u8, _format: String) -> Result<()
         .coding_rate(cr)
         .build();
 
-    info!("Reading samples from {:?}", input);
-    let samples = read_samples_f32(&input)?;
+    let source = match (&device, &input) {
+        (Some(uri), _) => uri.clone(),
+        (None, Some(input)) => input.display().to_string(),
+        (None, None) => anyhow::bail!("Either --input or --device is required"),
+    };
+    let (metrics, server) = start_metrics(metrics_port, &format!("LoRa SF{}", sf), &source)?;
+    let monitor = StreamMonitor::new(metrics.clone());
+
+    let samples = match (device, input) {
+        (Some(uri), _) => {
+            let num_samples = (duration_ms / 1000.0 * params.sample_rate) as usize;
+            info!("Capturing {} samples from {}", num_samples, uri);
+            capture_from_device(&uri, params.sample_rate, num_samples, &monitor)?
+        }
+        (None, Some(input)) => {
+            info!("Reading samples from {:?}", input);
+            let start = Instant::now();
+            let samples = read_samples_f32(&input)?;
+            monitor.record_block(&samples, start.elapsed(), 0);
+            samples
+        }
+        (None, None) => unreachable!(),
+    };
     info!("Read {} I/Q samples", samples.len());
 
     let mut demodulator = Demodulator::new(params.clone());
@@ -963,14 +1657,18 @@ fn cmd_rx(input: PathBuf, sf: u8, bw: u32, cr: u8, _format: String) -> Result<()
 
     if samples.len() <= preamble_len {
         warn!("Sample file too short to contain payload");
-        return Ok(());
+        return hold_metrics(server);
     }
 
     let payload_samples = &samples[preamble_len..];
 
+    metrics.packets_rx.inc();
     match demodulator.demodulate(payload_samples) {
         Ok(result) => {
             info!("Demodulated {} symbols", result.symbols.len());
+            metrics.packets_decoded.inc();
+            metrics.record_rssi(result.rssi);
+            metrics.freq_offset_hz.set(result.cfo.round() as i64);
 
             match String::from_utf8(result.payload.clone()) {
                 Ok(text) => {
@@ -986,10 +1684,11 @@ fn cmd_rx(input: PathBuf, sf: u8, bw: u32, cr: u8, _format: String) -> Result<()
         }
         Err(e) => {
             warn!("Demodulation failed: {}", e);
+            metrics.packets_failed.inc();
         }
     }
 
-    Ok(())
+    hold_metrics(server)
 }
 
 fn cmd_simulate(
@@ -1001,6 +1700,8 @@ fn cmd_simulate(
     bw: u32,
     cr: u8,
     save_samples: bool,
+    impairments: ImpairmentConfig,
+    interference: Vec<InterfererConfig>,
 ) -> Result<()> {
     validate_sf(sf)?;
     validate_cr(cr)?;
@@ -1016,6 +1717,9 @@ fn cmd_simulate(
         model: parse_channel_model(&channel_model)?,
         snr_db: snr,
         cfo_hz: cfo,
+        sample_rate: params.sample_rate,
+        impairments,
+        interference,
         ..Default::default()
     };
 
@@ -1023,6 +1727,8 @@ fn cmd_simulate(
     println!("Message: '{}'", message);
     println!("SF{}, BW {}kHz, CR 4/{}", sf, bw, cr);
     println!("Channel: {:?}, SNR: {:.1} dB, CFO: {:.1} Hz", channel_config.model, snr, cfo);
+    print_impairments(&channel_config.impairments, params.sample_rate);
+    print_interference(&channel_config.interference);
     println!();
 
     // Transmit - prepend length byte to payload for proper decoding
@@ -1041,6 +1747,18 @@ fn cmd_simulate(
         println!("  Saved to sim_tx.iq");
     }
 
+    // Implementation loss: impairments alone, without channel noise
+    if !channel_config.impairments.is_empty() {
+        let mut hardware = Impairments::new(channel_config.impairments.clone(), params.sample_rate);
+        let amplified = hardware.apply_tx(&tx_samples);
+        let impaired = hardware.apply_rx(&amplified);
+        println!(
+            "Impairments: SDR {:.1} dB (ADC clip rate {:.3}%)",
+            signal_to_distortion_db(&tx_samples, &impaired),
+            hardware.clip_rate() * 100.0
+        );
+    }
+
     // Channel
     let mut channel = Channel::new(channel_config);
     let rx_samples = channel.apply(&tx_samples);
@@ -1135,6 +1853,44 @@ fn cmd_simulate(
     Ok(())
 }
 
+fn print_interference(interferers: &[InterfererConfig]) {
+    for jammer in interferers {
+        println!("Jammer: {:?}, JSR {:.1} dB", jammer.source, jammer.jsr_db);
+    }
+}
+
+fn print_impairments(config: &ImpairmentConfig, sample_rate: f64) {
+    if let Some(pn) = &config.phase_noise {
+        println!(
+            "Phase noise: {} mask points, {:.2}° RMS",
+            pn.mask.len(),
+            pn.rms_phase_rad(sample_rate).to_degrees()
+        );
+    }
+    if let Some(iq) = &config.iq_imbalance {
+        println!(
+            "IQ imbalance: {:.2} dB / {:.2}°, image rejection {:.1} dB",
+            iq.gain_db,
+            iq.phase_deg,
+            iq.image_rejection_db()
+        );
+    }
+    if let Some((i, q)) = config.dc_offset {
+        println!("DC offset: {:.4} + j{:.4}", i, q);
+    }
+    if let Some(pa) = &config.pa {
+        println!("PA: {:?}, input back-off {:.1} dB", pa.model, pa.input_backoff_db);
+    }
+    if let Some(adc) = &config.adc {
+        println!(
+            "ADC: {} bits, full scale {:.2} (ideal SQNR {:.1} dB)",
+            adc.bits,
+            adc.full_scale,
+            adc.ideal_sqnr_db()
+        );
+    }
+}
+
 fn cmd_chirp(output: PathBuf, chirp_type: String, symbol: u16, sf: u8, bw: u32) -> Result<()> {
     use r4w_core::chirp::ChirpGenerator;
 
@@ -1170,6 +1926,40 @@ fn cmd_chirp(output: PathBuf, chirp_type: String, symbol: u16, sf: u8, bw: u32)
     Ok(())
 }
 
+fn cmd_link_budget(budget: &LinkBudget, model: &PathLossModel, distance: f64, freq: f64) -> Result<()> {
+    let report = budget.evaluate(model.path_loss_db(distance, freq));
+
+    println!("=== Link Budget ===");
+    println!();
+    println!("Model:               {:?}", model);
+    println!("Distance:            {:.0} m", distance);
+    println!("Frequency:           {:.3} MHz", freq / 1e6);
+    println!();
+    println!("  TX power:          {:>8.1} dBm", budget.tx_power_dbm);
+    println!("  TX antenna gain:   {:>8.1} dBi", budget.tx_antenna_gain_dbi);
+    println!("  TX cable loss:     {:>8.1} dB", budget.tx_cable_loss_db);
+    println!("  EIRP:              {:>8.1} dBm", report.eirp_dbm);
+    println!("  Path loss:         {:>8.1} dB", report.path_loss_db);
+    println!("  Other losses:      {:>8.1} dB", budget.other_losses_db);
+    println!("  RX antenna gain:   {:>8.1} dBi", budget.rx_antenna_gain_dbi);
+    println!("  RX cable loss:     {:>8.1} dB", budget.rx_cable_loss_db);
+    println!("  RX power:          {:>8.1} dBm", report.rx_power_dbm);
+    println!();
+    println!("  Noise floor:       {:>8.1} dBm (NF {:.0} dB)", report.noise_floor_dbm, budget.noise_figure_db);
+    println!("  SNR:               {:>8.1} dB", report.snr_db);
+    println!("  Sensitivity:       {:>8.1} dBm", report.sensitivity_dbm);
+    println!("  Margin:            {:>8.1} dB", report.margin_db);
+    println!();
+    println!("Link {}", if report.closes() { "CLOSES" } else { "FAILS" });
+    println!("Maximum range:       {:.0} m", budget.range_m(model, freq));
+    let sigma = model.shadowing_sigma_db();
+    if sigma > 0.0 {
+        println!("Shadowing σ:         {:.1} dB (margin = {:.2}σ)", sigma, report.margin_db / sigma);
+    }
+
+    Ok(())
+}
+
 fn cmd_info(sf: u8, bw: u32, cr: u8, payload_len: usize) -> Result<()> {
     validate_sf(sf)?;
     validate_cr(cr)?;
@@ -1224,6 +2014,50 @@ fn cmd_info(sf: u8, bw: u32, cr: u8, payload_len: usize) -> Result<()> {
     Ok(())
 }
 
+fn cmd_info_plan(
+    waveform: &str,
+    uri: &str,
+    sample_rate: f64,
+    freq: Option<f64>,
+    lo_offset: Option<f64>,
+    format: Option<String>,
+) -> Result<()> {
+    use r4w_sim::hal::{AdapterOptions, SampleFormat, StreamPlan};
+
+    let wf = WaveformFactory::create(waveform, sample_rate)
+        .ok_or_else(|| anyhow::anyhow!("Unknown waveform: {}", waveform))?;
+    let waveform_rate = wf.common_params().sample_rate;
+
+    let mut options = AdapterOptions {
+        frequency: freq,
+        lo_offset,
+        ..Default::default()
+    };
+    if let Some(name) = format {
+        options.format = Some(
+            SampleFormat::parse(&name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown sample format '{}' (cf32, ci16, ci8, cu8)", name))?,
+        );
+    }
+
+    let registry = r4w_sim::hal::create_default_registry();
+    let mut device = registry
+        .create(uri)
+        .map_err(|e| anyhow::anyhow!("Failed to open device '{}': {}", uri, e))?;
+    let mut plan = StreamPlan::for_device(device.as_mut(), waveform_rate, &options)
+        .map_err(|e| anyhow::anyhow!("No stream plan: {}", e))?;
+    plan.apply(device.as_mut())
+        .map_err(|e| anyhow::anyhow!("Failed to configure device: {}", e))?;
+
+    println!("=== Stream Plan ===");
+    println!();
+    println!("Waveform:       {}", wf.info().name);
+    println!("Device:         {} ({})", device.name(), uri);
+    println!("{}", plan);
+
+    Ok(())
+}
+
 /// Arguments for the analyze command
 struct AnalyzeArgs {
     input: PathBuf,
@@ -1243,11 +2077,21 @@ struct AnalyzeArgs {
     threshold: f64,
     max_peaks: usize,
     height: usize,
+    extract: Option<PathBuf>,
+    annotate: bool,
+    scd_method: String,
+    channels: usize,
+    pfa: Option<f64>,
+    waveform: Option<String>,
+    mask: Option<String>,
+    ref_level: f64,
 }
 
 fn cmd_analyze(args: AnalyzeArgs) -> Result<()> {
     use r4w_core::analysis::{
-        Colormap, PeakFinder, SignalStats, SpectrumAnalyzer, WaterfallGenerator, WindowFunction,
+        Colormap, ComplianceChecker, CyclicSpectrumAnalyzer, CyclostationaryDetector, EnergyDetector,
+        ModulationClassifier, PeakFinder, QualityAnalyzer, ScfMethod, SignalStats,
+        SpectrumAnalyzer, SpectrumMask, WaterfallGenerator, WindowFunction,
     };
     use r4w_core::fft_utils::FftProcessor;
 
@@ -1439,9 +2283,194 @@ fn cmd_analyze(args: AnalyzeArgs) -> Result<()> {
             }
         }
 
+        "classify" => {
+            let classifier = ModulationClassifier::new(args.sample_rate).with_fft_size(args.fft_size);
+            let result = classifier.classify(analyze_samples);
+
+            let output_text = match args.output_format.as_str() {
+                "json" => result.to_json(),
+                _ => result.to_text(),
+            };
+
+            if let Some(output_path) = args.output {
+                std::fs::write(&output_path, &output_text)?;
+                println!("Classification written to {:?}", output_path);
+            } else {
+                println!("{}", output_text);
+            }
+        }
+
+        "detect" => {
+            use r4w_sim::hal::sigmf::{annotate_detections, extract_detections, SigMfMeta};
+
+            // SigMF inputs carry their own rate and centre frequency
+            let is_sigmf = input_ext == "sigmf-meta" || input_ext == "sigmf-data";
+            let mut meta = if is_sigmf {
+                SigMfMeta::load(&args.input).map_err(|e| anyhow::anyhow!("{}", e))?
+            } else {
+                SigMfMeta::new(args.sample_rate, 0.0, "cf32_le")
+            };
+            let sample_rate = meta.global.sample_rate;
+
+            let waterfall = WaterfallGenerator::new(args.fft_size).compute(analyze_samples, sample_rate);
+            let detections = EnergyDetector::new()
+                .with_threshold(args.threshold)
+                .detect(&waterfall);
+
+            let output_text = match args.output_format.as_str() {
+                "json" => EnergyDetector::format_json(&detections),
+                "csv" => EnergyDetector::format_csv(&detections),
+                _ => EnergyDetector::format_text(&detections),
+            };
+
+            if let Some(output_path) = &args.output {
+                std::fs::write(output_path, &output_text)?;
+                println!("Detections written to {:?}", output_path);
+            } else {
+                println!("{}", output_text);
+            }
+
+            if let Some(dir) = &args.extract {
+                std::fs::create_dir_all(dir)?;
+                let stem = data_path.file_stem().and_then(|s| s.to_str()).unwrap_or("capture");
+                let paths = extract_detections(analyze_samples, &meta, &detections, dir.join(stem), 0.5)
+                    .map_err(|e| anyhow::anyhow!("Extraction failed: {}", e))?;
+                for path in &paths {
+                    println!("Extracted {}", path.with_extension("sigmf-meta").display());
+                }
+            }
+
+            if args.annotate {
+                if !is_sigmf {
+                    anyhow::bail!("--annotate needs a SigMF input");
+                }
+                annotate_detections(&mut meta, &detections);
+                meta.save(&args.input).map_err(|e| anyhow::anyhow!("{}", e))?;
+                println!("Added {} annotation(s) to {:?}", detections.len(), args.input.with_extension("sigmf-meta"));
+            }
+        }
+
+        "scd" => {
+            let method = ScfMethod::from_str(&args.scd_method)
+                .ok_or_else(|| anyhow::anyhow!("Unknown SCD method: '{}'. Use: fam, ssca", args.scd_method))?;
+            let analyzer = CyclicSpectrumAnalyzer::new(method, args.channels);
+            let result = analyzer.compute(analyze_samples, args.sample_rate);
+
+            #[allow(unused_variables)]
+            let colormap = Colormap::from_str(&args.colormap)
+                .unwrap_or(Colormap::Viridis);
+
+            match args.output_format.as_str() {
+                "png" => {
+                    #[cfg(feature = "image")]
+                    {
+                        let (min_db, max_db) = result.power_range();
+                        let png_data = result.to_png(colormap, min_db, max_db);
+                        let output_path = args.output.clone().unwrap_or_else(|| PathBuf::from("scd.png"));
+                        std::fs::write(&output_path, &png_data)?;
+                        println!("Spectral correlation PNG written to {:?}", output_path);
+                        println!("Dimensions: {}x{} (f x alpha)", result.frequencies.len(), result.alphas.len());
+                    }
+                    #[cfg(not(feature = "image"))]
+                    {
+                        anyhow::bail!("PNG output requires the 'image' feature. Use --output-format text instead.");
+                    }
+                }
+                format => {
+                    let output_text = match format {
+                        "json" => result.to_json(),
+                        "csv" => result.to_csv(),
+                        _ => result.to_text(),
+                    };
+                    if let Some(output_path) = &args.output {
+                        std::fs::write(output_path, &output_text)?;
+                        println!("Spectral correlation written to {:?}", output_path);
+                    } else {
+                        println!("{}", output_text);
+                    }
+                }
+            }
+
+            if let Some(pfa) = args.pfa {
+                // Monte Carlo runs reuse the estimator, so keep the record short
+                let record = &analyze_samples[..analyze_samples.len().min(8192)];
+                let detector = CyclostationaryDetector::new(analyzer.with_alpha_bins(512)).with_trials(50);
+                let threshold = detector.threshold(record.len(), args.sample_rate, pfa);
+                let statistic = detector.statistic(record, args.sample_rate);
+
+                println!();
+                println!("Cyclostationary Detection (Pfa = {})", pfa);
+                println!("══════════════════════════════════════════════════");
+                println!("Statistic:         {:.2} (threshold {:.2})", statistic, threshold);
+                println!("Feature present:   {}", if statistic > threshold { "yes" } else { "no" });
+                println!();
+                println!("{:>10}  {:>12}  {:>12}", "SNR (dB)", "Pd feature", "Pd energy");
+                let snrs: Vec<f64> = (-6..=2).map(|i| i as f64 * 5.0).collect();
+                for point in detector.compare(record, args.sample_rate, &snrs, pfa) {
+                    println!("{:>10.1}  {:>12.2}  {:>12.2}", point.snr_db, point.pd_feature, point.pd_energy);
+                }
+            }
+        }
+
+        "quality" => {
+            let name = args
+                .waveform
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("quality mode needs --waveform (e.g. QPSK)"))?;
+            let waveform = WaveformFactory::create(name, args.sample_rate)
+                .ok_or_else(|| anyhow::anyhow!("Unknown waveform: {}", name))?;
+            let analyzer = QualityAnalyzer::for_waveform(waveform.as_ref())
+                .ok_or_else(|| anyhow::anyhow!("{} has no constellation to measure against", name))?;
+            let report = analyzer
+                .measure(analyze_samples)
+                .ok_or_else(|| anyhow::anyhow!("Capture too short: need at least 9 symbols"))?;
+
+            let output_text = match args.output_format.as_str() {
+                "json" => report.to_json(),
+                "csv" => report.eye.to_csv(),
+                _ => report.to_text(),
+            };
+
+            if let Some(output_path) = args.output {
+                std::fs::write(&output_path, &output_text)?;
+                println!("Quality report written to {:?}", output_path);
+            } else {
+                println!("{}", output_text);
+            }
+        }
+
+        "mask" => {
+            let spec = args
+                .mask
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("mask mode needs --mask (e.g. etsi-868)"))?;
+            let mask = SpectrumMask::resolve(spec).map_err(|e| anyhow::anyhow!("Cannot load spectrum mask: {}", e))?;
+            let checker = ComplianceChecker::new(mask).with_reference_level(args.ref_level);
+            let report = checker
+                .check(analyze_samples, args.sample_rate)
+                .ok_or_else(|| anyhow::anyhow!("Capture too short for a compliance check"))?;
+
+            let output_text = match args.output_format.as_str() {
+                "json" => report.to_json(),
+                "csv" => report.to_csv(),
+                _ => report.to_text(),
+            };
+
+            if let Some(output_path) = args.output {
+                std::fs::write(&output_path, &output_text)?;
+                println!(
+                    "Compliance report written to {:?} ({})",
+                    output_path,
+                    if report.passed() { "PASS" } else { "FAIL" }
+                );
+            } else {
+                println!("{}", output_text);
+            }
+        }
+
         _ => {
             anyhow::bail!(
-                "Unknown analysis mode: '{}'. Use: basic, spectrum, waterfall, stats, peaks",
+                "Unknown analysis mode: '{}'. Use: basic, spectrum, waterfall, stats, peaks, classify, detect, scd, quality, mask",
                 args.mode
             );
         }
@@ -1635,6 +2664,7 @@ fn cmd_waveform(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_benchmark(
     port: u16,
     format: String,
@@ -1646,6 +2676,7 @@ fn cmd_benchmark(
     output_file: Option<PathBuf>,
     stats_interval: u64,
     list: bool,
+    metrics_port: Option<u16>,
 ) -> Result<()> {
     // List available waveforms
     if list || waveform.is_empty() {
@@ -1670,6 +2701,9 @@ fn cmd_benchmark(
         .context("Failed to bind UDP socket")?;
     receiver.set_timeout(Some(Duration::from_millis(100)))?;
 
+    let (shared_metrics, _metrics_server) = start_metrics(metrics_port, &waveform, &format!("udp://0.0.0.0:{}", port))?;
+    let monitor = StreamMonitor::new(shared_metrics.clone());
+
     // Setup Ctrl+C handler
     let running = Arc::new(AtomicBool::new(true));
     let r = running.clone();
@@ -1716,6 +2750,10 @@ fn cmd_benchmark(
                 // Process through waveform
                 let result = runner.process(&samples);
                 metrics.update(&result);
+                monitor.record_block(&samples, result.processing_time, 0);
+                if let Some(snr) = result.demod_result.snr_estimate {
+                    shared_metrics.record_snr(snr);
+                }
             }
             Ok(_) => {
                 // No data received (timeout)
@@ -1726,6 +2764,7 @@ fn cmd_benchmark(
             }
             Err(_e) => {
                 metrics.record_receive_error();
+                shared_metrics.device_errors.inc();
             }
         }
 
@@ -1780,6 +2819,59 @@ fn cmd_benchmark(
     Ok(())
 }
 
+fn cmd_rtl_tcp_server(device: String, listen: String, sample_rate: Option<f64>, no_pace: bool) -> Result<()> {
+    use r4w_sim::hal::RtlTcpServer;
+
+    let registry = r4w_sim::hal::create_default_registry();
+    let mut sdr = registry
+        .create(&device)
+        .map_err(|e| anyhow::anyhow!("Failed to open device '{}': {}", device, e))?;
+    if let Some(rate) = sample_rate {
+        sdr.tuner()
+            .set_sample_rate(rate)
+            .map_err(|e| anyhow::anyhow!("Failed to set sample rate: {}", e))?;
+    }
+    let name = sdr.name().to_string();
+    let rate = sdr.tuner().sample_rate();
+
+    let mut server = RtlTcpServer::bind(listen.as_str(), sdr)
+        .map_err(|e| anyhow::anyhow!("Failed to listen on {}: {}", listen, e))?
+        .with_realtime(!no_pace);
+
+    println!("rtl_tcp Server");
+    println!("==============");
+    println!("Device:      {}", name);
+    println!("Sample Rate: {} Hz", rate);
+    // A wildcard bind address is not something a client can connect to
+    let addr = server.local_addr();
+    let wildcard = addr.ip().is_unspecified();
+    let mut connect = addr;
+    if wildcard {
+        connect.set_ip(match addr.ip() {
+            std::net::IpAddr::V4(_) => std::net::Ipv4Addr::LOCALHOST.into(),
+            std::net::IpAddr::V6(_) => std::net::Ipv6Addr::LOCALHOST.into(),
+        });
+    }
+    println!("Listening:   {}{}", addr, if wildcard { " (all interfaces)" } else { "" });
+    println!("Pacing:      {}", if no_pace { "off" } else { "sample rate" });
+    println!("\nConnect with: r4w rx --device rtltcp://{}", connect);
+    if wildcard {
+        println!("  (from another machine, use this host's address with port {})", addr.port());
+    }
+    println!("Press Ctrl+C to stop\n");
+
+    let shutdown = server.shutdown_flag();
+    ctrlc::set_handler(move || {
+        shutdown.store(true, Ordering::SeqCst);
+    }).context("Failed to set Ctrl+C handler")?;
+
+    server.run().map_err(|e| anyhow::anyhow!("rtl_tcp server failed: {}", e))?;
+
+    let state = server.state();
+    println!("Served {} client(s), {} samples, {} commands", state.clients, state.samples_sent, state.commands);
+    Ok(())
+}
+
 fn cmd_udp_send(
     target: String,
     waveform: String,
@@ -1941,10 +3033,23 @@ fn cmd_udp_send(
     Ok(())
 }
 
-fn cmd_agent(port: u16, _foreground: bool) -> Result<()> {
+fn cmd_agent(port: u16, _foreground: bool, metrics_port: Option<u16>) -> Result<()> {
     println!("SDR Agent Daemon");
     println!("================");
     println!("Port: {}", port);
+    // Receive benchmarks started by the agent serve on the next port up
+    let rx_metrics_port = metrics_port
+        .map(|p| {
+            p.checked_add(1).ok_or_else(|| {
+                anyhow::anyhow!("--metrics-port {} leaves no port for receive metrics; use 65534 or lower", p)
+            })
+        })
+        .transpose()?;
+    let hostname = r4w_core::agent::DeviceInfo::gather().hostname;
+    let (metrics, _metrics_server) = start_metrics(metrics_port, "", &hostname)?;
+    if let Some(rx_metrics_port) = rx_metrics_port {
+        println!("RX metrics: http://0.0.0.0:{}/metrics", rx_metrics_port);
+    }
     println!();
 
     // Setup Ctrl+C handler
@@ -1955,7 +3060,10 @@ fn cmd_agent(port: u16, _foreground: bool) -> Result<()> {
         r.store(false, Ordering::SeqCst);
     }).context("Failed to set Ctrl+C handler")?;
 
-    let mut server = AgentServer::new(port);
+    let mut server = AgentServer::new(port).with_metrics(metrics);
+    if let Some(rx_metrics_port) = rx_metrics_port {
+        server = server.with_rx_metrics_port(rx_metrics_port);
+    }
 
     // Run server (blocking)
     server.run().context("Agent server failed")?;
@@ -2996,6 +4104,160 @@ fn cmd_adsb_generate(
     Ok(())
 }
 
+fn cmd_zigbee_file(input: PathBuf, sample_rate: f64, pcap: Option<PathBuf>, show_all: bool) -> Result<()> {
+    use r4w_core::waveform::zigbee::{MacPayload, PcapWriter, Zigbee};
+
+    let samples = read_samples_f32(&input)?;
+
+    println!("=== 802.15.4 I/Q File Decoder ===");
+    println!();
+    println!("File:        {:?}", input);
+    println!("Samples:     {}", samples.len());
+    println!("Sample Rate: {} Hz", sample_rate);
+    println!();
+
+    let zigbee = Zigbee::standard(sample_rate);
+    let ppdus = zigbee.decode_ppdus(&samples);
+
+    if ppdus.is_empty() {
+        println!("No 802.15.4 frames found in file.");
+        return Ok(());
+    }
+
+    let mut writer = match &pcap {
+        Some(path) => {
+            let file = File::create(path).context("Failed to create PCAP file")?;
+            Some(PcapWriter::new(BufWriter::new(file))?)
+        }
+        None => None,
+    };
+
+    println!("Found {} PPDU(s):", ppdus.len());
+    println!();
+    println!(
+        "{:<12} {:<8} {:<5} {:<8} {:<24} {:<24} Details",
+        "Time (ms)", "Type", "Seq", "PAN", "Source", "Destination"
+    );
+    println!("{}", "-".repeat(96));
+
+    let mut written = 0;
+    for ppdu in &ppdus {
+        let timestamp = ppdu.sample_offset as f64 / sample_rate;
+        let frame = ppdu.frame();
+        if frame.is_err() && !show_all {
+            continue;
+        }
+
+        if let Some(writer) = writer.as_mut() {
+            writer.write_frame(timestamp, &ppdu.psdu)?;
+            written += 1;
+        }
+
+        match frame {
+            Ok(frame) => {
+                let pan = frame
+                    .dst_pan_id
+                    .or(frame.src_pan_id)
+                    .map(|p| format!("{:04X}", p))
+                    .unwrap_or_default();
+                let src = frame.src_addr.map(|a| a.to_string()).unwrap_or_default();
+                let dst = frame.dst_addr.map(|a| a.to_string()).unwrap_or_default();
+                let details = match &frame.payload {
+                    MacPayload::Beacon(beacon) => format!(
+                        "assoc permit={}, {} byte payload",
+                        beacon.superframe.association_permit,
+                        beacon.payload.len()
+                    ),
+                    MacPayload::Data(data) => format!("{} bytes", data.len()),
+                    MacPayload::Ack => if frame.frame_pending { "pending".to_string() } else { String::new() },
+                    MacPayload::Command { command, .. } => format!("{:?}", command),
+                };
+                println!(
+                    "{:<12.3} {:<8} {:<5} {:<8} {:<24} {:<24} {}",
+                    timestamp * 1000.0,
+                    format!("{:?}", frame.frame_type()),
+                    frame.sequence_number,
+                    pan,
+                    src,
+                    dst,
+                    details
+                );
+            }
+            Err(e) => println!("{:<12.3} [{}]", timestamp * 1000.0, e),
+        }
+    }
+
+    if let (Some(writer), Some(path)) = (writer, pcap) {
+        writer.into_inner()?;
+        println!();
+        println!("Wrote {} frame(s) to {:?}", written, path);
+    }
+
+    Ok(())
+}
+
+fn cmd_zigbee_generate(
+    output: PathBuf,
+    pan_id: String,
+    dst: String,
+    src: String,
+    payload: String,
+    sample_rate: f64,
+) -> Result<()> {
+    use r4w_core::waveform::zigbee::{Beacon, MacAddress, MacFrame, Zigbee};
+    use r4w_core::waveform::Waveform;
+
+    let parse_hex16 = |value: &str, name: &str| {
+        u16::from_str_radix(value.trim().trim_start_matches("0x"), 16)
+            .with_context(|| format!("Invalid {}: {}", name, value))
+    };
+    let pan_id = parse_hex16(&pan_id, "PAN ID")?;
+    let dst = MacAddress::Short(parse_hex16(&dst, "destination address")?);
+    let src = MacAddress::Short(parse_hex16(&src, "source address")?);
+
+    let frames = [
+        MacFrame::beacon(0, pan_id, MacAddress::Short(0x0000), Beacon::default()),
+        MacFrame::data(1, pan_id, dst, src, payload.as_bytes()),
+        MacFrame::ack(1, false),
+    ];
+
+    println!("=== 802.15.4 Test Signal Generator ===");
+    println!();
+    println!("PAN ID:  {:04X}", pan_id);
+    println!("Output:  {:?}", output);
+    println!();
+
+    let zigbee = Zigbee::standard(sample_rate);
+    let gap = vec![IQSample::new(0.0, 0.0); zigbee.samples_per_symbol() * 12];
+    let mut samples = gap.clone();
+    for frame in &frames {
+        let mpdu = frame.encode();
+        println!(
+            "{:<10} {}",
+            format!("{:?}", frame.frame_type()),
+            mpdu.iter().map(|b| format!("{:02X}", b)).collect::<String>()
+        );
+        samples.extend(
+            zigbee
+                .modulate_frame(frame)
+                .with_context(|| format!("Cannot send {:?} frame", frame.frame_type()))?,
+        );
+        samples.extend_from_slice(&gap);
+    }
+
+    println!();
+    println!(
+        "Generated {} samples ({:.3} ms)",
+        samples.len(),
+        samples.len() as f64 / sample_rate * 1000.0
+    );
+
+    write_samples_f32(&samples, &output)?;
+    println!("Wrote samples to {:?}", output);
+
+    Ok(())
+}
+
 fn cmd_remote(address: String, command: RemoteCommand) -> Result<()> {
     // Parse address
     let (host, port) = if address.contains(':') {
@@ -3094,13 +4356,45 @@ fn cmd_remote(address: String, command: RemoteCommand) -> Result<()> {
     Ok(())
 }
 
-/// Compare waveforms across SNR range
+/// Parameter swept by `compare`
+#[derive(Debug, Clone, Copy)]
+enum CompareSweep {
+    /// SNR sweep, with any jammers at their configured JSR
+    Snr { min: f64, max: f64, step: f64 },
+    /// JSR sweep (applied to every jammer) at a fixed SNR
+    Jsr { min: f64, max: f64, step: f64, snr: f64 },
+}
+
+impl CompareSweep {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Snr { .. } => "SNR",
+            Self::Jsr { .. } => "JSR",
+        }
+    }
+
+    fn points(&self) -> Vec<f64> {
+        let (min, max, step) = match *self {
+            Self::Snr { min, max, step } | Self::Jsr { min, max, step, .. } => (min, max, step),
+        };
+        let mut points = Vec::new();
+        let mut x = min;
+        while x <= max + 0.001 {
+            points.push(x);
+            x += step;
+        }
+        points
+    }
+}
+
+/// Compare waveforms across an SNR or JSR range
+#[allow(clippy::too_many_arguments)]
 fn cmd_compare(
     waveforms: String,
-    snr_min: f64,
-    snr_max: f64,
-    snr_step: f64,
+    sweep: CompareSweep,
+    interference: Vec<InterfererConfig>,
     bits: usize,
+    packet_bytes: usize,
     sample_rate: f64,
     output: String,
     output_file: Option<PathBuf>,
@@ -3117,13 +4411,22 @@ fn cmd_compare(
             println!("  {}", name);
         }
         println!();
+        println!("Any other WaveformFactory name (see `r4w waveform --list`) is also accepted.");
+        println!();
         println!("Example: r4w compare -w BPSK,QPSK,8PSK --snr-min 0 --snr-max 15");
+        println!("         r4w compare -w BPSK,BFSK --sweep jsr --jammer cw:freq=1e3");
         return Ok(());
     }
 
     if waveforms.is_empty() {
         anyhow::bail!("No waveforms specified. Use -w BPSK,QPSK or --list to see options.");
     }
+    if matches!(sweep, CompareSweep::Jsr { .. }) && interference.is_empty() {
+        anyhow::bail!("A JSR sweep needs at least one --jammer");
+    }
+    if packet_bytes == 0 {
+        anyhow::bail!("--packet-bytes must be at least 1");
+    }
 
     // Parse waveform list
     let wf_names: Vec<&str> = waveforms.split(',').map(|s| s.trim()).collect();
@@ -3150,9 +4453,10 @@ fn cmd_compare(
             "OOK" => Box::new(ook::OOK::new(common.clone(), symbol_rate)),
             "ASK" => Box::new(ask::ASK::new_binary(common.clone(), symbol_rate, symbol_rate)),
             "4ASK" | "4-ASK" => Box::new(ask::ASK::new_4ask(common.clone(), symbol_rate, symbol_rate)),
-            _ => {
-                anyhow::bail!("Unknown waveform: {}. Use --list to see available waveforms.", name);
-            }
+            _ => match WaveformFactory::create(name, sample_rate) {
+                Some(wf) => wf,
+                None => anyhow::bail!("Unknown waveform: {}. Use --list to see available waveforms.", name),
+            },
         };
         wf_list.push((name.to_string(), wf));
     }
@@ -3163,34 +4467,40 @@ fn cmd_compare(
     let num_bytes = (bits + 7) / 8;
     let tx_bytes: Vec<u8> = (0..num_bytes).map(|_| rng.gen()).collect();
 
-    // SNR sweep
-    let snr_points: Vec<f64> = {
-        let mut points = Vec::new();
-        let mut snr = snr_min;
-        while snr <= snr_max + 0.001 {
-            points.push(snr);
-            snr += snr_step;
-        }
-        points
-    };
+    let points = sweep.points();
+    let label = sweep.label();
 
-    // Results: waveform -> [(snr, ber)]
-    let mut results: Vec<(String, Vec<(f64, f64)>)> = Vec::new();
+    // Results: waveform -> [(snr or jsr, ber, per)]
+    type Curve = Vec<(f64, f64, f64)>;
+    let mut results: Vec<(String, Curve)> = Vec::new();
 
     // Run BER tests
     for (name, wf) in &wf_list {
         let info = wf.info();
         let mut ber_curve = Vec::new();
 
-        for &snr in &snr_points {
+        for &x in &points {
             // Modulate
             let samples = wf.modulate(&tx_bytes);
 
-            // Add AWGN noise
-            let noisy_samples = if snr < 100.0 {
+            let (snr, jammers) = match sweep {
+                CompareSweep::Snr { .. } => (x, interference.clone()),
+                CompareSweep::Jsr { snr, .. } => (
+                    snr,
+                    interference
+                        .iter()
+                        .map(|j| InterfererConfig { jsr_db: x, ..j.clone() })
+                        .collect(),
+                ),
+            };
+
+            // Add AWGN noise and interference
+            let noisy_samples = if snr < 100.0 || !jammers.is_empty() {
                 let channel_config = ChannelConfig {
-                    model: ChannelModel::Awgn,
+                    model: if snr < 100.0 { ChannelModel::Awgn } else { ChannelModel::Ideal },
                     snr_db: snr,
+                    sample_rate,
+                    interference: jammers,
                     ..Default::default()
                 };
                 let mut channel = Channel::new(channel_config);
@@ -3219,7 +4529,19 @@ fn cmd_compare(
                 1.0
             };
 
-            ber_curve.push((snr, ber));
+            // Packet errors: any wrong or missing byte fails the packet
+            let packets: Vec<&[u8]> = tx_bytes.chunks(packet_bytes).collect();
+            let packet_errors = packets
+                .iter()
+                .enumerate()
+                .filter(|(p, packet)| {
+                    let offset = p * packet_bytes;
+                    rx_bytes.get(offset..offset + packet.len()) != Some(**packet)
+                })
+                .count();
+            let per = packet_errors as f64 / packets.len() as f64;
+
+            ber_curve.push((x, ber, per));
         }
 
         results.push((format!("{} ({} bits/sym)", name, info.bits_per_symbol), ber_curve));
@@ -3232,8 +4554,8 @@ fn cmd_compare(
             for (name, curve) in &results {
                 json_results.push(serde_json::json!({
                     "waveform": name,
-                    "data": curve.iter().map(|(snr, ber)| {
-                        serde_json::json!({"snr": snr, "ber": ber})
+                    "data": curve.iter().map(|(x, ber, per)| {
+                        serde_json::json!({label.to_lowercase(): x, "ber": ber, "per": per})
                     }).collect::<Vec<_>>()
                 }));
             }
@@ -3242,18 +4564,24 @@ fn cmd_compare(
         "csv" => {
             let mut csv = String::new();
             // Header
-            csv.push_str("SNR");
+            csv.push_str(label);
             for (name, _) in &results {
                 csv.push(',');
                 csv.push_str(name);
             }
+            for (name, _) in &results {
+                csv.push_str(&format!(",{} PER", name));
+            }
             csv.push('\n');
             // Data rows
-            for (i, snr) in snr_points.iter().enumerate() {
-                csv.push_str(&format!("{:.1}", snr));
+            for (i, x) in points.iter().enumerate() {
+                csv.push_str(&format!("{:.1}", x));
                 for (_, curve) in &results {
                     csv.push_str(&format!(",{:.6}", curve[i].1));
                 }
+                for (_, curve) in &results {
+                    csv.push_str(&format!(",{:.6}", curve[i].2));
+                }
                 csv.push('\n');
             }
             csv
@@ -3261,37 +4589,58 @@ fn cmd_compare(
         _ => {
             // Text format (table)
             let mut text = String::new();
-            text.push_str("=== Waveform Comparison (BER vs SNR) ===\n\n");
-            text.push_str(&format!("Test: {} bits per SNR point\n", bits));
-            text.push_str(&format!("SNR range: {} to {} dB (step {})\n\n", snr_min, snr_max, snr_step));
-
-            // Header
-            text.push_str(&format!("{:>8}", "SNR(dB)"));
-            for (name, _) in &results {
-                text.push_str(&format!("{:>15}", name.split(' ').next().unwrap_or(name)));
+            text.push_str(&format!("=== Waveform Comparison (BER/PER vs {}) ===\n\n", label));
+            text.push_str(&format!(
+                "Test: {} bits per {} point, {}-byte packets\n",
+                bits, label, packet_bytes
+            ));
+            match sweep {
+                CompareSweep::Snr { min, max, step } => {
+                    text.push_str(&format!("SNR range: {} to {} dB (step {})\n", min, max, step));
+                }
+                CompareSweep::Jsr { min, max, step, snr } => {
+                    text.push_str(&format!(
+                        "JSR range: {} to {} dB (step {}), SNR {} dB\n",
+                        min, max, step, snr
+                    ));
+                }
+            }
+            for jammer in &interference {
+                text.push_str(&format!("Jammer: {:?}\n", jammer.source));
             }
-            text.push('\n');
-            text.push_str(&"-".repeat(8 + results.len() * 15));
             text.push('\n');
 
-            // Data rows
-            for (i, snr) in snr_points.iter().enumerate() {
-                text.push_str(&format!("{:>8.1}", snr));
-                for (_, curve) in &results {
-                    let ber = curve[i].1;
-                    if ber == 0.0 {
-                        text.push_str(&format!("{:>15}", "0"));
-                    } else if ber < 0.0001 {
-                        text.push_str(&format!("{:>15.2e}", ber));
-                    } else {
-                        text.push_str(&format!("{:>15.4}", ber));
+            for (title, column) in [("BER", 1), ("PER", 2)] {
+                text.push_str(&format!("{}:\n", title));
+
+                // Header
+                text.push_str(&format!("{:>8}", format!("{}(dB)", label)));
+                for (name, _) in &results {
+                    text.push_str(&format!("{:>15}", name.split(' ').next().unwrap_or(name)));
+                }
+                text.push('\n');
+                text.push_str(&"-".repeat(8 + results.len() * 15));
+                text.push('\n');
+
+                // Data rows
+                for (i, x) in points.iter().enumerate() {
+                    text.push_str(&format!("{:>8.1}", x));
+                    for (_, curve) in &results {
+                        let rate = if column == 1 { curve[i].1 } else { curve[i].2 };
+                        if rate == 0.0 {
+                            text.push_str(&format!("{:>15}", "0"));
+                        } else if rate < 0.0001 {
+                            text.push_str(&format!("{:>15.2e}", rate));
+                        } else {
+                            text.push_str(&format!("{:>15.4}", rate));
+                        }
                     }
+                    text.push('\n');
                 }
                 text.push('\n');
             }
 
-            text.push('\n');
-            text.push_str("Legend: Lower BER is better. 0 = no errors detected.\n");
+            text.push_str("Legend: Lower BER/PER is better. 0 = no errors detected.\n");
             text
         }
     };
@@ -3309,6 +4658,7 @@ fn cmd_compare(
 }
 
 /// Record I/Q samples to SigMF file
+#[allow(clippy::too_many_arguments)]
 fn cmd_record(
     output: PathBuf,
     sample_rate: f64,
@@ -3317,6 +4667,7 @@ fn cmd_record(
     description: Option<String>,
     waveform: Option<String>,
     generate: Option<String>,
+    packet_args: PacketArgs,
 ) -> Result<()> {
     use r4w_sim::hal::sigmf::SigMfWriter;
 
@@ -3337,7 +4688,35 @@ fn cmd_record(
     }
 
     // Generate test signal or record from device
-    if let Some(signal_type) = generate {
+    if generate.as_deref().is_some_and(|g| g.eq_ignore_ascii_case("packets")) {
+        let wf = waveform
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("--generate packets needs --waveform"))?;
+        let scenario = packet_args.to_scenario(wf, sample_rate);
+        println!(
+            "Generating {} {} packets of {} bytes (seed {}, SNR {})",
+            scenario.packets,
+            wf,
+            scenario.payload_len,
+            scenario.seed,
+            scenario.snr_db.map_or("clean".to_string(), |snr| format!("{:.1} dB", snr))
+        );
+
+        let (samples, truth) = scenario.generate().map_err(|e| anyhow::anyhow!(e))?;
+        writer
+            .write_samples(&samples)
+            .map_err(|e| anyhow::anyhow!("Failed to write samples: {}", e))?;
+        for packet in &truth {
+            packet.annotate(writer.metadata_mut());
+        }
+
+        println!(
+            "Wrote {} samples ({:.2} seconds) with {} ground-truth annotations",
+            samples.len(),
+            samples.len() as f64 / sample_rate,
+            truth.len()
+        );
+    } else if let Some(signal_type) = generate {
         println!("Generating test signal: {}", signal_type);
 
         let num_samples = if duration > 0.0 {
@@ -3384,7 +4763,7 @@ fn cmd_record(
                     .collect()
             }
             _ => {
-                anyhow::bail!("Unknown signal type: {}. Use: tone, chirp, noise", signal_type);
+                anyhow::bail!("Unknown signal type: {}. Use: tone, chirp, noise, packets", signal_type);
             }
         };
 
@@ -3396,7 +4775,7 @@ fn cmd_record(
     } else {
         // TODO: Record from SDR device
         println!("Recording from device not yet implemented.");
-        println!("Use --generate <tone|chirp|noise> to create test signals.");
+        println!("Use --generate <tone|chirp|noise|packets> to create test signals.");
         return Ok(());
     }
 
@@ -3417,8 +4796,10 @@ fn cmd_playback(
     num_samples: usize,
     offset: usize,
     demodulate: Option<String>,
+    metrics_port: Option<u16>,
 ) -> Result<()> {
     use r4w_sim::hal::sigmf::SigMfReader;
+    use r4w_sim::scoring::{BurstDecoder, PacketTruth};
 
     let mut reader = SigMfReader::open(&input)
         .map_err(|e| anyhow::anyhow!("Failed to open SigMF file: {}", e))?;
@@ -3477,11 +4858,29 @@ fn cmd_playback(
             .map_err(|e| anyhow::anyhow!("Failed to seek: {}", e))?;
     }
 
-    let mut buffer = vec![IQSample::default(); samples_to_read];
-    let samples_read = reader.read_samples(&mut buffer)
-        .map_err(|e| anyhow::anyhow!("Failed to read samples: {}", e))?;
-
-    buffer.truncate(samples_read);
+    let (metrics, server) = start_metrics(
+        metrics_port,
+        demodulate.as_deref().unwrap_or_default(),
+        &input.display().to_string(),
+    )?;
+    let monitor = StreamMonitor::new(metrics.clone());
+
+    let mut buffer = Vec::with_capacity(samples_to_read);
+    let mut block = vec![IQSample::default(); samples_to_read.clamp(1, 65536)];
+    while buffer.len() < samples_to_read {
+        let len = (samples_to_read - buffer.len()).min(block.len());
+        let start = Instant::now();
+        let n = reader.read_samples(&mut block[..len]).map_err(|e| {
+            metrics.device_errors.inc();
+            anyhow::anyhow!("Failed to read samples: {}", e)
+        })?;
+        if n == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&block[..n]);
+        monitor.record_block(&block[..n], start.elapsed(), samples_to_read - buffer.len());
+    }
+    let samples_read = buffer.len();
 
     println!("=== SigMF Playback ===");
     println!("Read {} samples from offset {}", samples_read, offset);
@@ -3520,6 +4919,24 @@ fn cmd_playback(
         } else {
             println!("Decoded bytes: {:02X?}", &result.bits[..result.bits.len().min(64)]);
         }
+
+        // Packet counters, scored against ground truth when the recording has it
+        if let Some(burst_wf) = WaveformFactory::create(&wf_name, sample_rate) {
+            let (first, last) = (offset as u64, (offset + samples_read) as u64);
+            let truth: Vec<PacketTruth> = PacketTruth::from_meta(reader.metadata())
+                .into_iter()
+                .filter(|p| p.sample_start >= first && p.sample_start + p.sample_count <= last)
+                .map(|p| PacketTruth { sample_start: p.sample_start - first, ..p })
+                .collect();
+            let decodes = BurstDecoder::new(burst_wf).decode(&buffer);
+            match monitor.record_decodes(&buffer, &decodes, &truth, sample_rate) {
+                Some(report) => println!(
+                    "Packets: {} detected, {} decoded correctly of {} annotated",
+                    report.detected, report.correct, report.packets
+                ),
+                None => println!("Packets: {} bursts decoded", decodes.len()),
+            }
+        }
     } else {
         // Just show sample statistics
         let power: f64 = buffer.iter().map(|s| (s.re * s.re + s.im * s.im) as f64).sum::<f64>() / buffer.len() as f64;
@@ -3529,6 +4946,60 @@ fn cmd_playback(
         println!("First 10 samples: {:?}", &buffer[..buffer.len().min(10)]);
     }
 
+    hold_metrics(server)
+}
+
+/// Score demodulator decodes against ground-truth annotations
+fn cmd_score(
+    input: PathBuf,
+    waveform: Option<String>,
+    threshold_db: f64,
+    format: String,
+    max_per: Option<f64>,
+    min_detection: Option<f64>,
+) -> Result<()> {
+    use r4w_sim::hal::IqFileFormat;
+    use r4w_sim::scoring::{score, BurstDecoder, PacketTruth};
+
+    let in_format = IqFileFormat::detect(&input)
+        .ok_or_else(|| anyhow::anyhow!("Cannot detect the format of {:?}", input))?;
+    let mut source = in_format
+        .open(&input, None, None)
+        .map_err(|e| anyhow::anyhow!("Failed to open {:?}: {}", input, e))?;
+    let sample_rate = source.sample_rate();
+    let truth = PacketTruth::from_meta(source.metadata());
+    if truth.is_empty() {
+        anyhow::bail!("{:?} has no ground-truth annotations (r4w:payload)", input);
+    }
+
+    let wf_name = waveform.unwrap_or_else(|| truth[0].waveform.clone());
+    let wf = WaveformFactory::create(&wf_name, sample_rate)
+        .ok_or_else(|| anyhow::anyhow!("Unknown waveform: {}", wf_name))?;
+
+    let samples = source
+        .read_all()
+        .map_err(|e| anyhow::anyhow!("Failed to read samples: {}", e))?;
+    let decodes = BurstDecoder::new(wf).with_threshold_db(threshold_db).decode(&samples);
+    let report = score(&truth, &decodes, sample_rate);
+
+    match format.as_str() {
+        "json" => println!("{}", report.to_json()),
+        _ => {
+            println!("Recording: {:?} ({} packets, demodulated as {})", input, truth.len(), wf_name);
+            print!("{}", report.to_text());
+        }
+    }
+
+    if let Some(max) = max_per {
+        if report.packet_error_rate() > max {
+            anyhow::bail!("PER {:.4} exceeds {:.4}", report.packet_error_rate(), max);
+        }
+    }
+    if let Some(min) = min_detection {
+        if report.detection_rate() < min {
+            anyhow::bail!("Detection rate {:.4} below {:.4}", report.detection_rate(), min);
+        }
+    }
     Ok(())
 }
 
@@ -3537,269 +5008,207 @@ fn cmd_convert(
     input: PathBuf,
     output: PathBuf,
     from_format: Option<String>,
-    to_format: String,
+    to_format: Option<String>,
     sample_rate: Option<f64>,
     frequency: Option<f64>,
+    verify: bool,
 ) -> Result<()> {
-    use r4w_sim::hal::sigmf::{SigMfReader, SigMfWriter};
-    use std::io::{BufReader, BufWriter};
+    use r4w_sim::hal::IqFileFormat;
+
+    let parse_format = |name: &str| {
+        IqFileFormat::parse(name).ok_or_else(|| {
+            anyhow::anyhow!("Unknown format '{}' (expected one of: {})", name, IqFileFormat::names().join(", "))
+        })
+    };
 
     println!("=== Signal Format Conversion ===");
     println!("Input: {:?}", input);
     println!("Output: {:?}", output);
 
-    // Detect input format
-    let input_ext = input.extension().and_then(|e| e.to_str()).unwrap_or("");
-    let detected_format = from_format.unwrap_or_else(|| {
-        match input_ext {
-            "sigmf-meta" | "sigmf-data" => "sigmf".to_string(),
-            "iq" | "cf32" => "raw-cf32".to_string(),
-            "cs16" | "ci16" => "raw-ci16".to_string(),
-            "cs8" | "ci8" => "raw-ci8".to_string(),
-            _ => "raw-cf32".to_string(),
-        }
-    });
-
-    println!("Input format: {}", detected_format);
-    println!("Output format: {}", to_format);
-
-    // Read samples based on input format
-    let (samples, src_sample_rate, src_frequency): (Vec<IQSample>, f64, f64) = match detected_format.as_str() {
-        "sigmf" => {
-            let meta_path = if input_ext == "sigmf-data" {
-                input.with_extension("sigmf-meta")
-            } else {
-                input.clone()
-            };
-
-            let mut reader = SigMfReader::open(&meta_path)
-                .map_err(|e| anyhow::anyhow!("Failed to open SigMF: {}", e))?;
-
-            let meta = reader.metadata();
-            let sr = meta.global.sample_rate;
-            let freq = meta.captures.first().and_then(|c| c.frequency).unwrap_or(0.0);
-            let total = reader.total_samples() as usize;
-
-            let mut buffer = vec![IQSample::default(); total];
-            let read = reader.read_samples(&mut buffer)
-                .map_err(|e| anyhow::anyhow!("Failed to read: {}", e))?;
-            buffer.truncate(read);
-
-            (buffer, sr, freq)
+    let in_format = match from_format {
+        Some(name) => parse_format(&name)?,
+        None => IqFileFormat::detect(&input).unwrap_or_else(|| {
+            warn!("Cannot detect the format of {:?}; reading it as raw cf32 (pass --from to override)", input);
+            IqFileFormat::Raw(r4w_sim::hal::SampleFormat::ComplexFloat32)
+        }),
+    };
+    let out_format = match to_format {
+        Some(name) => parse_format(&name)?,
+        None if output.extension().is_none() => IqFileFormat::SigMf,
+        None => IqFileFormat::from_extension(&output).ok_or_else(|| {
+            anyhow::anyhow!("Unknown output extension on {:?}; pass --to (one of: {})", output, IqFileFormat::names().join(", "))
+        })?,
+    };
+    println!("Input format: {}", in_format);
+    println!("Output format: {}", out_format);
+
+    let mut source = in_format
+        .open(&input, sample_rate, frequency)
+        .map_err(|e| anyhow::anyhow!("Failed to open input: {}", e))?;
+
+    if verify {
+        match source.verify() {
+            Ok(true) => println!("SHA-512: OK"),
+            Ok(false) => println!("SHA-512: no checksum recorded"),
+            Err(e) => anyhow::bail!("Verification failed: {}", e),
         }
-        "raw-cf32" => {
-            let sr = sample_rate.ok_or_else(|| anyhow::anyhow!("--sample-rate required for raw input"))?;
-            let freq = frequency.unwrap_or(0.0);
-
-            let file = File::open(&input)?;
-            let mut reader = BufReader::new(file);
-            let mut samples = Vec::new();
-
-            loop {
-                let mut buf = [0u8; 8];
-                match std::io::Read::read_exact(&mut reader, &mut buf) {
-                    Ok(()) => {
-                        let re = f32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64;
-                        let im = f32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]) as f64;
-                        samples.push(IQSample::new(re, im));
-                    }
-                    Err(ref e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
-                    Err(e) => return Err(anyhow::anyhow!("Read error: {}", e)),
-                }
-            }
+    }
 
-            (samples, sr, freq)
+    let mut meta = source.metadata().clone();
+    if let Some(sr) = sample_rate {
+        meta.global.sample_rate = sr;
+    }
+    if let Some(freq) = frequency {
+        if let Some(capture) = meta.captures.first_mut() {
+            capture.frequency = Some(freq);
         }
-        "raw-ci16" => {
-            let sr = sample_rate.ok_or_else(|| anyhow::anyhow!("--sample-rate required for raw input"))?;
-            let freq = frequency.unwrap_or(0.0);
-
-            let file = File::open(&input)?;
-            let mut reader = BufReader::new(file);
-            let mut samples = Vec::new();
-
-            loop {
-                let mut buf = [0u8; 4];
-                match std::io::Read::read_exact(&mut reader, &mut buf) {
-                    Ok(()) => {
-                        let re = i16::from_le_bytes([buf[0], buf[1]]) as f64 / 32768.0;
-                        let im = i16::from_le_bytes([buf[2], buf[3]]) as f64 / 32768.0;
-                        samples.push(IQSample::new(re, im));
-                    }
-                    Err(ref e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
-                    Err(e) => return Err(anyhow::anyhow!("Read error: {}", e)),
-                }
-            }
+    }
+    println!(
+        "Sample rate: {} Hz, center frequency: {} Hz, {} samples",
+        meta.global.sample_rate,
+        meta.frequency(),
+        source.total_samples()
+    );
 
-            (samples, sr, freq)
+    let mut sink = out_format
+        .create(&output, &meta)
+        .map_err(|e| anyhow::anyhow!("Failed to create output: {}", e))?;
+
+    // Stream in blocks so large recordings never sit in memory
+    let mut buffer = vec![IQSample::default(); 65536];
+    loop {
+        let read = source
+            .read_samples(&mut buffer)
+            .map_err(|e| anyhow::anyhow!("Failed to read: {}", e))?;
+        if read == 0 {
+            break;
         }
-        _ => anyhow::bail!("Unsupported input format: {}", detected_format),
-    };
+        sink.write_samples(&buffer[..read])
+            .map_err(|e| anyhow::anyhow!("Failed to write: {}", e))?;
+    }
 
-    println!("Read {} samples", samples.len());
+    let written = sink.samples_written();
+    let out_meta = sink.finish().map_err(|e| anyhow::anyhow!("Failed to close output: {}", e))?;
+    println!("Wrote {} samples as {} ({})", written, out_format, out_meta.global.datatype);
+    if let Some(hash) = &out_meta.global.sha512 {
+        println!("SHA-512: {}", hash);
+    }
 
-    // Write samples based on output format
-    match to_format.as_str() {
-        "sigmf" => {
-            let out_sr = sample_rate.unwrap_or(src_sample_rate);
-            let out_freq = frequency.unwrap_or(src_frequency);
+    Ok(())
+}
 
-            let mut writer = SigMfWriter::create(&output, out_sr, out_freq)
-                .map_err(|e| anyhow::anyhow!("Failed to create SigMF: {}", e))?;
+/// Generate a synthetic dataset
+fn cmd_dataset(output: PathBuf, format: String, sweep: DatasetArgs) -> Result<()> {
+    use r4w_sim::dataset::{DatasetFormat, DatasetGenerator};
 
-            writer.write_samples(&samples)
-                .map_err(|e| anyhow::anyhow!("Failed to write: {}", e))?;
+    let format = DatasetFormat::parse(&format)
+        .ok_or_else(|| anyhow::anyhow!("Unknown dataset format: {}. Use npy or sigmf", format))?;
+    let config = sweep.to_config()?;
+    let generator = DatasetGenerator::new(config).map_err(|e| anyhow::anyhow!(e))?;
+    let config = generator.config();
 
-            writer.close().map_err(|e| anyhow::anyhow!("Failed to close: {}", e))?;
+    println!("Generating dataset");
+    println!("  Classes:     {}", config.waveforms.join(", "));
+    println!(
+        "  SNR:         {} points, {:.1} to {:.1} dB",
+        config.snr_db.len(),
+        config.snr_db.iter().cloned().fold(f64::INFINITY, f64::min),
+        config.snr_db.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+    );
+    println!("  Examples:    {} x {} samples", generator.len(), config.example_len);
+    println!("  Seed:        {}", config.seed);
 
-            println!("Wrote SigMF files:");
-            println!("  {}.sigmf-meta", output.display());
-            println!("  {}.sigmf-data", output.display());
-        }
-        "raw-cf32" => {
-            let file = File::create(&output)?;
-            let mut writer = BufWriter::new(file);
+    let start = Instant::now();
+    let manifest = generator
+        .write(&output, format)
+        .with_context(|| format!("Failed to write dataset to {:?}", output))?;
 
-            for sample in &samples {
-                std::io::Write::write_all(&mut writer, &(sample.re as f32).to_le_bytes())?;
-                std::io::Write::write_all(&mut writer, &(sample.im as f32).to_le_bytes())?;
-            }
+    println!();
+    println!("Wrote {} examples in {:.2?}", manifest.num_examples, start.elapsed());
+    for file in manifest.files.values() {
+        println!("  {}", output.join(file).display());
+    }
+    println!("  {}", output.join("manifest.json").display());
+    Ok(())
+}
 
-            println!("Wrote {} samples to {:?}", samples.len(), output);
+/// Shared metrics for a receive path, labelled with its waveform and
+/// device and served on `port` when one is given.
+fn start_metrics(port: Option<u16>, waveform: &str, device: &str) -> Result<(Arc<Metrics>, Option<MetricsServer>)> {
+    let metrics = Arc::new(Metrics::new());
+    metrics.set_waveform(waveform);
+    metrics.set_device(device);
+    let server = match port {
+        Some(port) => {
+            let server = MetricsServer::spawn(("0.0.0.0", port), metrics.clone())
+                .with_context(|| format!("Failed to start metrics server on port {}", port))?;
+            eprintln!("Metrics: http://{}/metrics", server.local_addr());
+            Some(server)
         }
-        "raw-ci16" => {
-            let file = File::create(&output)?;
-            let mut writer = BufWriter::new(file);
-
-            for sample in &samples {
-                let re = (sample.re * 32767.0).clamp(-32768.0, 32767.0) as i16;
-                let im = (sample.im * 32767.0).clamp(-32768.0, 32767.0) as i16;
-                std::io::Write::write_all(&mut writer, &re.to_le_bytes())?;
-                std::io::Write::write_all(&mut writer, &im.to_le_bytes())?;
-            }
+        None => None,
+    };
+    Ok((metrics, server))
+}
 
-            println!("Wrote {} samples to {:?}", samples.len(), output);
-        }
-        _ => anyhow::bail!("Unsupported output format: {}", to_format),
+/// Keep serving the metrics of a finished command until Ctrl+C.
+fn hold_metrics(server: Option<MetricsServer>) -> Result<()> {
+    let Some(server) = server else {
+        return Ok(());
+    };
+    eprintln!("Serving metrics on http://{}/metrics; press Ctrl+C to exit", server.local_addr());
+    let running = Arc::new(AtomicBool::new(true));
+    let r = running.clone();
+    ctrlc::set_handler(move || {
+        r.store(false, Ordering::SeqCst);
+    }).context("Failed to set Ctrl+C handler")?;
+    while running.load(Ordering::SeqCst) {
+        std::thread::sleep(Duration::from_millis(100));
     }
-
     Ok(())
 }
 
-/// Display or serve Prometheus metrics
-fn cmd_metrics(format: String, serve: bool, port: u16) -> Result<()> {
-    use r4w_core::observe::Metrics;
-
-    // Create a demo metrics instance
-    let metrics = Metrics::new();
-
-    // Populate with demo data
-    metrics.rx_samples.inc_by(1_000_000);
-    metrics.tx_samples.inc_by(500_000);
-    metrics.rx_buffer_level.set(4096);
-    metrics.tx_buffer_level.set(2048);
-    metrics.packets_decoded.inc_by(1500);
-    metrics.packets_failed.inc_by(12);
-    metrics.record_rssi(-85.5);
-    metrics.record_snr(15.2);
-    metrics.set_waveform("lora");
+/// Display metrics scraped from a running command
+fn cmd_metrics(format: String, host: String, port: u16, serve: bool) -> Result<()> {
+    use r4w_core::observe::{parse_prometheus, scrape};
 
     if serve {
-        println!("Starting metrics server on http://0.0.0.0:{}/metrics", port);
-        println!("Press Ctrl+C to stop");
-        println!();
-        println!("Add to prometheus.yml:");
-        println!("  - job_name: 'r4w'");
-        println!("    static_configs:");
-        println!("      - targets: ['localhost:{}']", port);
-
-        // Simple HTTP server for metrics
-        use std::net::TcpListener;
-        use std::io::{BufRead, BufReader, Write};
-
-        let listener = TcpListener::bind(format!("0.0.0.0:{}", port))?;
-
-        for stream in listener.incoming() {
-            match stream {
-                Ok(mut stream) => {
-                    let reader = BufReader::new(&stream);
-                    let request_line = reader.lines().next();
-
-                    if let Some(Ok(line)) = request_line {
-                        if line.contains("GET /metrics") || line.contains("GET / ") {
-                            let body = metrics.to_prometheus();
-                            let response = format!(
-                                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\n\r\n{}",
-                                body.len(),
-                                body
-                            );
-                            let _ = stream.write_all(response.as_bytes());
-                        } else {
-                            let response = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
-                            let _ = stream.write_all(response.as_bytes());
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Connection error: {}", e);
-                }
-            }
-        }
+        anyhow::bail!(
+            "`r4w metrics --serve` has been removed; pass --metrics-port to rx, playback, benchmark or agent to serve metrics, then read them with `r4w metrics --port <PORT>`"
+        );
+    }
 
-        Ok(())
-    } else {
-        // Just display metrics
-        match format.to_lowercase().as_str() {
-            "prometheus" => {
-                println!("{}", metrics.to_prometheus());
-            }
-            "json" => {
-                let snapshot = metrics.snapshot();
-                println!("{}", serde_json::to_string_pretty(&serde_json::json!({
-                    "rx_samples": snapshot.rx_samples,
-                    "tx_samples": snapshot.tx_samples,
-                    "rx_buffer_level": snapshot.rx_buffer_level,
-                    "tx_buffer_level": snapshot.tx_buffer_level,
-                    "rx_overflows": snapshot.rx_overflows,
-                    "tx_underflows": snapshot.tx_underflows,
-                    "packets_decoded": snapshot.packets_decoded,
-                    "packets_failed": snapshot.packets_failed,
-                    "rssi_dbm": snapshot.rssi_dbm,
-                    "snr_db": snapshot.snr_db,
-                    "active_waveform": snapshot.active_waveform,
-                    "decode_success_rate": snapshot.decode_success_rate(),
-                }))?);
-            }
-            _ => {
-                println!("=== R4W Metrics ===");
-                let snapshot = metrics.snapshot();
-                println!();
-                println!("Samples:");
-                println!("  RX: {}", snapshot.rx_samples);
-                println!("  TX: {}", snapshot.tx_samples);
-                println!();
-                println!("Buffers:");
-                println!("  RX level: {}", snapshot.rx_buffer_level);
-                println!("  TX level: {}", snapshot.tx_buffer_level);
-                println!();
-                println!("Packets:");
-                println!("  Decoded: {}", snapshot.packets_decoded);
-                println!("  Failed: {}", snapshot.packets_failed);
-                println!("  Success rate: {:.1}%", snapshot.decode_success_rate() * 100.0);
-                println!();
-                println!("Signal:");
-                println!("  RSSI: {:.1} dBm", snapshot.rssi_dbm);
-                println!("  SNR: {:.1} dB", snapshot.snr_db);
-                println!();
-                println!("Waveform: {}", snapshot.active_waveform);
-                println!();
-                println!("Use -f prometheus for Prometheus format");
-                println!("Use --serve to start HTTP endpoint");
+    let body = scrape((host.as_str(), port)).with_context(|| {
+        format!(
+            "No metrics endpoint at {}:{}; start rx, playback, benchmark or agent with --metrics-port",
+            host, port
+        )
+    })?;
+    let values = parse_prometheus(&body);
+
+    match format.to_lowercase().as_str() {
+        "prometheus" => {
+            print!("{}", body);
+        }
+        "json" => {
+            let map: serde_json::Map<String, serde_json::Value> = values
+                .into_iter()
+                .map(|(series, value)| (series, serde_json::json!(value)))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&map)?);
+        }
+        _ => {
+            println!("=== R4W Metrics ({}:{}) ===", host, port);
+            println!();
+            let width = values.iter().map(|(s, _)| s.len()).max().unwrap_or(0);
+            for (series, value) in values.iter().filter(|(s, _)| !s.contains("_bucket{")) {
+                println!("  {:<width$}  {}", series, value, width = width);
             }
+            println!();
+            println!("Use -f prometheus for Prometheus format");
         }
-
-        Ok(())
     }
+
+    Ok(())
 }
 
 fn main() -> Result<()> {
@@ -3826,15 +5235,19 @@ fn main() -> Result<()> {
             bw,
             cr,
             format,
-        } => cmd_tx(message, output, sf, bw, cr, format),
+            device,
+        } => cmd_tx(message, output, sf, bw, cr, format, device),
 
         Commands::Rx {
             input,
+            device,
+            duration,
             sf,
             bw,
             cr,
             format,
-        } => cmd_rx(input, sf, bw, cr, format),
+            metrics_port,
+        } => cmd_rx(input, device, duration, sf, bw, cr, format, metrics_port),
 
         Commands::Simulate {
             message,
@@ -3845,7 +5258,20 @@ fn main() -> Result<()> {
             bw,
             cr,
             save_samples,
-        } => cmd_simulate(message, snr, cfo, channel, sf, bw, cr, save_samples),
+            impairments,
+            interference,
+        } => cmd_simulate(
+            message,
+            snr,
+            cfo,
+            channel,
+            sf,
+            bw,
+            cr,
+            save_samples,
+            impairments.to_config()?,
+            interference.to_config()?,
+        ),
 
         Commands::Chirp {
             output,
@@ -3855,13 +5281,48 @@ fn main() -> Result<()> {
             bw,
         } => cmd_chirp(output, chirp_type, symbol, sf, bw),
 
+        Commands::Info {
+            plan: Some(plan),
+            sample_rate,
+            freq,
+            lo_offset,
+            format,
+            ..
+        } => cmd_info_plan(&plan[0], &plan[1], sample_rate, freq, lo_offset, format),
+
         Commands::Info {
             sf,
             bw,
             cr,
             payload_len,
+            ..
         } => cmd_info(sf, bw, cr, payload_len),
 
+        Commands::LinkBudget {
+            model,
+            distance,
+            freq,
+            tx_power,
+            tx_gain,
+            rx_gain,
+            cable_loss,
+            other_loss,
+            sf,
+            bw,
+        } => {
+            let model = PathLossModel::parse(&model).map_err(anyhow::Error::msg)?;
+            validate_sf(sf)?;
+            let params = LoRaParams::builder()
+                .spreading_factor(sf)
+                .bandwidth(validate_bw(bw)?)
+                .build();
+            let budget = LinkBudget::for_lora(&params, tx_power)
+                .with_antenna_gains(tx_gain, rx_gain)
+                .with_cable_losses(cable_loss, cable_loss)
+                .with_other_losses(other_loss);
+            cmd_link_budget(&budget, &model, distance, freq)
+        }
+
         Commands::Analyze {
             input,
             format,
@@ -3879,6 +5340,14 @@ fn main() -> Result<()> {
             threshold,
             max_peaks,
             height,
+            extract,
+            annotate,
+            scd_method,
+            channels,
+            pfa,
+            waveform,
+            mask,
+            ref_level,
         } => cmd_analyze(AnalyzeArgs {
             input,
             format,
@@ -3896,6 +5365,14 @@ fn main() -> Result<()> {
             threshold,
             max_peaks,
             height,
+            extract,
+            annotate,
+            scd_method,
+            channels,
+            pfa,
+            waveform,
+            mask,
+            ref_level,
         }),
 
         Commands::Waveform {
@@ -3918,7 +5395,8 @@ fn main() -> Result<()> {
             output_file,
             stats_interval,
             list,
-        } => cmd_benchmark(port, format, waveform, sample_rate, batch_size, duration, output, output_file, stats_interval, list),
+            metrics_port,
+        } => cmd_benchmark(port, format, waveform, sample_rate, batch_size, duration, output, output_file, stats_interval, list, metrics_port),
 
         Commands::UdpSend {
             target,
@@ -3934,7 +5412,14 @@ fn main() -> Result<()> {
             repeat,
         } => cmd_udp_send(target, waveform, sample_rate, format, message, pps, samples_per_packet, duration, snr, list, repeat),
 
-        Commands::Agent { port, foreground } => cmd_agent(port, foreground),
+        Commands::RtlTcpServer {
+            device,
+            listen,
+            sample_rate,
+            no_pace,
+        } => cmd_rtl_tcp_server(device, listen, sample_rate, no_pace),
+
+        Commands::Agent { port, foreground, metrics_port } => cmd_agent(port, foreground, metrics_port),
 
         Commands::Remote { address, command } => cmd_remote(address, command),
 
@@ -4001,6 +5486,24 @@ fn main() -> Result<()> {
             } => cmd_adsb_generate(output, icao, callsign, altitude, sample_rate),
         },
 
+        Commands::Zigbee { command } => match command {
+            ZigbeeCommand::File {
+                input,
+                sample_rate,
+                pcap,
+                all,
+            } => cmd_zigbee_file(input, sample_rate, pcap, all),
+
+            ZigbeeCommand::Generate {
+                output,
+                pan_id,
+                dst,
+                src,
+                payload,
+                sample_rate,
+            } => cmd_zigbee_generate(output, pan_id, dst, src, payload, sample_rate),
+        },
+
         Commands::Completions { shell } => {
             let mut cmd = Cli::command();
             let bin_name = cmd.get_name().to_string();
@@ -4013,12 +5516,36 @@ fn main() -> Result<()> {
             snr_min,
             snr_max,
             snr_step,
+            sweep,
+            snr,
+            jsr_min,
+            jsr_max,
+            jsr_step,
+            packet_bytes,
+            interference,
             bits,
             sample_rate,
             output,
             output_file,
             list,
-        } => cmd_compare(waveforms, snr_min, snr_max, snr_step, bits, sample_rate, output, output_file, list),
+        } => {
+            let sweep = match sweep.to_lowercase().as_str() {
+                "snr" => CompareSweep::Snr { min: snr_min, max: snr_max, step: snr_step },
+                "jsr" => CompareSweep::Jsr { min: jsr_min, max: jsr_max, step: jsr_step, snr },
+                _ => anyhow::bail!("Unknown sweep: {}. Use snr or jsr", sweep),
+            };
+            cmd_compare(
+                waveforms,
+                sweep,
+                interference.to_config()?,
+                bits,
+                packet_bytes,
+                sample_rate,
+                output,
+                output_file,
+                list,
+            )
+        }
         Commands::Record {
             output,
             sample_rate,
@@ -4027,7 +5554,8 @@ fn main() -> Result<()> {
             description,
             waveform,
             generate,
-        } => cmd_record(output, sample_rate, frequency, duration, description, waveform, generate),
+            packets,
+        } => cmd_record(output, sample_rate, frequency, duration, description, waveform, generate, packets),
         Commands::Playback {
             input,
             info,
@@ -4035,7 +5563,8 @@ fn main() -> Result<()> {
             samples,
             offset,
             demodulate,
-        } => cmd_playback(input, info, format, samples, offset, demodulate),
+            metrics_port,
+        } => cmd_playback(input, info, format, samples, offset, demodulate, metrics_port),
         Commands::Convert {
             input,
             output,
@@ -4043,7 +5572,17 @@ fn main() -> Result<()> {
             to,
             sample_rate,
             frequency,
-        } => cmd_convert(input, output, from, to, sample_rate, frequency),
-        Commands::Metrics { format, serve, port } => cmd_metrics(format, serve, port),
+            verify,
+        } => cmd_convert(input, output, from, to, sample_rate, frequency, verify),
+        Commands::Score {
+            input,
+            waveform,
+            threshold_db,
+            format,
+            max_per,
+            min_detection,
+        } => cmd_score(input, waveform, threshold_db, format, max_per, min_detection),
+        Commands::Dataset { output, format, sweep } => cmd_dataset(output, format, sweep),
+        Commands::Metrics { format, host, port, serve } => cmd_metrics(format, host, port, serve),
     }
 }
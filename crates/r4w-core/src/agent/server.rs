@@ -4,6 +4,7 @@
 
 use super::protocol::*;
 use super::DEFAULT_AGENT_PORT;
+use crate::observe::{parse_prometheus, scrape, Metrics};
 use std::io::{BufRead, BufReader, Write};
 use std::net::{TcpListener, TcpStream};
 use std::path::PathBuf;
@@ -22,6 +23,8 @@ pub struct AgentServer {
     tx_status: Arc<Mutex<TaskStatus>>,
     rx_status: Arc<Mutex<TaskStatus>>,
     metrics_callback: Option<Box<dyn Fn(MetricsData) + Send + Sync>>,
+    metrics: Option<Arc<Metrics>>,
+    rx_metrics_port: Option<u16>,
 }
 
 impl AgentServer {
@@ -36,6 +39,8 @@ impl AgentServer {
             tx_status: Arc::new(Mutex::new(TaskStatus::Idle)),
             rx_status: Arc::new(Mutex::new(TaskStatus::Idle)),
             metrics_callback: None,
+            metrics: None,
+            rx_metrics_port: None,
         }
     }
 
@@ -53,6 +58,20 @@ impl AgentServer {
         self
     }
 
+    /// Record agent activity (active waveform, failed launches) into shared metrics
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Have RX processes serve their metrics on this port
+    ///
+    /// `GetMetrics` then reports the running receiver's counters.
+    pub fn with_rx_metrics_port(mut self, port: u16) -> Self {
+        self.rx_metrics_port = Some(port);
+        self
+    }
+
     /// Run the agent server (blocking)
     pub fn run(&mut self) -> std::io::Result<()> {
         let listener = TcpListener::bind(("0.0.0.0", self.port))?;
@@ -262,6 +281,9 @@ impl AgentServer {
                 }
             }
             Err(e) => {
+                if let Some(metrics) = &self.metrics {
+                    metrics.device_errors.inc();
+                }
                 *self.tx_status.lock().unwrap() = TaskStatus::Failed {
                     error: e.to_string(),
                     failed_at: unix_timestamp(),
@@ -305,6 +327,12 @@ impl AgentServer {
             .arg(sample_rate.to_string())
             .arg("--stats-interval")
             .arg("1");
+        if let Some(metrics_port) = self.rx_metrics_port {
+            cmd.arg("--metrics-port").arg(metrics_port.to_string());
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics.set_waveform(&waveform);
+        }
 
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
@@ -327,6 +355,9 @@ impl AgentServer {
                 }
             }
             Err(e) => {
+                if let Some(metrics) = &self.metrics {
+                    metrics.device_errors.inc();
+                }
                 *self.rx_status.lock().unwrap() = TaskStatus::Failed {
                     error: e.to_string(),
                     failed_at: unix_timestamp(),
@@ -349,8 +380,7 @@ impl AgentServer {
     }
 
     fn cmd_get_metrics(&self) -> AgentResponse {
-        // Return current metrics (placeholder)
-        AgentResponse::Metrics(MetricsData {
+        let mut data = MetricsData {
             timestamp: unix_timestamp(),
             kind: MetricsKind::Rx,
             samples: 0,
@@ -360,7 +390,27 @@ impl AgentServer {
             avg_latency_us: None,
             ber_estimate: None,
             packets_sent: None,
-        })
+        };
+
+        // Counters of the running receiver, when it serves them
+        if let Some(body) = self.rx_metrics_port.and_then(|port| scrape(("127.0.0.1", port)).ok()) {
+            let values = parse_prometheus(&body);
+            let value = |name: &str| {
+                values
+                    .iter()
+                    .find(|(series, _)| series == name || series.starts_with(&format!("{}{{", name)))
+                    .map(|(_, v)| *v)
+            };
+            data.samples = value("r4w_rx_samples_total").unwrap_or(0.0) as u64;
+            if let (Some(sum), Some(count)) = (
+                value("r4w_processing_latency_us_sum"),
+                value("r4w_processing_latency_us_count"),
+            ) {
+                data.avg_latency_us = (count > 0.0).then(|| sum / count);
+            }
+        }
+
+        AgentResponse::Metrics(data)
     }
 
     fn cmd_list_waveforms(&self) -> AgentResponse {
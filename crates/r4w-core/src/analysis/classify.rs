@@ -0,0 +1,1363 @@
+//! Automatic Modulation Classification
+//!
+//! Blind identification of an unknown capture. The classifier estimates the
+//! occupied bandwidth, carrier offset, SNR and symbol rate of the strongest
+//! signal and returns a ranked list of modulation hypotheses with a
+//! confidence for each.
+//!
+//! ## Features
+//!
+//! - **Spectrum**: Welch PSD for noise floor, in-band SNR, 99% bandwidth and
+//!   centre offset; the capture is then band-limited and mixed to baseband
+//! - **Envelope**: coefficient of variation, fraction of "off" samples and the
+//!   number of discrete amplitude levels (OOK / ASK / QAM rings)
+//! - **Instantaneous frequency**: histogram peaks give the FSK order and tell
+//!   tones apart from frequency-hopping and analogue FM
+//! - **Power-law spectral lines**: a line in x^k identifies k-PSK (and the
+//!   carrier offset); a line in x^1 is a carrier (CW, AM, ASK)
+//! - **Higher-order cumulants**: noise-corrected C40/C42 separate PSK, QAM,
+//!   PAM and Gaussian-like (OFDM) constellations; a lattice fit gives the
+//!   QAM order
+//! - **Cyclic prefix**: an isolated autocorrelation peak at lag N gives the
+//!   OFDM FFT size, and the cyclic frequency of the lag-N product gives the
+//!   symbol period and hence the CP length
+//! - **Chirp rate**: a constant second phase difference identifies CSS/LoRa
+//! - **Symbol rate**: the strongest cyclostationary spectral line of |x|²,
+//!   |Δx|² or |Δf| (cyclic frequency = symbol rate)
+//!
+//! ## Example
+//!
+//! ```rust
+//! use r4w_core::analysis::{ModulationClassifier, ModulationFamily};
+//! use r4w_core::types::IQSample;
+//!
+//! // A plain carrier 1 kHz above centre
+//! let fs = 48_000.0;
+//! let samples: Vec<IQSample> = (0..16384)
+//!     .map(|n| IQSample::from_polar(1.0, 2.0 * std::f64::consts::PI * 1000.0 * n as f64 / fs))
+//!     .collect();
+//!
+//! let result = ModulationClassifier::new(fs).classify(&samples);
+//! assert_eq!(result.best().family, ModulationFamily::Tone);
+//! assert!((result.center_offset_hz - 1000.0).abs() < 50.0);
+//! ```
+
+use crate::fft_utils::FftProcessor;
+use crate::types::IQSample;
+use rustfft::num_complex::Complex64;
+use std::f64::consts::PI;
+use std::fmt;
+
+/// Modulation family hypothesis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModulationFamily {
+    /// No signal above the noise floor
+    Noise,
+    /// Unmodulated carrier (CW)
+    Tone,
+    /// Analogue amplitude modulation (carrier plus continuous envelope)
+    Am,
+    /// Analogue frequency modulation (continuous instantaneous frequency)
+    Fm,
+    /// On-off keying (including pulse-position schemes)
+    Ook,
+    /// M-ary amplitude shift keying
+    Ask(usize),
+    /// M-ary frequency shift keying
+    Fsk(usize),
+    /// M-ary phase shift keying
+    Psk(usize),
+    /// M-ary quadrature amplitude modulation
+    Qam(usize),
+    /// Chirp spread spectrum (LoRa, FMCW)
+    Css,
+    /// OFDM with the detected FFT size and cyclic prefix length (samples)
+    Ofdm {
+        /// FFT size in samples
+        fft_size: usize,
+        /// Cyclic prefix length in samples
+        cp_len: usize,
+    },
+    /// Frequency-hopping spread spectrum
+    Fhss,
+    /// Signal present but no family fits
+    Unknown,
+}
+
+impl ModulationFamily {
+    /// Short family name without the order (e.g. "PSK")
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Noise => "Noise",
+            Self::Tone => "Tone",
+            Self::Am => "AM",
+            Self::Fm => "FM",
+            Self::Ook => "OOK",
+            Self::Ask(_) => "ASK",
+            Self::Fsk(_) => "FSK",
+            Self::Psk(_) => "PSK",
+            Self::Qam(_) => "QAM",
+            Self::Css => "CSS",
+            Self::Ofdm { .. } => "OFDM",
+            Self::Fhss => "FHSS",
+            Self::Unknown => "Unknown",
+        }
+    }
+
+    /// Modulation order, if the family has one
+    pub fn order(&self) -> Option<usize> {
+        match *self {
+            Self::Ask(m) | Self::Fsk(m) | Self::Psk(m) | Self::Qam(m) => Some(m),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ModulationFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Ofdm { fft_size, cp_len } => write!(f, "OFDM (N={}, CP={})", fft_size, cp_len),
+            other => match other.order() {
+                Some(m) => write!(f, "{}-{}", m, other.name()),
+                None => write!(f, "{}", other.name()),
+            },
+        }
+    }
+}
+
+/// A ranked classification hypothesis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hypothesis {
+    /// Modulation family
+    pub family: ModulationFamily,
+    /// Confidence in [0, 1]; confidences of all hypotheses sum to 1
+    pub confidence: f64,
+}
+
+/// Features extracted from the capture (useful for debugging a decision)
+#[derive(Debug, Clone, Default)]
+pub struct SignalFeatures {
+    /// Envelope standard deviation over mean
+    pub envelope_cv: f64,
+    /// Fraction of samples with envelope below a quarter of the RMS
+    pub off_fraction: f64,
+    /// Number of discrete envelope levels (0 if the envelope is continuous)
+    pub envelope_levels: usize,
+    /// Number of peaks in the instantaneous-frequency histogram
+    pub frequency_peaks: usize,
+    /// Fraction of instantaneous-frequency mass near those peaks
+    pub frequency_concentration: f64,
+    /// Smallest power k in {1, 2, 4, 8} with a spectral line in x^k (0 if none)
+    pub power_line_order: usize,
+    /// Fraction of x^k energy in that line
+    pub power_line_strength: f64,
+    /// Noise-corrected |C40|
+    pub c40: f64,
+    /// Noise-corrected C42
+    pub c42: f64,
+    /// Best square-lattice level count per axis (QAM order = levels²)
+    pub lattice_levels: usize,
+    /// Lattice fit quality in [0, 1]
+    pub lattice_fit: f64,
+    /// Fraction of lag-L phase steps that agree with the median chirp angle
+    pub chirp_score: f64,
+    /// Chirp rate in Hz/s
+    pub chirp_rate: f64,
+    /// Cyclic-prefix autocorrelation peak prominence (0 if none)
+    pub cp_prominence: f64,
+    /// Detected OFDM FFT size (0 if none)
+    pub cp_fft_size: usize,
+    /// Detected OFDM cyclic prefix length
+    pub cp_len: usize,
+    /// Number of distinct hop channels seen by the short-time tracker
+    pub hop_channels: usize,
+    /// Fraction of short-time frames whose bandwidth is far below the total
+    pub hop_narrow_fraction: f64,
+}
+
+/// Result of classifying a capture
+#[derive(Debug, Clone)]
+pub struct Classification {
+    /// Hypotheses, best first
+    pub hypotheses: Vec<Hypothesis>,
+    /// Estimated symbol rate in symbols/second
+    pub symbol_rate: Option<f64>,
+    /// 99% occupied bandwidth in Hz
+    pub bandwidth_hz: f64,
+    /// Centre of the occupied band relative to the capture centre in Hz
+    pub center_offset_hz: f64,
+    /// In-band SNR estimate in dB
+    pub snr_db: f64,
+    /// Sample rate used for the analysis
+    pub sample_rate: f64,
+    /// Number of samples analysed
+    pub num_samples: usize,
+    /// Underlying features
+    pub features: SignalFeatures,
+}
+
+impl Classification {
+    /// The most likely hypothesis
+    pub fn best(&self) -> &Hypothesis {
+        &self.hypotheses[0]
+    }
+
+    /// Format as human-readable text
+    pub fn to_text(&self) -> String {
+        let mut output = String::new();
+        output.push_str("Modulation Classification\n");
+        output.push_str(&"═".repeat(50));
+        output.push('\n');
+
+        output.push_str(&format!("Samples:           {}\n", self.num_samples));
+        output.push_str(&format!("Sample Rate:       {:.0} Hz\n", self.sample_rate));
+        output.push_str(&format!("SNR (in-band):     {:.1} dB\n", self.snr_db));
+        output.push_str(&format!("Bandwidth (99%):   {:.1} Hz\n", self.bandwidth_hz));
+        output.push_str(&format!("Centre Offset:     {:.1} Hz\n", self.center_offset_hz));
+        match self.symbol_rate {
+            Some(rate) => output.push_str(&format!("Symbol Rate:       {:.1} sym/s\n", rate)),
+            None => output.push_str("Symbol Rate:       -\n"),
+        }
+
+        output.push_str("\nHypotheses\n");
+        output.push_str(&"─".repeat(50));
+        output.push('\n');
+        for (rank, h) in self.hypotheses.iter().enumerate() {
+            output.push_str(&format!(
+                "{:>2}. {:<24} {:>5.1}%\n",
+                rank + 1,
+                h.family.to_string(),
+                h.confidence * 100.0
+            ));
+        }
+
+        let f = &self.features;
+        output.push_str("\nFeatures\n");
+        output.push_str(&"─".repeat(50));
+        output.push('\n');
+        output.push_str(&format!("Envelope CV:       {:.3}\n", f.envelope_cv));
+        output.push_str(&format!("Envelope Levels:   {}\n", f.envelope_levels));
+        output.push_str(&format!("Off Fraction:      {:.3}\n", f.off_fraction));
+        output.push_str(&format!(
+            "Freq Peaks:        {} ({:.0}% concentrated)\n",
+            f.frequency_peaks,
+            f.frequency_concentration * 100.0
+        ));
+        output.push_str(&format!(
+            "Power Line:        x^{} ({:.2})\n",
+            f.power_line_order, f.power_line_strength
+        ));
+        output.push_str(&format!("|C40| / C42:       {:.3} / {:.3}\n", f.c40, f.c42));
+        output.push_str(&format!(
+            "Lattice:           {} levels ({:.2})\n",
+            f.lattice_levels, f.lattice_fit
+        ));
+        output.push_str(&format!(
+            "Chirp:             {:.2} ({:.3e} Hz/s)\n",
+            f.chirp_score, f.chirp_rate
+        ));
+        output.push_str(&format!(
+            "Cyclic Prefix:     {:.1} (N={}, CP={})\n",
+            f.cp_prominence, f.cp_fft_size, f.cp_len
+        ));
+        output.push_str(&format!(
+            "Hop Channels:      {} ({:.0}% narrow frames)\n",
+            f.hop_channels,
+            f.hop_narrow_fraction * 100.0
+        ));
+
+        output
+    }
+
+    /// Format as JSON
+    pub fn to_json(&self) -> String {
+        let hypotheses: Vec<String> = self
+            .hypotheses
+            .iter()
+            .map(|h| {
+                format!(
+                    r#"    {{
+      "family": "{}",
+      "label": "{}",
+      "order": {},
+      "confidence": {:.6}
+    }}"#,
+                    h.family.name(),
+                    h.family,
+                    h.family
+                        .order()
+                        .map(|m| m.to_string())
+                        .unwrap_or_else(|| "null".to_string()),
+                    h.confidence
+                )
+            })
+            .collect();
+
+        format!(
+            r#"{{
+  "num_samples": {},
+  "sample_rate": {:.0},
+  "snr_db": {:.6},
+  "bandwidth_hz": {:.6},
+  "center_offset_hz": {:.6},
+  "symbol_rate": {},
+  "hypotheses": [
+{}
+  ]
+}}"#,
+            self.num_samples,
+            self.sample_rate,
+            self.snr_db,
+            self.bandwidth_hz,
+            self.center_offset_hz,
+            self.symbol_rate
+                .map(|r| format!("{:.6}", r))
+                .unwrap_or_else(|| "null".to_string()),
+            hypotheses.join(",\n")
+        )
+    }
+}
+
+/// Blind modulation classifier
+#[derive(Debug, Clone)]
+pub struct ModulationClassifier {
+    sample_rate: f64,
+    fft_size: usize,
+    max_samples: usize,
+}
+
+impl ModulationClassifier {
+    /// Create a classifier for captures at the given sample rate
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            sample_rate,
+            fft_size: 1024,
+            max_samples: 1 << 17,
+        }
+    }
+
+    /// Set the FFT size used for the PSD estimate (power of two)
+    pub fn with_fft_size(mut self, fft_size: usize) -> Self {
+        self.fft_size = fft_size.next_power_of_two().max(64);
+        self
+    }
+
+    /// Limit the number of samples analysed (the start of the capture is used)
+    pub fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples.max(1024);
+        self
+    }
+
+    /// Classify a capture
+    pub fn classify(&self, samples: &[IQSample]) -> Classification {
+        let samples = &samples[..samples.len().min(self.max_samples)];
+        let fs = self.sample_rate;
+        let mut features = SignalFeatures::default();
+
+        let mut result = Classification {
+            hypotheses: vec![Hypothesis {
+                family: ModulationFamily::Noise,
+                confidence: 1.0,
+            }],
+            symbol_rate: None,
+            bandwidth_hz: 0.0,
+            center_offset_hz: 0.0,
+            snr_db: f64::NEG_INFINITY,
+            sample_rate: fs,
+            num_samples: samples.len(),
+            features: SignalFeatures::default(),
+        };
+        if samples.len() < 256 {
+            return result;
+        }
+
+        // Spectrum: noise floor, SNR, occupied band
+        let nfft = self.fft_size.min((samples.len() / 4).next_power_of_two()).max(64);
+        let psd = welch_psd(samples, nfft);
+        let mut band = occupied_band(&psd);
+        // A signal filling the whole capture leaves no quiet bins to measure
+        // the floor from. The envelope-moment estimate only ever
+        // underestimates, and is below the in-band SNR of a narrower signal,
+        // so it wins only when the PSD estimate has failed.
+        if let Some(full) = full_band(samples, &psd).filter(|full| full.snr > band.snr) {
+            band = full;
+        }
+        let bin_hz = fs / nfft as f64;
+        result.snr_db = 10.0 * band.snr.max(1e-12).log10();
+        result.bandwidth_hz = (band.width_bins * bin_hz).max(bin_hz);
+        result.center_offset_hz = band.center_bin * bin_hz - fs / 2.0;
+        if result.snr_db < 0.0 {
+            return result;
+        }
+
+        // Band-limit, mix to baseband and normalise to unit power
+        let z = baseband(samples, fs, result.center_offset_hz, result.bandwidth_hz, bin_hz);
+        let signal_fraction = band.snr / (1.0 + band.snr);
+        let bw_norm = (result.bandwidth_hz / fs).min(1.0);
+
+        envelope_features(&z, &mut features);
+        let inst_freq = instantaneous_frequency(&z);
+        result.symbol_rate = symbol_rate(&z, &inst_freq, fs, result.bandwidth_hz);
+        let symbol_period = result.symbol_rate.map(|rate| fs / rate);
+        frequency_features(&z, &inst_freq, bw_norm, symbol_period, &mut features);
+        let cfo = power_line_features(&z, &mut features);
+        cumulant_features(&z, cfo, signal_fraction, symbol_period, &mut features);
+        chirp_features(&z, fs, &mut features);
+        cyclic_prefix_features(&z, &mut features);
+        hop_features(&z, bw_norm, &mut features);
+
+        if features.power_line_order > 0 {
+            result.center_offset_hz += cfo * fs;
+        }
+        result.hypotheses = rank(&features, result.snr_db);
+
+        match result.hypotheses[0].family {
+            ModulationFamily::Ofdm { fft_size, cp_len } => {
+                result.symbol_rate = Some(fs / (fft_size + cp_len) as f64);
+            }
+            ModulationFamily::Css if features.chirp_rate.abs() > 0.0 => {
+                result.symbol_rate = Some(features.chirp_rate.abs() / result.bandwidth_hz);
+            }
+            ModulationFamily::Tone | ModulationFamily::Noise | ModulationFamily::Am | ModulationFamily::Fm => {
+                result.symbol_rate = None
+            }
+            _ => {}
+        }
+
+        result.features = features;
+        result
+    }
+}
+
+/// Welch PSD (Hann window, 50% overlap), DC-centred, normalised so the bins
+/// sum to the mean sample power
+fn welch_psd(samples: &[IQSample], nfft: usize) -> Vec<f64> {
+    let window: Vec<f64> = (0..nfft)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / nfft as f64).cos())
+        .collect();
+    let window_power: f64 = window.iter().map(|w| w * w).sum();
+    let mut fft = FftProcessor::new(nfft);
+    let mut psd = vec![0.0; nfft];
+    let mut frames = 0usize;
+    let mut buffer = vec![Complex64::new(0.0, 0.0); nfft];
+
+    let mut start = 0;
+    while start + nfft <= samples.len() {
+        for (i, b) in buffer.iter_mut().enumerate() {
+            *b = samples[start + i] * window[i];
+        }
+        fft.fft_inplace(&mut buffer);
+        for (p, b) in psd.iter_mut().zip(buffer.iter()) {
+            *p += b.norm_sqr();
+        }
+        frames += 1;
+        start += nfft / 2;
+    }
+
+    let scale = 1.0 / (frames.max(1) as f64 * window_power * nfft as f64);
+    FftProcessor::fft_shift(&psd).iter().map(|p| p * scale).collect()
+}
+
+struct Band {
+    snr: f64,
+    center_bin: f64,
+    width_bins: f64,
+}
+
+/// Noise floor from the quietest bins, then the 99% band of the excess power
+fn occupied_band(psd: &[f64]) -> Band {
+    let n = psd.len();
+    let mut sorted = psd.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let quiet = (n / 10).max(1);
+    let floor = sorted[..quiet].iter().sum::<f64>() / quiet as f64;
+    // The quietest tenth of an exponential-ish distribution sits below the mean
+    let floor = floor * 1.15;
+
+    let excess: Vec<f64> = psd.iter().map(|&p| (p - 1.5 * floor).max(0.0)).collect();
+    let total: f64 = excess.iter().sum();
+    if total <= 0.0 {
+        return Band {
+            snr: 0.0,
+            center_bin: n as f64 / 2.0,
+            width_bins: 0.0,
+        };
+    }
+
+    let mut acc = 0.0;
+    let mut lo = 0;
+    let mut hi = n - 1;
+    let mut found_lo = false;
+    for (i, &e) in excess.iter().enumerate() {
+        acc += e;
+        if !found_lo && acc >= 0.005 * total {
+            lo = i;
+            found_lo = true;
+        }
+        if acc >= 0.995 * total {
+            hi = i;
+            break;
+        }
+    }
+    let width = (hi - lo + 1) as f64;
+    let center = excess
+        .iter()
+        .enumerate()
+        .map(|(i, &e)| i as f64 * e)
+        .sum::<f64>()
+        / total;
+
+    let signal: f64 = psd[lo..=hi].iter().map(|&p| (p - floor).max(0.0)).sum();
+    let noise = floor * width;
+    Band {
+        snr: signal / noise.max(1e-30),
+        center_bin: center,
+        width_bins: width,
+    }
+}
+
+/// M2M4 SNR estimate for a constant-envelope signal occupying the whole
+/// capture, with the 99% band of the total power
+fn full_band(samples: &[IQSample], psd: &[f64]) -> Option<Band> {
+    let n = samples.len() as f64;
+    let m2 = samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / n;
+    let m4 = samples.iter().map(|s| s.norm_sqr().powi(2)).sum::<f64>() / n;
+    let signal = (2.0 * m2 * m2 - m4).max(0.0).sqrt();
+    let snr = signal / (m2 - signal).max(1e-12 * m2);
+    if snr < 4.0 {
+        return None;
+    }
+
+    let total: f64 = psd.iter().sum();
+    let mut acc = 0.0;
+    let mut lo = None;
+    let mut hi = psd.len() - 1;
+    for (i, &p) in psd.iter().enumerate() {
+        acc += p;
+        if lo.is_none() && acc >= 0.005 * total {
+            lo = Some(i);
+        }
+        if acc >= 0.995 * total {
+            hi = i;
+            break;
+        }
+    }
+    let lo = lo.unwrap_or(0);
+    let center = psd.iter().enumerate().map(|(i, &p)| i as f64 * p).sum::<f64>() / total;
+    Some(Band {
+        snr,
+        center_bin: center,
+        width_bins: (hi - lo + 1) as f64,
+    })
+}
+
+/// Brick-wall filter the occupied band, mix it to DC and normalise to unit power
+fn baseband(samples: &[IQSample], fs: f64, center_hz: f64, bandwidth_hz: f64, margin_hz: f64) -> Vec<IQSample> {
+    let n = samples.len().next_power_of_two();
+    let mut fft = FftProcessor::new(n);
+    let mut spectrum = fft.fft(samples);
+    let half_width = bandwidth_hz * 0.6 + 2.0 * margin_hz;
+    for (k, bin) in spectrum.iter_mut().enumerate() {
+        let f = if k < n / 2 { k as f64 } else { k as f64 - n as f64 } * fs / n as f64;
+        let mut offset = (f - center_hz).rem_euclid(fs);
+        if offset > fs / 2.0 {
+            offset -= fs;
+        }
+        if offset.abs() > half_width {
+            *bin = Complex64::new(0.0, 0.0);
+        }
+    }
+    fft.ifft_inplace(&mut spectrum);
+    spectrum.truncate(samples.len());
+
+    let step = -2.0 * PI * center_hz / fs;
+    let mut z: Vec<IQSample> = spectrum
+        .iter()
+        .enumerate()
+        .map(|(i, s)| s * Complex64::from_polar(1.0, step * i as f64))
+        .collect();
+    normalize(&mut z);
+    z
+}
+
+fn normalize(z: &mut [IQSample]) {
+    let power = z.iter().map(|s| s.norm_sqr()).sum::<f64>() / z.len().max(1) as f64;
+    if power > 0.0 {
+        let scale = 1.0 / power.sqrt();
+        for s in z.iter_mut() {
+            *s *= scale;
+        }
+    }
+}
+
+/// Instantaneous frequency in cycles/sample
+fn instantaneous_frequency(z: &[IQSample]) -> Vec<f64> {
+    z.windows(2)
+        .map(|w| (w[1] * w[0].conj()).arg() / (2.0 * PI))
+        .collect()
+}
+
+fn moving_average(x: &[f64], len: usize) -> Vec<f64> {
+    if len <= 1 || x.len() < len {
+        return x.to_vec();
+    }
+    let mut out = Vec::with_capacity(x.len() - len + 1);
+    let mut acc: f64 = x[..len].iter().sum();
+    out.push(acc / len as f64);
+    for i in len..x.len() {
+        acc += x[i] - x[i - len];
+        out.push(acc / len as f64);
+    }
+    out
+}
+
+/// Peaks of a histogram: local maxima above 8% of the largest that are
+/// separated from their neighbours by a clear valley
+fn histogram_peaks(hist: &[f64]) -> Vec<usize> {
+    let smooth: Vec<f64> = (0..hist.len())
+        .map(|i| {
+            let lo = i.saturating_sub(1);
+            let hi = (i + 1).min(hist.len() - 1);
+            hist[lo..=hi].iter().sum::<f64>() / (hi - lo + 1) as f64
+        })
+        .collect();
+    let max = smooth.iter().cloned().fold(0.0, f64::max);
+    if max <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut peaks: Vec<usize> = Vec::new();
+    for i in 0..smooth.len() {
+        let left = if i > 0 { smooth[i - 1] } else { 0.0 };
+        let right = if i + 1 < smooth.len() { smooth[i + 1] } else { 0.0 };
+        if smooth[i] < 0.08 * max || smooth[i] < left || smooth[i] <= right {
+            continue;
+        }
+        if let Some(&prev) = peaks.last() {
+            let valley = smooth[prev..=i].iter().cloned().fold(f64::INFINITY, f64::min);
+            if valley > 0.6 * smooth[prev].min(smooth[i]) {
+                // Same lobe: keep the taller one
+                if smooth[i] > smooth[prev] {
+                    peaks.pop();
+                    peaks.push(i);
+                }
+                continue;
+            }
+        }
+        peaks.push(i);
+    }
+    peaks
+}
+
+fn envelope_features(z: &[IQSample], features: &mut SignalFeatures) {
+    let env: Vec<f64> = z.iter().map(|s| s.norm()).collect();
+    let n = env.len() as f64;
+    let mean = env.iter().sum::<f64>() / n;
+    let var = env.iter().map(|a| (a - mean).powi(2)).sum::<f64>() / n;
+    features.envelope_cv = var.sqrt() / mean.max(1e-12);
+    // z has unit RMS
+    features.off_fraction = env.iter().filter(|&&a| a < 0.25).count() as f64 / n;
+
+    let mut sorted = env.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let top = sorted[(sorted.len() * 999) / 1000].max(1e-12);
+    let bins = 64;
+    let mut hist = vec![0.0; bins];
+    for a in &env {
+        let idx = ((a / top) * (bins - 1) as f64).round() as usize;
+        if idx < bins {
+            hist[idx] += 1.0;
+        }
+    }
+    let peaks = histogram_peaks(&hist);
+    let near: f64 = peaks
+        .iter()
+        .map(|&p| hist[p.saturating_sub(3)..=(p + 3).min(bins - 1)].iter().sum::<f64>())
+        .sum();
+    let total: f64 = hist.iter().sum();
+    features.envelope_levels = if near / total.max(1.0) > 0.6 { peaks.len() } else { 0 };
+}
+
+fn frequency_features(
+    z: &[IQSample],
+    inst_freq: &[f64],
+    bw_norm: f64,
+    symbol_period: Option<f64>,
+    features: &mut SignalFeatures,
+) {
+    let range = (0.6 * bw_norm).clamp(1e-4, 0.5);
+    let power: Vec<f64> = z.iter().map(|s| s.norm_sqr()).collect();
+
+    // Smooth over roughly one reciprocal bandwidth to suppress noise
+    let len = ((1.0 / bw_norm.max(1e-6)).round() as usize).clamp(1, 64);
+    let smooth = moving_average(inst_freq, len);
+    let weights = moving_average(&power, len + 1);
+    let (mut peaks, mut concentration) = frequency_histogram(smooth.iter().copied().zip(weights.iter().copied()), range);
+
+    // Pulse-shaped FSK never dwells on its tones; sampling the frequency at
+    // the symbol centres recovers them
+    if let Some(period) = symbol_period.filter(|&p| p >= 4.0) {
+        let len = ((period / 2.0).round() as usize).max(1);
+        let smooth = moving_average(inst_freq, len);
+        let weights = moving_average(&power, len + 1);
+        let count = ((smooth.len() as f64 - 1.0) / period) as usize;
+        for phase in 0..8 {
+            let offset = phase as f64 * period / 8.0;
+            let strobes = (0..count)
+                .map(|k| (offset + k as f64 * period).round() as usize)
+                .filter(|&i| i < smooth.len())
+                .map(|i| (smooth[i], weights[i]));
+            let (p, c) = frequency_histogram(strobes, range);
+            if c > concentration + 0.1 && p.len() >= peaks.len() && equally_spaced(&p) {
+                peaks = p;
+                concentration = c;
+            }
+        }
+    }
+
+    features.frequency_peaks = peaks.len();
+    features.frequency_concentration = concentration;
+}
+
+/// Power-weighted histogram of instantaneous frequency over ±range; returns
+/// the peaks and the fraction of weight near them
+fn frequency_histogram(values: impl Iterator<Item = (f64, f64)>, range: f64) -> (Vec<usize>, f64) {
+    let bins = 128;
+    let mut hist = vec![0.0; bins];
+    for (f, w) in values {
+        // Ignore "off" samples (OOK gaps) where the phase is pure noise
+        if w < 0.1 {
+            continue;
+        }
+        let idx = ((f + range) / (2.0 * range) * bins as f64).floor();
+        if idx >= 0.0 && (idx as usize) < bins {
+            hist[idx as usize] += w;
+        }
+    }
+    let total: f64 = hist.iter().sum();
+    // Peaks at the edge of the range are phase flips (PSK) folding to ±fs/2
+    let peaks: Vec<usize> = histogram_peaks(&hist)
+        .into_iter()
+        .filter(|&p| p >= 2 && p + 2 < bins)
+        .collect();
+    let near: f64 = (0..bins)
+        .filter(|&i| peaks.iter().any(|&p| i.abs_diff(p) <= 2))
+        .map(|i| hist[i])
+        .sum();
+    let concentration = if total > 0.0 { near / total } else { 0.0 };
+    (peaks, concentration)
+}
+
+/// 2 to 8 peaks on a regular grid, as the tones of an M-FSK signal are
+fn equally_spaced(peaks: &[usize]) -> bool {
+    if !(2..=8).contains(&peaks.len()) {
+        return false;
+    }
+    let spacing = (peaks[peaks.len() - 1] - peaks[0]) as f64 / (peaks.len() - 1) as f64;
+    peaks
+        .windows(2)
+        .all(|w| ((w[1] - w[0]) as f64 - spacing).abs() <= 2.0_f64.max(0.15 * spacing))
+}
+
+/// Fraction of energy in the strongest bin (±2) of an FFT, and its frequency
+/// in cycles/sample
+fn strongest_line(w: &[IQSample]) -> (f64, f64) {
+    let n = w.len().next_power_of_two();
+    let mut fft = FftProcessor::new(n);
+    let spectrum = fft.fft(w);
+    let power: Vec<f64> = spectrum.iter().map(|c| c.norm_sqr()).collect();
+    let total: f64 = power.iter().sum();
+    if total <= 0.0 {
+        return (0.0, 0.0);
+    }
+    let (peak, _) = power
+        .iter()
+        .enumerate()
+        .fold((0, 0.0), |best, (i, &p)| if p > best.1 { (i, p) } else { best });
+    let line: f64 = (0..5)
+        .map(|d| power[(peak + n + d - 2) % n])
+        .sum();
+    // Zero padding spreads energy; compensate so a pure tone scores ~1
+    let strength = (line / total * n as f64 / w.len() as f64).min(1.0);
+
+    let mut freq = (peak as f64 + parabolic_offset(&power, peak)) / n as f64;
+    if freq > 0.5 {
+        freq -= 1.0;
+    }
+    (strength, freq)
+}
+
+/// Spectral lines of x^k; returns the carrier offset (cycles/sample) implied
+/// by the detected line
+///
+/// The lowest power whose line is nearly as strong as the strongest wins, so
+/// BPSK reports x^2 even though x^4 and x^8 also carry a line.
+fn power_line_features(z: &[IQSample], features: &mut SignalFeatures) -> f64 {
+    let lines: Vec<(usize, f64, f64)> = [1usize, 2, 4, 8]
+        .iter()
+        .map(|&k| {
+            let w: Vec<IQSample> = z.iter().map(|s| s.powu(k as u32)).collect();
+            let (strength, freq) = strongest_line(&w);
+            (k, strength, freq)
+        })
+        .collect();
+    let strongest = lines.iter().map(|l| l.1).fold(0.0, f64::max);
+    match lines.iter().find(|l| l.1 > 0.2 && l.1 >= 0.8 * strongest) {
+        Some(&(k, strength, freq)) => {
+            features.power_line_order = k;
+            features.power_line_strength = strength;
+            freq / k as f64
+        }
+        // No line: still use the x^4 peak to de-rotate a square constellation
+        None => lines[2].2 / 4.0,
+    }
+}
+
+/// Noise-corrected fourth-order cumulants and a square-lattice (QAM) fit
+fn cumulant_features(
+    z: &[IQSample],
+    cfo: f64,
+    signal_fraction: f64,
+    symbol_period: Option<f64>,
+    features: &mut SignalFeatures,
+) {
+    let step = -2.0 * PI * cfo;
+    let x: Vec<IQSample> = z
+        .iter()
+        .enumerate()
+        .map(|(i, s)| s * Complex64::from_polar(1.0, step * i as f64))
+        .collect();
+    let n = x.len() as f64;
+    let m20 = x.iter().map(|s| s * s).sum::<Complex64>() / n;
+    let m40 = x.iter().map(|s| s.powu(4)).sum::<Complex64>() / n;
+    let m42 = x.iter().map(|s| s.norm_sqr().powi(2)).sum::<f64>() / n;
+    let c40 = m40 - 3.0 * m20 * m20;
+    let c42 = m42 - m20.norm_sqr() - 2.0;
+    // Gaussian noise has zero fourth-order cumulants; rescale to unit signal power
+    let s2 = signal_fraction.max(0.05).powi(2);
+    features.c40 = c40.norm() / s2;
+    features.c42 = c42 / s2;
+
+    // Fit the lattice to symbol-centre points when the symbol rate is known:
+    // averaging over half a symbol is close to a matched filter and removes
+    // most of the noise that would otherwise blur a dense constellation
+    let (points, gain) = match symbol_period.filter(|&p| p >= 4.0) {
+        Some(period) => {
+            let points = symbol_strobes(&x, period);
+            let power = points.iter().map(|s| s.norm_sqr()).sum::<f64>() / points.len().max(1) as f64;
+            (points, 1.0 / power.max(1e-12).sqrt())
+        }
+        None => (x, 1.0 / signal_fraction.max(0.05).sqrt()),
+    };
+    if points.is_empty() {
+        return;
+    }
+
+    // De-rotate so a square constellation sits on the axes (m40 is negative real)
+    let m40 = points.iter().map(|s| s.powu(4)).sum::<Complex64>();
+    let theta = (m40.arg() - PI) / 4.0;
+    let rot = Complex64::from_polar(gain, -theta);
+    let axes: Vec<f64> = points
+        .iter()
+        .flat_map(|s| {
+            let r = s * rot;
+            [r.re, r.im]
+        })
+        .collect();
+
+    let mut best = (0usize, 0.0f64);
+    for levels in [2usize, 4, 8, 16] {
+        let l = levels as f64;
+        let a = (3.0 / (2.0 * (l * l - 1.0))).sqrt();
+        let mse = axes
+            .iter()
+            .map(|&v| {
+                let idx = ((v / a + l - 1.0) / 2.0).round().clamp(0.0, l - 1.0);
+                let level = (2.0 * idx - l + 1.0) * a;
+                (v - level).powi(2)
+            })
+            .sum::<f64>()
+            / axes.len() as f64;
+        let fit = 1.0 - mse / ((2.0 * a).powi(2) / 12.0);
+        if fit > best.1 {
+            best = (levels, fit);
+        }
+    }
+    features.lattice_levels = best.0;
+    features.lattice_fit = best.1.max(0.0);
+}
+
+/// One point per symbol: the signal averaged over half a symbol, taken at
+/// the timing phase with the most energy
+fn symbol_strobes(x: &[IQSample], period: f64) -> Vec<IQSample> {
+    let len = ((period / 2.0).round() as usize).max(1);
+    if x.len() < len + 1 {
+        return Vec::new();
+    }
+    let mut averaged = Vec::with_capacity(x.len() - len + 1);
+    let mut acc: IQSample = x[..len].iter().sum();
+    averaged.push(acc / len as f64);
+    for i in len..x.len() {
+        acc += x[i] - x[i - len];
+        averaged.push(acc / len as f64);
+    }
+
+    let count = ((averaged.len() - 1) as f64 / period) as usize;
+    let strobe = |offset: f64| -> Vec<IQSample> {
+        (0..count)
+            .map(|k| averaged[((offset + k as f64 * period).round() as usize).min(averaged.len() - 1)])
+            .collect()
+    };
+    (0..8)
+        .map(|phase| strobe(phase as f64 * period / 8.0))
+        .max_by(|a, b| {
+            let pa: f64 = a.iter().map(|s| s.norm_sqr()).sum();
+            let pb: f64 = b.iter().map(|s| s.norm_sqr()).sum();
+            pa.partial_cmp(&pb).unwrap()
+        })
+        .unwrap_or_default()
+}
+
+/// A chirp has a constant second phase difference
+///
+/// The mean second difference gives a first rate estimate (biased by
+/// down-chirps, gaps and tapering). It is then re-measured over a lag L
+/// chosen to turn the chirp into about one radian per step, where noise is
+/// small; a chirp puts most of those steps at one non-zero angle.
+fn chirp_features(z: &[IQSample], fs: f64, features: &mut SignalFeatures) {
+    let p: Vec<IQSample> = z.windows(2).map(|w| w[1] * w[0].conj()).collect();
+    let sum: IQSample = p.windows(2).map(|w| w[1] * w[0].conj()).sum();
+    let coarse = sum.arg() / (2.0 * PI); // cycles/sample²
+    features.chirp_rate = coarse * fs * fs;
+
+    let lag = ((1.0 / (2.0 * PI * coarse.abs().max(1e-12))).sqrt() as usize).clamp(1, 1024);
+    if z.len() < 16 * lag {
+        return;
+    }
+    let r: Vec<IQSample> = (0..z.len() - lag).map(|i| z[i + lag] * z[i].conj()).collect();
+    let q: Vec<IQSample> = (0..r.len() - lag).map(|i| r[i + lag] * r[i].conj()).collect();
+    let mean_mag = q.iter().map(|c| c.norm()).sum::<f64>() / q.len() as f64;
+    // Ignore gaps (OOK off time, FMCW idle) where the angle is noise
+    let mut angles: Vec<f64> = q
+        .iter()
+        .filter(|c| c.norm() > 0.25 * mean_mag)
+        .map(|c| c.arg())
+        .collect();
+    if angles.is_empty() {
+        return;
+    }
+    angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let median = angles[angles.len() / 2];
+    if median.abs() < 0.5 {
+        return;
+    }
+    let near = angles.iter().filter(|a| (*a - median).abs() < 0.35).count();
+    features.chirp_score = near as f64 / q.len() as f64;
+    features.chirp_rate = median / (2.0 * PI * (lag * lag) as f64) * fs * fs;
+}
+
+/// OFDM: isolated autocorrelation peak at lag N, then the CP length from the
+/// cyclic frequency of z[n]·z*[n+N]
+fn cyclic_prefix_features(z: &[IQSample], features: &mut SignalFeatures) {
+    let n = z.len();
+    let max_lag = (n / 8).min(8192);
+    if max_lag < 64 {
+        return;
+    }
+    let size = (2 * n).next_power_of_two();
+    let mut fft = FftProcessor::new(size);
+    let mut spectrum = fft.fft(z);
+    for c in spectrum.iter_mut() {
+        *c = Complex64::new(c.norm_sqr(), 0.0);
+    }
+    fft.ifft_inplace(&mut spectrum);
+    let r0 = spectrum[0].re.max(1e-30);
+    let acf: Vec<f64> = spectrum[..=max_lag].iter().map(|c| c.norm() / r0).collect();
+
+    let mut best = (0usize, 0.0f64);
+    for lag in 16..=max_lag / 2 {
+        if acf[lag] < 0.05 || acf[lag] < acf[lag - 1] || acf[lag] < acf[lag + 1] {
+            continue;
+        }
+        let lo = lag / 2;
+        let hi = (lag * 2).min(max_lag);
+        let mut neighbourhood: Vec<f64> = (lo..=hi)
+            .filter(|&i| i + 2 < lag || i > lag + 2)
+            .map(|i| acf[i])
+            .collect();
+        neighbourhood.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let median = neighbourhood[neighbourhood.len() / 2].max(1e-3);
+        let prominence = acf[lag] / median;
+        if prominence > best.1 {
+            best = (lag, prominence);
+        }
+    }
+    features.cp_prominence = best.1;
+    if best.1 < 4.0 {
+        return;
+    }
+    let fft_size = best.0;
+    features.cp_fft_size = fft_size;
+
+    // The lag-N product repeats once per OFDM symbol (N + CP samples)
+    let products: Vec<IQSample> = (0..n - fft_size).map(|i| z[i] * z[i + fft_size].conj()).collect();
+    let size = products.len().next_power_of_two();
+    let mut fft = FftProcessor::new(size);
+    let power: Vec<f64> = fft.fft(&products).iter().map(|c| c.norm_sqr()).collect();
+    let lo = ((size as f64 / (1.5 * fft_size as f64)).floor() as usize).max(2);
+    let hi = ((size as f64 / (fft_size + 1) as f64).ceil() as usize).min(size / 2 - 2);
+    if lo >= hi {
+        return;
+    }
+    let peak = (lo..=hi)
+        .max_by(|&a, &b| power[a].partial_cmp(&power[b]).unwrap())
+        .unwrap();
+    let freq = (peak as f64 + parabolic_offset(&power, peak)) / size as f64;
+    let period = (1.0 / freq).round() as usize;
+    features.cp_len = period.saturating_sub(fft_size).max(1);
+}
+
+/// Short-time spectral peaks: a hopper dwells on many distinct narrow channels
+fn hop_features(z: &[IQSample], bw_norm: f64, features: &mut SignalFeatures) {
+    let frame = 128;
+    if z.len() < frame * 8 || bw_norm < 0.05 {
+        return;
+    }
+    let mut fft = FftProcessor::new(frame);
+    let mut channels: Vec<f64> = Vec::new();
+    let mut narrow = 0usize;
+    let mut frames = 0usize;
+    for chunk in z.chunks_exact(frame) {
+        let spectrum = fft.fft(chunk);
+        let power: Vec<f64> = spectrum.iter().map(|c| c.norm_sqr()).collect();
+        let total: f64 = power.iter().sum();
+        if total < 0.25 * frame as f64 * frame as f64 {
+            continue;
+        }
+        frames += 1;
+        let mut sorted: Vec<(usize, f64)> = power.iter().cloned().enumerate().collect();
+        sorted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        // Bins holding 90% of the frame's power
+        let mut acc = 0.0;
+        let mut used = 0;
+        for (_, p) in &sorted {
+            acc += p;
+            used += 1;
+            if acc >= 0.9 * total {
+                break;
+            }
+        }
+        let frame_bw = used as f64 / frame as f64;
+        if frame_bw < 0.3 * bw_norm {
+            narrow += 1;
+            let mut f = sorted[0].0 as f64 / frame as f64;
+            if f > 0.5 {
+                f -= 1.0;
+            }
+            if channels.iter().all(|c| (c - f).abs() > 1.5 / frame as f64) {
+                channels.push(f);
+            }
+        }
+    }
+    features.hop_channels = channels.len();
+    features.hop_narrow_fraction = if frames > 0 { narrow as f64 / frames as f64 } else { 0.0 };
+}
+
+/// Symbol rate from the strongest cyclostationary line of |x|², |Δx|² or |Δf|
+fn symbol_rate(z: &[IQSample], inst_freq: &[f64], fs: f64, bandwidth_hz: f64) -> Option<f64> {
+    let envelope: Vec<f64> = z.iter().map(|s| s.norm_sqr()).collect();
+    let transitions: Vec<f64> = z.windows(2).map(|w| (w[1] - w[0]).norm_sqr()).collect();
+    let freq_steps: Vec<f64> = inst_freq.windows(2).map(|w| (w[1] - w[0]).abs()).collect();
+
+    let candidates: Vec<(f64, f64)> = [&envelope, &transitions, &freq_steps]
+        .into_iter()
+        .filter_map(|feature| cyclic_line(feature, fs, bandwidth_hz))
+        .collect();
+    let (best_rate, best_prominence) = candidates
+        .iter()
+        .cloned()
+        .fold(None, |best: Option<(f64, f64)>, c| match best {
+            Some(b) if b.1 >= c.1 => Some(b),
+            _ => Some(c),
+        })?;
+
+    // One feature may lock onto a harmonic (e.g. filter ringing on |x|²); if
+    // another feature has a clear line at a sub-multiple, that is the rate
+    let rate = candidates
+        .iter()
+        .filter(|(rate, prominence)| {
+            let ratio = best_rate / rate;
+            *prominence > 0.1 * best_prominence && ratio > 1.5 && (ratio - ratio.round()).abs() < 0.02 * ratio
+        })
+        .map(|(rate, _)| *rate)
+        .fold(best_rate, f64::min);
+    Some(rate)
+}
+
+/// Fundamental of the strongest spectral line of a real feature between a
+/// few bins and the occupied bandwidth; returns (frequency Hz, prominence)
+///
+/// Prominence is measured against the local continuum so the coloured
+/// spectrum of the feature itself (e.g. sinc² of NRZ data) is not mistaken
+/// for a line.
+fn cyclic_line(x: &[f64], fs: f64, max_hz: f64) -> Option<(f64, f64)> {
+    let n = x.len().next_power_of_two();
+    let mean = x.iter().sum::<f64>() / x.len() as f64;
+    let buffer: Vec<IQSample> = x.iter().map(|&v| Complex64::new(v - mean, 0.0)).collect();
+    let mut fft = FftProcessor::new(n);
+    let spectrum = fft.fft(&buffer);
+    let power: Vec<f64> = spectrum[..n / 2].iter().map(|c| c.norm_sqr()).collect();
+
+    let mut cumulative = Vec::with_capacity(power.len() + 1);
+    cumulative.push(0.0);
+    for p in &power {
+        cumulative.push(cumulative.last().unwrap() + p);
+    }
+    let range_sum = |a: usize, b: usize| cumulative[b] - cumulative[a];
+
+    const GUARD: usize = 3;
+    const SPAN: usize = 32;
+    let bin_hz = fs / n as f64;
+    let lo = 8;
+    let hi = ((max_hz * 1.2 / bin_hz) as usize).clamp(lo + 16, power.len() - SPAN - 1);
+    let prominence = |k: usize| -> f64 {
+        let left = range_sum(k.saturating_sub(SPAN).max(1), k - GUARD + 1);
+        let right = range_sum(k + GUARD, k + SPAN + 1);
+        let count = (k - GUARD + 1 - k.saturating_sub(SPAN).max(1)) + (SPAN - GUARD + 1);
+        let continuum = ((left + right) / count as f64).max(1e-30);
+        power[k - 1..=k + 1].iter().cloned().fold(0.0, f64::max) / continuum
+    };
+
+    let (peak, peak_prom) = (lo..hi)
+        .map(|k| (k, prominence(k)))
+        .fold((0, 0.0), |best, (k, p)| if p > best.1 { (k, p) } else { best });
+    if peak_prom < 30.0 {
+        return None;
+    }
+
+    // The strongest line may be a harmonic; take the lowest sub-multiple that
+    // is itself a clear line
+    let mut fundamental = peak;
+    for m in (2..=32).rev() {
+        let k = (peak as f64 / m as f64).round() as usize;
+        if k >= lo && prominence(k) > 30.0_f64.max(0.05 * peak_prom) {
+            fundamental = k;
+            break;
+        }
+    }
+    let k = (fundamental - 1..=fundamental + 1)
+        .max_by(|&a, &b| power[a].partial_cmp(&power[b]).unwrap())
+        .unwrap();
+    Some(((k as f64 + parabolic_offset(&power, k)) * bin_hz, peak_prom))
+}
+
+/// Fractional offset of a spectral peak from parabolic interpolation of the
+/// neighbouring magnitudes
+fn parabolic_offset(power: &[f64], k: usize) -> f64 {
+    let n = power.len();
+    let a = power[(k + n - 1) % n].sqrt();
+    let b = power[k].sqrt();
+    let c = power[(k + 1) % n].sqrt();
+    let denom = a - 2.0 * b + c;
+    if denom.abs() > 1e-12 {
+        (0.5 * (a - c) / denom).clamp(-0.5, 0.5)
+    } else {
+        0.0
+    }
+}
+
+/// Score each family from the features and normalise into confidences
+fn rank(f: &SignalFeatures, snr_db: f64) -> Vec<Hypothesis> {
+    use ModulationFamily::*;
+
+    let constant_envelope = ramp(f.envelope_cv, 0.25, 0.12);
+    let varying_envelope = ramp(f.envelope_cv, 0.08, 0.2);
+    let on_off = ramp(f.off_fraction, 0.1, 0.25);
+    let carrier = if f.power_line_order == 1 { f.power_line_strength } else { 0.0 };
+    let psk_line = if f.power_line_order >= 2 {
+        ramp(f.power_line_strength, 0.2, 0.5)
+    } else {
+        0.0
+    };
+    let single_freq = if f.frequency_peaks == 1 {
+        ramp(f.frequency_concentration, 0.3, 0.6)
+    } else {
+        0.0
+    };
+    let gaussian = ramp(f.envelope_cv, 0.3, 0.45) * ramp(f.envelope_cv, 0.8, 0.6) * ramp(f.c42.abs(), 0.35, 0.15);
+    let css = ramp(f.chirp_score, 0.3, 0.6);
+    let fhss = if f.hop_channels >= 8 {
+        ramp(f.hop_channels as f64, 8.0, 16.0) * ramp(f.hop_narrow_fraction, 0.3, 0.6) * (1.0 - css)
+    } else {
+        0.0
+    };
+    let qam = if f.lattice_levels >= 4 {
+        ramp(f.envelope_cv, 0.12, 0.2)
+            * ramp(f.c42, -0.3, -0.55)
+            * ramp(f.c42, -1.0, -0.8)
+            * ramp(f.lattice_fit, 0.0, 0.3)
+            * (1.0 - on_off)
+    } else {
+        0.0
+    };
+    // Amplitude keying rides on a carrier; a PSK power line rules it out
+    let keyed = (1.0 - psk_line) * ramp(carrier, 0.1, 0.3).max(0.5);
+
+    let mut scores: Vec<(ModulationFamily, f64)> = Vec::new();
+    scores.push((Noise, ramp(snr_db, 6.0, 0.0)));
+
+    if f.cp_fft_size > 0 {
+        scores.push((
+            Ofdm {
+                fft_size: f.cp_fft_size,
+                cp_len: f.cp_len,
+            },
+            ramp(f.cp_prominence, 4.0, 8.0) * gaussian,
+        ));
+    }
+
+    scores.push((Css, css));
+
+    if f.hop_channels >= 8 {
+        scores.push((Fhss, fhss));
+    }
+
+    scores.push((Tone, ramp(carrier, 0.8, 0.95) * constant_envelope * (1.0 - css)));
+
+    if f.envelope_levels >= 3 {
+        let levels = f.envelope_levels.next_power_of_two().min(8);
+        let ask = on_off.max(varying_envelope) * keyed * ramp(carrier, 0.1, 0.4) * single_freq;
+        scores.push((Ask(levels), ask));
+        scores.push((Ook, 0.3 * on_off * keyed));
+    } else {
+        scores.push((Ook, on_off * keyed * (1.0 - css)));
+        let am = varying_envelope * (1.0 - on_off) * ramp(carrier, 0.1, 0.4) * single_freq.max(0.5);
+        if f.envelope_levels == 2 {
+            scores.push((Ask(2), am));
+        } else {
+            scores.push((Am, am));
+        }
+    }
+
+    if f.frequency_peaks >= 2 {
+        let order = f.frequency_peaks.next_power_of_two().min(16);
+        scores.push((
+            Fsk(order),
+            constant_envelope
+                * ramp(f.frequency_concentration, 0.4, 0.7)
+                * (1.0 - on_off)
+                * (1.0 - fhss)
+                * (1.0 - psk_line),
+        ));
+    }
+
+    if f.power_line_order >= 2 {
+        scores.push((Psk(f.power_line_order), psk_line * (1.0 - qam)));
+    }
+
+    if f.lattice_levels >= 4 {
+        scores.push((Qam(f.lattice_levels * f.lattice_levels), qam));
+    }
+
+    scores.push((
+        Fm,
+        constant_envelope * (1.0 - single_freq) * ramp(f.frequency_concentration, 0.6, 0.3) * (1.0 - css),
+    ));
+    scores.push((Unknown, 0.05));
+
+    let total: f64 = scores.iter().map(|(_, s)| s).sum();
+    let mut hypotheses: Vec<Hypothesis> = scores
+        .into_iter()
+        .filter(|(_, s)| *s > 0.0)
+        .map(|(family, s)| Hypothesis {
+            family,
+            confidence: s / total,
+        })
+        .collect();
+    hypotheses.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+    hypotheses
+}
+
+/// Linear ramp from 0 at `from` to 1 at `to` (either direction), clamped
+fn ramp(x: f64, from: f64, to: f64) -> f64 {
+    if (to - from).abs() < f64::EPSILON {
+        return if x >= to { 1.0 } else { 0.0 };
+    }
+    ((x - from) / (to - from)).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic uniform values in [0, 1)
+    fn lcg(seed: &mut u64) -> f64 {
+        *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (*seed >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn gaussian(seed: &mut u64, sigma: f64) -> IQSample {
+        let r = (-2.0 * lcg(seed).max(1e-12).ln()).sqrt() * sigma;
+        IQSample::from_polar(r, 2.0 * PI * lcg(seed))
+    }
+
+    #[test]
+    fn test_classify_noise() {
+        let mut seed = 1;
+        let samples: Vec<IQSample> = (0..32768).map(|_| gaussian(&mut seed, 1.0)).collect();
+
+        let result = ModulationClassifier::new(48000.0).classify(&samples);
+        assert_eq!(result.best().family, ModulationFamily::Noise);
+    }
+
+    #[test]
+    fn test_classify_qpsk_order_and_rate() {
+        let fs = 48000.0;
+        let sps = 16;
+        let mut seed = 2;
+        let samples: Vec<IQSample> = (0..4096)
+            .flat_map(|_| {
+                let k = (lcg(&mut seed) * 4.0) as usize;
+                let symbol = IQSample::from_polar(1.0, PI / 4.0 + k as f64 * PI / 2.0);
+                std::iter::repeat_n(symbol, sps)
+            })
+            .collect();
+        let samples: Vec<IQSample> = samples.iter().map(|&s| s + gaussian(&mut seed, 0.05)).collect();
+
+        let result = ModulationClassifier::new(fs).classify(&samples);
+        assert_eq!(result.best().family, ModulationFamily::Psk(4), "{}", result.to_text());
+        let rate = result.symbol_rate.expect("symbol rate");
+        assert!((rate - fs / sps as f64).abs() < 30.0, "rate {}", rate);
+    }
+
+    #[test]
+    fn test_classify_ofdm_cyclic_prefix() {
+        let (n, cp) = (64, 16);
+        let mut fft = FftProcessor::new(n);
+        let mut seed = 3;
+        let mut samples = Vec::new();
+        for _ in 0..400 {
+            let mut symbol: Vec<IQSample> = (0..n)
+                .map(|k| {
+                    if k == 0 || (27..=37).contains(&k) {
+                        IQSample::new(0.0, 0.0)
+                    } else {
+                        IQSample::from_polar(1.0, PI / 4.0 + (lcg(&mut seed) * 4.0).floor() * PI / 2.0)
+                    }
+                })
+                .collect();
+            fft.ifft_inplace(&mut symbol);
+            samples.extend_from_slice(&symbol[n - cp..]);
+            samples.extend_from_slice(&symbol);
+        }
+        let samples: Vec<IQSample> = samples.iter().map(|&s| s + gaussian(&mut seed, 0.02)).collect();
+
+        let result = ModulationClassifier::new(1e6).classify(&samples);
+        assert_eq!(
+            result.best().family,
+            ModulationFamily::Ofdm { fft_size: n, cp_len: cp },
+            "{}",
+            result.to_text()
+        );
+    }
+}
@@ -0,0 +1,1077 @@
+//! Spectrum Mask Compliance
+//!
+//! Checks a capture against regulatory emission limits: a spectrum mask
+//! evaluated per resolution bandwidth (RBW) with average and max-hold
+//! detectors, 99% occupied bandwidth, 6 dB bandwidth, adjacent channel power
+//! ratio (ACPR/ACLR), transmit power, power spectral density and duty cycle.
+//!
+//! Mask segments are offsets from the carrier, so the capture should be tuned
+//! to the channel centre. Limits are either absolute (dBm per RBW, which needs
+//! the receiver's reference level to map dBFS to dBm) or relative (dBr against
+//! the highest RBW reading). The built-in masks are simplified readings of the
+//! standards intended for pre-compliance work:
+//!
+//! | Name         | Standard                | Limits                                     |
+//! |--------------|-------------------------|--------------------------------------------|
+//! | `etsi-868`   | ETSI EN 300 220 (g1)    | 14 dBm, 1% duty cycle, -36 dBm spurious    |
+//! | `fcc-15.247` | FCC Part 15.247 (DTS)   | 30 dBm, 8 dBm/3 kHz, -20 dBc, 6 dB BW 500 kHz |
+//! | `802.11`     | IEEE 802.11 OFDM 20 MHz | 0/-20/-28/-40 dBr transmit mask            |
+//!
+//! User-defined masks load from YAML:
+//!
+//! ```yaml
+//! name: my-mask
+//! reference: relative
+//! rbw_hz: 1000.0
+//! channel_bandwidth_hz: 25000.0
+//! segments:
+//!   - { start_hz: 0.0, stop_hz: 12500.0, start_db: 0.0 }
+//!   - { start_hz: 12500.0, stop_hz: 25000.0, start_db: -30.0, stop_db: -50.0 }
+//!   - { start_hz: 25000.0, stop_hz: .inf, start_db: -60.0 }
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! use r4w_core::analysis::{ComplianceChecker, SpectrumMask};
+//! use r4w_core::types::IQSample;
+//!
+//! let mask = SpectrumMask::builtin("802.11").unwrap().scaled(1.0 / 1000.0);
+//! let samples: Vec<IQSample> = (0..65536)
+//!     .map(|n| IQSample::from_polar(0.5, 2.0 * std::f64::consts::PI * 1000.0 * n as f64 / 80_000.0))
+//!     .collect();
+//!
+//! let report = ComplianceChecker::new(mask).check(&samples, 80_000.0).unwrap();
+//! assert!(report.passed());
+//! ```
+
+use crate::analysis::spectrum::WindowFunction;
+use crate::config::ConfigError;
+use crate::fft_utils::FftProcessor;
+use crate::types::IQSample;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Largest FFT used for the fine power spectrum
+const MAX_FFT_SIZE: usize = 65536;
+
+/// How mask limits are referenced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitReference {
+    /// Limits in dBm per RBW
+    Absolute,
+    /// Limits in dB relative to the highest RBW reading (dBr)
+    Relative,
+}
+
+impl LimitReference {
+    /// Unit of mask limits
+    pub fn unit(&self) -> &'static str {
+        match self {
+            LimitReference::Absolute => "dBm",
+            LimitReference::Relative => "dBr",
+        }
+    }
+}
+
+/// A mask segment over a range of carrier offsets
+///
+/// The limit ramps linearly (in dB) from `start_db` to `stop_db`, or is flat
+/// when `stop_db` is omitted.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MaskSegment {
+    /// Start offset from the carrier in Hz
+    pub start_hz: f64,
+    /// Stop offset from the carrier in Hz (may be infinite)
+    pub stop_hz: f64,
+    /// Limit at the start offset
+    pub start_db: f64,
+    /// Limit at the stop offset (flat if omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_db: Option<f64>,
+}
+
+impl MaskSegment {
+    /// Create a flat segment
+    pub fn flat(start_hz: f64, stop_hz: f64, limit_db: f64) -> Self {
+        Self {
+            start_hz,
+            stop_hz,
+            start_db: limit_db,
+            stop_db: None,
+        }
+    }
+
+    /// Create a segment ramping linearly between two limits
+    pub fn ramp(start_hz: f64, stop_hz: f64, start_db: f64, stop_db: f64) -> Self {
+        Self {
+            start_hz,
+            stop_hz,
+            start_db,
+            stop_db: Some(stop_db),
+        }
+    }
+
+    /// Limit at an offset, if the segment covers it
+    pub fn limit_at(&self, offset_hz: f64) -> Option<f64> {
+        if offset_hz < self.start_hz || offset_hz > self.stop_hz {
+            return None;
+        }
+        match self.stop_db {
+            Some(stop_db) if self.stop_hz.is_finite() && self.stop_hz > self.start_hz => {
+                let t = (offset_hz - self.start_hz) / (self.stop_hz - self.start_hz);
+                Some(self.start_db + t * (stop_db - self.start_db))
+            }
+            _ => Some(self.start_db),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Spectrum mask and associated regulatory limits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpectrumMask {
+    /// Mask name
+    pub name: String,
+    /// Free-form description
+    #[serde(default)]
+    pub description: String,
+    /// How segment limits are referenced
+    pub reference: LimitReference,
+    /// Resolution bandwidth the mask is specified in
+    pub rbw_hz: f64,
+    /// Segments apply to |offset| when true, signed offsets otherwise
+    #[serde(default = "default_true")]
+    pub symmetric: bool,
+    /// Mask segments; overlapping segments use the strictest limit
+    pub segments: Vec<MaskSegment>,
+    /// Declared channel bandwidth (ACPR integration bandwidth)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_bandwidth_hz: Option<f64>,
+    /// Adjacent channel spacing (defaults to the channel bandwidth)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_spacing_hz: Option<f64>,
+    /// Maximum 99% occupied bandwidth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_obw_hz: Option<f64>,
+    /// Minimum 6 dB bandwidth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_6db_bandwidth_hz: Option<f64>,
+    /// Maximum transmit power while on air
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_power_dbm: Option<f64>,
+    /// Maximum power spectral density in `psd_rbw_hz`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_psd_dbm: Option<f64>,
+    /// Bandwidth the PSD limit is specified in (default 3 kHz)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub psd_rbw_hz: Option<f64>,
+    /// Maximum ACPR in dBc (worse of the two adjacent channels)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_acpr_db: Option<f64>,
+    /// Maximum duty cycle (fraction)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duty_cycle: Option<f64>,
+}
+
+impl SpectrumMask {
+    /// Create an empty mask with the given reference and RBW
+    pub fn new(name: &str, reference: LimitReference, rbw_hz: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            description: String::new(),
+            reference,
+            rbw_hz,
+            symmetric: true,
+            segments: Vec::new(),
+            channel_bandwidth_hz: None,
+            channel_spacing_hz: None,
+            max_obw_hz: None,
+            min_6db_bandwidth_hz: None,
+            max_power_dbm: None,
+            max_psd_dbm: None,
+            psd_rbw_hz: None,
+            max_acpr_db: None,
+            max_duty_cycle: None,
+        }
+    }
+
+    /// Add a segment
+    pub fn with_segment(mut self, segment: MaskSegment) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Names of the built-in masks
+    pub fn builtin_names() -> &'static [&'static str] {
+        &["etsi-868", "fcc-15.247", "802.11"]
+    }
+
+    /// Look up a built-in mask by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "etsi-868" | "en300220" | "etsi-en-300-220" => {
+                // 868.0-868.6 MHz sub-band: 25 mW ERP, 1% duty cycle; the
+                // out-of-band domain spans 400 kHz beyond the operating channel.
+                let mut mask = Self::new("etsi-868", LimitReference::Absolute, 1e3)
+                    .with_segment(MaskSegment::flat(0.0, 300e3, 14.0))
+                    .with_segment(MaskSegment::ramp(300e3, 700e3, 0.0, -36.0))
+                    .with_segment(MaskSegment::flat(700e3, f64::INFINITY, -36.0));
+                mask.description = "ETSI EN 300 220, 868.0-868.6 MHz (g1)".to_string();
+                mask.channel_bandwidth_hz = Some(600e3);
+                mask.max_obw_hz = Some(600e3);
+                mask.max_power_dbm = Some(14.0);
+                mask.max_duty_cycle = Some(0.01);
+                Some(mask)
+            }
+            "fcc-15.247" | "fcc15.247" | "fcc-dts" => {
+                // Digital transmission systems in 902-928 MHz: emissions
+                // outside the channel at least 20 dB below the highest 100 kHz.
+                let mut mask = Self::new("fcc-15.247", LimitReference::Relative, 100e3)
+                    .with_segment(MaskSegment::flat(0.0, 250e3, 0.0))
+                    .with_segment(MaskSegment::flat(250e3, f64::INFINITY, -20.0));
+                mask.description = "FCC 47 CFR 15.247 digital transmission system".to_string();
+                mask.channel_bandwidth_hz = Some(500e3);
+                mask.min_6db_bandwidth_hz = Some(500e3);
+                mask.max_power_dbm = Some(30.0);
+                mask.max_psd_dbm = Some(8.0);
+                mask.psd_rbw_hz = Some(3e3);
+                Some(mask)
+            }
+            "802.11" | "wifi" | "ieee-802.11" => {
+                // 20 MHz OFDM transmit spectrum mask; 10 and 5 MHz channels
+                // use the same mask scaled by 1/2 and 1/4.
+                let mut mask = Self::new("802.11", LimitReference::Relative, 100e3)
+                    .with_segment(MaskSegment::flat(0.0, 9e6, 0.0))
+                    .with_segment(MaskSegment::ramp(9e6, 11e6, 0.0, -20.0))
+                    .with_segment(MaskSegment::ramp(11e6, 20e6, -20.0, -28.0))
+                    .with_segment(MaskSegment::ramp(20e6, 30e6, -28.0, -40.0))
+                    .with_segment(MaskSegment::flat(30e6, f64::INFINITY, -40.0));
+                mask.description = "IEEE 802.11 OFDM 20 MHz transmit spectrum mask".to_string();
+                mask.channel_bandwidth_hz = Some(20e6);
+                Some(mask)
+            }
+            _ => None,
+        }
+    }
+
+    /// Parse a mask from YAML
+    pub fn from_yaml(yaml: &str) -> Result<Self, ConfigError> {
+        let mask: Self = serde_yaml::from_str(yaml).map_err(|e| ConfigError::ParseError(e.to_string()))?;
+        mask.validate()?;
+        Ok(mask)
+    }
+
+    /// Load a mask from a YAML file
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::ReadError(format!("{}: {}", path.display(), e)))?;
+        Self::from_yaml(&content)
+    }
+
+    /// Resolve a built-in mask name or a path to a YAML mask
+    pub fn resolve(spec: &str) -> Result<Self, ConfigError> {
+        if let Some(mask) = Self::builtin(spec) {
+            return Ok(mask);
+        }
+        let path = Path::new(spec);
+        if path.exists() {
+            return Self::load(path);
+        }
+        Err(ConfigError::NotFound(format!(
+            "{} (built-in masks: {})",
+            spec,
+            Self::builtin_names().join(", ")
+        )))
+    }
+
+    /// Serialize the mask to YAML
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).unwrap_or_default()
+    }
+
+    /// Check the mask definition for consistency
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.rbw_hz.is_nan() || self.rbw_hz <= 0.0 {
+            return Err(ConfigError::ValidationError(format!("{}: rbw_hz must be positive", self.name)));
+        }
+        if self.segments.is_empty() {
+            return Err(ConfigError::ValidationError(format!("{}: mask has no segments", self.name)));
+        }
+        for segment in &self.segments {
+            if segment.stop_hz <= segment.start_hz || (self.symmetric && segment.start_hz < 0.0) {
+                return Err(ConfigError::ValidationError(format!(
+                    "{}: invalid segment {}..{} Hz",
+                    self.name, segment.start_hz, segment.stop_hz
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Scale all frequencies by a factor (e.g. 0.5 for a half-rate channel)
+    pub fn scaled(&self, factor: f64) -> Self {
+        let scale = |v: Option<f64>| v.map(|f| f * factor);
+        let mut mask = self.clone();
+        for segment in &mut mask.segments {
+            segment.start_hz *= factor;
+            segment.stop_hz *= factor;
+        }
+        mask.rbw_hz *= factor;
+        mask.channel_bandwidth_hz = scale(self.channel_bandwidth_hz);
+        mask.channel_spacing_hz = scale(self.channel_spacing_hz);
+        mask.max_obw_hz = scale(self.max_obw_hz);
+        mask.min_6db_bandwidth_hz = scale(self.min_6db_bandwidth_hz);
+        mask.psd_rbw_hz = scale(self.psd_rbw_hz);
+        mask
+    }
+
+    /// Strictest limit at a carrier offset, if any segment covers it
+    pub fn limit_at(&self, offset_hz: f64) -> Option<f64> {
+        let offset = if self.symmetric { offset_hz.abs() } else { offset_hz };
+        self.segments
+            .iter()
+            .filter_map(|s| s.limit_at(offset))
+            .fold(None, |acc: Option<f64>, l| Some(acc.map_or(l, |a| a.min(l))))
+    }
+}
+
+/// Outcome of a single compliance test
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplianceCheck {
+    /// Test name
+    pub name: String,
+    /// Measured value
+    pub measured: f64,
+    /// Regulatory limit
+    pub limit: f64,
+    /// Distance from the limit in `unit` (negative when failing)
+    pub margin: f64,
+    /// Unit of measured, limit and margin
+    pub unit: &'static str,
+    /// Whether the test passed
+    pub pass: bool,
+}
+
+impl ComplianceCheck {
+    /// A test that passes when the measurement does not exceed the limit
+    pub fn at_most(name: &str, measured: f64, limit: f64, unit: &'static str) -> Self {
+        Self::new(name, measured, limit, limit - measured, unit)
+    }
+
+    /// A test that passes when the measurement reaches the limit
+    pub fn at_least(name: &str, measured: f64, limit: f64, unit: &'static str) -> Self {
+        Self::new(name, measured, limit, measured - limit, unit)
+    }
+
+    fn new(name: &str, measured: f64, limit: f64, margin: f64, unit: &'static str) -> Self {
+        Self {
+            name: name.to_string(),
+            measured,
+            limit,
+            margin,
+            unit,
+            pass: margin >= 0.0,
+        }
+    }
+}
+
+/// Result of a compliance check, with plot-ready traces
+#[derive(Debug, Clone)]
+pub struct ComplianceReport {
+    /// Mask name
+    pub mask: String,
+    /// How mask limits are referenced
+    pub reference: LimitReference,
+    /// Sample rate in Hz
+    pub sample_rate: f64,
+    /// Resolution bandwidth in Hz
+    pub rbw_hz: f64,
+    /// FFT size of the underlying spectrum
+    pub fft_size: usize,
+    /// Number of frames averaged / max-held
+    pub num_frames: usize,
+    /// Frequency offsets in Hz (DC at center)
+    pub frequencies: Vec<f64>,
+    /// Average detector power per RBW in dBm
+    pub average_dbm: Vec<f64>,
+    /// Max-hold detector power per RBW in dBm
+    pub peak_dbm: Vec<f64>,
+    /// Mask limit in dBm per RBW (None where the mask does not apply)
+    pub limit_dbm: Vec<Option<f64>>,
+    /// Reference level for relative masks (highest RBW reading) in dBm
+    pub reference_dbm: Option<f64>,
+    /// Smallest distance to the mask in dB (negative when violated)
+    pub mask_margin_db: Option<f64>,
+    /// Offset where the mask margin is smallest
+    pub worst_frequency_hz: Option<f64>,
+    /// Frequency ranges where the max-hold trace exceeds the mask
+    pub violations: Vec<(f64, f64)>,
+    /// 99% occupied bandwidth in Hz
+    pub occupied_bandwidth_hz: f64,
+    /// Lower edge of the occupied bandwidth
+    pub obw_low_hz: f64,
+    /// Upper edge of the occupied bandwidth
+    pub obw_high_hz: f64,
+    /// 6 dB bandwidth of the average trace in Hz
+    pub bandwidth_6db_hz: f64,
+    /// Mean power while transmitting in dBm
+    pub channel_power_dbm: f64,
+    /// Highest max-hold reading per RBW in dBm
+    pub peak_rbw_dbm: f64,
+    /// Highest average reading per RBW in dBm
+    pub average_rbw_dbm: f64,
+    /// Highest PSD in the mask's PSD bandwidth in dBm
+    pub psd_dbm: Option<f64>,
+    /// Lower adjacent channel power ratio in dBc
+    pub acpr_lower_db: Option<f64>,
+    /// Upper adjacent channel power ratio in dBc
+    pub acpr_upper_db: Option<f64>,
+    /// Fraction of the capture spent transmitting
+    pub duty_cycle: f64,
+    /// Time spent transmitting in seconds
+    pub on_time_s: f64,
+    /// Capture duration in seconds
+    pub observation_s: f64,
+    /// Number of transmissions seen
+    pub bursts: usize,
+    /// Individual test outcomes
+    pub checks: Vec<ComplianceCheck>,
+}
+
+impl ComplianceReport {
+    /// Whether every check passed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.pass)
+    }
+
+    /// Format as a human readable summary
+    pub fn to_text(&self) -> String {
+        let optional = |v: Option<f64>, unit: &str| match v {
+            Some(v) => format!("{:.2} {}", v, unit),
+            None => "-".to_string(),
+        };
+
+        let mut output = String::new();
+        output.push_str(&format!("Spectrum Mask Compliance: {}\n", self.mask));
+        output.push_str(&"═".repeat(50));
+        output.push('\n');
+        output.push_str(&format!("RBW:               {:.1} Hz ({} frames)\n", self.rbw_hz, self.num_frames));
+        output.push_str(&format!("Channel Power:     {:.2} dBm\n", self.channel_power_dbm));
+        output.push_str(&format!("Peak per RBW:      {:.2} dBm\n", self.peak_rbw_dbm));
+        output.push_str(&format!("Average per RBW:   {:.2} dBm\n", self.average_rbw_dbm));
+        output.push_str(&format!("PSD:               {}\n", optional(self.psd_dbm, "dBm")));
+        output.push_str(&format!(
+            "Occupied BW (99%): {:.1} Hz ({:.1} .. {:.1})\n",
+            self.occupied_bandwidth_hz, self.obw_low_hz, self.obw_high_hz
+        ));
+        output.push_str(&format!("6 dB Bandwidth:    {:.1} Hz\n", self.bandwidth_6db_hz));
+        output.push_str(&format!("ACPR (lower):      {}\n", optional(self.acpr_lower_db, "dBc")));
+        output.push_str(&format!("ACPR (upper):      {}\n", optional(self.acpr_upper_db, "dBc")));
+        output.push_str(&format!(
+            "Duty Cycle:        {:.3} % ({} bursts, {:.3} s of {:.3} s)\n",
+            self.duty_cycle * 100.0,
+            self.bursts,
+            self.on_time_s,
+            self.observation_s
+        ));
+        if let (Some(margin), Some(freq)) = (self.mask_margin_db, self.worst_frequency_hz) {
+            output.push_str(&format!("Mask Margin:       {:.2} dB at {:.1} Hz\n", margin, freq));
+        }
+        for (low, high) in &self.violations {
+            output.push_str(&format!("  Violation:       {:.1} .. {:.1} Hz\n", low, high));
+        }
+        output.push_str(&"─".repeat(50));
+        output.push('\n');
+        for check in &self.checks {
+            output.push_str(&format!(
+                "{:<24} {:>4}  {:.2} / {:.2} {} (margin {:.2})\n",
+                check.name,
+                if check.pass { "PASS" } else { "FAIL" },
+                check.measured,
+                check.limit,
+                check.unit,
+                check.margin
+            ));
+        }
+        output.push_str(&format!("Result:            {}\n", if self.passed() { "PASS" } else { "FAIL" }));
+        output
+    }
+
+    /// Format as JSON, including the traces and limit line
+    pub fn to_json(&self) -> String {
+        let optional = |v: Option<f64>| v.map(|v| format!("{:.6}", v)).unwrap_or_else(|| "null".to_string());
+        let list = |values: &[f64]| values.iter().map(|v| format!("{:.4}", v)).collect::<Vec<_>>().join(", ");
+        let limits = self.limit_dbm.iter().map(|&v| optional(v)).collect::<Vec<_>>().join(", ");
+        let violations = self
+            .violations
+            .iter()
+            .map(|(low, high)| format!("[{:.3}, {:.3}]", low, high))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let checks = self
+            .checks
+            .iter()
+            .map(|c| {
+                format!(
+                    r#"{{"name": "{}", "measured": {:.6}, "limit": {:.6}, "margin": {:.6}, "unit": "{}", "pass": {}}}"#,
+                    c.name, c.measured, c.limit, c.margin, c.unit, c.pass
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",\n    ");
+
+        format!(
+            r#"{{
+  "mask": "{}",
+  "reference": "{}",
+  "pass": {},
+  "sample_rate": {},
+  "rbw_hz": {},
+  "fft_size": {},
+  "num_frames": {},
+  "reference_dbm": {},
+  "mask_margin_db": {},
+  "worst_frequency_hz": {},
+  "violations": [{}],
+  "occupied_bandwidth_hz": {:.3},
+  "obw_low_hz": {:.3},
+  "obw_high_hz": {:.3},
+  "bandwidth_6db_hz": {:.3},
+  "channel_power_dbm": {:.6},
+  "peak_rbw_dbm": {:.6},
+  "average_rbw_dbm": {:.6},
+  "psd_dbm": {},
+  "acpr_lower_db": {},
+  "acpr_upper_db": {},
+  "duty_cycle": {:.6},
+  "on_time_s": {:.6},
+  "observation_s": {:.6},
+  "bursts": {},
+  "checks": [
+    {}
+  ],
+  "frequencies": [{}],
+  "average_dbm": [{}],
+  "peak_dbm": [{}],
+  "limit_dbm": [{}]
+}}"#,
+            self.mask,
+            self.reference.unit(),
+            self.passed(),
+            self.sample_rate,
+            self.rbw_hz,
+            self.fft_size,
+            self.num_frames,
+            optional(self.reference_dbm),
+            optional(self.mask_margin_db),
+            optional(self.worst_frequency_hz),
+            violations,
+            self.occupied_bandwidth_hz,
+            self.obw_low_hz,
+            self.obw_high_hz,
+            self.bandwidth_6db_hz,
+            self.channel_power_dbm,
+            self.peak_rbw_dbm,
+            self.average_rbw_dbm,
+            optional(self.psd_dbm),
+            optional(self.acpr_lower_db),
+            optional(self.acpr_upper_db),
+            self.duty_cycle,
+            self.on_time_s,
+            self.observation_s,
+            self.bursts,
+            checks,
+            list(&self.frequencies),
+            list(&self.average_dbm),
+            list(&self.peak_dbm),
+            limits
+        )
+    }
+
+    /// Format the traces and limit line as CSV
+    pub fn to_csv(&self) -> String {
+        let mut output = String::from("frequency_hz,average_dbm,peak_dbm,limit_dbm\n");
+        for (i, freq) in self.frequencies.iter().enumerate() {
+            output.push_str(&format!(
+                "{},{},{},{}\n",
+                freq,
+                self.average_dbm[i],
+                self.peak_dbm[i],
+                self.limit_dbm[i].map(|l| l.to_string()).unwrap_or_default()
+            ));
+        }
+        output
+    }
+}
+
+/// Checks captures against a spectrum mask
+pub struct ComplianceChecker {
+    mask: SpectrumMask,
+    reference_level_dbm: f64,
+}
+
+impl ComplianceChecker {
+    /// Create a checker for a mask, with 0 dBFS mapped to 0 dBm
+    pub fn new(mask: SpectrumMask) -> Self {
+        Self {
+            mask,
+            reference_level_dbm: 0.0,
+        }
+    }
+
+    /// Set the power in dBm that corresponds to 0 dBFS
+    ///
+    /// Include antenna gain here to check ERP/EIRP rather than conducted limits.
+    pub fn with_reference_level(mut self, dbm: f64) -> Self {
+        self.reference_level_dbm = dbm;
+        self
+    }
+
+    /// The mask being checked
+    pub fn mask(&self) -> &SpectrumMask {
+        &self.mask
+    }
+
+    /// Measure a capture and evaluate every limit the mask defines
+    ///
+    /// Returns `None` when the capture is shorter than the smallest FFT.
+    pub fn check(&self, samples: &[IQSample], sample_rate: f64) -> Option<ComplianceReport> {
+        let mask = &self.mask;
+        let psd_rbw = mask.max_psd_dbm.map(|_| mask.psd_rbw_hz.unwrap_or(3e3));
+        let min_rbw = psd_rbw.map_or(mask.rbw_hz, |p| p.min(mask.rbw_hz));
+
+        // At least two bins per RBW, limited by the capture length
+        let mut n = ((2.0 * sample_rate / min_rbw).ceil() as usize)
+            .next_power_of_two()
+            .clamp(64, MAX_FFT_SIZE);
+        while n > samples.len() && n > 64 {
+            n /= 2;
+        }
+        if samples.len() < n {
+            return None;
+        }
+
+        let bin_hz = sample_rate / n as f64;
+        let window = WindowFunction::Hann.generate(n);
+        let norm = n as f64 * window.iter().map(|w| w * w).sum::<f64>();
+        let mut processor = FftProcessor::new(n);
+        let rbw_bins = half_width(mask.rbw_hz, bin_hz);
+
+        let mut average = vec![0.0; n];
+        let mut peak = vec![0.0f64; n];
+        let mut num_frames = 0;
+        let mut pos = 0;
+        while pos + n <= samples.len() {
+            let mut frame: Vec<IQSample> = samples[pos..pos + n]
+                .iter()
+                .zip(window.iter())
+                .map(|(&s, &w)| s * w)
+                .collect();
+            processor.fft_inplace(&mut frame);
+            let power: Vec<f64> = frame.iter().map(|x| x.norm_sqr() / norm).collect();
+            let power = FftProcessor::fft_shift(&power);
+
+            for (acc, p) in average.iter_mut().zip(power.iter()) {
+                *acc += p;
+            }
+            for (held, p) in peak.iter_mut().zip(integrate(&power, rbw_bins)) {
+                *held = held.max(p);
+            }
+            num_frames += 1;
+            pos += n / 2;
+        }
+        for p in &mut average {
+            *p /= num_frames as f64;
+        }
+
+        let frequencies: Vec<f64> = (0..n).map(|i| (i as f64 - (n / 2) as f64) * bin_hz).collect();
+        let to_dbm = |p: f64| {
+            if p > 1e-20 {
+                10.0 * p.log10() + self.reference_level_dbm
+            } else {
+                -200.0 + self.reference_level_dbm
+            }
+        };
+        let average_dbm: Vec<f64> = integrate(&average, rbw_bins).into_iter().map(to_dbm).collect();
+        let peak_dbm: Vec<f64> = peak.iter().map(|&p| to_dbm(p)).collect();
+        let peak_rbw_dbm = peak_dbm.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let average_rbw_dbm = average_dbm.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+        // Mask limit line and margin against the max-hold trace
+        let reference_dbm = match mask.reference {
+            LimitReference::Absolute => None,
+            LimitReference::Relative => Some(peak_rbw_dbm),
+        };
+        let limit_dbm: Vec<Option<f64>> = frequencies
+            .iter()
+            .map(|&f| mask.limit_at(f).map(|l| l + reference_dbm.unwrap_or(0.0)))
+            .collect();
+        let mut mask_margin_db: Option<f64> = None;
+        let mut worst = 0;
+        let mut violations: Vec<(f64, f64)> = Vec::new();
+        let mut in_violation = false;
+        for (i, limit) in limit_dbm.iter().enumerate() {
+            let Some(limit) = limit else {
+                in_violation = false;
+                continue;
+            };
+            let margin = limit - peak_dbm[i];
+            if mask_margin_db.is_none_or(|m| margin < m) {
+                mask_margin_db = Some(margin);
+                worst = i;
+            }
+            if margin < 0.0 {
+                match violations.last_mut() {
+                    Some(range) if in_violation => range.1 = frequencies[i],
+                    _ => violations.push((frequencies[i], frequencies[i])),
+                }
+                in_violation = true;
+            } else {
+                in_violation = false;
+            }
+        }
+
+        // 99% occupied bandwidth from the fine average spectrum
+        let total: f64 = average.iter().sum();
+        let mut cumulative = 0.0;
+        let mut obw_low = 0;
+        let mut obw_high = n - 1;
+        let mut found_low = false;
+        for (i, p) in average.iter().enumerate() {
+            cumulative += p;
+            if !found_low && cumulative >= 0.005 * total {
+                obw_low = i;
+                found_low = true;
+            }
+            if cumulative >= 0.995 * total {
+                obw_high = i;
+                break;
+            }
+        }
+        let occupied_bandwidth_hz = (obw_high - obw_low + 1) as f64 * bin_hz;
+
+        // 6 dB bandwidth: outermost RBW readings within 6 dB of the maximum
+        let threshold = average_rbw_dbm - 6.0;
+        let above: Vec<usize> = (0..n).filter(|&i| average_dbm[i] >= threshold).collect();
+        let bandwidth_6db_hz = match (above.first(), above.last()) {
+            (Some(&lo), Some(&hi)) => (hi - lo + 1) as f64 * bin_hz,
+            _ => 0.0,
+        };
+
+        let psd_dbm = psd_rbw.map(|rbw| {
+            integrate(&average, half_width(rbw, bin_hz))
+                .into_iter()
+                .map(to_dbm)
+                .fold(f64::NEG_INFINITY, f64::max)
+        });
+
+        // Adjacent channel power relative to the main channel
+        let channel_bw = mask.channel_bandwidth_hz.unwrap_or(occupied_bandwidth_hz);
+        let spacing = mask.channel_spacing_hz.unwrap_or(channel_bw);
+        let band_power = |center: f64| -> f64 {
+            frequencies
+                .iter()
+                .zip(average.iter())
+                .filter(|(&f, _)| (f - center).abs() <= channel_bw / 2.0)
+                .map(|(_, &p)| p)
+                .sum()
+        };
+        let main_power = band_power(0.0);
+        let acpr = |center: f64| -> Option<f64> {
+            if center.abs() + channel_bw / 2.0 > sample_rate / 2.0 || main_power <= 0.0 {
+                return None;
+            }
+            Some(10.0 * (band_power(center) / main_power).max(1e-30).log10())
+        };
+        let acpr_lower_db = acpr(-spacing);
+        let acpr_upper_db = acpr(spacing);
+
+        let activity = duty_cycle(samples, sample_rate, (n / 4).max(8));
+        let channel_power_dbm = to_dbm(activity.on_power);
+
+        let mut checks = Vec::new();
+        if let Some(margin) = mask_margin_db {
+            let limit = limit_dbm[worst].unwrap_or_default() - reference_dbm.unwrap_or(0.0);
+            let measured = peak_dbm[worst] - reference_dbm.unwrap_or(0.0);
+            let mut check = ComplianceCheck::at_most("Spectrum mask", measured, limit, mask.reference.unit());
+            check.margin = margin;
+            checks.push(check);
+        }
+        if let Some(limit) = mask.max_obw_hz {
+            checks.push(ComplianceCheck::at_most("Occupied bandwidth", occupied_bandwidth_hz, limit, "Hz"));
+        }
+        if let Some(limit) = mask.min_6db_bandwidth_hz {
+            checks.push(ComplianceCheck::at_least("6 dB bandwidth", bandwidth_6db_hz, limit, "Hz"));
+        }
+        if let Some(limit) = mask.max_power_dbm {
+            checks.push(ComplianceCheck::at_most("Transmit power", channel_power_dbm, limit, "dBm"));
+        }
+        if let (Some(limit), Some(psd)) = (mask.max_psd_dbm, psd_dbm) {
+            checks.push(ComplianceCheck::at_most("Power spectral density", psd, limit, "dBm"));
+        }
+        if let Some(limit) = mask.max_acpr_db {
+            let worst_acpr = acpr_lower_db.into_iter().chain(acpr_upper_db).fold(None, |acc: Option<f64>, v| {
+                Some(acc.map_or(v, |a| a.max(v)))
+            });
+            if let Some(measured) = worst_acpr {
+                checks.push(ComplianceCheck::at_most("ACPR", measured, limit, "dBc"));
+            }
+        }
+        if let Some(limit) = mask.max_duty_cycle {
+            checks.push(ComplianceCheck::at_most(
+                "Duty cycle",
+                activity.duty_cycle * 100.0,
+                limit * 100.0,
+                "%",
+            ));
+        }
+
+        Some(ComplianceReport {
+            mask: mask.name.clone(),
+            reference: mask.reference,
+            sample_rate,
+            rbw_hz: mask.rbw_hz,
+            fft_size: n,
+            num_frames,
+            frequencies: frequencies.clone(),
+            average_dbm,
+            peak_dbm,
+            limit_dbm,
+            reference_dbm,
+            mask_margin_db,
+            worst_frequency_hz: mask_margin_db.map(|_| frequencies[worst]),
+            violations,
+            occupied_bandwidth_hz,
+            obw_low_hz: frequencies[obw_low] - bin_hz / 2.0,
+            obw_high_hz: frequencies[obw_high] + bin_hz / 2.0,
+            bandwidth_6db_hz,
+            channel_power_dbm,
+            peak_rbw_dbm,
+            average_rbw_dbm,
+            psd_dbm,
+            acpr_lower_db,
+            acpr_upper_db,
+            duty_cycle: activity.duty_cycle,
+            on_time_s: activity.on_time_s,
+            observation_s: samples.len() as f64 / sample_rate,
+            bursts: activity.bursts,
+            checks,
+        })
+    }
+}
+
+/// Number of bins either side of centre that make up one RBW
+fn half_width(rbw_hz: f64, bin_hz: f64) -> usize {
+    ((rbw_hz / bin_hz - 1.0) / 2.0).round().max(0.0) as usize
+}
+
+/// Sum power over a sliding window of `2 * half + 1` bins
+fn integrate(power: &[f64], half: usize) -> Vec<f64> {
+    let mut prefix = Vec::with_capacity(power.len() + 1);
+    prefix.push(0.0);
+    for p in power {
+        prefix.push(prefix.last().unwrap() + p);
+    }
+    (0..power.len())
+        .map(|i| {
+            let lo = i.saturating_sub(half);
+            let hi = (i + half + 1).min(power.len());
+            prefix[hi] - prefix[lo]
+        })
+        .collect()
+}
+
+/// Transmit activity over a capture
+struct Activity {
+    duty_cycle: f64,
+    on_time_s: f64,
+    on_power: f64,
+    bursts: usize,
+}
+
+/// Gate block powers halfway (in dB) between the noise floor and the peak
+///
+/// Captures without at least 10 dB between the two are treated as a
+/// continuous transmission.
+fn duty_cycle(samples: &[IQSample], sample_rate: f64, block: usize) -> Activity {
+    let powers: Vec<f64> = samples
+        .chunks_exact(block)
+        .map(|c| c.iter().map(|s| s.norm_sqr()).sum::<f64>() / block as f64)
+        .collect();
+    if powers.is_empty() {
+        let power = samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / samples.len().max(1) as f64;
+        return Activity {
+            duty_cycle: 1.0,
+            on_time_s: samples.len() as f64 / sample_rate,
+            on_power: power,
+            bursts: 1,
+        };
+    }
+
+    let db: Vec<f64> = powers.iter().map(|p| 10.0 * p.max(1e-30).log10()).collect();
+    let mut sorted = db.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let floor = sorted[sorted.len() / 10];
+    let peak = sorted[sorted.len() - 1];
+    let threshold = if peak - floor < 10.0 {
+        f64::NEG_INFINITY
+    } else {
+        (floor + peak) / 2.0
+    };
+
+    let mut active = 0;
+    let mut bursts = 0;
+    let mut on_power = 0.0;
+    let mut previous = false;
+    for (p, d) in powers.iter().zip(db.iter()) {
+        let on = *d >= threshold;
+        if on {
+            active += 1;
+            on_power += p;
+            if !previous {
+                bursts += 1;
+            }
+        }
+        previous = on;
+    }
+
+    Activity {
+        duty_cycle: active as f64 / powers.len() as f64,
+        on_time_s: (active * block) as f64 / sample_rate,
+        on_power: on_power / active.max(1) as f64,
+        bursts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use rand_distr::StandardNormal;
+    use std::f64::consts::PI;
+
+    /// Flat-topped multitone occupying `bandwidth` around DC
+    fn multitone(bandwidth: f64, fs: f64, amplitude: f64, len: usize) -> Vec<IQSample> {
+        let tones: Vec<f64> = (0..=40).map(|k| -bandwidth / 2.0 + bandwidth * k as f64 / 40.0).collect();
+        let mut rng = StdRng::seed_from_u64(7);
+        let phases: Vec<f64> = tones.iter().map(|_| rng.gen::<f64>() * 2.0 * PI).collect();
+        (0..len)
+            .map(|n| {
+                let t = n as f64 / fs;
+                tones
+                    .iter()
+                    .zip(phases.iter())
+                    .map(|(f, p)| IQSample::from_polar(amplitude, 2.0 * PI * f * t + p))
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_relative_mask_flags_spur() {
+        let fs = 100e3;
+        let mask = SpectrumMask::new("test", LimitReference::Relative, 500.0)
+            .with_segment(MaskSegment::flat(0.0, 5e3, 0.0))
+            .with_segment(MaskSegment::ramp(5e3, 10e3, -20.0, -40.0))
+            .with_segment(MaskSegment::flat(10e3, f64::INFINITY, -50.0));
+        let clean = multitone(8e3, fs, 0.05, 32768);
+        let checker = ComplianceChecker::new(mask);
+
+        let report = checker.check(&clean, fs).unwrap();
+        assert!(report.passed(), "{}", report.to_text());
+        assert!(report.violations.is_empty());
+        assert!((report.occupied_bandwidth_hz - 8e3).abs() < 800.0, "{}", report.occupied_bandwidth_hz);
+
+        // A spur 30 dB down at +20 kHz breaks the -50 dBr floor
+        let spurious: Vec<IQSample> = clean
+            .iter()
+            .enumerate()
+            .map(|(n, &s)| s + IQSample::from_polar(0.05 * 41f64.sqrt() * 0.03, 2.0 * PI * 20e3 * n as f64 / fs))
+            .collect();
+        let report = checker.check(&spurious, fs).unwrap();
+        assert!(!report.passed());
+        assert!(report.mask_margin_db.unwrap() < 0.0);
+        assert!((report.worst_frequency_hz.unwrap() - 20e3).abs() < 500.0);
+        assert!(report
+            .violations
+            .iter()
+            .all(|(low, high)| *low > 19e3 && *high < 21e3));
+        assert_eq!(report.checks[0].name, "Spectrum mask");
+        assert!(!report.checks[0].pass);
+    }
+
+    #[test]
+    fn test_etsi_duty_cycle_and_power() {
+        // 10 ms bursts every 100 ms over a noise floor: 10% duty cycle
+        let fs = 2e6;
+        let mut rng = StdRng::seed_from_u64(3);
+        let samples: Vec<IQSample> = (0..400_000)
+            .map(|n| {
+                let noise = IQSample::new(rng.sample(StandardNormal), rng.sample(StandardNormal)) * 1e-3;
+                let on = (n % 200_000) < 20_000;
+                let tone = IQSample::from_polar(0.5, 2.0 * PI * 50e3 * n as f64 / fs);
+                if on { tone + noise } else { noise }
+            })
+            .collect();
+
+        let mask = SpectrumMask::builtin("etsi-868").unwrap();
+        let report = ComplianceChecker::new(mask.clone()).check(&samples, fs).unwrap();
+        assert!((report.duty_cycle - 0.1).abs() < 0.01, "{}", report.duty_cycle);
+        assert_eq!(report.bursts, 2);
+        assert!((report.channel_power_dbm - 20.0 * 0.5f64.log10()).abs() < 0.2);
+
+        let duty = report.checks.iter().find(|c| c.name == "Duty cycle").unwrap();
+        assert!(!duty.pass);
+        assert!((duty.margin + 9.0).abs() < 1.0);
+        let power = report.checks.iter().find(|c| c.name == "Transmit power").unwrap();
+        assert!(power.pass);
+
+        // A 30 dB hotter transmitter exceeds 25 mW
+        let report = ComplianceChecker::new(mask)
+            .with_reference_level(30.0)
+            .check(&samples, fs)
+            .unwrap();
+        let power = report.checks.iter().find(|c| c.name == "Transmit power").unwrap();
+        assert!(!power.pass);
+    }
+
+    #[test]
+    fn test_mask_yaml_and_scaling() {
+        let yaml = r#"
+name: narrowband
+reference: absolute
+rbw_hz: 100.0
+channel_bandwidth_hz: 12500.0
+max_acpr_db: -40.0
+segments:
+  - { start_hz: 0.0, stop_hz: 6250.0, start_db: 0.0 }
+  - { start_hz: 6250.0, stop_hz: 12500.0, start_db: -30.0, stop_db: -60.0 }
+  - { start_hz: 12500.0, stop_hz: .inf, start_db: -60.0 }
+"#;
+        let mask = SpectrumMask::from_yaml(yaml).unwrap();
+        assert!(mask.symmetric);
+        assert_eq!(mask.limit_at(-9375.0), Some(-45.0));
+        assert_eq!(mask.limit_at(1e6), Some(-60.0));
+        assert_eq!(SpectrumMask::from_yaml(&mask.to_yaml()).unwrap(), mask);
+        assert!(SpectrumMask::from_yaml("name: x\nreference: absolute\nrbw_hz: 0\nsegments: []").is_err());
+
+        let wifi = SpectrumMask::builtin("802.11").unwrap();
+        let half = wifi.scaled(0.5);
+        assert_eq!(half.limit_at(10e6), wifi.limit_at(20e6));
+        assert_eq!(half.channel_bandwidth_hz, Some(10e6));
+        assert!(SpectrumMask::resolve("no-such-mask").is_err());
+
+        // ACPR of a channel-filling multitone is far below -40 dBc
+        let fs = 64e3;
+        let report = ComplianceChecker::new(mask).check(&multitone(10e3, fs, 0.01, 16384), fs).unwrap();
+        assert!(report.acpr_lower_db.unwrap() < -40.0);
+        assert!(report.acpr_upper_db.unwrap() < -40.0);
+        assert!(report.checks.iter().any(|c| c.name == "ACPR" && c.pass));
+        assert!(report.to_csv().lines().count() == report.fft_size + 1);
+    }
+}
@@ -0,0 +1,726 @@
+//! Cyclostationary Analysis
+//!
+//! Spectral correlation density (SCD) estimation and a cyclostationary
+//! feature detector for judging how visible a waveform is to an intercept
+//! receiver that looks for periodic statistics rather than raw energy.
+//!
+//! ## Estimators
+//!
+//! - **FAM** (FFT accumulation method): short FFTs channelize the signal,
+//!   products of channel pairs are Fourier transformed over time. Fast, with
+//!   coarse frequency resolution.
+//! - **SSCA** (strip spectral correlation): each channel is multiplied by the
+//!   conjugate input and transformed over the full record, giving the finest
+//!   cyclic-frequency resolution at higher cost.
+//!
+//! Both produce a [`ScfResult`]: |S_x^α(f)| on a grid of cyclic frequency α
+//! (rows, 0..fs) and spectral frequency f (columns, -fs/2..fs/2). Rows are
+//! max-pooled to `alpha_bins` so narrow features survive display.
+//!
+//! ## Detection
+//!
+//! [`CyclostationaryDetector`] reduces the SCD to its cyclic-frequency profile
+//! (max over f) and compares the strongest feature away from α = 0 with the
+//! median. Thresholds for a given Pfa and Pd at a given SNR come from Monte
+//! Carlo runs against white Gaussian noise, next to an ideal radiometer for
+//! comparison (see [`crate::lpi_metrics`] for the analytic energy model).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use r4w_core::analysis::{CyclicSpectrumAnalyzer, ScfMethod};
+//! use r4w_core::types::IQSample;
+//!
+//! // Rectangular BPSK at 10 samples/symbol has a feature at the symbol rate
+//! let fs = 8000.0;
+//! let samples: Vec<IQSample> = (0..8192)
+//!     .map(|n| {
+//!         let bit = ((n / 10) * 2654435761usize >> 7) & 1;
+//!         IQSample::new(if bit == 1 { 1.0 } else { -1.0 }, 0.0)
+//!     })
+//!     .collect();
+//!
+//! let scf = CyclicSpectrumAnalyzer::new(ScfMethod::Fam, 32).compute(&samples, fs);
+//! let strongest = scf.features(1)[0];
+//! assert!((strongest.alpha_hz - 800.0).abs() < 20.0);
+//! ```
+
+use super::waterfall::Colormap;
+use crate::fft_utils::FftProcessor;
+use crate::types::IQSample;
+use rustfft::num_complex::Complex64;
+use std::f64::consts::PI;
+
+/// Spectral correlation estimator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScfMethod {
+    /// FFT accumulation method
+    #[default]
+    Fam,
+    /// Strip spectral correlation algorithm
+    Ssca,
+}
+
+impl ScfMethod {
+    /// Parse method from string
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "fam" => Some(ScfMethod::Fam),
+            "ssca" => Some(ScfMethod::Ssca),
+            _ => None,
+        }
+    }
+
+    /// Short name
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScfMethod::Fam => "FAM",
+            ScfMethod::Ssca => "SSCA",
+        }
+    }
+}
+
+/// A cyclic feature (local maximum of the cyclic-frequency profile)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CyclicFeature {
+    /// Cyclic frequency α (Hz)
+    pub alpha_hz: f64,
+    /// Spectral frequency where the feature peaks (Hz)
+    pub frequency_hz: f64,
+    /// Feature strength relative to the profile median (dB)
+    pub strength_db: f64,
+}
+
+/// Spectral correlation estimate
+#[derive(Debug, Clone)]
+pub struct ScfResult {
+    /// |S_x^α(f)|: one row per cyclic frequency, one column per frequency
+    pub scf: Vec<Vec<f64>>,
+    /// Cyclic frequency of each row (Hz)
+    pub alphas: Vec<f64>,
+    /// Spectral frequency of each column (Hz)
+    pub frequencies: Vec<f64>,
+    /// Sample rate (Hz)
+    pub sample_rate: f64,
+    /// Estimator used
+    pub method: ScfMethod,
+}
+
+impl ScfResult {
+    /// Cyclic-frequency profile: max over f of |S_x^α(f)|
+    pub fn cyclic_profile(&self) -> Vec<f64> {
+        self.scf
+            .iter()
+            .map(|row| row.iter().cloned().fold(0.0, f64::max))
+            .collect()
+    }
+
+    /// Spectral correlation in dB (10·log10 of the magnitude)
+    pub fn scf_db(&self) -> Vec<Vec<f64>> {
+        self.scf
+            .iter()
+            .map(|row| row.iter().map(|&v| 10.0 * v.max(1e-30).log10()).collect())
+            .collect()
+    }
+
+    /// Rows closer to α = 0 than this are dominated by the PSD itself
+    fn guard_rows(&self) -> usize {
+        let spacing = self.alphas.get(1).copied().unwrap_or(self.sample_rate) - self.alphas[0];
+        let resolution = self.sample_rate / self.frequencies.len().max(1) as f64;
+        ((2.0 * resolution / spacing.max(f64::EPSILON)).ceil() as usize).max(1)
+    }
+
+    /// Strongest cyclic features away from α = 0, strongest first
+    pub fn features(&self, max_features: usize) -> Vec<CyclicFeature> {
+        let profile = self.cyclic_profile();
+        let guard = self.guard_rows().min(profile.len());
+        let median = median(&profile[guard..]).max(1e-30);
+
+        let mut peaks: Vec<usize> = (guard..profile.len())
+            .filter(|&i| {
+                let left = if i > guard { profile[i - 1] } else { 0.0 };
+                let right = profile.get(i + 1).copied().unwrap_or(0.0);
+                profile[i] > left && profile[i] >= right
+            })
+            .collect();
+        peaks.sort_by(|&a, &b| profile[b].partial_cmp(&profile[a]).unwrap());
+
+        peaks
+            .into_iter()
+            .take(max_features)
+            .map(|i| {
+                let column = self.scf[i]
+                    .iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+                    .map(|(c, _)| c)
+                    .unwrap_or(0);
+                CyclicFeature {
+                    alpha_hz: self.alphas[i],
+                    frequency_hz: self.frequencies[column],
+                    strength_db: 10.0 * (profile[i] / median).log10(),
+                }
+            })
+            .collect()
+    }
+
+    /// Auto-scale range (dB) for display: profile median to maximum
+    pub fn power_range(&self) -> (f64, f64) {
+        let db = self.scf_db();
+        let mut all: Vec<f64> = db.iter().flatten().cloned().collect();
+        all.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let min = all.get(all.len() / 2).copied().unwrap_or(-100.0);
+        let max = all.last().copied().unwrap_or(0.0);
+        (min, max)
+    }
+
+    /// Generate PNG image data (α increasing downwards, f across)
+    #[cfg(feature = "image")]
+    pub fn to_png(&self, colormap: Colormap, min_db: f64, max_db: f64) -> Vec<u8> {
+        use image::{codecs::png::PngEncoder, ImageBuffer, ImageEncoder, Rgb};
+
+        let width = self.frequencies.len();
+        let height = self.alphas.len();
+        let pixels = self.to_rgb_pixels(colormap, min_db, max_db);
+        let img: ImageBuffer<Rgb<u8>, Vec<u8>> =
+            ImageBuffer::from_raw(width as u32, height as u32, pixels).expect("pixel buffer size");
+
+        let mut buffer = Vec::new();
+        let encoder = PngEncoder::new(&mut buffer);
+        encoder
+            .write_image(&img, width as u32, height as u32, image::ColorType::Rgb8.into())
+            .expect("Failed to encode PNG");
+        buffer
+    }
+
+    /// Generate raw RGB pixel data (for non-image feature builds)
+    pub fn to_rgb_pixels(&self, colormap: Colormap, min_db: f64, max_db: f64) -> Vec<u8> {
+        let range = max_db - min_db;
+        self.scf_db()
+            .iter()
+            .flatten()
+            .flat_map(|&db| colormap.map((db - min_db) / range))
+            .collect()
+    }
+
+    /// Format the strongest features as text
+    pub fn to_text(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format!("Spectral Correlation ({})\n", self.method.name()));
+        output.push_str("══════════════════════════════════════════════════\n");
+        output.push_str(&format!("Sample Rate:       {:.0} Hz\n", self.sample_rate));
+        output.push_str(&format!(
+            "Resolution:        Δf = {:.1} Hz, Δα = {:.2} Hz\n",
+            self.sample_rate / self.frequencies.len() as f64,
+            self.alphas.get(1).copied().unwrap_or(0.0) - self.alphas.first().copied().unwrap_or(0.0)
+        ));
+        output.push('\n');
+        output.push_str("Cyclic Features\n");
+        output.push_str("──────────────────────────────────────────────────\n");
+        output.push_str(&format!("{:>4}  {:>14}  {:>14}  {:>10}\n", "#", "α (Hz)", "f (Hz)", "dB"));
+        for (i, feature) in self.features(10).iter().enumerate() {
+            output.push_str(&format!(
+                "{:>4}  {:>14.2}  {:>14.2}  {:>10.1}\n",
+                i + 1,
+                feature.alpha_hz,
+                feature.frequency_hz,
+                feature.strength_db
+            ));
+        }
+        output
+    }
+
+    /// Format features and the cyclic profile as JSON
+    pub fn to_json(&self) -> String {
+        let features: Vec<String> = self
+            .features(10)
+            .iter()
+            .map(|f| {
+                format!(
+                    "{{\"alpha_hz\": {:.3}, \"frequency_hz\": {:.3}, \"strength_db\": {:.2}}}",
+                    f.alpha_hz, f.frequency_hz, f.strength_db
+                )
+            })
+            .collect();
+        let profile: Vec<String> = self
+            .cyclic_profile()
+            .iter()
+            .map(|v| format!("{:.3}", 10.0 * v.max(1e-30).log10()))
+            .collect();
+        let alphas: Vec<String> = self.alphas.iter().map(|a| format!("{:.3}", a)).collect();
+
+        let mut output = String::from("{\n");
+        output.push_str(&format!("  \"method\": \"{}\",\n", self.method.name()));
+        output.push_str(&format!("  \"sample_rate\": {},\n", self.sample_rate));
+        output.push_str(&format!("  \"features\": [{}],\n", features.join(", ")));
+        output.push_str(&format!("  \"alpha_hz\": [{}],\n", alphas.join(", ")));
+        output.push_str(&format!("  \"profile_db\": [{}]\n", profile.join(", ")));
+        output.push_str("}\n");
+        output
+    }
+
+    /// Format the cyclic profile as CSV
+    pub fn to_csv(&self) -> String {
+        let mut output = String::from("alpha_hz,profile_db\n");
+        for (alpha, value) in self.alphas.iter().zip(self.cyclic_profile()) {
+            output.push_str(&format!("{:.3},{:.3}\n", alpha, 10.0 * value.max(1e-30).log10()));
+        }
+        output
+    }
+}
+
+/// Spectral correlation estimator
+#[derive(Debug, Clone)]
+pub struct CyclicSpectrumAnalyzer {
+    method: ScfMethod,
+    channels: usize,
+    alpha_bins: usize,
+}
+
+impl CyclicSpectrumAnalyzer {
+    /// Create an estimator with `channels` frequency channels (power of two)
+    pub fn new(method: ScfMethod, channels: usize) -> Self {
+        Self {
+            method,
+            channels: channels.next_power_of_two().max(8),
+            alpha_bins: 2048,
+        }
+    }
+
+    /// Set the number of cyclic-frequency rows in the result
+    pub fn with_alpha_bins(mut self, bins: usize) -> Self {
+        self.alpha_bins = bins.max(16);
+        self
+    }
+
+    /// Get the estimator
+    pub fn method(&self) -> ScfMethod {
+        self.method
+    }
+
+    /// Get the number of frequency channels
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Estimate the spectral correlation of `samples`
+    pub fn compute(&self, samples: &[IQSample], sample_rate: f64) -> ScfResult {
+        let mut grid = ScfGrid::new(self.channels, self.alpha_bins, sample_rate);
+        if samples.len() >= 2 * self.channels {
+            match self.method {
+                ScfMethod::Fam => self.fam(samples, &mut grid),
+                ScfMethod::Ssca => self.ssca(samples, &mut grid),
+            }
+        }
+        grid.into_result(self.method)
+    }
+
+    fn fam(&self, samples: &[IQSample], grid: &mut ScfGrid) {
+        let np = self.channels;
+        let hop = np / 4;
+        let frames = prev_power_of_two((samples.len() - np) / hop + 1);
+        let window = hamming(np);
+        let energy: f64 = window.iter().map(|w| w * w).sum();
+
+        // Channelize, then reference each channel's phase to the record start
+        let mut fft = FftProcessor::new(np);
+        let channelized: Vec<Vec<Complex64>> = (0..frames)
+            .map(|r| {
+                let mut frame: Vec<Complex64> = samples[r * hop..r * hop + np]
+                    .iter()
+                    .zip(&window)
+                    .map(|(s, w)| s * w)
+                    .collect();
+                fft.fft_inplace(&mut frame);
+                (0..np)
+                    .map(|i| {
+                        let k = shifted_index(i, np);
+                        let bin = frame[(k + np as isize) as usize % np];
+                        bin * Complex64::from_polar(1.0, -2.0 * PI * k as f64 * (r * hop) as f64 / np as f64)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Each channel pair resolves α within ±fs/(2·Np) of its centre
+        let mut fft_p = FftProcessor::new(frames);
+        let keep = (frames * hop / (2 * np)) as isize;
+        let scale = 1.0 / (frames as f64 * energy);
+        let mut product = vec![Complex64::new(0.0, 0.0); frames];
+        for i1 in 0..np {
+            for i2 in 0..np {
+                let (k1, k2) = (shifted_index(i1, np), shifted_index(i2, np));
+                if k1 < k2 {
+                    // |S^-α| = |S^α|; only α ≥ 0 is kept
+                    continue;
+                }
+                for (r, p) in product.iter_mut().enumerate() {
+                    *p = channelized[r][i1] * channelized[r][i2].conj();
+                }
+                fft_p.fft_inplace(&mut product);
+                for q in -keep..=keep {
+                    let value = product[(q + frames as isize) as usize % frames].norm() * scale;
+                    let alpha = (k1 - k2) as f64 / np as f64 + q as f64 / (hop * frames) as f64;
+                    let f = (k1 + k2) as f64 / (2 * np) as f64;
+                    grid.insert(f, alpha, value);
+                }
+            }
+        }
+    }
+
+    fn ssca(&self, samples: &[IQSample], grid: &mut ScfGrid) {
+        let np = self.channels;
+        let n = prev_power_of_two(samples.len() - np);
+        let window = hamming(np);
+        let energy: f64 = window.iter().map(|w| w * w).sum();
+
+        // Sliding channelizer, each strip multiplied by the centre sample
+        let mut fft = FftProcessor::new(np);
+        let mut strips = vec![vec![Complex64::new(0.0, 0.0); n]; np];
+        for t in 0..n {
+            let mut frame: Vec<Complex64> = samples[t..t + np]
+                .iter()
+                .zip(&window)
+                .map(|(s, w)| s * w)
+                .collect();
+            fft.fft_inplace(&mut frame);
+            let centre = samples[t + np / 2].conj();
+            for (i, strip) in strips.iter_mut().enumerate() {
+                let k = shifted_index(i, np);
+                let bin = frame[(k + np as isize) as usize % np];
+                strip[t] = bin * Complex64::from_polar(1.0, -2.0 * PI * k as f64 * t as f64 / np as f64) * centre;
+            }
+        }
+
+        let mut fft_n = FftProcessor::new(n);
+        let scale = 1.0 / (n as f64 * energy.sqrt());
+        for (i, strip) in strips.iter_mut().enumerate() {
+            let k = shifted_index(i, np);
+            fft_n.fft_inplace(strip);
+            for (j, value) in strip.iter().enumerate() {
+                let q = if j < n / 2 { j as isize } else { j as isize - n as isize };
+                let alpha = k as f64 / np as f64 + q as f64 / n as f64;
+                let f = (k as f64 / np as f64 - q as f64 / n as f64) / 2.0;
+                if alpha >= 0.0 {
+                    grid.insert(f, alpha, value.norm() * scale);
+                }
+            }
+        }
+    }
+}
+
+/// Max-pooled (α, f) accumulation grid, in normalised frequencies
+struct ScfGrid {
+    cells: Vec<Vec<f64>>,
+    columns: usize,
+    sample_rate: f64,
+}
+
+impl ScfGrid {
+    fn new(columns: usize, rows: usize, sample_rate: f64) -> Self {
+        Self {
+            cells: vec![vec![0.0; columns]; rows],
+            columns,
+            sample_rate,
+        }
+    }
+
+    /// `f` in [-0.5, 0.5), `alpha` in [0, 1]
+    fn insert(&mut self, f: f64, alpha: f64, value: f64) {
+        if !(-0.5..0.5).contains(&f) || !(0.0..=1.0).contains(&alpha) {
+            return;
+        }
+        let rows = self.cells.len();
+        let row = ((alpha * (rows - 1) as f64).round() as usize).min(rows - 1);
+        let column = (((f + 0.5) * self.columns as f64).floor() as usize).min(self.columns - 1);
+        let cell = &mut self.cells[row][column];
+        *cell = cell.max(value);
+    }
+
+    fn into_result(self, method: ScfMethod) -> ScfResult {
+        let rows = self.cells.len();
+        let fs = self.sample_rate;
+        ScfResult {
+            alphas: (0..rows).map(|r| r as f64 / (rows - 1) as f64 * fs).collect(),
+            frequencies: (0..self.columns)
+                .map(|c| (c as f64 / self.columns as f64 - 0.5) * fs)
+                .collect(),
+            scf: self.cells,
+            sample_rate: fs,
+            method,
+        }
+    }
+}
+
+/// Cyclic autocorrelation R_x^α(τ) for τ = 0..=max_lag
+///
+/// `alpha_hz` is the cyclic frequency; R_x^α(τ) = ⟨x(n+τ)·x*(n)·e^(-j2παn/fs)⟩.
+pub fn cyclic_autocorrelation(
+    samples: &[IQSample],
+    sample_rate: f64,
+    alpha_hz: f64,
+    max_lag: usize,
+) -> Vec<Complex64> {
+    let step = -2.0 * PI * alpha_hz / sample_rate;
+    (0..=max_lag)
+        .map(|lag| {
+            if lag >= samples.len() {
+                return Complex64::new(0.0, 0.0);
+            }
+            let count = samples.len() - lag;
+            let sum: Complex64 = (0..count)
+                .map(|n| samples[n + lag] * samples[n].conj() * Complex64::from_polar(1.0, step * n as f64))
+                .sum();
+            sum / count as f64
+        })
+        .collect()
+}
+
+/// Pd/Pfa estimate at one SNR
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectabilityPoint {
+    /// Signal-to-noise ratio over the full band (dB)
+    pub snr_db: f64,
+    /// Probability of detection with the cyclostationary detector
+    pub pd_feature: f64,
+    /// Probability of detection with an ideal radiometer
+    pub pd_energy: f64,
+    /// Probability of false alarm the thresholds were set for
+    pub pfa: f64,
+}
+
+/// Cyclostationary feature detector with Monte Carlo Pd/Pfa estimation
+#[derive(Debug, Clone)]
+pub struct CyclostationaryDetector {
+    analyzer: CyclicSpectrumAnalyzer,
+    trials: usize,
+    seed: u64,
+}
+
+impl CyclostationaryDetector {
+    /// Create a detector around a spectral correlation estimator
+    pub fn new(analyzer: CyclicSpectrumAnalyzer) -> Self {
+        Self {
+            analyzer,
+            trials: 100,
+            seed: 1,
+        }
+    }
+
+    /// Set the number of Monte Carlo trials per estimate
+    pub fn with_trials(mut self, trials: usize) -> Self {
+        self.trials = trials.max(2);
+        self
+    }
+
+    /// Set the noise generator seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Test statistic: strongest cyclic feature over the profile median
+    pub fn statistic(&self, samples: &[IQSample], sample_rate: f64) -> f64 {
+        let scf = self.analyzer.compute(samples, sample_rate);
+        let profile = scf.cyclic_profile();
+        let guard = scf.guard_rows().min(profile.len());
+        let tail = &profile[guard..];
+        let peak = tail.iter().cloned().fold(0.0, f64::max);
+        peak / median(tail).max(1e-30)
+    }
+
+    /// Threshold giving `pfa` on white Gaussian noise of `num_samples`
+    pub fn threshold(&self, num_samples: usize, sample_rate: f64, pfa: f64) -> f64 {
+        let mut noise = NoiseSource::new(self.seed);
+        let mut stats: Vec<f64> = (0..self.trials)
+            .map(|_| self.statistic(&noise.samples(num_samples, 1.0), sample_rate))
+            .collect();
+        quantile(&mut stats, 1.0 - pfa)
+    }
+
+    /// Probability of detection of `signal` in white noise at `snr_db`
+    ///
+    /// The signal is scaled to unit power and the noise to 10^(-snr/10).
+    pub fn detection_probability(&self, signal: &[IQSample], sample_rate: f64, snr_db: f64, threshold: f64) -> f64 {
+        let mut noise = NoiseSource::new(self.seed.wrapping_add(0x9e37_79b9));
+        let signal = unit_power(signal);
+        let noise_power = 10f64.powf(-snr_db / 10.0);
+        let detected = (0..self.trials)
+            .filter(|_| {
+                let noisy: Vec<IQSample> = signal
+                    .iter()
+                    .zip(noise.samples(signal.len(), noise_power))
+                    .map(|(s, n)| s + n)
+                    .collect();
+                self.statistic(&noisy, sample_rate) > threshold
+            })
+            .count();
+        detected as f64 / self.trials as f64
+    }
+
+    /// Pd of this detector and of an ideal radiometer over a range of SNRs
+    ///
+    /// Both thresholds are set for `pfa` on noise of the signal's length;
+    /// the radiometer knows the noise power exactly.
+    pub fn compare(&self, signal: &[IQSample], sample_rate: f64, snrs_db: &[f64], pfa: f64) -> Vec<DetectabilityPoint> {
+        let n = signal.len();
+        let feature_threshold = self.threshold(n, sample_rate, pfa);
+
+        let mut noise = NoiseSource::new(self.seed.wrapping_add(0x85eb_ca6b));
+        let mut noise_energy: Vec<f64> = (0..self.trials.max(1000))
+            .map(|_| mean_power(&noise.samples(n, 1.0)))
+            .collect();
+        let energy_threshold = quantile(&mut noise_energy, 1.0 - pfa);
+
+        let unit = unit_power(signal);
+        snrs_db
+            .iter()
+            .map(|&snr_db| {
+                let noise_power = 10f64.powf(-snr_db / 10.0);
+                let detected = (0..self.trials)
+                    .filter(|_| {
+                        let noisy: Vec<IQSample> = unit
+                            .iter()
+                            .zip(noise.samples(n, noise_power))
+                            .map(|(s, w)| s + w)
+                            .collect();
+                        mean_power(&noisy) / noise_power > energy_threshold
+                    })
+                    .count();
+                DetectabilityPoint {
+                    snr_db,
+                    pd_feature: self.detection_probability(signal, sample_rate, snr_db, feature_threshold),
+                    pd_energy: detected as f64 / self.trials as f64,
+                    pfa,
+                }
+            })
+            .collect()
+    }
+}
+
+/// White complex Gaussian noise (xorshift64 + Box-Muller)
+struct NoiseSource {
+    state: u64,
+}
+
+impl NoiseSource {
+    fn new(seed: u64) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    fn uniform(&mut self) -> f64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        ((self.state >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+
+    fn samples(&mut self, n: usize, power: f64) -> Vec<IQSample> {
+        let sigma = power.sqrt();
+        (0..n)
+            .map(|_| {
+                let r = (-self.uniform().ln()).sqrt() * sigma;
+                IQSample::from_polar(r, 2.0 * PI * self.uniform())
+            })
+            .collect()
+    }
+}
+
+fn unit_power(samples: &[IQSample]) -> Vec<IQSample> {
+    let scale = 1.0 / mean_power(samples).max(1e-30).sqrt();
+    samples.iter().map(|s| s * scale).collect()
+}
+
+fn mean_power(samples: &[IQSample]) -> f64 {
+    samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / samples.len().max(1) as f64
+}
+
+fn quantile(values: &mut [f64], q: f64) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let index = ((values.len() as f64 * q).ceil() as usize).clamp(1, values.len().max(1)) - 1;
+    values.get(index).copied().unwrap_or(0.0)
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    sorted.get(sorted.len() / 2).copied().unwrap_or(0.0)
+}
+
+fn hamming(n: usize) -> Vec<f64> {
+    (0..n)
+        .map(|i| 0.54 - 0.46 * (2.0 * PI * i as f64 / (n - 1).max(1) as f64).cos())
+        .collect()
+}
+
+/// Signed frequency index of FFT bin `i` in a shifted layout
+fn shifted_index(i: usize, n: usize) -> isize {
+    i as isize - (n / 2) as isize
+}
+
+fn prev_power_of_two(n: usize) -> usize {
+    if n == 0 {
+        0
+    } else {
+        1 << (usize::BITS - 1 - n.leading_zeros())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rectangular BPSK with a pseudo-random bit stream
+    fn bpsk(n: usize, sps: usize, seed: u64) -> Vec<IQSample> {
+        let mut noise = NoiseSource::new(seed);
+        let bits: Vec<f64> = (0..n / sps + 1)
+            .map(|_| if noise.uniform() < 0.5 { -1.0 } else { 1.0 })
+            .collect();
+        (0..n).map(|i| IQSample::new(bits[i / sps], 0.0)).collect()
+    }
+
+    #[test]
+    fn test_fam_and_ssca_find_symbol_rate() {
+        let fs = 16000.0;
+        let samples = bpsk(8192, 10, 3);
+        for method in [ScfMethod::Fam, ScfMethod::Ssca] {
+            let scf = CyclicSpectrumAnalyzer::new(method, 32).compute(&samples, fs);
+            let features = scf.features(3);
+            assert!(
+                features.iter().any(|f| (f.alpha_hz - 1600.0).abs() < 30.0),
+                "{:?}: {:?}",
+                method,
+                features
+            );
+            assert!(features[0].strength_db > 6.0, "{:?}: {:?}", method, features);
+        }
+    }
+
+    #[test]
+    fn test_cyclic_autocorrelation_of_tone() {
+        // A tone at f0 has R^α(0) = 0 unless α = 0, and R^0(τ) = e^(j2πf0τ)
+        let fs = 1000.0;
+        let samples: Vec<IQSample> = (0..1000)
+            .map(|n| IQSample::from_polar(1.0, 2.0 * PI * 100.0 * n as f64 / fs))
+            .collect();
+        let r0 = cyclic_autocorrelation(&samples, fs, 0.0, 2);
+        assert!((r0[0].norm() - 1.0).abs() < 1e-9);
+        assert!((r0[1].arg() - 2.0 * PI * 0.1).abs() < 1e-9);
+        let r50 = cyclic_autocorrelation(&samples, fs, 50.0, 0);
+        assert!(r50[0].norm() < 0.05);
+    }
+
+    #[test]
+    fn test_detector_pfa_and_pd() {
+        let fs = 8000.0;
+        let detector = CyclostationaryDetector::new(CyclicSpectrumAnalyzer::new(ScfMethod::Fam, 16).with_alpha_bins(512))
+            .with_trials(40);
+        let signal = bpsk(2048, 4, 9);
+
+        let points = detector.compare(&signal, fs, &[-30.0, 10.0], 0.05);
+        assert!(points[0].pd_feature <= 0.25, "{:?}", points[0]);
+        assert!(points[1].pd_feature >= 0.9, "{:?}", points[1]);
+        assert!(points[1].pd_energy >= 0.9, "{:?}", points[1]);
+    }
+}
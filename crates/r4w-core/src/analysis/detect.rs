@@ -0,0 +1,597 @@
+//! Blind Signal Detection and Channelization
+//!
+//! Segments a wideband recording into individual emissions in time and
+//! frequency, and extracts each one to its own narrowband baseband stream.
+//!
+//! ## Method
+//!
+//! 1. The waterfall ([`WaterfallResult`]) is averaged over a few frames to
+//!    tame the variance of single periodograms
+//! 2. The noise floor of each bin is a low percentile over time, smoothed
+//!    with a median across frequency so that continuous carriers do not
+//!    raise their own floor
+//! 3. Cells more than `threshold_db` above the floor are grouped into
+//!    connected regions (tolerating short gaps in time)
+//! 4. Each region becomes a [`Detection`] with its start, duration, centre,
+//!    bandwidth and in-band SNR
+//!
+//! [`extract`] then down-converts a detection to baseband, low-pass filters
+//! it and decimates to a rate just above its bandwidth.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use r4w_core::analysis::{extract, EnergyDetector, WaterfallGenerator};
+//! use r4w_core::types::IQSample;
+//!
+//! // A 5 kHz tone burst in the middle of a quiet 100 kHz capture
+//! let fs = 100_000.0;
+//! let mut seed = 1u32;
+//! let mut noise = move || {
+//!     seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+//!     1e-3 * (seed as f64 / u32::MAX as f64 - 0.5)
+//! };
+//! let samples: Vec<IQSample> = (0..50_000)
+//!     .map(|n| {
+//!         let on = (20_000..30_000).contains(&n);
+//!         let phase = 2.0 * std::f64::consts::PI * 5_000.0 * n as f64 / fs;
+//!         let tone = if on { IQSample::from_polar(1.0, phase) } else { IQSample::new(0.0, 0.0) };
+//!         tone + IQSample::new(noise(), noise())
+//!     })
+//!     .collect();
+//!
+//! let waterfall = WaterfallGenerator::new(256).compute(&samples, fs);
+//! let detections = EnergyDetector::new().detect(&waterfall);
+//! assert_eq!(detections.len(), 1);
+//! assert!((detections[0].center_hz - 5_000.0).abs() < 500.0);
+//!
+//! let (baseband, rate) = extract(&samples, fs, &detections[0], 0.5);
+//! assert!(rate < fs);
+//! assert!(!baseband.is_empty());
+//! ```
+
+use super::waterfall::WaterfallResult;
+use crate::types::IQSample;
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+/// One emission found in a waterfall
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    /// Start time in seconds from the start of the recording
+    pub start_s: f64,
+    /// Duration in seconds
+    pub duration_s: f64,
+    /// First sample of the emission
+    pub start_sample: u64,
+    /// Number of samples spanned
+    pub sample_count: u64,
+    /// Centre frequency relative to the recording centre (Hz)
+    pub center_hz: f64,
+    /// Occupied bandwidth (Hz)
+    pub bandwidth_hz: f64,
+    /// In-band signal-to-noise ratio (dB)
+    pub snr_db: f64,
+    /// Peak power above the noise floor (dB)
+    pub peak_db: f64,
+}
+
+impl Detection {
+    /// Lower frequency edge relative to the recording centre (Hz)
+    pub fn freq_lower_hz(&self) -> f64 {
+        self.center_hz - self.bandwidth_hz / 2.0
+    }
+
+    /// Upper frequency edge relative to the recording centre (Hz)
+    pub fn freq_upper_hz(&self) -> f64 {
+        self.center_hz + self.bandwidth_hz / 2.0
+    }
+}
+
+/// Energy detector over a waterfall
+#[derive(Debug, Clone)]
+pub struct EnergyDetector {
+    /// Detection threshold above the noise floor (dB)
+    threshold_db: f64,
+    /// Frames averaged before thresholding
+    average_frames: usize,
+    /// Minimum bandwidth of a detection (bins)
+    min_bins: usize,
+    /// Minimum duration of a detection (frames)
+    min_frames: usize,
+    /// Gap in time (frames) bridged within one emission
+    max_gap_frames: usize,
+}
+
+impl Default for EnergyDetector {
+    fn default() -> Self {
+        Self {
+            threshold_db: 6.0,
+            average_frames: 8,
+            min_bins: 1,
+            min_frames: 2,
+            max_gap_frames: 2,
+        }
+    }
+}
+
+impl EnergyDetector {
+    /// Create a detector with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set detection threshold above the noise floor (dB)
+    pub fn with_threshold(mut self, threshold_db: f64) -> Self {
+        self.threshold_db = threshold_db;
+        self
+    }
+
+    /// Set number of frames averaged before thresholding
+    pub fn with_average(mut self, frames: usize) -> Self {
+        self.average_frames = frames.max(1);
+        self
+    }
+
+    /// Set minimum detection size in bins and frames
+    pub fn with_min_size(mut self, bins: usize, frames: usize) -> Self {
+        self.min_bins = bins.max(1);
+        self.min_frames = frames.max(1);
+        self
+    }
+
+    /// Set the gap in time (frames) bridged within one emission
+    pub fn with_max_gap(mut self, frames: usize) -> Self {
+        self.max_gap_frames = frames;
+        self
+    }
+
+    /// Estimate the noise floor of each bin (linear power)
+    ///
+    /// The 20th percentile over time, scaled up to the mean of the averaged
+    /// noise power, then a median over 1/16 of the band.
+    pub fn noise_floor(&self, waterfall: &WaterfallResult) -> Vec<f64> {
+        let grid = self.averaged(waterfall);
+        self.floor_of(waterfall, &grid)
+    }
+
+    /// Find emissions in a waterfall
+    pub fn detect(&self, waterfall: &WaterfallResult) -> Vec<Detection> {
+        let grid = self.averaged(waterfall);
+        if grid.is_empty() {
+            return Vec::new();
+        }
+        let floor = self.floor_of(waterfall, &grid);
+        let frames = grid.len();
+        let bins = floor.len();
+        let threshold = 10f64.powf(self.threshold_db / 10.0);
+
+        let active: Vec<Vec<bool>> = grid
+            .iter()
+            .map(|row| row.iter().zip(&floor).map(|(p, f)| *p > f * threshold).collect())
+            .collect();
+
+        let mut labelled = vec![vec![false; bins]; frames];
+        let mut detections = Vec::new();
+        let reach = self.max_gap_frames as isize + 1;
+
+        for t0 in 0..frames {
+            for b0 in 0..bins {
+                if !active[t0][b0] || labelled[t0][b0] {
+                    continue;
+                }
+
+                // Flood fill one region, bridging short gaps in time
+                let mut cells = Vec::new();
+                let mut queue = VecDeque::from([(t0, b0)]);
+                labelled[t0][b0] = true;
+                while let Some((t, b)) = queue.pop_front() {
+                    cells.push((t, b));
+                    for dt in -reach..=reach {
+                        for db in -1isize..=1 {
+                            let (nt, nb) = (t as isize + dt, b as isize + db);
+                            if nt < 0 || nb < 0 || nt >= frames as isize || nb >= bins as isize {
+                                continue;
+                            }
+                            let (nt, nb) = (nt as usize, nb as usize);
+                            if active[nt][nb] && !labelled[nt][nb] {
+                                labelled[nt][nb] = true;
+                                queue.push_back((nt, nb));
+                            }
+                        }
+                    }
+                }
+
+                if let Some(detection) = self.measure(waterfall, &grid, &floor, &cells) {
+                    detections.push(detection);
+                }
+            }
+        }
+
+        // A much stronger emission leaks into neighbouring bins while it is
+        // on (window sidelobes) and splatters across the band when it is
+        // keyed; drop weak detections explained by either
+        let edge = waterfall.fft_size as u64;
+        let splatter: Vec<bool> = detections
+            .iter()
+            .map(|d| {
+                let end = d.start_sample + d.sample_count;
+                detections.iter().any(|s| {
+                    let s_end = s.start_sample + s.sample_count;
+                    let leakage = d.start_sample + edge >= s.start_sample
+                        && end <= s_end + edge
+                        && (d.center_hz - s.center_hz).abs() <= 8.0 * s.bandwidth_hz;
+                    let keying = d.sample_count <= 8 * edge
+                        && [s.start_sample, s_end]
+                            .iter()
+                            .any(|&e| d.start_sample <= e + 2 * edge && end + 2 * edge >= e);
+                    s.snr_db >= d.snr_db + 20.0 && (leakage || keying)
+                })
+            })
+            .collect();
+        let mut detections: Vec<Detection> = detections
+            .into_iter()
+            .zip(splatter)
+            .filter(|(_, splatter)| !splatter)
+            .map(|(d, _)| d)
+            .collect();
+
+        detections.sort_by(|a, b| {
+            a.start_sample
+                .cmp(&b.start_sample)
+                .then(a.center_hz.partial_cmp(&b.center_hz).unwrap())
+        });
+        detections
+    }
+
+    /// Summarise one connected region
+    fn measure(
+        &self,
+        waterfall: &WaterfallResult,
+        grid: &[Vec<f64>],
+        floor: &[f64],
+        cells: &[(usize, usize)],
+    ) -> Option<Detection> {
+        let t_first = cells.iter().map(|c| c.0).min()?;
+        let t_last = cells.iter().map(|c| c.0).max()?;
+        let region_first = cells.iter().map(|c| c.1).min()?;
+        let region_last = cells.iter().map(|c| c.1).max()?;
+
+        // Window leakage and switching transients spread a strong emission
+        // over many bins; report the band holding 99% of its excess power
+        let mut excess = vec![0.0; region_last - region_first + 1];
+        for &(t, b) in cells {
+            excess[b - region_first] += (grid[t][b] - floor[b]).max(0.0);
+        }
+        let total: f64 = excess.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut cumulative = 0.0;
+        let mut b_first = region_first;
+        let mut b_last = region_last;
+        for (i, e) in excess.iter().enumerate() {
+            let before = cumulative;
+            cumulative += e;
+            if before <= 0.005 * total && cumulative > 0.005 * total {
+                b_first = region_first + i;
+            }
+            if before < 0.995 * total && cumulative >= 0.995 * total {
+                b_last = region_first + i;
+            }
+        }
+        if b_last + 1 - b_first < self.min_bins {
+            return None;
+        }
+
+        let mut signal = 0.0;
+        let mut noise = 0.0;
+        let mut peak = 0.0f64;
+        let mut weighted_bin = 0.0;
+        for &(t, b) in cells.iter().filter(|c| (b_first..=b_last).contains(&c.1)) {
+            let excess = (grid[t][b] - floor[b]).max(0.0);
+            signal += excess;
+            noise += floor[b];
+            peak = peak.max(excess / floor[b]);
+            weighted_bin += excess * b as f64;
+        }
+        if signal <= 0.0 {
+            return None;
+        }
+
+        let resolution = waterfall.sample_rate / waterfall.fft_size as f64;
+        let centre_bin = weighted_bin / signal;
+        let first_freq = waterfall.frequencies.first().copied().unwrap_or(0.0);
+
+        // Averaging smears the region in time; find the edges again on the
+        // raw frames, using the power summed over the detected band
+        let band_floor: f64 = floor[b_first..=b_last].iter().sum();
+        let threshold = 10f64.powf(self.threshold_db / 10.0);
+        let last_raw = (t_last + self.average_frames - 1).min(waterfall.power_db.len() - 1);
+        let in_band = |t: usize| -> f64 {
+            waterfall.power_db[t][b_first..=b_last]
+                .iter()
+                .map(|db| 10f64.powf(db / 10.0))
+                .sum()
+        };
+        let mut active = (t_first..=last_raw).filter(|&t| in_band(t) > band_floor * threshold);
+        let first_frame = active.next()?;
+        let last_frame = active.next_back().unwrap_or(first_frame);
+        if last_frame + 1 - first_frame < self.min_frames {
+            return None;
+        }
+
+        // Frame f covers [f·hop, f·hop + fft); take the half-hop around each centre
+        let hop = waterfall.hop_size as u64;
+        let centre = |f: usize| f as u64 * hop + waterfall.fft_size as u64 / 2;
+        let start_sample = centre(first_frame).saturating_sub(hop / 2);
+        let sample_count = centre(last_frame) + hop / 2 - start_sample;
+
+        Some(Detection {
+            start_s: start_sample as f64 / waterfall.sample_rate,
+            duration_s: sample_count as f64 / waterfall.sample_rate,
+            start_sample,
+            sample_count,
+            center_hz: first_freq + centre_bin * resolution,
+            bandwidth_hz: (b_last - b_first + 1) as f64 * resolution,
+            snr_db: 10.0 * (signal / noise).log10(),
+            peak_db: 10.0 * peak.max(1e-30).log10(),
+        })
+    }
+
+    /// Linear power averaged over `average_frames` consecutive frames
+    fn averaged(&self, waterfall: &WaterfallResult) -> Vec<Vec<f64>> {
+        let linear: Vec<Vec<f64>> = waterfall
+            .power_db
+            .iter()
+            .map(|row| row.iter().map(|db| 10f64.powf(db / 10.0)).collect())
+            .collect();
+        let len = self.average_frames.min(linear.len()).max(1);
+        if linear.len() < len {
+            return Vec::new();
+        }
+
+        (0..=linear.len() - len)
+            .map(|t| {
+                let mut row = vec![0.0; linear[t].len()];
+                for frame in &linear[t..t + len] {
+                    for (acc, p) in row.iter_mut().zip(frame) {
+                        *acc += p / len as f64;
+                    }
+                }
+                row
+            })
+            .collect()
+    }
+
+    fn floor_of(&self, waterfall: &WaterfallResult, grid: &[Vec<f64>]) -> Vec<f64> {
+        let Some(first) = grid.first() else {
+            return Vec::new();
+        };
+        let bins = first.len();
+
+        // Averaged noise power is roughly chi-squared with 2k degrees of
+        // freedom; overlapping Hann frames are only partly independent.
+        // Wilson-Hilferty gives the 20th percentile as a fraction of the mean.
+        let overlap = (1.5 * waterfall.hop_size as f64 / waterfall.fft_size.max(1) as f64).min(1.0);
+        let k = (self.average_frames as f64 * overlap).max(1.0);
+        let z = -0.8416;
+        let percentile = (1.0 - 1.0 / (9.0 * k) + z / (3.0 * k.sqrt())).max(0.05).powi(3);
+
+        let per_bin: Vec<f64> = (0..bins)
+            .map(|b| {
+                let mut column: Vec<f64> = grid.iter().map(|row| row[b]).collect();
+                column.sort_by(|x, y| x.partial_cmp(y).unwrap());
+                column[column.len() / 5] / percentile
+            })
+            .collect();
+
+        let half = (bins / 32).max(1);
+        (0..bins)
+            .map(|b| {
+                let mut window: Vec<f64> = per_bin[b.saturating_sub(half)..(b + half + 1).min(bins)].to_vec();
+                window.sort_by(|x, y| x.partial_cmp(y).unwrap());
+                window[window.len() / 2].max(1e-30)
+            })
+            .collect()
+    }
+
+    /// Format detections as a text table
+    pub fn format_text(detections: &[Detection]) -> String {
+        let mut output = String::new();
+        output.push_str("Detections\n");
+        output.push_str("══════════════════════════════════════════════════════════════════\n");
+        output.push_str(&format!(
+            "{:>4}  {:>10}  {:>10}  {:>12}  {:>11}  {:>7}\n",
+            "#", "Start (s)", "Dur (ms)", "Centre (Hz)", "BW (Hz)", "SNR dB"
+        ));
+        output.push_str("──────────────────────────────────────────────────────────────────\n");
+        for (i, d) in detections.iter().enumerate() {
+            output.push_str(&format!(
+                "{:>4}  {:>10.6}  {:>10.3}  {:>12.1}  {:>11.1}  {:>7.1}\n",
+                i + 1,
+                d.start_s,
+                d.duration_s * 1000.0,
+                d.center_hz,
+                d.bandwidth_hz,
+                d.snr_db
+            ));
+        }
+        if detections.is_empty() {
+            output.push_str("  (no emissions above threshold)\n");
+        }
+        output
+    }
+
+    /// Format detections as JSON
+    pub fn format_json(detections: &[Detection]) -> String {
+        let mut output = String::new();
+        output.push_str("{\n  \"detections\": [\n");
+        for (i, d) in detections.iter().enumerate() {
+            output.push_str(&format!(
+                "    {{\"start_s\": {:.9}, \"duration_s\": {:.9}, \"start_sample\": {}, \"sample_count\": {}, \
+                 \"center_hz\": {:.3}, \"bandwidth_hz\": {:.3}, \"snr_db\": {:.2}, \"peak_db\": {:.2}}}",
+                d.start_s,
+                d.duration_s,
+                d.start_sample,
+                d.sample_count,
+                d.center_hz,
+                d.bandwidth_hz,
+                d.snr_db,
+                d.peak_db
+            ));
+            if i < detections.len() - 1 {
+                output.push(',');
+            }
+            output.push('\n');
+        }
+        output.push_str("  ],\n");
+        output.push_str(&format!("  \"count\": {}\n", detections.len()));
+        output.push_str("}\n");
+        output
+    }
+
+    /// Format detections as CSV
+    pub fn format_csv(detections: &[Detection]) -> String {
+        let mut output = String::from("start_s,duration_s,start_sample,sample_count,center_hz,bandwidth_hz,snr_db,peak_db\n");
+        for d in detections {
+            output.push_str(&format!(
+                "{:.9},{:.9},{},{},{:.3},{:.3},{:.2},{:.2}\n",
+                d.start_s, d.duration_s, d.start_sample, d.sample_count, d.center_hz, d.bandwidth_hz, d.snr_db, d.peak_db
+            ));
+        }
+        output
+    }
+}
+
+/// Down-convert, filter and decimate one detection to baseband
+///
+/// `margin` widens the passband beyond the detected bandwidth (0.5 keeps 50%
+/// extra). The mixer phase is referenced to the start of the recording, so
+/// extracts of one emission stay phase-consistent. Returns the baseband
+/// samples and their sample rate.
+pub fn extract(samples: &[IQSample], sample_rate: f64, detection: &Detection, margin: f64) -> (Vec<IQSample>, f64) {
+    let start = (detection.start_sample as usize).min(samples.len());
+    let end = (start + detection.sample_count as usize).min(samples.len());
+    let passband = (detection.bandwidth_hz * (1.0 + margin.max(0.0))).max(sample_rate / 1e6);
+    let decimation = ((sample_rate / passband).floor() as usize).max(1);
+    let output_rate = sample_rate / decimation as f64;
+
+    // Windowed-sinc low-pass at half the passband
+    let cutoff = (passband / 2.0 / sample_rate).min(0.5);
+    let taps = lowpass_taps(cutoff, 8 * decimation + 1);
+    let delay = taps.len() / 2;
+
+    let step = -2.0 * PI * detection.center_hz / sample_rate;
+    let mixed: Vec<IQSample> = (start..end)
+        .map(|n| samples[n] * IQSample::from_polar(1.0, step * n as f64))
+        .collect();
+
+    let output = (0..mixed.len())
+        .step_by(decimation)
+        .map(|i| {
+            taps.iter()
+                .enumerate()
+                .filter_map(|(k, h)| (i + delay).checked_sub(k).and_then(|j| mixed.get(j)).map(|s| s * h))
+                .sum()
+        })
+        .collect();
+    (output, output_rate)
+}
+
+/// Hamming-windowed sinc low-pass, unity DC gain
+fn lowpass_taps(cutoff: f64, len: usize) -> Vec<f64> {
+    let mid = (len / 2) as f64;
+    let mut taps: Vec<f64> = (0..len)
+        .map(|i| {
+            let x = i as f64 - mid;
+            let sinc = if x == 0.0 { 2.0 * cutoff } else { (2.0 * PI * cutoff * x).sin() / (PI * x) };
+            let window = 0.54 - 0.46 * (2.0 * PI * i as f64 / (len - 1).max(1) as f64).cos();
+            sinc * window
+        })
+        .collect();
+    let sum: f64 = taps.iter().sum();
+    taps.iter_mut().for_each(|t| *t /= sum);
+    taps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::WaterfallGenerator;
+
+    fn noise(n: usize, sigma: f64, seed: &mut u64) -> Vec<IQSample> {
+        (0..n)
+            .map(|_| {
+                let mut uniform = || {
+                    *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                    ((*seed >> 11) as f64 / (1u64 << 53) as f64).max(1e-12)
+                };
+                let r = (-2.0 * uniform().ln()).sqrt() * sigma;
+                IQSample::from_polar(r, 2.0 * PI * uniform())
+            })
+            .collect()
+    }
+
+    fn tone_burst(samples: &mut [IQSample], fs: f64, freq: f64, range: std::ops::Range<usize>, amplitude: f64) {
+        for n in range {
+            samples[n] += IQSample::from_polar(amplitude, 2.0 * PI * freq * n as f64 / fs);
+        }
+    }
+
+    #[test]
+    fn test_noise_only_has_no_detections() {
+        let mut seed = 1;
+        let samples = noise(65536, 0.1, &mut seed);
+        let waterfall = WaterfallGenerator::new(512).compute(&samples, 1e6);
+        assert!(EnergyDetector::new().detect(&waterfall).is_empty());
+    }
+
+    #[test]
+    fn test_detects_bursts_in_time_and_frequency() {
+        let fs = 1e6;
+        let mut seed = 2;
+        let mut samples = noise(200_000, 0.01, &mut seed);
+        tone_burst(&mut samples, fs, -200e3, 20_000..60_000, 0.1);
+        tone_burst(&mut samples, fs, 150e3, 100_000..180_000, 0.1);
+
+        let waterfall = WaterfallGenerator::new(1024).compute(&samples, fs);
+        let detections = EnergyDetector::new().detect(&waterfall);
+        assert_eq!(detections.len(), 2, "{}", EnergyDetector::format_text(&detections));
+
+        let first = &detections[0];
+        assert!((first.center_hz + 200e3).abs() < 2e3, "centre {}", first.center_hz);
+        assert!((first.start_sample as f64 - 20_000.0).abs() < 2048.0, "start {}", first.start_sample);
+        assert!((first.sample_count as f64 - 40_000.0).abs() < 4096.0, "count {}", first.sample_count);
+        assert!(first.snr_db > 10.0);
+
+        let second = &detections[1];
+        assert!((second.center_hz - 150e3).abs() < 2e3);
+        assert!((second.duration_s - 0.08).abs() < 0.004);
+    }
+
+    #[test]
+    fn test_extract_moves_detection_to_baseband() {
+        let fs = 1e6;
+        let mut seed = 3;
+        let mut samples = noise(100_000, 0.01, &mut seed);
+        tone_burst(&mut samples, fs, 250e3 + 1e3, 10_000..90_000, 0.5);
+
+        let waterfall = WaterfallGenerator::new(256).compute(&samples, fs);
+        let detections = EnergyDetector::new().detect(&waterfall);
+        assert_eq!(detections.len(), 1, "{}", EnergyDetector::format_text(&detections));
+
+        let mut detection = detections[0].clone();
+        detection.center_hz = 250e3;
+        let (baseband, rate) = extract(&samples, fs, &detection, 0.5);
+        assert!(rate <= fs / 8.0, "rate {}", rate);
+
+        // The residual 1 kHz offset should be all that is left
+        let steady = &baseband[baseband.len() / 4..3 * baseband.len() / 4];
+        let rotation: IQSample = steady.windows(2).map(|w| w[1] * w[0].conj()).sum();
+        let freq = rotation.arg() * rate / (2.0 * PI);
+        assert!((freq - 1e3).abs() < 50.0, "residual {}", freq);
+        let power = steady.iter().map(|s| s.norm_sqr()).sum::<f64>() / steady.len() as f64;
+        assert!((power - 0.25).abs() < 0.03, "power {}", power);
+    }
+}
@@ -0,0 +1,764 @@
+//! Direction Finding
+//!
+//! Angle-of-arrival estimation from phase-coherent multi-channel captures,
+//! one sample buffer per array element (as read from a multi-channel SigMF
+//! recording). Element positions are given in wavelengths and follow the
+//! simulator's convention: a far-field source at azimuth θ and elevation φ
+//! has the steering vector
+//!
+//! ```text
+//! a_k(θ, φ) = exp(j·2π·p_k · u(θ, φ))      u = (cos θ cos φ, sin θ cos φ, sin φ)
+//! ```
+//!
+//! so a ULA along y with spacing `d` gives `exp(j·2π·k·d·sin θ)`.
+//!
+//! ## Methods
+//!
+//! | Method         | Geometry  | Notes                                        |
+//! |----------------|-----------|----------------------------------------------|
+//! | Bartlett       | any       | Delay-and-sum beam scan, beamwidth-limited   |
+//! | Capon (MVDR)   | any       | Adaptive nulls, sharper than Bartlett        |
+//! | MUSIC          | any       | Noise-subspace pseudospectrum                |
+//! | Root-MUSIC     | ULA       | Polynomial rooting, no angle grid            |
+//! | ESPRIT         | ULA       | Rotational invariance of the signal subspace |
+//! | Interferometer | any       | Phase comparison against element 0, 1 source |
+//!
+//! Linear arrays are scanned over -90°..90° (front/back ambiguous); other
+//! layouts over the full circle.
+//!
+//! ## Calibration
+//!
+//! Unequal cable lengths and receiver gains add a fixed complex gain per
+//! channel. [`ArrayCalibration::from_reference`] measures it from a capture
+//! of a source at a known bearing and removes it from later captures.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use r4w_core::analysis::{DoaEstimator, DoaMethod, SensorArray};
+//! use r4w_core::types::IQSample;
+//!
+//! let array = SensorArray::ula(8, 0.5);
+//! let steering = array.steering_vector(25.0, 0.0);
+//! // 64 snapshots of a single source with a changing phase
+//! let channels: Vec<Vec<IQSample>> = steering
+//!     .iter()
+//!     .map(|a| (0..64).map(|n| a * IQSample::from_polar(1.0, 0.7 * n as f64)).collect())
+//!     .collect();
+//!
+//! let estimator = DoaEstimator::new(array).with_sources(1);
+//! let bearings = estimator.estimate(DoaMethod::Esprit, &channels).unwrap();
+//! assert!((bearings[0] - 25.0).abs() < 0.1);
+//! ```
+
+use crate::types::IQSample;
+use crate::waveform::fmcw::mimo::{symmetric_eigen, AngleSpectrum};
+use rustfft::num_complex::Complex64;
+use std::f64::consts::PI;
+
+/// Direction-finding algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DoaMethod {
+    /// Delay-and-sum beam scan
+    Bartlett,
+    /// Minimum variance distortionless response
+    Capon,
+    /// Multiple signal classification
+    #[default]
+    Music,
+    /// Polynomial-rooting MUSIC (ULA only)
+    RootMusic,
+    /// Estimation of signal parameters via rotational invariance (ULA only)
+    Esprit,
+    /// Correlative phase interferometer (single source)
+    Interferometer,
+}
+
+impl DoaMethod {
+    /// Parse method from string
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "bartlett" | "beamscan" => Some(DoaMethod::Bartlett),
+            "capon" | "mvdr" => Some(DoaMethod::Capon),
+            "music" => Some(DoaMethod::Music),
+            "rootmusic" => Some(DoaMethod::RootMusic),
+            "esprit" => Some(DoaMethod::Esprit),
+            "interferometer" | "phase" => Some(DoaMethod::Interferometer),
+            _ => None,
+        }
+    }
+
+    /// Short name
+    pub fn name(&self) -> &'static str {
+        match self {
+            DoaMethod::Bartlett => "Bartlett",
+            DoaMethod::Capon => "Capon",
+            DoaMethod::Music => "MUSIC",
+            DoaMethod::RootMusic => "Root-MUSIC",
+            DoaMethod::Esprit => "ESPRIT",
+            DoaMethod::Interferometer => "Interferometer",
+        }
+    }
+
+    /// Whether the method produces a spatial spectrum over an angle grid
+    pub fn is_spectral(&self) -> bool {
+        !matches!(self, DoaMethod::RootMusic | DoaMethod::Esprit)
+    }
+}
+
+/// Element positions of a receive array, in wavelengths
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorArray {
+    positions: Vec<[f64; 3]>,
+}
+
+impl SensorArray {
+    /// Array from explicit element positions (wavelengths)
+    pub fn new(positions: Vec<[f64; 3]>) -> Self {
+        assert!(!positions.is_empty(), "array needs at least one element");
+        Self { positions }
+    }
+
+    /// Uniform linear array along the y axis, element 0 at the origin
+    pub fn ula(num_elements: usize, spacing_wavelengths: f64) -> Self {
+        Self::new(
+            (0..num_elements.max(1))
+                .map(|k| [0.0, k as f64 * spacing_wavelengths, 0.0])
+                .collect(),
+        )
+    }
+
+    /// Uniform circular array in the horizontal plane, element 0 on the +x axis
+    pub fn uca(num_elements: usize, radius_wavelengths: f64) -> Self {
+        let n = num_elements.max(1);
+        Self::new(
+            (0..n)
+                .map(|k| {
+                    let a = 2.0 * PI * k as f64 / n as f64;
+                    [radius_wavelengths * a.cos(), radius_wavelengths * a.sin(), 0.0]
+                })
+                .collect(),
+        )
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Whether the array has no elements
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Element positions (wavelengths)
+    pub fn positions(&self) -> &[[f64; 3]] {
+        &self.positions
+    }
+
+    /// Element spacing if this is a ULA along y in element order
+    pub fn ula_spacing(&self) -> Option<f64> {
+        if self.len() < 2 {
+            return None;
+        }
+        let origin = self.positions[0];
+        let d = self.positions[1][1] - origin[1];
+        let uniform = self.positions.iter().enumerate().all(|(k, p)| {
+            (p[0] - origin[0]).abs() < 1e-9
+                && (p[2] - origin[2]).abs() < 1e-9
+                && (p[1] - origin[1] - k as f64 * d).abs() < 1e-9
+        });
+        (uniform && d > 0.0).then_some(d)
+    }
+
+    /// Steering vector for a far-field source
+    pub fn steering_vector(&self, azimuth_deg: f64, elevation_deg: f64) -> Vec<IQSample> {
+        let (az, el) = (azimuth_deg.to_radians(), elevation_deg.to_radians());
+        let u = [az.cos() * el.cos(), az.sin() * el.cos(), el.sin()];
+        self.positions
+            .iter()
+            .map(|p| IQSample::from_polar(1.0, 2.0 * PI * (p[0] * u[0] + p[1] * u[1] + p[2] * u[2])))
+            .collect()
+    }
+}
+
+/// Sample covariance `R = E[x xᴴ]` of a multi-channel capture
+///
+/// `channels` holds one buffer per element; the shortest sets the number
+/// of snapshots.
+pub fn covariance(channels: &[Vec<IQSample>]) -> Vec<Vec<Complex64>> {
+    let m = channels.len();
+    let n = channels.iter().map(|c| c.len()).min().unwrap_or(0);
+    let mut cov = vec![vec![Complex64::new(0.0, 0.0); m]; m];
+    for i in 0..m {
+        for j in i..m {
+            let sum: Complex64 = channels[i][..n]
+                .iter()
+                .zip(&channels[j][..n])
+                .map(|(a, b)| a * b.conj())
+                .sum();
+            cov[i][j] = sum / n.max(1) as f64;
+            cov[j][i] = cov[i][j].conj();
+        }
+    }
+    cov
+}
+
+/// Number of sources by the minimum description length criterion
+///
+/// `snapshots` is the number of samples the covariance was averaged over.
+pub fn estimate_num_sources(cov: &[Vec<Complex64>], snapshots: usize) -> usize {
+    let (values, _) = hermitian_eigen(cov);
+    let m = values.len();
+    let n = snapshots.max(1) as f64;
+    (0..m)
+        .map(|k| {
+            let noise: Vec<f64> = values[k..].iter().map(|v| v.max(1e-30)).collect();
+            let count = noise.len() as f64;
+            let arithmetic = noise.iter().sum::<f64>() / count;
+            let geometric = (noise.iter().map(|v| v.ln()).sum::<f64>() / count).exp();
+            let mdl = -n * count * (geometric / arithmetic).ln()
+                + 0.5 * (k * (2 * m - k)) as f64 * n.ln();
+            (k, mdl)
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(k, _)| k)
+        .unwrap_or(0)
+}
+
+/// Per-channel complex gains measured against a known source
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayCalibration {
+    gains: Vec<Complex64>,
+}
+
+impl ArrayCalibration {
+    /// No correction for `num_elements` channels
+    pub fn identity(num_elements: usize) -> Self {
+        Self {
+            gains: vec![Complex64::new(1.0, 0.0); num_elements],
+        }
+    }
+
+    /// Measure channel gains from a capture of one source at a known bearing
+    ///
+    /// The dominant eigenvector of the covariance is compared with the
+    /// ideal steering vector; gains are relative to element 0.
+    pub fn from_reference(
+        channels: &[Vec<IQSample>],
+        array: &SensorArray,
+        azimuth_deg: f64,
+        elevation_deg: f64,
+    ) -> Self {
+        assert_eq!(channels.len(), array.len(), "one channel per element");
+        let (_, vectors) = hermitian_eigen(&covariance(channels));
+        let steering = array.steering_vector(azimuth_deg, elevation_deg);
+        let raw: Vec<Complex64> = vectors[0].iter().zip(&steering).map(|(v, a)| v / a).collect();
+        let reference = raw[0];
+        Self {
+            gains: raw.iter().map(|g| g / reference).collect(),
+        }
+    }
+
+    /// Complex gain of each channel relative to element 0
+    pub fn gains(&self) -> &[Complex64] {
+        &self.gains
+    }
+
+    /// Remove the channel gains from a capture
+    pub fn apply(&self, channels: &[Vec<IQSample>]) -> Vec<Vec<IQSample>> {
+        channels
+            .iter()
+            .zip(&self.gains)
+            .map(|(c, g)| c.iter().map(|s| s / g).collect())
+            .collect()
+    }
+
+    /// Remove the channel gains from a covariance matrix
+    pub fn apply_covariance(&self, cov: &[Vec<Complex64>]) -> Vec<Vec<Complex64>> {
+        cov.iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(j, r)| r / (self.gains[i] * self.gains[j].conj()))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Angle-of-arrival estimator for a fixed array
+#[derive(Debug, Clone)]
+pub struct DoaEstimator {
+    array: SensorArray,
+    sources: usize,
+    elevation_deg: f64,
+    resolution_deg: f64,
+    calibration: Option<ArrayCalibration>,
+}
+
+impl DoaEstimator {
+    /// Create an estimator for one source in the horizontal plane
+    pub fn new(array: SensorArray) -> Self {
+        Self {
+            array,
+            sources: 1,
+            elevation_deg: 0.0,
+            resolution_deg: 0.1,
+            calibration: None,
+        }
+    }
+
+    /// Set the number of sources (subspace methods and peak count)
+    pub fn with_sources(mut self, sources: usize) -> Self {
+        self.sources = sources.max(1);
+        self
+    }
+
+    /// Set the elevation the azimuth scan assumes
+    pub fn with_elevation(mut self, elevation_deg: f64) -> Self {
+        self.elevation_deg = elevation_deg;
+        self
+    }
+
+    /// Set the angle grid step (degrees)
+    pub fn with_resolution(mut self, step_deg: f64) -> Self {
+        self.resolution_deg = step_deg.max(1e-3);
+        self
+    }
+
+    /// Apply a channel calibration before estimating
+    pub fn with_calibration(mut self, calibration: ArrayCalibration) -> Self {
+        assert_eq!(calibration.gains.len(), self.array.len(), "one gain per element");
+        self.calibration = Some(calibration);
+        self
+    }
+
+    /// Get the array
+    pub fn array(&self) -> &SensorArray {
+        &self.array
+    }
+
+    /// Azimuth grid: -90°..90° for a ULA, the full circle otherwise
+    pub fn scan_angles(&self) -> Vec<f64> {
+        let (start, span) = if self.array.ula_spacing().is_some() {
+            (-90.0, 180.0)
+        } else {
+            (-180.0, 360.0 - self.resolution_deg)
+        };
+        let steps = (span / self.resolution_deg).round() as usize;
+        (0..=steps).map(|i| start + i as f64 * self.resolution_deg).collect()
+    }
+
+    /// Bearings (degrees azimuth) of up to `sources` emitters
+    ///
+    /// Spectral methods list the strongest peak first. The interferometer
+    /// always reports a single bearing.
+    /// Returns `None` if the method needs a ULA and the array is not one.
+    pub fn estimate(&self, method: DoaMethod, channels: &[Vec<IQSample>]) -> Option<Vec<f64>> {
+        assert_eq!(channels.len(), self.array.len(), "one channel per element");
+        self.estimate_from_covariance(method, &covariance(channels))
+    }
+
+    /// Bearings from a precomputed covariance matrix
+    pub fn estimate_from_covariance(&self, method: DoaMethod, cov: &[Vec<Complex64>]) -> Option<Vec<f64>> {
+        match method {
+            DoaMethod::RootMusic => self.root_music(&self.calibrated(cov)),
+            DoaMethod::Esprit => self.esprit(&self.calibrated(cov)),
+            DoaMethod::Interferometer => Some(self.spectrum(method, cov)?.peaks(1)),
+            _ => Some(self.spectrum(method, cov)?.peaks(self.sources)),
+        }
+    }
+
+    /// Spatial spectrum over [`scan_angles`](Self::scan_angles)
+    ///
+    /// Returns `None` for the grid-free methods (root-MUSIC, ESPRIT).
+    pub fn spectrum(&self, method: DoaMethod, cov: &[Vec<Complex64>]) -> Option<AngleSpectrum> {
+        let cov = self.calibrated(cov);
+        let m = self.array.len();
+        let angles = self.scan_angles();
+        let steer = |angle: f64| self.array.steering_vector(angle, self.elevation_deg);
+
+        let power: Vec<f64> = match method {
+            DoaMethod::Bartlett => angles
+                .iter()
+                .map(|&a| quadratic_form(&cov, &steer(a)) / (m * m) as f64)
+                .collect(),
+            DoaMethod::Capon => {
+                // Diagonal loading keeps the inverse stable at high SNR
+                let load = 1e-6 * (0..m).map(|i| cov[i][i].re).sum::<f64>() / m as f64;
+                let mut loaded = cov.clone();
+                for (i, row) in loaded.iter_mut().enumerate() {
+                    row[i] += load;
+                }
+                let inverse = invert(&loaded)?;
+                angles
+                    .iter()
+                    .map(|&a| 1.0 / quadratic_form(&inverse, &steer(a)).max(1e-30))
+                    .collect()
+            }
+            DoaMethod::Music => {
+                let noise = self.noise_subspace(&cov);
+                angles
+                    .iter()
+                    .map(|&a| {
+                        let a = steer(a);
+                        let denom: f64 = noise.iter().map(|e| inner(e, &a).norm_sqr()).sum();
+                        1.0 / denom.max(1e-15)
+                    })
+                    .collect()
+            }
+            DoaMethod::Interferometer => {
+                // Phase of every element relative to element 0
+                let phases: Vec<Complex64> = (0..m)
+                    .map(|i| {
+                        let r = cov[i][0];
+                        if r.norm() > 0.0 { r / r.norm() } else { r }
+                    })
+                    .collect();
+                angles
+                    .iter()
+                    .map(|&a| {
+                        let a = steer(a);
+                        let reference = a[0].conj();
+                        let sum: Complex64 = phases.iter().zip(&a).map(|(p, s)| p * (s * reference).conj()).sum();
+                        sum.norm() / m as f64
+                    })
+                    .collect()
+            }
+            DoaMethod::RootMusic | DoaMethod::Esprit => return None,
+        };
+
+        Some(AngleSpectrum { angles_deg: angles, power })
+    }
+
+    fn calibrated(&self, cov: &[Vec<Complex64>]) -> Vec<Vec<Complex64>> {
+        match &self.calibration {
+            Some(calibration) => calibration.apply_covariance(cov),
+            None => cov.to_vec(),
+        }
+    }
+
+    fn noise_subspace(&self, cov: &[Vec<Complex64>]) -> Vec<Vec<Complex64>> {
+        let m = self.array.len();
+        assert!(self.sources < m, "subspace methods need fewer sources than elements");
+        let (_, vectors) = hermitian_eigen(cov);
+        vectors[self.sources..].to_vec()
+    }
+
+    fn spacing_to_azimuth(&self, phase: f64, spacing: f64) -> Option<f64> {
+        let s = phase / (2.0 * PI * spacing);
+        (s.abs() <= 1.0).then(|| s.asin().to_degrees())
+    }
+
+    fn root_music(&self, cov: &[Vec<Complex64>]) -> Option<Vec<f64>> {
+        let spacing = self.array.ula_spacing()?;
+        let m = self.array.len();
+        let noise = self.noise_subspace(cov);
+
+        // aᴴ·E_n·E_nᴴ·a with a_k = z^k is a Laurent polynomial in z whose
+        // coefficient of z^d is the sum of the d-th diagonal of E_n·E_nᴴ
+        let mut coefficients = vec![Complex64::new(0.0, 0.0); 2 * m - 1];
+        for e in &noise {
+            for k in 0..m {
+                for l in 0..m {
+                    coefficients[l + m - 1 - k] += e[k] * e[l].conj();
+                }
+            }
+        }
+        // Coefficients were accumulated lowest power first
+        coefficients.reverse();
+
+        let mut roots: Vec<Complex64> = polynomial_roots(&coefficients)
+            .into_iter()
+            .filter(|z| z.norm() <= 1.0)
+            .collect();
+        roots.sort_by(|a, b| (1.0 - a.norm()).total_cmp(&(1.0 - b.norm())));
+        Some(
+            roots
+                .iter()
+                .take(self.sources)
+                .filter_map(|z| self.spacing_to_azimuth(z.arg(), spacing))
+                .collect(),
+        )
+    }
+
+    fn esprit(&self, cov: &[Vec<Complex64>]) -> Option<Vec<f64>> {
+        let spacing = self.array.ula_spacing()?;
+        let m = self.array.len();
+        let d = self.sources;
+        assert!(d < m, "ESPRIT needs fewer sources than elements");
+        let (_, vectors) = hermitian_eigen(cov);
+
+        // Ψ = (E1ᴴE1)⁻¹·E1ᴴE2 for the two overlapping subarrays
+        let es = &vectors[..d];
+        let gram = |a: usize, b: usize| -> Vec<Vec<Complex64>> {
+            (0..d)
+                .map(|i| {
+                    (0..d)
+                        .map(|j| (0..m - 1).map(|k| es[i][k + a].conj() * es[j][k + b]).sum())
+                        .collect()
+                })
+                .collect()
+        };
+        let psi = multiply(&invert(&gram(0, 0))?, &gram(0, 1));
+
+        // Eigenvalues of Ψ are the phase steps exp(j·2π·d·sin θ)
+        Some(
+            polynomial_roots(&characteristic_polynomial(&psi))
+                .iter()
+                .filter_map(|z| self.spacing_to_azimuth(z.arg(), spacing))
+                .collect(),
+        )
+    }
+}
+
+/// `aᴴ·R·a` (real for Hermitian `R`)
+fn quadratic_form(r: &[Vec<Complex64>], a: &[Complex64]) -> f64 {
+    r.iter()
+        .enumerate()
+        .map(|(i, row)| a[i].conj() * row.iter().zip(a).map(|(v, x)| v * x).sum::<Complex64>())
+        .sum::<Complex64>()
+        .re
+}
+
+/// `eᴴ·a`
+fn inner(e: &[Complex64], a: &[Complex64]) -> Complex64 {
+    e.iter().zip(a).map(|(x, y)| x.conj() * y).sum()
+}
+
+fn multiply(a: &[Vec<Complex64>], b: &[Vec<Complex64>]) -> Vec<Vec<Complex64>> {
+    let n = b.first().map(|r| r.len()).unwrap_or(0);
+    a.iter()
+        .map(|row| (0..n).map(|j| row.iter().enumerate().map(|(k, v)| v * b[k][j]).sum()).collect())
+        .collect()
+}
+
+/// Gauss-Jordan inverse with partial pivoting; `None` if singular
+fn invert(a: &[Vec<Complex64>]) -> Option<Vec<Vec<Complex64>>> {
+    let n = a.len();
+    let mut work: Vec<Vec<Complex64>> = a
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut r = row.clone();
+            r.extend((0..n).map(|j| Complex64::new(if i == j { 1.0 } else { 0.0 }, 0.0)));
+            r
+        })
+        .collect();
+
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&x, &y| work[x][col].norm().total_cmp(&work[y][col].norm()))?;
+        if work[pivot][col].norm() < 1e-300 {
+            return None;
+        }
+        work.swap(col, pivot);
+        let p = work[col][col];
+        for v in work[col].iter_mut() {
+            *v /= p;
+        }
+        let pivot_row = work[col].clone();
+        for (row, values) in work.iter_mut().enumerate() {
+            let factor = values[col];
+            if row != col && factor.norm() > 0.0 {
+                for (v, p) in values.iter_mut().zip(&pivot_row) {
+                    *v -= factor * p;
+                }
+            }
+        }
+    }
+    Some(work.into_iter().map(|r| r[n..].to_vec()).collect())
+}
+
+/// Eigen-decomposition of a Hermitian matrix, strongest eigenvalue first
+///
+/// Uses the real embedding `[[Re, -Im], [Im, Re]]`, in which every complex
+/// eigenpair appears twice; one complex vector is kept per pair.
+fn hermitian_eigen(cov: &[Vec<Complex64>]) -> (Vec<f64>, Vec<Vec<Complex64>>) {
+    let m = cov.len();
+    let mut real = vec![vec![0.0; 2 * m]; 2 * m];
+    for i in 0..m {
+        for j in 0..m {
+            real[i][j] = cov[i][j].re;
+            real[i + m][j + m] = cov[i][j].re;
+            real[i][j + m] = -cov[i][j].im;
+            real[i + m][j] = cov[i][j].im;
+        }
+    }
+    let (values, vectors) = symmetric_eigen(real);
+    let mut order: Vec<usize> = (0..2 * m).collect();
+    order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+
+    let mut kept_values = Vec::with_capacity(m);
+    let mut kept: Vec<Vec<Complex64>> = Vec::with_capacity(m);
+    for i in order {
+        let mut v: Vec<Complex64> = (0..m).map(|k| Complex64::new(vectors[i][k], vectors[i][k + m])).collect();
+        // Drop the partner j·v of a vector already kept
+        for u in &kept {
+            let projection = inner(u, &v);
+            for (x, y) in v.iter_mut().zip(u) {
+                *x -= projection * y;
+            }
+        }
+        let norm = v.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+        if norm > 0.5 {
+            kept.push(v.iter().map(|x| x / norm).collect());
+            kept_values.push(values[i]);
+        }
+        if kept.len() == m {
+            break;
+        }
+    }
+    (kept_values, kept)
+}
+
+/// Monic characteristic polynomial (highest power first) by Faddeev-LeVerrier
+fn characteristic_polynomial(a: &[Vec<Complex64>]) -> Vec<Complex64> {
+    let n = a.len();
+    let zero = Complex64::new(0.0, 0.0);
+    let mut coefficients = vec![Complex64::new(1.0, 0.0)];
+    let mut mk = vec![vec![zero; n]; n];
+    for k in 1..=n {
+        let c_prev = *coefficients.last().unwrap();
+        for (i, row) in mk.iter_mut().enumerate() {
+            row[i] += c_prev;
+        }
+        mk = multiply(a, &mk);
+        let trace: Complex64 = (0..n).map(|i| mk[i][i]).sum();
+        coefficients.push(-trace / k as f64);
+    }
+    coefficients
+}
+
+/// Roots of a polynomial (highest power first) by Durand-Kerner iteration
+fn polynomial_roots(coefficients: &[Complex64]) -> Vec<Complex64> {
+    let start = coefficients.iter().position(|c| c.norm() > 1e-14).unwrap_or(coefficients.len());
+    let lead = match coefficients.get(start) {
+        Some(c) => *c,
+        None => return Vec::new(),
+    };
+    let monic: Vec<Complex64> = coefficients[start..].iter().map(|c| c / lead).collect();
+    let degree = monic.len() - 1;
+    let eval = |z: Complex64| monic.iter().fold(Complex64::new(0.0, 0.0), |acc, c| acc * z + c);
+
+    let seed = Complex64::new(0.4, 0.9);
+    let mut roots: Vec<Complex64> = (0..degree).map(|k| seed.powu(k as u32)).collect();
+    for _ in 0..2000 {
+        let mut shift = 0.0f64;
+        for i in 0..degree {
+            let denom: Complex64 = (0..degree)
+                .filter(|&j| j != i)
+                .map(|j| roots[i] - roots[j])
+                .fold(Complex64::new(1.0, 0.0), |acc, d| acc * d);
+            if denom.norm() < 1e-300 {
+                continue;
+            }
+            let step = eval(roots[i]) / denom;
+            roots[i] -= step;
+            shift = shift.max(step.norm());
+        }
+        if shift < 1e-13 {
+            break;
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use rand::rngs::StdRng;
+    use rand_distr::StandardNormal;
+
+    /// Independent random-phase sources plus white noise at `snr_db`
+    fn simulate(array: &SensorArray, bearings: &[f64], snr_db: f64, snapshots: usize, seed: u64) -> Vec<Vec<IQSample>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let sigma = (10f64.powf(-snr_db / 10.0) / 2.0).sqrt();
+        let steering: Vec<Vec<IQSample>> = bearings.iter().map(|&b| array.steering_vector(b, 0.0)).collect();
+        let mut channels = vec![Vec::with_capacity(snapshots); array.len()];
+        for _ in 0..snapshots {
+            let symbols: Vec<IQSample> = bearings
+                .iter()
+                .map(|_| IQSample::from_polar(1.0, rng.gen::<f64>() * 2.0 * PI))
+                .collect();
+            for (k, channel) in channels.iter_mut().enumerate() {
+                let noise = IQSample::new(rng.sample(StandardNormal), rng.sample(StandardNormal)) * sigma;
+                channel.push(steering.iter().zip(&symbols).map(|(a, s)| a[k] * s).sum::<IQSample>() + noise);
+            }
+        }
+        channels
+    }
+
+    #[test]
+    fn test_ula_methods_resolve_two_sources() {
+        let array = SensorArray::ula(8, 0.5);
+        let channels = simulate(&array, &[-20.0, 12.0], 15.0, 200, 1);
+        assert_eq!(estimate_num_sources(&covariance(&channels), 200), 2);
+
+        let estimator = DoaEstimator::new(array).with_sources(2);
+        for method in [
+            DoaMethod::Bartlett,
+            DoaMethod::Capon,
+            DoaMethod::Music,
+            DoaMethod::RootMusic,
+            DoaMethod::Esprit,
+        ] {
+            let mut bearings = estimator.estimate(method, &channels).unwrap();
+            bearings.sort_by(|a, b| a.total_cmp(b));
+            assert_eq!(bearings.len(), 2, "{}: {:?}", method.name(), bearings);
+            assert!((bearings[0] + 20.0).abs() < 1.0, "{}: {:?}", method.name(), bearings);
+            assert!((bearings[1] - 12.0).abs() < 1.0, "{}: {:?}", method.name(), bearings);
+        }
+    }
+
+    #[test]
+    fn test_uca_full_circle_and_interferometer() {
+        let array = SensorArray::uca(6, 0.5);
+        let estimator = DoaEstimator::new(array.clone()).with_resolution(0.5);
+        // Grid-free methods need a ULA
+        assert!(estimator.estimate(DoaMethod::Esprit, &simulate(&array, &[0.0], 10.0, 8, 2)).is_none());
+
+        for bearing in [-135.0, -30.0, 70.0, 160.0] {
+            let channels = simulate(&array, &[bearing], 10.0, 100, 3);
+            for method in [DoaMethod::Bartlett, DoaMethod::Capon, DoaMethod::Music, DoaMethod::Interferometer] {
+                let estimate = estimator.estimate(method, &channels).unwrap()[0];
+                assert!((estimate - bearing).abs() < 2.0, "{} {}: {}", method.name(), bearing, estimate);
+            }
+        }
+    }
+
+    #[test]
+    fn test_calibration_removes_channel_errors() {
+        let array = SensorArray::uca(5, 0.45);
+        let errors: Vec<Complex64> = [0.0, 0.9, -1.7, 2.4, 0.5]
+            .iter()
+            .zip([1.0, 0.8, 1.2, 0.9, 1.1])
+            .map(|(&phase, gain)| Complex64::from_polar(gain, phase))
+            .collect();
+        let distort = |channels: Vec<Vec<IQSample>>| -> Vec<Vec<IQSample>> {
+            channels
+                .into_iter()
+                .zip(&errors)
+                .map(|(c, g)| c.iter().map(|s| s * g).collect())
+                .collect()
+        };
+
+        let reference = distort(simulate(&array, &[40.0], 20.0, 200, 4));
+        let calibration = ArrayCalibration::from_reference(&reference, &array, 40.0, 0.0);
+        for (g, e) in calibration.gains().iter().zip(&errors) {
+            assert!((g - e / errors[0]).norm() < 0.05, "{:?}", calibration.gains());
+        }
+
+        let capture = distort(simulate(&array, &[-100.0], 15.0, 200, 5));
+        let uncalibrated = DoaEstimator::new(array.clone()).estimate(DoaMethod::Music, &capture).unwrap()[0];
+        let calibrated = DoaEstimator::new(array)
+            .with_calibration(calibration)
+            .estimate(DoaMethod::Music, &capture)
+            .unwrap()[0];
+        assert!((uncalibrated + 100.0).abs() > 3.0, "{}", uncalibrated);
+        assert!((calibrated + 100.0).abs() < 1.0, "{}", calibrated);
+    }
+}
@@ -10,6 +10,12 @@
 //! - **Waterfall Display**: Time-frequency spectrogram with PNG/ASCII output
 //! - **Signal Statistics**: Power, SNR, PAPR, DC offset, bandwidth estimation
 //! - **Peak Detection**: Find spectral peaks above threshold
+//! - **Signal Detection**: Segment wideband captures into emissions and channelize them
+//! - **Modulation Classification**: Blind symbol rate, bandwidth and modulation family estimation
+//! - **Cyclostationary Analysis**: FAM/SSCA spectral correlation and feature detection
+//! - **Direction Finding**: Bartlett, Capon, MUSIC, root-MUSIC, ESPRIT and interferometry for arrays
+//! - **Modulation Quality**: EVM, MER, IQ impairments and eye diagrams against a reference waveform
+//! - **Compliance**: Spectrum masks, occupied bandwidth, ACPR, power and duty cycle against regulatory limits
 //!
 //! ## Example
 //!
@@ -29,12 +35,27 @@
 //! println!("Mean power: {:.2} dBFS", stats.mean_power_dbfs);
 //! ```
 
+pub mod classify;
+pub mod compliance;
+pub mod cyclostationary;
+pub mod detect;
+pub mod doa;
 pub mod peaks;
+pub mod quality;
 pub mod spectrum;
 pub mod statistics;
 pub mod waterfall;
 
+pub use classify::{Classification, Hypothesis, ModulationClassifier, ModulationFamily, SignalFeatures};
+pub use compliance::{ComplianceCheck, ComplianceChecker, ComplianceReport, LimitReference, MaskSegment, SpectrumMask};
+pub use cyclostationary::{
+    cyclic_autocorrelation, CyclicFeature, CyclicSpectrumAnalyzer, CyclostationaryDetector, DetectabilityPoint,
+    ScfMethod, ScfResult,
+};
+pub use detect::{extract, Detection, EnergyDetector};
+pub use doa::{covariance, estimate_num_sources, ArrayCalibration, DoaEstimator, DoaMethod, SensorArray};
 pub use peaks::{PeakFinder, SpectralPeak};
+pub use quality::{EyeDiagram, QualityAnalyzer, QualityReport};
 pub use spectrum::{SpectrumAnalyzer, SpectrumResult, WindowFunction};
 pub use statistics::{IQImbalance, SignalStats};
 pub use waterfall::{Colormap, WaterfallGenerator, WaterfallResult};
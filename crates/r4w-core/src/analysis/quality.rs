@@ -0,0 +1,699 @@
+//! Modulation Quality
+//!
+//! Standards-style transmitter measurements for linearly modulated
+//! waveforms: EVM, MER, magnitude and phase error, frequency error, IQ
+//! origin offset, quadrature error and gain imbalance, plus eye-diagram
+//! traces with eye opening and zero-crossing jitter.
+//!
+//! ## Measurement Chain
+//!
+//! ```text
+//! capture → integrate-and-dump (1 symbol) → symbol timing (max strobe energy)
+//!         → M-th power frequency/phase estimate → decisions
+//!         → LS fit y = g·s + c (gain, phase, origin offset), decision-directed
+//!         → error vectors e = (y - c)/g - s
+//! ```
+//!
+//! EVM is normalised to the average power of the reference constellation,
+//! after removing frequency error, carrier phase, gain and origin offset
+//! (as in most transmitter test specifications). IQ imbalance is reported
+//! but not removed.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use r4w_core::analysis::QualityAnalyzer;
+//! use r4w_core::waveform::WaveformFactory;
+//!
+//! let qpsk = WaveformFactory::create("QPSK", 16_000.0).unwrap();
+//! let data: Vec<u8> = (0..64u32).map(|i| (i.wrapping_mul(2654435761) >> 13) as u8).collect();
+//! let capture = qpsk.modulate(&data);
+//!
+//! let report = QualityAnalyzer::for_waveform(qpsk.as_ref()).unwrap().measure(&capture).unwrap();
+//! assert!(report.evm_rms_percent < 0.1);
+//! ```
+
+use crate::types::IQSample;
+use crate::waveform::Waveform;
+use std::f64::consts::PI;
+
+/// Eye-diagram traces from the matched-filter output
+#[derive(Debug, Clone)]
+pub struct EyeDiagram {
+    /// Samples per symbol (unit interval)
+    pub samples_per_symbol: usize,
+    /// Traces of two symbols each, centred on a symbol decision instant
+    /// (index `samples_per_symbol`), corrected and normalised like the symbols
+    pub traces: Vec<Vec<IQSample>>,
+}
+
+impl EyeDiagram {
+    /// Format as CSV: one row per trace sample (`trace,index,time_ui,i,q`)
+    pub fn to_csv(&self) -> String {
+        let mut output = String::from("trace,index,time_ui,i,q\n");
+        let sps = self.samples_per_symbol as f64;
+        for (t, trace) in self.traces.iter().enumerate() {
+            for (n, s) in trace.iter().enumerate() {
+                output.push_str(&format!(
+                    "{},{},{:.4},{:.6},{:.6}\n",
+                    t,
+                    n,
+                    n as f64 / sps - 1.0,
+                    s.re,
+                    s.im
+                ));
+            }
+        }
+        output
+    }
+}
+
+/// Result of a modulation quality measurement
+#[derive(Debug, Clone)]
+pub struct QualityReport {
+    /// Number of symbols measured
+    pub num_symbols: usize,
+    /// Sample rate (Hz)
+    pub sample_rate: f64,
+    /// Symbol rate (symbols/s)
+    pub symbol_rate: f64,
+    /// RMS error vector magnitude (% of average constellation power)
+    pub evm_rms_percent: f64,
+    /// Peak error vector magnitude (%)
+    pub evm_peak_percent: f64,
+    /// Symbol index of the peak EVM
+    pub evm_peak_symbol: usize,
+    /// EVM of every symbol (%)
+    pub evm_per_symbol: Vec<f64>,
+    /// Modulation error ratio (dB)
+    pub mer_db: f64,
+    /// RMS magnitude error (%)
+    pub magnitude_error_percent: f64,
+    /// RMS phase error (degrees)
+    pub phase_error_deg: f64,
+    /// Carrier frequency error (Hz)
+    pub frequency_error_hz: f64,
+    /// Carrier phase offset removed before measuring (degrees)
+    pub phase_offset_deg: f64,
+    /// IQ origin offset relative to average symbol power (dB)
+    pub origin_offset_db: f64,
+    /// I/Q gain imbalance (dB), if the constellation uses both axes
+    pub gain_imbalance_db: Option<f64>,
+    /// Quadrature error: deviation of the I/Q axes from 90° (degrees)
+    pub quadrature_error_deg: Option<f64>,
+    /// Vertical eye opening at the decision instant (0 = closed, 1 = ideal)
+    pub eye_opening: f64,
+    /// RMS zero-crossing jitter (unit intervals)
+    pub jitter_rms_ui: Option<f64>,
+    /// Peak-to-peak zero-crossing jitter (unit intervals)
+    pub jitter_pp_ui: Option<f64>,
+    /// Measured symbols after correction, normalised to the reference
+    pub symbols: Vec<IQSample>,
+    /// Eye-diagram traces
+    pub eye: EyeDiagram,
+}
+
+impl QualityReport {
+    /// Format as human-readable text
+    pub fn to_text(&self) -> String {
+        let optional = |v: Option<f64>, unit: &str| match v {
+            Some(v) => format!("{:.3} {}", v, unit),
+            None => "-".to_string(),
+        };
+
+        let mut output = String::new();
+        output.push_str("Modulation Quality\n");
+        output.push_str(&"═".repeat(50));
+        output.push('\n');
+        output.push_str(&format!("Symbols:           {}\n", self.num_symbols));
+        output.push_str(&format!("Symbol Rate:       {:.1} sym/s\n", self.symbol_rate));
+        output.push_str(&format!("EVM (RMS):         {:.3} %\n", self.evm_rms_percent));
+        output.push_str(&format!(
+            "EVM (Peak):        {:.3} % (symbol {})\n",
+            self.evm_peak_percent, self.evm_peak_symbol
+        ));
+        output.push_str(&format!("MER:               {:.2} dB\n", self.mer_db));
+        output.push_str(&format!("Magnitude Error:   {:.3} %\n", self.magnitude_error_percent));
+        output.push_str(&format!("Phase Error:       {:.3} deg\n", self.phase_error_deg));
+        output.push_str(&format!("Frequency Error:   {:.3} Hz\n", self.frequency_error_hz));
+        output.push_str(&format!("Phase Offset:      {:.3} deg\n", self.phase_offset_deg));
+        output.push_str(&format!("IQ Origin Offset:  {:.2} dB\n", self.origin_offset_db));
+        output.push_str(&format!("Gain Imbalance:    {}\n", optional(self.gain_imbalance_db, "dB")));
+        output.push_str(&format!("Quadrature Error:  {}\n", optional(self.quadrature_error_deg, "deg")));
+        output.push_str(&format!("Eye Opening:       {:.1} %\n", self.eye_opening * 100.0));
+        output.push_str(&format!("Jitter (RMS):      {}\n", optional(self.jitter_rms_ui, "UI")));
+        output.push_str(&format!("Jitter (p-p):      {}\n", optional(self.jitter_pp_ui, "UI")));
+        output
+    }
+
+    /// Format as JSON, including per-symbol EVM and eye traces
+    pub fn to_json(&self) -> String {
+        let optional = |v: Option<f64>| v.map(|v| format!("{:.6}", v)).unwrap_or_else(|| "null".to_string());
+        let list = |values: &mut dyn Iterator<Item = f64>| -> String {
+            values.map(|v| format!("{:.6}", v)).collect::<Vec<_>>().join(", ")
+        };
+        let traces = |part: fn(&IQSample) -> f64| -> String {
+            self.eye
+                .traces
+                .iter()
+                .map(|t| format!("[{}]", list(&mut t.iter().map(part))))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        format!(
+            r#"{{
+  "num_symbols": {},
+  "sample_rate": {:.0},
+  "symbol_rate": {:.6},
+  "evm_rms_percent": {:.6},
+  "evm_peak_percent": {:.6},
+  "evm_peak_symbol": {},
+  "mer_db": {:.6},
+  "magnitude_error_percent": {:.6},
+  "phase_error_deg": {:.6},
+  "frequency_error_hz": {:.6},
+  "phase_offset_deg": {:.6},
+  "origin_offset_db": {:.6},
+  "gain_imbalance_db": {},
+  "quadrature_error_deg": {},
+  "eye_opening": {:.6},
+  "jitter_rms_ui": {},
+  "jitter_pp_ui": {},
+  "evm_per_symbol": [{}],
+  "eye": {{
+    "samples_per_symbol": {},
+    "i": [{}],
+    "q": [{}]
+  }}
+}}"#,
+            self.num_symbols,
+            self.sample_rate,
+            self.symbol_rate,
+            self.evm_rms_percent,
+            self.evm_peak_percent,
+            self.evm_peak_symbol,
+            self.mer_db,
+            self.magnitude_error_percent,
+            self.phase_error_deg,
+            self.frequency_error_hz,
+            self.phase_offset_deg,
+            self.origin_offset_db,
+            optional(self.gain_imbalance_db),
+            optional(self.quadrature_error_deg),
+            self.eye_opening,
+            optional(self.jitter_rms_ui),
+            optional(self.jitter_pp_ui),
+            list(&mut self.evm_per_symbol.iter().cloned()),
+            self.eye.samples_per_symbol,
+            traces(|s| s.re),
+            traces(|s| s.im),
+        )
+    }
+}
+
+/// Modulation quality analyzer for a known constellation
+#[derive(Debug, Clone)]
+pub struct QualityAnalyzer {
+    /// Reference constellation, normalised to unit average power
+    constellation: Vec<IQSample>,
+    samples_per_symbol: usize,
+    sample_rate: f64,
+    eye_traces: usize,
+}
+
+impl QualityAnalyzer {
+    /// Create an analyzer for rectangular symbols of `samples_per_symbol`
+    pub fn new(constellation: Vec<IQSample>, samples_per_symbol: usize, sample_rate: f64) -> Self {
+        assert!(!constellation.is_empty(), "constellation needs at least one point");
+        let power = constellation.iter().map(|c| c.norm_sqr()).sum::<f64>() / constellation.len() as f64;
+        let scale = 1.0 / power.max(1e-30).sqrt();
+        Self {
+            constellation: constellation.iter().map(|c| c * scale).collect(),
+            samples_per_symbol: samples_per_symbol.max(1),
+            sample_rate,
+            eye_traces: 64,
+        }
+    }
+
+    /// Create an analyzer from a waveform's constellation and symbol timing
+    ///
+    /// Returns `None` for waveforms without a constellation (FSK, analog, ...).
+    pub fn for_waveform(waveform: &dyn Waveform) -> Option<Self> {
+        let constellation = waveform.get_visualization(&[]).constellation;
+        if constellation.is_empty() {
+            return None;
+        }
+        Some(Self::new(
+            constellation,
+            waveform.samples_per_symbol(),
+            waveform.common_params().sample_rate,
+        ))
+    }
+
+    /// Set the maximum number of eye-diagram traces kept
+    pub fn with_eye_traces(mut self, traces: usize) -> Self {
+        self.eye_traces = traces;
+        self
+    }
+
+    /// Measure a capture; `None` if it holds fewer than 8 symbols
+    pub fn measure(&self, samples: &[IQSample]) -> Option<QualityReport> {
+        let sps = self.samples_per_symbol;
+        if samples.len() < 8 * sps + sps {
+            return None;
+        }
+        let symbol_period = sps as f64 / self.sample_rate;
+
+        // Symbol timing: integrate-and-dump phase with the most strobe energy
+        let filtered = integrate(samples, sps);
+        let offset = (0..sps)
+            .max_by(|&a, &b| strobe_energy(&filtered, a, sps).total_cmp(&strobe_energy(&filtered, b, sps)))
+            .unwrap_or(0);
+
+        // Coarse frequency and phase from the M-th power of the strobes
+        let m = rotational_symmetry(&self.constellation);
+        let strobes: Vec<IQSample> = filtered[offset..].iter().step_by(sps).cloned().collect();
+        let powered: Vec<IQSample> = strobes.iter().map(|y| y.powu(m as u32)).collect();
+        let rotation = |lag: usize| -> IQSample {
+            powered
+                .iter()
+                .zip(&powered[lag..])
+                .map(|(a, b)| b * a.conj())
+                .sum()
+        };
+        let mut frequency = rotation(1).arg() / (2.0 * PI * m as f64 * symbol_period);
+        // Longer lags refine the estimate within the ambiguity left by the last
+        let mut lag = 8;
+        while lag * 4 <= powered.len() {
+            let step = 2.0 * PI * m as f64 * lag as f64 * symbol_period;
+            let residual = rotation(lag) * IQSample::from_polar(1.0, -step * frequency);
+            frequency += residual.arg() / step;
+            lag *= 8;
+        }
+
+        let mut fit = Fit::default();
+        let mut y = Vec::new();
+        for pass in 0..2 {
+            y = strobes
+                .iter()
+                .enumerate()
+                .map(|(k, s)| s * IQSample::from_polar(1.0, -2.0 * PI * frequency * k as f64 * symbol_period))
+                .collect();
+
+            if pass == 0 {
+                let reference: IQSample = self.constellation.iter().map(|c| c.powu(m as u32)).sum();
+                let measured: IQSample = y.iter().map(|s| s.powu(m as u32)).sum();
+                let phase = if reference.norm() > 1e-9 {
+                    (measured * reference.conj()).arg() / m as f64
+                } else {
+                    0.0
+                };
+                let rms = (y.iter().map(|s| s.norm_sqr()).sum::<f64>() / y.len() as f64).sqrt();
+                fit = Fit {
+                    gain: IQSample::from_polar(rms.max(1e-30), phase),
+                    origin: IQSample::new(0.0, 0.0),
+                };
+            }
+
+            for _ in 0..4 {
+                let decisions: Vec<IQSample> = y.iter().map(|s| self.decide(fit.normalise(*s))).collect();
+                fit = Fit::least_squares(&y, &decisions).unwrap_or(fit);
+            }
+
+            // Residual frequency from a least-squares fit of the
+            // decision-directed phase against symbol index
+            let mut unwrapped = 0.0;
+            let mut previous = 0.0;
+            let points: Vec<(f64, f64)> = y
+                .iter()
+                .enumerate()
+                .filter_map(|(k, s)| {
+                    let normalised = fit.normalise(*s);
+                    let decision = self.decide(normalised);
+                    (decision.norm() > 1e-9).then(|| (k as f64, (normalised * decision.conj()).arg()))
+                })
+                .map(|(k, phase)| {
+                    unwrapped += (IQSample::from_polar(1.0, phase - previous)).arg();
+                    previous = phase;
+                    (k, unwrapped)
+                })
+                .collect();
+            frequency += phase_slope(&points) / (2.0 * PI * symbol_period);
+        }
+
+        let measured: Vec<IQSample> = y.iter().map(|s| fit.normalise(*s)).collect();
+        let decisions: Vec<IQSample> = measured.iter().map(|s| self.decide(*s)).collect();
+        let reference_power =
+            self.constellation.iter().map(|c| c.norm_sqr()).sum::<f64>() / self.constellation.len() as f64;
+        let reference_rms = reference_power.sqrt();
+        let n = measured.len() as f64;
+
+        let errors: Vec<f64> = measured.iter().zip(&decisions).map(|(s, d)| (s - d).norm()).collect();
+        let evm_per_symbol: Vec<f64> = errors.iter().map(|e| 100.0 * e / reference_rms).collect();
+        let (evm_peak_symbol, evm_peak_percent) = evm_per_symbol
+            .iter()
+            .cloned()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((0, 0.0));
+        let error_power = errors.iter().map(|e| e * e).sum::<f64>() / n;
+        let symbol_power = decisions.iter().map(|d| d.norm_sqr()).sum::<f64>() / n;
+
+        let magnitude_error = (measured
+            .iter()
+            .zip(&decisions)
+            .map(|(s, d)| (s.norm() - d.norm()).powi(2))
+            .sum::<f64>()
+            / n)
+            .sqrt();
+        let phases: Vec<f64> = measured
+            .iter()
+            .zip(&decisions)
+            .filter(|(_, d)| d.norm() > 1e-9)
+            .map(|(s, d)| (s * d.conj()).arg())
+            .collect();
+        let phase_error = (phases.iter().map(|p| p * p).sum::<f64>() / phases.len().max(1) as f64).sqrt();
+
+        let (gain_imbalance_db, quadrature_error_deg) = iq_imbalance(&measured, &decisions).unzip();
+        let eye = self.eye(samples, &fit, frequency, offset);
+        let (jitter_rms_ui, jitter_pp_ui) = self.jitter(&eye, &decisions).unzip();
+
+        Some(QualityReport {
+            num_symbols: measured.len(),
+            sample_rate: self.sample_rate,
+            symbol_rate: 1.0 / symbol_period,
+            evm_rms_percent: 100.0 * error_power.sqrt() / reference_rms,
+            evm_peak_percent,
+            evm_peak_symbol,
+            evm_per_symbol,
+            mer_db: 10.0 * (symbol_power / error_power.max(1e-30)).log10(),
+            magnitude_error_percent: 100.0 * magnitude_error / reference_rms,
+            phase_error_deg: phase_error.to_degrees(),
+            frequency_error_hz: frequency,
+            phase_offset_deg: fit.gain.arg().to_degrees(),
+            origin_offset_db: 20.0 * (fit.origin.norm() / (fit.gain.norm() * reference_rms)).max(1e-10).log10(),
+            gain_imbalance_db,
+            quadrature_error_deg,
+            eye_opening: self.eye_opening(&measured, &decisions),
+            jitter_rms_ui,
+            jitter_pp_ui,
+            symbols: measured,
+            eye,
+        })
+    }
+
+    /// Nearest reference point
+    fn decide(&self, s: IQSample) -> IQSample {
+        *self
+            .constellation
+            .iter()
+            .min_by(|a, b| (s - **a).norm_sqr().total_cmp(&(s - **b).norm_sqr()))
+            .unwrap()
+    }
+
+    /// Corrected matched-filter traces of two symbols around each strobe
+    fn eye(&self, samples: &[IQSample], fit: &Fit, frequency: f64, offset: usize) -> EyeDiagram {
+        let sps = self.samples_per_symbol;
+        // Strobe k sits at sample offset + k·sps; the frequency correction
+        // was applied after integration, referenced to strobe 0
+        let filtered: Vec<IQSample> = integrate(samples, sps)
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let t = (i as f64 - offset as f64) / self.sample_rate;
+                fit.normalise(s * IQSample::from_polar(1.0, -2.0 * PI * frequency * t))
+            })
+            .collect();
+
+        let traces = (1..)
+            .map(|k| offset + k * sps)
+            .take_while(|&centre| centre + sps < filtered.len())
+            .take(self.eye_traces)
+            .map(|centre| filtered[centre - sps..=centre + sps].to_vec())
+            .collect();
+        EyeDiagram {
+            samples_per_symbol: sps,
+            traces,
+        }
+    }
+
+    /// Worst vertical opening over adjacent decision levels on I and Q
+    fn eye_opening(&self, measured: &[IQSample], decisions: &[IQSample]) -> f64 {
+        let mut opening = 1.0f64;
+        for part in AXES {
+            let mut levels: Vec<f64> = self.constellation.iter().map(part).collect();
+            levels.sort_by(|a, b| a.total_cmp(b));
+            levels.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+            for pair in levels.windows(2) {
+                let values = |level: f64| {
+                    measured
+                        .iter()
+                        .zip(decisions)
+                        .filter(move |(_, d)| (part(d) - level).abs() < 1e-9)
+                        .map(move |(s, _)| part(s))
+                };
+                let lower = values(pair[0]).fold(f64::NEG_INFINITY, f64::max);
+                let upper = values(pair[1]).fold(f64::INFINITY, f64::min);
+                if lower.is_finite() && upper.is_finite() {
+                    opening = opening.min((upper - lower) / (pair[1] - pair[0]));
+                }
+            }
+        }
+        opening.clamp(0.0, 1.0)
+    }
+
+    /// Zero crossings between opposite symbols, relative to the nominal
+    /// symbol boundary
+    fn jitter(&self, eye: &EyeDiagram, decisions: &[IQSample]) -> Option<(f64, f64)> {
+        let sps = eye.samples_per_symbol as f64;
+        let mut offsets = Vec::new();
+        // Trace t spans strobes t+1 (index sps) and t+2 (index 2·sps)
+        for (t, trace) in eye.traces.iter().enumerate() {
+            let (a, b) = match (decisions.get(t + 1), decisions.get(t + 2)) {
+                (Some(a), Some(b)) => (a, b),
+                _ => break,
+            };
+            for part in AXES {
+                // Symmetric transitions cross zero mid-symbol when ideal
+                if part(a) * part(b) >= 0.0 || (part(a) + part(b)).abs() > 1e-6 {
+                    continue;
+                }
+                let segment = &trace[eye.samples_per_symbol..];
+                if let Some(i) = segment.windows(2).position(|w| part(&w[0]) * part(&w[1]) <= 0.0) {
+                    let (y0, y1) = (part(&segment[i]), part(&segment[i + 1]));
+                    let frac = if y0 != y1 { y0 / (y0 - y1) } else { 0.5 };
+                    offsets.push((i as f64 + frac) / sps - 0.5);
+                }
+            }
+        }
+        if offsets.is_empty() {
+            return None;
+        }
+        let mean = offsets.iter().sum::<f64>() / offsets.len() as f64;
+        let rms = (offsets.iter().map(|o| (o - mean).powi(2)).sum::<f64>() / offsets.len() as f64).sqrt();
+        let max = offsets.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let min = offsets.iter().cloned().fold(f64::INFINITY, f64::min);
+        Some((rms, max - min))
+    }
+}
+
+/// Complex gain and origin offset: `y = gain·s + origin`
+#[derive(Debug, Clone, Copy)]
+struct Fit {
+    gain: IQSample,
+    origin: IQSample,
+}
+
+impl Default for Fit {
+    fn default() -> Self {
+        Self {
+            gain: IQSample::new(1.0, 0.0),
+            origin: IQSample::new(0.0, 0.0),
+        }
+    }
+}
+
+impl Fit {
+    fn normalise(&self, y: IQSample) -> IQSample {
+        (y - self.origin) / self.gain
+    }
+
+    fn least_squares(y: &[IQSample], s: &[IQSample]) -> Option<Self> {
+        let n = y.len() as f64;
+        let y_mean: IQSample = y.iter().sum::<IQSample>() / n;
+        let s_mean: IQSample = s.iter().sum::<IQSample>() / n;
+        let cross: IQSample = y.iter().zip(s).map(|(a, b)| (a - y_mean) * (b - s_mean).conj()).sum();
+        let spread: f64 = s.iter().map(|b| (b - s_mean).norm_sqr()).sum();
+        if spread < 1e-12 {
+            return None;
+        }
+        let gain = cross / spread;
+        Some(Self {
+            gain,
+            origin: y_mean - gain * s_mean,
+        })
+    }
+}
+
+/// Least-squares slope of `(x, y)` points
+fn phase_slope(points: &[(f64, f64)]) -> f64 {
+    let n = points.len() as f64;
+    if n < 2.0 {
+        return 0.0;
+    }
+    let x_mean = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let y_mean = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let cross: f64 = points.iter().map(|p| (p.0 - x_mean) * (p.1 - y_mean)).sum();
+    let spread: f64 = points.iter().map(|p| (p.0 - x_mean).powi(2)).sum();
+    if spread > 0.0 { cross / spread } else { 0.0 }
+}
+
+/// Real and imaginary parts, for per-axis measurements
+const AXES: [fn(&IQSample) -> f64; 2] = [|s| s.re, |s| s.im];
+
+/// Integrate-and-dump: mean of `len` samples starting at each index
+fn integrate(samples: &[IQSample], len: usize) -> Vec<IQSample> {
+    if samples.len() < len {
+        return Vec::new();
+    }
+    let mut sum: IQSample = samples[..len].iter().sum();
+    let mut out = Vec::with_capacity(samples.len() - len + 1);
+    out.push(sum / len as f64);
+    for i in len..samples.len() {
+        sum += samples[i] - samples[i - len];
+        out.push(sum / len as f64);
+    }
+    out
+}
+
+fn strobe_energy(filtered: &[IQSample], offset: usize, sps: usize) -> f64 {
+    filtered[offset..].iter().step_by(sps).map(|s| s.norm_sqr()).sum()
+}
+
+/// Largest `m` for which a rotation by 2π/m maps the constellation onto itself
+fn rotational_symmetry(constellation: &[IQSample]) -> usize {
+    (1..=16)
+        .rev()
+        .find(|&m| {
+            let rotation = IQSample::from_polar(1.0, 2.0 * PI / m as f64);
+            constellation
+                .iter()
+                .all(|c| constellation.iter().any(|d| (c * rotation - d).norm() < 1e-6))
+        })
+        .unwrap_or(1)
+}
+
+/// Gain imbalance (dB) and quadrature error (degrees) from a real affine fit
+/// of the measured I and Q onto the reference I and Q
+fn iq_imbalance(measured: &[IQSample], decisions: &[IQSample]) -> Option<(f64, f64)> {
+    let n = measured.len() as f64;
+    let mean = |f: &dyn Fn(usize) -> f64| (0..measured.len()).map(f).sum::<f64>() / n;
+    let (si, sq) = (mean(&|k| decisions[k].re), mean(&|k| decisions[k].im));
+    let (yi, yq) = (mean(&|k| measured[k].re), mean(&|k| measured[k].im));
+
+    // Normal equations on centred data: [a b; b c]·[x; z] = [p; q]
+    let a = mean(&|k| (decisions[k].re - si).powi(2));
+    let b = mean(&|k| (decisions[k].re - si) * (decisions[k].im - sq));
+    let c = mean(&|k| (decisions[k].im - sq).powi(2));
+    let det = a * c - b * b;
+    if a < 1e-9 || c < 1e-9 || det.abs() < 1e-12 {
+        return None;
+    }
+    let solve = |p: f64, q: f64| ((c * p - b * q) / det, (a * q - b * p) / det);
+    let (i_from_i, i_from_q) = solve(
+        mean(&|k| (measured[k].re - yi) * (decisions[k].re - si)),
+        mean(&|k| (measured[k].re - yi) * (decisions[k].im - sq)),
+    );
+    let (q_from_i, q_from_q) = solve(
+        mean(&|k| (measured[k].im - yq) * (decisions[k].re - si)),
+        mean(&|k| (measured[k].im - yq) * (decisions[k].im - sq)),
+    );
+
+    // Images of the reference I and Q axes
+    let axis_i = IQSample::new(i_from_i, q_from_i);
+    let axis_q = IQSample::new(i_from_q, q_from_q);
+    let gain_imbalance = 20.0 * (axis_i.norm() / axis_q.norm().max(1e-30)).log10();
+    let quadrature = ((axis_q * axis_i.conj()).arg() - PI / 2.0).to_degrees();
+    Some((gain_imbalance, quadrature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waveform::WaveformFactory;
+    use rand::prelude::*;
+    use rand::rngs::StdRng;
+    use rand_distr::StandardNormal;
+
+    fn capture(name: &str, fs: f64, bytes: usize, seed: u64) -> (Box<dyn Waveform>, Vec<IQSample>) {
+        let waveform = WaveformFactory::create(name, fs).unwrap();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let data: Vec<u8> = (0..bytes).map(|_| rng.gen()).collect();
+        let samples = waveform.modulate(&data);
+        (waveform, samples)
+    }
+
+    #[test]
+    fn test_clean_qpsk_is_ideal() {
+        let (waveform, samples) = capture("QPSK", 16_000.0, 128, 1);
+        let report = QualityAnalyzer::for_waveform(waveform.as_ref()).unwrap().measure(&samples).unwrap();
+        assert_eq!(report.num_symbols, 512);
+        assert!(report.evm_rms_percent < 1e-6, "{}", report.evm_rms_percent);
+        assert!(report.frequency_error_hz.abs() < 1e-6);
+        assert!(report.quadrature_error_deg.unwrap().abs() < 1e-6);
+        assert!((report.eye_opening - 1.0).abs() < 1e-9);
+        assert!(report.jitter_rms_ui.unwrap() < 1e-9);
+        assert_eq!(report.eye.traces[0].len(), 33);
+    }
+
+    #[test]
+    fn test_16qam_frequency_phase_and_noise() {
+        let fs = 16_000.0;
+        let (waveform, samples) = capture("16QAM", fs, 512, 2);
+        let sps = waveform.samples_per_symbol();
+        let mut rng = StdRng::seed_from_u64(3);
+
+        // 25 dB MER after integrating over the symbol
+        let power = samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / samples.len() as f64;
+        let sigma = (0.25 * power / 10f64.powf(2.5) * sps as f64 / 2.0).sqrt();
+        let impaired: Vec<IQSample> = samples
+            .iter()
+            .enumerate()
+            .map(|(n, s)| {
+                let noise = IQSample::new(rng.sample(StandardNormal), rng.sample(StandardNormal)) * sigma;
+                s * IQSample::from_polar(0.5, 0.3 + 2.0 * PI * 23.0 * n as f64 / fs) + noise
+            })
+            .collect();
+
+        let report = QualityAnalyzer::for_waveform(waveform.as_ref()).unwrap().measure(&impaired).unwrap();
+        assert!((report.frequency_error_hz - 23.0).abs() < 0.5, "{}", report.frequency_error_hz);
+        assert!((report.mer_db - 25.0).abs() < 1.0, "{}", report.mer_db);
+        let expected_evm = 100.0 * 10f64.powf(-25.0 / 20.0);
+        assert!((report.evm_rms_percent - expected_evm).abs() < 0.6, "{}", report.evm_rms_percent);
+        assert!(report.evm_peak_percent > report.evm_rms_percent);
+        assert!(report.eye_opening > 0.3 && report.eye_opening < 1.0);
+        assert!(report.jitter_rms_ui.unwrap() < 0.1, "{:?}", report.jitter_rms_ui);
+    }
+
+    #[test]
+    fn test_iq_impairments_measured() {
+        let (waveform, samples) = capture("QPSK", 16_000.0, 256, 4);
+        // Q axis tilted by 3° and 1 dB weaker, plus a DC offset
+        let skew = 3f64.to_radians();
+        let q_gain = 10f64.powf(-1.0 / 20.0);
+        let impaired: Vec<IQSample> = samples
+            .iter()
+            .map(|s| {
+                IQSample::new(s.re - q_gain * skew.sin() * s.im, q_gain * skew.cos() * s.im)
+                    + IQSample::new(0.05, -0.02)
+            })
+            .collect();
+
+        let report = QualityAnalyzer::for_waveform(waveform.as_ref()).unwrap().measure(&impaired).unwrap();
+        assert!((report.gain_imbalance_db.unwrap() - 1.0).abs() < 0.05, "{:?}", report.gain_imbalance_db);
+        assert!((report.quadrature_error_deg.unwrap() - 3.0).abs() < 0.1, "{:?}", report.quadrature_error_deg);
+        let expected_origin = 20.0 * (0.05f64.hypot(0.02)).log10();
+        assert!((report.origin_offset_db - expected_origin).abs() < 1.0, "{}", report.origin_offset_db);
+    }
+}
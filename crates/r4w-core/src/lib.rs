@@ -61,6 +61,7 @@ pub mod observe;
 pub mod packet;
 pub mod params;
 pub mod plugin;
+pub mod propagation;
 pub mod rt;
 pub mod simd_utils;
 pub mod spreading;
@@ -71,6 +72,7 @@ pub mod synthesizer;
 pub mod time_sync;
 pub mod timing;
 pub mod types;
+pub mod vita49;
 pub mod waveform;
 pub mod whitening;
 
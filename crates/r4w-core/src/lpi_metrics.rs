@@ -221,7 +221,9 @@ pub fn required_processing_gain(
 
 /// Estimate detection probability based on energy detection
 ///
-/// Simplified model for educational purposes
+/// Simplified model for educational purposes. Feature detectors that exploit
+/// cyclostationarity are modelled by
+/// [`CyclostationaryDetector`](crate::analysis::CyclostationaryDetector).
 pub fn detection_probability(
     signal_psd_dbm_hz: f64,
     noise_figure_db: f64,
@@ -38,6 +38,7 @@ use super::telemetry::{DeviceMetrics, EnvironmentMetrics, Telemetry, TelemetryCo
 use super::traits::{MeshError, MeshNetwork, MeshResult, MeshStats};
 use super::wire::{WireHeader, WIRE_HEADER_SIZE};
 use std::time::{Duration, Instant};
+use crate::analysis::SpectrumMask;
 
 /// Meshtastic modem presets
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -141,6 +142,15 @@ impl Region {
             _ => 1.0,          // No limit (check local regulations)
         }
     }
+
+    /// Get the built-in regulatory spectrum mask for the region, if any
+    pub fn spectrum_mask(&self) -> Option<SpectrumMask> {
+        match self {
+            Region::EU | Region::Unset => SpectrumMask::builtin("etsi-868"),
+            Region::US => SpectrumMask::builtin("fcc-15.247"),
+            _ => None,
+        }
+    }
 }
 
 impl Default for Region {
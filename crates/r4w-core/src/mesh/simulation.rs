@@ -4,7 +4,8 @@
 //! without hardware. It models:
 //!
 //! - Multiple nodes with configurable positions
-//! - Radio propagation with path loss
+//! - Radio propagation with path loss (legacy log-distance, or any
+//!   [`PathLossModel`] with receiver thresholds from the preset's link budget)
 //! - Packet collisions and interference
 //! - Network topology changes (node join/leave)
 //! - Message delivery statistics
@@ -31,6 +32,8 @@
 use super::meshtastic::{MeshtasticConfig, MeshtasticNode, ModemPreset, Region};
 use super::packet::NodeId;
 use super::traits::MeshNetwork;
+use crate::params::LoRaParams;
+use crate::propagation::{LinkBudget, LinkReport, PathLossModel};
 use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
 
@@ -82,6 +85,12 @@ pub struct SimConfig {
     pub path_loss_exponent: f64,
     /// Reference distance for path loss (meters)
     pub reference_distance: f64,
+    /// Propagation model (None = log-distance from `path_loss_exponent`)
+    pub path_loss_model: Option<PathLossModel>,
+    /// Antenna gain of every node (dBi)
+    pub antenna_gain_dbi: f64,
+    /// Feedline loss of every node (dB)
+    pub cable_loss_db: f64,
     /// Background noise floor (dBm)
     pub noise_floor_dbm: f64,
     /// Minimum SNR for successful reception (dB)
@@ -108,6 +117,9 @@ impl Default for SimConfig {
             rx_sensitivity_dbm: -130.0,
             path_loss_exponent: 2.8, // Suburban
             reference_distance: 1.0,
+            path_loss_model: None,
+            antenna_gain_dbi: 0.0,
+            cable_loss_db: 0.0,
             noise_floor_dbm: -120.0,
             min_snr_db: -5.0, // LoRa can decode at negative SNR
             modem_preset: ModemPreset::LongFast,
@@ -145,6 +157,46 @@ impl SimConfig {
         self.message_rate = rate;
         self
     }
+
+    /// Set node antenna gain (dBi) and feedline loss (dB)
+    pub fn with_antennas(mut self, gain_dbi: f64, cable_loss_db: f64) -> Self {
+        self.antenna_gain_dbi = gain_dbi;
+        self.cable_loss_db = cable_loss_db;
+        self
+    }
+
+    /// Use a propagation model
+    ///
+    /// Sensitivity, noise floor and minimum SNR then follow the link
+    /// budget of the modem preset, power and antennas the simulation is
+    /// built with.
+    pub fn with_path_loss_model(mut self, model: PathLossModel) -> Self {
+        self.path_loss_model = Some(model);
+        self
+    }
+
+    /// Derive the reception thresholds from the link budget when a
+    /// propagation model is set
+    fn apply_link_budget(&mut self) {
+        if self.path_loss_model.is_none() {
+            return;
+        }
+        let (sf, bw, _) = self.modem_preset.lora_params();
+        let params = LoRaParams::builder().spreading_factor(sf).bandwidth(bw).build();
+        let budget = self.link_budget();
+        self.rx_sensitivity_dbm = budget.sensitivity_dbm;
+        self.noise_floor_dbm = budget.noise_floor_dbm();
+        self.min_snr_db = params.sf.snr_threshold();
+    }
+
+    /// Link budget of a node-to-node link for the modem preset
+    pub fn link_budget(&self) -> LinkBudget {
+        let (sf, bw, _) = self.modem_preset.lora_params();
+        let params = LoRaParams::builder().spreading_factor(sf).bandwidth(bw).build();
+        LinkBudget::for_lora(&params, self.tx_power_dbm)
+            .with_antenna_gains(self.antenna_gain_dbi, self.antenna_gain_dbi)
+            .with_cable_losses(self.cable_loss_db, self.cable_loss_db)
+    }
 }
 
 /// A packet in flight through the simulated channel
@@ -262,6 +314,8 @@ pub struct MeshSimulator {
     stats: SimStats,
     /// Event log for analysis
     event_log: Vec<SimEvent>,
+    /// Per-link shadowing (dB), `node_count × node_count`, symmetric
+    shadowing_db: Vec<f64>,
 }
 
 /// Simulation events for logging
@@ -279,7 +333,8 @@ pub enum SimEvent {
 
 impl MeshSimulator {
     /// Create a new simulator with the given configuration
-    pub fn new(config: SimConfig) -> Self {
+    pub fn new(mut config: SimConfig) -> Self {
+        config.apply_link_budget();
         let mut sim = Self {
             nodes: Vec::with_capacity(config.node_count),
             in_flight: Vec::new(),
@@ -289,6 +344,7 @@ impl MeshSimulator {
             rng_state: config.seed,
             stats: SimStats::default(),
             event_log: Vec::new(),
+            shadowing_db: Vec::new(),
             config,
         };
 
@@ -334,6 +390,23 @@ impl MeshSimulator {
             });
         }
 
+        // One log-normal shadowing value per node pair
+        let n = self.nodes.len();
+        self.shadowing_db = vec![0.0; n * n];
+        let sigma = self.config.path_loss_model.as_ref().map_or(0.0, |m| m.shadowing_sigma_db());
+        if sigma > 0.0 {
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    // Box-Muller
+                    let u1 = self.rand().max(1e-12);
+                    let u2 = self.rand();
+                    let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                    self.shadowing_db[i * n + j] = sigma * z;
+                    self.shadowing_db[j * n + i] = sigma * z;
+                }
+            }
+        }
+
         if self.config.verbose {
             println!("Initialized {} nodes", self.nodes.len());
             for (i, node) in self.nodes.iter().enumerate() {
@@ -356,17 +429,32 @@ impl MeshSimulator {
         (self.rng_state >> 33) as f64 / (1u64 << 31) as f64
     }
 
-    /// Calculate received signal strength between two positions
-    fn calculate_rssi(&self, tx_pos: &NodePosition, rx_pos: &NodePosition) -> f64 {
+    /// Median path loss between two positions (dB)
+    fn median_path_loss_db(&self, tx_pos: &NodePosition, rx_pos: &NodePosition) -> f64 {
+        if let Some(model) = &self.config.path_loss_model {
+            let freq_hz = self.config.region.primary_frequency() as f64;
+            return model.path_loss_db(tx_pos.distance_to(rx_pos), freq_hz);
+        }
+
         let distance = tx_pos.distance_to(rx_pos).max(self.config.reference_distance);
 
         // Log-distance path loss model
         // PL(d) = PL(d0) + 10 * n * log10(d/d0)
         let pl_reference = 20.0 * (4.0 * std::f64::consts::PI * self.config.reference_distance / 0.33).log10();
-        let path_loss = pl_reference
-            + 10.0 * self.config.path_loss_exponent * (distance / self.config.reference_distance).log10();
+        pl_reference + 10.0 * self.config.path_loss_exponent * (distance / self.config.reference_distance).log10()
+    }
+
+    /// Calculate received signal strength between two positions
+    fn calculate_rssi(&self, tx_pos: &NodePosition, rx_pos: &NodePosition) -> f64 {
+        let antennas = 2.0 * (self.config.antenna_gain_dbi - self.config.cable_loss_db);
+        self.config.tx_power_dbm + antennas - self.median_path_loss_db(tx_pos, rx_pos)
+    }
 
-        self.config.tx_power_dbm - path_loss
+    /// Received signal strength between two nodes, including shadowing
+    fn link_rssi(&self, from_idx: usize, to_idx: usize, tx_pos: &NodePosition) -> f64 {
+        let n = self.nodes.len();
+        self.calculate_rssi(tx_pos, &self.nodes[to_idx].position)
+            - self.shadowing_db.get(from_idx * n + to_idx).copied().unwrap_or(0.0)
     }
 
     /// Calculate SNR from RSSI
@@ -527,9 +615,6 @@ impl MeshSimulator {
         let rx_sensitivity = self.config.rx_sensitivity_dbm;
         let min_snr = self.config.min_snr_db;
         let noise_floor = self.config.noise_floor_dbm;
-        let tx_power = self.config.tx_power_dbm;
-        let path_loss_exp = self.config.path_loss_exponent;
-        let ref_dist = self.config.reference_distance;
         let verbose = self.config.verbose;
         let step_count = self.step_count;
 
@@ -538,14 +623,10 @@ impl MeshSimulator {
                 continue; // Don't deliver to self
             }
 
-            let node_pos = self.nodes[i].position;
             let is_transmitting = self.nodes[i].is_transmitting;
 
             // Calculate RSSI using path loss model
-            let distance = packet.source_pos.distance_to(&node_pos).max(ref_dist);
-            let pl_reference = 20.0 * (4.0 * std::f64::consts::PI * ref_dist / 0.33).log10();
-            let path_loss = pl_reference + 10.0 * path_loss_exp * (distance / ref_dist).log10();
-            let rssi = tx_power - path_loss;
+            let rssi = self.link_rssi(packet.source_idx, i, &packet.source_pos);
             let snr = rssi - noise_floor;
 
             // Check if signal is strong enough
@@ -745,6 +826,44 @@ impl MeshSimulator {
         adj
     }
 
+    /// Link budget between two nodes at their current positions
+    ///
+    /// Margin is measured against the configured receiver sensitivity.
+    pub fn link_report(&self, from_idx: usize, to_idx: usize) -> Option<LinkReport> {
+        let tx_pos = self.nodes.get(from_idx)?.position;
+        self.nodes.get(to_idx)?;
+        let rx_power_dbm = self.link_rssi(from_idx, to_idx, &tx_pos);
+        let eirp_dbm = self.config.tx_power_dbm + self.config.antenna_gain_dbi - self.config.cable_loss_db;
+        Some(LinkReport {
+            eirp_dbm,
+            path_loss_db: eirp_dbm + self.config.antenna_gain_dbi - self.config.cable_loss_db - rx_power_dbm,
+            rx_power_dbm,
+            noise_floor_dbm: self.config.noise_floor_dbm,
+            snr_db: rx_power_dbm - self.config.noise_floor_dbm,
+            sensitivity_dbm: self.config.rx_sensitivity_dbm,
+            margin_db: rx_power_dbm - self.config.rx_sensitivity_dbm,
+        })
+    }
+
+    /// Links that close on propagation alone (adjacency list)
+    ///
+    /// Unlike [`topology`](Self::topology), this does not depend on which
+    /// packets have been exchanged so far.
+    pub fn link_topology(&self) -> Vec<Vec<usize>> {
+        (0..self.nodes.len())
+            .map(|i| {
+                (0..self.nodes.len())
+                    .filter(|&j| {
+                        j != i
+                            && self
+                                .link_report(j, i)
+                                .is_some_and(|r| r.closes() && r.snr_db >= self.config.min_snr_db)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
     /// Check if the network is connected (all nodes reachable from node 0)
     pub fn is_connected(&self) -> bool {
         if self.nodes.is_empty() {
@@ -871,4 +990,76 @@ mod tests {
         // Packets should be lost due to distance
         // (This may or may not work depending on random positions)
     }
+
+    #[test]
+    fn test_path_loss_model_thresholds_from_preset() {
+        // Inputs changed after choosing the model still count
+        let mut config = SimConfig::default()
+            .with_path_loss_model(PathLossModel::FreeSpace)
+            .with_antennas(3.0, 1.0);
+        config.modem_preset = ModemPreset::ShortFast;
+        let sim = MeshSimulator::new(config.clone());
+
+        let (sf, bw, _) = ModemPreset::ShortFast.lora_params();
+        let params = LoRaParams::builder().spreading_factor(sf).bandwidth(bw).build();
+        assert_eq!(sim.config.rx_sensitivity_dbm, config.link_budget().sensitivity_dbm);
+        assert_eq!(sim.config.rx_sensitivity_dbm, params.sensitivity());
+        assert_eq!(sim.config.noise_floor_dbm, config.link_budget().noise_floor_dbm());
+        assert_eq!(sim.config.min_snr_db, params.sf.snr_threshold());
+    }
+
+    #[test]
+    fn test_connectivity_follows_propagation() {
+        let model = PathLossModel::parse("hata:hb=10,hm=1.5,env=urban").unwrap();
+        let config = SimConfig::default()
+            .with_node_count(12)
+            .with_area(20_000.0, 20_000.0)
+            .with_message_rate(0.0)
+            .with_path_loss_model(model.clone());
+        let sim = MeshSimulator::new(config.clone());
+
+        let range = config
+            .link_budget()
+            .range_m(&model, config.region.primary_frequency() as f64);
+        let links = sim.link_topology();
+        for (i, neighbors) in links.iter().enumerate() {
+            for j in 0..sim.node_count() {
+                if i == j {
+                    continue;
+                }
+                let d = sim.node_position(i).unwrap().distance_to(&sim.node_position(j).unwrap());
+                // Without shadowing, connectivity is exactly "within range"
+                if (d - range).abs() > 1.0 {
+                    assert_eq!(neighbors.contains(&j), d < range, "nodes {} and {} at {:.0} m", i, j, d);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_shadowing_is_symmetric_and_seeded() {
+        let config = SimConfig::default()
+            .with_node_count(6)
+            .with_path_loss_model(PathLossModel::parse("log-distance:n=3,sigma=8").unwrap());
+        let a = MeshSimulator::new(config.clone());
+        let b = MeshSimulator::new(config);
+        let mut any_shadowing = false;
+        for i in 0..6 {
+            for j in 0..6 {
+                if i == j {
+                    continue;
+                }
+                let ab = a.link_report(i, j).unwrap();
+                let ba = a.link_report(j, i).unwrap();
+                assert!((ab.rx_power_dbm - ba.rx_power_dbm).abs() < 1e-9);
+                assert_eq!(ab, b.link_report(i, j).unwrap());
+                let median = a.calculate_rssi(
+                    &a.node_position(i).unwrap(),
+                    &a.node_position(j).unwrap(),
+                );
+                any_shadowing |= (ab.rx_power_dbm - median).abs() > 0.1;
+            }
+        }
+        assert!(any_shadowing);
+    }
 }
@@ -27,10 +27,33 @@
 //! let snapshot = metrics.snapshot();
 //! println!("Total RX: {}", snapshot.rx_samples);
 //! ```
+//!
+//! ## Labels
+//!
+//! Every exported series carries the active waveform and any labels set
+//! on the instance, so several receivers can be scraped into one
+//! Prometheus job:
+//!
+//! ```rust
+//! use r4w_core::observe::Metrics;
+//!
+//! let metrics = Metrics::new();
+//! metrics.set_waveform("QPSK");
+//! metrics.set_device("rtltcp://localhost:1234");
+//! metrics.packets_decoded.inc();
+//!
+//! let text = metrics.to_prometheus();
+//! assert!(text.contains(
+//!     r#"r4w_packets_decoded_total{device="rtltcp://localhost:1234",waveform="QPSK"} 1"#
+//! ));
+//! ```
 
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::RwLock;
 
+use crate::types::IQSample;
+
 /// A simple atomic counter.
 #[derive(Debug, Default)]
 pub struct Counter {
@@ -247,6 +270,9 @@ pub struct Metrics {
     // Waveform info
     /// Currently active waveform name
     pub active_waveform: RwLock<String>,
+
+    /// Labels added to every exported series (besides `waveform`)
+    labels: RwLock<BTreeMap<String, String>>,
 }
 
 impl Metrics {
@@ -306,66 +332,137 @@ impl Metrics {
         }
     }
 
+    /// Set a label exported on every series.
+    pub fn set_label(&self, key: &str, value: &str) {
+        if let Ok(mut labels) = self.labels.write() {
+            labels.insert(key.to_string(), value.to_string());
+        }
+    }
+
+    /// Set a label, builder style.
+    pub fn with_label(self, key: &str, value: &str) -> Self {
+        self.set_label(key, value);
+        self
+    }
+
+    /// Set the `device` label.
+    pub fn set_device(&self, name: &str) {
+        self.set_label("device", name);
+    }
+
+    /// Labels of every series: the set labels plus the active waveform.
+    pub fn labels(&self) -> BTreeMap<String, String> {
+        let mut labels = self.labels.read().map(|l| l.clone()).unwrap_or_default();
+        let waveform = self.active_waveform.read().map(|w| w.clone()).unwrap_or_default();
+        if !waveform.is_empty() {
+            labels.insert("waveform".to_string(), waveform);
+        }
+        labels
+    }
+
+    /// Record a block of received samples: count and RSSI.
+    ///
+    /// Without a calibrated front end the RSSI is the block's mean power
+    /// in dB full scale.
+    pub fn record_rx(&self, samples: &[IQSample]) {
+        if samples.is_empty() {
+            return;
+        }
+        self.rx_samples.inc_by(samples.len() as u64);
+        let power = samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / samples.len() as f64;
+        self.record_rssi(10.0 * power.max(1e-20).log10());
+    }
+
     /// Record RSSI in dBm.
     pub fn record_rssi(&self, rssi_dbm: f64) {
-        self.rssi_dbm_x10.set((rssi_dbm * 10.0) as i64);
+        self.rssi_dbm_x10.set((rssi_dbm * 10.0).round() as i64);
     }
 
     /// Record SNR in dB.
     pub fn record_snr(&self, snr_db: f64) {
-        self.snr_db_x10.set((snr_db * 10.0) as i64);
+        self.snr_db_x10.set((snr_db * 10.0).round() as i64);
     }
 
     /// Export metrics in Prometheus text format.
     pub fn to_prometheus(&self) -> String {
         let s = self.snapshot();
+        let labels = format_labels(&self.labels());
         let mut output = String::new();
+        let mut series = |name: &str, kind: &str, help: &str, value: String| {
+            output.push_str(&format!("# HELP {} {}\n", name, help));
+            output.push_str(&format!("# TYPE {} {}\n", name, kind));
+            output.push_str(&format!("{}{} {}\n", name, labels, value));
+        };
 
         // Sample counters
-        output.push_str("# HELP r4w_rx_samples_total Total RX samples processed\n");
-        output.push_str("# TYPE r4w_rx_samples_total counter\n");
-        output.push_str(&format!("r4w_rx_samples_total {}\n", s.rx_samples));
-
-        output.push_str("# HELP r4w_tx_samples_total Total TX samples processed\n");
-        output.push_str("# TYPE r4w_tx_samples_total counter\n");
-        output.push_str(&format!("r4w_tx_samples_total {}\n", s.tx_samples));
+        series("r4w_rx_samples_total", "counter", "Total RX samples processed", s.rx_samples.to_string());
+        series("r4w_tx_samples_total", "counter", "Total TX samples processed", s.tx_samples.to_string());
 
         // Buffer gauges
-        output.push_str("# HELP r4w_rx_buffer_level Current RX buffer level\n");
-        output.push_str("# TYPE r4w_rx_buffer_level gauge\n");
-        output.push_str(&format!("r4w_rx_buffer_level {}\n", s.rx_buffer_level));
-
-        output.push_str("# HELP r4w_tx_buffer_level Current TX buffer level\n");
-        output.push_str("# TYPE r4w_tx_buffer_level gauge\n");
-        output.push_str(&format!("r4w_tx_buffer_level {}\n", s.tx_buffer_level));
+        series("r4w_rx_buffer_level", "gauge", "Current RX buffer level", s.rx_buffer_level.to_string());
+        series("r4w_tx_buffer_level", "gauge", "Current TX buffer level", s.tx_buffer_level.to_string());
 
         // Error counters
-        output.push_str("# HELP r4w_rx_overflows_total RX overflow events\n");
-        output.push_str("# TYPE r4w_rx_overflows_total counter\n");
-        output.push_str(&format!("r4w_rx_overflows_total {}\n", s.rx_overflows));
-
-        output.push_str("# HELP r4w_tx_underflows_total TX underflow events\n");
-        output.push_str("# TYPE r4w_tx_underflows_total counter\n");
-        output.push_str(&format!("r4w_tx_underflows_total {}\n", s.tx_underflows));
+        series("r4w_rx_overflows_total", "counter", "RX overflow events", s.rx_overflows.to_string());
+        series("r4w_tx_underflows_total", "counter", "TX underflow events", s.tx_underflows.to_string());
+        series("r4w_crc_errors_total", "counter", "Packet CRC errors", s.crc_errors.to_string());
+        series("r4w_device_errors_total", "counter", "Device errors", s.device_errors.to_string());
 
         // Signal metrics
-        output.push_str("# HELP r4w_rssi_dbm Current RSSI in dBm\n");
-        output.push_str("# TYPE r4w_rssi_dbm gauge\n");
-        output.push_str(&format!("r4w_rssi_dbm {}\n", s.rssi_dbm));
-
-        output.push_str("# HELP r4w_snr_db Current SNR in dB\n");
-        output.push_str("# TYPE r4w_snr_db gauge\n");
-        output.push_str(&format!("r4w_snr_db {}\n", s.snr_db));
+        series("r4w_rssi_dbm", "gauge", "Current RSSI in dBm", s.rssi_dbm.to_string());
+        series("r4w_snr_db", "gauge", "Current SNR in dB", s.snr_db.to_string());
+        series("r4w_freq_offset_hz", "gauge", "Current frequency offset estimate in Hz", s.freq_offset_hz.to_string());
 
         // Packet counters
-        output.push_str("# HELP r4w_packets_decoded_total Successfully decoded packets\n");
-        output.push_str("# TYPE r4w_packets_decoded_total counter\n");
-        output.push_str(&format!("r4w_packets_decoded_total {}\n", s.packets_decoded));
+        series("r4w_packets_rx_total", "counter", "Packets received", s.packets_rx.to_string());
+        series("r4w_packets_tx_total", "counter", "Packets transmitted", s.packets_tx.to_string());
+        series("r4w_packets_decoded_total", "counter", "Successfully decoded packets", s.packets_decoded.to_string());
+        series("r4w_packets_failed_total", "counter", "Packets that failed to decode", s.packets_failed.to_string());
+
+        // Timing histograms
+        push_histogram(
+            &mut output,
+            "r4w_processing_latency_us",
+            "Processing latency in microseconds",
+            &self.processing_latency_us,
+            &self.labels(),
+        );
 
         output
     }
 }
 
+/// `{k="v",...}`, or nothing without labels.
+fn format_labels(labels: &BTreeMap<String, String>) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| {
+            let v = v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            format!("{}=\"{}\"", k, v)
+        })
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
+
+fn push_histogram(output: &mut String, name: &str, help: &str, hist: &Histogram, labels: &BTreeMap<String, String>) {
+    output.push_str(&format!("# HELP {} {}\n", name, help));
+    output.push_str(&format!("# TYPE {} histogram\n", name));
+    let bounds = hist.boundaries().iter().map(|b| b.to_string()).chain(std::iter::once("+Inf".to_string()));
+    let mut cumulative = 0;
+    for (bound, count) in bounds.zip(hist.bucket_counts()) {
+        cumulative += count;
+        let mut bucket_labels = labels.clone();
+        bucket_labels.insert("le".to_string(), bound);
+        output.push_str(&format!("{}_bucket{} {}\n", name, format_labels(&bucket_labels), cumulative));
+    }
+    let labels = format_labels(labels);
+    output.push_str(&format!("{}_sum{} {}\n", name, labels, hist.sum()));
+    output.push_str(&format!("{}_count{} {}\n", name, labels, hist.count()));
+}
+
 /// A snapshot of metrics at a point in time.
 #[derive(Debug, Clone)]
 pub struct MetricsSnapshot {
@@ -500,4 +597,22 @@ mod tests {
         assert!(output.contains("r4w_rx_samples_total 1000"));
         assert!(output.contains("# TYPE r4w_rx_samples_total counter"));
     }
+
+    #[test]
+    fn test_labels() {
+        let metrics = Metrics::new().with_label("device", "file:\"a\".sigmf");
+        metrics.set_waveform("BPSK");
+        metrics.record_rx(&[IQSample::new(0.1, 0.0); 100]);
+        metrics.processing_latency_us.observe(3.0);
+        metrics.processing_latency_us.observe(30.0);
+        metrics.rx_overflows.inc_by(2);
+
+        let output = metrics.to_prometheus();
+        let labels = r#"{device="file:\"a\".sigmf",waveform="BPSK"}"#;
+        assert!(output.contains(&format!("r4w_rx_samples_total{} 100", labels)));
+        assert!(output.contains(&format!("r4w_rx_overflows_total{} 2", labels)));
+        assert!(output.contains(&format!("r4w_rssi_dbm{} -20", labels)));
+        assert!(output.contains(r#"r4w_processing_latency_us_bucket{device="file:\"a\".sigmf",le="5",waveform="BPSK"} 1"#));
+        assert!(output.contains(r#"r4w_processing_latency_us_bucket{device="file:\"a\".sigmf",le="+Inf",waveform="BPSK"} 2"#));
+    }
 }
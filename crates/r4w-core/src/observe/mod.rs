@@ -3,7 +3,8 @@
 //! This module provides three-pillar observability for R4W:
 //!
 //! - **Logging**: Structured JSON logs via `tracing`
-//! - **Metrics**: Prometheus-compatible counters, gauges, and histograms
+//! - **Metrics**: Prometheus-compatible counters, gauges, and histograms,
+//!   served over HTTP by [`MetricsServer`]
 //! - **Capture**: Real-time I/Q sample capture in SigMF format
 //!
 //! ## Quick Start
@@ -51,10 +52,12 @@
 pub mod capture;
 pub mod logging;
 pub mod metrics;
+pub mod server;
 
 pub use capture::{CaptureConfig, CaptureInfo, CaptureManager, CaptureState, CaptureStats, TriggerMode};
 pub use logging::{init_logging, LogConfig, LogFormat, LogLevel};
 pub use metrics::{Metrics, MetricsSnapshot};
+pub use server::{parse_prometheus, scrape, MetricsServer};
 
 /// Initialize all observability subsystems.
 ///
@@ -0,0 +1,161 @@
+//! # Metrics HTTP Endpoint
+//!
+//! Serves a shared [`Metrics`] instance in Prometheus text format from a
+//! background thread, so long-running commands can be scraped while they
+//! process samples.
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use r4w_core::observe::{scrape, Metrics, MetricsServer};
+//!
+//! let metrics = Arc::new(Metrics::new());
+//! let server = MetricsServer::spawn("127.0.0.1:0", metrics.clone()).unwrap();
+//!
+//! metrics.rx_samples.inc_by(4096);
+//! let text = scrape(server.local_addr()).unwrap();
+//! assert!(text.contains("r4w_rx_samples_total 4096"));
+//! ```
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use super::Metrics;
+
+/// Background HTTP server for `GET /metrics`
+///
+/// Stops when dropped.
+#[derive(Debug)]
+pub struct MetricsServer {
+    addr: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MetricsServer {
+    /// Bind and start serving `metrics`.
+    pub fn spawn<A: ToSocketAddrs>(addr: A, metrics: Arc<Metrics>) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let stop = shutdown.clone();
+        let handle = std::thread::Builder::new()
+            .name("r4w-metrics".to_string())
+            .spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            if let Err(e) = respond(stream, &metrics) {
+                                tracing::debug!("Metrics request failed: {}", e);
+                            }
+                        }
+                        Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                            std::thread::sleep(Duration::from_millis(20));
+                        }
+                        Err(e) => tracing::warn!("Metrics accept error: {}", e),
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            addr,
+            shutdown,
+            handle: Some(handle),
+        })
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn respond(mut stream: TcpStream, metrics: &Metrics) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let response = if request_line.starts_with("GET /metrics") || request_line.starts_with("GET / ") {
+        let body = metrics.to_prometheus();
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    } else {
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+    };
+    stream.write_all(response.as_bytes())
+}
+
+/// Fetch `/metrics` from a running endpoint and return the body.
+pub fn scrape<A: ToSocketAddrs>(addr: A) -> io::Result<String> {
+    let mut stream = TcpStream::connect(addr)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    stream.write_all(b"GET /metrics HTTP/1.0\r\nAccept: text/plain\r\n\r\n")?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Malformed HTTP response"))?;
+    if !head.starts_with("HTTP/1.1 200") && !head.starts_with("HTTP/1.0 200") {
+        let status = head.lines().next().unwrap_or_default();
+        return Err(io::Error::other(format!("Metrics endpoint returned {}", status)));
+    }
+    Ok(body.to_string())
+}
+
+/// Parse Prometheus text into `(series, value)` pairs, where the series
+/// is the metric name with its label set, e.g. `r4w_snr_db{waveform="BPSK"}`.
+pub fn parse_prometheus(text: &str) -> Vec<(String, f64)> {
+    text.lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .filter_map(|line| {
+            let (series, value) = line.rsplit_once(' ')?;
+            let value = match value {
+                "+Inf" => f64::INFINITY,
+                "-Inf" => f64::NEG_INFINITY,
+                v => v.parse().ok()?,
+            };
+            Some((series.to_string(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serve_and_scrape() {
+        let metrics = Arc::new(Metrics::new().with_label("device", "test"));
+        let server = MetricsServer::spawn("127.0.0.1:0", metrics.clone()).unwrap();
+        metrics.packets_decoded.inc_by(7);
+
+        let body = scrape(server.local_addr()).unwrap();
+        let values = parse_prometheus(&body);
+        assert!(values.contains(&(r#"r4w_packets_decoded_total{device="test"}"#.to_string(), 7.0)));
+
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        stream.write_all(b"GET /nope HTTP/1.0\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 404"));
+    }
+}
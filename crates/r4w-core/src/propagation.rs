@@ -0,0 +1,662 @@
+//! Path Loss and Link Budget Models
+//!
+//! Converts geometry and frequency into propagation loss, and a transmitter
+//! and receiver description into a link margin.
+//!
+//! ## Path Loss Models
+//!
+//! | Model | Use | Validity |
+//! |-------|-----|----------|
+//! | Free space | Line of sight, no obstructions | Any distance in the far field |
+//! | Two-ray ground | LOS over flat ground | Antennas well above ground |
+//! | Log-distance | Empirical, with log-normal shadowing | Any, with fitted exponent |
+//! | Okumura-Hata / COST-231 | Macro cells, urban to open | 150-2000 MHz, 1-20 km |
+//! | P.1546-style | Point-to-area land paths | 30-3000 MHz, 1-1000 km |
+//!
+//! ```text
+//! Free space:    L = 20·log10(4πd/λ)
+//! Log-distance:  L = L_fs(d0) + 10·n·log10(d/d0) + X_σ
+//! Two-ray:       L = -20·log10(λ/4π · |e^(-jkd1)/d1 - e^(-jkd2)/d2|)
+//!                  ≈ 40·log10(d) - 20·log10(ht·hr)   beyond the crossover
+//! ```
+//!
+//! Models return the median loss; shadowing (where the model has it) is
+//! exposed as a standard deviation so simulators can draw one value per link.
+//!
+//! ## Link Budget
+//!
+//! ```text
+//! P_rx   = P_tx + G_tx - L_cable,tx - L_path + G_rx - L_cable,rx
+//! N      = -174 dBm/Hz + 10·log10(B) + NF
+//! margin = P_rx - sensitivity
+//! ```
+//!
+//! ```rust
+//! use r4w_core::params::LoRaParams;
+//! use r4w_core::propagation::{LinkBudget, PathLossModel};
+//!
+//! let params = LoRaParams::builder().spreading_factor(12).bandwidth(125_000).build();
+//! let budget = LinkBudget::for_lora(&params, 20.0);
+//! let model = PathLossModel::parse("hata:hb=30,hm=1.5,env=suburban").unwrap();
+//!
+//! let report = budget.evaluate(model.path_loss_db(5_000.0, 915e6));
+//! assert!(report.margin_db > 0.0);
+//! assert!(budget.range_m(&model, 915e6) > 5_000.0);
+//! ```
+
+use crate::params::LoRaParams;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// Speed of light (m/s)
+pub const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// Thermal noise density at 290 K (dBm/Hz)
+pub const THERMAL_NOISE_DBM_HZ: f64 = -174.0;
+
+/// Terrain/clutter category for the empirical models
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Environment {
+    /// Large city with tall buildings
+    DenseUrban,
+    /// Small to medium city
+    Urban,
+    /// Suburban, residential
+    #[default]
+    Suburban,
+    /// Open or rural area
+    Open,
+}
+
+impl Environment {
+    /// Representative clutter height used by the P.1546-style model (meters)
+    pub fn clutter_height_m(&self) -> f64 {
+        match self {
+            Self::DenseUrban | Self::Urban => 20.0,
+            Self::Suburban | Self::Open => 10.0,
+        }
+    }
+
+    fn parse(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "denseurban" | "largecity" | "metro" => Ok(Self::DenseUrban),
+            "urban" | "city" => Ok(Self::Urban),
+            "suburban" => Ok(Self::Suburban),
+            "open" | "rural" => Ok(Self::Open),
+            _ => Err(format!("Unknown environment '{}'", s)),
+        }
+    }
+}
+
+/// Propagation model converting distance and frequency into path loss
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PathLossModel {
+    /// Friis free-space loss
+    #[default]
+    FreeSpace,
+    /// Direct ray plus a ground reflection (reflection coefficient -1)
+    TwoRay {
+        /// Transmit antenna height (m)
+        tx_height_m: f64,
+        /// Receive antenna height (m)
+        rx_height_m: f64,
+    },
+    /// Free space up to `d0`, then `10·n·log10(d/d0)`, with log-normal shadowing
+    LogDistance {
+        /// Path loss exponent (2 = free space, 2.7-3.5 urban, 4-6 indoor)
+        exponent: f64,
+        /// Reference distance d0 (m)
+        reference_distance_m: f64,
+        /// Shadowing standard deviation (dB)
+        #[serde(default)]
+        shadowing_sigma_db: f64,
+    },
+    /// Okumura-Hata, switching to COST-231 Hata above 1500 MHz
+    Hata {
+        /// Base station antenna height (m, 30-200)
+        base_height_m: f64,
+        /// Mobile antenna height (m, 1-10)
+        mobile_height_m: f64,
+        /// Environment correction
+        #[serde(default)]
+        environment: Environment,
+    },
+    /// Median land-path loss in the style of ITU-R P.1546
+    ///
+    /// A Hata-form median referenced to a 1.5 m receiver, with the P.1546
+    /// receiving-antenna height correction against representative clutter,
+    /// the location-variability correction and the free-space limit.
+    P1546 {
+        /// Effective transmit antenna height (m)
+        tx_height_m: f64,
+        /// Receive antenna height above ground (m)
+        rx_height_m: f64,
+        /// Clutter category
+        #[serde(default)]
+        environment: Environment,
+        /// Percentage of locations at which the loss is not exceeded (1-99)
+        #[serde(default = "default_location_percent")]
+        location_percent: f64,
+    },
+}
+
+fn default_location_percent() -> f64 {
+    50.0
+}
+
+/// Free-space path loss (dB), never negative
+pub fn free_space_loss_db(distance_m: f64, freq_hz: f64) -> f64 {
+    (20.0 * (4.0 * PI * distance_m * freq_hz / SPEED_OF_LIGHT).log10()).max(0.0)
+}
+
+/// Thermal noise power in a bandwidth (dBm)
+pub fn thermal_noise_dbm(bandwidth_hz: f64, noise_figure_db: f64) -> f64 {
+    THERMAL_NOISE_DBM_HZ + 10.0 * bandwidth_hz.log10() + noise_figure_db
+}
+
+impl PathLossModel {
+    /// Median path loss in dB at a distance (m) and carrier frequency (Hz)
+    pub fn path_loss_db(&self, distance_m: f64, freq_hz: f64) -> f64 {
+        let d = distance_m.max(1e-3);
+        match *self {
+            Self::FreeSpace => free_space_loss_db(d, freq_hz),
+            Self::TwoRay { tx_height_m, rx_height_m } => two_ray_loss_db(d, freq_hz, tx_height_m, rx_height_m),
+            Self::LogDistance { exponent, reference_distance_m, .. } => {
+                let d0 = reference_distance_m.max(1e-3);
+                if d <= d0 {
+                    free_space_loss_db(d, freq_hz)
+                } else {
+                    free_space_loss_db(d0, freq_hz) + 10.0 * exponent * (d / d0).log10()
+                }
+            }
+            Self::Hata { base_height_m, mobile_height_m, environment } => {
+                let loss = hata_loss_db(d, freq_hz, base_height_m, mobile_height_m, environment);
+                loss.max(free_space_loss_db(d, freq_hz))
+            }
+            Self::P1546 { tx_height_m, rx_height_m, environment, location_percent } => {
+                let f_mhz = freq_hz / 1e6;
+                let median = hata_loss_db(d, freq_hz, tx_height_m, 1.5, environment);
+                let clutter = environment.clutter_height_m();
+                let height_gain = p1546_rx_height_correction_db(f_mhz, rx_height_m, clutter)
+                    - p1546_rx_height_correction_db(f_mhz, 1.5, clutter);
+                let sigma = self.location_sigma_db(freq_hz);
+                let q = (location_percent / 100.0).clamp(0.01, 0.99);
+                // Field strength exceeded at q of locations is E50 + Qi(q)·σ
+                let loss = median - height_gain - inverse_q(q) * sigma;
+                loss.max(free_space_loss_db(d, freq_hz))
+            }
+        }
+    }
+
+    /// Standard deviation of per-link log-normal shadowing (dB)
+    pub fn shadowing_sigma_db(&self) -> f64 {
+        match self {
+            Self::LogDistance { shadowing_sigma_db, .. } => *shadowing_sigma_db,
+            _ => 0.0,
+        }
+    }
+
+    /// P.1546 location variability `σ_L = K + 1.3·log10(f_MHz)` (dB)
+    fn location_sigma_db(&self, freq_hz: f64) -> f64 {
+        let k = match self {
+            Self::P1546 { environment: Environment::Open, .. } => 0.5,
+            _ => 1.2,
+        };
+        k + 1.3 * (freq_hz / 1e6).log10()
+    }
+
+    /// Short name for reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FreeSpace => "free-space",
+            Self::TwoRay { .. } => "two-ray",
+            Self::LogDistance { .. } => "log-distance",
+            Self::Hata { .. } => "hata",
+            Self::P1546 { .. } => "p1546",
+        }
+    }
+
+    /// Parse a spec written as `kind:key=value,...`
+    ///
+    /// | Kind | Keys (defaults) |
+    /// |------|-----------------|
+    /// | `free-space` | none |
+    /// | `two-ray` | `ht` (10), `hr` (1.5) |
+    /// | `log-distance` | `n` (2.8), `d0` (1), `sigma` (0) |
+    /// | `hata` | `hb` (30), `hm` (1.5), `env` (suburban) |
+    /// | `p1546` | `h1` (30), `h2` (1.5), `env` (suburban), `loc` (50) |
+    ///
+    /// Environments: `dense-urban`, `urban`, `suburban`, `open`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (kind, params) = spec.split_once(':').unwrap_or((spec, ""));
+        let mut values: Vec<(&str, &str)> = Vec::new();
+        for pair in params.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected key=value, got '{}'", pair))?;
+            values.push((key.trim(), value.trim()));
+        }
+        let lookup = |key: &str| values.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+        let number = |key: &str, default: f64| -> Result<f64, String> {
+            lookup(key).map_or(Ok(default), |v| {
+                v.parse().map_err(|_| format!("Invalid value for '{}': '{}'", key, v))
+            })
+        };
+        let environment = || lookup("env").map_or(Ok(Environment::default()), Environment::parse);
+
+        let (model, allowed): (Self, &[&str]) = match kind.trim().to_lowercase().replace('_', "-").as_str() {
+            "free-space" | "freespace" | "fspl" => (Self::FreeSpace, &[]),
+            "two-ray" | "tworay" => (
+                Self::TwoRay {
+                    tx_height_m: number("ht", 10.0)?,
+                    rx_height_m: number("hr", 1.5)?,
+                },
+                &["ht", "hr"],
+            ),
+            "log-distance" | "logdistance" => (
+                Self::LogDistance {
+                    exponent: number("n", 2.8)?,
+                    reference_distance_m: number("d0", 1.0)?,
+                    shadowing_sigma_db: number("sigma", 0.0)?,
+                },
+                &["n", "d0", "sigma"],
+            ),
+            "hata" | "okumura-hata" | "cost231" | "cost-231" => (
+                Self::Hata {
+                    base_height_m: number("hb", 30.0)?,
+                    mobile_height_m: number("hm", 1.5)?,
+                    environment: environment()?,
+                },
+                &["hb", "hm", "env"],
+            ),
+            "p1546" | "p.1546" => (
+                Self::P1546 {
+                    tx_height_m: number("h1", 30.0)?,
+                    rx_height_m: number("h2", 1.5)?,
+                    environment: environment()?,
+                    location_percent: number("loc", default_location_percent())?,
+                },
+                &["h1", "h2", "env", "loc"],
+            ),
+            other => return Err(format!("Unknown path loss model '{}'", other)),
+        };
+
+        if let Some((key, _)) = values.iter().find(|(k, _)| !allowed.contains(k)) {
+            return Err(format!("Unknown parameter '{}' for '{}' model", key, model.name()));
+        }
+        Ok(model)
+    }
+}
+
+/// Two-ray ground reflection loss with a perfectly reflecting ground
+fn two_ray_loss_db(d: f64, freq_hz: f64, ht: f64, hr: f64) -> f64 {
+    let lambda = SPEED_OF_LIGHT / freq_hz;
+    let k = 2.0 * PI / lambda;
+    let d1 = (d * d + (ht - hr).powi(2)).sqrt();
+    let d2 = (d * d + (ht + hr).powi(2)).sqrt();
+    // e^(-jkd1)/d1 - e^(-jkd2)/d2
+    let re = (k * d1).cos() / d1 - (k * d2).cos() / d2;
+    let im = -(k * d1).sin() / d1 + (k * d2).sin() / d2;
+    let magnitude = (re * re + im * im).sqrt() * lambda / (4.0 * PI);
+    // Cap the depth of interference nulls
+    (-20.0 * magnitude.max(1e-15).log10()).max(0.0)
+}
+
+/// Okumura-Hata (≤ 1500 MHz) or COST-231 Hata (> 1500 MHz) median loss
+fn hata_loss_db(d: f64, freq_hz: f64, hb: f64, hm: f64, environment: Environment) -> f64 {
+    let f = freq_hz / 1e6;
+    let log_f = f.log10();
+    let d_km = (d / 1000.0).max(1e-3);
+    let hb = hb.max(1.0);
+
+    // Mobile antenna height correction
+    let a_hm = match environment {
+        Environment::DenseUrban if f >= 300.0 => 3.2 * (11.75 * hm).log10().powi(2) - 4.97,
+        Environment::DenseUrban => 8.29 * (1.54 * hm).log10().powi(2) - 1.1,
+        _ => (1.1 * log_f - 0.7) * hm - (1.56 * log_f - 0.8),
+    };
+    let slope = (44.9 - 6.55 * hb.log10()) * d_km.log10();
+
+    let urban = if f <= 1500.0 {
+        69.55 + 26.16 * log_f - 13.82 * hb.log10() - a_hm + slope
+    } else {
+        let c_m = if environment == Environment::DenseUrban { 3.0 } else { 0.0 };
+        46.3 + 33.9 * log_f - 13.82 * hb.log10() - a_hm + slope + c_m
+    };
+
+    // COST-231 covers suburban and open areas with C_m = 0; the Okumura
+    // corrections below only hold up to 1500 MHz
+    match environment {
+        Environment::DenseUrban | Environment::Urban => urban,
+        _ if f > 1500.0 => urban,
+        Environment::Suburban => urban - 2.0 * (f / 28.0).log10().powi(2) - 5.4,
+        Environment::Open => urban - 4.78 * log_f.powi(2) + 18.33 * log_f - 40.94,
+    }
+}
+
+/// P.1546 receiving antenna height correction relative to clutter height `r` (dB of field strength)
+fn p1546_rx_height_correction_db(f_mhz: f64, h2: f64, r: f64) -> f64 {
+    if h2 >= r {
+        (3.2 + 6.2 * f_mhz.log10()) * (h2 / r).log10()
+    } else {
+        let h_dif = r - h2;
+        let theta_clut = (h_dif / 27.0).atan().to_degrees();
+        let nu = 0.0108 * f_mhz.sqrt() * (h_dif * theta_clut).sqrt();
+        6.03 - knife_edge_loss_db(nu)
+    }
+}
+
+/// ITU-R knife-edge diffraction loss J(ν) (dB)
+fn knife_edge_loss_db(nu: f64) -> f64 {
+    if nu <= -0.78 {
+        0.0
+    } else {
+        6.9 + 20.0 * (((nu - 0.1).powi(2) + 1.0).sqrt() + nu - 0.1).log10()
+    }
+}
+
+/// Inverse complementary cumulative normal Qi(x), as approximated in P.1546
+fn inverse_q(x: f64) -> f64 {
+    fn t_minus_xi(x: f64) -> f64 {
+        let t = (-2.0 * x.ln()).sqrt();
+        let xi = ((0.010328 * t + 0.802853) * t + 2.515517)
+            / (((0.001308 * t + 0.189269) * t + 1.432788) * t + 1.0);
+        t - xi
+    }
+    if x <= 0.5 {
+        t_minus_xi(x)
+    } else {
+        -t_minus_xi(1.0 - x)
+    }
+}
+
+/// Transmitter and receiver parameters for a link budget
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkBudget {
+    /// Transmit power at the PA output (dBm)
+    pub tx_power_dbm: f64,
+    /// Transmit antenna gain (dBi)
+    pub tx_antenna_gain_dbi: f64,
+    /// Transmit feedline and connector loss (dB)
+    pub tx_cable_loss_db: f64,
+    /// Receive antenna gain (dBi)
+    pub rx_antenna_gain_dbi: f64,
+    /// Receive feedline and connector loss (dB)
+    pub rx_cable_loss_db: f64,
+    /// Receiver noise figure (dB)
+    pub noise_figure_db: f64,
+    /// Receiver noise bandwidth (Hz)
+    pub bandwidth_hz: f64,
+    /// Minimum received power for successful demodulation (dBm)
+    pub sensitivity_dbm: f64,
+    /// Additional losses: fading margin, body loss, polarisation (dB)
+    pub other_losses_db: f64,
+}
+
+impl LinkBudget {
+    /// Link budget for a generic receiver needing `required_snr_db` in `bandwidth_hz`
+    pub fn new(tx_power_dbm: f64, bandwidth_hz: f64, noise_figure_db: f64, required_snr_db: f64) -> Self {
+        Self {
+            tx_power_dbm,
+            tx_antenna_gain_dbi: 0.0,
+            tx_cable_loss_db: 0.0,
+            rx_antenna_gain_dbi: 0.0,
+            rx_cable_loss_db: 0.0,
+            noise_figure_db,
+            bandwidth_hz,
+            sensitivity_dbm: thermal_noise_dbm(bandwidth_hz, noise_figure_db) + required_snr_db,
+            other_losses_db: 0.0,
+        }
+    }
+
+    /// Link budget for a LoRa receiver, using [`LoRaParams::sensitivity`]
+    pub fn for_lora(params: &LoRaParams, tx_power_dbm: f64) -> Self {
+        Self {
+            sensitivity_dbm: params.sensitivity(),
+            ..Self::new(tx_power_dbm, params.bw.hz(), 6.0, params.sf.snr_threshold())
+        }
+    }
+
+    /// Set antenna gains (dBi)
+    pub fn with_antenna_gains(mut self, tx_dbi: f64, rx_dbi: f64) -> Self {
+        self.tx_antenna_gain_dbi = tx_dbi;
+        self.rx_antenna_gain_dbi = rx_dbi;
+        self
+    }
+
+    /// Set feedline losses (dB)
+    pub fn with_cable_losses(mut self, tx_db: f64, rx_db: f64) -> Self {
+        self.tx_cable_loss_db = tx_db;
+        self.rx_cable_loss_db = rx_db;
+        self
+    }
+
+    /// Set additional losses such as a fade margin (dB)
+    pub fn with_other_losses(mut self, loss_db: f64) -> Self {
+        self.other_losses_db = loss_db;
+        self
+    }
+
+    /// Effective isotropic radiated power (dBm)
+    pub fn eirp_dbm(&self) -> f64 {
+        self.tx_power_dbm + self.tx_antenna_gain_dbi - self.tx_cable_loss_db
+    }
+
+    /// Receiver noise floor (dBm)
+    pub fn noise_floor_dbm(&self) -> f64 {
+        thermal_noise_dbm(self.bandwidth_hz, self.noise_figure_db)
+    }
+
+    /// Largest path loss that still closes the link (dB)
+    pub fn max_path_loss_db(&self) -> f64 {
+        self.eirp_dbm() + self.rx_antenna_gain_dbi - self.rx_cable_loss_db - self.other_losses_db - self.sensitivity_dbm
+    }
+
+    /// Evaluate the link for a given path loss
+    pub fn evaluate(&self, path_loss_db: f64) -> LinkReport {
+        let rx_power_dbm = self.eirp_dbm() - path_loss_db - self.other_losses_db + self.rx_antenna_gain_dbi
+            - self.rx_cable_loss_db;
+        let noise_floor_dbm = self.noise_floor_dbm();
+        LinkReport {
+            eirp_dbm: self.eirp_dbm(),
+            path_loss_db,
+            rx_power_dbm,
+            noise_floor_dbm,
+            snr_db: rx_power_dbm - noise_floor_dbm,
+            sensitivity_dbm: self.sensitivity_dbm,
+            margin_db: rx_power_dbm - self.sensitivity_dbm,
+        }
+    }
+
+    /// Largest distance (m, up to 1000 km) at which the link closes
+    ///
+    /// Scans distance logarithmically, so models with interference nulls
+    /// (two-ray) report the last distance with positive margin.
+    pub fn range_m(&self, model: &PathLossModel, freq_hz: f64) -> f64 {
+        let max_loss = self.max_path_loss_db();
+        let closes = |d: f64| model.path_loss_db(d, freq_hz) <= max_loss;
+
+        const STEPS_PER_DECADE: usize = 200;
+        let step = 10f64.powf(1.0 / STEPS_PER_DECADE as f64);
+        let mut last_ok = None;
+        let mut d = 1.0;
+        while d <= 1.0e6 {
+            if closes(d) {
+                last_ok = Some(d);
+            }
+            d *= step;
+        }
+        let Some(mut lo) = last_ok else {
+            return 0.0;
+        };
+        if lo * step > 1.0e6 {
+            return lo;
+        }
+        let mut hi = lo * step;
+        for _ in 0..40 {
+            let mid = (lo + hi) / 2.0;
+            if closes(mid) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+}
+
+/// Result of evaluating a [`LinkBudget`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LinkReport {
+    /// Effective isotropic radiated power (dBm)
+    pub eirp_dbm: f64,
+    /// Path loss (dB)
+    pub path_loss_db: f64,
+    /// Received power at the receiver input (dBm)
+    pub rx_power_dbm: f64,
+    /// Receiver noise floor (dBm)
+    pub noise_floor_dbm: f64,
+    /// Signal-to-noise ratio at the receiver (dB)
+    pub snr_db: f64,
+    /// Receiver sensitivity (dBm)
+    pub sensitivity_dbm: f64,
+    /// Received power above sensitivity (dB, negative if the link fails)
+    pub margin_db: f64,
+}
+
+impl LinkReport {
+    /// Whether the link closes
+    pub fn closes(&self) -> bool {
+        self.margin_db >= 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_space_reference() {
+        // 1 km at 1 GHz ≈ 92.45 dB
+        assert!((free_space_loss_db(1000.0, 1e9) - 92.45).abs() < 0.01);
+        // Doubling distance adds 6 dB
+        let m = PathLossModel::FreeSpace;
+        let delta = m.path_loss_db(2000.0, 915e6) - m.path_loss_db(1000.0, 915e6);
+        assert!((delta - 6.02).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_two_ray_far_field() {
+        let (ht, hr, f) = (30.0, 2.0, 900e6);
+        let model = PathLossModel::TwoRay { tx_height_m: ht, rx_height_m: hr };
+        // Well beyond the crossover distance 4π·ht·hr/λ ≈ 2.3 km
+        let d: f64 = 50_000.0;
+        let plane_earth = 40.0 * d.log10() - 20.0 * (ht * hr).log10();
+        assert!((model.path_loss_db(d, f) - plane_earth).abs() < 0.1);
+        // Close in, it oscillates around free space
+        let near = model.path_loss_db(100.0, f) - free_space_loss_db(100.0, f);
+        assert!(near < 6.1);
+    }
+
+    #[test]
+    fn test_log_distance() {
+        let model = PathLossModel::parse("log-distance:n=3,d0=10,sigma=8").unwrap();
+        let f = 2.4e9;
+        let at_d0 = model.path_loss_db(10.0, f);
+        assert!((at_d0 - free_space_loss_db(10.0, f)).abs() < 1e-9);
+        assert!((model.path_loss_db(100.0, f) - at_d0 - 30.0).abs() < 1e-9);
+        assert_eq!(model.shadowing_sigma_db(), 8.0);
+    }
+
+    #[test]
+    fn test_hata_reference_values() {
+        // Urban, 900 MHz, hb 30 m, hm 1.5 m, 1 km ≈ 126.4 dB
+        let urban = PathLossModel::Hata {
+            base_height_m: 30.0,
+            mobile_height_m: 1.5,
+            environment: Environment::Urban,
+        };
+        assert!((urban.path_loss_db(1000.0, 900e6) - 126.4).abs() < 0.2);
+
+        let loss = |env| {
+            PathLossModel::Hata { base_height_m: 30.0, mobile_height_m: 1.5, environment: env }
+                .path_loss_db(5000.0, 900e6)
+        };
+        assert!(loss(Environment::Urban) > loss(Environment::Suburban));
+        assert!(loss(Environment::Suburban) > loss(Environment::Open));
+
+        // COST-231 takes over above 1500 MHz: metropolitan adds 3 dB
+        let cost = |env| {
+            PathLossModel::Hata { base_height_m: 30.0, mobile_height_m: 1.5, environment: env }
+                .path_loss_db(2000.0, 1800e6)
+        };
+        assert!(cost(Environment::DenseUrban) > cost(Environment::Urban));
+
+        // Suburban COST-231 is the C_m = 0 formula, without Okumura corrections
+        let log_f = 1800f64.log10();
+        let a_hm = (1.1 * log_f - 0.7) * 1.5 - (1.56 * log_f - 0.8);
+        let c_m0 = 46.3 + 33.9 * log_f - 13.82 * 30f64.log10() - a_hm + (44.9 - 6.55 * 30f64.log10()) * 2f64.log10();
+        assert!((c_m0 - 146.8).abs() < 0.1);
+        assert!((cost(Environment::Suburban) - c_m0).abs() < 1e-9);
+        assert!((cost(Environment::Open) - c_m0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_p1546_corrections() {
+        let model = |h2: f64, loc: f64| PathLossModel::P1546 {
+            tx_height_m: 50.0,
+            rx_height_m: h2,
+            environment: Environment::Suburban,
+            location_percent: loc,
+        };
+        let f = 600e6;
+        let d = 10_000.0;
+        // Higher receive antenna, lower loss
+        assert!(model(10.0, 50.0).path_loss_db(d, f) < model(1.5, 50.0).path_loss_db(d, f));
+        // Covering more locations needs more margin: σ_L = 1.2 + 1.3·log10(600) ≈ 4.81 dB
+        let delta = model(1.5, 90.0).path_loss_db(d, f) - model(1.5, 50.0).path_loss_db(d, f);
+        assert!((delta - 1.2816 * 4.81).abs() < 0.1, "delta {}", delta);
+        // Never below free space
+        assert!(model(100.0, 1.0).path_loss_db(100.0, f) >= free_space_loss_db(100.0, f));
+    }
+
+    #[test]
+    fn test_inverse_q() {
+        assert!(inverse_q(0.5).abs() < 1e-3);
+        assert!((inverse_q(0.1) - 1.2816).abs() < 1e-3);
+        assert!((inverse_q(0.9) + 1.2816).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_link_budget_margin_and_range() {
+        let params = LoRaParams::builder().spreading_factor(7).bandwidth(125_000).build();
+        let budget = LinkBudget::for_lora(&params, 14.0)
+            .with_antenna_gains(2.0, 2.0)
+            .with_cable_losses(1.0, 1.0);
+        assert_eq!(budget.sensitivity_dbm, params.sensitivity());
+        assert!((budget.eirp_dbm() - 15.0).abs() < 1e-12);
+
+        let report = budget.evaluate(100.0);
+        assert!((report.rx_power_dbm - (15.0 - 100.0 + 1.0)).abs() < 1e-12);
+        assert!((report.margin_db - (report.rx_power_dbm - params.sensitivity())).abs() < 1e-12);
+        assert!(report.closes());
+
+        // At the computed range the margin is zero
+        let model = PathLossModel::FreeSpace;
+        let range = budget.range_m(&model, 868e6);
+        let at_range = budget.evaluate(model.path_loss_db(range, 868e6));
+        assert!(at_range.margin_db.abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_errors_and_serde() {
+        assert!(PathLossModel::parse("hata:env=swamp").is_err());
+        assert!(PathLossModel::parse("two-ray:hb=3").is_err());
+        assert!(PathLossModel::parse("okapi").is_err());
+        let model = PathLossModel::parse("p1546:h1=40,env=urban,loc=90").unwrap();
+        let json = serde_json::to_string(&model).unwrap();
+        assert_eq!(serde_json::from_str::<PathLossModel>(&json).unwrap(), model);
+    }
+}
@@ -0,0 +1,1152 @@
+//! # VITA 49 Radio Transport (VRT)
+//!
+//! Encoding and decoding of VITA 49.0/49.2 packets, the standard transport
+//! used by commercial digitizers and SDRs to stream I/Q samples with
+//! timestamps and metadata over a network.
+//!
+//! ## Packet Layout
+//!
+//! ```text
+//! ┌────────┬───────────┬──────────┬─────────┬─────────────┬─────────┬─────────┐
+//! │ Header │ Stream ID │ Class ID │ Integer │ Fractional  │ Payload │ Trailer │
+//! │ 1 word │ 1 word    │ 2 words  │ TS 1 w  │ TS 2 words  │ N words │ 1 word  │
+//! └────────┴───────────┴──────────┴─────────┴─────────────┴─────────┴─────────┘
+//!            (optional)  (optional) (if TSI)  (if TSF)               (data only)
+//! ```
+//!
+//! All fields are big-endian 32-bit words.
+//!
+//! - **Signal data packets** ([`DataPacket`]) carry complex samples as
+//!   `ci8`, `ci16` or `cf32` ([`PayloadFormat`]). The payload format is not
+//!   on the wire; it is agreed out of band or announced in a context packet.
+//! - **Context packets** ([`ContextPacket`]) describe a stream: RF
+//!   frequency, bandwidth, gain, sample rate and the other CIF0 fields.
+//!   Fields that the codec does not model are skipped on decode.
+//!
+//! ## Timestamps
+//!
+//! A [`VrtTimestamp`] combines an integer-seconds part (TSI: UTC, GPS or
+//! other) with a fractional part (TSF: sample count, picoseconds or a
+//! free-running count). A sample-count TSF without an integer part is a
+//! free-running sample index, which keeps sample timing exact across the
+//! network.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use r4w_core::vita49::{DataPacket, PayloadFormat, VrtPacket, VrtTimestamp};
+//! use r4w_core::types::IQSample;
+//!
+//! let samples = vec![IQSample::new(0.5, -0.25); 100];
+//! let mut packet = DataPacket::from_samples(&samples, PayloadFormat::Ci16);
+//! packet.stream_id = Some(0x1234);
+//! packet.timestamp = VrtTimestamp::sample_count(48_000);
+//!
+//! let bytes = packet.encode().unwrap();
+//! match VrtPacket::decode(&bytes).unwrap() {
+//!     VrtPacket::Data(rx) => {
+//!         assert_eq!(rx.stream_id, Some(0x1234));
+//!         assert_eq!(rx.timestamp.sample_index(), Some(48_000));
+//!         assert_eq!(rx.samples(PayloadFormat::Ci16).len(), 100);
+//!     }
+//!     _ => unreachable!(),
+//! }
+//! ```
+
+use crate::types::IQSample;
+
+/// Radix (fractional bits) of frequency, bandwidth and sample rate fields.
+const FREQ_RADIX: i32 = 20;
+
+/// Radix of reference level and gain fields.
+const GAIN_RADIX: i32 = 7;
+
+/// Radix of the temperature field.
+const TEMP_RADIX: i32 = 6;
+
+/// Largest packet the 16-bit size field can describe (words).
+pub const MAX_PACKET_WORDS: usize = u16::MAX as usize;
+
+/// Errors decoding or encoding VRT packets
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum VrtError {
+    #[error("packet truncated: need {needed} bytes, have {available}")]
+    Truncated { needed: usize, available: usize },
+
+    #[error("unsupported packet type {0}")]
+    UnsupportedType(u8),
+
+    #[error("packet of {0} words exceeds the VRT size limit")]
+    TooLarge(usize),
+
+    #[error("invalid packet: {0}")]
+    Invalid(String),
+}
+
+/// Packet type (header bits 31–28)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketType {
+    /// Signal data without stream ID (0)
+    SignalData,
+    /// Signal data with stream ID (1)
+    SignalDataWithStreamId,
+    /// Context (4)
+    Context,
+}
+
+impl PacketType {
+    fn code(self) -> u32 {
+        match self {
+            Self::SignalData => 0x0,
+            Self::SignalDataWithStreamId => 0x1,
+            Self::Context => 0x4,
+        }
+    }
+}
+
+/// Integer-seconds timestamp type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tsi {
+    #[default]
+    None,
+    /// Seconds since 1970-01-01 UTC
+    Utc,
+    /// Seconds since the GPS epoch (1980-01-06)
+    Gps,
+    /// Application-defined epoch
+    Other,
+}
+
+impl Tsi {
+    fn from_bits(bits: u32) -> Self {
+        match bits & 0x3 {
+            0 => Self::None,
+            1 => Self::Utc,
+            2 => Self::Gps,
+            _ => Self::Other,
+        }
+    }
+
+    fn bits(self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Utc => 1,
+            Self::Gps => 2,
+            Self::Other => 3,
+        }
+    }
+}
+
+/// Fractional timestamp type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tsf {
+    #[default]
+    None,
+    /// Sample periods (since the integer second, or free-running without TSI)
+    SampleCount,
+    /// Picoseconds since the integer second
+    RealTime,
+    /// Free-running count
+    FreeRunning,
+}
+
+impl Tsf {
+    fn from_bits(bits: u32) -> Self {
+        match bits & 0x3 {
+            0 => Self::None,
+            1 => Self::SampleCount,
+            2 => Self::RealTime,
+            _ => Self::FreeRunning,
+        }
+    }
+
+    fn bits(self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::SampleCount => 1,
+            Self::RealTime => 2,
+            Self::FreeRunning => 3,
+        }
+    }
+}
+
+/// Packet timestamp (integer and fractional parts)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VrtTimestamp {
+    pub tsi: Tsi,
+    /// Integer seconds (meaningful when `tsi` is not `None`)
+    pub integer: u32,
+    pub tsf: Tsf,
+    /// Fractional part (meaningful when `tsf` is not `None`)
+    pub fractional: u64,
+}
+
+impl VrtTimestamp {
+    /// Free-running sample index (no integer part).
+    pub fn sample_count(samples: u64) -> Self {
+        Self {
+            tsf: Tsf::SampleCount,
+            fractional: samples,
+            ..Default::default()
+        }
+    }
+
+    /// UTC seconds plus picoseconds from Unix-epoch nanoseconds.
+    pub fn from_utc_nanos(nanos: u64) -> Self {
+        Self {
+            tsi: Tsi::Utc,
+            integer: (nanos / 1_000_000_000) as u32,
+            tsf: Tsf::RealTime,
+            fractional: (nanos % 1_000_000_000) * 1000,
+        }
+    }
+
+    /// Unix-epoch nanoseconds, for UTC timestamps with a picosecond fraction.
+    pub fn utc_nanos(&self) -> Option<u64> {
+        let frac = match self.tsf {
+            Tsf::RealTime => self.fractional / 1000,
+            Tsf::None => 0,
+            _ => return None,
+        };
+        (self.tsi == Tsi::Utc).then(|| self.integer as u64 * 1_000_000_000 + frac)
+    }
+
+    /// Free-running sample index, for sample-count timestamps without TSI.
+    pub fn sample_index(&self) -> Option<u64> {
+        (self.tsi == Tsi::None && self.tsf == Tsf::SampleCount).then_some(self.fractional)
+    }
+
+    /// Timestamp `samples` sample periods later.
+    ///
+    /// Sample counts under an integer second and picosecond fractions
+    /// carry into the integer seconds.
+    pub fn advance(&self, samples: u64, sample_rate: f64) -> Self {
+        let mut next = *self;
+        let (fractional, period) = match self.tsf {
+            Tsf::None => return next,
+            Tsf::SampleCount => (self.fractional + samples, sample_rate.round() as u64),
+            Tsf::RealTime => {
+                let ps = (samples as f64 * 1e12 / sample_rate).round() as u64;
+                (self.fractional + ps, 1_000_000_000_000)
+            }
+            Tsf::FreeRunning => (self.fractional + samples, 0),
+        };
+        if self.tsi != Tsi::None && period > 0 {
+            next.integer = self.integer.wrapping_add((fractional / period) as u32);
+            next.fractional = fractional % period;
+        } else {
+            next.fractional = fractional;
+        }
+        next
+    }
+
+    fn words(&self) -> usize {
+        (self.tsi != Tsi::None) as usize + 2 * (self.tsf != Tsf::None) as usize
+    }
+}
+
+/// Class identifier (OUI plus information and packet class codes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ClassId {
+    /// IEEE organizationally unique identifier (24 bits)
+    pub oui: u32,
+    pub information_class: u16,
+    pub packet_class: u16,
+    /// Pad bits at the end of the payload (VITA 49.2, 5 bits)
+    pub pad_bits: u8,
+}
+
+impl ClassId {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let word = ((self.pad_bits as u32 & 0x1f) << 27) | (self.oui & 0x00ff_ffff);
+        put_u32(out, word);
+        put_u32(out, ((self.information_class as u32) << 16) | self.packet_class as u32);
+    }
+
+    fn decode(w0: u32, w1: u32) -> Self {
+        Self {
+            oui: w0 & 0x00ff_ffff,
+            information_class: (w1 >> 16) as u16,
+            packet_class: w1 as u16,
+            pad_bits: (w0 >> 27) as u8,
+        }
+    }
+}
+
+/// Signal data trailer: state and event indicators with their enables
+///
+/// `None` means the indicator is not enabled (not reported).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Trailer {
+    pub calibrated_time: Option<bool>,
+    pub valid_data: Option<bool>,
+    pub reference_lock: Option<bool>,
+    /// `true` for AGC, `false` for manual gain
+    pub agc: Option<bool>,
+    pub detected_signal: Option<bool>,
+    pub spectral_inversion: Option<bool>,
+    pub over_range: Option<bool>,
+    pub sample_loss: Option<bool>,
+    /// Number of context packets associated with this data packet (7 bits)
+    pub associated_context_packets: Option<u8>,
+}
+
+impl Trailer {
+    fn indicators(&self) -> [Option<bool>; 8] {
+        [
+            self.calibrated_time,
+            self.valid_data,
+            self.reference_lock,
+            self.agc,
+            self.detected_signal,
+            self.spectral_inversion,
+            self.over_range,
+            self.sample_loss,
+        ]
+    }
+
+    /// Encode as a trailer word.
+    pub fn to_word(&self) -> u32 {
+        let mut word = 0u32;
+        for (i, indicator) in self.indicators().iter().enumerate() {
+            if let Some(value) = indicator {
+                word |= 1 << (31 - i);
+                word |= (*value as u32) << (19 - i);
+            }
+        }
+        if let Some(count) = self.associated_context_packets {
+            word |= (1 << 7) | (count as u32 & 0x7f);
+        }
+        word
+    }
+
+    /// Decode a trailer word.
+    pub fn from_word(word: u32) -> Self {
+        let get = |i: u32| ((word >> (31 - i)) & 1 == 1).then(|| (word >> (19 - i)) & 1 == 1);
+        Self {
+            calibrated_time: get(0),
+            valid_data: get(1),
+            reference_lock: get(2),
+            agc: get(3),
+            detected_signal: get(4),
+            spectral_inversion: get(5),
+            over_range: get(6),
+            sample_loss: get(7),
+            associated_context_packets: ((word >> 7) & 1 == 1).then_some((word & 0x7f) as u8),
+        }
+    }
+}
+
+/// Complex sample formats carried in signal data payloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PayloadFormat {
+    /// 8-bit signed I/Q (2 bytes per sample)
+    Ci8,
+    /// 16-bit signed I/Q (4 bytes per sample)
+    #[default]
+    Ci16,
+    /// IEEE-754 single-precision I/Q (8 bytes per sample)
+    Cf32,
+}
+
+impl PayloadFormat {
+    /// Bytes per complex sample.
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            Self::Ci8 => 2,
+            Self::Ci16 => 4,
+            Self::Cf32 => 8,
+        }
+    }
+
+    /// Short name (`ci8`, `ci16`, `cf32`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ci8 => "ci8",
+            Self::Ci16 => "ci16",
+            Self::Cf32 => "cf32",
+        }
+    }
+
+    /// Parse a short name.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "ci8" | "i8" | "sc8" => Some(Self::Ci8),
+            "ci16" | "i16" | "sc16" => Some(Self::Ci16),
+            "cf32" | "f32" | "fc32" => Some(Self::Cf32),
+            _ => None,
+        }
+    }
+
+    /// Context "data packet payload format" field.
+    pub fn to_field(self) -> u64 {
+        // Processing-efficient packing, complex cartesian
+        let (item_format, bits) = match self {
+            Self::Ci8 => (0b00000u64, 8u64),
+            Self::Ci16 => (0b00000, 16),
+            Self::Cf32 => (0b01110, 32),
+        };
+        let word = (0b01 << 29) | (item_format << 24) | ((bits - 1) << 6) | (bits - 1);
+        word << 32
+    }
+
+    /// Recognise a context "data packet payload format" field.
+    pub fn from_field(field: u64) -> Option<Self> {
+        let word = (field >> 32) as u32;
+        let complex_cartesian = (word >> 29) & 0x3 == 0b01;
+        let item_format = (word >> 24) & 0x1f;
+        let bits = (word & 0x3f) + 1;
+        match (complex_cartesian, item_format, bits) {
+            (true, 0b00000, 8) => Some(Self::Ci8),
+            (true, 0b00000, 16) => Some(Self::Ci16),
+            (true, 0b01110, 32) => Some(Self::Cf32),
+            _ => None,
+        }
+    }
+
+    fn encode(self, samples: &[IQSample], out: &mut Vec<u8>) {
+        match self {
+            Self::Ci8 => {
+                for s in samples {
+                    out.push(quantize(s.re, 127.0) as i8 as u8);
+                    out.push(quantize(s.im, 127.0) as i8 as u8);
+                }
+            }
+            Self::Ci16 => {
+                for s in samples {
+                    out.extend_from_slice(&(quantize(s.re, 32767.0) as i16).to_be_bytes());
+                    out.extend_from_slice(&(quantize(s.im, 32767.0) as i16).to_be_bytes());
+                }
+            }
+            Self::Cf32 => {
+                for s in samples {
+                    out.extend_from_slice(&(s.re as f32).to_be_bytes());
+                    out.extend_from_slice(&(s.im as f32).to_be_bytes());
+                }
+            }
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> Vec<IQSample> {
+        match self {
+            Self::Ci8 => bytes
+                .chunks_exact(2)
+                .map(|b| IQSample::new(b[0] as i8 as f64 / 127.0, b[1] as i8 as f64 / 127.0))
+                .collect(),
+            Self::Ci16 => bytes
+                .chunks_exact(4)
+                .map(|b| {
+                    IQSample::new(
+                        i16::from_be_bytes([b[0], b[1]]) as f64 / 32767.0,
+                        i16::from_be_bytes([b[2], b[3]]) as f64 / 32767.0,
+                    )
+                })
+                .collect(),
+            Self::Cf32 => bytes
+                .chunks_exact(8)
+                .map(|b| {
+                    IQSample::new(
+                        f32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                        f32::from_be_bytes([b[4], b[5], b[6], b[7]]) as f64,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+fn quantize(value: f64, scale: f64) -> f64 {
+    (value * scale).round().clamp(-scale - 1.0, scale)
+}
+
+fn put_u32(out: &mut Vec<u8>, word: u32) {
+    out.extend_from_slice(&word.to_be_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn to_fixed(value: f64, radix: i32) -> i64 {
+    (value * (1u64 << radix) as f64).round() as i64
+}
+
+fn from_fixed(value: i64, radix: i32) -> f64 {
+    value as f64 / (1u64 << radix) as f64
+}
+
+fn to_fixed16(value: f64, radix: i32) -> u32 {
+    (to_fixed(value, radix).clamp(i16::MIN as i64, i16::MAX as i64) as i16) as u16 as u32
+}
+
+fn from_fixed16(bits: u32, radix: i32) -> f64 {
+    from_fixed(bits as u16 as i16 as i64, radix)
+}
+
+/// Big-endian word reader over a packet
+struct Words<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Words<'a> {
+    fn u32(&mut self) -> Result<u32, VrtError> {
+        let end = self.pos + 4;
+        let b = self.bytes.get(self.pos..end).ok_or(VrtError::Truncated {
+            needed: end,
+            available: self.bytes.len(),
+        })?;
+        self.pos = end;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64, VrtError> {
+        Ok(((self.u32()? as u64) << 32) | self.u32()? as u64)
+    }
+
+    fn skip(&mut self, words: usize) -> Result<(), VrtError> {
+        for _ in 0..words {
+            self.u32()?;
+        }
+        Ok(())
+    }
+}
+
+/// Fields shared by all packet types
+struct Prologue {
+    packet_type: u8,
+    trailer: bool,
+    packet_count: u8,
+    stream_id: Option<u32>,
+    class_id: Option<ClassId>,
+    timestamp: VrtTimestamp,
+}
+
+fn read_prologue<'a>(bytes: &'a [u8]) -> Result<(Prologue, Words<'a>), VrtError> {
+    let mut words = Words { bytes, pos: 0 };
+    let header = words.u32()?;
+    let size = (header & 0xffff) as usize * 4;
+    if size < 4 {
+        return Err(VrtError::Invalid("zero packet size".to_string()));
+    }
+    if size > bytes.len() {
+        return Err(VrtError::Truncated {
+            needed: size,
+            available: bytes.len(),
+        });
+    }
+    words.bytes = &bytes[..size];
+
+    let packet_type = (header >> 28) as u8;
+    let has_stream_id = matches!(packet_type, 0x1 | 0x3 | 0x4 | 0x5 | 0x6 | 0x7);
+    let stream_id = if has_stream_id { Some(words.u32()?) } else { None };
+    let class_id = if header & (1 << 27) != 0 {
+        let (w0, w1) = (words.u32()?, words.u32()?);
+        Some(ClassId::decode(w0, w1))
+    } else {
+        None
+    };
+
+    let mut timestamp = VrtTimestamp {
+        tsi: Tsi::from_bits(header >> 22),
+        tsf: Tsf::from_bits(header >> 20),
+        ..Default::default()
+    };
+    if timestamp.tsi != Tsi::None {
+        timestamp.integer = words.u32()?;
+    }
+    if timestamp.tsf != Tsf::None {
+        timestamp.fractional = words.u64()?;
+    }
+
+    let prologue = Prologue {
+        packet_type,
+        trailer: packet_type <= 0x3 && header & (1 << 26) != 0,
+        packet_count: ((header >> 16) & 0xf) as u8,
+        stream_id,
+        class_id,
+        timestamp,
+    };
+    Ok((prologue, words))
+}
+
+fn write_prologue(
+    out: &mut Vec<u8>,
+    packet_type: PacketType,
+    trailer: bool,
+    packet_count: u8,
+    stream_id: Option<u32>,
+    class_id: Option<&ClassId>,
+    timestamp: &VrtTimestamp,
+) {
+    let header = (packet_type.code() << 28)
+        | ((class_id.is_some() as u32) << 27)
+        | ((trailer as u32) << 26)
+        | (timestamp.tsi.bits() << 22)
+        | (timestamp.tsf.bits() << 20)
+        | ((packet_count as u32 & 0xf) << 16);
+    put_u32(out, header);
+    if let Some(id) = stream_id {
+        put_u32(out, id);
+    }
+    if let Some(class_id) = class_id {
+        class_id.encode(out);
+    }
+    if timestamp.tsi != Tsi::None {
+        put_u32(out, timestamp.integer);
+    }
+    if timestamp.tsf != Tsf::None {
+        put_u64(out, timestamp.fractional);
+    }
+}
+
+/// Fill in the packet size field once the packet is complete.
+fn finish(mut out: Vec<u8>) -> Result<Vec<u8>, VrtError> {
+    let words = out.len() / 4;
+    if words > MAX_PACKET_WORDS {
+        return Err(VrtError::TooLarge(words));
+    }
+    let header = u32::from_be_bytes([out[0], out[1], out[2], out[3]]) | words as u32;
+    out[..4].copy_from_slice(&header.to_be_bytes());
+    Ok(out)
+}
+
+/// Signal data packet
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DataPacket {
+    /// Stream identifier (`None` encodes packet type 0)
+    pub stream_id: Option<u32>,
+    pub class_id: Option<ClassId>,
+    pub timestamp: VrtTimestamp,
+    /// Modulo-16 packet counter
+    pub packet_count: u8,
+    pub trailer: Option<Trailer>,
+    /// Raw payload, a whole number of 32-bit words
+    pub payload: Vec<u8>,
+}
+
+impl DataPacket {
+    /// Packet carrying `samples` in `format`.
+    ///
+    /// A `ci8` payload with an odd number of samples is padded to a whole
+    /// word; the pad is recorded in the class ID as VITA 49.2 specifies.
+    pub fn from_samples(samples: &[IQSample], format: PayloadFormat) -> Self {
+        let mut payload = Vec::with_capacity(samples.len() * format.bytes_per_sample() + 2);
+        format.encode(samples, &mut payload);
+        let pad_bytes = (4 - payload.len() % 4) % 4;
+        payload.resize(payload.len() + pad_bytes, 0);
+
+        Self {
+            class_id: (pad_bytes > 0).then(|| ClassId {
+                pad_bits: (pad_bytes * 8) as u8,
+                ..Default::default()
+            }),
+            payload,
+            ..Default::default()
+        }
+    }
+
+    /// Decode the payload as `format`, dropping any pad bits.
+    pub fn samples(&self, format: PayloadFormat) -> Vec<IQSample> {
+        let pad_bytes = self.class_id.map_or(0, |c| c.pad_bits as usize / 8);
+        let len = self.payload.len().saturating_sub(pad_bytes);
+        format.decode(&self.payload[..len])
+    }
+
+    /// Encode to bytes.
+    pub fn encode(&self) -> Result<Vec<u8>, VrtError> {
+        if !self.payload.len().is_multiple_of(4) {
+            return Err(VrtError::Invalid("payload is not a whole number of words".to_string()));
+        }
+        let packet_type = match self.stream_id {
+            Some(_) => PacketType::SignalDataWithStreamId,
+            None => PacketType::SignalData,
+        };
+        let mut out = Vec::with_capacity(28 + self.payload.len() + 4);
+        write_prologue(
+            &mut out,
+            packet_type,
+            self.trailer.is_some(),
+            self.packet_count,
+            self.stream_id,
+            self.class_id.as_ref(),
+            &self.timestamp,
+        );
+        out.extend_from_slice(&self.payload);
+        if let Some(trailer) = &self.trailer {
+            put_u32(&mut out, trailer.to_word());
+        }
+        finish(out)
+    }
+
+    /// Encoded size in bytes.
+    pub fn encoded_len(&self) -> usize {
+        4 * (1
+            + self.stream_id.is_some() as usize
+            + 2 * self.class_id.is_some() as usize
+            + self.timestamp.words()
+            + self.trailer.is_some() as usize)
+            + self.payload.len()
+    }
+}
+
+/// Context packet (IF context, CIF0 fields)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ContextPacket {
+    pub stream_id: u32,
+    pub class_id: Option<ClassId>,
+    pub timestamp: VrtTimestamp,
+    /// Modulo-16 packet counter
+    pub packet_count: u8,
+    /// Some context field changed since the previous packet
+    pub change_indicator: bool,
+    pub reference_point_id: Option<u32>,
+    pub bandwidth_hz: Option<f64>,
+    pub if_reference_hz: Option<f64>,
+    pub rf_reference_hz: Option<f64>,
+    pub rf_offset_hz: Option<f64>,
+    pub if_band_offset_hz: Option<f64>,
+    pub reference_level_dbm: Option<f64>,
+    /// Total gain (stage 1 + stage 2; encoded entirely in stage 1)
+    pub gain_db: Option<f64>,
+    pub over_range_count: Option<u32>,
+    pub sample_rate_hz: Option<f64>,
+    /// Timestamp adjustment (femtoseconds)
+    pub timestamp_adjustment_fs: Option<i64>,
+    pub temperature_c: Option<f64>,
+    /// Payload format of the paired data stream (`None` if absent or unrecognised)
+    pub data_format: Option<PayloadFormat>,
+}
+
+// CIF0 field enables
+const CIF_CHANGE: u32 = 1 << 31;
+const CIF_REF_POINT: u32 = 1 << 30;
+const CIF_BANDWIDTH: u32 = 1 << 29;
+const CIF_IF_REF: u32 = 1 << 28;
+const CIF_RF_REF: u32 = 1 << 27;
+const CIF_RF_OFFSET: u32 = 1 << 26;
+const CIF_IF_OFFSET: u32 = 1 << 25;
+const CIF_REF_LEVEL: u32 = 1 << 24;
+const CIF_GAIN: u32 = 1 << 23;
+const CIF_OVER_RANGE: u32 = 1 << 22;
+const CIF_SAMPLE_RATE: u32 = 1 << 21;
+const CIF_TS_ADJUST: u32 = 1 << 20;
+const CIF_TS_CAL: u32 = 1 << 19;
+const CIF_TEMPERATURE: u32 = 1 << 18;
+const CIF_DEVICE_ID: u32 = 1 << 17;
+const CIF_STATE_EVENT: u32 = 1 << 16;
+const CIF_DATA_FORMAT: u32 = 1 << 15;
+
+/// Fixed-size CIF0 fields after the data format, as (enable bit, words).
+/// Decoding stops at the first variable-length field (GPS ASCII, context
+/// association lists), which follow these.
+const CIF_FIXED_TAIL: [(u32, usize); 5] = [(14, 11), (13, 11), (12, 13), (11, 13), (10, 1)];
+
+impl ContextPacket {
+    /// Context packet for `stream_id` with no fields set.
+    pub fn new(stream_id: u32) -> Self {
+        Self {
+            stream_id,
+            ..Default::default()
+        }
+    }
+
+    fn cif0(&self) -> u32 {
+        let mut cif = 0;
+        let flags = [
+            (self.change_indicator, CIF_CHANGE),
+            (self.reference_point_id.is_some(), CIF_REF_POINT),
+            (self.bandwidth_hz.is_some(), CIF_BANDWIDTH),
+            (self.if_reference_hz.is_some(), CIF_IF_REF),
+            (self.rf_reference_hz.is_some(), CIF_RF_REF),
+            (self.rf_offset_hz.is_some(), CIF_RF_OFFSET),
+            (self.if_band_offset_hz.is_some(), CIF_IF_OFFSET),
+            (self.reference_level_dbm.is_some(), CIF_REF_LEVEL),
+            (self.gain_db.is_some(), CIF_GAIN),
+            (self.over_range_count.is_some(), CIF_OVER_RANGE),
+            (self.sample_rate_hz.is_some(), CIF_SAMPLE_RATE),
+            (self.timestamp_adjustment_fs.is_some(), CIF_TS_ADJUST),
+            (self.temperature_c.is_some(), CIF_TEMPERATURE),
+            (self.data_format.is_some(), CIF_DATA_FORMAT),
+        ];
+        for (present, bit) in flags {
+            if present {
+                cif |= bit;
+            }
+        }
+        cif
+    }
+
+    /// Encode to bytes.
+    pub fn encode(&self) -> Result<Vec<u8>, VrtError> {
+        let mut out = Vec::with_capacity(96);
+        write_prologue(
+            &mut out,
+            PacketType::Context,
+            false,
+            self.packet_count,
+            Some(self.stream_id),
+            self.class_id.as_ref(),
+            &self.timestamp,
+        );
+        put_u32(&mut out, self.cif0());
+
+        let freq = |v: f64| to_fixed(v, FREQ_RADIX) as u64;
+        if let Some(id) = self.reference_point_id {
+            put_u32(&mut out, id);
+        }
+        for value in [
+            self.bandwidth_hz,
+            self.if_reference_hz,
+            self.rf_reference_hz,
+            self.rf_offset_hz,
+            self.if_band_offset_hz,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            put_u64(&mut out, freq(value));
+        }
+        if let Some(level) = self.reference_level_dbm {
+            put_u32(&mut out, to_fixed16(level, GAIN_RADIX));
+        }
+        if let Some(gain) = self.gain_db {
+            put_u32(&mut out, to_fixed16(gain, GAIN_RADIX));
+        }
+        if let Some(count) = self.over_range_count {
+            put_u32(&mut out, count);
+        }
+        if let Some(rate) = self.sample_rate_hz {
+            put_u64(&mut out, freq(rate));
+        }
+        if let Some(adjust) = self.timestamp_adjustment_fs {
+            put_u64(&mut out, adjust as u64);
+        }
+        if let Some(temp) = self.temperature_c {
+            put_u32(&mut out, to_fixed16(temp, TEMP_RADIX));
+        }
+        if let Some(format) = self.data_format {
+            put_u64(&mut out, format.to_field());
+        }
+        finish(out)
+    }
+
+    fn decode_fields(&mut self, words: &mut Words<'_>) -> Result<(), VrtError> {
+        let cif0 = words.u32()?;
+        // CIF1..CIF7 enable words (VITA 49.2) precede all fields
+        let extra_cifs = [1, 2, 3, 7].iter().filter(|&&b| cif0 & (1 << b) != 0).count();
+        words.skip(extra_cifs)?;
+
+        let has = |bit: u32| cif0 & bit != 0;
+        let freq = |v: u64| from_fixed(v as i64, FREQ_RADIX);
+
+        self.change_indicator = has(CIF_CHANGE);
+        if has(CIF_REF_POINT) {
+            self.reference_point_id = Some(words.u32()?);
+        }
+        if has(CIF_BANDWIDTH) {
+            self.bandwidth_hz = Some(freq(words.u64()?));
+        }
+        if has(CIF_IF_REF) {
+            self.if_reference_hz = Some(freq(words.u64()?));
+        }
+        if has(CIF_RF_REF) {
+            self.rf_reference_hz = Some(freq(words.u64()?));
+        }
+        if has(CIF_RF_OFFSET) {
+            self.rf_offset_hz = Some(freq(words.u64()?));
+        }
+        if has(CIF_IF_OFFSET) {
+            self.if_band_offset_hz = Some(freq(words.u64()?));
+        }
+        if has(CIF_REF_LEVEL) {
+            self.reference_level_dbm = Some(from_fixed16(words.u32()?, GAIN_RADIX));
+        }
+        if has(CIF_GAIN) {
+            let word = words.u32()?;
+            self.gain_db = Some(from_fixed16(word, GAIN_RADIX) + from_fixed16(word >> 16, GAIN_RADIX));
+        }
+        if has(CIF_OVER_RANGE) {
+            self.over_range_count = Some(words.u32()?);
+        }
+        if has(CIF_SAMPLE_RATE) {
+            self.sample_rate_hz = Some(freq(words.u64()?));
+        }
+        if has(CIF_TS_ADJUST) {
+            self.timestamp_adjustment_fs = Some(words.u64()? as i64);
+        }
+        if has(CIF_TS_CAL) {
+            words.skip(1)?;
+        }
+        if has(CIF_TEMPERATURE) {
+            self.temperature_c = Some(from_fixed16(words.u32()?, TEMP_RADIX));
+        }
+        if has(CIF_DEVICE_ID) {
+            words.skip(2)?;
+        }
+        if has(CIF_STATE_EVENT) {
+            words.skip(1)?;
+        }
+        if has(CIF_DATA_FORMAT) {
+            self.data_format = PayloadFormat::from_field(words.u64()?);
+        }
+        for (bit, len) in CIF_FIXED_TAIL {
+            if has(1 << bit) {
+                words.skip(len)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Any decoded VRT packet
+#[derive(Debug, Clone, PartialEq)]
+pub enum VrtPacket {
+    Data(DataPacket),
+    Context(ContextPacket),
+}
+
+impl VrtPacket {
+    /// Decode one packet from the start of `bytes`.
+    ///
+    /// Bytes beyond the packet size field are ignored.
+    pub fn decode(bytes: &[u8]) -> Result<Self, VrtError> {
+        let (prologue, mut words) = read_prologue(bytes)?;
+        match prologue.packet_type {
+            0x0 | 0x1 => {
+                let end = words.bytes.len() - 4 * prologue.trailer as usize;
+                if end < words.pos {
+                    return Err(VrtError::Invalid("trailer overlaps header".to_string()));
+                }
+                let trailer = if prologue.trailer {
+                    let b = &words.bytes[end..];
+                    Some(Trailer::from_word(u32::from_be_bytes([b[0], b[1], b[2], b[3]])))
+                } else {
+                    None
+                };
+                Ok(Self::Data(DataPacket {
+                    stream_id: prologue.stream_id,
+                    class_id: prologue.class_id,
+                    timestamp: prologue.timestamp,
+                    packet_count: prologue.packet_count,
+                    trailer,
+                    payload: words.bytes[words.pos..end].to_vec(),
+                }))
+            }
+            0x4 => {
+                let mut packet = ContextPacket {
+                    stream_id: prologue.stream_id.unwrap_or(0),
+                    class_id: prologue.class_id,
+                    timestamp: prologue.timestamp,
+                    packet_count: prologue.packet_count,
+                    ..Default::default()
+                };
+                packet.decode_fields(&mut words)?;
+                Ok(Self::Context(packet))
+            }
+            other => Err(VrtError::UnsupportedType(other)),
+        }
+    }
+
+    /// Encode to bytes.
+    pub fn encode(&self) -> Result<Vec<u8>, VrtError> {
+        match self {
+            Self::Data(p) => p.encode(),
+            Self::Context(p) => p.encode(),
+        }
+    }
+
+    /// Stream identifier, if the packet carries one.
+    pub fn stream_id(&self) -> Option<u32> {
+        match self {
+            Self::Data(p) => p.stream_id,
+            Self::Context(p) => Some(p.stream_id),
+        }
+    }
+
+    /// Packet timestamp.
+    pub fn timestamp(&self) -> &VrtTimestamp {
+        match self {
+            Self::Data(p) => &p.timestamp,
+            Self::Context(p) => &p.timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(n: usize) -> Vec<IQSample> {
+        (0..n)
+            .map(|i| IQSample::new((i as f64 / n as f64) - 0.5, 0.25 - (i as f64 / n as f64) * 0.5))
+            .collect()
+    }
+
+    #[test]
+    fn test_data_packet_roundtrip() {
+        let samples = ramp(101);
+        for format in [PayloadFormat::Ci8, PayloadFormat::Ci16, PayloadFormat::Cf32] {
+            let mut packet = DataPacket::from_samples(&samples, format);
+            packet.stream_id = Some(0xdead_beef);
+            packet.timestamp = VrtTimestamp::from_utc_nanos(1_700_000_000_123_456_789);
+            packet.packet_count = 13;
+            packet.trailer = Some(Trailer {
+                valid_data: Some(true),
+                reference_lock: Some(false),
+                sample_loss: Some(false),
+                associated_context_packets: Some(1),
+                ..Default::default()
+            });
+
+            let bytes = packet.encode().unwrap();
+            assert_eq!(bytes.len(), packet.encoded_len());
+            assert_eq!(bytes.len() % 4, 0);
+
+            let decoded = match VrtPacket::decode(&bytes).unwrap() {
+                VrtPacket::Data(p) => p,
+                other => panic!("unexpected {:?}", other),
+            };
+            assert_eq!(decoded, packet);
+            assert_eq!(decoded.timestamp.utc_nanos(), Some(1_700_000_000_123_456_789));
+
+            let rx = decoded.samples(format);
+            assert_eq!(rx.len(), samples.len(), "{}", format.name());
+            let tolerance = if format == PayloadFormat::Ci8 { 1.0 / 127.0 } else { 1e-4 };
+            for (a, b) in rx.iter().zip(&samples) {
+                assert!((a - b).norm() < tolerance, "{}: {} vs {}", format.name(), a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_header_layout() {
+        // Packet type 1, trailer, TSI none, TSF sample count, count 5, 3 words + 2 ts + 1 trailer
+        let mut packet = DataPacket::from_samples(&[IQSample::new(0.0, 0.0)], PayloadFormat::Ci16);
+        packet.stream_id = Some(7);
+        packet.timestamp = VrtTimestamp::sample_count(0x1_0000_0002);
+        packet.packet_count = 5;
+        packet.trailer = Some(Trailer::default());
+        let bytes = packet.encode().unwrap();
+
+        assert_eq!(&bytes[..4], &[0x14, 0x15, 0x00, 0x06]);
+        assert_eq!(&bytes[4..8], &[0, 0, 0, 7]);
+        assert_eq!(&bytes[8..16], &[0, 0, 0, 1, 0, 0, 0, 2]);
+
+        // Packet type 0 omits the stream ID
+        packet.stream_id = None;
+        let bytes = packet.encode().unwrap();
+        assert_eq!(bytes[0] >> 4, 0);
+        assert_eq!(bytes.len(), 20);
+        assert!(matches!(VrtPacket::decode(&bytes).unwrap(), VrtPacket::Data(p) if p.stream_id.is_none()));
+    }
+
+    #[test]
+    fn test_context_packet_roundtrip() {
+        let packet = ContextPacket {
+            stream_id: 0x1234,
+            timestamp: VrtTimestamp::from_utc_nanos(1_000_000_000_500),
+            packet_count: 2,
+            change_indicator: true,
+            reference_point_id: Some(0x64),
+            bandwidth_hz: Some(20e6),
+            if_reference_hz: Some(-1.25e6),
+            rf_reference_hz: Some(2_437_000_000.5),
+            reference_level_dbm: Some(-20.0),
+            gain_db: Some(31.5),
+            over_range_count: Some(3),
+            sample_rate_hz: Some(30.72e6),
+            timestamp_adjustment_fs: Some(-250_000),
+            temperature_c: Some(41.25),
+            data_format: Some(PayloadFormat::Ci16),
+            ..Default::default()
+        };
+        let bytes = packet.encode().unwrap();
+        assert_eq!(bytes[0] >> 4, 4);
+
+        match VrtPacket::decode(&bytes).unwrap() {
+            VrtPacket::Context(decoded) => assert_eq!(decoded, packet),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_context_skips_unmodelled_fields() {
+        // Hand-built packet: CIF0 = RF reference + device ID + sample rate + formatted GPS
+        let mut bytes = Vec::new();
+        put_u32(&mut bytes, 0x4000_0000);
+        put_u32(&mut bytes, 9);
+        put_u32(&mut bytes, CIF_RF_REF | CIF_SAMPLE_RATE | CIF_DEVICE_ID | (1 << 14));
+        put_u64(&mut bytes, to_fixed(915e6, FREQ_RADIX) as u64);
+        put_u64(&mut bytes, to_fixed(1e6, FREQ_RADIX) as u64);
+        put_u64(&mut bytes, 0x00ab_cdef_0000_0001);
+        for _ in 0..11 {
+            put_u32(&mut bytes, 0xffff_ffff);
+        }
+        let bytes = finish(bytes).unwrap();
+
+        match VrtPacket::decode(&bytes).unwrap() {
+            VrtPacket::Context(p) => {
+                assert_eq!(p.stream_id, 9);
+                assert_eq!(p.rf_reference_hz, Some(915e6));
+                assert_eq!(p.sample_rate_hz, Some(1e6));
+                assert_eq!(p.gain_db, None);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut packet = DataPacket::from_samples(&ramp(8), PayloadFormat::Ci16);
+        packet.stream_id = Some(1);
+        let bytes = packet.encode().unwrap();
+
+        assert!(matches!(VrtPacket::decode(&bytes[..bytes.len() - 4]), Err(VrtError::Truncated { .. })));
+        assert!(matches!(VrtPacket::decode(&bytes[..2]), Err(VrtError::Truncated { .. })));
+
+        let mut command = bytes.clone();
+        command[0] = 0x60 | (command[0] & 0x0f);
+        assert_eq!(VrtPacket::decode(&command), Err(VrtError::UnsupportedType(6)));
+
+        let huge = DataPacket::from_samples(&vec![IQSample::new(0.0, 0.0); 70_000], PayloadFormat::Ci16);
+        assert!(matches!(huge.encode(), Err(VrtError::TooLarge(_))));
+    }
+
+    #[test]
+    fn test_timestamp_advance() {
+        let free = VrtTimestamp::sample_count(10).advance(5_000_000, 1e6);
+        assert_eq!(free.sample_index(), Some(5_000_010));
+
+        let utc = VrtTimestamp::from_utc_nanos(999_999_000).advance(2_000, 1e6);
+        assert_eq!(utc.utc_nanos(), Some(1_001_999_000));
+        assert_eq!(utc.integer, 1);
+
+        let counted = VrtTimestamp {
+            tsi: Tsi::Gps,
+            integer: 7,
+            tsf: Tsf::SampleCount,
+            fractional: 999_000,
+        }
+        .advance(1_500, 1e6);
+        assert_eq!((counted.integer, counted.fractional), (8, 500));
+    }
+
+    #[test]
+    fn test_trailer_and_format_fields() {
+        let trailer = Trailer {
+            calibrated_time: Some(true),
+            over_range: Some(true),
+            sample_loss: Some(false),
+            ..Default::default()
+        };
+        let word = trailer.to_word();
+        assert_eq!(word, 0x8300_0000 | 0x0008_2000);
+        assert_eq!(Trailer::from_word(word), trailer);
+
+        for format in [PayloadFormat::Ci8, PayloadFormat::Ci16, PayloadFormat::Cf32] {
+            assert_eq!(PayloadFormat::from_field(format.to_field()), Some(format));
+            assert_eq!(PayloadFormat::parse(format.name()), Some(format));
+        }
+    }
+}
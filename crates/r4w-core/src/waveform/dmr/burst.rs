@@ -0,0 +1,888 @@
+//! DMR Burst Layer
+//!
+//! Layer 2 structures carried in each 30 ms TDMA slot (ETSI TS 102 361-1):
+//!
+//! ```text
+//! Repeater (BS) slot, 144 symbols:
+//! ┌──────┬──────────┬──────┬────────────┬──────┬──────────┐
+//! │ CACH │  Info    │ Slot │ SYNC or    │ Slot │  Info    │
+//! │  24  │  98 bits │ Type │ EMB+embed  │ Type │  98 bits │
+//! │ bits │          │  10  │  48 bits   │  10  │          │
+//! └──────┴──────────┴──────┴────────────┴──────┴──────────┘
+//!
+//! Voice burst: 108 voice bits │ SYNC or EMB+embedded │ 108 voice bits
+//! ```
+//!
+//! - **CACH**: Common Announcement Channel with Hamming(7,4) protected TACT
+//!   (access type, TDMA channel, LCSS) plus 17 bits of short LC payload
+//! - **Slot Type**: colour code and data type, Golay(20,8) protected
+//! - **EMB**: colour code, privacy indicator and LCSS for voice bursts B-F,
+//!   QR(16,7) protected
+//! - **Info**: 196 bits, BPTC(196,96) coded for control and rate ½ data,
+//!   uncoded for rate 1 data
+
+use super::fec::{
+    bits_to_bytes, bits_to_value, bytes_to_bits, crc_ccitt, hamming_7_4_decode,
+    hamming_7_4_encode, value_to_bits, Bptc19696, Golay2087, Qr1676, Rs129,
+};
+use super::DmrSyncType;
+
+/// Bits in a DMR burst (excluding CACH)
+pub const BURST_BITS: usize = 264;
+/// Bits in a CACH
+pub const CACH_BITS: usize = 24;
+/// Offset of the SYNC/EMB field within a burst
+pub const SYNC_OFFSET: usize = 108;
+/// Length of the SYNC/EMB field
+pub const SYNC_BITS: usize = 48;
+
+/// CRC mask applied to CSBK CRC-CCITT
+const CSBK_CRC_MASK: u16 = 0xA5A5;
+/// CRC mask applied to data header CRC-CCITT
+const DATA_HEADER_CRC_MASK: u16 = 0xCCCC;
+/// RS(12,9) parity mask for voice LC header
+const VOICE_LC_HEADER_MASK: [u8; 3] = [0x96, 0x96, 0x96];
+/// RS(12,9) parity mask for terminator with LC
+const TERMINATOR_LC_MASK: [u8; 3] = [0x99, 0x99, 0x99];
+
+/// CACH bit positions carrying the 7 Hamming-coded TACT bits
+const TACT_POSITIONS: [usize; 7] = [0, 4, 8, 12, 14, 18, 22];
+
+/// Slot type data type field (4 bits)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataType {
+    /// Privacy indicator header
+    PiHeader,
+    /// Voice link control header
+    VoiceLcHeader,
+    /// Terminator with link control
+    TerminatorWithLc,
+    /// Control signalling block
+    Csbk,
+    /// Multi-block control header
+    MbcHeader,
+    /// Multi-block control continuation
+    MbcContinuation,
+    /// Data header
+    DataHeader,
+    /// Rate ½ coded data
+    Rate12Data,
+    /// Rate ¾ coded data
+    Rate34Data,
+    /// Idle burst
+    Idle,
+    /// Rate 1 (uncoded) data
+    Rate1Data,
+}
+
+impl DataType {
+    /// 4-bit on-air value
+    pub fn to_u8(self) -> u8 {
+        match self {
+            Self::PiHeader => 0,
+            Self::VoiceLcHeader => 1,
+            Self::TerminatorWithLc => 2,
+            Self::Csbk => 3,
+            Self::MbcHeader => 4,
+            Self::MbcContinuation => 5,
+            Self::DataHeader => 6,
+            Self::Rate12Data => 7,
+            Self::Rate34Data => 8,
+            Self::Idle => 9,
+            Self::Rate1Data => 10,
+        }
+    }
+
+    /// Parse from 4-bit on-air value (11-15 are reserved)
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value & 0x0F {
+            0 => Some(Self::PiHeader),
+            1 => Some(Self::VoiceLcHeader),
+            2 => Some(Self::TerminatorWithLc),
+            3 => Some(Self::Csbk),
+            4 => Some(Self::MbcHeader),
+            5 => Some(Self::MbcContinuation),
+            6 => Some(Self::DataHeader),
+            7 => Some(Self::Rate12Data),
+            8 => Some(Self::Rate34Data),
+            9 => Some(Self::Idle),
+            10 => Some(Self::Rate1Data),
+            _ => None,
+        }
+    }
+}
+
+/// Slot type field: colour code + data type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotType {
+    /// Colour code (0-15)
+    pub color_code: u8,
+    /// Data type of the burst
+    pub data_type: DataType,
+}
+
+impl SlotType {
+    /// Encode into 20 Golay(20,8) bits
+    pub fn encode(&self) -> Vec<u8> {
+        let data = ((self.color_code & 0x0F) << 4) | self.data_type.to_u8();
+        value_to_bits(Golay2087::encode(data), 20)
+    }
+
+    /// Decode from 20 bits, correcting up to 3 errors
+    pub fn decode(bits: &[u8]) -> Option<Self> {
+        let data = Golay2087::decode(bits_to_value(&bits[..20]))?;
+        Some(Self {
+            color_code: data >> 4,
+            data_type: DataType::from_u8(data & 0x0F)?,
+        })
+    }
+}
+
+/// Link control start/stop indicator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lcss {
+    /// Single fragment LC or first fragment of CSBK signalling
+    #[default]
+    SingleFragment,
+    /// First fragment of LC signalling
+    FirstFragment,
+    /// Last fragment of LC or CSBK signalling
+    LastFragment,
+    /// Continuation fragment of LC or CSBK signalling
+    Continuation,
+}
+
+impl Lcss {
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::SingleFragment => 0,
+            Self::FirstFragment => 1,
+            Self::LastFragment => 2,
+            Self::Continuation => 3,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value & 0x03 {
+            0 => Self::SingleFragment,
+            1 => Self::FirstFragment,
+            2 => Self::LastFragment,
+            _ => Self::Continuation,
+        }
+    }
+}
+
+/// Common Announcement Channel (repeater outbound only)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cach {
+    /// Access type: `true` when the inbound channel is busy
+    pub busy: bool,
+    /// TDMA channel (timeslot, 0 or 1) of the following burst
+    pub timeslot: u8,
+    /// Short LC fragment indicator
+    pub lcss: Lcss,
+    /// 17-bit short LC payload fragment
+    pub payload: u32,
+}
+
+impl Cach {
+    /// Encode into 24 interleaved bits
+    pub fn encode(&self) -> Vec<u8> {
+        let tact = [
+            self.busy as u8,
+            self.timeslot & 1,
+            (self.lcss.to_u8() >> 1) & 1,
+            self.lcss.to_u8() & 1,
+        ];
+        let tact = hamming_7_4_encode(&tact);
+        let payload = value_to_bits(self.payload & 0x1_FFFF, 17);
+
+        let mut out = vec![0u8; CACH_BITS];
+        let mut tact_iter = tact.iter();
+        let mut payload_iter = payload.iter();
+        for (i, bit) in out.iter_mut().enumerate() {
+            *bit = if TACT_POSITIONS.contains(&i) {
+                *tact_iter.next().unwrap_or(&0)
+            } else {
+                *payload_iter.next().unwrap_or(&0)
+            };
+        }
+        out
+    }
+
+    /// Decode from 24 bits; `None` if the TACT is uncorrectable
+    pub fn decode(bits: &[u8]) -> Option<Self> {
+        let mut tact: Vec<u8> = TACT_POSITIONS.iter().map(|&i| bits[i]).collect();
+        if !hamming_7_4_decode(&mut tact) {
+            return None;
+        }
+        let payload: Vec<u8> = (0..CACH_BITS)
+            .filter(|i| !TACT_POSITIONS.contains(i))
+            .map(|i| bits[i])
+            .collect();
+
+        Some(Self {
+            busy: tact[0] == 1,
+            timeslot: tact[1],
+            lcss: Lcss::from_u8((tact[2] << 1) | tact[3]),
+            payload: bits_to_value(&payload),
+        })
+    }
+}
+
+/// Embedded signalling (EMB) field of voice bursts B-F
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Emb {
+    /// Colour code (0-15)
+    pub color_code: u8,
+    /// Privacy indicator
+    pub privacy: bool,
+    /// Embedded LC fragment indicator
+    pub lcss: Lcss,
+}
+
+impl Emb {
+    /// Encode into 16 QR(16,7) bits
+    pub fn encode(&self) -> Vec<u8> {
+        let data = ((self.color_code & 0x0F) << 3) | ((self.privacy as u8) << 2) | self.lcss.to_u8();
+        value_to_bits(Qr1676::encode(data) as u32, 16)
+    }
+
+    /// Decode from 16 bits, correcting up to 2 errors
+    pub fn decode(bits: &[u8]) -> Option<Self> {
+        let data = Qr1676::decode(bits_to_value(&bits[..16]) as u16)?;
+        Some(Self {
+            color_code: data >> 3,
+            privacy: (data >> 2) & 1 == 1,
+            lcss: Lcss::from_u8(data),
+        })
+    }
+}
+
+/// Full link control opcode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flco {
+    /// Group voice channel user
+    GroupVoice,
+    /// Unit to unit voice channel user
+    UnitToUnit,
+    /// Any other opcode
+    Other(u8),
+}
+
+impl Flco {
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::GroupVoice => 0x00,
+            Self::UnitToUnit => 0x03,
+            Self::Other(v) => v & 0x3F,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value & 0x3F {
+            0x00 => Self::GroupVoice,
+            0x03 => Self::UnitToUnit,
+            v => Self::Other(v),
+        }
+    }
+}
+
+/// Full link control (72 bits) carried in voice LC header and terminator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FullLc {
+    /// Protect flag
+    pub protect: bool,
+    /// Full link control opcode
+    pub flco: Flco,
+    /// Feature set ID (0 = standard)
+    pub fid: u8,
+    /// Service options (emergency, privacy, broadcast, priority)
+    pub service_options: u8,
+    /// Destination (group or unit) ID, 24 bits
+    pub dst_id: u32,
+    /// Source unit ID, 24 bits
+    pub src_id: u32,
+}
+
+impl FullLc {
+    /// Group voice call from `src_id` to talkgroup `dst_id`
+    pub fn group_voice(dst_id: u32, src_id: u32) -> Self {
+        Self {
+            protect: false,
+            flco: Flco::GroupVoice,
+            fid: 0,
+            service_options: 0,
+            dst_id: dst_id & 0xFF_FFFF,
+            src_id: src_id & 0xFF_FFFF,
+        }
+    }
+
+    /// Private (unit to unit) voice call
+    pub fn unit_to_unit(dst_id: u32, src_id: u32) -> Self {
+        Self {
+            flco: Flco::UnitToUnit,
+            ..Self::group_voice(dst_id, src_id)
+        }
+    }
+
+    /// Serialize to 9 bytes
+    pub fn to_bytes(&self) -> [u8; 9] {
+        [
+            ((self.protect as u8) << 7) | self.flco.to_u8(),
+            self.fid,
+            self.service_options,
+            (self.dst_id >> 16) as u8,
+            (self.dst_id >> 8) as u8,
+            self.dst_id as u8,
+            (self.src_id >> 16) as u8,
+            (self.src_id >> 8) as u8,
+            self.src_id as u8,
+        ]
+    }
+
+    /// Parse from 9 bytes
+    pub fn from_bytes(bytes: &[u8; 9]) -> Self {
+        Self {
+            protect: bytes[0] & 0x80 != 0,
+            flco: Flco::from_u8(bytes[0]),
+            fid: bytes[1],
+            service_options: bytes[2],
+            dst_id: u24(&bytes[3..6]),
+            src_id: u24(&bytes[6..9]),
+        }
+    }
+
+    /// Encode into 96 bits (LC + masked RS(12,9) parity)
+    fn encode(&self, mask: [u8; 3]) -> Vec<u8> {
+        let lc = self.to_bytes();
+        let parity = Rs129::encode(&lc);
+        let mut bytes = lc.to_vec();
+        bytes.extend(parity.iter().zip(mask).map(|(p, m)| p ^ m));
+        bytes_to_bits(&bytes)
+    }
+
+    /// Decode from 96 bits, correcting one byte error with RS(12,9)
+    fn decode(bits: &[u8], mask: [u8; 3]) -> Option<Self> {
+        let bytes = bits_to_bytes(&bits[..96]);
+        let mut codeword = [0u8; 12];
+        codeword.copy_from_slice(&bytes);
+        for (c, m) in codeword[9..].iter_mut().zip(mask) {
+            *c ^= m;
+        }
+        if !Rs129::decode(&mut codeword) {
+            return None;
+        }
+        let mut lc = [0u8; 9];
+        lc.copy_from_slice(&codeword[..9]);
+        Some(Self::from_bytes(&lc))
+    }
+}
+
+/// Control Signalling Block (96 bits including CRC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Csbk {
+    /// Last block flag
+    pub last_block: bool,
+    /// Protect flag
+    pub protect: bool,
+    /// CSBK opcode (6 bits)
+    pub opcode: u8,
+    /// Feature set ID
+    pub fid: u8,
+    /// Opcode-specific data (64 bits)
+    pub data: [u8; 8],
+}
+
+impl Csbk {
+    /// Unit to unit voice service request opcode
+    pub const OPCODE_UU_VOICE_REQ: u8 = 0x04;
+    /// Unit to unit voice service answer response opcode
+    pub const OPCODE_UU_ANS_RSP: u8 = 0x05;
+    /// Negative acknowledge response opcode
+    pub const OPCODE_NACK_RSP: u8 = 0x26;
+    /// BS outbound activation opcode
+    pub const OPCODE_BS_DWN_ACT: u8 = 0x38;
+    /// Preamble CSBK opcode
+    pub const OPCODE_PREAMBLE: u8 = 0x3D;
+
+    /// CSBK whose last 48 data bits carry target and source addresses
+    pub fn addressed(opcode: u8, dst_id: u32, src_id: u32) -> Self {
+        let mut data = [0u8; 8];
+        data[2..5].copy_from_slice(&u24_bytes(dst_id));
+        data[5..8].copy_from_slice(&u24_bytes(src_id));
+        Self {
+            last_block: true,
+            protect: false,
+            opcode: opcode & 0x3F,
+            fid: 0,
+            data,
+        }
+    }
+
+    /// Preamble CSBK announcing `blocks` following blocks
+    pub fn preamble(group: bool, blocks: u8, dst_id: u32, src_id: u32) -> Self {
+        let mut csbk = Self::addressed(Self::OPCODE_PREAMBLE, dst_id, src_id);
+        csbk.data[0] = (group as u8) << 6;
+        csbk.data[1] = blocks;
+        csbk
+    }
+
+    /// Target address (for addressed opcodes)
+    pub fn dst_id(&self) -> u32 {
+        u24(&self.data[2..5])
+    }
+
+    /// Source address (for addressed opcodes)
+    pub fn src_id(&self) -> u32 {
+        u24(&self.data[5..8])
+    }
+
+    /// Encode into 96 bits with masked CRC-CCITT
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![
+            ((self.last_block as u8) << 7) | ((self.protect as u8) << 6) | (self.opcode & 0x3F),
+            self.fid,
+        ];
+        bytes.extend_from_slice(&self.data);
+        let crc = crc_ccitt(&bytes) ^ CSBK_CRC_MASK;
+        bytes.extend_from_slice(&crc.to_be_bytes());
+        bytes_to_bits(&bytes)
+    }
+
+    /// Decode from 96 bits, returning `None` on CRC failure
+    fn decode(bits: &[u8]) -> Option<Self> {
+        let bytes = bits_to_bytes(&bits[..96]);
+        let crc = u16::from_be_bytes([bytes[10], bytes[11]]);
+        if crc_ccitt(&bytes[..10]) ^ CSBK_CRC_MASK != crc {
+            return None;
+        }
+        let mut data = [0u8; 8];
+        data.copy_from_slice(&bytes[2..10]);
+        Some(Self {
+            last_block: bytes[0] & 0x80 != 0,
+            protect: bytes[0] & 0x40 != 0,
+            opcode: bytes[0] & 0x3F,
+            fid: bytes[1],
+            data,
+        })
+    }
+}
+
+/// Unconfirmed/confirmed data header (96 bits including CRC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataHeader {
+    /// Group (`true`) or individual destination
+    pub group: bool,
+    /// Response requested (confirmed delivery)
+    pub response_requested: bool,
+    /// Data packet format (4 bits, 2 = unconfirmed, 3 = confirmed)
+    pub format: u8,
+    /// Service access point identifier (4 bits)
+    pub sap: u8,
+    /// Destination ID
+    pub dst_id: u32,
+    /// Source ID
+    pub src_id: u32,
+    /// Number of data blocks that follow
+    pub blocks_to_follow: u8,
+}
+
+impl DataHeader {
+    /// Unconfirmed data packet format
+    pub const FORMAT_UNCONFIRMED: u8 = 0x02;
+
+    /// Unconfirmed data header for `blocks` following blocks
+    pub fn unconfirmed(group: bool, dst_id: u32, src_id: u32, blocks: u8) -> Self {
+        Self {
+            group,
+            response_requested: false,
+            format: Self::FORMAT_UNCONFIRMED,
+            sap: 0,
+            dst_id: dst_id & 0xFF_FFFF,
+            src_id: src_id & 0xFF_FFFF,
+            blocks_to_follow: blocks & 0x7F,
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![
+            ((self.group as u8) << 7) | ((self.response_requested as u8) << 6) | (self.format & 0x0F),
+            (self.sap & 0x0F) << 4,
+        ];
+        bytes.extend_from_slice(&u24_bytes(self.dst_id));
+        bytes.extend_from_slice(&u24_bytes(self.src_id));
+        bytes.push(0x80 | (self.blocks_to_follow & 0x7F));
+        bytes.push(0);
+        let crc = crc_ccitt(&bytes) ^ DATA_HEADER_CRC_MASK;
+        bytes.extend_from_slice(&crc.to_be_bytes());
+        bytes_to_bits(&bytes)
+    }
+
+    fn decode(bits: &[u8]) -> Option<Self> {
+        let bytes = bits_to_bytes(&bits[..96]);
+        let crc = u16::from_be_bytes([bytes[10], bytes[11]]);
+        if crc_ccitt(&bytes[..10]) ^ DATA_HEADER_CRC_MASK != crc {
+            return None;
+        }
+        Some(Self {
+            group: bytes[0] & 0x80 != 0,
+            response_requested: bytes[0] & 0x40 != 0,
+            format: bytes[0] & 0x0F,
+            sap: bytes[1] >> 4,
+            dst_id: u24(&bytes[2..5]),
+            src_id: u24(&bytes[5..8]),
+            blocks_to_follow: bytes[8] & 0x7F,
+        })
+    }
+}
+
+/// Embedded signalling fragment carried by voice bursts B-F
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbeddedFragment {
+    /// Privacy indicator
+    pub privacy: bool,
+    /// Fragment position within the embedded LC
+    pub lcss: Lcss,
+    /// 32-bit embedded signalling fragment
+    pub data: [u8; 4],
+}
+
+/// Burst payload, one per 30 ms slot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BurstPayload {
+    /// Voice LC header announcing a voice call
+    VoiceLcHeader(FullLc),
+    /// Terminator with LC ending a voice call
+    TerminatorWithLc(FullLc),
+    /// Control signalling block
+    Csbk(Csbk),
+    /// Data header
+    DataHeader(DataHeader),
+    /// Rate ½ data block (12 bytes, BPTC coded)
+    Rate12Data([u8; 12]),
+    /// Rate 1 data block (24 bytes, uncoded)
+    Rate1Data([u8; 24]),
+    /// Idle burst
+    Idle,
+    /// Voice burst: 216 bits of vocoder data (three 72-bit frames)
+    /// with either a sync pattern (burst A) or embedded signalling (B-F)
+    Voice {
+        /// Vocoder frames
+        ambe: [u8; 27],
+        /// Embedded signalling; `None` for the sync-carrying burst A
+        embedded: Option<EmbeddedFragment>,
+    },
+}
+
+impl BurstPayload {
+    /// Data type carried in the slot type field (`None` for voice)
+    pub fn data_type(&self) -> Option<DataType> {
+        match self {
+            Self::VoiceLcHeader(_) => Some(DataType::VoiceLcHeader),
+            Self::TerminatorWithLc(_) => Some(DataType::TerminatorWithLc),
+            Self::Csbk(_) => Some(DataType::Csbk),
+            Self::DataHeader(_) => Some(DataType::DataHeader),
+            Self::Rate12Data(_) => Some(DataType::Rate12Data),
+            Self::Rate1Data(_) => Some(DataType::Rate1Data),
+            Self::Idle => Some(DataType::Idle),
+            Self::Voice { .. } => None,
+        }
+    }
+
+    /// Whether this payload is a voice burst
+    pub fn is_voice(&self) -> bool {
+        matches!(self, Self::Voice { .. })
+    }
+
+    /// Encode the 196-bit info field of a data/control burst
+    fn encode_info(&self) -> Vec<u8> {
+        let bptc_info = match self {
+            Self::VoiceLcHeader(lc) => lc.encode(VOICE_LC_HEADER_MASK),
+            Self::TerminatorWithLc(lc) => lc.encode(TERMINATOR_LC_MASK),
+            Self::Csbk(csbk) => csbk.encode(),
+            Self::DataHeader(header) => header.encode(),
+            Self::Rate12Data(block) => bytes_to_bits(block),
+            Self::Idle => bytes_to_bits(&IDLE_PATTERN),
+            Self::Rate1Data(block) => {
+                let mut bits = bytes_to_bits(block);
+                bits.extend_from_slice(&[0; 4]);
+                return bits;
+            }
+            Self::Voice { .. } => unreachable!("voice bursts carry no info field"),
+        };
+        Bptc19696::encode(&bptc_info)
+    }
+
+    /// Decode the 196-bit info field for the given data type
+    fn decode_info(data_type: DataType, info: &[u8]) -> Option<(Self, bool)> {
+        if data_type == DataType::Rate1Data {
+            let mut block = [0u8; 24];
+            block.copy_from_slice(&bits_to_bytes(&info[..192]));
+            return Some((Self::Rate1Data(block), true));
+        }
+
+        let (bits, fec_ok) = Bptc19696::decode(info);
+        let payload = match data_type {
+            DataType::VoiceLcHeader => Self::VoiceLcHeader(FullLc::decode(&bits, VOICE_LC_HEADER_MASK)?),
+            DataType::TerminatorWithLc => {
+                Self::TerminatorWithLc(FullLc::decode(&bits, TERMINATOR_LC_MASK)?)
+            }
+            DataType::Csbk => Self::Csbk(Csbk::decode(&bits)?),
+            DataType::DataHeader => Self::DataHeader(DataHeader::decode(&bits)?),
+            DataType::Rate12Data => {
+                let mut block = [0u8; 12];
+                block.copy_from_slice(&bits_to_bytes(&bits));
+                Self::Rate12Data(block)
+            }
+            DataType::Idle => Self::Idle,
+            _ => return None,
+        };
+        Some((payload, fec_ok))
+    }
+}
+
+/// Idle burst info content (ETSI idle message pattern)
+const IDLE_PATTERN: [u8; 12] = [
+    0xFF, 0x83, 0xDF, 0x17, 0x32, 0x09, 0x4E, 0x1C, 0xAD, 0x00, 0x00, 0x00,
+];
+
+/// A burst to be transmitted in one timeslot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DmrBurst {
+    /// Timeslot (0 or 1)
+    pub timeslot: u8,
+    /// Colour code (0-15)
+    pub color_code: u8,
+    /// Burst content
+    pub payload: BurstPayload,
+}
+
+impl DmrBurst {
+    /// Create a burst
+    pub fn new(timeslot: u8, color_code: u8, payload: BurstPayload) -> Self {
+        Self {
+            timeslot: timeslot & 1,
+            color_code: color_code & 0x0F,
+            payload,
+        }
+    }
+
+    /// Assemble the 264 burst bits using the given sync pattern for
+    /// sync-carrying bursts
+    pub fn to_bits(&self, sync: DmrSyncType) -> Vec<u8> {
+        let mut bits = Vec::with_capacity(BURST_BITS);
+        let sync_bits = value_to_bits_u64(sync.pattern(), SYNC_BITS);
+
+        match &self.payload {
+            BurstPayload::Voice { ambe, embedded } => {
+                let voice = bytes_to_bits(ambe);
+                bits.extend_from_slice(&voice[..108]);
+                match embedded {
+                    None => bits.extend_from_slice(&sync_bits),
+                    Some(fragment) => {
+                        let emb = Emb {
+                            color_code: self.color_code,
+                            privacy: fragment.privacy,
+                            lcss: fragment.lcss,
+                        }
+                        .encode();
+                        bits.extend_from_slice(&emb[..8]);
+                        bits.extend(bytes_to_bits(&fragment.data));
+                        bits.extend_from_slice(&emb[8..]);
+                    }
+                }
+                bits.extend_from_slice(&voice[108..]);
+            }
+            payload => {
+                let data_type = payload.data_type().unwrap_or(DataType::Idle);
+                let slot_type = SlotType {
+                    color_code: self.color_code,
+                    data_type,
+                }
+                .encode();
+                let info = payload.encode_info();
+                bits.extend_from_slice(&info[..98]);
+                bits.extend_from_slice(&slot_type[..10]);
+                bits.extend_from_slice(&sync_bits);
+                bits.extend_from_slice(&slot_type[10..]);
+                bits.extend_from_slice(&info[98..]);
+            }
+        }
+
+        bits
+    }
+}
+
+/// A burst recovered by the receiver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedBurst {
+    /// Dibit index of the burst start in the demodulated stream
+    pub position: usize,
+    /// Timeslot (0 or 1), from CACH or sync type
+    pub timeslot: u8,
+    /// Colour code from slot type or EMB (inherited for voice sync bursts)
+    pub color_code: Option<u8>,
+    /// Sync pattern found in the burst, if any
+    pub sync: Option<DmrSyncType>,
+    /// Decoded CACH preceding the burst (repeater outbound only)
+    pub cach: Option<Cach>,
+    /// Burst content
+    pub payload: BurstPayload,
+    /// `false` if FEC detected residual errors that were not corrected
+    pub fec_ok: bool,
+}
+
+impl DecodedBurst {
+    /// Parse a data/control burst (one carrying a slot type)
+    pub(crate) fn parse_data(bits: &[u8]) -> Option<(SlotType, BurstPayload, bool)> {
+        let mut slot_bits = bits[98..108].to_vec();
+        slot_bits.extend_from_slice(&bits[156..166]);
+        let slot_type = SlotType::decode(&slot_bits)?;
+
+        let mut info = bits[..98].to_vec();
+        info.extend_from_slice(&bits[166..264]);
+        let (payload, fec_ok) = BurstPayload::decode_info(slot_type.data_type, &info)?;
+        Some((slot_type, payload, fec_ok))
+    }
+
+    /// Parse a voice burst; `embedded` selects EMB decoding (bursts B-F)
+    pub(crate) fn parse_voice(bits: &[u8], embedded: bool) -> Option<(Option<Emb>, BurstPayload)> {
+        let mut voice = bits[..108].to_vec();
+        voice.extend_from_slice(&bits[156..264]);
+        let mut ambe = [0u8; 27];
+        ambe.copy_from_slice(&bits_to_bytes(&voice));
+
+        if !embedded {
+            return Some((None, BurstPayload::Voice { ambe, embedded: None }));
+        }
+
+        let mut emb_bits = bits[108..116].to_vec();
+        emb_bits.extend_from_slice(&bits[148..156]);
+        let emb = Emb::decode(&emb_bits)?;
+        let mut data = [0u8; 4];
+        data.copy_from_slice(&bits_to_bytes(&bits[116..148]));
+
+        let fragment = EmbeddedFragment {
+            privacy: emb.privacy,
+            lcss: emb.lcss,
+            data,
+        };
+        Some((
+            Some(emb),
+            BurstPayload::Voice {
+                ambe,
+                embedded: Some(fragment),
+            },
+        ))
+    }
+}
+
+/// Unpack the low `n` bits of a 64-bit value, MSB first
+pub(crate) fn value_to_bits_u64(value: u64, n: usize) -> Vec<u8> {
+    (0..n).rev().map(|i| ((value >> i) & 1) as u8).collect()
+}
+
+fn u24(bytes: &[u8]) -> u32 {
+    ((bytes[0] as u32) << 16) | ((bytes[1] as u32) << 8) | bytes[2] as u32
+}
+
+fn u24_bytes(value: u32) -> [u8; 3] {
+    [(value >> 16) as u8, (value >> 8) as u8, value as u8]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_type_roundtrip() {
+        let slot_type = SlotType {
+            color_code: 7,
+            data_type: DataType::Csbk,
+        };
+        let mut bits = slot_type.encode();
+        assert_eq!(bits.len(), 20);
+        bits[0] ^= 1;
+        bits[11] ^= 1;
+        assert_eq!(SlotType::decode(&bits), Some(slot_type));
+    }
+
+    #[test]
+    fn test_cach_roundtrip() {
+        let cach = Cach {
+            busy: true,
+            timeslot: 1,
+            lcss: Lcss::Continuation,
+            payload: 0x1_2345,
+        };
+        let mut bits = cach.encode();
+        assert_eq!(bits.len(), CACH_BITS);
+        bits[TACT_POSITIONS[3]] ^= 1;
+        assert_eq!(Cach::decode(&bits), Some(cach));
+    }
+
+    #[test]
+    fn test_emb_roundtrip() {
+        let emb = Emb {
+            color_code: 12,
+            privacy: false,
+            lcss: Lcss::FirstFragment,
+        };
+        let mut bits = emb.encode();
+        bits[2] ^= 1;
+        bits[15] ^= 1;
+        assert_eq!(Emb::decode(&bits), Some(emb));
+    }
+
+    #[test]
+    fn test_full_lc_rs_correction() {
+        let lc = FullLc::group_voice(9, 3_120_001);
+        let mut bits = lc.encode(VOICE_LC_HEADER_MASK);
+        // Corrupt one whole byte
+        for bit in &mut bits[32..40] {
+            *bit ^= 1;
+        }
+        assert_eq!(FullLc::decode(&bits, VOICE_LC_HEADER_MASK), Some(lc));
+        // Wrong mask must not validate
+        let clean = lc.encode(VOICE_LC_HEADER_MASK);
+        assert_ne!(FullLc::decode(&clean, TERMINATOR_LC_MASK), Some(lc));
+    }
+
+    #[test]
+    fn test_csbk_crc() {
+        let csbk = Csbk::preamble(true, 3, 100, 2_000_000);
+        let bits = csbk.encode();
+        let decoded = Csbk::decode(&bits).unwrap();
+        assert_eq!(decoded, csbk);
+        assert_eq!(decoded.dst_id(), 100);
+        assert_eq!(decoded.src_id(), 2_000_000);
+
+        let mut corrupted = bits;
+        corrupted[40] ^= 1;
+        assert!(Csbk::decode(&corrupted).is_none());
+    }
+
+    #[test]
+    fn test_burst_layout() {
+        let burst = DmrBurst::new(
+            0,
+            1,
+            BurstPayload::Csbk(Csbk::addressed(Csbk::OPCODE_UU_VOICE_REQ, 5, 6)),
+        );
+        let bits = burst.to_bits(DmrSyncType::BsData);
+        assert_eq!(bits.len(), BURST_BITS);
+
+        let sync = bits_to_value_u64(&bits[SYNC_OFFSET..SYNC_OFFSET + SYNC_BITS]);
+        assert_eq!(sync, DmrSyncType::BsData.pattern());
+
+        let (slot_type, payload, fec_ok) = DecodedBurst::parse_data(&bits).unwrap();
+        assert_eq!(slot_type.color_code, 1);
+        assert_eq!(payload, burst.payload);
+        assert!(fec_ok);
+    }
+
+    fn bits_to_value_u64(bits: &[u8]) -> u64 {
+        bits.iter().fold(0u64, |acc, &b| (acc << 1) | b as u64)
+    }
+}
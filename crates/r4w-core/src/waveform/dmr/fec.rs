@@ -0,0 +1,583 @@
+//! DMR Forward Error Correction
+//!
+//! Block codes used by the DMR burst layer (ETSI TS 102 361-1 Annex B):
+//!
+//! | Code | Used for |
+//! |------|----------|
+//! | BPTC(196,96) | Data/control burst info field (CSBK, LC header, rate ½ data) |
+//! | Hamming(15,11,3) / Hamming(13,9,3) | Row/column codes inside BPTC(196,96) |
+//! | Hamming(7,4,3) | CACH TACT bits |
+//! | Golay(20,8,7) | Slot type (colour code + data type) |
+//! | QR(16,7,6) | Embedded signalling (EMB) in voice bursts |
+//! | Reed-Solomon(12,9) | Full link control in voice LC header/terminator |
+//! | CRC-CCITT | CSBK and data header integrity |
+//!
+//! All bit-level functions operate on slices of bits stored one per byte
+//! (`0` or `1`), most significant bit first.
+
+/// Parity equations for Hamming(15,11,3): data bit indices feeding each parity bit
+const HAMMING_15_11: [&[usize]; 4] = [
+    &[0, 1, 2, 3, 5, 7, 8],
+    &[1, 2, 3, 4, 6, 8, 9],
+    &[2, 3, 4, 5, 7, 9, 10],
+    &[0, 1, 2, 4, 6, 7, 10],
+];
+
+/// Parity equations for Hamming(13,9,3)
+const HAMMING_13_9: [&[usize]; 4] = [
+    &[0, 1, 3, 5, 6],
+    &[0, 1, 2, 4, 6, 7],
+    &[0, 1, 2, 3, 5, 7, 8],
+    &[0, 2, 4, 5, 8],
+];
+
+/// Parity equations for Hamming(7,4,3)
+const HAMMING_7_4: [&[usize]; 3] = [&[0, 1, 2], &[1, 2, 3], &[0, 1, 3]];
+
+/// Compute Hamming parity bits for `data` given the parity equations
+fn hamming_parity(data: &[u8], equations: &[&[usize]]) -> Vec<u8> {
+    equations
+        .iter()
+        .map(|eq| eq.iter().fold(0u8, |acc, &i| acc ^ (data[i] & 1)))
+        .collect()
+}
+
+/// Correct up to one bit error in a systematic Hamming codeword in place.
+///
+/// The codeword layout is `k` data bits followed by the parity bits.
+/// Returns `false` if the syndrome does not match any single-bit error.
+fn hamming_correct(codeword: &mut [u8], k: usize, equations: &[&[usize]]) -> bool {
+    let parity = hamming_parity(&codeword[..k], equations);
+    let syndrome = parity
+        .iter()
+        .zip(&codeword[k..])
+        .enumerate()
+        .fold(0u32, |acc, (i, (&p, &r))| acc | (((p ^ r) as u32) << i));
+
+    if syndrome == 0 {
+        return true;
+    }
+
+    // Syndrome of a parity-bit error is a single set bit
+    if syndrome.is_power_of_two() {
+        let idx = k + syndrome.trailing_zeros() as usize;
+        codeword[idx] ^= 1;
+        return true;
+    }
+
+    // Syndrome of a data-bit error is the set of equations it participates in
+    let data_bit = (0..k).find(|bit| {
+        let signature = equations
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, eq)| acc | ((eq.contains(bit) as u32) << i));
+        signature == syndrome
+    });
+    match data_bit {
+        Some(bit) => {
+            codeword[bit] ^= 1;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Hamming(15,11,3) encode: returns 15 bits (11 data + 4 parity)
+pub fn hamming_15_11_encode(data: &[u8]) -> Vec<u8> {
+    let mut out = data[..11].to_vec();
+    out.extend(hamming_parity(&out, &HAMMING_15_11));
+    out
+}
+
+/// Hamming(15,11,3) single-error correction in place
+pub fn hamming_15_11_decode(codeword: &mut [u8]) -> bool {
+    hamming_correct(&mut codeword[..15], 11, &HAMMING_15_11)
+}
+
+/// Hamming(13,9,3) encode: returns 13 bits (9 data + 4 parity)
+pub fn hamming_13_9_encode(data: &[u8]) -> Vec<u8> {
+    let mut out = data[..9].to_vec();
+    out.extend(hamming_parity(&out, &HAMMING_13_9));
+    out
+}
+
+/// Hamming(13,9,3) single-error correction in place
+pub fn hamming_13_9_decode(codeword: &mut [u8]) -> bool {
+    hamming_correct(&mut codeword[..13], 9, &HAMMING_13_9)
+}
+
+/// Hamming(7,4,3) encode: returns 7 bits (4 data + 3 parity)
+pub fn hamming_7_4_encode(data: &[u8]) -> Vec<u8> {
+    let mut out = data[..4].to_vec();
+    out.extend(hamming_parity(&out, &HAMMING_7_4));
+    out
+}
+
+/// Hamming(7,4,3) single-error correction in place
+pub fn hamming_7_4_decode(codeword: &mut [u8]) -> bool {
+    hamming_correct(&mut codeword[..7], 4, &HAMMING_7_4)
+}
+
+/// Block Product Turbo Code BPTC(196,96)
+///
+/// The 96 information bits (plus 3 reserved bits) are arranged into a
+/// 13×15 matrix: rows 0-8 carry Hamming(15,11,3) codewords and every
+/// column is a Hamming(13,9,3) codeword. The 196-bit block (one leading
+/// reserved bit + 195 matrix bits) is interleaved with `index = a × 181 mod 196`.
+pub struct Bptc19696;
+
+impl Bptc19696 {
+    /// Number of coded bits
+    pub const CODED_BITS: usize = 196;
+    /// Number of information bits
+    pub const INFO_BITS: usize = 96;
+
+    const ROWS: usize = 13;
+    const COLS: usize = 15;
+
+    /// Interleaved position of non-interleaved bit `a`
+    fn interleave_index(a: usize) -> usize {
+        (a * 181) % Self::CODED_BITS
+    }
+
+    /// Encode 96 information bits into a 196-bit interleaved block
+    pub fn encode(info: &[u8]) -> Vec<u8> {
+        assert!(info.len() >= Self::INFO_BITS, "BPTC(196,96) needs 96 info bits");
+
+        // Fill the 9×11 data area, skipping the three reserved bits R(2..0)
+        let mut matrix = vec![0u8; Self::ROWS * Self::COLS];
+        let mut src = info.iter();
+        for row in 0..9 {
+            let start = if row == 0 { 3 } else { 0 };
+            for col in start..11 {
+                matrix[row * Self::COLS + col] = *src.next().unwrap_or(&0) & 1;
+            }
+        }
+
+        // Row codes
+        for row in 0..9 {
+            let base = row * Self::COLS;
+            let coded = hamming_15_11_encode(&matrix[base..base + 11]);
+            matrix[base..base + Self::COLS].copy_from_slice(&coded);
+        }
+
+        // Column codes (including the row-parity columns)
+        for col in 0..Self::COLS {
+            let column: Vec<u8> = (0..9).map(|r| matrix[r * Self::COLS + col]).collect();
+            let coded = hamming_13_9_encode(&column);
+            for (r, &bit) in coded.iter().enumerate() {
+                matrix[r * Self::COLS + col] = bit;
+            }
+        }
+
+        // Prepend reserved bit R(3) and interleave
+        let mut raw = Vec::with_capacity(Self::CODED_BITS);
+        raw.push(0);
+        raw.extend_from_slice(&matrix);
+
+        let mut out = vec![0u8; Self::CODED_BITS];
+        for (a, &bit) in raw.iter().enumerate() {
+            out[Self::interleave_index(a)] = bit;
+        }
+        out
+    }
+
+    /// Decode a 196-bit interleaved block.
+    ///
+    /// Returns the 96 information bits and whether every row and column
+    /// code was consistent after correction.
+    pub fn decode(coded: &[u8]) -> (Vec<u8>, bool) {
+        assert!(coded.len() >= Self::CODED_BITS, "BPTC(196,96) needs 196 coded bits");
+
+        let mut matrix: Vec<u8> = (1..Self::CODED_BITS)
+            .map(|a| coded[Self::interleave_index(a)] & 1)
+            .collect();
+
+        // Iterate column/row correction; a few passes resolve most patterns
+        let mut valid = false;
+        for _ in 0..3 {
+            valid = true;
+            for col in 0..Self::COLS {
+                let mut column: Vec<u8> =
+                    (0..Self::ROWS).map(|r| matrix[r * Self::COLS + col]).collect();
+                let before = column.clone();
+                if !hamming_13_9_decode(&mut column) {
+                    valid = false;
+                }
+                if column != before {
+                    valid = false;
+                    for (r, &bit) in column.iter().enumerate() {
+                        matrix[r * Self::COLS + col] = bit;
+                    }
+                }
+            }
+            for row in 0..9 {
+                let base = row * Self::COLS;
+                let slice = &mut matrix[base..base + Self::COLS];
+                let before = slice.to_vec();
+                if !hamming_15_11_decode(slice) || slice != before.as_slice() {
+                    valid = false;
+                }
+            }
+            if valid {
+                break;
+            }
+        }
+
+        let mut info = Vec::with_capacity(Self::INFO_BITS);
+        for row in 0..9 {
+            let start = if row == 0 { 3 } else { 0 };
+            for col in start..11 {
+                info.push(matrix[row * Self::COLS + col]);
+            }
+        }
+        (info, valid)
+    }
+}
+
+/// Generator polynomial of the Golay(23,12) code: x¹¹+x¹⁰+x⁶+x⁵+x⁴+x²+1
+const GOLAY_POLY: u32 = 0xC75;
+
+/// Generator polynomial of the QR(17,9) code: x⁸+x⁵+x⁴+x³+1
+const QR_POLY: u32 = 0x139;
+
+/// Remainder of `data · x^degree` modulo `poly` (binary polynomials)
+fn poly_remainder(data: u32, data_bits: u32, poly: u32, degree: u32) -> u32 {
+    let mut reg = data << degree;
+    for i in (degree..degree + data_bits).rev() {
+        if reg & (1 << i) != 0 {
+            reg ^= poly << (i - degree);
+        }
+    }
+    reg & ((1 << degree) - 1)
+}
+
+/// Golay(20,8,7) code protecting the slot type field
+///
+/// Shortened, extended Golay(24,12): 8 data bits, 11 cyclic parity bits
+/// and one overall parity bit. Corrects up to 3 bit errors.
+pub struct Golay2087;
+
+impl Golay2087 {
+    /// Encode 8 data bits into a 20-bit codeword (data in the top byte)
+    pub fn encode(data: u8) -> u32 {
+        let rem = poly_remainder(data as u32, 8, GOLAY_POLY, 11);
+        let parity = ((data as u32).count_ones() + rem.count_ones()) & 1;
+        ((data as u32) << 12) | (rem << 1) | parity
+    }
+
+    /// Decode a 20-bit codeword, returning `None` if more than 3 bits are in error
+    pub fn decode(code: u32) -> Option<u8> {
+        nearest_codeword(code & 0xF_FFFF, 256, 3, |d| Self::encode(d as u8)).map(|d| d as u8)
+    }
+}
+
+/// Quadratic residue QR(16,7,6) code protecting the EMB field
+///
+/// Shortened, extended QR(17,9): 7 data bits, 8 cyclic parity bits and one
+/// overall parity bit. Corrects up to 2 bit errors.
+pub struct Qr1676;
+
+impl Qr1676 {
+    /// Encode 7 data bits into a 16-bit codeword (data in the top 7 bits)
+    pub fn encode(data: u8) -> u16 {
+        let data = (data & 0x7F) as u32;
+        let rem = poly_remainder(data, 7, QR_POLY, 8);
+        let parity = (data.count_ones() + rem.count_ones()) & 1;
+        ((data << 9) | (rem << 1) | parity) as u16
+    }
+
+    /// Decode a 16-bit codeword, returning `None` if more than 2 bits are in error
+    pub fn decode(code: u16) -> Option<u8> {
+        nearest_codeword(code as u32, 128, 2, |d| Self::encode(d as u8) as u32).map(|d| d as u8)
+    }
+}
+
+/// Maximum-likelihood decode of a short block code by exhaustive search
+fn nearest_codeword(
+    code: u32,
+    num_words: u32,
+    max_errors: u32,
+    encode: impl Fn(u32) -> u32,
+) -> Option<u32> {
+    (0..num_words)
+        .map(|d| (d, (encode(d) ^ code).count_ones()))
+        .min_by_key(|&(_, dist)| dist)
+        .filter(|&(_, dist)| dist <= max_errors)
+        .map(|(d, _)| d)
+}
+
+/// GF(2⁸) arithmetic with primitive polynomial x⁸+x⁴+x³+x²+1 (0x11D)
+struct Gf256 {
+    exp: [u8; 512],
+    log: [u8; 256],
+}
+
+impl Gf256 {
+    fn new() -> Self {
+        let mut exp = [0u8; 512];
+        let mut log = [0u8; 256];
+        let mut x: u16 = 1;
+        for (i, e) in exp.iter_mut().take(255).enumerate() {
+            *e = x as u8;
+            log[x as usize] = i as u8;
+            x <<= 1;
+            if x & 0x100 != 0 {
+                x ^= 0x11D;
+            }
+        }
+        for i in 255..512 {
+            exp[i] = exp[i - 255];
+        }
+        Self { exp, log }
+    }
+
+    fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            self.exp[self.log[a as usize] as usize + self.log[b as usize] as usize]
+        }
+    }
+
+    fn div(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            self.exp[self.log[a as usize] as usize + 255 - self.log[b as usize] as usize]
+        }
+    }
+
+    fn pow_alpha(&self, n: usize) -> u8 {
+        self.exp[n % 255]
+    }
+}
+
+/// Reed-Solomon RS(12,9) over GF(2⁸) protecting 72-bit full link control
+///
+/// Generator g(x) = (x-α)(x-α²)(x-α³) = x³ + 0x0E·x² + 0x38·x + 0x40.
+/// Three parity bytes detect up to 3 and correct 1 byte error.
+pub struct Rs129;
+
+impl Rs129 {
+    const GENERATOR: [u8; 3] = [0x0E, 0x38, 0x40];
+
+    /// Compute the 3 parity bytes for 9 data bytes
+    pub fn encode(data: &[u8; 9]) -> [u8; 3] {
+        let gf = Gf256::new();
+        let mut parity = [0u8; 3];
+        for &byte in data {
+            let feedback = byte ^ parity[0];
+            parity[0] = parity[1] ^ gf.mul(feedback, Self::GENERATOR[0]);
+            parity[1] = parity[2] ^ gf.mul(feedback, Self::GENERATOR[1]);
+            parity[2] = gf.mul(feedback, Self::GENERATOR[2]);
+        }
+        parity
+    }
+
+    /// Check and correct a 12-byte codeword in place.
+    ///
+    /// Returns `false` if the codeword has uncorrectable errors.
+    pub fn decode(codeword: &mut [u8; 12]) -> bool {
+        let gf = Gf256::new();
+
+        // Syndromes S_i = c(α^i), c[0] is the highest-degree coefficient
+        let syndromes: Vec<u8> = (1..=3)
+            .map(|i| {
+                codeword
+                    .iter()
+                    .fold(0u8, |acc, &c| gf.mul(acc, gf.pow_alpha(i)) ^ c)
+            })
+            .collect();
+
+        if syndromes.iter().all(|&s| s == 0) {
+            return true;
+        }
+        if syndromes.contains(&0) {
+            return false;
+        }
+
+        // Single error e at degree j: S1 = e·α^j, S2 = e·α^2j, S3 = e·α^3j
+        let x = gf.div(syndromes[1], syndromes[0]);
+        if gf.div(syndromes[2], syndromes[1]) != x {
+            return false;
+        }
+        let degree = gf.log[x as usize] as usize;
+        if degree >= 12 {
+            return false;
+        }
+        let magnitude = gf.div(gf.mul(syndromes[0], syndromes[0]), syndromes[1]);
+        codeword[11 - degree] ^= magnitude;
+        true
+    }
+}
+
+/// CRC-CCITT (x¹⁶+x¹²+x⁵+1) as used for CSBK and data headers.
+///
+/// Initial value 0, result inverted. The caller XORs the data-type
+/// specific CRC mask.
+pub fn crc_ccitt(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            if crc & 0x8000 != 0 {
+                crc = (crc << 1) ^ 0x1021;
+            } else {
+                crc <<= 1;
+            }
+        }
+    }
+    !crc
+}
+
+/// Pack MSB-first bits into bytes
+pub fn bits_to_bytes(bits: &[u8]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |acc, (i, &b)| acc | ((b & 1) << (7 - i)))
+        })
+        .collect()
+}
+
+/// Unpack bytes into MSB-first bits
+pub fn bytes_to_bits(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|&b| (0..8).rev().map(move |i| (b >> i) & 1))
+        .collect()
+}
+
+/// Unpack the low `n` bits of `value`, MSB first
+pub fn value_to_bits(value: u32, n: usize) -> Vec<u8> {
+    (0..n).rev().map(|i| ((value >> i) & 1) as u8).collect()
+}
+
+/// Pack MSB-first bits into an integer
+pub fn bits_to_value(bits: &[u8]) -> u32 {
+    bits.iter().fold(0u32, |acc, &b| (acc << 1) | (b & 1) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hamming_single_error_correction() {
+        let data = [1, 0, 1, 1, 0, 0, 1, 0, 1, 1, 0];
+        let coded = hamming_15_11_encode(&data);
+        for i in 0..15 {
+            let mut corrupted = coded.clone();
+            corrupted[i] ^= 1;
+            assert!(hamming_15_11_decode(&mut corrupted));
+            assert_eq!(corrupted, coded, "bit {} not corrected", i);
+        }
+
+        let coded = hamming_13_9_encode(&data[..9]);
+        for i in 0..13 {
+            let mut corrupted = coded.clone();
+            corrupted[i] ^= 1;
+            assert!(hamming_13_9_decode(&mut corrupted));
+            assert_eq!(corrupted, coded);
+        }
+
+        let coded = hamming_7_4_encode(&data[..4]);
+        for i in 0..7 {
+            let mut corrupted = coded.clone();
+            corrupted[i] ^= 1;
+            assert!(hamming_7_4_decode(&mut corrupted));
+            assert_eq!(corrupted, coded);
+        }
+    }
+
+    #[test]
+    fn test_bptc_roundtrip_with_errors() {
+        let info: Vec<u8> = (0..96).map(|i| ((i * 7 + 3) % 5 == 0) as u8).collect();
+        let coded = Bptc19696::encode(&info);
+        assert_eq!(coded.len(), 196);
+
+        let (decoded, valid) = Bptc19696::decode(&coded);
+        assert!(valid);
+        assert_eq!(decoded, info);
+
+        // Scattered errors are corrected by the product code
+        let mut corrupted = coded.clone();
+        for idx in [5, 50, 100, 150, 190] {
+            corrupted[idx] ^= 1;
+        }
+        let (decoded, valid) = Bptc19696::decode(&corrupted);
+        assert!(valid);
+        assert_eq!(decoded, info);
+    }
+
+    #[test]
+    fn test_golay_2087() {
+        for data in [0x00u8, 0x12, 0xA5, 0xFF] {
+            let code = Golay2087::encode(data);
+            assert!(code < (1 << 20));
+            assert_eq!(Golay2087::decode(code), Some(data));
+            // Three errors are correctable
+            assert_eq!(Golay2087::decode(code ^ 0b1000_0000_0100_0000_0001), Some(data));
+        }
+    }
+
+    #[test]
+    fn test_golay_minimum_distance() {
+        let min = (1..256u32)
+            .map(|d| Golay2087::encode(d as u8).count_ones())
+            .min()
+            .unwrap();
+        assert!(min >= 7);
+    }
+
+    #[test]
+    fn test_qr_1676() {
+        // First non-zero entry of the ETSI QR(16,7,6) table
+        assert_eq!(Qr1676::encode(1), 0x0273);
+
+        let min = (1..128u8).map(|d| Qr1676::encode(d).count_ones()).min().unwrap();
+        assert_eq!(min, 6);
+
+        for data in 0..128u8 {
+            let code = Qr1676::encode(data);
+            assert_eq!(Qr1676::decode(code ^ 0x8001), Some(data));
+        }
+    }
+
+    #[test]
+    fn test_rs_129() {
+        let data = [0x00, 0x00, 0x00, 0x00, 0x12, 0x34, 0x00, 0x0B, 0xB8];
+        let parity = Rs129::encode(&data);
+
+        let mut codeword = [0u8; 12];
+        codeword[..9].copy_from_slice(&data);
+        codeword[9..].copy_from_slice(&parity);
+        assert!(Rs129::decode(&mut codeword.clone()));
+
+        // Single byte error anywhere is corrected
+        for pos in 0..12 {
+            let mut corrupted = codeword;
+            corrupted[pos] ^= 0x5A;
+            assert!(Rs129::decode(&mut corrupted));
+            assert_eq!(corrupted, codeword);
+        }
+
+        // Two byte errors exceed t = 1; this pattern is detected, not miscorrected
+        let mut corrupted = codeword;
+        corrupted[1] ^= 0x01;
+        corrupted[7] ^= 0x80;
+        assert!(!Rs129::decode(&mut corrupted));
+    }
+
+    #[test]
+    fn test_bit_packing() {
+        let bytes = [0xA5, 0x3C];
+        let bits = bytes_to_bits(&bytes);
+        assert_eq!(bits.len(), 16);
+        assert_eq!(bits_to_bytes(&bits), bytes);
+        assert_eq!(bits_to_value(&value_to_bits(0x1234, 16)), 0x1234);
+    }
+}
@@ -0,0 +1,978 @@
+//! DMR (Digital Mobile Radio) Waveform
+//!
+//! This module implements the DMR digital radio standard (ETSI TS 102 361)
+//! widely adopted by government, utilities, and public safety organizations.
+//!
+//! # Overview
+//!
+//! DMR is an ETSI open standard for digital mobile radio that provides
+//! efficient spectrum use through 2-slot TDMA in 12.5 kHz channels.
+//!
+//! # Key Features
+//!
+//! - 4FSK modulation at 4800 symbols/sec
+//! - 2-slot TDMA (two voice/data channels per 12.5 kHz)
+//! - AMBE+2 voice codec (3.6 kbps + 2.4 kbps FEC)
+//! - Optional ARC4 or AES-256 encryption
+//!
+//! # DMR Tiers
+//!
+//! - Tier I: Unlicensed (dPMR446) - 0.5W, 8 channels
+//! - Tier II: Licensed conventional - repeater/direct
+//! - Tier III: Licensed trunked - full infrastructure
+//!
+//! # Burst Layer
+//!
+//! [`Dmr::modulate_bursts`] and [`Dmr::decode_bursts`] carry complete layer 2
+//! bursts (see [`burst`]) with their FEC (see [`fec`]): voice LC headers and
+//! terminators, CSBKs, data headers, rate ½ and rate 1 data, and voice
+//! superframes with embedded signalling.
+//!
+//! ```rust
+//! use r4w_core::waveform::dmr::{BurstPayload, Dmr, DmrBurst, FullLc};
+//!
+//! let dmr = Dmr::tier2(48000.0);
+//! let lc = FullLc::group_voice(91, 3_120_001);
+//! let samples = dmr.modulate_bursts(&[
+//!     DmrBurst::new(0, 1, BurstPayload::VoiceLcHeader(lc)),
+//! ]);
+//!
+//! let decoded = dmr.decode_bursts(&samples);
+//! assert!(decoded.iter().any(|b| b.payload == BurstPayload::VoiceLcHeader(lc)));
+//! ```
+
+pub mod burst;
+pub mod fec;
+
+use std::f64::consts::PI;
+
+use crate::types::IQSample;
+use crate::waveform::{CommonParams, DemodResult, VisualizationData, Waveform, WaveformInfo};
+
+pub use burst::{
+    BurstPayload, Cach, Csbk, DataHeader, DataType, DecodedBurst, DmrBurst, Emb,
+    EmbeddedFragment, Flco, FullLc, Lcss, SlotType,
+};
+
+/// DMR tier (operational mode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DmrTier {
+    /// Tier I: Unlicensed 446 MHz (dPMR446)
+    Tier1,
+    /// Tier II: Licensed conventional
+    #[default]
+    Tier2,
+    /// Tier III: Licensed trunked
+    Tier3,
+}
+
+/// DMR operating mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DmrMode {
+    /// Direct mode (simplex)
+    #[default]
+    Direct,
+    /// Repeater mode
+    Repeater,
+    /// Trunked mode (Tier III only)
+    Trunked,
+}
+
+/// DMR burst types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmrBurstType {
+    /// Voice burst
+    Voice,
+    /// CSBK (Control Signalling Block)
+    Csbk,
+    /// Data header
+    DataHeader,
+    /// Rate 1/2 data
+    DataRate12,
+    /// Rate 3/4 data
+    DataRate34,
+    /// Idle burst
+    Idle,
+}
+
+/// DMR sync pattern types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmrSyncType {
+    /// Base Station Voice
+    BsVoice,
+    /// Base Station Data
+    BsData,
+    /// Mobile Station Voice
+    MsVoice,
+    /// Mobile Station Data
+    MsData,
+    /// RC Sync
+    RcSync,
+    /// Direct Mode Voice Timeslot 1
+    DmoVoiceTs1,
+    /// Direct Mode Data Timeslot 1
+    DmoDataTs1,
+    /// Direct Mode Voice Timeslot 2
+    DmoVoiceTs2,
+    /// Direct Mode Data Timeslot 2
+    DmoDataTs2,
+}
+
+impl DmrSyncType {
+    /// Get the 48-bit sync pattern
+    pub fn pattern(&self) -> u64 {
+        match self {
+            Self::BsVoice => 0x755FD7DF75F7,
+            Self::BsData => 0xDFF57D75DF5D,
+            Self::MsVoice => 0x7F7D5DD57DFD,
+            Self::MsData => 0xD5D7F77FD757,
+            Self::RcSync => 0x77D55F7DFD77,
+            Self::DmoVoiceTs1 => 0x5D577F7757FF,
+            Self::DmoDataTs1 => 0xF7FDD5DDFD55,
+            Self::DmoVoiceTs2 => 0x7DFFD5F55D5F,
+            Self::DmoDataTs2 => 0xD7557F5FF7F5,
+        }
+    }
+
+    /// All sync patterns, for correlation
+    pub const ALL: [DmrSyncType; 9] = [
+        Self::BsVoice,
+        Self::BsData,
+        Self::MsVoice,
+        Self::MsData,
+        Self::RcSync,
+        Self::DmoVoiceTs1,
+        Self::DmoDataTs1,
+        Self::DmoVoiceTs2,
+        Self::DmoDataTs2,
+    ];
+
+    /// Whether this pattern precedes a voice burst
+    pub fn is_voice(&self) -> bool {
+        matches!(
+            self,
+            Self::BsVoice | Self::MsVoice | Self::DmoVoiceTs1 | Self::DmoVoiceTs2
+        )
+    }
+
+    /// Timeslot implied by a direct mode sync pattern
+    pub fn direct_timeslot(&self) -> Option<u8> {
+        match self {
+            Self::DmoVoiceTs1 | Self::DmoDataTs1 => Some(0),
+            Self::DmoVoiceTs2 | Self::DmoDataTs2 => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Sync pattern used by a transmitter in `mode` for the given burst
+    pub fn for_burst(mode: DmrMode, voice: bool, timeslot: u8) -> Self {
+        match (mode, voice, timeslot & 1) {
+            (DmrMode::Direct, true, 0) => Self::DmoVoiceTs1,
+            (DmrMode::Direct, true, _) => Self::DmoVoiceTs2,
+            (DmrMode::Direct, false, 0) => Self::DmoDataTs1,
+            (DmrMode::Direct, false, _) => Self::DmoDataTs2,
+            (_, true, _) => Self::BsVoice,
+            (_, false, _) => Self::BsData,
+        }
+    }
+}
+
+/// DMR TDMA timing constants
+pub struct DmrTiming;
+
+impl DmrTiming {
+    /// Slots per frame
+    pub const SLOTS_PER_FRAME: usize = 2;
+    /// Frame duration in milliseconds
+    pub const FRAME_DURATION_MS: f64 = 60.0;
+    /// Slot duration in milliseconds
+    pub const SLOT_DURATION_MS: f64 = 30.0;
+    /// Guard time in milliseconds (CACH + guard)
+    pub const GUARD_TIME_MS: f64 = 2.5;
+    /// Symbols per slot
+    pub const SYMBOLS_PER_SLOT: usize = 144;
+    /// Symbol rate
+    pub const SYMBOL_RATE: f64 = 4800.0;
+    /// Frames per superframe
+    pub const FRAMES_PER_SUPERFRAME: usize = 6;
+    /// Superframe duration in milliseconds
+    pub const SUPERFRAME_DURATION_MS: f64 = 360.0;
+}
+
+/// DMR 4FSK frequency deviations from center (Hz)
+/// Gray coded: 00 -> -1944, 01 -> -648, 11 -> +648, 10 -> +1944
+const DMR_DEVIATIONS: [f64; 4] = [-1944.0, -648.0, 648.0, 1944.0];
+
+/// Dibit to symbol mapping (Gray code)
+fn dibit_to_symbol(dibit: u8) -> u8 {
+    match dibit & 0x03 {
+        0b00 => 0, // -1944 Hz
+        0b01 => 1, // -648 Hz
+        0b11 => 2, // +648 Hz
+        0b10 => 3, // +1944 Hz
+        _ => 0,
+    }
+}
+
+/// Symbol to dibit mapping (reverse Gray code)
+fn symbol_to_dibit(symbol: u8) -> u8 {
+    match symbol & 0x03 {
+        0 => 0b00,
+        1 => 0b01,
+        2 => 0b11,
+        3 => 0b10,
+        _ => 0,
+    }
+}
+
+/// Sync pattern found in a dibit stream: (dibit index, pattern, bit errors)
+type SyncHit = (usize, DmrSyncType, u32);
+
+/// DMR waveform implementation
+#[derive(Debug)]
+pub struct Dmr {
+    /// Common waveform parameters
+    common: CommonParams,
+    /// DMR tier
+    tier: DmrTier,
+    /// Operating mode
+    mode: DmrMode,
+    /// Timeslot (0 or 1)
+    timeslot: u8,
+    /// Samples per symbol
+    samples_per_sym: usize,
+    /// Current phase for FSK generation
+    phase: f64,
+    /// RRC filter coefficients
+    rrc_filter: Vec<f64>,
+}
+
+impl Dmr {
+    /// Standard symbol rate (4800 symbols/sec)
+    pub const SYMBOL_RATE: f64 = 4800.0;
+    /// Channel bandwidth (12.5 kHz)
+    pub const BANDWIDTH: f64 = 12500.0;
+    /// Bits per symbol (4FSK = 2 bits)
+    pub const BITS_PER_SYMBOL: u8 = 2;
+    /// RRC rolloff factor
+    pub const RRC_ROLLOFF: f64 = 0.2;
+    /// Maximum bit errors tolerated when correlating a 48-bit sync pattern
+    pub const SYNC_MAX_ERRORS: u32 = 4;
+
+    /// Create a new DMR instance
+    pub fn new(sample_rate: f64, tier: DmrTier, mode: DmrMode) -> Self {
+        let samples_per_sym = (sample_rate / Self::SYMBOL_RATE) as usize;
+
+        // Generate RRC filter
+        let rrc_filter = Self::generate_rrc_filter(samples_per_sym, 8, Self::RRC_ROLLOFF);
+
+        Self {
+            common: CommonParams {
+                sample_rate,
+                carrier_freq: 0.0,
+                amplitude: 1.0,
+            },
+            tier,
+            mode,
+            timeslot: 0,
+            samples_per_sym: samples_per_sym.max(1),
+            phase: 0.0,
+            rrc_filter,
+        }
+    }
+
+    /// Create standard Tier II DMR
+    pub fn tier2(sample_rate: f64) -> Self {
+        Self::new(sample_rate, DmrTier::Tier2, DmrMode::Repeater)
+    }
+
+    /// Create direct mode DMR
+    pub fn direct(sample_rate: f64) -> Self {
+        Self::new(sample_rate, DmrTier::Tier2, DmrMode::Direct)
+    }
+
+    /// Create Tier III trunked DMR
+    pub fn tier3(sample_rate: f64) -> Self {
+        Self::new(sample_rate, DmrTier::Tier3, DmrMode::Trunked)
+    }
+
+    /// Set timeslot (0 or 1)
+    pub fn with_timeslot(mut self, slot: u8) -> Self {
+        self.timeslot = slot & 1;
+        self
+    }
+
+    /// Generate Root Raised Cosine filter coefficients
+    fn generate_rrc_filter(sps: usize, span: usize, rolloff: f64) -> Vec<f64> {
+        let num_taps = span * sps + 1;
+        let mut filter = vec![0.0; num_taps];
+        let half = (num_taps / 2) as f64;
+
+        for i in 0..num_taps {
+            let t = (i as f64 - half) / sps as f64;
+
+            if t.abs() < 1e-10 {
+                // t = 0 case
+                filter[i] = 1.0 - rolloff + 4.0 * rolloff / PI;
+            } else if (t.abs() - 1.0 / (4.0 * rolloff)).abs() < 1e-10 && rolloff > 0.0 {
+                // t = ±1/(4*α) case
+                let a = (1.0 + 2.0 / PI) * (PI / (4.0 * rolloff)).sin();
+                let b = (1.0 - 2.0 / PI) * (PI / (4.0 * rolloff)).cos();
+                filter[i] = rolloff / 2.0_f64.sqrt() * (a + b);
+            } else {
+                // General case
+                let num = (PI * t * (1.0 - rolloff)).sin()
+                    + 4.0 * rolloff * t * (PI * t * (1.0 + rolloff)).cos();
+                let den = PI * t * (1.0 - (4.0 * rolloff * t).powi(2));
+                if den.abs() > 1e-10 {
+                    filter[i] = num / den;
+                } else {
+                    filter[i] = 0.0;
+                }
+            }
+        }
+
+        // Normalize
+        let sum: f64 = filter.iter().map(|x| x.abs()).sum();
+        if sum > 0.0 {
+            for coef in &mut filter {
+                *coef /= sum;
+            }
+        }
+
+        filter
+    }
+
+    /// Generate sync pattern as samples
+    fn generate_sync(&mut self, sync_type: DmrSyncType) -> Vec<IQSample> {
+        let pattern = sync_type.pattern();
+
+        // Extract dibits from 48-bit pattern (24 dibits)
+        let mut dibits = Vec::with_capacity(24);
+        for i in (0..24).rev() {
+            let dibit = ((pattern >> (i * 2)) & 0x03) as u8;
+            dibits.push(dibit);
+        }
+
+        self.fsk4_modulate(&dibits)
+    }
+
+    /// 4FSK modulation
+    ///
+    /// The symbol deviations are RRC-shaped before the frequency modulator,
+    /// so the output has constant envelope.
+    fn fsk4_modulate(&mut self, dibits: &[u8]) -> Vec<IQSample> {
+        let levels: Vec<f64> = dibits
+            .iter()
+            .flat_map(|&dibit| {
+                let freq = DMR_DEVIATIONS[dibit_to_symbol(dibit) as usize];
+                std::iter::repeat_n(freq, self.samples_per_sym)
+            })
+            .collect();
+
+        let mut samples = Vec::with_capacity(levels.len());
+        for freq in self.apply_rrc_filter(&levels) {
+            samples.push(IQSample::new(self.phase.cos(), self.phase.sin()));
+            self.phase += 2.0 * PI * freq / self.common.sample_rate;
+
+            // Keep phase bounded
+            if self.phase.abs() > 2.0 * PI {
+                self.phase %= 2.0 * PI;
+            }
+        }
+
+        samples
+    }
+
+    /// Apply RRC filter (unity DC gain) to the frequency trajectory
+    fn apply_rrc_filter(&self, levels: &[f64]) -> Vec<f64> {
+        let dc_gain: f64 = self.rrc_filter.iter().sum();
+        if self.rrc_filter.is_empty() || dc_gain.abs() < 1e-12 {
+            return levels.to_vec();
+        }
+
+        let half_len = self.rrc_filter.len() / 2;
+        (0..levels.len())
+            .map(|i| {
+                // Hold the edge levels so the first/last symbols are not attenuated
+                let sum: f64 = self
+                    .rrc_filter
+                    .iter()
+                    .enumerate()
+                    .map(|(j, &coef)| {
+                        let idx = (i + j).saturating_sub(half_len).min(levels.len() - 1);
+                        levels[idx] * coef
+                    })
+                    .sum();
+                sum / dc_gain
+            })
+            .collect()
+    }
+
+    /// Demodulate 4FSK
+    fn fsk4_demodulate(&self, samples: &[IQSample]) -> Vec<u8> {
+        self.fsk4_demodulate_soft(samples).0
+    }
+
+    /// Demodulate 4FSK, also returning the mean distance (Hz) between the
+    /// measured symbol frequencies and the nearest deviation level
+    fn fsk4_demodulate_soft(&self, samples: &[IQSample]) -> (Vec<u8>, f64) {
+        let mut dibits = Vec::new();
+        let mut total_error = 0.0;
+
+        for chunk in samples.chunks(self.samples_per_sym) {
+            if chunk.len() < 2 {
+                continue;
+            }
+
+            // Estimate frequency from phase differences over the middle of
+            // the symbol, where inter-symbol interference is smallest
+            let lo = (chunk.len() / 4).max(1);
+            let hi = (chunk.len() - chunk.len() / 4).max(lo + 1);
+            let mut freq_sum = 0.0;
+            for i in lo..hi {
+                let phase_diff = (chunk[i] * chunk[i - 1].conj()).arg();
+                let freq = phase_diff * self.common.sample_rate / (2.0 * PI);
+                freq_sum += freq;
+            }
+            let avg_freq = freq_sum / (hi - lo) as f64;
+
+            // Find closest symbol
+            let mut best_symbol = 0u8;
+            let mut best_error = f64::MAX;
+
+            for (sym, &dev) in DMR_DEVIATIONS.iter().enumerate() {
+                let error = (avg_freq - dev).abs();
+                if error < best_error {
+                    best_error = error;
+                    best_symbol = sym as u8;
+                }
+            }
+
+            total_error += best_error;
+            dibits.push(symbol_to_dibit(best_symbol));
+        }
+
+        let mean_error = total_error / dibits.len().max(1) as f64;
+        (dibits, mean_error)
+    }
+
+    /// Modulate a sequence of layer 2 bursts.
+    ///
+    /// Bursts are placed in consecutive 30 ms slots alternating between
+    /// timeslot 0 and 1; when a burst's timeslot does not match the next
+    /// slot, an idle burst is inserted. In repeater and trunked mode each
+    /// burst is preceded by a CACH announcing its timeslot; in direct mode
+    /// the CACH position is left as guard time.
+    pub fn modulate_bursts(&self, bursts: &[DmrBurst]) -> Vec<IQSample> {
+        let mut dmr = Self::new(self.common.sample_rate, self.tier, self.mode);
+        let mut dibits = Vec::with_capacity(bursts.len() * DmrTiming::SYMBOLS_PER_SLOT * 2);
+        let mut next_slot = 0u8;
+
+        for burst in bursts {
+            if burst.timeslot != next_slot {
+                let idle = DmrBurst::new(next_slot, burst.color_code, BurstPayload::Idle);
+                dibits.extend(self.slot_dibits(&idle));
+                next_slot ^= 1;
+            }
+            dibits.extend(self.slot_dibits(burst));
+            next_slot ^= 1;
+        }
+
+        // Trailing CACH/guard so the last burst is not cut at the filter edge
+        if !bursts.is_empty() {
+            let tail = self.slot_dibits(&DmrBurst::new(next_slot, 0, BurstPayload::Idle));
+            dibits.extend_from_slice(&tail[..burst::CACH_BITS / 2]);
+        }
+
+        dmr.fsk4_modulate(&dibits)
+    }
+
+    /// CACH (or guard) followed by the burst, as dibits
+    fn slot_dibits(&self, burst: &DmrBurst) -> Vec<u8> {
+        let mut bits = match self.mode {
+            DmrMode::Direct => vec![0u8; burst::CACH_BITS],
+            _ => Cach {
+                busy: true,
+                timeslot: burst.timeslot,
+                lcss: Lcss::SingleFragment,
+                payload: 0,
+            }
+            .encode(),
+        };
+        let sync = DmrSyncType::for_burst(self.mode, burst.payload.is_voice(), burst.timeslot);
+        bits.extend(burst.to_bits(sync));
+        bits.chunks(2).map(|pair| (pair[0] << 1) | pair[1]).collect()
+    }
+
+    /// Find sync patterns in a dibit stream
+    fn find_syncs(dibits: &[u8]) -> Vec<SyncHit> {
+        const SYNC_DIBITS: usize = burst::SYNC_BITS / 2;
+        let mut found = Vec::new();
+        if dibits.len() < SYNC_DIBITS {
+            return found;
+        }
+
+        let mut i = 0;
+        while i + SYNC_DIBITS <= dibits.len() {
+            let word = dibits[i..i + SYNC_DIBITS]
+                .iter()
+                .fold(0u64, |acc, &d| (acc << 2) | (d & 0x03) as u64);
+            let best = DmrSyncType::ALL
+                .iter()
+                .map(|&t| (t, (word ^ t.pattern()).count_ones()))
+                .min_by_key(|&(_, errors)| errors);
+
+            match best {
+                Some((sync, errors)) if errors <= Self::SYNC_MAX_ERRORS => {
+                    found.push((i, sync, errors));
+                    i += SYNC_DIBITS;
+                }
+                _ => i += 1,
+            }
+        }
+        found
+    }
+
+    /// Demodulate samples and decode every DMR burst found.
+    ///
+    /// Symbol timing is chosen by correlating against the sync patterns;
+    /// the first sync found anchors the 144-symbol slot grid. Voice bursts
+    /// B-F (which carry EMB instead of sync) are decoded when they follow a
+    /// voice sync burst of the same timeslot within a superframe.
+    pub fn decode_bursts(&self, samples: &[IQSample]) -> Vec<DecodedBurst> {
+        const SLOT_DIBITS: usize = DmrTiming::SYMBOLS_PER_SLOT;
+        const BURST_DIBITS: usize = burst::BURST_BITS / 2;
+        const CACH_DIBITS: usize = burst::CACH_BITS / 2;
+        const SYNC_START: usize = burst::SYNC_OFFSET / 2;
+
+        // Timing recovery: pick the sample offset with the most syncs, then
+        // the smallest distance to the deviation levels (widest eye)
+        let mut best: Option<(Vec<u8>, Vec<SyncHit>, f64)> = None;
+        for offset in 0..self.samples_per_sym.min(samples.len()) {
+            let (dibits, error) = self.fsk4_demodulate_soft(&samples[offset..]);
+            let syncs = Self::find_syncs(&dibits);
+            let better = match &best {
+                None => true,
+                Some((_, best_syncs, best_error)) => {
+                    syncs.len() > best_syncs.len()
+                        || (syncs.len() == best_syncs.len() && error < *best_error)
+                }
+            };
+            if better {
+                best = Some((dibits, syncs, error));
+            }
+        }
+        let Some((dibits, syncs, _)) = best else {
+            return Vec::new();
+        };
+        let Some(&(first_sync, _, _)) = syncs.first() else {
+            return Vec::new();
+        };
+
+        let to_bits = |d: &[u8]| -> Vec<u8> { d.iter().flat_map(|&x| [(x >> 1) & 1, x & 1]).collect() };
+        let sync_at = |start: usize| {
+            syncs
+                .iter()
+                .find(|&&(pos, _, _)| pos == start + SYNC_START)
+                .map(|&(_, sync, _)| sync)
+        };
+
+        let anchor = (first_sync + SLOT_DIBITS - SYNC_START % SLOT_DIBITS) % SLOT_DIBITS;
+        let repeater = syncs
+            .iter()
+            .any(|(_, s, _)| matches!(s, DmrSyncType::BsVoice | DmrSyncType::BsData));
+
+        let mut decoded = Vec::new();
+        let mut slot_ref: Option<(usize, u8)> = None;
+        let mut color_codes: [Option<u8>; 2] = [None; 2];
+        let mut voice_sync_index: [Option<usize>; 2] = [None; 2];
+
+        let mut index = 0usize;
+        let mut start = anchor;
+        while start + BURST_DIBITS <= dibits.len() {
+            let bits = to_bits(&dibits[start..start + BURST_DIBITS]);
+            let sync = sync_at(start);
+            let cach = if repeater && start >= CACH_DIBITS {
+                Cach::decode(&to_bits(&dibits[start - CACH_DIBITS..start]))
+            } else {
+                None
+            };
+
+            let known_slot = cach
+                .as_ref()
+                .map(|c| c.timeslot)
+                .or_else(|| sync.and_then(|s| s.direct_timeslot()));
+            if let Some(slot) = known_slot {
+                slot_ref = Some((index, slot));
+            }
+            let timeslot = known_slot.unwrap_or_else(|| match slot_ref {
+                Some((ref_index, ref_slot)) => ref_slot ^ ((index - ref_index) & 1) as u8,
+                None => (index & 1) as u8,
+            });
+            let ts = timeslot as usize;
+
+            let parsed = match sync {
+                Some(s) if s.is_voice() => {
+                    voice_sync_index[ts] = Some(index);
+                    DecodedBurst::parse_voice(&bits, false)
+                        .map(|(_, payload)| (color_codes[ts], payload, true))
+                }
+                Some(_) => DecodedBurst::parse_data(&bits).map(|(slot_type, payload, fec_ok)| {
+                    (Some(slot_type.color_code), payload, fec_ok)
+                }),
+                None => {
+                    let in_superframe = voice_sync_index[ts].is_some_and(|v| {
+                        let frames = index - v;
+                        frames > 0 && frames.is_multiple_of(2) && frames <= 2 * (DmrTiming::FRAMES_PER_SUPERFRAME - 1)
+                    });
+                    if in_superframe {
+                        DecodedBurst::parse_voice(&bits, true)
+                            .map(|(emb, payload)| (emb.map(|e| e.color_code), payload, true))
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            if let Some((color_code, payload, fec_ok)) = parsed {
+                if color_code.is_some() {
+                    color_codes[ts] = color_code;
+                }
+                if !payload.is_voice() {
+                    voice_sync_index[ts] = None;
+                }
+                decoded.push(DecodedBurst {
+                    position: start,
+                    timeslot,
+                    color_code,
+                    sync,
+                    cach,
+                    payload,
+                    fec_ok,
+                });
+            }
+
+            start += SLOT_DIBITS;
+            index += 1;
+        }
+
+        decoded
+    }
+}
+
+impl Waveform for Dmr {
+    fn info(&self) -> WaveformInfo {
+        WaveformInfo {
+            name: "DMR",
+            full_name: "Digital Mobile Radio",
+            description: "ETSI open standard for digital mobile radio providing \
+                efficient spectrum use through 2-slot TDMA in 12.5 kHz channels",
+            complexity: 3,
+            bits_per_symbol: Self::BITS_PER_SYMBOL,
+            carries_data: true,
+            characteristics: &[
+                "4FSK at 4800 symbols/sec",
+                "2-slot TDMA (12.5 kHz channel)",
+                "AMBE+2 voice codec",
+                "ARC4 or AES-256 encryption",
+                "Tier I/II/III operation",
+                "9.6 kbps gross data rate",
+                "Repeater and direct modes",
+            ],
+            history: "Developed by ETSI in the 2000s as an open standard alternative \
+                to proprietary digital radio systems. DMR addresses the need for \
+                spectral efficiency and interoperability in the 12.5 kHz narrowband \
+                environment.",
+            modern_usage: "Widely adopted by commercial users, utilities, government \
+                agencies, and amateur radio operators. Major manufacturers include \
+                Motorola, Hytera, and Kenwood. Popular for its balance of features \
+                and cost-effectiveness.",
+        }
+    }
+
+    fn common_params(&self) -> &CommonParams {
+        &self.common
+    }
+
+    fn modulate(&self, data: &[u8]) -> Vec<IQSample> {
+        // Create mutable copy for phase tracking
+        let mut dmr = Self::new(self.common.sample_rate, self.tier, self.mode);
+
+        // Generate sync burst
+        let sync_type = match self.mode {
+            DmrMode::Direct => DmrSyncType::DmoVoiceTs1,
+            _ => DmrSyncType::BsVoice,
+        };
+        let mut samples = dmr.generate_sync(sync_type);
+
+        // Convert data bytes to dibits
+        let dibits: Vec<u8> = data
+            .iter()
+            .flat_map(|&b| {
+                vec![
+                    (b >> 6) & 0x03,
+                    (b >> 4) & 0x03,
+                    (b >> 2) & 0x03,
+                    b & 0x03,
+                ]
+            })
+            .collect();
+
+        // Modulate data
+        samples.extend(dmr.fsk4_modulate(&dibits));
+
+        samples
+    }
+
+    fn demodulate(&self, samples: &[IQSample]) -> DemodResult {
+        // Skip sync burst (24 dibits = 24 symbols)
+        let sync_symbols = 24;
+        let data_start = sync_symbols * self.samples_per_sym;
+
+        if samples.len() <= data_start {
+            return DemodResult::default();
+        }
+
+        let data_samples = &samples[data_start..];
+        let dibits = self.fsk4_demodulate(data_samples);
+
+        // Pack dibits to bytes
+        let bytes: Vec<u8> = dibits
+            .chunks(4)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u8, |acc, (i, &d)| acc | ((d & 0x03) << (6 - i * 2)))
+            })
+            .collect();
+
+        DemodResult {
+            bits: bytes,
+            symbols: dibits.iter().map(|&d| d as u16).collect(),
+            ber_estimate: None,
+            snr_estimate: None,
+            metadata: std::collections::HashMap::new(),
+        }
+    }
+
+    fn samples_per_symbol(&self) -> usize {
+        self.samples_per_sym
+    }
+
+    fn get_visualization(&self, data: &[u8]) -> VisualizationData {
+        let samples = self.modulate(data);
+
+        // 4FSK represented as frequency levels on imaginary axis
+        let constellation: Vec<IQSample> = DMR_DEVIATIONS
+            .iter()
+            .enumerate()
+            .map(|(_i, &dev)| {
+                // Normalize deviation to unit circle
+                let normalized = dev / 2000.0;
+                IQSample::new(0.5, normalized * 0.5)
+            })
+            .collect();
+
+        let labels: Vec<String> = vec![
+            format!("00: {:+} Hz", DMR_DEVIATIONS[0] as i32),
+            format!("01: {:+} Hz", DMR_DEVIATIONS[1] as i32),
+            format!("11: {:+} Hz", DMR_DEVIATIONS[2] as i32),
+            format!("10: {:+} Hz", DMR_DEVIATIONS[3] as i32),
+        ];
+
+        VisualizationData {
+            samples,
+            constellation,
+            constellation_labels: labels,
+            spectrum: Vec::new(),
+            description: format!(
+                "DMR {:?} {:?} mode, TS{}, 4FSK",
+                self.tier, self.mode, self.timeslot
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dmr_creation() {
+        let dmr = Dmr::tier2(48000.0);
+        assert_eq!(dmr.samples_per_sym, 10); // 48000/4800 = 10
+    }
+
+    #[test]
+    fn test_gray_code() {
+        // Test dibit to symbol mapping (Gray code)
+        assert_eq!(dibit_to_symbol(0b00), 0);
+        assert_eq!(dibit_to_symbol(0b01), 1);
+        assert_eq!(dibit_to_symbol(0b11), 2);
+        assert_eq!(dibit_to_symbol(0b10), 3);
+
+        // Test reverse mapping
+        assert_eq!(symbol_to_dibit(0), 0b00);
+        assert_eq!(symbol_to_dibit(1), 0b01);
+        assert_eq!(symbol_to_dibit(2), 0b11);
+        assert_eq!(symbol_to_dibit(3), 0b10);
+    }
+
+    #[test]
+    fn test_dmr_modulation() {
+        let dmr = Dmr::tier2(48000.0);
+        let data = vec![0xAA, 0x55, 0xF0, 0x0F];
+        let modulated = dmr.modulate(&data);
+
+        assert!(!modulated.is_empty());
+
+        // Check all samples have reasonable amplitude
+        for sample in &modulated {
+            assert!(sample.norm() <= 2.0);
+        }
+    }
+
+    #[test]
+    fn test_dmr_demodulation() {
+        let dmr = Dmr::tier2(48000.0);
+        let data = vec![0xAB, 0xCD];
+        let modulated = dmr.modulate(&data);
+        let demod = dmr.demodulate(&modulated);
+
+        // Should recover some data
+        assert!(!demod.bits.is_empty() || !demod.symbols.is_empty());
+    }
+
+    #[test]
+    fn test_sync_patterns() {
+        // Verify sync patterns are 48 bits
+        let bs_voice = DmrSyncType::BsVoice.pattern();
+        assert!(bs_voice < (1u64 << 48));
+
+        let ms_voice = DmrSyncType::MsVoice.pattern();
+        assert!(ms_voice < (1u64 << 48));
+    }
+
+    #[test]
+    fn test_dmr_modes() {
+        let tier2 = Dmr::tier2(48000.0);
+        let tier3 = Dmr::tier3(48000.0);
+        let direct = Dmr::direct(48000.0);
+
+        assert_eq!(tier2.tier, DmrTier::Tier2);
+        assert_eq!(tier3.tier, DmrTier::Tier3);
+        assert_eq!(direct.mode, DmrMode::Direct);
+    }
+
+    #[test]
+    fn test_timeslot() {
+        let dmr = Dmr::tier2(48000.0).with_timeslot(1);
+        assert_eq!(dmr.timeslot, 1);
+
+        // Should clamp to 0 or 1
+        let dmr2 = Dmr::tier2(48000.0).with_timeslot(5);
+        assert_eq!(dmr2.timeslot, 1); // 5 & 1 = 1
+    }
+
+    /// Voice call, CSBK and data transfer on both timeslots
+    fn burst_corpus() -> Vec<DmrBurst> {
+        let call = FullLc::group_voice(91, 3_120_001);
+        let private = FullLc::unit_to_unit(2_000_002, 2_000_001);
+        let mut corpus = vec![
+            DmrBurst::new(0, 1, BurstPayload::VoiceLcHeader(call)),
+            DmrBurst::new(1, 7, BurstPayload::Csbk(Csbk::preamble(false, 2, 2_000_002, 2_000_001))),
+        ];
+        for frame in 0..DmrTiming::FRAMES_PER_SUPERFRAME {
+            let ambe = [frame as u8 * 17; 27];
+            let embedded = (frame > 0).then_some(EmbeddedFragment {
+                privacy: false,
+                lcss: if frame == 1 { Lcss::FirstFragment } else { Lcss::Continuation },
+                data: [frame as u8; 4],
+            });
+            corpus.push(DmrBurst::new(0, 1, BurstPayload::Voice { ambe, embedded }));
+            let data = match frame {
+                0 => BurstPayload::DataHeader(DataHeader::unconfirmed(false, 2_000_002, 2_000_001, 2)),
+                1 => BurstPayload::Rate12Data(*b"hello, dmr!\0"),
+                2 => BurstPayload::Rate1Data(*b"full rate data block 24b"),
+                _ => BurstPayload::Idle,
+            };
+            corpus.push(DmrBurst::new(1, 7, data));
+        }
+        corpus.push(DmrBurst::new(0, 1, BurstPayload::TerminatorWithLc(call)));
+        corpus.push(DmrBurst::new(1, 7, BurstPayload::VoiceLcHeader(private)));
+        corpus
+    }
+
+    fn assert_corpus_roundtrip(dmr: &Dmr) {
+        let corpus = burst_corpus();
+        let samples = dmr.modulate_bursts(&corpus);
+        let decoded = dmr.decode_bursts(&samples);
+
+        assert_eq!(decoded.len(), corpus.len());
+        for (tx, rx) in corpus.iter().zip(&decoded) {
+            assert_eq!(rx.timeslot, tx.timeslot);
+            assert_eq!(rx.color_code, Some(tx.color_code));
+            assert_eq!(rx.payload, tx.payload);
+            assert!(rx.fec_ok);
+        }
+    }
+
+    #[test]
+    fn test_burst_corpus_roundtrip_repeater() {
+        let dmr = Dmr::tier2(48000.0);
+        assert_corpus_roundtrip(&dmr);
+
+        let decoded = dmr.decode_bursts(&dmr.modulate_bursts(&burst_corpus()));
+        assert!(decoded.iter().all(|b| b.cach.is_some()));
+        match &decoded[0].payload {
+            BurstPayload::VoiceLcHeader(lc) => {
+                assert_eq!(lc.flco, Flco::GroupVoice);
+                assert_eq!(lc.dst_id, 91);
+                assert_eq!(lc.src_id, 3_120_001);
+            }
+            other => panic!("expected voice LC header, got {:?}", other),
+        }
+        match &decoded[1].payload {
+            BurstPayload::Csbk(csbk) => {
+                assert_eq!(csbk.opcode, Csbk::OPCODE_PREAMBLE);
+                assert_eq!(csbk.dst_id(), 2_000_002);
+                assert_eq!(csbk.src_id(), 2_000_001);
+            }
+            other => panic!("expected CSBK, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_burst_corpus_roundtrip_direct() {
+        let dmr = Dmr::direct(48000.0);
+        assert_corpus_roundtrip(&dmr);
+    }
+
+    #[test]
+    fn test_decode_bursts_with_timing_offset() {
+        let dmr = Dmr::tier2(48000.0);
+        let lc = FullLc::group_voice(1, 2);
+        let mut samples = vec![IQSample::new(1.0, 0.0); 37];
+        samples.extend(dmr.modulate_bursts(&[
+            DmrBurst::new(0, 3, BurstPayload::VoiceLcHeader(lc)),
+            DmrBurst::new(1, 3, BurstPayload::TerminatorWithLc(lc)),
+        ]));
+
+        let decoded = dmr.decode_bursts(&samples);
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].payload, BurstPayload::VoiceLcHeader(lc));
+        assert_eq!(decoded[1].timeslot, 1);
+        assert_eq!(decoded[1].color_code, Some(3));
+    }
+
+    #[test]
+    fn test_waveform_info() {
+        let dmr = Dmr::tier2(48000.0);
+        let info = dmr.info();
+
+        assert_eq!(info.name, "DMR");
+        assert!(info.description.to_lowercase().contains("etsi"));
+    }
+}
@@ -0,0 +1,432 @@
+//! Two-dimensional CFAR detection on range-Doppler maps
+//!
+//! Constant False Alarm Rate detectors estimate the local noise floor
+//! around each cell under test (CUT) from a ring of training cells,
+//! skipping a small guard region so that the target's own energy does not
+//! leak into the estimate:
+//!
+//! ```text
+//!            Doppler →
+//!        T T T T T T T T T
+//!        T T T T T T T T T
+//!  Range T T G G G G G T T
+//!    ↓   T T G G C G G T T      C = cell under test
+//!        T T G G G G G T T      G = guard cells
+//!        T T T T T T T T T      T = training cells
+//!        T T T T T T T T T
+//! ```
+//!
+//! The detection threshold is `α · noise`, where the scale `α` is chosen
+//! from the desired probability of false alarm assuming square-law
+//! detected, exponentially distributed noise:
+//!
+//! - **CA-CFAR**: noise = mean of all training cells.
+//!   `α = N · (Pfa^(-1/N) − 1)`
+//! - **GO-CFAR / SO-CFAR**: the training ring is split into leading and
+//!   lagging halves along range (cells level with the CUT count towards
+//!   both); noise = greater / smaller of the two means. GO holds Pfa at clutter edges, SO resolves closely spaced
+//!   targets. Both use the CA scaling for `N/2` cells as an approximation.
+//! - **OS-CFAR**: noise = k-th smallest training cell. `α` solves
+//!   `Pfa = Π_{i=0}^{k-1} (N − i) / (N − i + α)` and is robust against
+//!   interfering targets inside the training window.
+//!
+//! The Doppler axis wraps around (it is periodic); near the range edges
+//! only the training cells that exist are used and `α` is recomputed for
+//! the reduced cell count.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::RangeDopplerMap;
+
+/// Noise estimator used by the CFAR detector
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CfarKind {
+    /// Cell averaging: mean of all training cells
+    CellAveraging,
+    /// Greatest-of the leading/lagging training halves
+    GreatestOf,
+    /// Smallest-of the leading/lagging training halves
+    SmallestOf,
+    /// Ordered statistic: `rank` (0..1) quantile of the training cells
+    OrderedStatistic {
+        /// Fractional rank of the order statistic (typically 0.75)
+        rank: f64,
+    },
+}
+
+/// CFAR detector configuration
+#[derive(Debug, Clone)]
+pub struct CfarConfig {
+    /// Noise estimator
+    pub kind: CfarKind,
+    /// Guard cells on each side of the CUT along range
+    pub guard_range: usize,
+    /// Guard cells on each side of the CUT along Doppler
+    pub guard_doppler: usize,
+    /// Training cells beyond the guard band along range
+    pub train_range: usize,
+    /// Training cells beyond the guard band along Doppler
+    pub train_doppler: usize,
+    /// Desired probability of false alarm per cell
+    pub pfa: f64,
+    /// Only report detections that are local maxima of the map
+    pub peaks_only: bool,
+}
+
+impl Default for CfarConfig {
+    fn default() -> Self {
+        Self {
+            kind: CfarKind::CellAveraging,
+            guard_range: 2,
+            guard_doppler: 2,
+            train_range: 4,
+            train_doppler: 4,
+            pfa: 1e-6,
+            peaks_only: true,
+        }
+    }
+}
+
+impl CfarConfig {
+    /// Cell-averaging CFAR with default window
+    pub fn ca(pfa: f64) -> Self {
+        Self { kind: CfarKind::CellAveraging, pfa, ..Default::default() }
+    }
+
+    /// Ordered-statistic CFAR with default window
+    pub fn os(pfa: f64, rank: f64) -> Self {
+        Self { kind: CfarKind::OrderedStatistic { rank }, pfa, ..Default::default() }
+    }
+
+    /// Greatest-of CFAR with default window
+    pub fn go(pfa: f64) -> Self {
+        Self { kind: CfarKind::GreatestOf, pfa, ..Default::default() }
+    }
+
+    /// Smallest-of CFAR with default window
+    pub fn so(pfa: f64) -> Self {
+        Self { kind: CfarKind::SmallestOf, pfa, ..Default::default() }
+    }
+}
+
+/// A cell that exceeded the CFAR threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfarDetection {
+    /// Range bin index
+    pub range_bin: usize,
+    /// Doppler bin index
+    pub doppler_bin: usize,
+    /// Range of the bin (meters)
+    pub range_m: f64,
+    /// Velocity of the bin (m/s)
+    pub velocity_mps: f64,
+    /// Power of the cell under test (linear, magnitude squared)
+    pub power: f64,
+    /// Local noise estimate (linear)
+    pub noise: f64,
+    /// Power above the local noise estimate (dB)
+    pub snr_db: f64,
+}
+
+/// 2D CFAR detector
+#[derive(Debug, Clone)]
+pub struct Cfar2d {
+    config: CfarConfig,
+}
+
+impl Cfar2d {
+    /// Create a detector
+    pub fn new(config: CfarConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get configuration
+    pub fn config(&self) -> &CfarConfig {
+        &self.config
+    }
+
+    /// Threshold scale factor for `n` training cells
+    pub fn scale_factor(&self, n: usize) -> f64 {
+        let pfa = self.config.pfa.clamp(1e-300, 1.0);
+        match self.config.kind {
+            CfarKind::CellAveraging => ca_scale(n, pfa),
+            CfarKind::GreatestOf | CfarKind::SmallestOf => ca_scale(n / 2, pfa),
+            CfarKind::OrderedStatistic { rank } => os_scale(n, os_rank(n, rank), pfa),
+        }
+    }
+
+    /// Run the detector over a range-Doppler map
+    pub fn detect(&self, map: &RangeDopplerMap) -> Vec<CfarDetection> {
+        let power: Vec<Vec<f64>> = map
+            .data
+            .iter()
+            .map(|row| row.iter().map(|&m| m * m).collect())
+            .collect();
+        let num_range = power.len();
+        let num_doppler = power.first().map_or(0, |r| r.len());
+
+        let cfg = &self.config;
+        let outer_r = (cfg.guard_range + cfg.train_range) as isize;
+        let outer_d = (cfg.guard_doppler + cfg.train_doppler) as isize;
+        let guard_r = cfg.guard_range as isize;
+        let guard_d = cfg.guard_doppler as isize;
+
+        let mut scales: HashMap<(usize, bool), f64> = HashMap::new();
+        let mut detections = Vec::new();
+        let mut leading = Vec::new();
+        let mut lagging = Vec::new();
+        let mut center = Vec::new();
+
+        for r in 0..num_range {
+            for d in 0..num_doppler {
+                leading.clear();
+                lagging.clear();
+                center.clear();
+
+                for dr in -outer_r..=outer_r {
+                    let nr = r as isize + dr;
+                    if nr < 0 || nr >= num_range as isize {
+                        continue;
+                    }
+                    for dd in -outer_d..=outer_d {
+                        if dr.abs() <= guard_r && dd.abs() <= guard_d {
+                            continue;
+                        }
+                        let nd = (d as isize + dd).rem_euclid(num_doppler as isize);
+                        let p = power[nr as usize][nd as usize];
+                        match dr.cmp(&0) {
+                            Ordering::Less => leading.push(p),
+                            Ordering::Greater => lagging.push(p),
+                            Ordering::Equal => center.push(p),
+                        }
+                    }
+                }
+
+                let n = leading.len() + lagging.len() + center.len();
+                if n == 0 {
+                    continue;
+                }
+
+                // A one-sided window at the range edge degenerates GO/SO to CA
+                let one_sided = leading.is_empty() || lagging.is_empty();
+                let total = leading.iter().chain(&lagging).chain(&center).sum::<f64>();
+                let half_means = || {
+                    let c = center.iter().sum::<f64>();
+                    let lead = (leading.iter().sum::<f64>() + c) / (leading.len() + center.len()) as f64;
+                    let lag = (lagging.iter().sum::<f64>() + c) / (lagging.len() + center.len()) as f64;
+                    (lead, lag)
+                };
+
+                let noise = match cfg.kind {
+                    CfarKind::CellAveraging => total / n as f64,
+                    CfarKind::GreatestOf | CfarKind::SmallestOf if one_sided => total / n as f64,
+                    CfarKind::GreatestOf => {
+                        let (lead, lag) = half_means();
+                        lead.max(lag)
+                    }
+                    CfarKind::SmallestOf => {
+                        let (lead, lag) = half_means();
+                        lead.min(lag)
+                    }
+                    CfarKind::OrderedStatistic { rank } => {
+                        leading.extend_from_slice(&lagging);
+                        leading.extend_from_slice(&center);
+                        let k = os_rank(n, rank);
+                        let (_, kth, _) = leading
+                            .select_nth_unstable_by(k - 1, |a, b| a.total_cmp(b));
+                        *kth
+                    }
+                };
+
+                let alpha = *scales.entry((n, one_sided)).or_insert_with(|| {
+                    if one_sided {
+                        ca_scale(n, cfg.pfa.clamp(1e-300, 1.0))
+                    } else {
+                        self.scale_factor(n)
+                    }
+                });
+                let cut = power[r][d];
+                if cut <= alpha * noise {
+                    continue;
+                }
+                if cfg.peaks_only && !is_local_max(&power, r, d) {
+                    continue;
+                }
+
+                detections.push(CfarDetection {
+                    range_bin: r,
+                    doppler_bin: d,
+                    range_m: map.range_axis.get(r).copied().unwrap_or(0.0),
+                    velocity_mps: map.velocity_axis.get(d).copied().unwrap_or(0.0),
+                    power: cut,
+                    noise,
+                    snr_db: 10.0 * (cut / noise.max(f64::MIN_POSITIVE)).log10(),
+                });
+            }
+        }
+
+        detections
+    }
+}
+
+/// CA-CFAR scale for `n` cells
+fn ca_scale(n: usize, pfa: f64) -> f64 {
+    let n = n.max(1) as f64;
+    n * (pfa.powf(-1.0 / n) - 1.0)
+}
+
+/// 1-based order statistic index for `n` cells
+fn os_rank(n: usize, rank: f64) -> usize {
+    ((rank.clamp(0.0, 1.0) * n as f64).round() as usize).clamp(1, n.max(1))
+}
+
+/// OS-CFAR scale: solve `Π_{i<k} (n − i)/(n − i + α) = pfa` by bisection
+fn os_scale(n: usize, k: usize, pfa: f64) -> f64 {
+    let pfa_of = |alpha: f64| -> f64 {
+        (0..k)
+            .map(|i| {
+                let m = (n - i) as f64;
+                m / (m + alpha)
+            })
+            .product()
+    };
+
+    let mut lo = 0.0;
+    let mut hi = 1.0;
+    while pfa_of(hi) > pfa && hi < 1e12 {
+        hi *= 2.0;
+    }
+    for _ in 0..100 {
+        let mid = 0.5 * (lo + hi);
+        if pfa_of(mid) > pfa {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    hi
+}
+
+/// Local maximum over the 3x3 neighbourhood (Doppler wraps)
+fn is_local_max(power: &[Vec<f64>], r: usize, d: usize) -> bool {
+    let num_range = power.len() as isize;
+    let num_doppler = power[0].len() as isize;
+    let val = power[r][d];
+
+    for dr in -1isize..=1 {
+        let nr = r as isize + dr;
+        if nr < 0 || nr >= num_range {
+            continue;
+        }
+        for dd in -1isize..=1 {
+            if dr == 0 && dd == 0 {
+                continue;
+            }
+            let nd = (d as isize + dd).rem_euclid(num_doppler);
+            if power[nr as usize][nd as usize] > val {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use rand::rngs::StdRng;
+    use rand_distr::Exp1;
+
+    fn noise_map(rng: &mut StdRng, rows: usize, cols: usize) -> RangeDopplerMap {
+        // Magnitude whose square is exponential with unit mean
+        let data: Vec<Vec<f64>> = (0..rows)
+            .map(|_| {
+                (0..cols)
+                    .map(|_| {
+                        let p: f64 = rng.sample(Exp1);
+                        p.sqrt()
+                    })
+                    .collect()
+            })
+            .collect();
+        RangeDopplerMap {
+            range_axis: (0..rows).map(|r| r as f64).collect(),
+            velocity_axis: (0..cols).map(|d| d as f64 - cols as f64 / 2.0).collect(),
+            num_range_bins: rows,
+            num_doppler_bins: cols,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_ca_scale_matches_pfa() {
+        // For N = 1, α = 1/Pfa − 1
+        assert!((ca_scale(1, 0.01) - 99.0).abs() < 1e-9);
+        // Scale grows as Pfa shrinks
+        assert!(ca_scale(16, 1e-6) > ca_scale(16, 1e-3));
+    }
+
+    #[test]
+    fn test_os_scale_solves_pfa() {
+        let alpha = os_scale(24, 18, 1e-4);
+        let pfa: f64 = (0..18).map(|i| (24 - i) as f64 / ((24 - i) as f64 + alpha)).product();
+        assert!((pfa - 1e-4).abs() / 1e-4 < 1e-6);
+    }
+
+    #[test]
+    fn test_false_alarm_rate() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let map = noise_map(&mut rng, 64, 64);
+        let pfa = 1e-2;
+
+        for kind in [
+            CfarKind::CellAveraging,
+            CfarKind::OrderedStatistic { rank: 0.75 },
+            CfarKind::GreatestOf,
+        ] {
+            let cfar = Cfar2d::new(CfarConfig { kind, pfa, peaks_only: false, ..Default::default() });
+            let rate = cfar.detect(&map).len() as f64 / (64.0 * 64.0);
+            assert!(rate < 3.0 * pfa, "{:?}: false alarm rate {}", kind, rate);
+            assert!(rate > pfa / 5.0, "{:?}: false alarm rate {}", kind, rate);
+        }
+    }
+
+    #[test]
+    fn test_detects_targets_all_kinds() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut map = noise_map(&mut rng, 64, 32);
+        map.data[20][10] = 30.0;
+        map.data[45][25] = 20.0;
+
+        for kind in [
+            CfarKind::CellAveraging,
+            CfarKind::OrderedStatistic { rank: 0.75 },
+            CfarKind::GreatestOf,
+            CfarKind::SmallestOf,
+        ] {
+            let cfar = Cfar2d::new(CfarConfig { kind, ..Default::default() });
+            let dets = cfar.detect(&map);
+            assert_eq!(dets.len(), 2, "{:?}: {:?}", kind, dets);
+            assert!(dets.iter().any(|d| d.range_bin == 20 && d.doppler_bin == 10));
+            assert!(dets.iter().any(|d| d.range_bin == 45 && d.doppler_bin == 25));
+        }
+    }
+
+    #[test]
+    fn test_os_resists_masking() {
+        // A strong target inside the weak target's training window inflates
+        // the CA noise estimate; the OS estimate ignores the outlier.
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut map = noise_map(&mut rng, 48, 48);
+        map.data[24][24] = 6.0;
+        map.data[28][24] = 30.0;
+
+        let weak = |dets: &[CfarDetection]| dets.iter().any(|d| d.range_bin == 24 && d.doppler_bin == 24);
+        let ca = Cfar2d::new(CfarConfig::ca(1e-6)).detect(&map);
+        let os = Cfar2d::new(CfarConfig::os(1e-6, 0.75)).detect(&map);
+        assert!(!weak(&ca));
+        assert!(weak(&os));
+    }
+}
@@ -38,15 +38,68 @@
 //! - **Chirp duration (T)**: Affects unambiguous range and velocity
 //! - **Chirp rate (k = B/T)**: Rate of frequency change
 //! - **Number of chirps**: Enables velocity measurement via Doppler
+//!
+//! ## Processing Chain
+//!
+//! ```text
+//! targets ──► simulate_array_echo ──► range FFT ──► Doppler FFT ──► CFAR
+//!                                    (per chirp)   (per range bin)    │
+//!                  tracker ◄── angle FFT / MUSIC across channels ◄────┘
+//! ```
+//!
+//! - [`cfar`]: CA/GO/SO/OS-CFAR detection on the range-Doppler map
+//! - [`mimo`]: virtual-array MIMO layouts, angle FFT, beam scan and MUSIC
+//! - [`tracker`]: alpha-beta multi-target tracker across frames
+//!
+//! ```rust
+//! use r4w_core::waveform::fmcw::{
+//!     CfarConfig, ChirpDirection, Fmcw, FmcwConfig, RadarTarget, VirtualArray,
+//! };
+//! use r4w_core::waveform::CommonParams;
+//!
+//! let config = FmcwConfig {
+//!     bandwidth_hz: 20e6,
+//!     chirp_duration_s: 12.8e-6,
+//!     num_chirps: 16,
+//!     idle_time_s: 0.0,
+//!     chirp_direction: ChirpDirection::Sawtooth,
+//!     start_freq_offset_hz: -10e6,
+//!     use_window: true,
+//! };
+//! let common = CommonParams { sample_rate: 20e6, carrier_freq: 77e9, amplitude: 1.0 };
+//! let radar = Fmcw::new(common, config);
+//! let array = VirtualArray::uniform(2, 4, 0.5);
+//!
+//! let tx = radar.generate_frame().samples;
+//! let targets = [RadarTarget::new(45.0, 0.0).with_azimuth(20.0)];
+//! let rx = radar.simulate_array_echo(&tx, &targets, 77e9, &array);
+//!
+//! let reports = radar.process_array_frame(&tx, &rx, &array, &CfarConfig::ca(1e-6), 77e9);
+//! let strongest = reports.iter().max_by(|a, b| a.snr_db.total_cmp(&b.snr_db)).unwrap();
+//! assert!((strongest.range_m - 45.0).abs() < 7.5);
+//! assert!((strongest.azimuth_deg - 20.0).abs() < 3.0);
+//! ```
+
+pub mod cfar;
+pub mod mimo;
+pub mod tracker;
+
+pub use cfar::{Cfar2d, CfarConfig, CfarDetection, CfarKind};
+pub use mimo::{angle_fft, beamscan, music, AngleSpectrum, VirtualArray};
+pub use tracker::{MultiTargetTracker, Track, TrackerConfig};
 
 use std::f64::consts::PI;
 
+use crate::fft_utils::FftProcessor;
 use crate::types::IQSample;
 use super::{CommonParams, DemodResult, Waveform, WaveformInfo};
 
 /// Speed of light (m/s)
 pub const SPEED_OF_LIGHT: f64 = 299_792_458.0;
 
+/// Closest range (m) at which echoes are simulated; nearer targets are moved out to it
+pub const MIN_TARGET_RANGE_M: f64 = 0.1;
+
 /// FMCW modulated signal with metadata
 #[derive(Debug, Clone)]
 pub struct FmcwSignal {
@@ -192,6 +245,56 @@ impl FmcwConfig {
     }
 }
 
+/// Point target for echo simulation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadarTarget {
+    /// Range (meters); echoes use at least [`MIN_TARGET_RANGE_M`]
+    pub range_m: f64,
+    /// Radial velocity (m/s, positive closing / positive Doppler)
+    pub velocity_mps: f64,
+    /// Azimuth from boresight (degrees)
+    pub azimuth_deg: f64,
+    /// Radar cross-section (dBsm)
+    pub rcs_dbsm: f64,
+}
+
+impl RadarTarget {
+    /// Target at boresight with 0 dBsm RCS
+    pub fn new(range_m: f64, velocity_mps: f64) -> Self {
+        Self {
+            range_m,
+            velocity_mps,
+            azimuth_deg: 0.0,
+            rcs_dbsm: 0.0,
+        }
+    }
+
+    /// Set azimuth (degrees)
+    pub fn with_azimuth(mut self, azimuth_deg: f64) -> Self {
+        self.azimuth_deg = azimuth_deg;
+        self
+    }
+
+    /// Set radar cross-section (dBsm)
+    pub fn with_rcs(mut self, rcs_dbsm: f64) -> Self {
+        self.rcs_dbsm = rcs_dbsm;
+        self
+    }
+}
+
+/// Target report produced by the processing chain
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadarMeasurement {
+    /// Range (meters)
+    pub range_m: f64,
+    /// Radial velocity (m/s, positive closing)
+    pub velocity_mps: f64,
+    /// Azimuth from boresight (degrees)
+    pub azimuth_deg: f64,
+    /// SNR over the CFAR noise estimate (dB)
+    pub snr_db: f64,
+}
+
 /// FMCW radar waveform generator
 #[derive(Debug, Clone)]
 pub struct Fmcw {
@@ -310,47 +413,88 @@ impl Fmcw {
         }
     }
 
-    /// Simulate radar echo with target at given range and velocity
+    /// Simulate the echo of point targets at a single receive antenna
+    ///
+    /// Each target contributes a copy of `tx_signal` delayed by the
+    /// round-trip time (fractional delays are applied in the frequency
+    /// domain), rotated by the carrier phase `−2π·f_c·τ`, shifted by the
+    /// Doppler frequency `2v/λ` and scaled by `√σ / R²`.
     pub fn simulate_echo(
         &self,
         tx_signal: &[IQSample],
-        range_m: f64,
-        velocity_mps: f64,
+        targets: &[RadarTarget],
         carrier_freq_hz: f64,
-        rcs_db: f64,  // Radar cross-section in dB
     ) -> Vec<IQSample> {
-        let time_delay = 2.0 * range_m / SPEED_OF_LIGHT;
-        let sample_delay = (time_delay * self.common.sample_rate) as usize;
-
-        // Doppler frequency shift
-        let wavelength = SPEED_OF_LIGHT / carrier_freq_hz;
-        let doppler_freq = 2.0 * velocity_mps / wavelength;
-
-        // Attenuation (simplified path loss)
-        let attenuation = 10.0_f64.powf(rcs_db / 20.0) / (range_m * range_m);
-
         let mut rx_signal = vec![IQSample::new(0.0, 0.0); tx_signal.len()];
+        for target in targets {
+            for (rx, echo) in rx_signal.iter_mut().zip(self.target_echo(tx_signal, target, carrier_freq_hz)) {
+                *rx += echo;
+            }
+        }
+        rx_signal
+    }
 
-        for (i, _sample) in tx_signal.iter().enumerate() {
-            if i >= sample_delay && (i - sample_delay) < tx_signal.len() {
-                let t = i as f64 / self.common.sample_rate;
-                let doppler_phase = 2.0 * PI * doppler_freq * t;
-
-                // Apply delay, attenuation, and Doppler
-                let phase_shift_i = doppler_phase.cos();
-                let phase_shift_q = doppler_phase.sin();
-
-                let delayed_sample = tx_signal[i - sample_delay];
-
-                // Complex multiply for phase shift
-                rx_signal[i] = IQSample::new(
-                    attenuation * (delayed_sample.re * phase_shift_i - delayed_sample.im * phase_shift_q),
-                    attenuation * (delayed_sample.re * phase_shift_q + delayed_sample.im * phase_shift_i),
-                );
+    /// Simulate the echo of point targets at every virtual channel of a
+    /// MIMO array (one output per channel, in [`VirtualArray::positions`] order)
+    pub fn simulate_array_echo(
+        &self,
+        tx_signal: &[IQSample],
+        targets: &[RadarTarget],
+        carrier_freq_hz: f64,
+        array: &VirtualArray,
+    ) -> Vec<Vec<IQSample>> {
+        let mut channels = vec![vec![IQSample::new(0.0, 0.0); tx_signal.len()]; array.len()];
+        for target in targets {
+            let echo = self.target_echo(tx_signal, target, carrier_freq_hz);
+            let steering = array.steering_vector(target.azimuth_deg);
+            for (channel, a) in channels.iter_mut().zip(&steering) {
+                for (rx, e) in channel.iter_mut().zip(&echo) {
+                    *rx += e * a;
+                }
             }
         }
+        channels
+    }
 
-        rx_signal
+    /// Echo of a single target at the array reference point
+    fn target_echo(&self, tx_signal: &[IQSample], target: &RadarTarget, carrier_freq_hz: f64) -> Vec<IQSample> {
+        let fs = self.common.sample_rate;
+        let n = tx_signal.len();
+        // R² attenuation diverges at zero range and a negative delay has no echo
+        let range_m = target.range_m.max(MIN_TARGET_RANGE_M);
+        let time_delay = 2.0 * range_m / SPEED_OF_LIGHT;
+        let sample_delay = time_delay * fs;
+
+        // Zero-pad so the delayed tail does not wrap around to the start
+        let size = (n + sample_delay.ceil() as usize + 1).next_power_of_two();
+        let mut fft = FftProcessor::new(size);
+        let mut spectrum = fft.fft(tx_signal);
+        for (k, x) in spectrum.iter_mut().enumerate() {
+            let f = if k >= size / 2 { k as f64 - size as f64 } else { k as f64 };
+            *x *= IQSample::from_polar(1.0, -2.0 * PI * f * sample_delay / size as f64);
+        }
+        fft.ifft_inplace(&mut spectrum);
+
+        // Doppler frequency shift
+        let doppler_freq = if carrier_freq_hz > 0.0 {
+            2.0 * target.velocity_mps * carrier_freq_hz / SPEED_OF_LIGHT
+        } else {
+            0.0
+        };
+        let carrier_phase = -2.0 * PI * carrier_freq_hz * time_delay;
+
+        // Attenuation (simplified radar equation amplitude)
+        let attenuation = 10.0_f64.powf(target.rcs_dbsm / 20.0) / (range_m * range_m);
+
+        spectrum
+            .into_iter()
+            .take(n)
+            .enumerate()
+            .map(|(i, s)| {
+                let t = i as f64 / fs;
+                s * IQSample::from_polar(attenuation, carrier_phase + 2.0 * PI * doppler_freq * t)
+            })
+            .collect()
     }
 
     /// Mix TX and RX to get beat signal (dechirp)
@@ -410,6 +554,136 @@ impl Fmcw {
         (beat_freq * SPEED_OF_LIGHT * self.config.chirp_duration_s) / (2.0 * self.config.bandwidth_hz)
     }
 
+    /// Direction of chirp `chirp_idx` within a frame
+    fn frame_chirp_direction(&self, chirp_idx: usize) -> ChirpDirection {
+        match self.config.chirp_direction {
+            ChirpDirection::Down => ChirpDirection::Down,
+            ChirpDirection::Triangle if chirp_idx % 2 == 1 => ChirpDirection::Down,
+            _ => ChirpDirection::Up,
+        }
+    }
+
+    /// Range axis of the range-Doppler map (meters per bin)
+    pub fn range_axis(&self) -> Vec<f64> {
+        let resolution = self.config.range_resolution();
+        (0..self.samples_per_chirp / 2).map(|r| r as f64 * resolution).collect()
+    }
+
+    /// Velocity axis of the range-Doppler map (m/s, zero velocity centred)
+    pub fn velocity_axis(&self, carrier_freq_hz: f64) -> Vec<f64> {
+        let m = self.config.num_chirps;
+        let pri = (self.samples_per_chirp + self.samples_idle) as f64 / self.common.sample_rate;
+        let wavelength = SPEED_OF_LIGHT / carrier_freq_hz;
+        (0..m)
+            .map(|d| {
+                let doppler = (d as f64 - (m / 2) as f64) / (m as f64 * pri);
+                doppler * wavelength / 2.0
+            })
+            .collect()
+    }
+
+    /// Complex range-Doppler spectrum `[range_bin][doppler_bin]` of one
+    /// receive channel
+    ///
+    /// Each chirp is dechirped against the transmitted frame and
+    /// range-FFT'd; the Doppler FFT across chirps is shifted so that zero
+    /// velocity sits at bin `num_chirps / 2`. Hann windows are applied in
+    /// both dimensions when `use_window` is set.
+    pub fn range_doppler_spectrum(&self, tx_frame: &[IQSample], rx_frame: &[IQSample]) -> Vec<Vec<IQSample>> {
+        let n = self.samples_per_chirp;
+        let m = self.config.num_chirps;
+        let num_range = n / 2;
+        let mut range_fft = FftProcessor::new(n);
+        let mut cube = vec![vec![IQSample::new(0.0, 0.0); m]; num_range];
+
+        for chirp_idx in 0..m {
+            let (Some(tx), Some(rx)) = (self.extract_chirp(tx_frame, chirp_idx), self.extract_chirp(rx_frame, chirp_idx)) else {
+                break;
+            };
+            let mut beat = self.dechirp(&tx, &rx);
+            if self.config.use_window {
+                self.apply_window(&mut beat);
+            }
+            range_fft.fft_inplace(&mut beat);
+
+            // Up-chirp echoes land at negative beat frequencies
+            let down = self.frame_chirp_direction(chirp_idx) == ChirpDirection::Down;
+            for (r, row) in cube.iter_mut().enumerate() {
+                row[chirp_idx] = if down { beat[r] } else { beat[(n - r) % n] };
+            }
+        }
+
+        let mut doppler_fft = FftProcessor::new(m);
+        for row in cube.iter_mut() {
+            if self.config.use_window && m > 1 {
+                self.apply_window(row);
+            }
+            doppler_fft.fft_inplace(row);
+            row.rotate_right(m / 2);
+        }
+
+        cube
+    }
+
+    /// Magnitude range-Doppler map of one receive channel
+    pub fn range_doppler_map(&self, tx_frame: &[IQSample], rx_frame: &[IQSample], carrier_freq_hz: f64) -> RangeDopplerMap {
+        let spectrum = self.range_doppler_spectrum(tx_frame, rx_frame);
+        RangeDopplerMap::from_power(
+            spectrum.iter().map(|row| row.iter().map(|x| x.norm_sqr()).collect()).collect(),
+            self.range_axis(),
+            self.velocity_axis(carrier_freq_hz),
+        )
+    }
+
+    /// Full MIMO processing of one frame: non-coherent range-Doppler map
+    /// over all virtual channels, CFAR detection, then angle estimation on
+    /// the channel snapshot of every detected cell
+    ///
+    /// Uniform virtual arrays use a 256-point angle FFT; other layouts fall
+    /// back to a 0.5° Bartlett beam scan.
+    pub fn process_array_frame(
+        &self,
+        tx_frame: &[IQSample],
+        rx_channels: &[Vec<IQSample>],
+        array: &VirtualArray,
+        cfar: &CfarConfig,
+        carrier_freq_hz: f64,
+    ) -> Vec<RadarMeasurement> {
+        let spectra: Vec<Vec<Vec<IQSample>>> = rx_channels
+            .iter()
+            .map(|rx| self.range_doppler_spectrum(tx_frame, rx))
+            .collect();
+        let Some(first) = spectra.first() else {
+            return Vec::new();
+        };
+
+        let power: Vec<Vec<f64>> = (0..first.len())
+            .map(|r| {
+                (0..first[r].len())
+                    .map(|d| spectra.iter().map(|s| s[r][d].norm_sqr()).sum())
+                    .collect()
+            })
+            .collect();
+        let map = RangeDopplerMap::from_power(power, self.range_axis(), self.velocity_axis(carrier_freq_hz));
+        let scan: Vec<f64> = (0..=360).map(|i| -90.0 + 0.5 * i as f64).collect();
+
+        Cfar2d::new(cfar.clone())
+            .detect(&map)
+            .into_iter()
+            .map(|det| {
+                let snapshot: Vec<IQSample> = spectra.iter().map(|s| s[det.range_bin][det.doppler_bin]).collect();
+                let spectrum = angle_fft(&snapshot, array, 256)
+                    .unwrap_or_else(|| beamscan(&snapshot, array, &scan));
+                RadarMeasurement {
+                    range_m: det.range_m,
+                    velocity_mps: det.velocity_mps,
+                    azimuth_deg: spectrum.peak().unwrap_or(0.0),
+                    snr_db: det.snr_db,
+                }
+            })
+            .collect()
+    }
+
     /// Generate info string about radar parameters
     pub fn info(&self, carrier_freq_hz: f64) -> String {
         format!(
@@ -452,6 +726,25 @@ pub struct RangeDopplerMap {
 }
 
 impl RangeDopplerMap {
+    /// Build a map from linear power values `[range_bin][doppler_bin]`
+    pub fn from_power(power: Vec<Vec<f64>>, range_axis: Vec<f64>, velocity_axis: Vec<f64>) -> Self {
+        let num_range_bins = power.len();
+        let num_doppler_bins = power.first().map_or(0, |r| r.len());
+        Self {
+            data: power.into_iter().map(|row| row.into_iter().map(f64::sqrt).collect()).collect(),
+            range_axis,
+            velocity_axis,
+            num_range_bins,
+            num_doppler_bins,
+        }
+    }
+
+    /// Detect targets with an adaptive CFAR threshold instead of the
+    /// global threshold used by [`find_targets`](Self::find_targets)
+    pub fn detect_cfar(&self, config: &CfarConfig) -> Vec<CfarDetection> {
+        Cfar2d::new(config.clone()).detect(self)
+    }
+
     /// Find peaks in the range-Doppler map
     pub fn find_targets(&self, threshold_db: f64) -> Vec<(f64, f64, f64)> {
         let mut targets = Vec::new();
@@ -742,7 +1035,7 @@ mod tests {
         let tx = fmcw.generate_chirp(ChirpDirection::Up);
 
         // Simulate target at 50m, 0 m/s velocity
-        let rx = fmcw.simulate_echo(&tx, 50.0, 0.0, 24e9, 0.0);
+        let rx = fmcw.simulate_echo(&tx, &[RadarTarget::new(50.0, 0.0)], 24e9);
 
         // RX should have delayed signal
         assert_eq!(rx.len(), tx.len());
@@ -764,4 +1057,151 @@ mod tests {
             assert!(energy_after > energy_before * 0.1 || energy_before < 0.001);
         }
     }
+
+    fn chain_radar() -> Fmcw {
+        let config = FmcwConfig {
+            bandwidth_hz: 20e6,
+            chirp_duration_s: 25.6e-6,
+            num_chirps: 32,
+            idle_time_s: 0.0,
+            chirp_direction: ChirpDirection::Sawtooth,
+            start_freq_offset_hz: -10e6,
+            use_window: true,
+        };
+        Fmcw::new(
+            CommonParams {
+                sample_rate: 20e6,
+                carrier_freq: 77e9,
+                amplitude: 1.0,
+            },
+            config,
+        )
+    }
+
+    fn add_noise(rng: &mut rand::rngs::StdRng, channels: &mut [Vec<IQSample>], sigma: f64) {
+        use rand::Rng;
+        use rand_distr::StandardNormal;
+        for channel in channels.iter_mut() {
+            for s in channel.iter_mut() {
+                let n: f64 = rng.sample(StandardNormal);
+                let m: f64 = rng.sample(StandardNormal);
+                *s += IQSample::new(n * sigma, m * sigma);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_target_echo_superposition() {
+        let fmcw = chain_radar();
+        let tx = fmcw.generate_frame().samples;
+        let a = RadarTarget::new(60.0, 5.0).with_rcs(10.0);
+        let b = RadarTarget::new(150.0, -10.0).with_rcs(15.0);
+
+        let both = fmcw.simulate_echo(&tx, &[a, b], 77e9);
+        let only_a = fmcw.simulate_echo(&tx, &[a], 77e9);
+        let only_b = fmcw.simulate_echo(&tx, &[b], 77e9);
+        for i in (0..tx.len()).step_by(97) {
+            assert!((both[i] - only_a[i] - only_b[i]).norm() < 1e-12);
+        }
+
+        // Boresight target: every virtual channel sees the same echo
+        let array = VirtualArray::uniform(2, 2, 0.5);
+        let channels = fmcw.simulate_array_echo(&tx, &[a], 77e9, &array);
+        assert_eq!(channels.len(), 4);
+        assert!((channels[3][1000] - only_a[1000]).norm() < 1e-12);
+
+        // Targets at or behind the radar are clamped to the minimum range
+        let near = fmcw.simulate_echo(&tx, &[RadarTarget::new(MIN_TARGET_RANGE_M, 0.0)], 77e9);
+        for range_m in [0.0, -20.0, f64::NAN] {
+            let echo = fmcw.simulate_echo(&tx, &[RadarTarget::new(range_m, 0.0)], 77e9);
+            assert!(echo.iter().all(|s| s.re.is_finite() && s.im.is_finite()));
+            assert_eq!(echo, near);
+        }
+    }
+
+    #[test]
+    fn test_range_doppler_map_peaks() {
+        let fmcw = chain_radar();
+        let tx = fmcw.generate_frame().samples;
+        let targets = [RadarTarget::new(60.0, 5.0), RadarTarget::new(150.0, -10.0)];
+        let rx = fmcw.simulate_echo(&tx, &targets, 77e9);
+
+        let map = fmcw.range_doppler_map(&tx, &rx, 77e9);
+        assert_eq!(map.num_range_bins, 256);
+        assert_eq!(map.num_doppler_bins, 32);
+
+        let dets = map.detect_cfar(&CfarConfig::ca(1e-6));
+        assert_eq!(dets.len(), 2, "{:?}", dets);
+        for target in &targets {
+            assert!(dets.iter().any(|d| {
+                (d.range_m - target.range_m).abs() <= fmcw.config().range_resolution()
+                    && (d.velocity_mps - target.velocity_mps).abs() <= fmcw.config().velocity_resolution(77e9)
+            }), "{:?} not in {:?}", target, dets);
+        }
+    }
+
+    #[test]
+    fn test_mimo_chain_with_noise() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(30);
+        let fmcw = chain_radar();
+        let array = VirtualArray::uniform(2, 4, 0.5);
+        let tx = fmcw.generate_frame().samples;
+        let targets = [
+            RadarTarget::new(60.0, 5.0).with_azimuth(-20.0).with_rcs(10.0),
+            RadarTarget::new(150.0, -10.0).with_azimuth(25.0).with_rcs(15.0),
+            // Same range as the first target but different velocity and angle
+            RadarTarget::new(60.0, -20.0).with_azimuth(10.0).with_rcs(5.0),
+        ];
+        let mut rx = fmcw.simulate_array_echo(&tx, &targets, 77e9, &array);
+        add_noise(&mut rng, &mut rx, 1e-4);
+
+        for kind in [CfarKind::CellAveraging, CfarKind::OrderedStatistic { rank: 0.75 }, CfarKind::GreatestOf] {
+            let cfar = CfarConfig { kind, ..CfarConfig::ca(1e-6) };
+            let reports = fmcw.process_array_frame(&tx, &rx, &array, &cfar, 77e9);
+            assert_eq!(reports.len(), 3, "{:?}: {:?}", kind, reports);
+            for target in &targets {
+                assert!(reports.iter().any(|r| {
+                    (r.range_m - target.range_m).abs() <= 7.5
+                        && (r.velocity_mps - target.velocity_mps).abs() <= 2.5
+                        && (r.azimuth_deg - target.azimuth_deg).abs() <= 2.0
+                }), "{:?}: {:?} not in {:?}", kind, target, reports);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tracking_over_frames() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(31);
+        let fmcw = chain_radar();
+        let array = VirtualArray::uniform(2, 4, 0.5);
+        let tx = fmcw.generate_frame().samples;
+        let mut tracker = MultiTargetTracker::new(TrackerConfig {
+            gate_range_m: 10.0,
+            ..Default::default()
+        });
+        let dt = 0.05;
+
+        for frame in 0..12 {
+            let t = frame as f64 * dt;
+            let targets = [
+                RadarTarget::new(100.0 - 15.0 * t, 15.0).with_azimuth(-10.0).with_rcs(10.0),
+                RadarTarget::new(40.0 + 10.0 * t, -10.0).with_azimuth(30.0).with_rcs(10.0),
+            ];
+            let mut rx = fmcw.simulate_array_echo(&tx, &targets, 77e9, &array);
+            add_noise(&mut rng, &mut rx, 1e-4);
+            let reports = fmcw.process_array_frame(&tx, &rx, &array, &CfarConfig::ca(1e-6), 77e9);
+            tracker.update(&reports, dt);
+        }
+
+        let confirmed: Vec<&Track> = tracker.confirmed().collect();
+        assert_eq!(confirmed.len(), 2, "{:?}", tracker.tracks());
+        let t_end = 11.0 * dt;
+        let closing = confirmed.iter().find(|t| t.azimuth_deg < 0.0).unwrap();
+        let receding = confirmed.iter().find(|t| t.azimuth_deg > 0.0).unwrap();
+        assert!((closing.range_m - (100.0 - 15.0 * t_end)).abs() < 5.0, "{:?}", closing);
+        assert!((receding.range_m - (40.0 + 10.0 * t_end)).abs() < 5.0, "{:?}", receding);
+        assert!((closing.velocity_mps - 15.0).abs() < 3.0, "{:?}", closing);
+    }
 }
@@ -0,0 +1,255 @@
+//! Multi-target alpha-beta tracker
+//!
+//! Detections from successive frames are associated to existing tracks by
+//! greedy global-nearest-neighbour matching inside a gate, then smoothed
+//! with fixed-gain alpha-beta filters:
+//!
+//! ```text
+//! predict:  r̂ = r − v·Δt           (v > 0 means closing, range decreases)
+//!           θ̂ = θ + θ̇·Δt
+//! update:   r = r̂ + α·(z_r − r̂)
+//!           v = v + α·(z_v − v)
+//!           θ = θ̂ + α·(z_θ − θ̂)
+//!           θ̇ = θ̇ + (β/Δt)·(z_θ − θ̂)
+//! ```
+//!
+//! The range rate is measured directly by the Doppler processing, so only
+//! azimuth needs a rate estimate derived from position residuals.
+//!
+//! Unassociated detections start tentative tracks, which are confirmed
+//! after `confirm_hits` updates and dropped after `max_misses` consecutive
+//! frames without a detection.
+
+use super::RadarMeasurement;
+
+/// Tracker configuration
+#[derive(Debug, Clone)]
+pub struct TrackerConfig {
+    /// Position smoothing gain (0..1)
+    pub alpha: f64,
+    /// Azimuth-rate smoothing gain (0..1)
+    pub beta: f64,
+    /// Association gate in range (meters)
+    pub gate_range_m: f64,
+    /// Association gate in velocity (m/s)
+    pub gate_velocity_mps: f64,
+    /// Association gate in azimuth (degrees)
+    pub gate_azimuth_deg: f64,
+    /// Hits required to confirm a track
+    pub confirm_hits: usize,
+    /// Consecutive misses before a track is dropped
+    pub max_misses: usize,
+}
+
+impl Default for TrackerConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.5,
+            beta: 0.2,
+            gate_range_m: 5.0,
+            gate_velocity_mps: 3.0,
+            gate_azimuth_deg: 8.0,
+            confirm_hits: 3,
+            max_misses: 3,
+        }
+    }
+}
+
+/// A tracked target
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track {
+    /// Unique track identifier
+    pub id: u32,
+    /// Filtered range (meters)
+    pub range_m: f64,
+    /// Filtered radial velocity (m/s, positive closing)
+    pub velocity_mps: f64,
+    /// Filtered azimuth (degrees)
+    pub azimuth_deg: f64,
+    /// Azimuth rate (degrees/s)
+    pub azimuth_rate_dps: f64,
+    /// Number of associated detections
+    pub hits: usize,
+    /// Consecutive frames without a detection
+    pub misses: usize,
+    /// Whether the track has been confirmed
+    pub confirmed: bool,
+}
+
+impl Track {
+    fn predicted(&self, dt: f64) -> (f64, f64) {
+        (
+            self.range_m - self.velocity_mps * dt,
+            self.azimuth_deg + self.azimuth_rate_dps * dt,
+        )
+    }
+}
+
+/// Multi-target alpha-beta tracker
+#[derive(Debug, Clone)]
+pub struct MultiTargetTracker {
+    config: TrackerConfig,
+    tracks: Vec<Track>,
+    next_id: u32,
+}
+
+impl MultiTargetTracker {
+    /// Create a tracker
+    pub fn new(config: TrackerConfig) -> Self {
+        Self { config, tracks: Vec::new(), next_id: 1 }
+    }
+
+    /// All live tracks (tentative and confirmed)
+    pub fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    /// Confirmed tracks only
+    pub fn confirmed(&self) -> impl Iterator<Item = &Track> {
+        self.tracks.iter().filter(|t| t.confirmed)
+    }
+
+    /// Process one frame of detections taken `dt` seconds after the previous one
+    pub fn update(&mut self, measurements: &[RadarMeasurement], dt: f64) -> &[Track] {
+        let cfg = &self.config;
+
+        // Normalised distance for every gated track/measurement pair
+        let mut pairs = Vec::new();
+        for (ti, track) in self.tracks.iter().enumerate() {
+            let (r_pred, az_pred) = track.predicted(dt);
+            for (mi, m) in measurements.iter().enumerate() {
+                let dr = (m.range_m - r_pred) / cfg.gate_range_m;
+                let dv = (m.velocity_mps - track.velocity_mps) / cfg.gate_velocity_mps;
+                let da = (m.azimuth_deg - az_pred) / cfg.gate_azimuth_deg;
+                if dr.abs() <= 1.0 && dv.abs() <= 1.0 && da.abs() <= 1.0 {
+                    pairs.push((dr * dr + dv * dv + da * da, ti, mi));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut track_used = vec![false; self.tracks.len()];
+        let mut meas_used = vec![false; measurements.len()];
+        for &(_, ti, mi) in &pairs {
+            if track_used[ti] || meas_used[mi] {
+                continue;
+            }
+            track_used[ti] = true;
+            meas_used[mi] = true;
+
+            let m = &measurements[mi];
+            let track = &mut self.tracks[ti];
+            let (r_pred, az_pred) = track.predicted(dt);
+            let e_r = m.range_m - r_pred;
+            let e_az = m.azimuth_deg - az_pred;
+
+            track.range_m = r_pred + cfg.alpha * e_r;
+            track.velocity_mps += cfg.alpha * (m.velocity_mps - track.velocity_mps);
+            track.azimuth_deg = az_pred + cfg.alpha * e_az;
+            if dt > 0.0 {
+                track.azimuth_rate_dps += cfg.beta / dt * e_az;
+            }
+            track.hits += 1;
+            track.misses = 0;
+            track.confirmed |= track.hits >= cfg.confirm_hits;
+        }
+
+        for (track, used) in self.tracks.iter_mut().zip(&track_used) {
+            if !used {
+                let (r_pred, az_pred) = track.predicted(dt);
+                track.range_m = r_pred;
+                track.azimuth_deg = az_pred;
+                track.misses += 1;
+            }
+        }
+        let max_misses = cfg.max_misses;
+        self.tracks.retain(|t| t.misses <= max_misses);
+
+        for (m, _) in measurements.iter().zip(&meas_used).filter(|(_, used)| !**used) {
+            self.tracks.push(Track {
+                id: self.next_id,
+                range_m: m.range_m,
+                velocity_mps: m.velocity_mps,
+                azimuth_deg: m.azimuth_deg,
+                azimuth_rate_dps: 0.0,
+                hits: 1,
+                misses: 0,
+                confirmed: self.config.confirm_hits <= 1,
+            });
+            self.next_id += 1;
+        }
+
+        &self.tracks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use rand::rngs::StdRng;
+    use rand_distr::Normal;
+
+    fn meas(range_m: f64, velocity_mps: f64, azimuth_deg: f64) -> RadarMeasurement {
+        RadarMeasurement { range_m, velocity_mps, azimuth_deg, snr_db: 20.0 }
+    }
+
+    #[test]
+    fn test_tracks_two_targets() {
+        let mut tracker = MultiTargetTracker::new(TrackerConfig::default());
+        let mut rng = StdRng::seed_from_u64(1);
+        let noise_r = Normal::new(0.0, 0.5).unwrap();
+        let noise_a = Normal::new(0.0, 0.5).unwrap();
+        let dt = 0.05;
+
+        for frame in 0..40 {
+            let t = frame as f64 * dt;
+            // Target A closing at 10 m/s, target B receding at 5 m/s
+            let a = meas(80.0 - 10.0 * t + rng.sample(noise_r), 10.0, -15.0 + rng.sample(noise_a));
+            let b = meas(40.0 + 5.0 * t + rng.sample(noise_r), -5.0, 20.0 + 4.0 * t + rng.sample(noise_a));
+            tracker.update(&[a, b], dt);
+        }
+
+        let confirmed: Vec<&Track> = tracker.confirmed().collect();
+        assert_eq!(confirmed.len(), 2);
+
+        let t_end = 39.0 * dt;
+        let a = confirmed.iter().find(|t| t.azimuth_deg < 0.0).unwrap();
+        let b = confirmed.iter().find(|t| t.azimuth_deg > 0.0).unwrap();
+        assert!((a.range_m - (80.0 - 10.0 * t_end)).abs() < 1.0, "{:?}", a);
+        assert!((a.velocity_mps - 10.0).abs() < 1.0, "{:?}", a);
+        assert!((b.range_m - (40.0 + 5.0 * t_end)).abs() < 1.0, "{:?}", b);
+        assert!((b.azimuth_rate_dps - 4.0).abs() < 3.0, "{:?}", b);
+        // Identities are stable: only two tracks were ever created
+        assert!(confirmed.iter().all(|t| t.id <= 2));
+    }
+
+    #[test]
+    fn test_track_lifecycle() {
+        let config = TrackerConfig { confirm_hits: 2, max_misses: 1, ..Default::default() };
+        let mut tracker = MultiTargetTracker::new(config);
+
+        tracker.update(&[meas(50.0, 0.0, 0.0)], 0.1);
+        assert_eq!(tracker.tracks().len(), 1);
+        assert!(!tracker.tracks()[0].confirmed);
+
+        tracker.update(&[meas(50.1, 0.0, 0.2)], 0.1);
+        assert!(tracker.tracks()[0].confirmed);
+
+        // Coast through one miss, drop after the second
+        tracker.update(&[], 0.1);
+        assert_eq!(tracker.tracks().len(), 1);
+        tracker.update(&[], 0.1);
+        assert!(tracker.tracks().is_empty());
+    }
+
+    #[test]
+    fn test_clutter_starts_tentative_tracks_only() {
+        let mut tracker = MultiTargetTracker::new(TrackerConfig::default());
+        for frame in 0..10 {
+            let clutter = meas(10.0 + 17.0 * frame as f64, 0.0, -30.0 + 7.0 * frame as f64);
+            tracker.update(&[meas(60.0, 0.0, 5.0), clutter], 0.1);
+        }
+        assert_eq!(tracker.confirmed().count(), 1);
+    }
+}
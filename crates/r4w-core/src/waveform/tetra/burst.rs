@@ -0,0 +1,212 @@
+//! TETRA Burst Assembly
+//!
+//! Bit layouts of the TMO bursts (EN 300 392-2 clause 9.4.4):
+//!
+//! ```text
+//! Synchronization continuous downlink burst (510 bits):
+//! │ q11-22 │HA│ freq corr (80) │ BSCH (120) │ y (38) │ AACH (30) │ block 2 (216) │HB│ q1-10 │
+//!
+//! Normal continuous downlink burst (510 bits):
+//! │ q11-22 │HA│ block 1 (216) │ bb (14) │ n/p (22) │ bb (16) │ block 2 (216) │HB│ q1-10 │
+//!
+//! Control uplink burst (206 bits + guard):
+//! │ tail (4) │ SCH/HU 1 (84) │ x (30) │ SCH/HU 2 (84) │ tail (4) │
+//! ```
+//!
+//! Normal downlink bursts use training sequence `n` when blocks 1 and 2
+//! carry one full-slot channel, and `p` when they carry two half-slot channels.
+
+/// Normal training sequence 1 (`n`): full-slot blocks
+pub const NORMAL_TRAINING_N: [u8; 22] = [
+    1, 1, 0, 1, 0, 0, 0, 0, 1, 1, 1, 0, 1, 0, 0, 1, 1, 1, 0, 1, 0, 0,
+];
+
+/// Normal training sequence 2 (`p`): two half-slot blocks
+pub const NORMAL_TRAINING_P: [u8; 22] = [
+    0, 1, 1, 1, 1, 0, 1, 0, 0, 1, 0, 0, 0, 0, 1, 1, 0, 1, 1, 1, 1, 0,
+];
+
+/// Normal training sequence 3 (`q`): split across continuous burst edges
+pub const NORMAL_TRAINING_Q: [u8; 22] = [
+    1, 0, 1, 1, 0, 1, 1, 1, 0, 0, 0, 0, 0, 1, 1, 0, 1, 0, 1, 1, 0, 1,
+];
+
+/// Extended training sequence (`x`) used by control uplink bursts
+pub const EXTENDED_TRAINING_X: [u8; 30] = [
+    1, 0, 0, 1, 1, 1, 0, 1, 0, 0, 0, 0, 1, 1, 1, 0, 1, 0, 0, 1, 1, 1, 0, 1, 0, 0, 0, 0, 1, 1,
+];
+
+/// Synchronization training sequence (`y`)
+pub const SYNC_TRAINING_Y: [u8; 38] = [
+    1, 1, 0, 0, 0, 0, 0, 1, 1, 0, 0, 1, 1, 1, 0, 0, 1, 1, 1, 0, 1, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0,
+    1, 1, 0, 0, 1, 1, 1,
+];
+
+/// Bits in a continuous downlink burst (one timeslot)
+pub const DOWNLINK_BURST_BITS: usize = 510;
+/// Bits in a control uplink burst (excluding guard)
+pub const CONTROL_UPLINK_BITS: usize = 206;
+
+/// Offset of the BSCH block in a synchronization burst
+pub const SB_BSCH_OFFSET: usize = 94;
+/// Offset of the synchronization training sequence
+pub const SB_TRAINING_OFFSET: usize = 214;
+/// Offset of the broadcast block (AACH) in a synchronization burst
+pub const SB_AACH_OFFSET: usize = 252;
+/// Offset of block 2 in either downlink burst
+pub const BLOCK2_OFFSET: usize = 282;
+/// Offset of block 1 in a normal downlink burst
+pub const NDB_BLOCK1_OFFSET: usize = 14;
+/// Offset of the normal training sequence in a normal downlink burst
+pub const NDB_TRAINING_OFFSET: usize = 244;
+/// Offset of the extended training sequence in a control uplink burst
+pub const CB_TRAINING_OFFSET: usize = 88;
+
+/// Bits in one half of a control uplink burst's SCH/HU block
+const CB_HALF_BITS: usize = 84;
+
+/// Frequency correction field: 8 ones, 64 zeros, 8 ones
+fn frequency_correction() -> Vec<u8> {
+    let mut bits = vec![1u8; 8];
+    bits.extend_from_slice(&[0; 64]);
+    bits.extend_from_slice(&[1; 8]);
+    bits
+}
+
+/// Training sequence carried by a normal downlink burst
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalTraining {
+    /// `n`: blocks 1 and 2 form one full-slot channel
+    FullSlot,
+    /// `p`: blocks 1 and 2 are independent half-slot channels
+    HalfSlots,
+}
+
+impl NormalTraining {
+    /// Training bits
+    pub fn bits(self) -> &'static [u8] {
+        match self {
+            Self::FullSlot => &NORMAL_TRAINING_N,
+            Self::HalfSlots => &NORMAL_TRAINING_P,
+        }
+    }
+}
+
+/// Continuous downlink burst contents (type-5 bits)
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownlinkBurst {
+    /// Synchronization burst: BSCH (120), AACH (30), block 2 (216)
+    Sync {
+        /// Scrambled BSCH bits
+        bsch: Vec<u8>,
+        /// Broadcast block bits
+        aach: Vec<u8>,
+        /// Block 2 bits (BNCH or SCH/HD)
+        block2: Vec<u8>,
+    },
+    /// Normal burst: block 1 (216), AACH (30), block 2 (216)
+    Normal {
+        /// Training sequence selecting full- or half-slot use
+        training: NormalTraining,
+        /// Block 1 bits
+        block1: Vec<u8>,
+        /// Broadcast block bits
+        aach: Vec<u8>,
+        /// Block 2 bits
+        block2: Vec<u8>,
+    },
+}
+
+impl DownlinkBurst {
+    /// Assemble the 510 burst bits
+    pub fn to_bits(&self) -> Vec<u8> {
+        let mut bits = Vec::with_capacity(DOWNLINK_BURST_BITS);
+        bits.extend_from_slice(&NORMAL_TRAINING_Q[10..]);
+        bits.extend_from_slice(&[0, 0]); // phase adjustment HA
+
+        match self {
+            Self::Sync { bsch, aach, block2 } => {
+                bits.extend(frequency_correction());
+                bits.extend_from_slice(&bsch[..120]);
+                bits.extend_from_slice(&SYNC_TRAINING_Y);
+                bits.extend_from_slice(&aach[..30]);
+                bits.extend_from_slice(&block2[..216]);
+            }
+            Self::Normal {
+                training,
+                block1,
+                aach,
+                block2,
+            } => {
+                bits.extend_from_slice(&block1[..216]);
+                bits.extend_from_slice(&aach[..14]);
+                bits.extend_from_slice(training.bits());
+                bits.extend_from_slice(&aach[14..30]);
+                bits.extend_from_slice(&block2[..216]);
+            }
+        }
+
+        bits.extend_from_slice(&[0, 0]); // phase adjustment HB
+        bits.extend_from_slice(&NORMAL_TRAINING_Q[..10]);
+        bits
+    }
+}
+
+/// Control uplink burst carrying one SCH/HU block (168 type-5 bits)
+pub fn control_uplink_bits(sch_hu: &[u8]) -> Vec<u8> {
+    let mut bits = Vec::with_capacity(CONTROL_UPLINK_BITS);
+    bits.extend_from_slice(&[0; 4]);
+    bits.extend_from_slice(&sch_hu[..CB_HALF_BITS]);
+    bits.extend_from_slice(&EXTENDED_TRAINING_X);
+    bits.extend_from_slice(&sch_hu[CB_HALF_BITS..2 * CB_HALF_BITS]);
+    bits.extend_from_slice(&[0; 4]);
+    bits
+}
+
+/// Extract the SCH/HU soft bits from a control uplink burst
+pub fn control_uplink_payload<T: Copy>(burst: &[T]) -> Vec<T> {
+    let mut out = burst[4..4 + CB_HALF_BITS].to_vec();
+    let second = CB_TRAINING_OFFSET + EXTENDED_TRAINING_X.len();
+    out.extend_from_slice(&burst[second..second + CB_HALF_BITS]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downlink_burst_lengths() {
+        let sync = DownlinkBurst::Sync {
+            bsch: vec![1; 120],
+            aach: vec![0; 30],
+            block2: vec![1; 216],
+        };
+        let bits = sync.to_bits();
+        assert_eq!(bits.len(), DOWNLINK_BURST_BITS);
+        assert_eq!(&bits[SB_TRAINING_OFFSET..SB_TRAINING_OFFSET + 38], &SYNC_TRAINING_Y);
+        assert!(bits[SB_BSCH_OFFSET..SB_BSCH_OFFSET + 120].iter().all(|&b| b == 1));
+
+        let normal = DownlinkBurst::Normal {
+            training: NormalTraining::HalfSlots,
+            block1: vec![0; 216],
+            aach: vec![0; 30],
+            block2: vec![0; 216],
+        };
+        let bits = normal.to_bits();
+        assert_eq!(bits.len(), DOWNLINK_BURST_BITS);
+        assert_eq!(
+            &bits[NDB_TRAINING_OFFSET..NDB_TRAINING_OFFSET + 22],
+            &NORMAL_TRAINING_P
+        );
+    }
+
+    #[test]
+    fn test_control_uplink_layout() {
+        let payload: Vec<u8> = (0..168).map(|i| (i % 3 == 0) as u8).collect();
+        let bits = control_uplink_bits(&payload);
+        assert_eq!(bits.len(), CONTROL_UPLINK_BITS);
+        assert_eq!(&bits[CB_TRAINING_OFFSET..CB_TRAINING_OFFSET + 30], &EXTENDED_TRAINING_X);
+        assert_eq!(control_uplink_payload(&bits), payload);
+    }
+}
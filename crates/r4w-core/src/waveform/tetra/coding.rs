@@ -0,0 +1,428 @@
+//! TETRA Channel Coding
+//!
+//! Implements the lower MAC coding chain of EN 300 392-2 clause 8:
+//!
+//! ```text
+//! type-1 ─► CRC-16 + 4 tail ─► type-2 ─► RCPC ─► type-3 ─► interleave ─► type-4 ─► scramble ─► type-5
+//! ```
+//!
+//! - **Block code**: CRC-CCITT, complemented, with all-ones preset
+//! - **RCPC**: rate 1/4 mother code (K=5) punctured to rate 2/3 or 1/3
+//! - **Interleaving**: block interleaver `k = 1 + (a·i mod K)`
+//! - **Scrambling**: 32-bit LFSR seeded with the extended colour code
+//!   (MCC, MNC, colour code); BSCH uses the all-zero code
+//!
+//! Bits are stored one per byte. Soft bits are `f64` values where a positive
+//! value means bit 1 and magnitude is confidence (0.0 = erasure).
+
+/// Mother code generator taps (delays 0..4) for G1..G4
+const MOTHER_CODE: [[u8; 5]; 4] = [
+    [1, 1, 0, 0, 1], // G1 = 1 + D + D⁴
+    [1, 0, 1, 1, 1], // G2 = 1 + D² + D³ + D⁴
+    [1, 1, 1, 0, 1], // G3 = 1 + D + D² + D⁴
+    [1, 1, 0, 1, 1], // G4 = 1 + D + D³ + D⁴
+];
+
+/// Number of tail bits flushing the K=5 encoder
+pub const TAIL_BITS: usize = 4;
+
+/// Number of CRC bits appended to type-1 blocks
+pub const CRC_BITS: usize = 16;
+
+/// Rate-compatible punctured convolutional code rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RcpcRate {
+    /// Rate 2/3 (signalling channels)
+    TwoThirds,
+    /// Rate 1/3 (TCH/2.4 style protection)
+    OneThird,
+}
+
+impl RcpcRate {
+    /// Puncturing pattern P(1..t) (1-based positions within each period of 8 mother bits)
+    fn pattern(self) -> &'static [usize] {
+        match self {
+            Self::TwoThirds => &[1, 2, 5],
+            Self::OneThird => &[1, 2, 3, 5, 6, 7],
+        }
+    }
+
+    /// Number of type-3 bits for `type2_bits` input bits
+    pub fn coded_len(self, type2_bits: usize) -> usize {
+        type2_bits * self.pattern().len() / 2
+    }
+
+    /// Mother-code index of type-3 bit `j` (0-based)
+    fn mother_index(self, j: usize) -> usize {
+        let t = self.pattern().len();
+        8 * (j / t) + self.pattern()[j % t] - 1
+    }
+}
+
+/// Encode with the rate 1/4 mother code, returning 4 bits per input bit
+fn mother_encode(bits: &[u8]) -> Vec<u8> {
+    let mut window = [0u8; 5];
+    let mut out = Vec::with_capacity(bits.len() * 4);
+    for &bit in bits {
+        window.rotate_right(1);
+        window[0] = bit & 1;
+        for taps in &MOTHER_CODE {
+            out.push(taps.iter().zip(&window).fold(0, |acc, (&t, &w)| acc ^ (t & w)));
+        }
+    }
+    out
+}
+
+/// RCPC encode type-2 bits (which must end with the 4 tail zeros)
+pub fn rcpc_encode(type2: &[u8], rate: RcpcRate) -> Vec<u8> {
+    let mother = mother_encode(type2);
+    (0..rate.coded_len(type2.len()))
+        .map(|j| mother[rate.mother_index(j)])
+        .collect()
+}
+
+/// Viterbi decode soft type-3 bits back to `type2_len` type-2 bits.
+///
+/// The trellis starts and ends in state 0 (tail-terminated).
+pub fn rcpc_decode(soft: &[f64], type2_len: usize, rate: RcpcRate) -> Vec<u8> {
+    const STATES: usize = 16;
+
+    // De-puncture: erased mother bits get zero metric
+    let mut mother = vec![0.0; type2_len * 4];
+    for (j, &s) in soft.iter().enumerate().take(rate.coded_len(type2_len)) {
+        mother[rate.mother_index(j)] = s;
+    }
+
+    // Precompute branch outputs: state holds the 4 previous input bits
+    let outputs: Vec<[[u8; 4]; 2]> = (0..STATES)
+        .map(|state| {
+            let mut per_input = [[0u8; 4]; 2];
+            for (input, out) in per_input.iter_mut().enumerate() {
+                let window = [
+                    input as u8,
+                    (state & 1) as u8,
+                    ((state >> 1) & 1) as u8,
+                    ((state >> 2) & 1) as u8,
+                    ((state >> 3) & 1) as u8,
+                ];
+                for (g, taps) in MOTHER_CODE.iter().enumerate() {
+                    out[g] = taps.iter().zip(&window).fold(0, |acc, (&t, &w)| acc ^ (t & w));
+                }
+            }
+            per_input
+        })
+        .collect();
+
+    let mut metrics = [f64::NEG_INFINITY; STATES];
+    metrics[0] = 0.0;
+    let mut history: Vec<[(u8, u8); STATES]> = Vec::with_capacity(type2_len);
+
+    for step in 0..type2_len {
+        let received = &mother[step * 4..step * 4 + 4];
+        let mut next = [f64::NEG_INFINITY; STATES];
+        let mut from = [(0u8, 0u8); STATES];
+
+        for state in 0..STATES {
+            if metrics[state] == f64::NEG_INFINITY {
+                continue;
+            }
+            for (input, coded) in outputs[state].iter().enumerate() {
+                let branch: f64 = coded
+                    .iter()
+                    .zip(received)
+                    .map(|(&c, &r)| if c == 1 { r } else { -r })
+                    .sum();
+                let next_state = ((state << 1) | input) & (STATES - 1);
+                let candidate = metrics[state] + branch;
+                if candidate > next[next_state] {
+                    next[next_state] = candidate;
+                    from[next_state] = (state as u8, input as u8);
+                }
+            }
+        }
+
+        metrics = next;
+        history.push(from);
+    }
+
+    // Trace back from state 0 (encoder flushed by tail bits)
+    let mut bits = vec![0u8; type2_len];
+    let mut state = 0usize;
+    for step in (0..type2_len).rev() {
+        let (prev, input) = history[step][state];
+        bits[step] = input;
+        state = prev as usize;
+    }
+    bits
+}
+
+/// CRC-16 block code over type-1 bits (complemented remainder, all-ones preset)
+pub fn crc16(bits: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &bit in bits {
+        let feedback = ((crc >> 15) as u8 ^ (bit & 1)) & 1;
+        crc <<= 1;
+        if feedback == 1 {
+            crc ^= 0x1021;
+        }
+    }
+    !crc
+}
+
+/// Block interleave `K` type-3 bits with parameter `a`
+pub fn interleave(type3: &[u8], a: usize) -> Vec<u8> {
+    let k = type3.len();
+    let mut out = vec![0u8; k];
+    for (i, &bit) in type3.iter().enumerate() {
+        out[(a * (i + 1)) % k] = bit;
+    }
+    out
+}
+
+/// Inverse of [`interleave`] for soft bits
+pub fn deinterleave(type4: &[f64], a: usize) -> Vec<f64> {
+    let k = type4.len();
+    (0..k).map(|i| type4[(a * (i + 1)) % k]).collect()
+}
+
+/// Extended colour code seeding the scrambler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExtendedColourCode {
+    /// Mobile country code (10 bits)
+    pub mcc: u16,
+    /// Mobile network code (14 bits)
+    pub mnc: u16,
+    /// Base station colour code (6 bits)
+    pub colour_code: u8,
+}
+
+impl ExtendedColourCode {
+    /// Create from MCC, MNC and colour code
+    pub fn new(mcc: u16, mnc: u16, colour_code: u8) -> Self {
+        Self {
+            mcc: mcc & 0x3FF,
+            mnc: mnc & 0x3FFF,
+            colour_code: colour_code & 0x3F,
+        }
+    }
+
+    /// 30-bit value e(1..30)
+    pub fn value(&self) -> u32 {
+        ((self.mcc as u32) << 20) | ((self.mnc as u32) << 6) | self.colour_code as u32
+    }
+
+    /// Generate `len` scrambling sequence bits
+    pub fn scrambling_sequence(&self, len: usize) -> Vec<u8> {
+        // Taps of c(x) = 1+x+x²+x⁴+x⁵+x⁷+x⁸+x¹⁰+x¹¹+x¹²+x¹⁶+x²²+x²³+x²⁶+x³²
+        const TAPS: [u32; 14] = [32, 26, 23, 22, 16, 12, 11, 10, 8, 7, 5, 4, 2, 1];
+
+        // Register holds p(k-32)..p(k-1); seeded with e(1..30), p(-1)=p(0)=1
+        let mut reg: u32 = (self.value() << 2) | 0b11;
+        (0..len)
+            .map(|_| {
+                let bit = TAPS.iter().fold(0u32, |acc, &t| acc ^ (reg >> (32 - t))) & 1;
+                reg = (reg >> 1) | (bit << 31);
+                bit as u8
+            })
+            .collect()
+    }
+
+    /// Scramble (or descramble) hard bits in place
+    pub fn scramble(&self, bits: &mut [u8]) {
+        let sequence = self.scrambling_sequence(bits.len());
+        for (bit, s) in bits.iter_mut().zip(sequence) {
+            *bit ^= s;
+        }
+    }
+
+    /// Descramble soft bits in place
+    pub fn descramble_soft(&self, soft: &mut [f64]) {
+        let sequence = self.scrambling_sequence(soft.len());
+        for (value, s) in soft.iter_mut().zip(sequence) {
+            if s == 1 {
+                *value = -*value;
+            }
+        }
+    }
+}
+
+/// Logical channels with their coding parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogicalChannel {
+    /// Broadcast synchronization channel (SYNC PDU)
+    Bsch,
+    /// Broadcast network channel (SYSINFO PDU), half slot
+    Bnch,
+    /// Signalling channel, half slot downlink
+    SchHd,
+    /// Signalling channel, full slot
+    SchF,
+    /// Signalling channel, half slot uplink (control uplink burst)
+    SchHu,
+}
+
+impl LogicalChannel {
+    /// Number of type-1 (information) bits
+    pub fn type1_bits(self) -> usize {
+        match self {
+            Self::Bsch => 60,
+            Self::Bnch | Self::SchHd => 124,
+            Self::SchF => 268,
+            Self::SchHu => 92,
+        }
+    }
+
+    /// Number of type-2 bits (type-1 + CRC + tail)
+    pub fn type2_bits(self) -> usize {
+        self.type1_bits() + CRC_BITS + TAIL_BITS
+    }
+
+    /// Number of type-5 (on-air) bits
+    pub fn coded_bits(self) -> usize {
+        RcpcRate::TwoThirds.coded_len(self.type2_bits())
+    }
+
+    /// Interleaving parameter `a`
+    pub fn interleave_a(self) -> usize {
+        match self {
+            Self::Bsch => 11,
+            Self::Bnch | Self::SchHd => 101,
+            Self::SchF => 103,
+            Self::SchHu => 13,
+        }
+    }
+
+    /// Run the full TX coding chain: type-1 bits to scrambled type-5 bits.
+    ///
+    /// BSCH is always scrambled with the all-zero extended colour code.
+    pub fn encode(self, type1: &[u8], ecc: ExtendedColourCode) -> Vec<u8> {
+        assert_eq!(type1.len(), self.type1_bits(), "{:?} type-1 length", self);
+
+        let mut type2 = type1.to_vec();
+        let crc = crc16(type1);
+        type2.extend((0..CRC_BITS).rev().map(|i| ((crc >> i) & 1) as u8));
+        type2.extend_from_slice(&[0; TAIL_BITS]);
+
+        let type3 = rcpc_encode(&type2, RcpcRate::TwoThirds);
+        let mut type5 = interleave(&type3, self.interleave_a());
+        self.scrambler(ecc).scramble(&mut type5);
+        type5
+    }
+
+    /// Run the RX chain on soft type-5 bits, returning the type-1 bits
+    /// and whether the CRC matched.
+    pub fn decode(self, soft: &[f64], ecc: ExtendedColourCode) -> (Vec<u8>, bool) {
+        let mut type5 = soft[..self.coded_bits()].to_vec();
+        self.scrambler(ecc).descramble_soft(&mut type5);
+        let type3 = deinterleave(&type5, self.interleave_a());
+        let type2 = rcpc_decode(&type3, self.type2_bits(), RcpcRate::TwoThirds);
+
+        let type1 = type2[..self.type1_bits()].to_vec();
+        let received_crc = type2[self.type1_bits()..self.type1_bits() + CRC_BITS]
+            .iter()
+            .fold(0u16, |acc, &b| (acc << 1) | b as u16);
+        let crc_ok = crc16(&type1) == received_crc;
+        (type1, crc_ok)
+    }
+
+    fn scrambler(self, ecc: ExtendedColourCode) -> ExtendedColourCode {
+        match self {
+            Self::Bsch => ExtendedColourCode::default(),
+            _ => ecc,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern_bits(len: usize, seed: usize) -> Vec<u8> {
+        (0..len).map(|i| (((i + seed) * 37 + i / 3) % 7 < 3) as u8).collect()
+    }
+
+    fn to_soft(bits: &[u8]) -> Vec<f64> {
+        bits.iter().map(|&b| if b == 1 { 1.0 } else { -1.0 }).collect()
+    }
+
+    #[test]
+    fn test_coded_lengths() {
+        assert_eq!(LogicalChannel::Bsch.coded_bits(), 120);
+        assert_eq!(LogicalChannel::Bnch.coded_bits(), 216);
+        assert_eq!(LogicalChannel::SchF.coded_bits(), 432);
+        assert_eq!(LogicalChannel::SchHu.coded_bits(), 168);
+        assert_eq!(RcpcRate::OneThird.coded_len(148), 444);
+    }
+
+    #[test]
+    fn test_rcpc_roundtrip_both_rates() {
+        for rate in [RcpcRate::TwoThirds, RcpcRate::OneThird] {
+            let mut type2 = pattern_bits(100, 1);
+            type2.extend_from_slice(&[0; TAIL_BITS]);
+            let coded = rcpc_encode(&type2, rate);
+            assert_eq!(coded.len(), rate.coded_len(type2.len()));
+
+            let mut soft = to_soft(&coded);
+            // Flip a few well-separated bits
+            for idx in [3, 40, 90, 130] {
+                soft[idx] = -soft[idx];
+            }
+            assert_eq!(rcpc_decode(&soft, type2.len(), rate), type2);
+        }
+    }
+
+    #[test]
+    fn test_interleave_inverse() {
+        let bits = pattern_bits(216, 5);
+        let interleaved = interleave(&bits, 101);
+        assert_ne!(interleaved, bits);
+        let restored: Vec<u8> = deinterleave(&to_soft(&interleaved), 101)
+            .iter()
+            .map(|&s| (s > 0.0) as u8)
+            .collect();
+        assert_eq!(restored, bits);
+    }
+
+    #[test]
+    fn test_scrambling_depends_on_colour_code() {
+        let a = ExtendedColourCode::new(262, 1010, 1).scrambling_sequence(64);
+        let b = ExtendedColourCode::new(262, 1010, 2).scrambling_sequence(64);
+        assert_ne!(a, b);
+
+        let mut bits = pattern_bits(64, 2);
+        let original = bits.clone();
+        let ecc = ExtendedColourCode::new(262, 1010, 1);
+        ecc.scramble(&mut bits);
+        assert_ne!(bits, original);
+        ecc.scramble(&mut bits);
+        assert_eq!(bits, original);
+    }
+
+    #[test]
+    fn test_channel_roundtrip() {
+        let ecc = ExtendedColourCode::new(234, 30, 17);
+        for channel in [
+            LogicalChannel::Bsch,
+            LogicalChannel::Bnch,
+            LogicalChannel::SchF,
+            LogicalChannel::SchHu,
+        ] {
+            let type1 = pattern_bits(channel.type1_bits(), 3);
+            let coded = channel.encode(&type1, ecc);
+            assert_eq!(coded.len(), channel.coded_bits());
+
+            let (decoded, crc_ok) = channel.decode(&to_soft(&coded), ecc);
+            assert!(crc_ok, "{:?}", channel);
+            assert_eq!(decoded, type1);
+        }
+    }
+
+    #[test]
+    fn test_wrong_colour_code_fails_crc() {
+        let type1 = pattern_bits(124, 4);
+        let coded = LogicalChannel::Bnch.encode(&type1, ExtendedColourCode::new(234, 30, 17));
+        let (_, crc_ok) =
+            LogicalChannel::Bnch.decode(&to_soft(&coded), ExtendedColourCode::new(234, 30, 18));
+        assert!(!crc_ok);
+    }
+}
@@ -0,0 +1,974 @@
+//! TETRA (Terrestrial Trunked Radio) Waveform
+//!
+//! This module implements the TETRA digital radio standard used by
+//! European emergency services and public safety organizations.
+//!
+//! # Overview
+//!
+//! TETRA is an ETSI standard (EN 300 392) for professional mobile radio,
+//! widely deployed by emergency services, military, and transport.
+//!
+//! # Key Features
+//!
+//! - 4-slot TDMA in 25 kHz channels
+//! - π/4-DQPSK modulation at 18 ksymbols/sec
+//! - 36 kbps gross bit rate (9 kbps per slot)
+//! - TEA1/TEA2/TEA3 encryption algorithms
+//! - Direct Mode (DMO) and Trunked Mode (TMO)
+//!
+//! # Physical Layer
+//!
+//! [`Tetra::modulate_downlink`] builds a TMO continuous downlink from
+//! synchronization and normal bursts (see [`burst`]) carrying coded
+//! logical channels (see [`coding`]). [`Tetra::decode_downlink`] runs the
+//! receive chain:
+//!
+//! ```text
+//! I/Q → RRC matched filter → timing (training correlation) → π/4-DQPSK
+//!     → burst split → descramble → deinterleave → Viterbi → CRC → PDUs
+//! ```
+//!
+//! The BSCH (scrambled with the all-zero code) yields MCC, MNC and colour
+//! code; these seed the scrambler for every other channel of the cell.
+//!
+//! ```rust
+//! use r4w_core::waveform::tetra::{DownlinkSlot, SyncPdu, SysInfoPdu, Tetra};
+//!
+//! let tetra = Tetra::tmo(72000.0);
+//! let samples = tetra.modulate_downlink(&[DownlinkSlot::Sync {
+//!     sync: SyncPdu::new(262, 1010, 5),
+//!     sysinfo: SysInfoPdu::new(3600, 100),
+//! }]);
+//!
+//! let downlink = tetra.decode_downlink(&samples);
+//! let sync = downlink.sync.unwrap();
+//! assert_eq!((sync.mcc, sync.mnc, sync.colour_code), (262, 1010, 5));
+//! ```
+
+pub mod burst;
+pub mod coding;
+pub mod pdu;
+
+use std::f64::consts::PI;
+
+use crate::types::IQSample;
+use crate::waveform::{CommonParams, DemodResult, VisualizationData, Waveform, WaveformInfo};
+
+pub use burst::{DownlinkBurst, NormalTraining};
+pub use coding::{ExtendedColourCode, LogicalChannel, RcpcRate};
+pub use pdu::{SyncPdu, SysInfoPdu};
+
+/// TETRA operating mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TetraMode {
+    /// Trunked Mode Operation (requires infrastructure)
+    #[default]
+    Tmo,
+    /// Direct Mode Operation (terminal to terminal)
+    Dmo,
+}
+
+/// TETRA encryption algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TetraEncryption {
+    /// No encryption
+    #[default]
+    None,
+    /// TEA1 (export controlled)
+    Tea1,
+    /// TEA2 (EU public safety)
+    Tea2,
+    /// TEA3 (open markets)
+    Tea3,
+}
+
+/// TETRA burst types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BurstType {
+    /// Normal uplink burst
+    NormalUp,
+    /// Normal downlink burst
+    NormalDown,
+    /// Synchronization burst
+    Sync,
+    /// Control uplink burst
+    ControlUp,
+}
+
+impl BurstType {
+    /// Get training sequence for this burst type
+    ///
+    /// Normal bursts return sequence `n`; they may also carry `p` to signal
+    /// half-slot use (see [`NormalTraining`]).
+    pub fn training_sequence(&self) -> &'static [u8] {
+        match self {
+            Self::NormalUp | Self::NormalDown => &burst::NORMAL_TRAINING_N,
+            Self::Sync => &burst::SYNC_TRAINING_Y,
+            Self::ControlUp => &burst::EXTENDED_TRAINING_X,
+        }
+    }
+}
+
+/// One downlink timeslot to transmit
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownlinkSlot {
+    /// Synchronization burst carrying SYNC (BSCH) and SYSINFO (BNCH)
+    Sync {
+        /// SYNC PDU; its MCC/MNC/colour code scramble the following slots
+        sync: SyncPdu,
+        /// SYSINFO PDU
+        sysinfo: SysInfoPdu,
+    },
+    /// Normal burst carrying one SCH/F block (268 type-1 bits)
+    FullSlot(Vec<u8>),
+    /// Normal burst carrying two SCH/HD blocks (124 type-1 bits each)
+    HalfSlots(Vec<u8>, Vec<u8>),
+}
+
+/// A logical channel block recovered from the downlink
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedBlock {
+    /// Timeslot index relative to the first burst in the capture
+    pub slot: usize,
+    /// Logical channel the block was decoded as
+    pub channel: LogicalChannel,
+    /// Type-1 bits
+    pub bits: Vec<u8>,
+    /// CRC check result
+    pub crc_ok: bool,
+}
+
+/// Result of decoding a TMO downlink capture
+#[derive(Debug, Clone, Default)]
+pub struct TmoDownlink {
+    /// First SYNC PDU with a valid CRC
+    pub sync: Option<SyncPdu>,
+    /// First SYSINFO PDU with a valid CRC
+    pub sysinfo: Option<SysInfoPdu>,
+    /// Every block decoded, in slot order
+    pub blocks: Vec<DecodedBlock>,
+    /// Normalized correlation of the synchronization training sequence
+    pub sync_quality: f64,
+}
+
+/// TETRA frame timing constants
+pub struct TetraTiming;
+
+impl TetraTiming {
+    /// Slots per frame
+    pub const SLOTS_PER_FRAME: usize = 4;
+    /// Frames per multiframe
+    pub const FRAMES_PER_MULTIFRAME: usize = 18;
+    /// Multiframes per hyperframe
+    pub const MULTIFRAMES_PER_HYPERFRAME: usize = 60;
+    /// Frame duration in milliseconds
+    pub const FRAME_DURATION_MS: f64 = 56.67;
+    /// Slot duration in milliseconds
+    pub const SLOT_DURATION_MS: f64 = 14.167;
+    /// Symbols per slot
+    pub const SYMBOLS_PER_SLOT: usize = 255;
+    /// Symbol rate in symbols/sec
+    pub const SYMBOL_RATE: f64 = 18000.0;
+}
+
+/// π/4-DQPSK phase change indexed by dibit: 00→π/4, 01→3π/4, 10→-π/4, 11→-3π/4
+const PI4DQPSK_PHASE_CHANGES: [f64; 4] = [PI / 4.0, 3.0 * PI / 4.0, -PI / 4.0, -3.0 * PI / 4.0];
+
+/// Minimum normalized training correlation accepted as a burst
+const TRAINING_THRESHOLD: f64 = 0.7;
+
+/// TETRA sync word patterns
+#[allow(dead_code)]
+const NORMAL_TRAINING_SEQ_1: u32 = 0x1ACFFC1D;
+#[allow(dead_code)]
+const NORMAL_TRAINING_SEQ_2: u32 = 0x1653302E;
+
+/// TETRA waveform implementation
+#[derive(Debug)]
+pub struct Tetra {
+    /// Common waveform parameters
+    common: CommonParams,
+    /// Operating mode (TMO/DMO)
+    mode: TetraMode,
+    /// Encryption algorithm
+    encryption: TetraEncryption,
+    /// Samples per symbol
+    samples_per_sym: usize,
+    /// Current phase for differential modulation
+    phase: f64,
+    /// RRC filter coefficients
+    rrc_filter: Vec<f64>,
+    /// Filter span in symbols
+    #[allow(dead_code)]
+    filter_span: usize,
+}
+
+impl Tetra {
+    /// Standard symbol rate (18 ksymbols/sec)
+    pub const SYMBOL_RATE: f64 = 18000.0;
+    /// Channel bandwidth (25 kHz)
+    pub const BANDWIDTH: f64 = 25000.0;
+    /// Bits per symbol (π/4-DQPSK = 2 bits)
+    pub const BITS_PER_SYMBOL: u8 = 2;
+    /// RRC rolloff factor
+    pub const RRC_ROLLOFF: f64 = 0.35;
+
+    /// Create a new TETRA instance with default parameters
+    pub fn new(sample_rate: f64, mode: TetraMode, encryption: TetraEncryption) -> Self {
+        let samples_per_sym = (sample_rate / Self::SYMBOL_RATE) as usize;
+        let filter_span = 8;
+
+        // Generate RRC filter
+        let rrc_filter = Self::generate_rrc_filter(samples_per_sym, filter_span, Self::RRC_ROLLOFF);
+
+        Self {
+            common: CommonParams {
+                sample_rate,
+                carrier_freq: 0.0,
+                amplitude: 1.0,
+            },
+            mode,
+            encryption,
+            samples_per_sym: samples_per_sym.max(1),
+            phase: 0.0,
+            rrc_filter,
+            filter_span,
+        }
+    }
+
+    /// Create standard TMO TETRA instance
+    pub fn tmo(sample_rate: f64) -> Self {
+        Self::new(sample_rate, TetraMode::Tmo, TetraEncryption::None)
+    }
+
+    /// Create DMO (direct mode) TETRA instance
+    pub fn dmo(sample_rate: f64) -> Self {
+        Self::new(sample_rate, TetraMode::Dmo, TetraEncryption::None)
+    }
+
+    /// Create encrypted TETRA instance
+    pub fn with_encryption(sample_rate: f64, encryption: TetraEncryption) -> Self {
+        Self::new(sample_rate, TetraMode::Tmo, encryption)
+    }
+
+    /// Generate Root Raised Cosine filter coefficients
+    fn generate_rrc_filter(sps: usize, span: usize, rolloff: f64) -> Vec<f64> {
+        let num_taps = span * sps + 1;
+        let mut filter = vec![0.0; num_taps];
+        let half = (num_taps / 2) as f64;
+
+        for i in 0..num_taps {
+            let t = (i as f64 - half) / sps as f64;
+
+            if t.abs() < 1e-10 {
+                // t = 0 case
+                filter[i] = 1.0 - rolloff + 4.0 * rolloff / PI;
+            } else if (t.abs() - 1.0 / (4.0 * rolloff)).abs() < 1e-10 {
+                // t = ±1/(4*α) case
+                let a = (1.0 + 2.0 / PI) * (PI / (4.0 * rolloff)).sin();
+                let b = (1.0 - 2.0 / PI) * (PI / (4.0 * rolloff)).cos();
+                filter[i] = rolloff / 2.0_f64.sqrt() * (a + b);
+            } else {
+                // General case
+                let num = (PI * t * (1.0 - rolloff)).sin()
+                    + 4.0 * rolloff * t * (PI * t * (1.0 + rolloff)).cos();
+                let den = PI * t * (1.0 - (4.0 * rolloff * t).powi(2));
+                filter[i] = num / den;
+            }
+        }
+
+        // Normalize
+        let sum: f64 = filter.iter().sum();
+        for coef in &mut filter {
+            *coef /= sum;
+        }
+
+        filter
+    }
+
+    /// Generate sync burst
+    fn generate_sync_burst(&mut self) -> Vec<IQSample> {
+        // Simplified sync burst with training sequence
+        let training = BurstType::Sync.training_sequence();
+        self.pi4dqpsk_modulate(training)
+    }
+
+    /// π/4-DQPSK modulation
+    fn pi4dqpsk_modulate(&mut self, dibits: &[u8]) -> Vec<IQSample> {
+        let mut samples = Vec::with_capacity(dibits.len() * self.samples_per_sym);
+
+        for &dibit in dibits {
+            self.phase += PI4DQPSK_PHASE_CHANGES[(dibit & 0x03) as usize];
+
+            // Keep phase in [-π, π]
+            while self.phase > PI {
+                self.phase -= 2.0 * PI;
+            }
+            while self.phase < -PI {
+                self.phase += 2.0 * PI;
+            }
+
+            // Generate samples for this symbol
+            for _ in 0..self.samples_per_sym {
+                samples.push(IQSample::new(self.phase.cos(), self.phase.sin()));
+            }
+        }
+
+        // Apply RRC pulse shaping (convolution)
+        self.apply_rrc_filter(&samples)
+    }
+
+    /// Apply RRC filter
+    fn apply_rrc_filter(&self, samples: &[IQSample]) -> Vec<IQSample> {
+        if self.rrc_filter.is_empty() {
+            return samples.to_vec();
+        }
+
+        let filter_len = self.rrc_filter.len();
+        let half_len = filter_len / 2;
+        let mut filtered = Vec::with_capacity(samples.len());
+
+        for i in 0..samples.len() {
+            let mut sum_i = 0.0;
+            let mut sum_q = 0.0;
+
+            for (j, &coef) in self.rrc_filter.iter().enumerate() {
+                let idx = i as i64 - half_len as i64 + j as i64;
+                if idx >= 0 && (idx as usize) < samples.len() {
+                    sum_i += samples[idx as usize].re * coef;
+                    sum_q += samples[idx as usize].im * coef;
+                }
+            }
+
+            filtered.push(IQSample::new(sum_i, sum_q));
+        }
+
+        filtered
+    }
+
+    /// Demodulate π/4-DQPSK
+    fn pi4dqpsk_demodulate(&self, samples: &[IQSample]) -> Vec<u8> {
+        let mut dibits = Vec::new();
+        let mut prev_phase = 0.0f64;
+
+        for chunk in samples.chunks(self.samples_per_sym) {
+            if chunk.is_empty() {
+                break;
+            }
+
+            // Average phase over symbol
+            let avg_i: f64 = chunk.iter().map(|s| s.re).sum::<f64>() / chunk.len() as f64;
+            let avg_q: f64 = chunk.iter().map(|s| s.im).sum::<f64>() / chunk.len() as f64;
+            let current_phase = avg_q.atan2(avg_i);
+
+            // Calculate phase difference
+            let mut delta = current_phase - prev_phase;
+            while delta > PI {
+                delta -= 2.0 * PI;
+            }
+            while delta < -PI {
+                delta += 2.0 * PI;
+            }
+
+            // Map phase difference to dibit
+            let dibit = if delta > PI / 2.0 {
+                0b01 // 3π/4
+            } else if delta > 0.0 {
+                0b00 // π/4
+            } else if delta > -PI / 2.0 {
+                0b10 // -π/4
+            } else {
+                0b11 // -3π/4
+            };
+
+            dibits.push(dibit);
+            prev_phase = current_phase;
+        }
+
+        dibits
+    }
+
+    /// Map dibits to unit-amplitude π/4-DQPSK symbols, preceded by a
+    /// phase reference symbol
+    fn pi4dqpsk_symbols(dibits: &[u8]) -> Vec<IQSample> {
+        let mut phase = 0.0f64;
+        let mut symbols = Vec::with_capacity(dibits.len() + 1);
+        symbols.push(IQSample::new(1.0, 0.0));
+        for &dibit in dibits {
+            phase += PI4DQPSK_PHASE_CHANGES[(dibit & 0x03) as usize];
+            symbols.push(IQSample::new(phase.cos(), phase.sin()));
+        }
+        symbols
+    }
+
+    /// Full convolution with the RRC filter
+    fn rrc_convolve(&self, samples: &[IQSample]) -> Vec<IQSample> {
+        let taps = &self.rrc_filter;
+        if samples.is_empty() || taps.is_empty() {
+            return samples.to_vec();
+        }
+        let mut out = vec![IQSample::new(0.0, 0.0); samples.len() + taps.len() - 1];
+        for (i, &x) in samples.iter().enumerate() {
+            for (j, &h) in taps.iter().enumerate() {
+                out[i + j] += x * h;
+            }
+        }
+        out
+    }
+
+    /// RRC pulse shaping of symbols (zero-stuffed upsampling + filter),
+    /// scaled to unit average power
+    fn shape_symbols(&self, symbols: &[IQSample]) -> Vec<IQSample> {
+        let mut upsampled = vec![IQSample::new(0.0, 0.0); symbols.len() * self.samples_per_sym];
+        for (i, &sym) in symbols.iter().enumerate() {
+            upsampled[i * self.samples_per_sym] = sym * self.samples_per_sym as f64;
+        }
+        self.rrc_convolve(&upsampled)
+    }
+
+    /// Modulate bits (MSB first within each dibit) as an RRC-shaped burst stream
+    fn modulate_bits(&self, bits: &[u8]) -> Vec<IQSample> {
+        let dibits: Vec<u8> = bits.chunks(2).map(|p| (p[0] << 1) | p.get(1).copied().unwrap_or(0)).collect();
+        self.shape_symbols(&Self::pi4dqpsk_symbols(&dibits))
+    }
+
+    /// Matched filter, symbol timing and differential detection.
+    ///
+    /// For each sample phase the symbol-rate stream is differentially
+    /// detected into soft bits (positive = 1) and correlated against
+    /// `training`; the phase with the strongest correlation wins. Returns
+    /// the soft bits and the correlation peaks above threshold as
+    /// `(bit index of training start, normalized correlation)`.
+    fn recover_soft_bits(&self, samples: &[IQSample], training: &[u8]) -> (Vec<f64>, Vec<(usize, f64)>) {
+        let filtered = self.rrc_convolve(samples);
+        let mut best: (Vec<f64>, Vec<(usize, f64)>, f64) = (Vec::new(), Vec::new(), 0.0);
+
+        for offset in 0..self.samples_per_sym.min(filtered.len()) {
+            let symbols: Vec<IQSample> = filtered[offset..]
+                .iter()
+                .step_by(self.samples_per_sym)
+                .copied()
+                .collect();
+            let soft = Self::differential_soft_bits(&symbols);
+            let peaks = Self::correlate_training(&soft, training);
+            let strongest = peaks.iter().map(|&(_, c)| c).fold(0.0, f64::max);
+            if strongest > best.2 || best.0.is_empty() {
+                best = (soft, peaks, strongest);
+            }
+        }
+
+        (best.0, best.1)
+    }
+
+    /// Differential detection: dibit (b1, b2) has b1 = 1 for a negative
+    /// phase change and b2 = 1 for |Δφ| > π/2
+    fn differential_soft_bits(symbols: &[IQSample]) -> Vec<f64> {
+        let diffs: Vec<IQSample> = symbols.windows(2).map(|w| w[1] * w[0].conj()).collect();
+        let scale = diffs.iter().map(|z| z.norm()).sum::<f64>() / diffs.len().max(1) as f64;
+        if scale <= 0.0 {
+            return vec![0.0; diffs.len() * 2];
+        }
+        diffs
+            .iter()
+            .flat_map(|z| {
+                let z = z / scale;
+                // π/4 rotation puts the four phase changes on the axes' diagonals
+                [-z.im, -z.re]
+            })
+            .collect()
+    }
+
+    /// Normalized correlation of soft bits against a training sequence at
+    /// every dibit-aligned position; returns local maxima above threshold
+    fn correlate_training(soft: &[f64], training: &[u8]) -> Vec<(usize, f64)> {
+        if soft.len() < training.len() {
+            return Vec::new();
+        }
+        let corr: Vec<f64> = (0..=soft.len() - training.len())
+            .step_by(2)
+            .map(|pos| {
+                let window = &soft[pos..pos + training.len()];
+                let energy: f64 = window.iter().map(|s| s.abs()).sum();
+                if energy <= 0.0 {
+                    return 0.0;
+                }
+                let dot: f64 = window
+                    .iter()
+                    .zip(training)
+                    .map(|(&s, &t)| if t == 1 { s } else { -s })
+                    .sum();
+                dot / energy
+            })
+            .collect();
+
+        (0..corr.len())
+            .filter(|&i| {
+                corr[i] >= TRAINING_THRESHOLD
+                    && (i == 0 || corr[i] >= corr[i - 1])
+                    && (i + 1 == corr.len() || corr[i] > corr[i + 1])
+            })
+            .map(|i| (i * 2, corr[i]))
+            .collect()
+    }
+
+    /// Normalized correlation of a training sequence at one bit position
+    fn training_match(soft: &[f64], pos: usize, training: &[u8]) -> f64 {
+        if pos + training.len() > soft.len() {
+            return 0.0;
+        }
+        Self::correlate_training(&soft[pos..pos + training.len()], training)
+            .first()
+            .map(|&(_, c)| c)
+            .unwrap_or(0.0)
+    }
+
+    /// Modulate a TMO continuous downlink.
+    ///
+    /// Slots following a [`DownlinkSlot::Sync`] are scrambled with that
+    /// cell's extended colour code. The AACH broadcast blocks are sent as
+    /// zeros (no access assignment).
+    pub fn modulate_downlink(&self, slots: &[DownlinkSlot]) -> Vec<IQSample> {
+        let mut ecc = ExtendedColourCode::default();
+        let aach = vec![0u8; 30];
+        let mut bits = Vec::with_capacity(slots.len() * burst::DOWNLINK_BURST_BITS);
+
+        for slot in slots {
+            let burst = match slot {
+                DownlinkSlot::Sync { sync, sysinfo } => {
+                    ecc = sync.extended_colour_code();
+                    DownlinkBurst::Sync {
+                        bsch: LogicalChannel::Bsch.encode(&sync.to_bits(), ecc),
+                        aach: aach.clone(),
+                        block2: LogicalChannel::Bnch.encode(&sysinfo.to_bits(), ecc),
+                    }
+                }
+                DownlinkSlot::FullSlot(sch_f) => {
+                    let coded = LogicalChannel::SchF.encode(sch_f, ecc);
+                    DownlinkBurst::Normal {
+                        training: NormalTraining::FullSlot,
+                        block1: coded[..216].to_vec(),
+                        aach: aach.clone(),
+                        block2: coded[216..].to_vec(),
+                    }
+                }
+                DownlinkSlot::HalfSlots(first, second) => DownlinkBurst::Normal {
+                    training: NormalTraining::HalfSlots,
+                    block1: LogicalChannel::SchHd.encode(first, ecc),
+                    aach: aach.clone(),
+                    block2: LogicalChannel::SchHd.encode(second, ecc),
+                },
+            };
+            bits.extend(burst.to_bits());
+        }
+
+        self.modulate_bits(&bits)
+    }
+
+    /// Locate and decode every burst of a TMO continuous downlink.
+    ///
+    /// Synchronization bursts are found by correlating the `y` training
+    /// sequence; they fix symbol timing, the 510-bit slot grid and (via the
+    /// BSCH) the scrambling code. Normal bursts on the grid are classified
+    /// by their `n`/`p` training sequence as SCH/F or SCH/HD pairs.
+    pub fn decode_downlink(&self, samples: &[IQSample]) -> TmoDownlink {
+        const SLOT_BITS: usize = burst::DOWNLINK_BURST_BITS;
+
+        let (soft, peaks) = self.recover_soft_bits(samples, &burst::SYNC_TRAINING_Y);
+        let mut result = TmoDownlink::default();
+        let Some(&(first_peak, _)) = peaks.first() else {
+            return result;
+        };
+        result.sync_quality = peaks.iter().map(|&(_, c)| c).fold(0.0, f64::max);
+
+        let slot_start = |training_pos: usize| training_pos.checked_sub(burst::SB_TRAINING_OFFSET);
+        let sb_starts: Vec<usize> = peaks.iter().filter_map(|&(p, _)| slot_start(p)).collect();
+
+        // BSCH carries the cell identity needed to descramble everything else
+        let mut ecc = None;
+        for &start in &sb_starts {
+            let bsch = &soft[start + burst::SB_BSCH_OFFSET..];
+            let (bits, crc_ok) = LogicalChannel::Bsch.decode(bsch, ExtendedColourCode::default());
+            if crc_ok {
+                let sync = SyncPdu::from_bits(&bits);
+                ecc = Some(sync.extended_colour_code());
+                result.sync = Some(sync);
+                break;
+            }
+        }
+        let Some(ecc) = ecc else {
+            return result;
+        };
+
+        let anchor = (first_peak + SLOT_BITS - burst::SB_TRAINING_OFFSET % SLOT_BITS) % SLOT_BITS;
+        let mut start = anchor;
+        let mut slot = 0;
+        while start + SLOT_BITS <= soft.len() {
+            let burst_soft = &soft[start..start + SLOT_BITS];
+            let block1 = &burst_soft[burst::NDB_BLOCK1_OFFSET..burst::NDB_BLOCK1_OFFSET + 216];
+            let block2 = &burst_soft[burst::BLOCK2_OFFSET..burst::BLOCK2_OFFSET + 216];
+            let mut push = |channel: LogicalChannel, (bits, crc_ok): (Vec<u8>, bool)| {
+                if channel == LogicalChannel::Bsch && crc_ok {
+                    result.sync.get_or_insert(SyncPdu::from_bits(&bits));
+                }
+                if matches!(channel, LogicalChannel::Bnch | LogicalChannel::SchHd) && crc_ok {
+                    if let Some(sysinfo) = SysInfoPdu::from_bits(&bits) {
+                        result.sysinfo.get_or_insert(sysinfo);
+                    }
+                }
+                result.blocks.push(DecodedBlock {
+                    slot,
+                    channel,
+                    bits,
+                    crc_ok,
+                });
+            };
+
+            if sb_starts.contains(&start) {
+                let bsch = &burst_soft[burst::SB_BSCH_OFFSET..];
+                push(
+                    LogicalChannel::Bsch,
+                    LogicalChannel::Bsch.decode(bsch, ExtendedColourCode::default()),
+                );
+                push(LogicalChannel::Bnch, LogicalChannel::Bnch.decode(block2, ecc));
+            } else {
+                let full = Self::training_match(burst_soft, burst::NDB_TRAINING_OFFSET, NormalTraining::FullSlot.bits());
+                let half = Self::training_match(burst_soft, burst::NDB_TRAINING_OFFSET, NormalTraining::HalfSlots.bits());
+                if half > full && half >= TRAINING_THRESHOLD {
+                    push(LogicalChannel::SchHd, LogicalChannel::SchHd.decode(block1, ecc));
+                    push(LogicalChannel::SchHd, LogicalChannel::SchHd.decode(block2, ecc));
+                } else if full >= TRAINING_THRESHOLD {
+                    let mut coded = block1.to_vec();
+                    coded.extend_from_slice(block2);
+                    push(LogicalChannel::SchF, LogicalChannel::SchF.decode(&coded, ecc));
+                }
+            }
+
+            start += SLOT_BITS;
+            slot += 1;
+        }
+
+        result
+    }
+
+    /// Modulate a control uplink burst carrying one SCH/HU block
+    /// (92 type-1 bits), with one symbol of silence either side
+    pub fn modulate_control_uplink(&self, sch_hu: &[u8], ecc: ExtendedColourCode) -> Vec<IQSample> {
+        let coded = LogicalChannel::SchHu.encode(sch_hu, ecc);
+        let guard = vec![IQSample::new(0.0, 0.0); self.samples_per_sym];
+        let mut samples = guard.clone();
+        samples.extend(self.modulate_bits(&burst::control_uplink_bits(&coded)));
+        samples.extend(guard);
+        samples
+    }
+
+    /// Detect a control uplink burst by its extended training sequence and
+    /// decode its SCH/HU block, returning the type-1 bits and CRC result
+    pub fn decode_control_uplink(
+        &self,
+        samples: &[IQSample],
+        ecc: ExtendedColourCode,
+    ) -> Option<(Vec<u8>, bool)> {
+        let (soft, peaks) = self.recover_soft_bits(samples, &burst::EXTENDED_TRAINING_X);
+        let &(training_pos, _) = peaks.iter().max_by(|a, b| a.1.total_cmp(&b.1))?;
+        let start = training_pos.checked_sub(burst::CB_TRAINING_OFFSET)?;
+        if start + burst::CONTROL_UPLINK_BITS > soft.len() {
+            return None;
+        }
+        let payload = burst::control_uplink_payload(&soft[start..start + burst::CONTROL_UPLINK_BITS]);
+        Some(LogicalChannel::SchHu.decode(&payload, ecc))
+    }
+}
+
+impl Waveform for Tetra {
+    fn info(&self) -> WaveformInfo {
+        WaveformInfo {
+            name: "TETRA",
+            full_name: "Terrestrial Trunked Radio",
+            description: "European standard for professional mobile radio used by \
+                emergency services, public safety, and military organizations",
+            complexity: 4,
+            bits_per_symbol: Self::BITS_PER_SYMBOL,
+            carries_data: true,
+            characteristics: &[
+                "4-slot TDMA in 25 kHz channels",
+                "π/4-DQPSK at 18 ksymbols/sec",
+                "36 kbps gross (9 kbps per slot)",
+                "TEA1/TEA2/TEA3 encryption",
+                "DMO and TMO modes",
+                "Voice + data + SDS",
+                "Group/individual/broadcast calls",
+            ],
+            history: "Developed by ETSI in the 1990s as the European digital PMR standard. \
+                TETRA stands for Terrestrial Trunked Radio (originally Trans-European \
+                Trunked Radio). First commercial networks deployed in 1997.",
+            modern_usage: "Standard for emergency services across Europe and many other \
+                countries. Used by police, fire, ambulance, military, transport, and \
+                utilities. NATO-adopted for tactical communications.",
+        }
+    }
+
+    fn common_params(&self) -> &CommonParams {
+        &self.common
+    }
+
+    fn modulate(&self, data: &[u8]) -> Vec<IQSample> {
+        // Create mutable copy for phase tracking
+        let mut tetra = Self::new(self.common.sample_rate, self.mode, self.encryption);
+
+        // Generate sync burst
+        let mut samples = tetra.generate_sync_burst();
+
+        // Convert data bytes to dibits
+        let dibits: Vec<u8> = data
+            .iter()
+            .flat_map(|&b| {
+                vec![
+                    (b >> 6) & 0x03,
+                    (b >> 4) & 0x03,
+                    (b >> 2) & 0x03,
+                    b & 0x03,
+                ]
+            })
+            .collect();
+
+        // Modulate data
+        samples.extend(tetra.pi4dqpsk_modulate(&dibits));
+
+        samples
+    }
+
+    fn demodulate(&self, samples: &[IQSample]) -> DemodResult {
+        // Skip sync burst (approximate)
+        let sync_symbols = BurstType::Sync.training_sequence().len();
+        let data_start = sync_symbols * self.samples_per_sym;
+
+        if samples.len() <= data_start {
+            return DemodResult::default();
+        }
+
+        let data_samples = &samples[data_start..];
+        let dibits = self.pi4dqpsk_demodulate(data_samples);
+
+        // Pack dibits to bytes
+        let bytes: Vec<u8> = dibits
+            .chunks(4)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u8, |acc, (i, &d)| acc | ((d & 0x03) << (6 - i * 2)))
+            })
+            .collect();
+
+        DemodResult {
+            bits: bytes,
+            symbols: dibits.iter().map(|&d| d as u16).collect(),
+            ber_estimate: None,
+            snr_estimate: None,
+            metadata: std::collections::HashMap::new(),
+        }
+    }
+
+    fn samples_per_symbol(&self) -> usize {
+        self.samples_per_sym
+    }
+
+    fn get_visualization(&self, data: &[u8]) -> VisualizationData {
+        let samples = self.modulate(data);
+
+        // π/4-DQPSK constellation (8 points, alternating between two QPSK sets)
+        let a = 1.0 / 2.0_f64.sqrt();
+        let constellation = vec![
+            // First QPSK set (odd symbols)
+            IQSample::new(a, a),
+            IQSample::new(-a, a),
+            IQSample::new(-a, -a),
+            IQSample::new(a, -a),
+            // Second QPSK set (even symbols, rotated 45°)
+            IQSample::new(1.0, 0.0),
+            IQSample::new(0.0, 1.0),
+            IQSample::new(-1.0, 0.0),
+            IQSample::new(0.0, -1.0),
+        ];
+
+        VisualizationData {
+            samples,
+            constellation,
+            constellation_labels: vec![
+                "00".to_string(),
+                "01".to_string(),
+                "11".to_string(),
+                "10".to_string(),
+                "00'".to_string(),
+                "01'".to_string(),
+                "11'".to_string(),
+                "10'".to_string(),
+            ],
+            spectrum: Vec::new(),
+            description: format!(
+                "TETRA {:?} mode, {:?} encryption, π/4-DQPSK",
+                self.mode, self.encryption
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tetra_creation() {
+        let tetra = Tetra::tmo(72000.0);
+        assert_eq!(tetra.samples_per_sym, 4); // 72000/18000 = 4
+    }
+
+    #[test]
+    fn test_tetra_modulation() {
+        let tetra = Tetra::tmo(72000.0);
+        let data = vec![0xAA, 0x55, 0xF0, 0x0F];
+        let modulated = tetra.modulate(&data);
+
+        assert!(!modulated.is_empty());
+
+        // Check amplitude is reasonable
+        for sample in &modulated {
+            assert!(sample.norm() <= 2.0);
+        }
+    }
+
+    #[test]
+    fn test_tetra_demodulation() {
+        let tetra = Tetra::tmo(72000.0);
+        let data = vec![0xAB, 0xCD];
+        let modulated = tetra.modulate(&data);
+        let demod = tetra.demodulate(&modulated);
+
+        // Should recover some data (may not be perfect without full frame handling)
+        assert!(!demod.bits.is_empty() || !demod.symbols.is_empty());
+    }
+
+    #[test]
+    fn test_tetra_modes() {
+        let tmo = Tetra::tmo(72000.0);
+        let dmo = Tetra::dmo(72000.0);
+
+        assert_eq!(tmo.mode, TetraMode::Tmo);
+        assert_eq!(dmo.mode, TetraMode::Dmo);
+    }
+
+    #[test]
+    fn test_tetra_encryption() {
+        let tea2 = Tetra::with_encryption(72000.0, TetraEncryption::Tea2);
+        assert_eq!(tea2.encryption, TetraEncryption::Tea2);
+    }
+
+    #[test]
+    fn test_waveform_info() {
+        let tetra = Tetra::tmo(72000.0);
+        let info = tetra.info();
+
+        assert_eq!(info.name, "TETRA");
+        assert!(info.description.to_lowercase().contains("european"));
+    }
+
+    fn tmo_downlink_slots() -> Vec<DownlinkSlot> {
+        let first: Vec<u8> = (0..124).map(|i| (i % 3 == 0) as u8).collect();
+        let second: Vec<u8> = (0..124).map(|i| (i % 5 == 1) as u8).collect();
+        let full: Vec<u8> = (0..268).map(|i| ((i * 7) % 11 < 4) as u8).collect();
+        let sync = SyncPdu::new(262, 1010, 5);
+        let sysinfo = SysInfoPdu::new(3600, 100);
+        vec![
+            DownlinkSlot::Sync { sync, sysinfo },
+            DownlinkSlot::HalfSlots(first, second),
+            DownlinkSlot::FullSlot(full),
+            DownlinkSlot::Sync { sync, sysinfo },
+        ]
+    }
+
+    fn check_downlink(decoded: &TmoDownlink, slots: &[DownlinkSlot]) {
+        let sync = decoded.sync.expect("SYNC PDU not decoded");
+        assert_eq!(sync.mcc, 262);
+        assert_eq!(sync.mnc, 1010);
+        assert_eq!(sync.colour_code, 5);
+        assert_eq!(decoded.sysinfo.map(|s| s.location_area), Some(100));
+
+        let DownlinkSlot::HalfSlots(first, second) = &slots[1] else { unreachable!() };
+        let DownlinkSlot::FullSlot(full) = &slots[2] else { unreachable!() };
+        let half: Vec<_> = decoded.blocks.iter().filter(|b| b.channel == LogicalChannel::SchHd).collect();
+        assert_eq!(half.len(), 2);
+        assert!(half.iter().all(|b| b.crc_ok && b.slot == 1));
+        assert_eq!(&half[0].bits, first);
+        assert_eq!(&half[1].bits, second);
+
+        let sch_f = decoded
+            .blocks
+            .iter()
+            .find(|b| b.channel == LogicalChannel::SchF)
+            .expect("SCH/F not decoded");
+        assert!(sch_f.crc_ok);
+        assert_eq!(sch_f.slot, 2);
+        assert_eq!(&sch_f.bits, full);
+    }
+
+    #[test]
+    fn test_tmo_downlink_roundtrip() {
+        let tetra = Tetra::tmo(72000.0);
+        let slots = tmo_downlink_slots();
+        let samples = tetra.modulate_downlink(&slots);
+        let decoded = tetra.decode_downlink(&samples);
+        check_downlink(&decoded, &slots);
+        assert!(decoded.sync_quality > 0.95);
+    }
+
+    #[test]
+    fn test_tmo_downlink_impaired() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        use rand_distr::{Distribution, Normal};
+
+        let tetra = Tetra::tmo(72000.0);
+        let slots = tmo_downlink_slots();
+        let clean = tetra.modulate_downlink(&slots);
+
+        // Unknown timing, carrier phase and ~12 dB SNR
+        let mut rng = StdRng::seed_from_u64(27);
+        let noise = Normal::new(0.0, 0.18).unwrap();
+        let rotation = IQSample::new(0.3f64.cos(), 0.3f64.sin());
+        let mut samples = vec![IQSample::new(0.0, 0.0); 13];
+        samples.extend(clean.iter().map(|&s| s * rotation));
+        for s in samples.iter_mut() {
+            *s += IQSample::new(noise.sample(&mut rng), noise.sample(&mut rng));
+        }
+
+        check_downlink(&tetra.decode_downlink(&samples), &slots);
+    }
+
+    #[test]
+    fn test_downlink_without_sync_burst() {
+        // Without a BSCH the scrambling code is unknown, so nothing is decoded
+        let tetra = Tetra::tmo(72000.0);
+        let slots = tmo_downlink_slots();
+        let samples = tetra.modulate_downlink(&slots[1..3]);
+        let decoded = tetra.decode_downlink(&samples);
+        assert!(decoded.sync.is_none());
+        assert!(decoded.blocks.is_empty());
+    }
+
+    #[test]
+    fn test_control_uplink_roundtrip() {
+        let tetra = Tetra::tmo(72000.0);
+        let ecc = ExtendedColourCode::new(262, 1010, 5);
+        let payload: Vec<u8> = (0..92).map(|i| (i % 4 == 1) as u8).collect();
+        let samples = tetra.modulate_control_uplink(&payload, ecc);
+
+        let (bits, crc_ok) = tetra.decode_control_uplink(&samples, ecc).expect("burst not found");
+        assert!(crc_ok);
+        assert_eq!(bits, payload);
+
+        let (_, crc_ok) = tetra
+            .decode_control_uplink(&samples, ExtendedColourCode::new(262, 1011, 5))
+            .expect("burst not found");
+        assert!(!crc_ok);
+    }
+}
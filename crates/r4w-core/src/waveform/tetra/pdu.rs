@@ -0,0 +1,297 @@
+//! TETRA Broadcast PDUs
+//!
+//! The two broadcast PDUs a mobile needs to camp on a cell:
+//!
+//! - **SYNC** (BSCH, 60 bits): colour code, TDMA timing and the
+//!   D-MLE-SYNC TM-SDU with MCC/MNC
+//! - **SYSINFO** (BNCH, 124 bits): carrier, access parameters and the
+//!   D-MLE-SYSINFO TM-SDU with location area and service details
+
+use super::coding::ExtendedColourCode;
+
+/// Bit writer for MSB-first fields
+struct BitWriter(Vec<u8>);
+
+impl BitWriter {
+    fn put(&mut self, value: u32, bits: usize) {
+        self.0.extend((0..bits).rev().map(|i| ((value >> i) & 1) as u8));
+    }
+}
+
+/// Bit reader for MSB-first fields
+struct BitReader<'a> {
+    bits: &'a [u8],
+    pos: usize,
+}
+
+impl BitReader<'_> {
+    fn get(&mut self, bits: usize) -> u32 {
+        let value = self.bits[self.pos..self.pos + bits]
+            .iter()
+            .fold(0u32, |acc, &b| (acc << 1) | (b & 1) as u32);
+        self.pos += bits;
+        value
+    }
+}
+
+/// SYNC PDU broadcast on the BSCH
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncPdu {
+    /// System code (4 bits)
+    pub system_code: u8,
+    /// Colour code (6 bits)
+    pub colour_code: u8,
+    /// Timeslot number (0-3)
+    pub timeslot: u8,
+    /// Frame number (1-18)
+    pub frame: u8,
+    /// Multiframe number (1-60)
+    pub multiframe: u8,
+    /// Sharing mode (2 bits)
+    pub sharing_mode: u8,
+    /// TS reserved frames (3 bits)
+    pub reserved_frames: u8,
+    /// U-plane DTX allowed
+    pub u_plane_dtx: bool,
+    /// Frame 18 extension allowed
+    pub frame18_extension: bool,
+    /// Mobile country code (10 bits)
+    pub mcc: u16,
+    /// Mobile network code (14 bits)
+    pub mnc: u16,
+    /// Neighbour cell broadcast (2 bits)
+    pub neighbour_cell_broadcast: u8,
+    /// Cell service level (2 bits)
+    pub cell_service_level: u8,
+    /// Late entry supported
+    pub late_entry: bool,
+}
+
+impl SyncPdu {
+    /// SYNC PDU for a cell, with timing at the start of a multiframe
+    pub fn new(mcc: u16, mnc: u16, colour_code: u8) -> Self {
+        Self {
+            system_code: 0b0010,
+            colour_code: colour_code & 0x3F,
+            timeslot: 0,
+            frame: 18,
+            multiframe: 1,
+            sharing_mode: 0,
+            reserved_frames: 0,
+            u_plane_dtx: false,
+            frame18_extension: false,
+            mcc: mcc & 0x3FF,
+            mnc: mnc & 0x3FFF,
+            neighbour_cell_broadcast: 0,
+            cell_service_level: 0,
+            late_entry: true,
+        }
+    }
+
+    /// Extended colour code used to scramble every other channel of this cell
+    pub fn extended_colour_code(&self) -> ExtendedColourCode {
+        ExtendedColourCode::new(self.mcc, self.mnc, self.colour_code)
+    }
+
+    /// Serialize to 60 type-1 bits
+    pub fn to_bits(&self) -> Vec<u8> {
+        let mut w = BitWriter(Vec::with_capacity(60));
+        w.put(self.system_code as u32, 4);
+        w.put(self.colour_code as u32, 6);
+        w.put(self.timeslot as u32, 2);
+        w.put(self.frame as u32, 5);
+        w.put(self.multiframe as u32, 6);
+        w.put(self.sharing_mode as u32, 2);
+        w.put(self.reserved_frames as u32, 3);
+        w.put(self.u_plane_dtx as u32, 1);
+        w.put(self.frame18_extension as u32, 1);
+        w.put(0, 1); // reserved
+        w.put(self.mcc as u32, 10);
+        w.put(self.mnc as u32, 14);
+        w.put(self.neighbour_cell_broadcast as u32, 2);
+        w.put(self.cell_service_level as u32, 2);
+        w.put(self.late_entry as u32, 1);
+        w.0
+    }
+
+    /// Parse from 60 type-1 bits
+    pub fn from_bits(bits: &[u8]) -> Self {
+        let mut r = BitReader { bits, pos: 0 };
+        let system_code = r.get(4) as u8;
+        let colour_code = r.get(6) as u8;
+        let timeslot = r.get(2) as u8;
+        let frame = r.get(5) as u8;
+        let multiframe = r.get(6) as u8;
+        let sharing_mode = r.get(2) as u8;
+        let reserved_frames = r.get(3) as u8;
+        let u_plane_dtx = r.get(1) == 1;
+        let frame18_extension = r.get(1) == 1;
+        r.get(1);
+        Self {
+            system_code,
+            colour_code,
+            timeslot,
+            frame,
+            multiframe,
+            sharing_mode,
+            reserved_frames,
+            u_plane_dtx,
+            frame18_extension,
+            mcc: r.get(10) as u16,
+            mnc: r.get(14) as u16,
+            neighbour_cell_broadcast: r.get(2) as u8,
+            cell_service_level: r.get(2) as u8,
+            late_entry: r.get(1) == 1,
+        }
+    }
+}
+
+/// SYSINFO PDU broadcast on the BNCH
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SysInfoPdu {
+    /// Main carrier number (12 bits)
+    pub main_carrier: u16,
+    /// Frequency band (4 bits)
+    pub frequency_band: u8,
+    /// Carrier offset (2 bits)
+    pub offset: u8,
+    /// Duplex spacing (3 bits)
+    pub duplex_spacing: u8,
+    /// Reverse operation
+    pub reverse_operation: bool,
+    /// Number of common secondary control channels (2 bits)
+    pub secondary_control_channels: u8,
+    /// MS_TXPWR_MAX_CELL (3 bits)
+    pub ms_txpwr_max: u8,
+    /// RXLEV_ACCESS_MIN (4 bits)
+    pub rxlev_access_min: u8,
+    /// ACCESS_PARAMETER (4 bits)
+    pub access_parameter: u8,
+    /// RADIO_DOWNLINK_TIMEOUT (4 bits)
+    pub radio_downlink_timeout: u8,
+    /// Hyperframe number (16 bits)
+    pub hyperframe: u16,
+    /// Location area (14 bits)
+    pub location_area: u16,
+    /// Subscriber class (16 bits)
+    pub subscriber_class: u16,
+    /// BS service details (12 bits)
+    pub bs_service_details: u16,
+}
+
+impl SysInfoPdu {
+    /// MAC PDU type for broadcast
+    const MAC_PDU_BROADCAST: u32 = 0b10;
+
+    /// SYSINFO for a cell on `main_carrier` in `location_area`
+    pub fn new(main_carrier: u16, location_area: u16) -> Self {
+        Self {
+            main_carrier: main_carrier & 0xFFF,
+            frequency_band: 4,
+            offset: 0,
+            duplex_spacing: 0,
+            reverse_operation: false,
+            secondary_control_channels: 0,
+            ms_txpwr_max: 5,
+            rxlev_access_min: 0,
+            access_parameter: 0,
+            radio_downlink_timeout: 0,
+            hyperframe: 0,
+            location_area: location_area & 0x3FFF,
+            subscriber_class: 0xFFFF,
+            bs_service_details: 0,
+        }
+    }
+
+    /// Serialize to 124 type-1 bits
+    pub fn to_bits(&self) -> Vec<u8> {
+        let mut w = BitWriter(Vec::with_capacity(124));
+        w.put(Self::MAC_PDU_BROADCAST, 2);
+        w.put(0, 2); // broadcast type: SYSINFO
+        w.put(self.main_carrier as u32, 12);
+        w.put(self.frequency_band as u32, 4);
+        w.put(self.offset as u32, 2);
+        w.put(self.duplex_spacing as u32, 3);
+        w.put(self.reverse_operation as u32, 1);
+        w.put(self.secondary_control_channels as u32, 2);
+        w.put(self.ms_txpwr_max as u32, 3);
+        w.put(self.rxlev_access_min as u32, 4);
+        w.put(self.access_parameter as u32, 4);
+        w.put(self.radio_downlink_timeout as u32, 4);
+        w.put(0, 1); // hyperframe number follows (not cipher key)
+        w.put(self.hyperframe as u32, 16);
+        w.put(0, 2); // optional field flag: even multiframe definition
+        w.put(0, 20); // optional field value
+        w.put(self.location_area as u32, 14);
+        w.put(self.subscriber_class as u32, 16);
+        w.put(self.bs_service_details as u32, 12);
+        w.0
+    }
+
+    /// Parse from 124 type-1 bits; `None` if not a SYSINFO PDU
+    pub fn from_bits(bits: &[u8]) -> Option<Self> {
+        let mut r = BitReader { bits, pos: 0 };
+        if r.get(2) != Self::MAC_PDU_BROADCAST || r.get(2) != 0 {
+            return None;
+        }
+        let main_carrier = r.get(12) as u16;
+        let frequency_band = r.get(4) as u8;
+        let offset = r.get(2) as u8;
+        let duplex_spacing = r.get(3) as u8;
+        let reverse_operation = r.get(1) == 1;
+        let secondary_control_channels = r.get(2) as u8;
+        let ms_txpwr_max = r.get(3) as u8;
+        let rxlev_access_min = r.get(4) as u8;
+        let access_parameter = r.get(4) as u8;
+        let radio_downlink_timeout = r.get(4) as u8;
+        r.get(1);
+        let hyperframe = r.get(16) as u16;
+        r.get(2);
+        r.get(20);
+        Some(Self {
+            main_carrier,
+            frequency_band,
+            offset,
+            duplex_spacing,
+            reverse_operation,
+            secondary_control_channels,
+            ms_txpwr_max,
+            rxlev_access_min,
+            access_parameter,
+            radio_downlink_timeout,
+            hyperframe,
+            location_area: r.get(14) as u16,
+            subscriber_class: r.get(16) as u16,
+            bs_service_details: r.get(12) as u16,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_pdu_roundtrip() {
+        let mut sync = SyncPdu::new(262, 1010, 42);
+        sync.timeslot = 2;
+        sync.frame = 18;
+        sync.multiframe = 37;
+        let bits = sync.to_bits();
+        assert_eq!(bits.len(), 60);
+        assert_eq!(SyncPdu::from_bits(&bits), sync);
+    }
+
+    #[test]
+    fn test_sysinfo_pdu_roundtrip() {
+        let mut sysinfo = SysInfoPdu::new(3600, 1234);
+        sysinfo.hyperframe = 0xBEEF;
+        let bits = sysinfo.to_bits();
+        assert_eq!(bits.len(), 124);
+        assert_eq!(SysInfoPdu::from_bits(&bits), Some(sysinfo));
+
+        let mut not_broadcast = bits;
+        not_broadcast[0] = 0;
+        assert_eq!(SysInfoPdu::from_bits(&not_broadcast), None);
+    }
+}
@@ -32,6 +32,39 @@
 //! - Secure communications
 //! - Through-wall imaging
 //! - High-precision ranging
+//!
+//! ## Ranging
+//!
+//! [`ranging`] provides leading-edge time-of-arrival detection and
+//! SS-/DS-TWR exchanges; [`positioning`] solves trilateration and TDoA
+//! fixes from the resulting ranges.
+//!
+//! ```rust
+//! use r4w_core::waveform::uwb::{DsTwrExchange, DeviceClock, UwbIr};
+//!
+//! let uwb = UwbIr::ieee_802_15_4a(4e9);
+//! let detector = uwb.first_path_detector();
+//! let toa = detector.detect(&uwb.pulse_at(1000, 250.3)).unwrap();
+//! assert!((toa.first_path - 250.3).abs() < 0.2);
+//!
+//! let exchange = DsTwrExchange::simulate(
+//!     DeviceClock::with_drift(10.0),
+//!     DeviceClock::with_drift(-10.0),
+//!     12.0,
+//!     500e-6,
+//!     700e-6,
+//!     || 0.0,
+//! );
+//! assert!((exchange.distance_m() - 12.0).abs() < 0.01);
+//! ```
+
+pub mod positioning;
+pub mod ranging;
+
+pub use positioning::{tdoa_locate, trilaterate, PositionFix};
+pub use ranging::{
+    DeviceClock, DsTwrExchange, FirstPathConfig, FirstPathDetector, SsTwrExchange, ToaEstimate, SPEED_OF_LIGHT,
+};
 
 use super::{CommonParams, DemodResult, VisualizationData, Waveform, WaveformInfo};
 use crate::types::IQSample;
@@ -66,6 +99,23 @@ fn is_packed_bytes(data: &[u8]) -> bool {
     data.iter().any(|&b| b > 1)
 }
 
+/// Circularly delay samples by a fractional number of samples
+pub fn fractional_delay(samples: &[IQSample], delay: f64) -> Vec<IQSample> {
+    let n = samples.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut fft = crate::fft_utils::FftProcessor::new(n);
+    let mut spectrum = fft.fft(samples);
+    for (k, bin) in spectrum.iter_mut().enumerate() {
+        // Signed frequency index; the Nyquist bin is kept real
+        let f = if 2 * k < n { k as f64 } else if 2 * k == n { 0.0 } else { k as f64 - n as f64 };
+        *bin *= IQSample::from_polar(1.0, -2.0 * PI * f * delay / n as f64);
+    }
+    fft.ifft_inplace(&mut spectrum);
+    spectrum
+}
+
 /// UWB pulse shape
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PulseShape {
@@ -235,6 +285,31 @@ impl UwbIr {
         pulse
     }
 
+    /// Pulse template (unit energy)
+    pub fn pulse_template(&self) -> &[f64] {
+        &self.pulse_template
+    }
+
+    /// Samples per pulse interval
+    pub fn samples_per_interval(&self) -> usize {
+        self.samples_per_interval
+    }
+
+    /// First-path detector matched to this pulse shape
+    pub fn first_path_detector(&self) -> FirstPathDetector {
+        FirstPathDetector::new(&self.pulse_template, self.common.sample_rate, FirstPathConfig::default())
+    }
+
+    /// `len` samples containing one pulse starting at fractional sample
+    /// `start`, band-limited-interpolated by an FFT phase ramp
+    pub fn pulse_at(&self, len: usize, start: f64) -> Vec<IQSample> {
+        let mut samples = vec![IQSample::new(0.0, 0.0); len];
+        for (s, &p) in samples.iter_mut().zip(&self.pulse_template) {
+            *s = IQSample::new(p * self.common.amplitude, 0.0);
+        }
+        fractional_delay(&samples, start)
+    }
+
     /// Get pulse bandwidth (approximate -10 dB bandwidth)
     pub fn bandwidth(&self) -> f64 {
         // Approximate: BW ≈ 1 / pulse_duration for Gaussian pulses
@@ -0,0 +1,226 @@
+//! Multilateration from UWB Ranges
+//!
+//! - **Trilateration** (two-way ranging): solve for the position whose
+//!   distances to the anchors best match the measured ranges
+//! - **TDoA**: the tag only transmits; synchronized anchors measure arrival
+//!   time differences, giving range differences to a reference anchor
+//!
+//! Both are solved by Gauss-Newton least squares with Levenberg damping,
+//! for 2D (`D = 2`) or 3D (`D = 3`) positions. At least `D + 1` anchors are
+//! needed for trilateration and `D + 1` for TDoA (`D` differences).
+
+/// Position fix
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionFix<const D: usize> {
+    /// Estimated position
+    pub position: [f64; D],
+    /// RMS of the range (or range-difference) residuals in meters
+    pub residual_rms: f64,
+    /// Iterations used
+    pub iterations: usize,
+}
+
+const MAX_ITERATIONS: usize = 50;
+const CONVERGENCE_M: f64 = 1e-9;
+
+fn distance<const D: usize>(a: &[f64; D], b: &[f64; D]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f64>().sqrt()
+}
+
+/// Unit vector from `anchor` towards `p` (gradient of the distance)
+fn unit<const D: usize>(p: &[f64; D], anchor: &[f64; D]) -> [f64; D] {
+    let d = distance(p, anchor).max(1e-12);
+    std::array::from_fn(|i| (p[i] - anchor[i]) / d)
+}
+
+/// Solve `a · x = b` by Gaussian elimination with partial pivoting
+fn solve<const D: usize>(mut a: [[f64; D]; D], mut b: [f64; D]) -> Option<[f64; D]> {
+    for col in 0..D {
+        let pivot = (col..D).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..D {
+            let factor = a[row][col] / a[col][col];
+            let (upper, lower) = a.split_at_mut(row);
+            for (x, &pivot_x) in lower[0][col..].iter_mut().zip(&upper[col][col..]) {
+                *x -= factor * pivot_x;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = [0.0; D];
+    for row in (0..D).rev() {
+        let sum: f64 = (row + 1..D).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+/// Levenberg-Marquardt on residuals `r_i(p)` with Jacobian rows `J_i(p)`
+fn least_squares<const D: usize>(
+    initial: [f64; D],
+    residuals: impl Fn(&[f64; D]) -> Vec<(f64, [f64; D])>,
+) -> Option<PositionFix<D>> {
+    let cost = |p: &[f64; D]| residuals(p).iter().map(|(r, _)| r * r).sum::<f64>();
+    let mut p = initial;
+    let mut current = cost(&p);
+    let mut lambda = 1e-3;
+    let mut iterations = 0;
+
+    while iterations < MAX_ITERATIONS {
+        iterations += 1;
+        let terms = residuals(&p);
+        let mut jtj = [[0.0; D]; D];
+        let mut jtr = [0.0; D];
+        for (r, j) in &terms {
+            for a in 0..D {
+                jtr[a] -= j[a] * r;
+                for b in 0..D {
+                    jtj[a][b] += j[a] * j[b];
+                }
+            }
+        }
+
+        let mut damped = jtj;
+        for (a, row) in damped.iter_mut().enumerate() {
+            row[a] += lambda * jtj[a][a].max(1e-9);
+        }
+        let step = solve(damped, jtr)?;
+        let candidate: [f64; D] = std::array::from_fn(|i| p[i] + step[i]);
+        let candidate_cost = cost(&candidate);
+
+        if candidate_cost <= current {
+            p = candidate;
+            current = candidate_cost;
+            lambda = (lambda * 0.3).max(1e-12);
+            if step.iter().map(|s| s * s).sum::<f64>().sqrt() < CONVERGENCE_M {
+                break;
+            }
+        } else {
+            lambda *= 10.0;
+            if lambda > 1e12 {
+                break;
+            }
+        }
+    }
+
+    let n = residuals(&p).len().max(1);
+    Some(PositionFix {
+        position: p,
+        residual_rms: (current / n as f64).sqrt(),
+        iterations,
+    })
+}
+
+/// Centroid of the anchors
+fn centroid<const D: usize>(anchors: &[[f64; D]]) -> [f64; D] {
+    std::array::from_fn(|i| anchors.iter().map(|a| a[i]).sum::<f64>() / anchors.len() as f64)
+}
+
+/// Position from ranges to known anchors (two-way ranging)
+pub fn trilaterate<const D: usize>(anchors: &[[f64; D]], ranges: &[f64]) -> Option<PositionFix<D>> {
+    if anchors.len() < D + 1 || anchors.len() != ranges.len() {
+        return None;
+    }
+
+    // Linearized initial guess: subtract the first anchor's sphere equation
+    let a0 = &anchors[0];
+    let mut ata = [[0.0; D]; D];
+    let mut atb = [0.0; D];
+    for (anchor, &range) in anchors.iter().zip(ranges).skip(1) {
+        let row: [f64; D] = std::array::from_fn(|i| 2.0 * (anchor[i] - a0[i]));
+        let rhs = ranges[0] * ranges[0] - range * range + anchor.iter().map(|x| x * x).sum::<f64>()
+            - a0.iter().map(|x| x * x).sum::<f64>();
+        for a in 0..D {
+            atb[a] += row[a] * rhs;
+            for b in 0..D {
+                ata[a][b] += row[a] * row[b];
+            }
+        }
+    }
+    let initial = solve(ata, atb).unwrap_or_else(|| centroid(anchors));
+
+    least_squares(initial, |p| {
+        anchors
+            .iter()
+            .zip(ranges)
+            .map(|(anchor, &range)| (distance(p, anchor) - range, unit(p, anchor)))
+            .collect()
+    })
+}
+
+/// Position from TDoA range differences.
+///
+/// `range_differences[i]` is `|p − anchors[i + 1]| − |p − anchors[0]|`,
+/// i.e. the arrival time difference against anchor 0 times the speed of light.
+pub fn tdoa_locate<const D: usize>(anchors: &[[f64; D]], range_differences: &[f64]) -> Option<PositionFix<D>> {
+    if anchors.len() < D + 1 || anchors.len() != range_differences.len() + 1 {
+        return None;
+    }
+    let reference = &anchors[0];
+
+    least_squares(centroid(anchors), |p| {
+        let u0 = unit(p, reference);
+        let d0 = distance(p, reference);
+        anchors[1..]
+            .iter()
+            .zip(range_differences)
+            .map(|(anchor, &diff)| {
+                let ui = unit(p, anchor);
+                (distance(p, anchor) - d0 - diff, std::array::from_fn(|k| ui[k] - u0[k]))
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANCHORS_2D: [[f64; 2]; 4] = [[0.0, 0.0], [20.0, 0.0], [20.0, 15.0], [0.0, 15.0]];
+
+    #[test]
+    fn test_trilaterate_2d() {
+        let tag = [7.5, 4.2];
+        let ranges: Vec<f64> = ANCHORS_2D.iter().map(|a| distance(&tag, a)).collect();
+        let fix = trilaterate(&ANCHORS_2D, &ranges).unwrap();
+        assert!(distance(&fix.position, &tag) < 1e-6);
+        assert!(fix.residual_rms < 1e-6);
+    }
+
+    #[test]
+    fn test_trilaterate_3d_noisy() {
+        let anchors = [
+            [0.0, 0.0, 3.0],
+            [10.0, 0.0, 2.5],
+            [10.0, 8.0, 3.0],
+            [0.0, 8.0, 0.5],
+            [5.0, 4.0, 3.2],
+        ];
+        let tag = [3.0, 5.0, 1.2];
+        let noise = [0.05, -0.03, 0.02, -0.04, 0.01];
+        let ranges: Vec<f64> = anchors.iter().zip(noise).map(|(a, n)| distance(&tag, a) + n).collect();
+        let fix = trilaterate(&anchors, &ranges).unwrap();
+        assert!(distance(&fix.position, &tag) < 0.3);
+        assert!(fix.residual_rms < 0.1);
+    }
+
+    #[test]
+    fn test_tdoa_2d() {
+        let tag = [13.0, 9.0];
+        let d0 = distance(&tag, &ANCHORS_2D[0]);
+        let diffs: Vec<f64> = ANCHORS_2D[1..].iter().map(|a| distance(&tag, a) - d0).collect();
+        let fix = tdoa_locate(&ANCHORS_2D, &diffs).unwrap();
+        assert!(distance(&fix.position, &tag) < 1e-6);
+    }
+
+    #[test]
+    fn test_insufficient_anchors() {
+        assert!(trilaterate(&ANCHORS_2D[..2], &[1.0, 2.0]).is_none());
+        assert!(tdoa_locate(&ANCHORS_2D[..2], &[1.0]).is_none());
+        assert!(trilaterate(&ANCHORS_2D, &[1.0, 2.0]).is_none());
+    }
+}
@@ -0,0 +1,481 @@
+//! UWB Time-of-Arrival and Two-Way Ranging
+//!
+//! ## First-Path Detection
+//!
+//! The receiver correlates against the pulse template and its Hilbert
+//! transform, giving the envelope of the channel impulse response (CIR).
+//! In multipath the strongest tap is often a reflection, so the time of
+//! arrival is taken from the *leading edge*: the earliest CIR sample within
+//! a search-back window before the peak that exceeds both a noise-based and
+//! a peak-relative threshold. The first path's own peak is then refined
+//! with parabolic interpolation for sub-sample resolution.
+//!
+//! ## Two-Way Ranging
+//!
+//! ```text
+//! SS-TWR:                     DS-TWR:
+//! Initiator    Responder      Initiator    Responder
+//!   t1 ──Poll──▶ t2             t1 ──Poll──▶ t2
+//!   t4 ◀─Resp─── t3             t4 ◀─Resp─── t3
+//!                               t5 ──Final─▶ t6
+//! ToF = (Tround − Treply) / 2
+//! ```
+//!
+//! Each device timestamps with its own clock. SS-TWR is biased by the
+//! clock drift times the reply delay (1 ppm × 1 ms ≈ 15 cm) unless the
+//! relative drift is known, e.g. from carrier frequency offset. The
+//! asymmetric DS-TWR estimator cancels drift to first order.
+
+use crate::fft_utils::FftProcessor;
+use crate::types::IQSample;
+use rustfft::num_complex::Complex64;
+
+/// Speed of light in m/s
+pub const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// First-path detector thresholds
+#[derive(Debug, Clone, Copy)]
+pub struct FirstPathConfig {
+    /// Threshold relative to the median CIR envelope (noise floor)
+    pub noise_factor: f64,
+    /// Threshold relative to the CIR peak
+    pub peak_fraction: f64,
+    /// How far before the peak to search for the first path, in seconds
+    pub search_back_s: f64,
+}
+
+impl Default for FirstPathConfig {
+    fn default() -> Self {
+        Self {
+            noise_factor: 6.0,
+            peak_fraction: 0.25,
+            search_back_s: 100e-9,
+        }
+    }
+}
+
+/// Time-of-arrival estimate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToaEstimate {
+    /// Fractional sample index where the first-path pulse starts
+    pub first_path: f64,
+    /// Sample index of the strongest CIR tap
+    pub peak_index: usize,
+    /// CIR envelope at the first path
+    pub first_path_amplitude: f64,
+    /// CIR envelope at the peak
+    pub peak_amplitude: f64,
+    /// Median CIR envelope (noise floor estimate)
+    pub noise_level: f64,
+}
+
+impl ToaEstimate {
+    /// First-path arrival time in seconds
+    pub fn time_s(&self, sample_rate: f64) -> f64 {
+        self.first_path / sample_rate
+    }
+
+    /// Samples between the first path and the strongest path
+    pub fn first_to_peak(&self) -> f64 {
+        self.peak_index as f64 - self.first_path
+    }
+}
+
+/// Leading-edge time-of-arrival detector
+#[derive(Debug, Clone)]
+pub struct FirstPathDetector {
+    /// In-phase template (pulse, zero-padded on both sides)
+    in_phase: Vec<f64>,
+    /// Quadrature template (Hilbert transform of the pulse)
+    quadrature: Vec<f64>,
+    /// Padding before the pulse in the templates
+    padding: usize,
+    sample_rate: f64,
+    config: FirstPathConfig,
+}
+
+impl FirstPathDetector {
+    /// Detector for a real pulse template sampled at `sample_rate`
+    pub fn new(template: &[f64], sample_rate: f64, config: FirstPathConfig) -> Self {
+        let padding = template.len();
+        let len = template.len() + 2 * padding;
+        let fft_size = (2 * len).next_power_of_two();
+
+        let mut padded = vec![Complex64::new(0.0, 0.0); fft_size];
+        for (i, &t) in template.iter().enumerate() {
+            padded[padding + i] = Complex64::new(t, 0.0);
+        }
+
+        // Analytic signal: zero negative frequencies, double positive ones
+        let mut fft = FftProcessor::new(fft_size);
+        fft.fft_inplace(&mut padded);
+        for (k, bin) in padded.iter_mut().enumerate() {
+            if k > 0 && k < fft_size / 2 {
+                *bin *= 2.0;
+            } else if k > fft_size / 2 {
+                *bin = Complex64::new(0.0, 0.0);
+            }
+        }
+        fft.ifft_inplace(&mut padded);
+
+        let norm = template.iter().map(|t| t * t).sum::<f64>().sqrt().max(f64::MIN_POSITIVE);
+        Self {
+            in_phase: padded[..len].iter().map(|c| c.re / norm).collect(),
+            quadrature: padded[..len].iter().map(|c| c.im / norm).collect(),
+            padding,
+            sample_rate,
+            config,
+        }
+    }
+
+    /// Detector configuration
+    pub fn config(&self) -> &FirstPathConfig {
+        &self.config
+    }
+
+    /// CIR envelope: index `n` corresponds to a pulse starting at sample `n`
+    pub fn cir(&self, samples: &[IQSample]) -> Vec<f64> {
+        let len = self.in_phase.len();
+        let total = samples.len() + self.padding;
+        (0..total.saturating_sub(self.padding))
+            .map(|n| {
+                // Template tap k aligns with sample n − padding + k
+                let mut i_acc = IQSample::new(0.0, 0.0);
+                let mut q_acc = IQSample::new(0.0, 0.0);
+                for k in 0..len {
+                    let Some(idx) = (n + k).checked_sub(self.padding) else {
+                        continue;
+                    };
+                    let Some(&x) = samples.get(idx) else {
+                        break;
+                    };
+                    i_acc += x * self.in_phase[k];
+                    q_acc += x * self.quadrature[k];
+                }
+                (i_acc.norm_sqr() + q_acc.norm_sqr()).sqrt()
+            })
+            .collect()
+    }
+
+    /// Estimate the first-path arrival in `samples`
+    pub fn detect(&self, samples: &[IQSample]) -> Option<ToaEstimate> {
+        self.detect_cir(&self.cir(samples))
+    }
+
+    /// Coherently accumulate `repetitions` periods of a repeated preamble
+    /// symbol, then estimate the first-path arrival within one period
+    pub fn detect_accumulated(&self, samples: &[IQSample], period: usize, repetitions: usize) -> Option<ToaEstimate> {
+        if period == 0 || repetitions == 0 || samples.len() < period * repetitions {
+            return None;
+        }
+        let mut accumulated = vec![IQSample::new(0.0, 0.0); period];
+        for chunk in samples.chunks_exact(period).take(repetitions) {
+            for (acc, &x) in accumulated.iter_mut().zip(chunk) {
+                *acc += x;
+            }
+        }
+        self.detect(&accumulated)
+    }
+
+    /// Leading-edge search on a CIR envelope
+    pub fn detect_cir(&self, cir: &[f64]) -> Option<ToaEstimate> {
+        let (peak_index, &peak_amplitude) = cir.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1))?;
+        if peak_amplitude <= 0.0 {
+            return None;
+        }
+
+        let mut sorted = cir.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let noise_level = sorted[sorted.len() / 2];
+
+        let threshold = (self.config.noise_factor * noise_level).max(self.config.peak_fraction * peak_amplitude);
+        let search_back = (self.config.search_back_s * self.sample_rate).round() as usize;
+        let start = peak_index.saturating_sub(search_back);
+
+        // Earliest threshold crossing, then climb to that path's own peak
+        let mut first = (start..=peak_index).find(|&n| cir[n] > threshold)?;
+        while first + 1 < cir.len() && cir[first + 1] > cir[first] {
+            first += 1;
+        }
+
+        Some(ToaEstimate {
+            first_path: first as f64 + parabolic_offset(cir, first),
+            peak_index,
+            first_path_amplitude: cir[first],
+            peak_amplitude,
+            noise_level,
+        })
+    }
+}
+
+/// Sub-sample offset of a local maximum from a parabola through three points
+fn parabolic_offset(values: &[f64], index: usize) -> f64 {
+    if index == 0 || index + 1 >= values.len() {
+        return 0.0;
+    }
+    let (a, b, c) = (values[index - 1], values[index], values[index + 1]);
+    let denom = a - 2.0 * b + c;
+    if denom.abs() < f64::EPSILON {
+        0.0
+    } else {
+        (0.5 * (a - c) / denom).clamp(-0.5, 0.5)
+    }
+}
+
+/// Free-running device clock
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DeviceClock {
+    /// Local time at true time zero, in seconds
+    pub offset_s: f64,
+    /// Frequency error in parts per million (positive = runs fast)
+    pub drift_ppm: f64,
+}
+
+impl DeviceClock {
+    /// Clock with the given frequency error
+    pub fn with_drift(drift_ppm: f64) -> Self {
+        Self {
+            offset_s: 0.0,
+            drift_ppm,
+        }
+    }
+
+    /// Local timestamp of a true time
+    pub fn local(&self, true_s: f64) -> f64 {
+        self.offset_s + true_s * (1.0 + self.drift_ppm * 1e-6)
+    }
+
+    /// True time of a local timestamp
+    pub fn true_time(&self, local_s: f64) -> f64 {
+        (local_s - self.offset_s) / (1.0 + self.drift_ppm * 1e-6)
+    }
+}
+
+/// Single-sided two-way ranging timestamps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SsTwrExchange {
+    /// Poll transmit (initiator clock)
+    pub t1: f64,
+    /// Poll receive (responder clock)
+    pub t2: f64,
+    /// Response transmit (responder clock)
+    pub t3: f64,
+    /// Response receive (initiator clock)
+    pub t4: f64,
+}
+
+impl SsTwrExchange {
+    /// Simulate an exchange over `distance_m`.
+    ///
+    /// The responder replies `reply_delay_s` after the poll by its own
+    /// clock. `rx_error` is called once per reception and returns the
+    /// time-of-arrival error in seconds (e.g. from first-path detection).
+    pub fn simulate(
+        initiator: DeviceClock,
+        responder: DeviceClock,
+        distance_m: f64,
+        reply_delay_s: f64,
+        mut rx_error: impl FnMut() -> f64,
+    ) -> Self {
+        let tof = distance_m / SPEED_OF_LIGHT;
+        let t1 = initiator.local(0.0);
+        let t2_exact = responder.local(tof);
+        let t3 = t2_exact + reply_delay_s;
+        let response_arrival = responder.true_time(t3) + tof;
+        Self {
+            t1,
+            t2: t2_exact + rx_error(),
+            t3,
+            t4: initiator.local(response_arrival) + rx_error(),
+        }
+    }
+
+    /// Round-trip time measured by the initiator
+    pub fn round(&self) -> f64 {
+        self.t4 - self.t1
+    }
+
+    /// Reply time measured by the responder
+    pub fn reply(&self) -> f64 {
+        self.t3 - self.t2
+    }
+
+    /// Time of flight ignoring clock drift
+    pub fn tof(&self) -> f64 {
+        (self.round() - self.reply()) / 2.0
+    }
+
+    /// Time of flight with the responder's reply time rescaled to the
+    /// initiator's clock; `relative_drift_ppm` is the responder's frequency
+    /// error relative to the initiator (e.g. from carrier offset estimation)
+    pub fn tof_compensated(&self, relative_drift_ppm: f64) -> f64 {
+        (self.round() - self.reply() / (1.0 + relative_drift_ppm * 1e-6)) / 2.0
+    }
+
+    /// Distance from [`Self::tof_compensated`]
+    pub fn distance_m(&self, relative_drift_ppm: f64) -> f64 {
+        self.tof_compensated(relative_drift_ppm) * SPEED_OF_LIGHT
+    }
+}
+
+/// Double-sided two-way ranging timestamps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DsTwrExchange {
+    /// Poll transmit (initiator clock)
+    pub t1: f64,
+    /// Poll receive (responder clock)
+    pub t2: f64,
+    /// Response transmit (responder clock)
+    pub t3: f64,
+    /// Response receive (initiator clock)
+    pub t4: f64,
+    /// Final transmit (initiator clock)
+    pub t5: f64,
+    /// Final receive (responder clock)
+    pub t6: f64,
+}
+
+impl DsTwrExchange {
+    /// Simulate an exchange; each side replies after its own reply delay
+    /// (which need not match). See [`SsTwrExchange::simulate`].
+    pub fn simulate(
+        initiator: DeviceClock,
+        responder: DeviceClock,
+        distance_m: f64,
+        responder_reply_s: f64,
+        initiator_reply_s: f64,
+        mut rx_error: impl FnMut() -> f64,
+    ) -> Self {
+        let tof = distance_m / SPEED_OF_LIGHT;
+        let ss = SsTwrExchange::simulate(initiator, responder, distance_m, responder_reply_s, &mut rx_error);
+
+        // Final is scheduled from the exact (not the measured) response arrival
+        let t4_exact = initiator.local(responder.true_time(ss.t3) + tof);
+        let t5 = t4_exact + initiator_reply_s;
+        let final_arrival = initiator.true_time(t5) + tof;
+        Self {
+            t1: ss.t1,
+            t2: ss.t2,
+            t3: ss.t3,
+            t4: ss.t4,
+            t5,
+            t6: responder.local(final_arrival) + rx_error(),
+        }
+    }
+
+    /// Time of flight from the asymmetric DS-TWR estimator
+    pub fn tof(&self) -> f64 {
+        let round1 = self.t4 - self.t1;
+        let reply1 = self.t3 - self.t2;
+        let round2 = self.t6 - self.t3;
+        let reply2 = self.t5 - self.t4;
+        (round1 * round2 - reply1 * reply2) / (round1 + round2 + reply1 + reply2)
+    }
+
+    /// Distance in meters
+    pub fn distance_m(&self) -> f64 {
+        self.tof() * SPEED_OF_LIGHT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doublet(len: usize) -> Vec<f64> {
+        let sigma = len as f64 / 6.0;
+        (0..len)
+            .map(|i| {
+                let t = (i as f64 - len as f64 / 2.0) / sigma;
+                (t * t - 1.0) * (-t * t / 2.0).exp()
+            })
+            .collect()
+    }
+
+    /// Doublet pulse starting at a fractional sample position
+    fn delayed_doublet(len: usize, total: usize, start: f64) -> Vec<IQSample> {
+        let sigma = len as f64 / 6.0;
+        (0..total)
+            .map(|n| {
+                let t = (n as f64 - start - len as f64 / 2.0) / sigma;
+                IQSample::new((t * t - 1.0) * (-t * t / 2.0).exp(), 0.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_subsample_toa() {
+        let detector = FirstPathDetector::new(&doublet(32), 1e9, FirstPathConfig::default());
+        for start in [100.0, 100.25, 100.5, 137.8] {
+            let toa = detector.detect(&delayed_doublet(32, 400, start)).unwrap();
+            assert!((toa.first_path - start).abs() < 0.15, "start {} got {}", start, toa.first_path);
+        }
+    }
+
+    #[test]
+    fn test_first_path_before_stronger_reflection() {
+        let detector = FirstPathDetector::new(&doublet(32), 1e9, FirstPathConfig::default());
+        let direct = delayed_doublet(32, 400, 120.0);
+        let reflection = delayed_doublet(32, 400, 170.0);
+        let samples: Vec<IQSample> = direct.iter().zip(&reflection).map(|(&d, &r)| d * 0.5 + r).collect();
+
+        let toa = detector.detect(&samples).unwrap();
+        assert_eq!(toa.peak_index, 170);
+        assert!((toa.first_path - 120.0).abs() < 0.5);
+        assert!((toa.first_to_peak() - 50.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_accumulated_detection() {
+        let detector = FirstPathDetector::new(&doublet(16), 1e9, FirstPathConfig::default());
+        let symbol = delayed_doublet(16, 200, 42.0);
+        let mut samples = Vec::new();
+        for _ in 0..8 {
+            samples.extend_from_slice(&symbol);
+        }
+        let toa = detector.detect_accumulated(&samples, 200, 8).unwrap();
+        assert!((toa.first_path - 42.0).abs() < 0.15);
+        assert!(detector.detect_accumulated(&samples, 200, 9).is_none());
+    }
+
+    #[test]
+    fn test_ss_twr_drift() {
+        let distance = 25.0;
+        let initiator = DeviceClock::with_drift(-10.0);
+        let responder = DeviceClock {
+            offset_s: 12.345,
+            drift_ppm: 10.0,
+        };
+        let exchange = SsTwrExchange::simulate(initiator, responder, distance, 1e-3, || 0.0);
+
+        // 20 ppm × 1 ms / 2 ≈ 3 m of bias without compensation
+        let uncompensated = exchange.tof() * SPEED_OF_LIGHT;
+        assert!((uncompensated - distance).abs() > 2.0);
+
+        // Residual error is the initiator's own drift times the ToF (sub-mm)
+        let relative = (1.0 + 10e-6) / (1.0 - 10e-6) * 1e6 - 1e6;
+        assert!((exchange.distance_m(relative) - distance).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_ds_twr_cancels_drift() {
+        let distance = 42.0;
+        let initiator = DeviceClock::with_drift(15.0);
+        let responder = DeviceClock {
+            offset_s: -3.0,
+            drift_ppm: -20.0,
+        };
+        // Asymmetric reply delays
+        let exchange = DsTwrExchange::simulate(initiator, responder, distance, 300e-6, 800e-6, || 0.0);
+        assert!((exchange.distance_m() - distance).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_clock_inverse() {
+        let clock = DeviceClock {
+            offset_s: 1.5,
+            drift_ppm: 40.0,
+        };
+        assert!((clock.true_time(clock.local(0.123)) - 0.123).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,782 @@
+//! IEEE 802.15.4 MAC Frame Codec
+//!
+//! ```text
+//! MPDU:
+//! │ FC (2) │ Seq (1) │ Dst PAN (0/2) │ Dst addr (0/2/8) │ Src PAN (0/2) │ Src addr (0/2/8) │ payload │ FCS (2) │
+//!
+//! Frame control (LSB first):
+//! │ type (3) │ sec │ pend │ AR │ PAN comp │ rsvd (3) │ dst mode (2) │ version (2) │ src mode (2) │
+//! ```
+//!
+//! Multi-byte fields are little-endian. The FCS is CRC-16/ITU-T
+//! (polynomial x^16 + x^12 + x^5 + 1, zero preset, reflected) over the
+//! MAC header and payload. Security-enabled frames are not supported.
+
+use std::fmt;
+
+/// Bytes in the frame check sequence
+pub const FCS_LEN: usize = 2;
+
+/// CRC-16/ITU-T frame check sequence
+pub fn crc16_itu(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &byte in data {
+        crc ^= byte as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0x8408 } else { crc >> 1 };
+        }
+    }
+    crc
+}
+
+/// MAC frame decoding errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    /// Frame ended before a required field
+    Truncated,
+    /// FCS mismatch
+    BadFcs {
+        /// FCS carried by the frame
+        received: u16,
+        /// FCS computed over the frame
+        computed: u16,
+    },
+    /// Reserved frame type
+    InvalidFrameType(u8),
+    /// Reserved addressing mode
+    InvalidAddressMode(u8),
+    /// Security-enabled frames are not supported
+    SecurityNotSupported,
+    /// PSDU longer than the 127-octet PHY limit
+    TooLong(usize),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Truncated => write!(f, "Frame truncated"),
+            FrameError::BadFcs { received, computed } => {
+                write!(f, "FCS mismatch: received {:04X}, computed {:04X}", received, computed)
+            }
+            FrameError::InvalidFrameType(t) => write!(f, "Invalid frame type {}", t),
+            FrameError::InvalidAddressMode(m) => write!(f, "Invalid addressing mode {}", m),
+            FrameError::SecurityNotSupported => write!(f, "Security-enabled frames not supported"),
+            FrameError::TooLong(len) => write!(f, "PSDU of {} octets exceeds the 127-octet limit", len),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// MAC frame type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameType {
+    /// Beacon
+    Beacon = 0,
+    /// Data
+    Data = 1,
+    /// Acknowledgment
+    Ack = 2,
+    /// MAC command
+    MacCommand = 3,
+}
+
+impl FrameType {
+    fn from_bits(value: u8) -> Result<Self, FrameError> {
+        match value {
+            0 => Ok(Self::Beacon),
+            1 => Ok(Self::Data),
+            2 => Ok(Self::Ack),
+            3 => Ok(Self::MacCommand),
+            other => Err(FrameError::InvalidFrameType(other)),
+        }
+    }
+}
+
+/// Device address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacAddress {
+    /// 16-bit short address
+    Short(u16),
+    /// 64-bit extended (EUI-64) address
+    Extended(u64),
+}
+
+impl MacAddress {
+    /// Broadcast short address
+    pub const BROADCAST: MacAddress = MacAddress::Short(0xFFFF);
+
+    /// Frame control addressing mode
+    fn mode(self) -> u8 {
+        match self {
+            Self::Short(_) => 2,
+            Self::Extended(_) => 3,
+        }
+    }
+
+    fn write(self, out: &mut Vec<u8>) {
+        match self {
+            Self::Short(addr) => out.extend_from_slice(&addr.to_le_bytes()),
+            Self::Extended(addr) => out.extend_from_slice(&addr.to_le_bytes()),
+        }
+    }
+}
+
+impl fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Short(addr) => write!(f, "0x{:04x}", addr),
+            Self::Extended(addr) => {
+                let bytes = addr.to_be_bytes();
+                for (i, b) in bytes.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ":")?;
+                    }
+                    write!(f, "{:02x}", b)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Decoded frame control field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameControl {
+    /// Frame type
+    pub frame_type: FrameType,
+    /// Security enabled
+    pub security_enabled: bool,
+    /// Frame pending
+    pub frame_pending: bool,
+    /// Acknowledgment request
+    pub ack_request: bool,
+    /// PAN ID compression (source PAN ID elided)
+    pub pan_id_compression: bool,
+    /// Destination addressing mode (0, 2 or 3)
+    pub dst_addr_mode: u8,
+    /// Frame version (0 = 2003, 1 = 2006)
+    pub frame_version: u8,
+    /// Source addressing mode (0, 2 or 3)
+    pub src_addr_mode: u8,
+}
+
+impl FrameControl {
+    /// Pack into the 16-bit field
+    pub fn to_u16(self) -> u16 {
+        (self.frame_type as u16)
+            | (self.security_enabled as u16) << 3
+            | (self.frame_pending as u16) << 4
+            | (self.ack_request as u16) << 5
+            | (self.pan_id_compression as u16) << 6
+            | (self.dst_addr_mode as u16 & 0x3) << 10
+            | (self.frame_version as u16 & 0x3) << 12
+            | (self.src_addr_mode as u16 & 0x3) << 14
+    }
+
+    /// Unpack the 16-bit field
+    pub fn from_u16(value: u16) -> Result<Self, FrameError> {
+        let dst_addr_mode = ((value >> 10) & 0x3) as u8;
+        let src_addr_mode = ((value >> 14) & 0x3) as u8;
+        for mode in [dst_addr_mode, src_addr_mode] {
+            if mode == 1 {
+                return Err(FrameError::InvalidAddressMode(mode));
+            }
+        }
+        Ok(Self {
+            frame_type: FrameType::from_bits((value & 0x7) as u8)?,
+            security_enabled: value & (1 << 3) != 0,
+            frame_pending: value & (1 << 4) != 0,
+            ack_request: value & (1 << 5) != 0,
+            pan_id_compression: value & (1 << 6) != 0,
+            dst_addr_mode,
+            frame_version: ((value >> 12) & 0x3) as u8,
+            src_addr_mode,
+        })
+    }
+}
+
+/// Beacon superframe specification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuperframeSpec {
+    /// Beacon order (15 = non-beacon network)
+    pub beacon_order: u8,
+    /// Superframe order
+    pub superframe_order: u8,
+    /// Final contention access period slot
+    pub final_cap_slot: u8,
+    /// Battery life extension
+    pub battery_life_extension: bool,
+    /// Sent by the PAN coordinator
+    pub pan_coordinator: bool,
+    /// Coordinator accepts association requests
+    pub association_permit: bool,
+}
+
+impl Default for SuperframeSpec {
+    /// Non-beacon-enabled PAN coordinator permitting association
+    fn default() -> Self {
+        Self {
+            beacon_order: 15,
+            superframe_order: 15,
+            final_cap_slot: 15,
+            battery_life_extension: false,
+            pan_coordinator: true,
+            association_permit: true,
+        }
+    }
+}
+
+impl SuperframeSpec {
+    /// Pack into the 16-bit field
+    pub fn to_u16(self) -> u16 {
+        (self.beacon_order as u16 & 0xF)
+            | (self.superframe_order as u16 & 0xF) << 4
+            | (self.final_cap_slot as u16 & 0xF) << 8
+            | (self.battery_life_extension as u16) << 12
+            | (self.pan_coordinator as u16) << 14
+            | (self.association_permit as u16) << 15
+    }
+
+    /// Unpack the 16-bit field
+    pub fn from_u16(value: u16) -> Self {
+        Self {
+            beacon_order: (value & 0xF) as u8,
+            superframe_order: ((value >> 4) & 0xF) as u8,
+            final_cap_slot: ((value >> 8) & 0xF) as u8,
+            battery_life_extension: value & (1 << 12) != 0,
+            pan_coordinator: value & (1 << 14) != 0,
+            association_permit: value & (1 << 15) != 0,
+        }
+    }
+}
+
+/// Guaranteed time slot allocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GtsDescriptor {
+    /// Device the GTS belongs to
+    pub short_address: u16,
+    /// First superframe slot (0-15)
+    pub starting_slot: u8,
+    /// Number of slots (0-15)
+    pub length: u8,
+    /// GTS is for reception by the device (direction bit set)
+    pub receive: bool,
+}
+
+/// Beacon frame payload
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Beacon {
+    /// Superframe specification
+    pub superframe: SuperframeSpec,
+    /// Coordinator accepts GTS requests
+    pub gts_permit: bool,
+    /// GTS allocations (at most 7)
+    pub gts: Vec<GtsDescriptor>,
+    /// Short addresses with pending data (at most 7 addresses in total)
+    pub pending_short: Vec<u16>,
+    /// Extended addresses with pending data
+    pub pending_extended: Vec<u64>,
+    /// Beacon payload (e.g. Zigbee NWK beacon)
+    pub payload: Vec<u8>,
+}
+
+impl Beacon {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.superframe.to_u16().to_le_bytes());
+
+        let gts_count = self.gts.len().min(7);
+        out.push(gts_count as u8 | (self.gts_permit as u8) << 7);
+        if gts_count > 0 {
+            let directions = self.gts[..gts_count]
+                .iter()
+                .enumerate()
+                .fold(0u8, |acc, (i, g)| acc | (g.receive as u8) << i);
+            out.push(directions);
+            for g in &self.gts[..gts_count] {
+                out.extend_from_slice(&g.short_address.to_le_bytes());
+                out.push((g.starting_slot & 0xF) | (g.length & 0xF) << 4);
+            }
+        }
+
+        let short_count = self.pending_short.len().min(7);
+        let extended_count = self.pending_extended.len().min(7 - short_count);
+        out.push(short_count as u8 | (extended_count as u8) << 4);
+        for addr in &self.pending_short[..short_count] {
+            out.extend_from_slice(&addr.to_le_bytes());
+        }
+        for addr in &self.pending_extended[..extended_count] {
+            out.extend_from_slice(&addr.to_le_bytes());
+        }
+
+        out.extend_from_slice(&self.payload);
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, FrameError> {
+        let superframe = SuperframeSpec::from_u16(r.u16()?);
+
+        let gts_spec = r.u8()?;
+        let gts_count = (gts_spec & 0x7) as usize;
+        let mut gts = Vec::with_capacity(gts_count);
+        if gts_count > 0 {
+            let directions = r.u8()?;
+            for i in 0..gts_count {
+                let short_address = r.u16()?;
+                let slots = r.u8()?;
+                gts.push(GtsDescriptor {
+                    short_address,
+                    starting_slot: slots & 0xF,
+                    length: slots >> 4,
+                    receive: directions & (1 << i) != 0,
+                });
+            }
+        }
+
+        let pending_spec = r.u8()?;
+        let pending_short = (0..pending_spec & 0x7)
+            .map(|_| r.u16())
+            .collect::<Result<_, _>>()?;
+        let pending_extended = (0..(pending_spec >> 4) & 0x7)
+            .map(|_| r.u64())
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            superframe,
+            gts_permit: gts_spec & 0x80 != 0,
+            gts,
+            pending_short,
+            pending_extended,
+            payload: r.rest().to_vec(),
+        })
+    }
+}
+
+/// MAC command frame identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacCommand {
+    /// Association request
+    AssociationRequest,
+    /// Association response
+    AssociationResponse,
+    /// Disassociation notification
+    DisassociationNotification,
+    /// Data request (poll)
+    DataRequest,
+    /// PAN ID conflict notification
+    PanIdConflict,
+    /// Orphan notification
+    OrphanNotification,
+    /// Beacon request
+    BeaconRequest,
+    /// Coordinator realignment
+    CoordinatorRealignment,
+    /// GTS request
+    GtsRequest,
+    /// Reserved or vendor-specific identifier
+    Other(u8),
+}
+
+impl MacCommand {
+    /// Command frame identifier
+    pub fn id(self) -> u8 {
+        match self {
+            Self::AssociationRequest => 0x01,
+            Self::AssociationResponse => 0x02,
+            Self::DisassociationNotification => 0x03,
+            Self::DataRequest => 0x04,
+            Self::PanIdConflict => 0x05,
+            Self::OrphanNotification => 0x06,
+            Self::BeaconRequest => 0x07,
+            Self::CoordinatorRealignment => 0x08,
+            Self::GtsRequest => 0x09,
+            Self::Other(id) => id,
+        }
+    }
+
+    /// Command from its frame identifier
+    pub fn from_id(id: u8) -> Self {
+        match id {
+            0x01 => Self::AssociationRequest,
+            0x02 => Self::AssociationResponse,
+            0x03 => Self::DisassociationNotification,
+            0x04 => Self::DataRequest,
+            0x05 => Self::PanIdConflict,
+            0x06 => Self::OrphanNotification,
+            0x07 => Self::BeaconRequest,
+            0x08 => Self::CoordinatorRealignment,
+            0x09 => Self::GtsRequest,
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// Frame-type specific contents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacPayload {
+    /// Beacon frame
+    Beacon(Beacon),
+    /// Data frame payload (e.g. Zigbee NWK frame)
+    Data(Vec<u8>),
+    /// Acknowledgment (no payload)
+    Ack,
+    /// MAC command and its parameters
+    Command {
+        /// Command identifier
+        command: MacCommand,
+        /// Command parameters
+        payload: Vec<u8>,
+    },
+}
+
+impl MacPayload {
+    /// Frame type carrying this payload
+    pub fn frame_type(&self) -> FrameType {
+        match self {
+            Self::Beacon(_) => FrameType::Beacon,
+            Self::Data(_) => FrameType::Data,
+            Self::Ack => FrameType::Ack,
+            Self::Command { .. } => FrameType::MacCommand,
+        }
+    }
+}
+
+/// IEEE 802.15.4 MAC frame
+///
+/// Addressing modes and PAN ID compression are derived from which address
+/// fields are present: leaving `src_pan_id` as `None` while both addresses
+/// are set produces an intra-PAN frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacFrame {
+    /// Sequence number (DSN or BSN)
+    pub sequence_number: u8,
+    /// Frame pending flag
+    pub frame_pending: bool,
+    /// Acknowledgment request flag
+    pub ack_request: bool,
+    /// Frame version (0 = 2003, 1 = 2006)
+    pub frame_version: u8,
+    /// Destination PAN identifier
+    pub dst_pan_id: Option<u16>,
+    /// Destination address
+    pub dst_addr: Option<MacAddress>,
+    /// Source PAN identifier (absent when compressed into `dst_pan_id`)
+    pub src_pan_id: Option<u16>,
+    /// Source address
+    pub src_addr: Option<MacAddress>,
+    /// Frame contents
+    pub payload: MacPayload,
+}
+
+impl MacFrame {
+    /// Beacon from a coordinator
+    pub fn beacon(sequence_number: u8, pan_id: u16, src: MacAddress, beacon: Beacon) -> Self {
+        Self {
+            sequence_number,
+            frame_pending: false,
+            ack_request: false,
+            frame_version: 0,
+            dst_pan_id: None,
+            dst_addr: None,
+            src_pan_id: Some(pan_id),
+            src_addr: Some(src),
+            payload: MacPayload::Beacon(beacon),
+        }
+    }
+
+    /// Intra-PAN data frame; unicast frames request an acknowledgment
+    pub fn data(sequence_number: u8, pan_id: u16, dst: MacAddress, src: MacAddress, payload: &[u8]) -> Self {
+        Self {
+            sequence_number,
+            frame_pending: false,
+            ack_request: dst != MacAddress::BROADCAST,
+            frame_version: 0,
+            dst_pan_id: Some(pan_id),
+            dst_addr: Some(dst),
+            src_pan_id: None,
+            src_addr: Some(src),
+            payload: MacPayload::Data(payload.to_vec()),
+        }
+    }
+
+    /// Acknowledgment of the frame with `sequence_number`
+    pub fn ack(sequence_number: u8, frame_pending: bool) -> Self {
+        Self {
+            sequence_number,
+            frame_pending,
+            ack_request: false,
+            frame_version: 0,
+            dst_pan_id: None,
+            dst_addr: None,
+            src_pan_id: None,
+            src_addr: None,
+            payload: MacPayload::Ack,
+        }
+    }
+
+    /// Intra-PAN MAC command frame
+    pub fn command(
+        sequence_number: u8,
+        pan_id: u16,
+        dst: MacAddress,
+        src: Option<MacAddress>,
+        command: MacCommand,
+        payload: &[u8],
+    ) -> Self {
+        Self {
+            sequence_number,
+            frame_pending: false,
+            ack_request: dst != MacAddress::BROADCAST && src.is_some(),
+            frame_version: 0,
+            dst_pan_id: Some(pan_id),
+            dst_addr: Some(dst),
+            src_pan_id: None,
+            src_addr: src,
+            payload: MacPayload::Command {
+                command,
+                payload: payload.to_vec(),
+            },
+        }
+    }
+
+    /// Frame type
+    pub fn frame_type(&self) -> FrameType {
+        self.payload.frame_type()
+    }
+
+    /// Frame control field implied by this frame's contents
+    pub fn frame_control(&self) -> FrameControl {
+        FrameControl {
+            frame_type: self.frame_type(),
+            security_enabled: false,
+            frame_pending: self.frame_pending,
+            ack_request: self.ack_request,
+            pan_id_compression: self.dst_addr.is_some()
+                && self.src_addr.is_some()
+                && self.src_pan_id.is_none(),
+            dst_addr_mode: self.dst_addr.map_or(0, MacAddress::mode),
+            frame_version: self.frame_version,
+            src_addr_mode: self.src_addr.map_or(0, MacAddress::mode),
+        }
+    }
+
+    /// Encode to an MPDU, including FCS
+    pub fn encode(&self) -> Vec<u8> {
+        let fc = self.frame_control();
+        let mut out = Vec::with_capacity(32);
+        out.extend_from_slice(&fc.to_u16().to_le_bytes());
+        out.push(self.sequence_number);
+
+        if let Some(addr) = self.dst_addr {
+            out.extend_from_slice(&self.dst_pan_id.unwrap_or(0xFFFF).to_le_bytes());
+            addr.write(&mut out);
+        }
+        if let Some(addr) = self.src_addr {
+            if !fc.pan_id_compression {
+                out.extend_from_slice(&self.src_pan_id.unwrap_or(0xFFFF).to_le_bytes());
+            }
+            addr.write(&mut out);
+        }
+
+        match &self.payload {
+            MacPayload::Beacon(beacon) => beacon.write(&mut out),
+            MacPayload::Data(data) => out.extend_from_slice(data),
+            MacPayload::Ack => {}
+            MacPayload::Command { command, payload } => {
+                out.push(command.id());
+                out.extend_from_slice(payload);
+            }
+        }
+
+        let fcs = crc16_itu(&out);
+        out.extend_from_slice(&fcs.to_le_bytes());
+        out
+    }
+
+    /// Decode an MPDU, verifying its FCS
+    pub fn decode(mpdu: &[u8]) -> Result<Self, FrameError> {
+        if mpdu.len() < 3 + FCS_LEN {
+            return Err(FrameError::Truncated);
+        }
+        let (body, fcs) = mpdu.split_at(mpdu.len() - FCS_LEN);
+        let received = u16::from_le_bytes([fcs[0], fcs[1]]);
+        let computed = crc16_itu(body);
+        if received != computed {
+            return Err(FrameError::BadFcs { received, computed });
+        }
+
+        let mut r = Reader { data: body, pos: 0 };
+        let fc = FrameControl::from_u16(r.u16()?)?;
+        if fc.security_enabled {
+            return Err(FrameError::SecurityNotSupported);
+        }
+        let sequence_number = r.u8()?;
+
+        let (dst_pan_id, dst_addr) = match fc.dst_addr_mode {
+            0 => (None, None),
+            mode => (Some(r.u16()?), Some(r.address(mode)?)),
+        };
+        let (src_pan_id, src_addr) = match fc.src_addr_mode {
+            0 => (None, None),
+            mode => {
+                let pan = if fc.pan_id_compression { None } else { Some(r.u16()?) };
+                (pan, Some(r.address(mode)?))
+            }
+        };
+
+        let payload = match fc.frame_type {
+            FrameType::Beacon => MacPayload::Beacon(Beacon::read(&mut r)?),
+            FrameType::Data => MacPayload::Data(r.rest().to_vec()),
+            FrameType::Ack => MacPayload::Ack,
+            FrameType::MacCommand => MacPayload::Command {
+                command: MacCommand::from_id(r.u8()?),
+                payload: r.rest().to_vec(),
+            },
+        };
+
+        Ok(Self {
+            sequence_number,
+            frame_pending: fc.frame_pending,
+            ack_request: fc.ack_request,
+            frame_version: fc.frame_version,
+            dst_pan_id,
+            dst_addr,
+            src_pan_id,
+            src_addr,
+            payload,
+        })
+    }
+}
+
+/// Little-endian field reader
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], FrameError> {
+        let bytes = self.data.get(self.pos..self.pos + n).ok_or(FrameError::Truncated)?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, FrameError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, FrameError> {
+        let b = self.take(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u64(&mut self) -> Result<u64, FrameError> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn address(&mut self, mode: u8) -> Result<MacAddress, FrameError> {
+        match mode {
+            2 => Ok(MacAddress::Short(self.u16()?)),
+            3 => Ok(MacAddress::Extended(self.u64()?)),
+            other => Err(FrameError::InvalidAddressMode(other)),
+        }
+    }
+
+    fn rest(&mut self) -> &[u8] {
+        let rest = &self.data[self.pos..];
+        self.pos = self.data.len();
+        rest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc16_itu_check_value() {
+        assert_eq!(crc16_itu(b"123456789"), 0x2189);
+    }
+
+    #[test]
+    fn test_ack_frame_bytes() {
+        // Imm-Ack for DSN 0x56: FC 0x0002, FCS over [02 00 56]
+        let ack = MacFrame::ack(0x56, false).encode();
+        assert_eq!(&ack[..3], &[0x02, 0x00, 0x56]);
+        assert_eq!(ack.len(), 5);
+        assert_eq!(MacFrame::decode(&ack).unwrap(), MacFrame::ack(0x56, false));
+    }
+
+    #[test]
+    fn test_data_frame_roundtrip() {
+        let frame = MacFrame::data(7, 0x1A62, MacAddress::Short(0x0000), MacAddress::Short(0x796F), b"hello");
+        let mpdu = frame.encode();
+        // FC: data, AR, PAN compression, short/short
+        assert_eq!(&mpdu[..2], &[0x61, 0x88]);
+        assert_eq!(MacFrame::decode(&mpdu).unwrap(), frame);
+
+        let mut extended = MacFrame::data(
+            8,
+            0xABCD,
+            MacAddress::Extended(0x0011_2233_4455_6677),
+            MacAddress::Extended(0x8899_AABB_CCDD_EEFF),
+            &[1, 2, 3],
+        );
+        extended.src_pan_id = Some(0x1234);
+        extended.frame_version = 1;
+        let decoded = MacFrame::decode(&extended.encode()).unwrap();
+        assert!(!decoded.frame_control().pan_id_compression);
+        assert_eq!(decoded, extended);
+    }
+
+    #[test]
+    fn test_beacon_roundtrip() {
+        let beacon = Beacon {
+            gts_permit: true,
+            gts: vec![GtsDescriptor {
+                short_address: 0x1234,
+                starting_slot: 12,
+                length: 3,
+                receive: true,
+            }],
+            pending_short: vec![0x0001, 0x0002],
+            pending_extended: vec![0xDEAD_BEEF_0000_0001],
+            payload: vec![0x00, 0x22, 0x84],
+            ..Default::default()
+        };
+        let frame = MacFrame::beacon(42, 0x1A62, MacAddress::Short(0x0000), beacon);
+        assert_eq!(MacFrame::decode(&frame.encode()).unwrap(), frame);
+    }
+
+    #[test]
+    fn test_command_roundtrip() {
+        let frame = MacFrame::command(
+            3,
+            0xFFFF,
+            MacAddress::BROADCAST,
+            None,
+            MacCommand::BeaconRequest,
+            &[],
+        );
+        let mpdu = frame.encode();
+        assert_eq!(&mpdu[..2], &[0x03, 0x08]);
+        let decoded = MacFrame::decode(&mpdu).unwrap();
+        assert_eq!(decoded.frame_type(), FrameType::MacCommand);
+        assert_eq!(decoded, frame);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut mpdu = MacFrame::ack(1, true).encode();
+        mpdu[2] ^= 0xFF;
+        assert!(matches!(MacFrame::decode(&mpdu), Err(FrameError::BadFcs { .. })));
+        assert_eq!(MacFrame::decode(&[0x02, 0x00]), Err(FrameError::Truncated));
+
+        // Data frame claiming a short destination with no room for it
+        let mut body = vec![0x01, 0x08, 0x00];
+        let fcs = crc16_itu(&body);
+        body.extend_from_slice(&fcs.to_le_bytes());
+        assert_eq!(MacFrame::decode(&body), Err(FrameError::Truncated));
+    }
+}
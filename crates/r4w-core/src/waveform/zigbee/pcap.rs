@@ -0,0 +1,67 @@
+//! PCAP Output for 802.15.4 Frames
+//!
+//! Writes classic libpcap files with link type
+//! `LINKTYPE_IEEE802_15_4_WITHFCS` (195), so frames decoded by r4w can be
+//! opened directly in Wireshark.
+
+use std::io::{self, Write};
+
+/// IEEE 802.15.4 MPDU including FCS
+pub const LINKTYPE_IEEE802_15_4_WITHFCS: u32 = 195;
+
+/// libpcap file writer
+pub struct PcapWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> PcapWriter<W> {
+    /// Write the global header and return the writer
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        inner.write_all(&0xA1B2_C3D4u32.to_le_bytes())?; // magic, microseconds
+        inner.write_all(&2u16.to_le_bytes())?; // version major
+        inner.write_all(&4u16.to_le_bytes())?; // version minor
+        inner.write_all(&0i32.to_le_bytes())?; // thiszone
+        inner.write_all(&0u32.to_le_bytes())?; // sigfigs
+        inner.write_all(&65535u32.to_le_bytes())?; // snaplen
+        inner.write_all(&LINKTYPE_IEEE802_15_4_WITHFCS.to_le_bytes())?;
+        Ok(Self { inner })
+    }
+
+    /// Append one MPDU (with FCS) captured at `timestamp` seconds
+    pub fn write_frame(&mut self, timestamp: f64, mpdu: &[u8]) -> io::Result<()> {
+        let timestamp = timestamp.max(0.0);
+        let seconds = timestamp.floor();
+        let micros = ((timestamp - seconds) * 1e6).round().min(999_999.0);
+        self.inner.write_all(&(seconds as u32).to_le_bytes())?;
+        self.inner.write_all(&(micros as u32).to_le_bytes())?;
+        self.inner.write_all(&(mpdu.len() as u32).to_le_bytes())?;
+        self.inner.write_all(&(mpdu.len() as u32).to_le_bytes())?;
+        self.inner.write_all(mpdu)
+    }
+
+    /// Flush and return the underlying writer
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pcap_layout() {
+        let mut writer = PcapWriter::new(Vec::new()).unwrap();
+        writer.write_frame(1.25, &[0x02, 0x00, 0x56, 0xAA, 0xBB]).unwrap();
+        let bytes = writer.into_inner().unwrap();
+
+        assert_eq!(bytes.len(), 24 + 16 + 5);
+        assert_eq!(&bytes[..4], &[0xD4, 0xC3, 0xB2, 0xA1]);
+        assert_eq!(u32::from_le_bytes(bytes[20..24].try_into().unwrap()), 195);
+        assert_eq!(u32::from_le_bytes(bytes[24..28].try_into().unwrap()), 1);
+        assert_eq!(u32::from_le_bytes(bytes[28..32].try_into().unwrap()), 250_000);
+        assert_eq!(u32::from_le_bytes(bytes[32..36].try_into().unwrap()), 5);
+        assert_eq!(&bytes[40..], &[0x02, 0x00, 0x56, 0xAA, 0xBB]);
+    }
+}
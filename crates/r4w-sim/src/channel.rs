@@ -13,6 +13,10 @@
 //! 4. **Multipath**: Multiple signal copies arriving at different times
 //! 5. **Frequency Offset**: Carrier frequency mismatch
 //! 6. **Timing Drift**: Clock differences between TX and RX
+//! 7. **Hardware Impairments**: PA nonlinearity, phase noise, IQ imbalance,
+//!    DC offset and ADC quantisation (see [`crate::impairments`])
+//! 8. **Interference**: CW, swept, partial-band, pulsed, follower and
+//!    co-channel jammers (see [`crate::interference`])
 //!
 //! ## Usage
 //!
@@ -34,6 +38,9 @@
 //! ```
 
 use crate::doppler::{DopplerGenerator, DopplerModel, JakesDoppler};
+use crate::impairments::{ImpairmentConfig, Impairments};
+use crate::interference::{Interference, InterfererConfig};
+use r4w_core::propagation::{thermal_noise_dbm, LinkBudget, PathLossModel};
 use r4w_core::types::{Complex, IQSample};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
@@ -256,6 +263,21 @@ impl TappedDelayLine {
         }
     }
 
+    /// Re-seed the per-tap Doppler generators for reproducible fading
+    pub fn reseed(&mut self, seed: u64) {
+        let sample_rate = self.sample_rate;
+        for (i, generator) in self.doppler_generators.iter_mut().enumerate() {
+            if let Some(doppler) = generator {
+                *doppler = JakesDoppler::with_seed(
+                    doppler.max_doppler_hz(),
+                    sample_rate,
+                    16,
+                    seed.wrapping_add(i as u64),
+                );
+            }
+        }
+    }
+
     /// Reset all delay buffers
     pub fn reset(&mut self) {
         for buffer in &mut self.delay_buffers {
@@ -383,6 +405,16 @@ pub struct ChannelConfig {
     /// TDL channel profile
     #[serde(default)]
     pub tdl_profile: TdlProfile,
+
+    // === Front-end impairments ===
+    /// Transceiver hardware impairments (PA, phase noise, IQ, DC, ADC)
+    #[serde(default)]
+    pub impairments: ImpairmentConfig,
+
+    // === Interference ===
+    /// Jammers and co-channel interferers added at the receiver
+    #[serde(default)]
+    pub interference: Vec<InterfererConfig>,
 }
 
 fn default_carrier_freq() -> f64 {
@@ -434,6 +466,8 @@ impl Default for ChannelConfig {
             doppler_model: DopplerModelConfig::default(),
             tdl_enabled: false,
             tdl_profile: TdlProfile::default(),
+            impairments: ImpairmentConfig::default(),
+            interference: Vec::new(),
         }
     }
 }
@@ -512,6 +546,18 @@ impl ChannelConfig {
         }
     }
 
+    /// Set the SNR from a link budget and a path loss model at a distance
+    ///
+    /// Uses `carrier_frequency_hz` for the path loss. The SNR is referred to
+    /// the noise in the full `sample_rate` bandwidth, which is what the AWGN
+    /// model adds; `path_loss_db` is left unchanged.
+    pub fn with_link_budget(mut self, budget: &LinkBudget, model: &PathLossModel, distance_m: f64) -> Self {
+        let path_loss_db = model.path_loss_db(distance_m, self.carrier_frequency_hz);
+        let report = budget.evaluate(path_loss_db);
+        self.snr_db = report.rx_power_dbm - thermal_noise_dbm(self.sample_rate, budget.noise_figure_db);
+        self
+    }
+
     /// Get effective Doppler frequency (from velocity if specified)
     pub fn effective_doppler_hz(&self) -> f64 {
         if self.max_doppler_hz > 0.0 {
@@ -539,6 +585,10 @@ pub struct Channel {
     tdl: Option<TappedDelayLine>,
     /// Doppler generator for flat fading (optional)
     doppler: Option<DopplerGenerator>,
+    /// Front-end impairments (optional)
+    impairments: Option<Impairments>,
+    /// Interference sources (optional)
+    interference: Option<Interference>,
 }
 
 impl Channel {
@@ -582,6 +632,18 @@ impl Channel {
             None
         };
 
+        let impairments = if config.impairments.is_empty() {
+            None
+        } else {
+            Some(Impairments::new(config.impairments.clone(), config.sample_rate))
+        };
+
+        let interference = if config.interference.is_empty() {
+            None
+        } else {
+            Some(Interference::new(config.interference.clone(), config.sample_rate))
+        };
+
         Self {
             config,
             rng: StdRng::from_entropy(),
@@ -590,9 +652,47 @@ impl Channel {
             multipath_buffer,
             tdl,
             doppler,
+            impairments,
+            interference,
         }
     }
 
+    /// Create a channel whose random processes are reproducible
+    ///
+    /// The seed covers AWGN, flat fading, the Doppler generators of
+    /// the fading and TDL models, impairments and interference.
+    pub fn with_seed(config: ChannelConfig, seed: u64) -> Self {
+        let mut channel = Self::new(config);
+        channel.rng = StdRng::seed_from_u64(seed);
+        if let Some(ref mut tdl) = channel.tdl {
+            tdl.reseed(seed.wrapping_add(1));
+        }
+        if channel.doppler.is_some() {
+            channel.doppler = Some(DopplerGenerator::with_seed(
+                channel.config.doppler_model.into(),
+                channel.config.effective_doppler_hz(),
+                channel.config.sample_rate,
+                16,
+                seed.wrapping_add(1),
+            ));
+        }
+        if channel.impairments.is_some() {
+            channel.impairments = Some(Impairments::with_seed(
+                channel.config.impairments.clone(),
+                channel.config.sample_rate,
+                seed.wrapping_add(2),
+            ));
+        }
+        if channel.interference.is_some() {
+            channel.interference = Some(Interference::with_seed(
+                channel.config.interference.clone(),
+                channel.config.sample_rate,
+                seed.wrapping_add(3),
+            ));
+        }
+        channel
+    }
+
     /// Reset channel state
     pub fn reset(&mut self) {
         self.cfo_phase = 0.0;
@@ -615,7 +715,41 @@ impl Channel {
     }
 
     /// Apply channel effects to samples
+    ///
+    /// Transmit impairments (PA) are applied before propagation and
+    /// receive impairments (phase noise, IQ imbalance, DC offset, ADC)
+    /// after the noise is added. Interference is added to the received
+    /// signal, with its power referenced to the transmitted block.
     pub fn apply(&mut self, samples: &[IQSample]) -> Vec<IQSample> {
+        let mut impairments = self.impairments.take();
+        let tx = match impairments {
+            Some(ref mut imp) => imp.apply_tx(samples),
+            None => samples.to_vec(),
+        };
+        let mut rx = self.apply_propagation(&tx);
+        if let Some(ref mut interference) = self.interference {
+            interference.apply(&tx, &mut rx);
+        }
+        let out = match impairments {
+            Some(ref mut imp) => imp.apply_rx(&rx),
+            None => rx,
+        };
+        self.impairments = impairments;
+        out
+    }
+
+    /// Front-end impairment state, if any are configured
+    pub fn impairments(&self) -> Option<&Impairments> {
+        self.impairments.as_ref()
+    }
+
+    /// Interference state, if any interferers are configured
+    pub fn interference(&self) -> Option<&Interference> {
+        self.interference.as_ref()
+    }
+
+    /// Apply the propagation model and noise
+    fn apply_propagation(&mut self, samples: &[IQSample]) -> Vec<IQSample> {
         match self.config.model {
             ChannelModel::Ideal => samples.to_vec(),
             ChannelModel::Awgn => self.apply_awgn(samples),
@@ -904,4 +1038,20 @@ mod tests {
         // Ideal channel should pass through unchanged
         assert_eq!(samples, output);
     }
+
+    #[test]
+    fn test_snr_from_link_budget() {
+        let budget = LinkBudget::new(10.0, 125_000.0, 6.0, 0.0);
+        let base = ChannelConfig {
+            sample_rate: 125_000.0,
+            carrier_frequency_hz: 915e6,
+            ..Default::default()
+        };
+        let near = base.clone().with_link_budget(&budget, &PathLossModel::FreeSpace, 1_000.0);
+        let far = base.with_link_budget(&budget, &PathLossModel::FreeSpace, 10_000.0);
+
+        // 10 dBm - 91.7 dB FSPL against a -117 dBm noise floor
+        assert!((near.snr_db - 35.3).abs() < 0.1, "snr {}", near.snr_db);
+        assert!((near.snr_db - far.snr_db - 20.0).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,628 @@
+//! Synthetic RF Datasets
+//!
+//! Labeled I/Q examples for training classifiers, in the spirit of the
+//! RadioML datasets. Every waveform in the sweep is modulated from random
+//! payloads, given a random carrier offset, passed through a fading
+//! channel with front-end impairments and AWGN, and cropped at a random
+//! offset to a fixed length.
+//!
+//! The sweep is every waveform × every SNR × `examples_per_point`. Each
+//! example draws from its own RNG, seeded from the dataset seed and the
+//! example index, so a dataset is bit-for-bit reproducible regardless of
+//! how many threads generate it (with the `parallel` feature, examples
+//! are generated on the rayon pool; `RAYON_NUM_THREADS` limits it).
+//!
+//! Two output formats, both with a `manifest.json` describing the sweep
+//! and every example:
+//!
+//! - **NPY**: `X.npy` (`float32`, shape `[N, 2, L]`, I then Q),
+//!   `y.npy` (`int64` class indices) and `snr.npy` (`float32` dB), ready
+//!   for `numpy.load` or conversion to HDF5.
+//! - **SigMF**: one `dataset.sigmf-meta`/`.sigmf-data` recording of the
+//!   examples back to back, each with an annotation carrying its label,
+//!   SNR, CFO, fading and seed.
+//!
+//! ```rust
+//! use r4w_sim::dataset::{DatasetConfig, DatasetGenerator};
+//!
+//! let config = DatasetConfig {
+//!     waveforms: vec!["BPSK".into(), "QPSK".into()],
+//!     snr_db: vec![0.0, 10.0],
+//!     examples_per_point: 2,
+//!     example_len: 256,
+//!     ..Default::default()
+//! };
+//! let generator = DatasetGenerator::new(config).unwrap();
+//! assert_eq!(generator.len(), 8);
+//!
+//! let (samples, info) = generator.example(5);
+//! assert_eq!(samples.len(), 256);
+//! assert_eq!(info.label, "QPSK");
+//! ```
+
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+use std::path::Path;
+
+use r4w_core::types::IQSample;
+use r4w_core::waveform::{Waveform, WaveformFactory};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::channel::{Channel, ChannelConfig, ChannelModel, TdlProfile};
+use crate::hal::sigmf::{annotate_waveform, SigMfWriter};
+use crate::impairments::ImpairmentConfig;
+
+/// Examples generated (and held in memory) at a time while writing
+const CHUNK: usize = 512;
+
+/// Fading applied to an example before noise
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Fading {
+    /// AWGN only
+    None,
+    /// Flat Rayleigh fading with a Jakes Doppler spectrum
+    Rayleigh {
+        /// Maximum Doppler frequency in Hz
+        max_doppler_hz: f64,
+    },
+    /// Frequency-selective 3GPP tapped delay line
+    Tdl {
+        /// Delay profile (EPA, EVA, ETU)
+        profile: TdlProfile,
+        /// Maximum Doppler frequency in Hz (0 for static taps)
+        max_doppler_hz: f64,
+    },
+}
+
+impl Fading {
+    /// Parse `none`, `rayleigh`, `epa`, `eva` or `etu`.
+    pub fn parse(name: &str, max_doppler_hz: f64) -> Option<Self> {
+        let tdl = |profile| Some(Self::Tdl { profile, max_doppler_hz });
+        match name.trim().to_lowercase().as_str() {
+            "none" | "awgn" => Some(Self::None),
+            "rayleigh" | "jakes" => Some(Self::Rayleigh { max_doppler_hz }),
+            "epa" => tdl(TdlProfile::Epa),
+            "eva" => tdl(TdlProfile::Eva),
+            "etu" => tdl(TdlProfile::Etu),
+            _ => None,
+        }
+    }
+
+    /// Short name used in labels and the manifest
+    pub fn name(&self) -> String {
+        match self {
+            Self::None => "none".to_string(),
+            Self::Rayleigh { .. } => "rayleigh".to_string(),
+            Self::Tdl { profile, .. } => format!("{:?}", profile).to_lowercase(),
+        }
+    }
+
+    fn channel_config(&self, snr_db: f64, sample_rate: f64, impairments: ImpairmentConfig) -> ChannelConfig {
+        let base = ChannelConfig {
+            snr_db,
+            sample_rate,
+            impairments,
+            ..Default::default()
+        };
+        match *self {
+            Self::None => ChannelConfig { model: ChannelModel::Awgn, ..base },
+            Self::Rayleigh { max_doppler_hz } => ChannelConfig {
+                model: ChannelModel::JakesFading,
+                doppler_enabled: true,
+                max_doppler_hz,
+                ..base
+            },
+            Self::Tdl { profile, max_doppler_hz } => ChannelConfig {
+                model: if max_doppler_hz > 0.0 {
+                    ChannelModel::FrequencySelective
+                } else {
+                    ChannelModel::TdlAwgn
+                },
+                tdl_enabled: true,
+                tdl_profile: profile,
+                doppler_enabled: max_doppler_hz > 0.0,
+                max_doppler_hz,
+                ..base
+            },
+        }
+    }
+}
+
+/// What to generate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatasetConfig {
+    /// Waveform names (the classes), as accepted by [`WaveformFactory::create`]
+    pub waveforms: Vec<String>,
+    /// Sample rate in Hz
+    pub sample_rate: f64,
+    /// Samples per example
+    pub example_len: usize,
+    /// Examples per waveform and SNR
+    pub examples_per_point: usize,
+    /// SNR values in dB
+    pub snr_db: Vec<f64>,
+    /// Carrier offset, uniform in +/- this many Hz
+    pub max_cfo_hz: f64,
+    /// Fading models, one picked at random per example
+    pub fading: Vec<Fading>,
+    /// Impairment sets, one picked at random per example (none if empty)
+    pub impairments: Vec<ImpairmentConfig>,
+    /// Scale every example to unit average power
+    pub normalize: bool,
+    /// Dataset seed
+    pub seed: u64,
+}
+
+impl Default for DatasetConfig {
+    fn default() -> Self {
+        Self {
+            waveforms: ["BPSK", "QPSK", "8PSK", "16QAM", "64QAM", "BFSK", "4FSK", "OOK", "ASK", "4ASK"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            sample_rate: 48_000.0,
+            example_len: 1024,
+            examples_per_point: 100,
+            snr_db: (-5..=10).map(|i| i as f64 * 2.0).collect(),
+            max_cfo_hz: 0.0,
+            fading: vec![Fading::None],
+            impairments: Vec::new(),
+            normalize: true,
+            seed: 0,
+        }
+    }
+}
+
+/// Output file layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatasetFormat {
+    /// `X.npy`, `y.npy` and `snr.npy` tensors
+    Npy,
+    /// One annotated SigMF recording
+    SigMf,
+}
+
+impl DatasetFormat {
+    /// Parse `npy` or `sigmf`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "npy" | "numpy" => Some(Self::Npy),
+            "sigmf" => Some(Self::SigMf),
+            _ => None,
+        }
+    }
+}
+
+/// Parameters of one example
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExampleInfo {
+    /// Position in the dataset
+    pub index: usize,
+    /// Class index into [`DatasetConfig::waveforms`]
+    pub class: usize,
+    /// Waveform name
+    pub label: String,
+    /// SNR in dB
+    pub snr_db: f64,
+    /// Carrier frequency offset in Hz
+    pub cfo_hz: f64,
+    /// Fading model name
+    pub fading: String,
+    /// Index into [`DatasetConfig::impairments`], if any were applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impairments: Option<usize>,
+    /// Seed of this example's RNG
+    pub seed: u64,
+    /// First sample in the SigMF recording
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_start: Option<u64>,
+}
+
+/// `manifest.json`: the sweep, the files and every example
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetManifest {
+    /// Output format
+    pub format: DatasetFormat,
+    /// Class names, indexed by label
+    pub classes: Vec<String>,
+    /// Number of examples
+    pub num_examples: usize,
+    /// Samples per example
+    pub example_len: usize,
+    /// Role to file name, e.g. `x` -> `X.npy`
+    pub files: BTreeMap<String, String>,
+    /// Configuration that generated the dataset
+    pub config: DatasetConfig,
+    /// Per-example parameters, in dataset order
+    pub examples: Vec<ExampleInfo>,
+}
+
+impl DatasetManifest {
+    /// Load a `manifest.json`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        serde_json::from_reader(io::BufReader::new(file)).map_err(io::Error::other)
+    }
+}
+
+/// Seeded, parallel dataset generator
+#[derive(Debug)]
+pub struct DatasetGenerator {
+    config: DatasetConfig,
+    waveforms: Vec<Box<dyn Waveform>>,
+    /// Payload bytes per modulation call, sized to roughly fill an example
+    payload_len: Vec<usize>,
+}
+
+impl DatasetGenerator {
+    /// Validate the configuration and set up the waveforms.
+    pub fn new(config: DatasetConfig) -> Result<Self, String> {
+        if config.waveforms.is_empty() || config.snr_db.is_empty() || config.fading.is_empty() {
+            return Err("Dataset needs at least one waveform, SNR and fading model".to_string());
+        }
+        if config.example_len == 0 || config.sample_rate <= 0.0 {
+            return Err("Example length and sample rate must be positive".to_string());
+        }
+        for fading in &config.fading {
+            if matches!(fading, Fading::Rayleigh { max_doppler_hz } if *max_doppler_hz <= 0.0) {
+                return Err("Rayleigh fading needs a positive maximum Doppler".to_string());
+            }
+        }
+        for (i, impairments) in config.impairments.iter().enumerate() {
+            impairments.validate().map_err(|e| format!("Impairment set {}: {}", i, e))?;
+        }
+
+        let mut waveforms = Vec::with_capacity(config.waveforms.len());
+        let mut payload_len = Vec::with_capacity(config.waveforms.len());
+        for name in &config.waveforms {
+            let waveform = WaveformFactory::create(name, config.sample_rate)
+                .ok_or_else(|| format!("Unknown waveform: {}", name))?;
+            let probe = waveform.modulate(&[0xa5; 16]).len();
+            if probe == 0 {
+                return Err(format!("{} produced no samples", name));
+            }
+            let samples_per_byte = (probe as f64 / 16.0).max(1e-3);
+            payload_len.push(((config.example_len as f64 / samples_per_byte).ceil() as usize).clamp(1, 4096));
+            waveforms.push(waveform);
+        }
+
+        Ok(Self {
+            config,
+            waveforms,
+            payload_len,
+        })
+    }
+
+    /// The configuration
+    pub fn config(&self) -> &DatasetConfig {
+        &self.config
+    }
+
+    /// Number of examples
+    pub fn len(&self) -> usize {
+        self.config.waveforms.len() * self.config.snr_db.len() * self.config.examples_per_point
+    }
+
+    /// Whether the sweep is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Seed of example `index`.
+    pub fn example_seed(&self, index: usize) -> u64 {
+        splitmix64(self.config.seed ^ splitmix64(index as u64))
+    }
+
+    /// Generate example `index`; the same index always gives the same samples.
+    pub fn example(&self, index: usize) -> (Vec<IQSample>, ExampleInfo) {
+        let cfg = &self.config;
+        let per_class = cfg.snr_db.len() * cfg.examples_per_point;
+        let class = index / per_class;
+        let snr_db = cfg.snr_db[(index % per_class) / cfg.examples_per_point];
+        let seed = self.example_seed(index);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        // Random payloads until there is room for a random crop
+        let waveform = &self.waveforms[class];
+        let mut signal: Vec<IQSample> = Vec::with_capacity(2 * cfg.example_len);
+        while signal.len() < 2 * cfg.example_len {
+            let payload: Vec<u8> = (0..self.payload_len[class]).map(|_| rng.gen()).collect();
+            let burst = waveform.modulate(&payload);
+            if burst.is_empty() {
+                break;
+            }
+            signal.extend(burst);
+        }
+        signal.resize(signal.len().max(cfg.example_len), IQSample::new(0.0, 0.0));
+
+        let cfo_hz = if cfg.max_cfo_hz > 0.0 {
+            rng.gen_range(-cfg.max_cfo_hz..=cfg.max_cfo_hz)
+        } else {
+            0.0
+        };
+        let phase0 = rng.gen_range(0.0..2.0 * PI);
+        let step = 2.0 * PI * cfo_hz / cfg.sample_rate;
+        for (n, s) in signal.iter_mut().enumerate() {
+            *s *= IQSample::from_polar(1.0, phase0 + step * n as f64);
+        }
+
+        let fading = &cfg.fading[rng.gen_range(0..cfg.fading.len())];
+        let impairments = (!cfg.impairments.is_empty()).then(|| rng.gen_range(0..cfg.impairments.len()));
+        let impairment_config = impairments.map(|i| cfg.impairments[i].clone()).unwrap_or_default();
+        let channel_config = fading.channel_config(snr_db, cfg.sample_rate, impairment_config);
+        let received = Channel::with_seed(channel_config, rng.gen()).apply(&signal);
+
+        let offset = rng.gen_range(0..=received.len() - cfg.example_len);
+        let mut samples = received[offset..offset + cfg.example_len].to_vec();
+        if cfg.normalize {
+            let power = samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / samples.len() as f64;
+            if power > 0.0 {
+                let scale = 1.0 / power.sqrt();
+                samples.iter_mut().for_each(|s| *s *= scale);
+            }
+        }
+
+        let info = ExampleInfo {
+            index,
+            class,
+            label: cfg.waveforms[class].clone(),
+            snr_db,
+            cfo_hz,
+            fading: fading.name(),
+            impairments,
+            seed,
+            sample_start: None,
+        };
+        (samples, info)
+    }
+
+    /// Generate a range of examples, in parallel with the `parallel` feature.
+    pub fn examples(&self, range: Range<usize>) -> Vec<(Vec<IQSample>, ExampleInfo)> {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            range.into_par_iter().map(|i| self.example(i)).collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            range.map(|i| self.example(i)).collect()
+        }
+    }
+
+    /// Generate the whole dataset into `dir` and write its manifest.
+    pub fn write<P: AsRef<Path>>(&self, dir: P, format: DatasetFormat) -> io::Result<DatasetManifest> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let (files, examples) = match format {
+            DatasetFormat::Npy => self.write_npy(dir)?,
+            DatasetFormat::SigMf => self.write_sigmf(dir)?,
+        };
+
+        let manifest = DatasetManifest {
+            format,
+            classes: self.config.waveforms.clone(),
+            num_examples: self.len(),
+            example_len: self.config.example_len,
+            files,
+            config: self.config.clone(),
+            examples,
+        };
+        let file = File::create(dir.join("manifest.json"))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), &manifest).map_err(io::Error::other)?;
+        Ok(manifest)
+    }
+
+    fn write_npy(&self, dir: &Path) -> io::Result<(BTreeMap<String, String>, Vec<ExampleInfo>)> {
+        let n = self.len();
+        let len = self.config.example_len;
+        let mut x = BufWriter::new(File::create(dir.join("X.npy"))?);
+        x.write_all(&npy_header("<f4", &[n, 2, len]))?;
+
+        let mut infos = Vec::with_capacity(n);
+        for start in (0..n).step_by(CHUNK) {
+            for (samples, info) in self.examples(start..(start + CHUNK).min(n)) {
+                for s in &samples {
+                    x.write_all(&(s.re as f32).to_le_bytes())?;
+                }
+                for s in &samples {
+                    x.write_all(&(s.im as f32).to_le_bytes())?;
+                }
+                infos.push(info);
+            }
+        }
+        x.flush()?;
+
+        let mut y = npy_header("<i8", &[n]);
+        y.extend(infos.iter().flat_map(|i| (i.class as i64).to_le_bytes()));
+        std::fs::write(dir.join("y.npy"), y)?;
+        let mut snr = npy_header("<f4", &[n]);
+        snr.extend(infos.iter().flat_map(|i| (i.snr_db as f32).to_le_bytes()));
+        std::fs::write(dir.join("snr.npy"), snr)?;
+
+        let files = [("x", "X.npy"), ("y", "y.npy"), ("snr", "snr.npy")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Ok((files, infos))
+    }
+
+    fn write_sigmf(&self, dir: &Path) -> io::Result<(BTreeMap<String, String>, Vec<ExampleInfo>)> {
+        let sdr_err = |e: crate::device::SdrError| io::Error::other(e.to_string());
+        let n = self.len();
+        let mut writer = SigMfWriter::create(dir.join("dataset"), self.config.sample_rate, 0.0).map_err(sdr_err)?;
+        writer.set_description(&format!(
+            "Synthetic dataset: {} classes x {} SNRs x {} examples of {} samples (seed {})",
+            self.config.waveforms.len(),
+            self.config.snr_db.len(),
+            self.config.examples_per_point,
+            self.config.example_len,
+            self.config.seed
+        ));
+
+        let mut infos = Vec::with_capacity(n);
+        for start in (0..n).step_by(CHUNK) {
+            for (samples, mut info) in self.examples(start..(start + CHUNK).min(n)) {
+                let sample_start = writer.samples_written();
+                writer.write_samples(&samples).map_err(sdr_err)?;
+
+                let params = format!("snr={:.1}dB cfo={:.1}Hz fading={}", info.snr_db, info.cfo_hz, info.fading);
+                let meta = writer.metadata_mut();
+                annotate_waveform(meta, &info.label, sample_start, samples.len() as u64, &params);
+                let ann = meta.annotations.last_mut().expect("annotation just added");
+                ann.extensions.insert("r4w:class".to_string(), serde_json::json!(info.class));
+                ann.extensions.insert("r4w:snr_db".to_string(), serde_json::json!(info.snr_db));
+                ann.extensions.insert("r4w:cfo_hz".to_string(), serde_json::json!(info.cfo_hz));
+                ann.extensions.insert("r4w:fading".to_string(), serde_json::json!(info.fading));
+                ann.extensions.insert("r4w:seed".to_string(), serde_json::json!(info.seed));
+
+                info.sample_start = Some(sample_start);
+                infos.push(info);
+            }
+        }
+        writer.close().map_err(sdr_err)?;
+
+        let files = [("meta", "dataset.sigmf-meta"), ("data", "dataset.sigmf-data")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Ok((files, infos))
+    }
+}
+
+/// NPY v1.0 header for a C-order array, padded to 64 bytes.
+fn npy_header(descr: &str, shape: &[usize]) -> Vec<u8> {
+    let dims: Vec<String> = shape.iter().map(|d| d.to_string()).collect();
+    let shape = match dims.len() {
+        1 => format!("({},)", dims[0]),
+        _ => format!("({})", dims.join(", ")),
+    };
+    let mut dict = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", descr, shape);
+    let unpadded = 10 + dict.len() + 1;
+    dict.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    dict.push('\n');
+
+    let mut header = Vec::with_capacity(10 + dict.len());
+    header.extend_from_slice(b"\x93NUMPY\x01\x00");
+    header.extend_from_slice(&(dict.len() as u16).to_le_bytes());
+    header.extend_from_slice(dict.as_bytes());
+    header
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hal::sigmf::SigMfReader;
+    use crate::impairments::IqImbalance;
+    use tempfile::TempDir;
+
+    fn small_config() -> DatasetConfig {
+        DatasetConfig {
+            waveforms: vec!["BPSK".into(), "QPSK".into(), "BFSK".into()],
+            snr_db: vec![0.0, 10.0],
+            examples_per_point: 3,
+            example_len: 128,
+            seed: 42,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reproducible_and_parallel_safe() {
+        let generator = DatasetGenerator::new(small_config()).unwrap();
+        assert_eq!(generator.len(), 18);
+
+        let batch = generator.examples(0..18);
+        for (i, (samples, info)) in batch.iter().enumerate() {
+            assert_eq!(&generator.example(i), &(samples.clone(), info.clone()));
+        }
+        assert_eq!(batch[7].1.label, "QPSK");
+        assert_eq!(batch[7].1.snr_db, 0.0);
+        assert_eq!(batch[10].1.snr_db, 10.0);
+        let power = batch[0].0.iter().map(|s| s.norm_sqr()).sum::<f64>() / 128.0;
+        assert!((power - 1.0).abs() < 1e-9);
+
+        let reseeded = DatasetGenerator::new(DatasetConfig { seed: 43, ..small_config() }).unwrap();
+        assert_ne!(reseeded.example(0).0, batch[0].0);
+        assert!(DatasetGenerator::new(DatasetConfig { waveforms: vec!["nope".into()], ..small_config() }).is_err());
+        let empty_mask = ImpairmentConfig {
+            phase_noise: Some(crate::impairments::PhaseNoiseConfig::new(Vec::new())),
+            ..Default::default()
+        };
+        assert!(DatasetGenerator::new(DatasetConfig { impairments: vec![empty_mask], ..small_config() }).is_err());
+    }
+
+    #[test]
+    fn test_channel_sweep() {
+        let config = DatasetConfig {
+            max_cfo_hz: 500.0,
+            fading: vec![
+                Fading::None,
+                Fading::parse("rayleigh", 50.0).unwrap(),
+                Fading::parse("eva", 0.0).unwrap(),
+            ],
+            impairments: vec![ImpairmentConfig {
+                iq_imbalance: Some(IqImbalance { gain_db: 1.0, phase_deg: 5.0 }),
+                ..Default::default()
+            }],
+            ..small_config()
+        };
+        let generator = DatasetGenerator::new(config).unwrap();
+        let examples = generator.examples(0..generator.len());
+        let fadings: std::collections::BTreeSet<_> = examples.iter().map(|(_, i)| i.fading.clone()).collect();
+        assert_eq!(fadings.len(), 3, "{:?}", fadings);
+        assert!(examples.iter().all(|(s, i)| {
+            s.iter().all(|v| v.re.is_finite() && v.im.is_finite()) && i.cfo_hz.abs() <= 500.0 && i.impairments == Some(0)
+        }));
+        assert!(Fading::parse("bogus", 1.0).is_none());
+    }
+
+    #[test]
+    fn test_write_npy_and_sigmf() {
+        let temp_dir = TempDir::new().unwrap();
+        let generator = DatasetGenerator::new(small_config()).unwrap();
+
+        let npy_dir = temp_dir.path().join("npy");
+        let manifest = generator.write(&npy_dir, DatasetFormat::Npy).unwrap();
+        let x = std::fs::read(npy_dir.join("X.npy")).unwrap();
+        let header_len = 10 + u16::from_le_bytes([x[8], x[9]]) as usize;
+        assert_eq!(header_len % 64, 0);
+        assert!(String::from_utf8_lossy(&x[10..header_len]).contains("'shape': (18, 2, 128)"));
+        assert_eq!(x.len(), header_len + 18 * 2 * 128 * 4);
+        let first_i = f32::from_le_bytes(x[header_len..header_len + 4].try_into().unwrap());
+        assert_eq!(first_i, generator.example(0).0[0].re as f32);
+
+        let y = std::fs::read(npy_dir.join("y.npy")).unwrap();
+        let label = |k: usize| i64::from_le_bytes(y[y.len() - 8 * (18 - k)..][..8].try_into().unwrap());
+        assert_eq!((label(0), label(6), label(17)), (0, 1, 2));
+
+        let loaded = DatasetManifest::load(npy_dir.join("manifest.json")).unwrap();
+        assert_eq!(loaded.examples, manifest.examples);
+        assert_eq!(loaded.config, small_config());
+
+        let sigmf_dir = temp_dir.path().join("sigmf");
+        let manifest = generator.write(&sigmf_dir, DatasetFormat::SigMf).unwrap();
+        let reader = SigMfReader::open(sigmf_dir.join("dataset")).unwrap();
+        assert_eq!(reader.total_samples(), 18 * 128);
+        let annotations = &reader.metadata().annotations;
+        assert_eq!(annotations.len(), 18);
+        assert_eq!(annotations[17].label.as_deref(), Some("BFSK"));
+        assert_eq!(annotations[17].sample_start, 17 * 128);
+        assert_eq!(annotations[17].extensions["r4w:snr_db"], serde_json::json!(10.0));
+        assert_eq!(manifest.examples[17].sample_start, Some(17 * 128));
+    }
+}
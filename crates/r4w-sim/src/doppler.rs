@@ -259,8 +259,15 @@ pub struct FlatDoppler {
 impl FlatDoppler {
     /// Create a new flat Doppler generator
     pub fn new(max_doppler_hz: f64, sample_rate: f64, num_sinusoids: usize) -> Self {
-        let mut rng = StdRng::from_entropy();
+        Self::with_rng(max_doppler_hz, sample_rate, num_sinusoids, StdRng::from_entropy())
+    }
+
+    /// Create with specific seed for reproducibility
+    pub fn with_seed(max_doppler_hz: f64, sample_rate: f64, num_sinusoids: usize, seed: u64) -> Self {
+        Self::with_rng(max_doppler_hz, sample_rate, num_sinusoids, StdRng::seed_from_u64(seed))
+    }
 
+    fn with_rng(max_doppler_hz: f64, sample_rate: f64, num_sinusoids: usize, mut rng: StdRng) -> Self {
         // Uniformly distributed frequencies between -f_d and +f_d
         let frequencies: Vec<f64> = (0..num_sinusoids)
             .map(|i| {
@@ -349,8 +356,16 @@ impl GaussianDoppler {
     /// * `sample_rate` - Sample rate in Hz
     /// * `num_sinusoids` - Number of oscillators
     pub fn new(doppler_rms_hz: f64, sample_rate: f64, num_sinusoids: usize) -> Self {
+        Self::with_rng(doppler_rms_hz, sample_rate, num_sinusoids, StdRng::from_entropy())
+    }
+
+    /// Create with specific seed for reproducibility
+    pub fn with_seed(doppler_rms_hz: f64, sample_rate: f64, num_sinusoids: usize, seed: u64) -> Self {
+        Self::with_rng(doppler_rms_hz, sample_rate, num_sinusoids, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(doppler_rms_hz: f64, sample_rate: f64, num_sinusoids: usize, mut rng: StdRng) -> Self {
         use rand_distr::{Distribution, Normal};
-        let mut rng = StdRng::from_entropy();
 
         let normal = Normal::new(0.0, doppler_rms_hz).unwrap();
         let frequencies: Vec<f64> = (0..num_sinusoids)
@@ -445,6 +460,28 @@ impl DopplerGenerator {
         }
     }
 
+    /// Create a Doppler generator with a specific seed for reproducibility
+    pub fn with_seed(
+        model: DopplerModel,
+        max_doppler_hz: f64,
+        sample_rate: f64,
+        num_sinusoids: usize,
+        seed: u64,
+    ) -> Self {
+        match model {
+            DopplerModel::Jakes => DopplerGenerator::Jakes(JakesDoppler::with_seed(
+                max_doppler_hz, sample_rate, num_sinusoids, seed,
+            )),
+            DopplerModel::Flat => DopplerGenerator::Flat(FlatDoppler::with_seed(
+                max_doppler_hz, sample_rate, num_sinusoids, seed,
+            )),
+            DopplerModel::Gaussian => DopplerGenerator::Gaussian(GaussianDoppler::with_seed(
+                max_doppler_hz, sample_rate, num_sinusoids, seed,
+            )),
+            DopplerModel::Static => DopplerGenerator::Static,
+        }
+    }
+
     /// Generate a single fading sample
     pub fn next_sample(&mut self) -> IQSample {
         match self {
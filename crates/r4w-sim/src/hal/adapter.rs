@@ -0,0 +1,697 @@
+//! Stream Adaptation
+//!
+//! Waveforms run at their own [`CommonParams::sample_rate`](r4w_core::waveform::CommonParams),
+//! while a device supports only a range of rates
+//! ([`TunerControl::sample_rate_range`]) and moves samples in its own
+//! format. A [`StreamPlan`] reconciles the two and a [`StreamAdapter`]
+//! carries it out:
+//!
+//! ```text
+//!   TX: waveform ──► resample L/M ──► shift +f_off ──► encode ──► device (LO = f_c − f_off)
+//!   RX: waveform ◄── resample M/L ◄── shift −f_off ◄── decode ◄── device
+//! ```
+//!
+//! - **Rate**: the waveform rate itself when the device supports it,
+//!   otherwise the lowest supported rate that is a simple ratio `L/M` of it
+//!   (`M ≤ 16`), so a short polyphase filter does the resampling. If the
+//!   device then sets a different rate, the ratio follows the actual rate.
+//! - **LO offset**: devices with a DC spike ([`SdrDeviceExt::has_dc_offset`])
+//!   are tuned `f_off` away from the carrier so the spike and LO leakage
+//!   fall outside the signal; the digital shift puts the signal back. The
+//!   automatic offset is 0.75 × the occupied bandwidth, which needs a
+//!   device rate of 2.5 × the bandwidth.
+//! - **Format**: [`StreamAdapter::encode`]/[`decode`](StreamAdapter::decode)
+//!   convert to and from the device's native [`SampleFormat`] for
+//!   transports that carry raw bytes.
+//!
+//! ```rust
+//! use r4w_sim::hal::adapter::{AdapterOptions, StreamAdapter};
+//! use r4w_sim::hal::create_default_registry;
+//! use r4w_core::types::IQSample;
+//!
+//! let mut device = create_default_registry().create("sim://").unwrap();
+//! // The simulator supports the waveform rate and has no DC spike
+//! let options = AdapterOptions::default().with_frequency(433.92e6);
+//! let mut adapter = StreamAdapter::negotiate(device.as_mut(), 100_000.0, &options).unwrap();
+//! assert_eq!(adapter.plan().device_rate, 100_000.0);
+//! assert!(adapter.plan().is_passthrough());
+//! assert_eq!(device.tuner().frequency(), 433_920_000);
+//!
+//! let tx = adapter.to_device(&[IQSample::new(1.0, 0.0); 100]);
+//! assert_eq!(tx.len(), 100);
+//! ```
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use r4w_core::types::IQSample;
+
+use super::{SampleFormat, SdrDeviceExt, SdrError, SdrResult};
+
+/// Largest decimation factor tried when picking a device rate
+const MAX_PLAN_DECIMATION: usize = 16;
+
+/// Largest denominator when matching a rate the device chose itself
+const MAX_RATIO_DENOMINATOR: usize = 1000;
+
+/// Input samples on each side of an output sample in the resampling filter
+const HALF_TAPS: usize = 16;
+
+/// Automatic LO offset as a fraction of the occupied bandwidth
+const AUTO_OFFSET_FACTOR: f64 = 0.75;
+
+/// Options for [`StreamPlan::new`]
+#[derive(Debug, Clone, Default)]
+pub struct AdapterOptions {
+    /// Carrier frequency in Hz (None = the device's current frequency)
+    pub frequency: Option<f64>,
+    /// Occupied bandwidth in Hz (None = the waveform sample rate)
+    pub bandwidth: Option<f64>,
+    /// LO offset in Hz (None = automatic, only for devices with a DC spike)
+    pub lo_offset: Option<f64>,
+    /// Device-side sample format (None = the device's native format)
+    pub format: Option<SampleFormat>,
+}
+
+impl AdapterOptions {
+    /// Set the carrier frequency
+    pub fn with_frequency(mut self, frequency_hz: f64) -> Self {
+        self.frequency = Some(frequency_hz);
+        self
+    }
+
+    /// Set the occupied bandwidth
+    pub fn with_bandwidth(mut self, bandwidth_hz: f64) -> Self {
+        self.bandwidth = Some(bandwidth_hz);
+        self
+    }
+
+    /// Set a fixed LO offset (0 disables it)
+    pub fn with_lo_offset(mut self, offset_hz: f64) -> Self {
+        self.lo_offset = Some(offset_hz);
+        self
+    }
+
+    /// Set the device-side sample format
+    pub fn with_format(mut self, format: SampleFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+}
+
+/// What the planner needs to know about a device
+#[derive(Debug, Clone)]
+pub struct DeviceLimits {
+    /// Supported sample rates (Hz)
+    pub sample_rate_range: (f64, f64),
+    /// Supported center frequencies (Hz)
+    pub frequency_range: (u64, u64),
+    /// Current center frequency (Hz)
+    pub frequency: f64,
+    /// Native sample format
+    pub format: SampleFormat,
+    /// DC spike / LO leakage at the center frequency
+    pub dc_offset: bool,
+}
+
+impl DeviceLimits {
+    /// Query a device
+    pub fn of(device: &mut dyn SdrDeviceExt) -> Self {
+        let format = device.native_format();
+        let dc_offset = device.has_dc_offset();
+        let tuner = device.tuner();
+        Self {
+            sample_rate_range: tuner.sample_rate_range(),
+            frequency_range: tuner.frequency_range(),
+            frequency: tuner.frequency() as f64,
+            format,
+            dc_offset,
+        }
+    }
+}
+
+/// Negotiated mapping between a waveform and a device
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamPlan {
+    /// Waveform sample rate (Hz)
+    pub waveform_rate: f64,
+    /// Occupied bandwidth (Hz)
+    pub bandwidth: f64,
+    /// Device sample rate (Hz)
+    pub device_rate: f64,
+    /// Resampling ratio device/waveform = `interpolation / decimation`
+    pub interpolation: usize,
+    /// See [`interpolation`](Self::interpolation)
+    pub decimation: usize,
+    /// Carrier frequency of the signal (Hz)
+    pub carrier_frequency: f64,
+    /// Device LO frequency (Hz)
+    pub lo_frequency: f64,
+    /// Carrier minus LO: where the signal sits in the device baseband (Hz)
+    pub lo_offset: f64,
+    /// Device-side sample format
+    pub format: SampleFormat,
+    /// Compromises made while planning
+    pub notes: Vec<String>,
+}
+
+impl StreamPlan {
+    /// Plan a stream for a waveform at `waveform_rate` on a device
+    pub fn new(waveform_rate: f64, device: &DeviceLimits, options: &AdapterOptions) -> SdrResult<Self> {
+        if !(waveform_rate > 0.0 && waveform_rate.is_finite()) {
+            return Err(SdrError::ConfigError(format!("Invalid waveform sample rate {}", waveform_rate)));
+        }
+        let (min_rate, max_rate) = device.sample_rate_range;
+        let bandwidth = options.bandwidth.unwrap_or(waveform_rate);
+        let carrier = options.frequency.unwrap_or(device.frequency);
+        let mut notes = Vec::new();
+
+        let mut offset = match options.lo_offset {
+            Some(offset) => offset,
+            None if device.dc_offset => AUTO_OFFSET_FACTOR * bandwidth,
+            None => 0.0,
+        };
+        if options.lo_offset.is_none() && offset != 0.0 && bandwidth + 2.0 * offset > max_rate {
+            notes.push(format!(
+                "LO offset disabled: {:.0} S/s device rate needed, {:.0} S/s supported",
+                bandwidth + 2.0 * offset,
+                max_rate
+            ));
+            offset = 0.0;
+        }
+
+        // Keep the LO inside the tuning range, offsetting the other way if needed
+        let (min_freq, max_freq) = (device.frequency_range.0 as f64, device.frequency_range.1 as f64);
+        if offset != 0.0 && carrier - offset < min_freq && carrier + offset <= max_freq {
+            offset = -offset;
+        }
+
+        let needed = bandwidth + 2.0 * offset.abs();
+        if needed > max_rate {
+            notes.push(format!(
+                "Device rate limited to {:.0} S/s: signal needs {:.0} S/s and will be filtered",
+                max_rate, needed
+            ));
+        }
+        let (interpolation, decimation) = choose_ratio(waveform_rate, needed, (min_rate, max_rate)).ok_or_else(|| {
+            SdrError::ConfigError(format!(
+                "No device rate in [{:.0}, {:.0}] S/s fits a {:.0} S/s waveform",
+                min_rate, max_rate, waveform_rate
+            ))
+        })?;
+        let device_rate = waveform_rate * interpolation as f64 / decimation as f64;
+
+        Ok(Self {
+            waveform_rate,
+            bandwidth,
+            device_rate,
+            interpolation,
+            decimation,
+            carrier_frequency: carrier,
+            lo_frequency: carrier - offset,
+            lo_offset: offset,
+            format: options.format.unwrap_or(device.format),
+            notes,
+        })
+    }
+
+    /// Plan a stream for a device
+    pub fn for_device(device: &mut dyn SdrDeviceExt, waveform_rate: f64, options: &AdapterOptions) -> SdrResult<Self> {
+        Self::new(waveform_rate, &DeviceLimits::of(device), options)
+    }
+
+    /// Configure the device and update the plan to what it actually set
+    pub fn apply(&mut self, device: &mut dyn SdrDeviceExt) -> SdrResult<()> {
+        let tuner = device.tuner();
+        let actual = tuner.set_sample_rate(self.device_rate)?;
+        if (actual - self.device_rate).abs() > 1e-9 * self.device_rate {
+            let (l, m) = rational(actual / self.waveform_rate, MAX_RATIO_DENOMINATOR);
+            let error_ppm = (self.waveform_rate * l as f64 / m as f64 / actual - 1.0) * 1e6;
+            self.notes.push(format!(
+                "Device set {:.3} S/s instead of {:.3} S/s (resampling {}/{}, {:+.2} ppm)",
+                actual, self.device_rate, l, m, error_ppm
+            ));
+            self.device_rate = actual;
+            self.interpolation = l;
+            self.decimation = m;
+        }
+        let lo = tuner.set_frequency(self.lo_frequency.max(0.0).round() as u64)?;
+        self.lo_frequency = lo as f64;
+        self.lo_offset = self.carrier_frequency - self.lo_frequency;
+        Ok(())
+    }
+
+    /// Device samples per waveform sample
+    pub fn ratio(&self) -> f64 {
+        self.interpolation as f64 / self.decimation as f64
+    }
+
+    /// No resampling or frequency shift needed
+    pub fn is_passthrough(&self) -> bool {
+        self.interpolation == self.decimation && self.lo_offset == 0.0
+    }
+}
+
+impl fmt::Display for StreamPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Waveform rate:  {:.0} S/s ({:.1} kHz occupied)", self.waveform_rate, self.bandwidth / 1e3)?;
+        if self.interpolation == self.decimation {
+            writeln!(f, "Device rate:    {:.0} S/s (no resampling)", self.device_rate)?;
+        } else {
+            writeln!(
+                f,
+                "Device rate:    {:.0} S/s (resample {}/{})",
+                self.device_rate, self.interpolation, self.decimation
+            )?;
+        }
+        writeln!(f, "Carrier:        {:.6} MHz", self.carrier_frequency / 1e6)?;
+        writeln!(
+            f,
+            "LO:             {:.6} MHz (offset {:+.3} kHz)",
+            self.lo_frequency / 1e6,
+            self.lo_offset / 1e3
+        )?;
+        write!(f, "Format:         {} ({} bytes/sample)", self.format, self.format.bytes_per_sample())?;
+        for note in &self.notes {
+            write!(f, "\nNote:           {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resampling ratio `(L, M)` for the lowest device rate `≥ needed` in `range`
+///
+/// Falls back to the highest supported rate if `needed` is out of reach.
+fn choose_ratio(waveform_rate: f64, needed: f64, (min, max): (f64, f64)) -> Option<(usize, usize)> {
+    let lo = needed.max(min);
+    let fits = |rate: f64| rate >= min * (1.0 - 1e-12) && rate <= max * (1.0 + 1e-12);
+    if waveform_rate >= lo && fits(waveform_rate) {
+        return Some((1, 1));
+    }
+
+    let mut best: Option<(f64, usize, usize)> = None;
+    for m in 1..=MAX_PLAN_DECIMATION {
+        let l = if lo <= max {
+            (lo * m as f64 / waveform_rate * (1.0 - 1e-12)).ceil()
+        } else {
+            (max * m as f64 / waveform_rate * (1.0 + 1e-12)).floor()
+        }
+        .max(1.0) as usize;
+        let rate = waveform_rate * l as f64 / m as f64;
+        if !fits(rate) {
+            continue;
+        }
+        let better = match best {
+            None => true,
+            Some((r, _, _)) if lo <= max => rate < r * (1.0 - 1e-12),
+            Some((r, _, _)) => rate > r * (1.0 + 1e-12),
+        };
+        if better {
+            best = Some((rate, l, m));
+        }
+    }
+    best.map(|(_, l, m)| reduce(l, m))
+}
+
+/// Best `L/M ≈ ratio` with `M ≤ max_denominator`
+fn rational(ratio: f64, max_denominator: usize) -> (usize, usize) {
+    let mut best = (ratio.round().max(1.0) as usize, 1);
+    let mut best_error = f64::INFINITY;
+    for m in 1..=max_denominator {
+        let l = (ratio * m as f64).round().max(1.0);
+        let error = (l / m as f64 - ratio).abs();
+        if error < best_error * (1.0 - 1e-9) {
+            best = (l as usize, m);
+            best_error = error;
+        }
+    }
+    reduce(best.0, best.1)
+}
+
+fn reduce(l: usize, m: usize) -> (usize, usize) {
+    let (mut a, mut b) = (l, m);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    (l / a, m / a)
+}
+
+/// Rational `L/M` sample rate converter
+///
+/// Output sample `n` is the input interpolated at time `n·M/L` (in input
+/// samples) with a Hann-windowed sinc of ±16 taps, cut off at the lower of
+/// the two Nyquist rates. There is no group delay: output 0 lines up with
+/// input 0, and integer-ratio interpolation reproduces the input samples
+/// exactly. Streaming calls give the same result as one call on the whole
+/// signal; [`flush`](Self::flush) emits what the last input still owes.
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    interpolation: usize,
+    decimation: usize,
+    /// `taps[phase][j]` weights input `idx + 1 − HALF_TAPS + j`
+    taps: Vec<Vec<f64>>,
+    buffer: Vec<IQSample>,
+    /// Input index of `buffer[0]`
+    base: u64,
+    /// Input samples received
+    received: u64,
+    /// Output samples produced
+    produced: u64,
+}
+
+impl Resampler {
+    /// Converter producing `interpolation` outputs per `decimation` inputs
+    pub fn new(interpolation: usize, decimation: usize) -> Self {
+        assert!(interpolation > 0 && decimation > 0, "resampling factors must be positive");
+        let (interpolation, decimation) = reduce(interpolation, decimation);
+        let cutoff = (interpolation as f64 / decimation as f64).min(1.0);
+        let taps = (0..interpolation)
+            .map(|phase| {
+                let frac = phase as f64 / interpolation as f64;
+                let raw: Vec<f64> = (0..2 * HALF_TAPS)
+                    .map(|j| {
+                        let d = frac + HALF_TAPS as f64 - 1.0 - j as f64;
+                        let window = 0.5 * (1.0 + (PI * d / HALF_TAPS as f64).cos());
+                        cutoff * sinc(cutoff * d) * window
+                    })
+                    .collect();
+                // Unity gain at DC for every phase
+                let sum: f64 = raw.iter().sum();
+                raw.into_iter().map(|h| h / sum).collect()
+            })
+            .collect();
+        Self {
+            interpolation,
+            decimation,
+            taps,
+            buffer: Vec::new(),
+            base: 0,
+            received: 0,
+            produced: 0,
+        }
+    }
+
+    /// Output samples per input sample
+    pub fn ratio(&self) -> f64 {
+        self.interpolation as f64 / self.decimation as f64
+    }
+
+    /// Convert the next block of input
+    pub fn process(&mut self, input: &[IQSample]) -> Vec<IQSample> {
+        if self.interpolation == self.decimation {
+            self.received += input.len() as u64;
+            self.produced += input.len() as u64;
+            return input.to_vec();
+        }
+        self.buffer.extend_from_slice(input);
+        self.received += input.len() as u64;
+        self.run(u64::MAX)
+    }
+
+    /// Emit the outputs that still depend on samples after the last input
+    /// and start over
+    pub fn flush(&mut self) -> Vec<IQSample> {
+        let out = if self.interpolation == self.decimation {
+            Vec::new()
+        } else {
+            // Outputs up to the end of the input, with zeros beyond it
+            let limit = (self.received * self.interpolation as u64).div_ceil(self.decimation as u64);
+            self.buffer.extend(std::iter::repeat_n(IQSample::new(0.0, 0.0), HALF_TAPS));
+            self.run(limit)
+        };
+        self.reset();
+        out
+    }
+
+    /// Drop all state
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.base = 0;
+        self.received = 0;
+        self.produced = 0;
+    }
+
+    /// Produce outputs while their inputs are buffered (and below `limit`)
+    fn run(&mut self, limit: u64) -> Vec<IQSample> {
+        let (l, m) = (self.interpolation as u64, self.decimation as u64);
+        let end = self.base + self.buffer.len() as u64;
+        let mut out = Vec::new();
+        while self.produced < limit {
+            let position = self.produced * m;
+            let idx = position / l;
+            if idx + HALF_TAPS as u64 >= end {
+                break;
+            }
+            let taps = &self.taps[(position % l) as usize];
+            let first = idx as i64 + 1 - HALF_TAPS as i64;
+            let mut acc = IQSample::new(0.0, 0.0);
+            for (j, h) in taps.iter().enumerate() {
+                let i = first + j as i64;
+                if i >= self.base as i64 {
+                    acc += self.buffer[(i - self.base as i64) as usize] * h;
+                }
+            }
+            out.push(acc);
+            self.produced += 1;
+        }
+
+        // Keep only what the next output can still reach
+        let next = self.produced * m / l;
+        let keep_from = (next + 1).saturating_sub(HALF_TAPS as u64).max(self.base);
+        let drop = ((keep_from - self.base) as usize).min(self.buffer.len());
+        self.buffer.drain(..drop);
+        self.base += drop as u64;
+        out
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Continuous-phase frequency shifter
+#[derive(Debug, Clone)]
+struct Mixer {
+    step: f64,
+    phase: f64,
+}
+
+impl Mixer {
+    fn new(frequency_hz: f64, sample_rate: f64) -> Self {
+        Self {
+            step: 2.0 * PI * frequency_hz / sample_rate,
+            phase: 0.0,
+        }
+    }
+
+    fn process(&mut self, samples: &mut [IQSample]) {
+        if self.step == 0.0 {
+            return;
+        }
+        for s in samples {
+            *s *= IQSample::from_polar(1.0, self.phase);
+            self.phase = (self.phase + self.step) % (2.0 * PI);
+        }
+    }
+}
+
+/// Runs a [`StreamPlan`]: resampling, LO offset and format conversion
+/// between waveform samples and device samples
+#[derive(Debug, Clone)]
+pub struct StreamAdapter {
+    plan: StreamPlan,
+    tx_resampler: Resampler,
+    tx_mixer: Mixer,
+    rx_resampler: Resampler,
+    rx_mixer: Mixer,
+}
+
+impl StreamAdapter {
+    /// Adapter for an already applied plan
+    pub fn new(plan: StreamPlan) -> Self {
+        Self {
+            tx_resampler: Resampler::new(plan.interpolation, plan.decimation),
+            tx_mixer: Mixer::new(plan.lo_offset, plan.device_rate),
+            rx_resampler: Resampler::new(plan.decimation, plan.interpolation),
+            rx_mixer: Mixer::new(-plan.lo_offset, plan.device_rate),
+            plan,
+        }
+    }
+
+    /// Plan a stream, configure the device accordingly and build the adapter
+    pub fn negotiate(device: &mut dyn SdrDeviceExt, waveform_rate: f64, options: &AdapterOptions) -> SdrResult<Self> {
+        let mut plan = StreamPlan::for_device(device, waveform_rate, options)?;
+        plan.apply(device)?;
+        Ok(Self::new(plan))
+    }
+
+    /// The plan being carried out
+    pub fn plan(&self) -> &StreamPlan {
+        &self.plan
+    }
+
+    /// Waveform samples to device samples (TX)
+    pub fn to_device(&mut self, samples: &[IQSample]) -> Vec<IQSample> {
+        let mut out = self.tx_resampler.process(samples);
+        self.tx_mixer.process(&mut out);
+        out
+    }
+
+    /// Remaining device samples at the end of a transmission
+    pub fn flush_to_device(&mut self) -> Vec<IQSample> {
+        let mut out = self.tx_resampler.flush();
+        self.tx_mixer.process(&mut out);
+        self.tx_mixer.phase = 0.0;
+        out
+    }
+
+    /// Device samples to waveform samples (RX)
+    pub fn from_device(&mut self, samples: &[IQSample]) -> Vec<IQSample> {
+        let mut shifted = samples.to_vec();
+        self.rx_mixer.process(&mut shifted);
+        self.rx_resampler.process(&shifted)
+    }
+
+    /// Remaining waveform samples at the end of a capture
+    pub fn flush_from_device(&mut self) -> Vec<IQSample> {
+        self.rx_mixer.phase = 0.0;
+        self.rx_resampler.flush()
+    }
+
+    /// Device samples to the device's wire format
+    pub fn encode(&self, samples: &[IQSample]) -> Vec<u8> {
+        self.plan.format.encode(samples)
+    }
+
+    /// Device wire format to device samples
+    pub fn decode(&self, bytes: &[u8]) -> Vec<IQSample> {
+        self.plan.format.decode(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rtl_limits() -> DeviceLimits {
+        DeviceLimits {
+            sample_rate_range: (225_001.0, 3_200_000.0),
+            frequency_range: (24_000_000, 1_766_000_000),
+            frequency: 100e6,
+            format: SampleFormat::ComplexUint8,
+            dc_offset: true,
+        }
+    }
+
+    fn tone(freq: f64, rate: f64, n: usize) -> Vec<IQSample> {
+        (0..n)
+            .map(|i| IQSample::from_polar(1.0, 2.0 * PI * freq * i as f64 / rate))
+            .collect()
+    }
+
+    /// Frequency of a tone from the average phase step
+    fn frequency(samples: &[IQSample], rate: f64) -> f64 {
+        let sum: IQSample = samples.windows(2).map(|w| w[1] * w[0].conj()).sum();
+        sum.arg() * rate / (2.0 * PI)
+    }
+
+    #[test]
+    fn test_plan_rate_offset_and_format() {
+        // 125 kS/s LoRa on an RTL-SDR: 2.5 × 125k = 312.5k needed, 5/2 ratio
+        let options = AdapterOptions::default().with_frequency(868.1e6);
+        let plan = StreamPlan::new(125_000.0, &rtl_limits(), &options).unwrap();
+        assert_eq!((plan.interpolation, plan.decimation), (5, 2));
+        assert_eq!(plan.device_rate, 312_500.0);
+        assert_eq!(plan.lo_offset, 93_750.0);
+        assert_eq!(plan.lo_frequency, 868.1e6 - 93_750.0);
+        assert_eq!(plan.format, SampleFormat::ComplexUint8);
+        assert!(plan.notes.is_empty());
+
+        // Without an offset the minimum rate wins: 225001 → 29/16 × 125k
+        let plan = StreamPlan::new(125_000.0, &rtl_limits(), &options.clone().with_lo_offset(0.0)).unwrap();
+        assert_eq!((plan.interpolation, plan.decimation), (29, 16));
+
+        // Too wide for the device: offset dropped, rate capped, decimation
+        let plan = StreamPlan::new(10e6, &rtl_limits(), &options).unwrap();
+        assert_eq!(plan.lo_offset, 0.0);
+        assert_eq!(plan.device_rate, 3_125_000.0);
+        assert_eq!(plan.notes.len(), 2);
+
+        // A carrier at the bottom of the tuning range offsets upwards
+        let low = AdapterOptions::default().with_frequency(24.05e6);
+        let plan = StreamPlan::new(125_000.0, &rtl_limits(), &low).unwrap();
+        assert!(plan.lo_offset < 0.0 && plan.lo_frequency >= 24e6);
+
+        assert!(StreamPlan::new(0.0, &rtl_limits(), &options).is_err());
+    }
+
+    #[test]
+    fn test_resampler_streaming_and_passband() {
+        // Interpolation keeps the original samples
+        let input = tone(1_000.0, 48_000.0, 480);
+        let mut up = Resampler::new(3, 1);
+        let mut out = up.process(&input);
+        out.extend(up.flush());
+        assert_eq!(out.len(), 1440);
+        for i in 0..480 {
+            assert!((out[3 * i] - input[i]).norm() < 1e-9);
+        }
+
+        // Block-wise processing matches a single call
+        let mut whole = Resampler::new(5, 7);
+        let mut expected = whole.process(&input);
+        expected.extend(whole.flush());
+        let mut blocks = Resampler::new(5, 7);
+        let mut got = Vec::new();
+        for chunk in input.chunks(37) {
+            got.extend(blocks.process(chunk));
+        }
+        got.extend(blocks.flush());
+        assert_eq!(got.len(), expected.len());
+        assert!(got.iter().zip(&expected).all(|(a, b)| (a - b).norm() < 1e-12));
+
+        // In-band tones keep frequency and amplitude; out-of-band ones are removed
+        let rate_out = 48_000.0 * 5.0 / 7.0;
+        let mid = &expected[50..expected.len() - 50];
+        assert!((frequency(mid, rate_out) - 1_000.0).abs() < 1.0);
+        assert!(mid.iter().all(|s| (s.norm() - 1.0).abs() < 0.01));
+        let mut down = Resampler::new(1, 4);
+        let rejected = down.process(&tone(18_000.0, 48_000.0, 4800));
+        let power: f64 = rejected[20..].iter().map(|s| s.norm_sqr()).sum::<f64>() / (rejected.len() - 20) as f64;
+        assert!(power < 1e-3, "alias power {}", power);
+    }
+
+    #[test]
+    fn test_adapter_round_trip_through_sim() {
+        let mut device = super::super::create_default_registry().create("sim://").unwrap();
+        // Force the offset path on a device without a DC spike
+        let options = AdapterOptions::default().with_frequency(915e6).with_lo_offset(50_000.0);
+        let mut adapter = StreamAdapter::negotiate(device.as_mut(), 40_000.0, &options).unwrap();
+        let plan = adapter.plan().clone();
+        assert_eq!(plan.device_rate, 140_000.0);
+        assert_eq!((plan.interpolation, plan.decimation), (7, 2));
+        assert_eq!(device.tuner().frequency(), 914_950_000);
+        assert_eq!(device.tuner().sample_rate(), 140_000.0);
+        assert_eq!(plan.format, SampleFormat::ComplexFloat32);
+
+        // A 2 kHz waveform tone appears at offset + 2 kHz on the device
+        let signal = tone(2_000.0, 40_000.0, 4000);
+        let mut tx = adapter.to_device(&signal);
+        tx.extend(adapter.flush_to_device());
+        assert_eq!(tx.len(), 14_000);
+        assert!((frequency(&tx[100..13_900], 140_000.0) - 52_000.0).abs() < 1.0);
+
+        // ... and comes back where it started
+        let bytes = adapter.encode(&tx);
+        let mut rx = adapter.from_device(&adapter.decode(&bytes));
+        rx.extend(adapter.flush_from_device());
+        assert_eq!(rx.len(), 4000);
+        for (a, b) in rx[100..3900].iter().zip(&signal[100..3900]) {
+            assert!((a - b).norm() < 0.01);
+        }
+    }
+}
@@ -0,0 +1,626 @@
+//! # GNU Radio File Metadata
+//!
+//! Recordings from GNU Radio's File Meta Sink. Each segment of samples is
+//! preceded by a header, a PMT dictionary serialized big-endian:
+//!
+//! ```text
+//! version  int32    metadata version (0)
+//! rx_rate  double   sample rate in Hz
+//! rx_time  tuple    (uint64 seconds, double fractional seconds) of the first sample
+//! size     int32    bytes per item
+//! type     int32    0 byte, 1 short, 2 int, 3 long, 4 long long, 5 float, 6 double
+//! cplx     bool     complex items
+//! strt     uint64   header plus extras length (149 + extras)
+//! bytes    uint64   segment length in bytes
+//! ```
+//!
+//! Any extra dictionary (typically `rx_freq` from a USRP source) follows
+//! the header. Headers are either inline, in front of every segment, or
+//! "detached" into `<file>.hdr` with the data file holding samples only.
+//! Segments become SigMF captures wherever the time or frequency jumps.
+
+use r4w_core::types::IQSample;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use super::iqfile::{hdr_path, sigmf_datatype, IqSink, IqSource};
+use super::sigmf::{SigMfCapture, SigMfMeta};
+use super::SampleFormat;
+use crate::device::{SdrError, SdrResult};
+
+/// Length of a serialized header without extras.
+pub const HEADER_LEN: usize = 149;
+
+const PST_TRUE: u8 = 0x00;
+const PST_FALSE: u8 = 0x01;
+const PST_SYMBOL: u8 = 0x02;
+const PST_INT32: u8 = 0x03;
+const PST_DOUBLE: u8 = 0x04;
+const PST_COMPLEX: u8 = 0x05;
+const PST_NULL: u8 = 0x06;
+const PST_PAIR: u8 = 0x07;
+const PST_VECTOR: u8 = 0x08;
+const PST_UINT64: u8 = 0x0b;
+const PST_TUPLE: u8 = 0x0c;
+const PST_INT64: u8 = 0x0d;
+
+/// GNU Radio item type codes.
+const TYPE_BYTE: i32 = 0;
+const TYPE_SHORT: i32 = 1;
+const TYPE_FLOAT: i32 = 5;
+
+/// A polymorphic type (PMT) value, as far as file headers use them.
+#[derive(Debug, Clone, PartialEq)]
+enum Pmt {
+    Bool(bool),
+    Null,
+    Symbol(String),
+    Int32(i32),
+    Int64(i64),
+    Uint64(u64),
+    Double(f64),
+    Complex(f64, f64),
+    Pair(Box<Pmt>, Box<Pmt>),
+    Vector(Vec<Pmt>),
+    Tuple(Vec<Pmt>),
+}
+
+impl Pmt {
+    /// A dictionary: a list of `(key . value)` pairs in serialization order.
+    fn dict(entries: Vec<(&str, Pmt)>) -> Self {
+        entries.into_iter().rev().fold(Pmt::Null, |rest, (key, value)| {
+            let entry = Pmt::Pair(Box::new(Pmt::Symbol(key.to_string())), Box::new(value));
+            Pmt::Pair(Box::new(entry), Box::new(rest))
+        })
+    }
+
+    /// Look up a key in a dictionary.
+    fn get(&self, key: &str) -> Option<&Pmt> {
+        let mut node = self;
+        while let Pmt::Pair(entry, rest) = node {
+            if let Pmt::Pair(k, v) = entry.as_ref() {
+                if matches!(k.as_ref(), Pmt::Symbol(s) if s == key) {
+                    return Some(v);
+                }
+            }
+            node = rest;
+        }
+        None
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Pmt::Double(v) => Some(v),
+            Pmt::Int32(v) => Some(v as f64),
+            Pmt::Int64(v) => Some(v as f64),
+            Pmt::Uint64(v) => Some(v as f64),
+            _ => None,
+        }
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Pmt::Uint64(v) => Some(v),
+            Pmt::Int32(v) => u64::try_from(v).ok(),
+            Pmt::Int64(v) => u64::try_from(v).ok(),
+            _ => None,
+        }
+    }
+
+    fn serialize(&self, out: &mut Vec<u8>) {
+        match self {
+            Pmt::Bool(true) => out.push(PST_TRUE),
+            Pmt::Bool(false) => out.push(PST_FALSE),
+            Pmt::Null => out.push(PST_NULL),
+            Pmt::Symbol(s) => {
+                out.push(PST_SYMBOL);
+                out.extend_from_slice(&(s.len() as u16).to_be_bytes());
+                out.extend_from_slice(s.as_bytes());
+            }
+            Pmt::Int32(v) => {
+                out.push(PST_INT32);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            Pmt::Int64(v) => {
+                out.push(PST_INT64);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            Pmt::Uint64(v) => {
+                out.push(PST_UINT64);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            Pmt::Double(v) => {
+                out.push(PST_DOUBLE);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            Pmt::Complex(re, im) => {
+                out.push(PST_COMPLEX);
+                out.extend_from_slice(&re.to_be_bytes());
+                out.extend_from_slice(&im.to_be_bytes());
+            }
+            Pmt::Pair(car, cdr) => {
+                out.push(PST_PAIR);
+                car.serialize(out);
+                cdr.serialize(out);
+            }
+            Pmt::Vector(items) | Pmt::Tuple(items) => {
+                out.push(if matches!(self, Pmt::Vector(_)) { PST_VECTOR } else { PST_TUPLE });
+                out.extend_from_slice(&(items.len() as u32).to_be_bytes());
+                for item in items {
+                    item.serialize(out);
+                }
+            }
+        }
+    }
+
+    /// Deserialize one value, returning it and the bytes consumed.
+    fn deserialize(bytes: &[u8]) -> SdrResult<(Pmt, usize)> {
+        let truncated = || SdrError::ConfigError("Truncated GNU Radio header".to_string());
+        let take = |pos: usize, n: usize| bytes.get(pos..pos + n).ok_or_else(truncated);
+        let tag = *bytes.first().ok_or_else(truncated)?;
+
+        Ok(match tag {
+            PST_TRUE => (Pmt::Bool(true), 1),
+            PST_FALSE => (Pmt::Bool(false), 1),
+            PST_NULL => (Pmt::Null, 1),
+            PST_SYMBOL => {
+                let len = u16::from_be_bytes(take(1, 2)?.try_into().expect("2 bytes")) as usize;
+                (Pmt::Symbol(String::from_utf8_lossy(take(3, len)?).into_owned()), 3 + len)
+            }
+            PST_INT32 => (Pmt::Int32(i32::from_be_bytes(take(1, 4)?.try_into().expect("4 bytes"))), 5),
+            PST_INT64 => (Pmt::Int64(i64::from_be_bytes(take(1, 8)?.try_into().expect("8 bytes"))), 9),
+            PST_UINT64 => (Pmt::Uint64(u64::from_be_bytes(take(1, 8)?.try_into().expect("8 bytes"))), 9),
+            PST_DOUBLE => (Pmt::Double(f64::from_be_bytes(take(1, 8)?.try_into().expect("8 bytes"))), 9),
+            PST_COMPLEX => (
+                Pmt::Complex(
+                    f64::from_be_bytes(take(1, 8)?.try_into().expect("8 bytes")),
+                    f64::from_be_bytes(take(9, 8)?.try_into().expect("8 bytes")),
+                ),
+                17,
+            ),
+            PST_PAIR => {
+                let (car, n1) = Pmt::deserialize(&bytes[1..])?;
+                let (cdr, n2) = Pmt::deserialize(&bytes[1 + n1..])?;
+                (Pmt::Pair(Box::new(car), Box::new(cdr)), 1 + n1 + n2)
+            }
+            PST_VECTOR | PST_TUPLE => {
+                let len = u32::from_be_bytes(take(1, 4)?.try_into().expect("4 bytes")) as usize;
+                let mut pos = 5;
+                let mut items = Vec::with_capacity(len.min(64));
+                for _ in 0..len {
+                    let (item, n) = Pmt::deserialize(bytes.get(pos..).ok_or_else(truncated)?)?;
+                    items.push(item);
+                    pos += n;
+                }
+                (if tag == PST_VECTOR { Pmt::Vector(items) } else { Pmt::Tuple(items) }, pos)
+            }
+            other => {
+                return Err(SdrError::Unsupported(format!("PMT type 0x{:02x} in GNU Radio header", other)))
+            }
+        })
+    }
+}
+
+/// Whether the bytes start with a GNU Radio metadata header.
+pub(crate) fn looks_like_header(bytes: &[u8]) -> bool {
+    bytes.starts_with(&[PST_PAIR, PST_PAIR, PST_SYMBOL])
+        && Pmt::deserialize(bytes).is_ok_and(|(header, _)| header.get("strt").is_some())
+}
+
+/// One parsed segment header.
+#[derive(Debug, Clone)]
+struct SegmentHeader {
+    sample_rate: f64,
+    /// Seconds and fractional seconds of the first sample
+    rx_time: Option<(u64, f64)>,
+    frequency: Option<f64>,
+    format: SampleFormat,
+    /// Header plus extras length
+    header_len: u64,
+    /// Segment length in bytes
+    bytes: u64,
+}
+
+impl SegmentHeader {
+    /// Parse a header and its extras from the start of `bytes`.
+    fn parse(bytes: &[u8]) -> SdrResult<Self> {
+        let (header, consumed) = Pmt::deserialize(bytes)?;
+        let field = |key: &str| {
+            header.get(key).ok_or_else(|| SdrError::ConfigError(format!("GNU Radio header has no {}", key)))
+        };
+        let int = |key: &str| field(key).and_then(|v| {
+            v.as_f64().ok_or_else(|| SdrError::ConfigError(format!("GNU Radio header {} is not a number", key)))
+        });
+
+        let item_type = int("type")? as i32;
+        let size = int("size")? as usize;
+        let complex = matches!(field("cplx")?, Pmt::Bool(true));
+        let format = match (item_type, size, complex) {
+            (TYPE_FLOAT, 8, true) => SampleFormat::ComplexFloat32,
+            (TYPE_SHORT, 4, true) => SampleFormat::ComplexInt16,
+            (TYPE_BYTE, 2, true) => SampleFormat::ComplexInt8,
+            _ => {
+                return Err(SdrError::Unsupported(format!(
+                    "GNU Radio items of type {} size {} ({})",
+                    item_type,
+                    size,
+                    if complex { "complex" } else { "real" }
+                )))
+            }
+        };
+        let rx_time = match header.get("rx_time") {
+            Some(Pmt::Tuple(t)) if t.len() == 2 => t[0].as_u64().zip(t[1].as_f64()),
+            _ => None,
+        };
+        let header_len = field("strt")?.as_u64().unwrap_or(HEADER_LEN as u64);
+
+        // Extras are best effort: an unknown PMT type there is not fatal
+        let frequency = bytes
+            .get(consumed..header_len as usize)
+            .filter(|extras| !extras.is_empty())
+            .and_then(|extras| Pmt::deserialize(extras).ok())
+            .and_then(|(extras, _)| extras.get("rx_freq").and_then(Pmt::as_f64));
+
+        Ok(Self {
+            sample_rate: int("rx_rate")?,
+            rx_time,
+            frequency,
+            format,
+            header_len,
+            bytes: field("bytes")?.as_u64().unwrap_or(0),
+        })
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        let (item_type, size) = match self.format {
+            SampleFormat::ComplexInt16 => (TYPE_SHORT, 4),
+            SampleFormat::ComplexInt8 => (TYPE_BYTE, 2),
+            _ => (TYPE_FLOAT, 8),
+        };
+        let (secs, frac) = self.rx_time.unwrap_or((0, 0.0));
+        let extras = self.frequency.map(|f| {
+            let mut out = Vec::new();
+            Pmt::dict(vec![("rx_freq", Pmt::Double(f))]).serialize(&mut out);
+            out
+        });
+
+        // Same key order as gr::blocks::file_meta_sink (dict_add prepends)
+        let mut out = Vec::with_capacity(HEADER_LEN + 32);
+        Pmt::dict(vec![
+            ("bytes", Pmt::Uint64(self.bytes)),
+            ("strt", Pmt::Uint64((HEADER_LEN + extras.as_ref().map_or(0, Vec::len)) as u64)),
+            ("cplx", Pmt::Bool(true)),
+            ("type", Pmt::Int32(item_type)),
+            ("size", Pmt::Int32(size)),
+            ("rx_time", Pmt::Tuple(vec![Pmt::Uint64(secs), Pmt::Double(frac)])),
+            ("rx_rate", Pmt::Double(self.sample_rate)),
+            ("version", Pmt::Int32(0)),
+        ])
+        .serialize(&mut out);
+        debug_assert_eq!(out.len(), HEADER_LEN);
+        out.extend(extras.unwrap_or_default());
+        out
+    }
+
+    fn datetime(&self) -> Option<String> {
+        let (secs, frac) = self.rx_time.filter(|(s, f)| *s > 0 || *f > 0.0)?;
+        chrono::DateTime::from_timestamp(secs as i64, (frac * 1e9).round() as u32).map(|t| t.to_rfc3339())
+    }
+}
+
+/// A run of samples in the data file.
+#[derive(Debug, Clone)]
+struct Segment {
+    offset: u64,
+    start: u64,
+    samples: u64,
+}
+
+/// Reader for GNU Radio metadata files, inline or detached headers.
+pub struct GnuRadioReader {
+    meta: SigMfMeta,
+    format: SampleFormat,
+    file: BufReader<File>,
+    segments: Vec<Segment>,
+    position: u64,
+    total_samples: u64,
+}
+
+impl GnuRadioReader {
+    /// Open a recording.
+    ///
+    /// Pass the data file (its `.hdr` is picked up when present) or the
+    /// `.hdr` file itself.
+    pub fn open<P: AsRef<Path>>(path: P) -> SdrResult<Self> {
+        let path = path.as_ref();
+        let (data_path, header_path) = if path.extension().is_some_and(|e| e == "hdr") {
+            (path.with_extension(""), Some(path.to_path_buf()))
+        } else if hdr_path(path).exists() {
+            (path.to_path_buf(), Some(hdr_path(path)))
+        } else {
+            (path.to_path_buf(), None)
+        };
+
+        let open = |p: &Path| {
+            File::open(p).map_err(|e| SdrError::ConfigError(format!("Failed to open {}: {}", p.display(), e)))
+        };
+        let read_err = |e: std::io::Error| SdrError::HardwareError(format!("Read failed: {}", e));
+        let data_file = open(&data_path)?;
+        let data_len = data_file.metadata().map_err(read_err)?.len();
+        let mut file = BufReader::new(data_file);
+
+        // (header, byte offset of the segment in the data file)
+        let mut headers: Vec<(SegmentHeader, u64)> = Vec::new();
+        match header_path {
+            Some(header_path) => {
+                let mut bytes = Vec::new();
+                open(&header_path)?.read_to_end(&mut bytes).map_err(read_err)?;
+                let (mut pos, mut offset) = (0usize, 0u64);
+                while pos < bytes.len() {
+                    let header = SegmentHeader::parse(&bytes[pos..])?;
+                    pos += header.header_len as usize;
+                    let segment_offset = offset;
+                    offset += header.bytes;
+                    headers.push((header, segment_offset));
+                }
+            }
+            None => {
+                let mut pos = 0u64;
+                while pos < data_len {
+                    let mut bytes = vec![0u8; (data_len - pos).min(4096) as usize];
+                    file.seek(SeekFrom::Start(pos)).map_err(read_err)?;
+                    file.read_exact(&mut bytes).map_err(read_err)?;
+                    let header = SegmentHeader::parse(&bytes)?;
+                    let segment_offset = pos + header.header_len;
+                    // An unfinished recording leaves zero bytes in its last header
+                    let bytes = match header.bytes {
+                        0 => data_len.saturating_sub(segment_offset),
+                        n => n,
+                    };
+                    pos = segment_offset + bytes;
+                    headers.push((SegmentHeader { bytes, ..header }, segment_offset));
+                }
+            }
+        }
+        if let Some((last, offset)) = headers.last_mut() {
+            if last.bytes == 0 {
+                last.bytes = data_len.saturating_sub(*offset);
+            }
+        }
+
+        let (first, _) = headers.first().cloned().ok_or_else(|| {
+            SdrError::ConfigError(format!("{}: no GNU Radio header found", path.display()))
+        })?;
+        let format = first.format;
+        let bps = format.bytes_per_sample() as u64;
+
+        let mut meta = SigMfMeta::new(first.sample_rate, first.frequency.unwrap_or(0.0), sigmf_datatype(format));
+        meta.global.datetime = first.datetime();
+        meta.captures[0].datetime = first.datetime();
+
+        let mut segments = Vec::with_capacity(headers.len());
+        let mut start = 0u64;
+        let mut frequency = first.frequency;
+        let mut expected_time: Option<f64> = None;
+        for (header, offset) in &headers {
+            if header.format != format {
+                return Err(SdrError::Unsupported(format!(
+                    "{}: item type changes between segments",
+                    path.display()
+                )));
+            }
+            let time = header.rx_time.map(|(s, f)| s as f64 + f);
+            let jumped = matches!((time, expected_time), (Some(t), Some(e)) if (t - e).abs() > 1e-6);
+            let retuned = header.frequency.is_some() && header.frequency != frequency;
+            if start > 0 && (jumped || retuned) {
+                meta.captures.push(SigMfCapture {
+                    sample_start: start,
+                    frequency: header.frequency.or(frequency),
+                    datetime: header.datetime(),
+                    ..Default::default()
+                });
+            }
+            frequency = header.frequency.or(frequency);
+
+            let samples = header.bytes / bps;
+            expected_time = time.map(|t| t + samples as f64 / header.sample_rate);
+            segments.push(Segment { offset: *offset, start, samples });
+            start += samples;
+        }
+
+        Ok(Self {
+            meta,
+            format,
+            file,
+            segments,
+            position: 0,
+            total_samples: start,
+        })
+    }
+}
+
+impl IqSource for GnuRadioReader {
+    fn metadata(&self) -> &SigMfMeta {
+        &self.meta
+    }
+
+    fn total_samples(&self) -> u64 {
+        self.total_samples
+    }
+
+    fn read_samples(&mut self, buffer: &mut [IQSample]) -> SdrResult<usize> {
+        let bps = self.format.bytes_per_sample() as u64;
+        let mut done = 0;
+        while done < buffer.len() && self.position < self.total_samples {
+            let Some(segment) = self.segments.iter().find(|s| self.position < s.start + s.samples) else {
+                break;
+            };
+            let skip = self.position - segment.start;
+            let count = ((segment.samples - skip) as usize).min(buffer.len() - done);
+            let mut bytes = vec![0u8; count * bps as usize];
+            self.file
+                .seek(SeekFrom::Start(segment.offset + skip * bps))
+                .and_then(|_| self.file.read_exact(&mut bytes))
+                .map_err(|e| SdrError::HardwareError(format!("Read failed: {}", e)))?;
+            for (out, sample) in buffer[done..].iter_mut().zip(self.format.decode(&bytes)) {
+                *out = sample;
+            }
+            done += count;
+            self.position += count as u64;
+        }
+        Ok(done)
+    }
+}
+
+/// Writer producing a single-segment GNU Radio metadata file.
+pub struct GnuRadioWriter {
+    meta: SigMfMeta,
+    header: SegmentHeader,
+    file: BufWriter<File>,
+    /// Detached header file, `None` for an inline header
+    header_path: Option<PathBuf>,
+}
+
+impl GnuRadioWriter {
+    /// Create a recording; `format` must be `cf32`, `ci16` or `ci8`.
+    ///
+    /// The sample rate, `rx_freq` and `rx_time` come from `meta`.
+    pub fn create<P: AsRef<Path>>(path: P, meta: &SigMfMeta, format: SampleFormat, detached: bool) -> SdrResult<Self> {
+        if matches!(format, SampleFormat::ComplexUint8) {
+            return Err(SdrError::Unsupported("GNU Radio has no unsigned complex items".to_string()));
+        }
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to create {}: {}", path.display(), e))
+        })?;
+        let rx_time = meta
+            .global
+            .datetime
+            .as_deref()
+            .and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok())
+            .map(|t| (t.timestamp().max(0) as u64, t.timestamp_subsec_nanos() as f64 / 1e9));
+        let header = SegmentHeader {
+            sample_rate: meta.global.sample_rate,
+            rx_time,
+            frequency: Some(meta.frequency()),
+            format,
+            header_len: 0,
+            bytes: 0,
+        };
+
+        let mut out_meta = SigMfMeta::new(meta.global.sample_rate, meta.frequency(), sigmf_datatype(format));
+        out_meta.global.datetime = header.datetime();
+        out_meta.captures[0].datetime = header.datetime();
+
+        let mut writer = Self {
+            meta: out_meta,
+            header,
+            file: BufWriter::new(file),
+            header_path: detached.then(|| hdr_path(path)),
+        };
+        if !detached {
+            let placeholder = writer.header.serialize();
+            writer.file.write_all(&placeholder).map_err(|e| {
+                SdrError::HardwareError(format!("Write failed: {}", e))
+            })?;
+        }
+        Ok(writer)
+    }
+}
+
+impl IqSink for GnuRadioWriter {
+    fn write_samples(&mut self, samples: &[IQSample]) -> SdrResult<usize> {
+        let bytes = self.header.format.encode(samples);
+        self.file.write_all(&bytes).map_err(|e| {
+            SdrError::HardwareError(format!("Write failed: {}", e))
+        })?;
+        self.header.bytes += bytes.len() as u64;
+        Ok(samples.len())
+    }
+
+    fn samples_written(&self) -> u64 {
+        self.header.bytes / self.header.format.bytes_per_sample() as u64
+    }
+
+    fn finish(mut self: Box<Self>) -> SdrResult<SigMfMeta> {
+        let write_err = |e: std::io::Error| SdrError::HardwareError(format!("Write failed: {}", e));
+        let header = self.header.serialize();
+        match &self.header_path {
+            Some(path) => std::fs::write(path, &header).map_err(write_err)?,
+            None => {
+                self.file.seek(SeekFrom::Start(0)).map_err(write_err)?;
+                self.file.write_all(&header).map_err(write_err)?;
+            }
+        }
+        self.file.flush().map_err(write_err)?;
+        Ok(self.meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn header(bytes: u64, secs: u64, frequency: f64) -> Vec<u8> {
+        SegmentHeader {
+            sample_rate: 1e6,
+            rx_time: Some((secs, 0.25)),
+            frequency: Some(frequency),
+            format: SampleFormat::ComplexInt16,
+            header_len: 0,
+            bytes,
+        }
+        .serialize()
+    }
+
+    #[test]
+    fn test_header_layout() {
+        let bytes = header(4000, 1_700_000_000, 915e6);
+        // 149-byte header plus the rx_freq extras dictionary
+        assert_eq!(bytes.len(), HEADER_LEN + 22);
+        assert!(looks_like_header(&bytes));
+        assert!(!looks_like_header(&[0u8; 200]));
+
+        let parsed = SegmentHeader::parse(&bytes).unwrap();
+        assert_eq!(parsed.header_len, bytes.len() as u64);
+        assert_eq!(parsed.bytes, 4000);
+        assert_eq!(parsed.format, SampleFormat::ComplexInt16);
+        assert_eq!(parsed.frequency, Some(915e6));
+        assert_eq!(parsed.datetime().as_deref(), Some("2023-11-14T22:13:20.250+00:00"));
+    }
+
+    #[test]
+    fn test_inline_segments_become_captures() {
+        // Three segments: the second continues the first, the third is retuned
+        let samples: Vec<IQSample> = (0..300).map(|i| IQSample::new(i as f64 / 1000.0, -0.5)).collect();
+        let encode = |s: &[IQSample]| SampleFormat::ComplexInt16.encode(s);
+        let mut file = Vec::new();
+        file.extend(header(400, 100, 915e6));
+        file.extend(encode(&samples[..100]));
+        let mut second = SegmentHeader::parse(&header(800, 100, 915e6)).unwrap();
+        second.rx_time = Some((100, 0.25 + 100.0 / 1e6));
+        file.extend(second.serialize());
+        file.extend(encode(&samples[100..300]));
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("capture.bin");
+        let mut third = header(0, 200, 916e6);
+        third.extend(encode(&samples[..50]));
+        file.extend(third);
+        std::fs::write(&path, &file).unwrap();
+
+        let mut reader = GnuRadioReader::open(&path).unwrap();
+        assert_eq!(reader.total_samples(), 350);
+        let captures = &reader.metadata().captures;
+        assert_eq!(captures.len(), 2);
+        assert_eq!(captures[1].sample_start, 300);
+        assert_eq!(captures[1].frequency, Some(916e6));
+
+        let read = reader.read_all().unwrap();
+        assert_eq!(read.len(), 350);
+        assert!((read[150].re - 0.15).abs() < 1e-4);
+        assert!((read[349].re - 0.049).abs() < 1e-4);
+    }
+}
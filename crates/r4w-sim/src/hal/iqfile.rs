@@ -0,0 +1,696 @@
+//! # IQ File Formats
+//!
+//! A common interface over the IQ recording formats found in the wild, so
+//! tools can read and write any of them without caring which one they
+//! were handed. Every format maps its header into a [`SigMfMeta`] (sample
+//! rate, centre frequency, datatype, start time), which is also what a
+//! sink is created from.
+//!
+//! | Format                       | Extensions                     | Metadata                                    |
+//! |------------------------------|--------------------------------|---------------------------------------------|
+//! | SigMF                        | `.sigmf-meta`/`.sigmf-data`    | Everything, `core:sha512` verified          |
+//! | SigMF archive                | `.sigmf`                       | As SigMF, first recording of the archive    |
+//! | WAV IQ (SDR#, SDRuno, HDSDR) | `.wav`                         | Sample rate; `auxi` frequency and times     |
+//! | GNU Radio file meta sink     | any, `.hdr` when detached      | `rx_rate`, `rx_time`, `rx_freq` per segment |
+//! | HackRF (`hackrf_transfer`)   | `.cs8`, `.hackrf`              | File name hints only                        |
+//! | Raw cf32/ci16/ci8/cu8        | `.cf32`, `.cfile`, `.raw`, ... | File name hints only                        |
+//!
+//! Headerless formats take the sample rate and frequency from the caller,
+//! or from recorder file names such as
+//! `SDRSharp_20240101_120000Z_433920000Hz_IQ.wav` and
+//! `gqrx_20240101_120000_433920000_2000000_fc.raw`.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use r4w_sim::hal::iqfile::IqFileFormat;
+//!
+//! let format = IqFileFormat::detect("capture.wav").unwrap();
+//! let mut source = format.open("capture.wav", None, None)?;
+//! let mut sink = IqFileFormat::SigMf.create("capture", source.metadata())?;
+//!
+//! let mut buffer = vec![Default::default(); 65536];
+//! loop {
+//!     let n = source.read_samples(&mut buffer)?;
+//!     if n == 0 { break; }
+//!     sink.write_samples(&buffer[..n])?;
+//! }
+//! sink.finish()?;
+//! ```
+
+use r4w_core::types::IQSample;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::gnuradio::{GnuRadioReader, GnuRadioWriter};
+use super::sigmf::{SigMfArchive, SigMfMeta, SigMfReader, SigMfWriter};
+use super::wav::{WavIqReader, WavIqWriter};
+use super::SampleFormat;
+use crate::device::{SdrError, SdrResult};
+
+/// A readable IQ recording.
+pub trait IqSource {
+    /// Recording metadata, mapped into SigMF terms.
+    fn metadata(&self) -> &SigMfMeta;
+
+    /// Total samples (per channel).
+    fn total_samples(&self) -> u64;
+
+    /// Read samples into a buffer, returning how many were read (0 at the end).
+    ///
+    /// Multi-channel recordings fill the buffer with interleaved channels.
+    fn read_samples(&mut self, buffer: &mut [IQSample]) -> SdrResult<usize>;
+
+    /// Verify the recording's checksum.
+    ///
+    /// Returns `false` when the format carries no checksum and an error
+    /// when the checksum does not match.
+    fn verify(&mut self) -> SdrResult<bool> {
+        Ok(false)
+    }
+
+    /// Sample rate in Hz.
+    fn sample_rate(&self) -> f64 {
+        self.metadata().global.sample_rate
+    }
+
+    /// Centre frequency in Hz (0 when unknown).
+    fn frequency(&self) -> f64 {
+        self.metadata().frequency()
+    }
+
+    /// Read all remaining samples.
+    fn read_all(&mut self) -> SdrResult<Vec<IQSample>> {
+        let mut samples = Vec::new();
+        let mut buffer = vec![IQSample::new(0.0, 0.0); 65536];
+        loop {
+            let n = self.read_samples(&mut buffer)?;
+            if n == 0 {
+                return Ok(samples);
+            }
+            samples.extend_from_slice(&buffer[..n]);
+        }
+    }
+}
+
+/// A writable IQ recording.
+pub trait IqSink {
+    /// Append samples, returning how many were written.
+    fn write_samples(&mut self, samples: &[IQSample]) -> SdrResult<usize>;
+
+    /// Samples written so far (per channel).
+    fn samples_written(&self) -> u64;
+
+    /// Finalize headers and return the metadata that was recorded.
+    fn finish(self: Box<Self>) -> SdrResult<SigMfMeta>;
+}
+
+impl IqSource for SigMfReader {
+    fn metadata(&self) -> &SigMfMeta {
+        SigMfReader::metadata(self)
+    }
+
+    fn total_samples(&self) -> u64 {
+        SigMfReader::total_samples(self)
+    }
+
+    fn read_samples(&mut self, buffer: &mut [IQSample]) -> SdrResult<usize> {
+        SigMfReader::read_samples(self, buffer)
+    }
+
+    fn verify(&mut self) -> SdrResult<bool> {
+        SigMfReader::verify(self)
+    }
+}
+
+impl IqSink for SigMfWriter {
+    fn write_samples(&mut self, samples: &[IQSample]) -> SdrResult<usize> {
+        SigMfWriter::write_samples(self, samples)
+    }
+
+    fn samples_written(&self) -> u64 {
+        SigMfWriter::samples_written(self)
+    }
+
+    fn finish(self: Box<Self>) -> SdrResult<SigMfMeta> {
+        self.close()
+    }
+}
+
+/// IQ recording format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IqFileFormat {
+    /// SigMF `.sigmf-meta`/`.sigmf-data` pair
+    SigMf,
+    /// SigMF `.sigmf` tar archive
+    SigMfArchive,
+    /// Stereo WAV with I on the left and Q on the right channel
+    Wav,
+    /// GNU Radio file meta sink, header inline or in a `.hdr` file
+    GnuRadio {
+        /// Headers live in `<file>.hdr` rather than inline
+        detached: bool,
+    },
+    /// Headerless interleaved samples (HackRF is `ci8`, RTL-SDR `cu8`)
+    Raw(SampleFormat),
+}
+
+impl IqFileFormat {
+    /// Names accepted by [`parse`](Self::parse).
+    pub fn names() -> &'static [&'static str] {
+        &[
+            "sigmf",
+            "sigmf-archive",
+            "wav",
+            "gnuradio",
+            "gnuradio-detached",
+            "hackrf",
+            "rtlsdr",
+            "raw-cf32",
+            "raw-ci16",
+            "raw-ci8",
+            "raw-cu8",
+        ]
+    }
+
+    /// Parse a format name such as `wav`, `gnuradio` or `raw-ci16`.
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        match name.as_str() {
+            "sigmf" => Some(Self::SigMf),
+            "sigmf-archive" | "sigmf-tar" => Some(Self::SigMfArchive),
+            "wav" | "wave" => Some(Self::Wav),
+            "gnuradio" | "gr" | "gnuradio-meta" => Some(Self::GnuRadio { detached: false }),
+            "gnuradio-detached" | "gr-detached" => Some(Self::GnuRadio { detached: true }),
+            "hackrf" => Some(Self::Raw(SampleFormat::ComplexInt8)),
+            "rtlsdr" | "rtl-sdr" => Some(Self::Raw(SampleFormat::ComplexUint8)),
+            other => SampleFormat::parse(other.strip_prefix("raw-").unwrap_or(other)).map(Self::Raw),
+        }
+    }
+
+    /// Canonical name.
+    pub fn name(&self) -> String {
+        match self {
+            Self::SigMf => "sigmf".to_string(),
+            Self::SigMfArchive => "sigmf-archive".to_string(),
+            Self::Wav => "wav".to_string(),
+            Self::GnuRadio { detached: false } => "gnuradio".to_string(),
+            Self::GnuRadio { detached: true } => "gnuradio-detached".to_string(),
+            Self::Raw(format) => format!("raw-{}", format.name()),
+        }
+    }
+
+    /// Format implied by a file name's extension alone.
+    ///
+    /// Use this for files about to be written; [`Self::detect`] also looks
+    /// at the contents of existing files.
+    pub fn from_extension<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+        match ext.as_str() {
+            "sigmf-meta" | "sigmf-data" => Some(Self::SigMf),
+            "sigmf" => Some(Self::SigMfArchive),
+            "hdr" => Some(Self::GnuRadio { detached: true }),
+            "wav" | "wave" => Some(Self::Wav),
+            "cf32" | "fc32" | "cfile" | "iq" | "raw" => Some(Self::Raw(SampleFormat::ComplexFloat32)),
+            "ci16" | "cs16" | "sc16" => Some(Self::Raw(SampleFormat::ComplexInt16)),
+            "ci8" | "cs8" | "sc8" | "hackrf" => Some(Self::Raw(SampleFormat::ComplexInt8)),
+            "cu8" => Some(Self::Raw(SampleFormat::ComplexUint8)),
+            _ => None,
+        }
+    }
+
+    /// Detect the format of an existing file from its extension and contents.
+    ///
+    /// Returns `None` for files that look headerless but have no telling
+    /// extension.
+    pub fn detect<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let path = path.as_ref();
+        let by_extension = Self::from_extension(path);
+        if matches!(by_extension, Some(Self::SigMf | Self::SigMfArchive | Self::GnuRadio { .. })) {
+            return by_extension;
+        }
+
+        let mut head = [0u8; 512];
+        let len = File::open(path).and_then(|mut f| f.read(&mut head)).unwrap_or(0);
+        let head = &head[..len];
+        if head.len() >= 12 && (&head[0..4] == b"RIFF" || &head[0..4] == b"RF64") && &head[8..12] == b"WAVE" {
+            return Some(Self::Wav);
+        }
+        if super::gnuradio::looks_like_header(head) {
+            return Some(Self::GnuRadio { detached: false });
+        }
+        if head.len() >= 262 && &head[257..262] == b"ustar" {
+            return Some(Self::SigMfArchive);
+        }
+        if hdr_path(path).exists() {
+            return Some(Self::GnuRadio { detached: true });
+        }
+
+        by_extension.filter(|f| *f != Self::Wav)
+    }
+
+    /// Open a recording for reading.
+    ///
+    /// `sample_rate` and `frequency` are only used by raw files, which
+    /// otherwise fall back to hints in the file name.
+    pub fn open<P: AsRef<Path>>(
+        &self,
+        path: P,
+        sample_rate: Option<f64>,
+        frequency: Option<f64>,
+    ) -> SdrResult<Box<dyn IqSource>> {
+        let path = path.as_ref();
+        Ok(match self {
+            Self::SigMf => Box::new(SigMfReader::open(path)?),
+            Self::SigMfArchive => Box::new(SigMfReader::open_archive(path, None)?),
+            Self::Wav => Box::new(WavIqReader::open(path)?),
+            Self::GnuRadio { .. } => Box::new(GnuRadioReader::open(path)?),
+            Self::Raw(format) => Box::new(RawIqReader::open(path, *format, sample_rate, frequency)?),
+        })
+    }
+
+    /// Create a recording for writing, described by `meta`.
+    ///
+    /// The sample rate, frequency, start time and (where the format can
+    /// store it) the datatype are taken from `meta`. SigMF outputs keep
+    /// the rest of the metadata, including annotations.
+    pub fn create<P: AsRef<Path>>(&self, path: P, meta: &SigMfMeta) -> SdrResult<Box<dyn IqSink>> {
+        let path = path.as_ref();
+        let format = SampleFormat::parse(&meta.global.datatype);
+        Ok(match self {
+            Self::SigMf => Box::new(sigmf_writer(path, meta)?),
+            Self::SigMfArchive => Box::new(SigMfArchiveWriter::create(path, meta)?),
+            Self::Wav => {
+                let format = match format {
+                    Some(f @ (SampleFormat::ComplexFloat32 | SampleFormat::ComplexUint8)) => f,
+                    _ => SampleFormat::ComplexInt16,
+                };
+                Box::new(WavIqWriter::create(path, meta, format)?)
+            }
+            Self::GnuRadio { detached } => {
+                let format = match format {
+                    Some(f @ (SampleFormat::ComplexInt16 | SampleFormat::ComplexInt8)) => f,
+                    _ => SampleFormat::ComplexFloat32,
+                };
+                Box::new(GnuRadioWriter::create(path, meta, format, *detached)?)
+            }
+            Self::Raw(format) => Box::new(RawIqWriter::create(path, *format)?),
+        })
+    }
+}
+
+impl std::fmt::Display for IqFileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+/// Detached GNU Radio header path for a data file.
+pub(crate) fn hdr_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".hdr");
+    PathBuf::from(name)
+}
+
+/// SigMF datatype for a sample format.
+pub fn sigmf_datatype(format: SampleFormat) -> &'static str {
+    match format {
+        SampleFormat::ComplexFloat32 => "cf32_le",
+        SampleFormat::ComplexInt16 => "ci16_le",
+        SampleFormat::ComplexInt8 => "ci8",
+        SampleFormat::ComplexUint8 => "cu8",
+    }
+}
+
+/// SigMF writer carrying over everything but the checksum from `meta`.
+fn sigmf_writer(path: &Path, meta: &SigMfMeta) -> SdrResult<SigMfWriter> {
+    let datatype = SampleFormat::parse(&meta.global.datatype).map_or("cf32_le", sigmf_datatype);
+    let channels = meta.global.num_channels.unwrap_or(1).max(1) as usize;
+    let mut writer = SigMfWriter::create_multichannel(path, meta.global.sample_rate, meta.frequency(), datatype, channels)?;
+
+    let out = writer.metadata_mut();
+    let created = out.global.datetime.clone();
+    *out = meta.clone();
+    out.global.datatype = datatype.to_string();
+    out.global.num_channels = Some(channels as u32);
+    out.global.sha512 = None;
+    if out.global.datetime.is_none() {
+        out.global.datetime = created;
+    }
+    if out.captures.is_empty() {
+        out.captures = SigMfMeta::default().captures;
+    }
+    Ok(writer)
+}
+
+/// Writes a `.sigmf` archive holding a single recording.
+///
+/// Samples go to a split recording in a scratch directory next to the
+/// archive, which is packed and removed on [`finish`](IqSink::finish).
+pub struct SigMfArchiveWriter {
+    writer: SigMfWriter,
+    path: PathBuf,
+    scratch: PathBuf,
+    base: PathBuf,
+}
+
+impl SigMfArchiveWriter {
+    /// Create an archive at `path` (`.sigmf` is added if missing).
+    pub fn create<P: AsRef<Path>>(path: P, meta: &SigMfMeta) -> SdrResult<Self> {
+        let path = path.as_ref().with_extension("sigmf");
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| SdrError::ConfigError(format!("Invalid archive name {}", path.display())))?
+            .to_string();
+        let scratch = path.with_file_name(format!(".{}.sigmf-tmp", stem));
+        std::fs::create_dir_all(&scratch).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to create {}: {}", scratch.display(), e))
+        })?;
+        let base = scratch.join(&stem);
+        Ok(Self {
+            writer: sigmf_writer(&base, meta)?,
+            path,
+            scratch,
+            base,
+        })
+    }
+}
+
+impl IqSink for SigMfArchiveWriter {
+    fn write_samples(&mut self, samples: &[IQSample]) -> SdrResult<usize> {
+        self.writer.write_samples(samples)
+    }
+
+    fn samples_written(&self) -> u64 {
+        self.writer.samples_written()
+    }
+
+    fn finish(self: Box<Self>) -> SdrResult<SigMfMeta> {
+        let meta = self.writer.close()?;
+        let packed = SigMfArchive::create(&self.path, &[&self.base], None);
+        let _ = std::fs::remove_dir_all(&self.scratch);
+        packed.map(|_| meta)
+    }
+}
+
+/// Reader for headerless interleaved IQ files.
+pub struct RawIqReader {
+    meta: SigMfMeta,
+    format: SampleFormat,
+    file: BufReader<File>,
+    position: u64,
+    total_samples: u64,
+}
+
+impl RawIqReader {
+    /// Open a raw file.
+    ///
+    /// Missing parameters come from the file name; the sample rate is
+    /// required one way or the other.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        format: SampleFormat,
+        sample_rate: Option<f64>,
+        frequency: Option<f64>,
+    ) -> SdrResult<Self> {
+        let path = path.as_ref();
+        let hints = FilenameHints::parse(path);
+        let sample_rate = sample_rate.or(hints.sample_rate).ok_or_else(|| {
+            SdrError::ConfigError(format!("{}: sample rate required for raw {} input", path.display(), format))
+        })?;
+        let file = File::open(path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to open {}: {}", path.display(), e))
+        })?;
+        let len = file.metadata().map_err(|e| {
+            SdrError::HardwareError(format!("Failed to get file size: {}", e))
+        })?.len();
+
+        let mut meta = SigMfMeta::new(sample_rate, frequency.or(hints.frequency).unwrap_or(0.0), sigmf_datatype(format));
+        meta.global.datetime = hints.datetime.clone();
+        meta.captures[0].datetime = hints.datetime;
+
+        Ok(Self {
+            meta,
+            format,
+            file: BufReader::new(file),
+            position: 0,
+            total_samples: len / format.bytes_per_sample() as u64,
+        })
+    }
+}
+
+impl IqSource for RawIqReader {
+    fn metadata(&self) -> &SigMfMeta {
+        &self.meta
+    }
+
+    fn total_samples(&self) -> u64 {
+        self.total_samples
+    }
+
+    fn read_samples(&mut self, buffer: &mut [IQSample]) -> SdrResult<usize> {
+        let count = buffer.len().min((self.total_samples - self.position) as usize);
+        let mut bytes = vec![0u8; count * self.format.bytes_per_sample()];
+        self.file.read_exact(&mut bytes).map_err(|e| {
+            SdrError::HardwareError(format!("Read failed: {}", e))
+        })?;
+        for (out, sample) in buffer.iter_mut().zip(self.format.decode(&bytes)) {
+            *out = sample;
+        }
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+/// Writer for headerless interleaved IQ files.
+pub struct RawIqWriter {
+    format: SampleFormat,
+    file: BufWriter<File>,
+    samples_written: u64,
+    meta: SigMfMeta,
+}
+
+impl RawIqWriter {
+    /// Create a raw file.
+    pub fn create<P: AsRef<Path>>(path: P, format: SampleFormat) -> SdrResult<Self> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to create {}: {}", path.display(), e))
+        })?;
+        Ok(Self {
+            format,
+            file: BufWriter::new(file),
+            samples_written: 0,
+            meta: SigMfMeta::new(0.0, 0.0, sigmf_datatype(format)),
+        })
+    }
+}
+
+impl IqSink for RawIqWriter {
+    fn write_samples(&mut self, samples: &[IQSample]) -> SdrResult<usize> {
+        self.file.write_all(&self.format.encode(samples)).map_err(|e| {
+            SdrError::HardwareError(format!("Write failed: {}", e))
+        })?;
+        self.samples_written += samples.len() as u64;
+        Ok(samples.len())
+    }
+
+    fn samples_written(&self) -> u64 {
+        self.samples_written
+    }
+
+    fn finish(mut self: Box<Self>) -> SdrResult<SigMfMeta> {
+        self.file.flush().map_err(|e| {
+            SdrError::HardwareError(format!("Flush failed: {}", e))
+        })?;
+        Ok(self.meta)
+    }
+}
+
+/// Recording parameters encoded in a recorder's file name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilenameHints {
+    /// Centre frequency in Hz
+    pub frequency: Option<f64>,
+    /// Sample rate in Hz
+    pub sample_rate: Option<f64>,
+    /// Start time (RFC 3339, UTC)
+    pub datetime: Option<String>,
+}
+
+impl FilenameHints {
+    /// Parse SDR#/SDRuno/HDSDR (`..._20240101_120000Z_433920000Hz_IQ`)
+    /// and gqrx (`gqrx_20240101_120000_433920000_2000000_fc`) names.
+    pub fn parse<P: AsRef<Path>>(path: P) -> Self {
+        let stem = path.as_ref().file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let tokens: Vec<&str> = stem.split('_').collect();
+        let mut hints = Self::default();
+
+        for pair in tokens.windows(2) {
+            let (date, time) = (pair[0], pair[1].trim_end_matches('Z'));
+            if date.len() == 8 && time.len() == 6 && date.bytes().chain(time.bytes()).all(|b| b.is_ascii_digit()) {
+                hints.datetime = chrono::NaiveDateTime::parse_from_str(&format!("{}{}", date, time), "%Y%m%d%H%M%S")
+                    .ok()
+                    .map(|t| t.and_utc().to_rfc3339());
+                break;
+            }
+        }
+
+        if tokens.first().is_some_and(|t| t.eq_ignore_ascii_case("gqrx")) && tokens.len() >= 6 {
+            hints.frequency = tokens[3].parse().ok();
+            hints.sample_rate = tokens[4].parse().ok();
+            return hints;
+        }
+
+        for token in &tokens {
+            let Some(value) = token.strip_suffix("Hz") else { continue };
+            let (digits, scale) = match value.chars().last() {
+                Some('k') => (&value[..value.len() - 1], 1e3),
+                Some('M') => (&value[..value.len() - 1], 1e6),
+                Some('G') => (&value[..value.len() - 1], 1e9),
+                _ => (value, 1.0),
+            };
+            if let Ok(v) = digits.parse::<f64>() {
+                hints.frequency = Some(v * scale);
+            }
+        }
+        hints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn tone(len: usize) -> Vec<IQSample> {
+        (0..len)
+            .map(|i| IQSample::from_polar(0.5, i as f64 * 0.05))
+            .collect()
+    }
+
+    fn copy(source: &mut dyn IqSource, mut sink: Box<dyn IqSink>) -> SigMfMeta {
+        let mut buffer = vec![IQSample::new(0.0, 0.0); 1000];
+        loop {
+            let n = source.read_samples(&mut buffer).unwrap();
+            if n == 0 {
+                break;
+            }
+            sink.write_samples(&buffer[..n]).unwrap();
+        }
+        sink.finish().unwrap()
+    }
+
+    #[test]
+    fn test_format_names_and_detection() {
+        for name in IqFileFormat::names() {
+            let format = IqFileFormat::parse(name).unwrap();
+            if !matches!(*name, "hackrf" | "rtlsdr") {
+                assert_eq!(format.name(), *name);
+            }
+        }
+        assert_eq!(IqFileFormat::parse("hackrf"), Some(IqFileFormat::Raw(SampleFormat::ComplexInt8)));
+        assert_eq!(IqFileFormat::detect("x.sigmf-meta"), Some(IqFileFormat::SigMf));
+        assert_eq!(IqFileFormat::detect("x.sigmf"), Some(IqFileFormat::SigMfArchive));
+        assert_eq!(IqFileFormat::detect("x.cs8"), Some(IqFileFormat::Raw(SampleFormat::ComplexInt8)));
+        assert_eq!(IqFileFormat::detect("x.unknown"), None);
+
+        // Output formats ignore whatever is already on disk
+        let dir = TempDir::new().unwrap();
+        let stale = dir.path().join("out.cf32");
+        let mut wav = IqFileFormat::Wav.create(&stale, &SigMfMeta::new(48_000.0, 0.0, "cf32_le")).unwrap();
+        wav.write_samples(&tone(10)).unwrap();
+        wav.finish().unwrap();
+        std::fs::write(dir.path().join("out.cf32.hdr"), b"").unwrap();
+        assert_eq!(IqFileFormat::detect(&stale), Some(IqFileFormat::Wav));
+        assert_eq!(IqFileFormat::from_extension(&stale), Some(IqFileFormat::Raw(SampleFormat::ComplexFloat32)));
+        assert_eq!(IqFileFormat::from_extension("out.wav"), Some(IqFileFormat::Wav));
+        assert_eq!(IqFileFormat::from_extension("out.hdr"), Some(IqFileFormat::GnuRadio { detached: true }));
+        assert_eq!(IqFileFormat::from_extension("out.bin"), None);
+
+        let hints = FilenameHints::parse("SDRSharp_20240102_030405Z_433920000Hz_IQ.wav");
+        assert_eq!(hints.frequency, Some(433_920_000.0));
+        assert_eq!(hints.datetime.as_deref(), Some("2024-01-02T03:04:05+00:00"));
+        let hints = FilenameHints::parse("HDSDR_20240102_030405Z_7100kHz_RF.wav");
+        assert_eq!(hints.frequency, Some(7_100_000.0));
+        let hints = FilenameHints::parse("gqrx_20240102_030405_433920000_2000000_fc.raw");
+        assert_eq!(hints.frequency, Some(433_920_000.0));
+        assert_eq!(hints.sample_rate, Some(2_000_000.0));
+    }
+
+    #[test]
+    fn test_convert_through_every_format() {
+        let dir = TempDir::new().unwrap();
+        let samples = tone(2500);
+        let mut meta = SigMfMeta::new(250e3, 868.1e6, "cf32_le").with_description("chain");
+        meta.global.datetime = Some("2024-05-06T07:08:09+00:00".to_string());
+        meta.captures[0].datetime = meta.global.datetime.clone();
+
+        let mut sink = IqFileFormat::SigMf.create(dir.path().join("a"), &meta).unwrap();
+        sink.write_samples(&samples).unwrap();
+        sink.finish().unwrap();
+
+        // SigMF -> archive -> WAV -> GNU Radio -> detached GNU Radio -> SigMF
+        let steps = [
+            (IqFileFormat::SigMf, "a.sigmf-meta"),
+            (IqFileFormat::SigMfArchive, "b.sigmf"),
+            (IqFileFormat::Wav, "c.wav"),
+            (IqFileFormat::GnuRadio { detached: false }, "d.dat"),
+            (IqFileFormat::GnuRadio { detached: true }, "e.dat"),
+        ];
+        for (i, (format, name)) in steps.iter().enumerate() {
+            let path = dir.path().join(name);
+            assert_eq!(IqFileFormat::detect(&path), Some(*format), "{}", name);
+            let mut source = format.open(&path, None, None).unwrap();
+            assert_eq!(source.total_samples(), 2500);
+            assert_eq!(source.sample_rate(), 250e3);
+            assert_eq!(source.frequency(), 868.1e6);
+            assert_eq!(
+                source.metadata().global.datetime.as_deref().map(|d| &d[..19]),
+                Some("2024-05-06T07:08:09")
+            );
+            if *format == IqFileFormat::SigMfArchive {
+                assert_eq!(source.metadata().global.description.as_deref(), Some("chain"));
+                assert!(source.verify().unwrap());
+            }
+
+            let (next_format, next_name) = steps.get(i + 1).copied().unwrap_or((IqFileFormat::SigMf, "f"));
+            let meta = source.metadata().clone();
+            copy(source.as_mut(), next_format.create(dir.path().join(next_name), &meta).unwrap());
+        }
+
+        let mut last = SigMfReader::open(dir.path().join("f")).unwrap();
+        assert!(last.verify().unwrap());
+        // WAV stored the chain as 16-bit PCM
+        for (a, b) in samples.iter().zip(last.read_all().unwrap()) {
+            assert!((a - b).norm() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_raw_hackrf_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("gqrx_20240102_030405_915000000_2000000_fc.cs8");
+        let samples = tone(100);
+        let mut sink = IqFileFormat::parse("hackrf").unwrap().create(&path, &SigMfMeta::default()).unwrap();
+        sink.write_samples(&samples).unwrap();
+        sink.finish().unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 200);
+
+        let format = IqFileFormat::detect(&path).unwrap();
+        let mut source = format.open(&path, None, None).unwrap();
+        assert_eq!(source.sample_rate(), 2e6);
+        assert_eq!(source.frequency(), 915e6);
+        assert!(!source.verify().unwrap());
+        for (a, b) in samples.iter().zip(source.read_all().unwrap()) {
+            assert!((a - b).norm() < 0.02);
+        }
+
+        let unnamed = dir.path().join("capture.cs8");
+        std::fs::copy(&path, &unnamed).unwrap();
+        assert!(format.open(&unnamed, None, None).is_err());
+        assert_eq!(format.open(&unnamed, Some(1e6), None).unwrap().total_samples(), 100);
+    }
+}
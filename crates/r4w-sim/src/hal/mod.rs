@@ -5,6 +5,8 @@
 //! - **StreamHandle**: Streaming I/Q samples with timestamps
 //! - **TunerControl**: Frequency, sample rate, gain, bandwidth
 //! - **ClockControl**: Clock source, time source, PPS synchronization
+//! - **TimedControl**: Tuning and gain changes scheduled at a device time
+//! - **StreamAdapter**: Rate, LO offset and format negotiation between a waveform and a device
 //! - **SdrDevice**: High-level device interface combining all capabilities
 //!
 //! ## Architecture
@@ -17,7 +19,8 @@
 //! │   SdrDevice, StreamHandle, TunerControl, ClockControl       │
 //! ├───────────────┬───────────────┬─────────────────────────────┤
 //! │   Simulator   │   File I/O    │  Hardware Drivers           │
-//! │               │   (SigMF)     │  (UHD, SoapySDR, RTL-SDR)   │
+//! │               │ (SigMF, WAV,  │  (UHD, SoapySDR, RTL-SDR,   │
+//! │               │  GNU Radio)   │   rtl_tcp, VITA 49)         │
 //! ├───────────────┴───────────────┴─────────────────────────────┤
 //! │                  OS Abstraction (libc, winapi)              │
 //! └─────────────────────────────────────────────────────────────┘
@@ -43,22 +46,36 @@ use r4w_core::timing::{Timestamp, TimeSource, HardwareClock};
 use r4w_core::types::IQSample;
 use std::time::Duration;
 
+pub mod adapter;
 pub mod attenuator;
+pub mod gnuradio;
+pub mod iqfile;
 pub mod rtlsdr;
 #[cfg(feature = "rtlsdr")]
 pub mod rtlsdr_ffi;
+pub mod rtltcp;
 pub mod sigmf;
+pub mod sim;
 pub mod soapysdr;
 #[cfg(feature = "soapysdr")]
 pub mod soapysdr_ffi;
 pub mod uhd;
+pub mod vrt;
+pub mod wav;
 
 pub use crate::device::{DeviceCapabilities, DeviceInfo, SdrConfig, SdrError, SdrResult};
+pub use adapter::{AdapterOptions, DeviceLimits, Resampler, StreamAdapter, StreamPlan};
 pub use attenuator::{Attenuator, AttenuatorCapabilities, AttenuatorTestHarness, create_attenuator};
+pub use gnuradio::{GnuRadioReader, GnuRadioWriter};
+pub use iqfile::{IqFileFormat, IqSink, IqSource};
 pub use rtlsdr::RtlSdrDriver;
-pub use sigmf::{FileDriver, SigMfDevice, SigMfMeta, SigMfReader, SigMfWriter};
+pub use rtltcp::{RtlTcpCommand, RtlTcpDevice, RtlTcpDriver, RtlTcpServer, RtlTcpServerHandle, RtlTcpState, RtlTunerType};
+pub use sigmf::{FileDriver, SigMfArchive, SigMfCollection, SigMfDevice, SigMfMeta, SigMfReader, SigMfWriter};
+pub use sim::{SimDevice, SimDriver, SimParams};
 pub use soapysdr::SoapySdrDriver;
 pub use uhd::UhdDriver;
+pub use vrt::{VrtDevice, VrtDriver, VrtParams, VrtReceiver, VrtSender, VrtTimestampMode};
+pub use wav::{WavIqReader, WavIqWriter};
 
 /// Clock source for hardware clock.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -88,7 +105,8 @@ pub enum StreamDirection {
 pub struct StreamConfig {
     /// Stream direction
     pub direction: StreamDirection,
-    /// Number of channels
+    /// Channel indices carried by the stream (one buffer each in
+    /// [`StreamHandle::read_multi`]/[`StreamHandle::write_multi`])
     pub channels: Vec<usize>,
     /// Buffer size in samples
     pub buffer_size: usize,
@@ -120,6 +138,88 @@ pub enum SampleFormat {
     ComplexInt16,
     /// 8-bit signed integer I/Q
     ComplexInt8,
+    /// 8-bit unsigned (offset binary) I/Q, the RTL-SDR wire format
+    ComplexUint8,
+}
+
+impl SampleFormat {
+    /// Parse a SigMF-style name (`cf32`, `ci16`, `ci8`, `cu8`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().trim_end_matches("_le") {
+            "cf32" | "f32" | "float" => Some(Self::ComplexFloat32),
+            "ci16" | "sc16" | "i16" => Some(Self::ComplexInt16),
+            "ci8" | "sc8" | "i8" => Some(Self::ComplexInt8),
+            "cu8" | "u8" => Some(Self::ComplexUint8),
+            _ => None,
+        }
+    }
+
+    /// Short name (`cf32`, `ci16`, `ci8`, `cu8`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ComplexFloat32 => "cf32",
+            Self::ComplexInt16 => "ci16",
+            Self::ComplexInt8 => "ci8",
+            Self::ComplexUint8 => "cu8",
+        }
+    }
+
+    /// Bytes per complex sample.
+    pub fn bytes_per_sample(&self) -> usize {
+        match self {
+            Self::ComplexFloat32 => 8,
+            Self::ComplexInt16 => 4,
+            Self::ComplexInt8 | Self::ComplexUint8 => 2,
+        }
+    }
+
+    /// Convert samples to interleaved little-endian I/Q bytes.
+    ///
+    /// Integer formats map ±1.0 to full scale and saturate beyond it.
+    pub fn encode(&self, samples: &[IQSample]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(samples.len() * self.bytes_per_sample());
+        for s in samples {
+            for v in [s.re, s.im] {
+                match self {
+                    Self::ComplexFloat32 => out.extend_from_slice(&(v as f32).to_le_bytes()),
+                    Self::ComplexInt16 => {
+                        let q = (v * 32767.0).round().clamp(-32768.0, 32767.0) as i16;
+                        out.extend_from_slice(&q.to_le_bytes());
+                    }
+                    Self::ComplexInt8 => out.push((v * 127.0).round().clamp(-128.0, 127.0) as i8 as u8),
+                    Self::ComplexUint8 => out.push((v * 127.5 + 127.5).round().clamp(0.0, 255.0) as u8),
+                }
+            }
+        }
+        out
+    }
+
+    /// Convert interleaved little-endian I/Q bytes to samples.
+    ///
+    /// A trailing partial sample is ignored.
+    pub fn decode(&self, bytes: &[u8]) -> Vec<IQSample> {
+        bytes
+            .chunks_exact(self.bytes_per_sample())
+            .map(|c| match self {
+                Self::ComplexFloat32 => IQSample::new(
+                    f32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f64,
+                    f32::from_le_bytes([c[4], c[5], c[6], c[7]]) as f64,
+                ),
+                Self::ComplexInt16 => IQSample::new(
+                    i16::from_le_bytes([c[0], c[1]]) as f64 / 32767.0,
+                    i16::from_le_bytes([c[2], c[3]]) as f64 / 32767.0,
+                ),
+                Self::ComplexInt8 => IQSample::new(c[0] as i8 as f64 / 127.0, c[1] as i8 as f64 / 127.0),
+                Self::ComplexUint8 => IQSample::new((c[0] as f64 - 127.5) / 127.5, (c[1] as f64 - 127.5) / 127.5),
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for SampleFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
 /// Stream status information.
@@ -135,6 +235,68 @@ pub struct StreamStatus {
     pub samples_processed: u64,
     /// Current buffer fill level (samples)
     pub buffer_level: usize,
+    /// Requested time of the most recent late packet or timed start
+    pub last_late: Option<Timestamp>,
+    /// TX bursts completed with an end-of-burst flag
+    pub bursts_completed: u64,
+}
+
+/// Metadata of a TX write: timing and burst framing.
+///
+/// A burst starts with `start_of_burst` and ends with `end_of_burst`;
+/// writes in between continue it back to back. Running out of samples
+/// inside a burst is an underflow, the silence after an end-of-burst is
+/// not. If the first packet of a timed burst is late, the whole burst is
+/// dropped.
+#[derive(Debug, Clone, Default)]
+pub struct TxMetadata {
+    /// When to transmit the first sample (None = continue / immediately)
+    pub time: Option<Timestamp>,
+    /// First packet of a burst
+    pub start_of_burst: bool,
+    /// Last packet of a burst
+    pub end_of_burst: bool,
+}
+
+impl TxMetadata {
+    /// Untimed continuous streaming (what plain [`StreamHandle::write`] does).
+    pub fn streaming(time: Option<Timestamp>) -> Self {
+        Self {
+            time,
+            ..Default::default()
+        }
+    }
+
+    /// A complete burst in one write, transmitted at `time`.
+    pub fn burst(time: Timestamp) -> Self {
+        Self {
+            time: Some(time),
+            start_of_burst: true,
+            end_of_burst: true,
+        }
+    }
+
+    /// First packet of a burst transmitted at `time`.
+    pub fn start_of_burst(time: Option<Timestamp>) -> Self {
+        Self {
+            time,
+            start_of_burst: true,
+            end_of_burst: false,
+        }
+    }
+
+    /// Packet continuing the current burst.
+    pub fn continuation() -> Self {
+        Self::default()
+    }
+
+    /// Last packet of the current burst.
+    pub fn end_of_burst() -> Self {
+        Self {
+            end_of_burst: true,
+            ..Default::default()
+        }
+    }
 }
 
 /// Streaming interface for I/Q samples.
@@ -178,6 +340,66 @@ pub trait StreamHandle: Send {
         timeout: Duration,
     ) -> SdrResult<usize>;
 
+    /// Number of channels carried by the stream.
+    fn num_channels(&self) -> usize {
+        1
+    }
+
+    /// Read samples from a multi-channel RX stream.
+    ///
+    /// `buffers` holds one buffer per stream channel, in the order of
+    /// [`StreamConfig::channels`]. All channels are filled with the same
+    /// number of samples, which share the returned timestamp.
+    fn read_multi(
+        &mut self,
+        buffers: &mut [&mut [IQSample]],
+        timeout: Duration,
+    ) -> SdrResult<(usize, Timestamp)> {
+        match buffers {
+            [buffer] => self.read(buffer, timeout),
+            _ => Err(channel_mismatch(self.num_channels(), buffers.len())),
+        }
+    }
+
+    /// Write samples with burst metadata.
+    ///
+    /// Drivers without burst support transmit the samples as a timed
+    /// streaming write.
+    fn write_with_metadata(
+        &mut self,
+        buffer: &[IQSample],
+        metadata: &TxMetadata,
+        timeout: Duration,
+    ) -> SdrResult<usize> {
+        self.write(buffer, metadata.time.clone(), timeout)
+    }
+
+    /// Start an RX stream whose first sample is taken at `time`.
+    ///
+    /// A start time that has already passed starts the stream
+    /// immediately and is reported in [`StreamStatus::late_count`].
+    fn start_at(&mut self, time: Timestamp) -> SdrResult<()> {
+        let _ = time;
+        Err(SdrError::Unsupported("Timed stream start".to_string()))
+    }
+
+    /// Write samples to a multi-channel TX stream.
+    ///
+    /// `buffers` holds one buffer per stream channel; the channels are
+    /// transmitted together starting at `timestamp`. Returns the number of
+    /// samples written per channel.
+    fn write_multi(
+        &mut self,
+        buffers: &[&[IQSample]],
+        timestamp: Option<Timestamp>,
+        timeout: Duration,
+    ) -> SdrResult<usize> {
+        match buffers {
+            [buffer] => self.write(buffer, timestamp, timeout),
+            _ => Err(channel_mismatch(self.num_channels(), buffers.len())),
+        }
+    }
+
     /// Get current stream status.
     fn status(&self) -> StreamStatus;
 
@@ -191,6 +413,14 @@ pub trait StreamHandle: Send {
     fn free_space(&self) -> usize;
 }
 
+/// Error for a multi-channel call whose buffer count does not match the stream.
+pub(crate) fn channel_mismatch(channels: usize, buffers: usize) -> SdrError {
+    SdrError::ConfigError(format!(
+        "Stream has {} channel(s) but {} buffer(s) were given",
+        channels, buffers
+    ))
+}
+
 /// Tuner control interface for frequency, gain, and sample rate.
 pub trait TunerControl: Send {
     /// Set center frequency.
@@ -252,6 +482,90 @@ pub trait TunerControl: Send {
     fn gain_range(&self) -> (f64, f64);
 }
 
+/// Command executed by a [`TimedControl`] queue.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimedCommand {
+    /// Tune the center frequency (Hz)
+    SetFrequency(u64),
+    /// Set the receive gain (dB)
+    SetRxGain(f64),
+    /// Set the transmit gain (dB)
+    SetTxGain(f64),
+    /// Set the analog bandwidth (Hz)
+    SetBandwidth(f64),
+}
+
+/// Queue of commands executed at a device time.
+///
+/// Frequency hopping and TDMA waveforms retune on sample boundaries
+/// rather than whenever the host gets around to it. Use
+/// [`at`](#method.at) for the builder form:
+///
+/// ```rust
+/// use r4w_sim::hal::{create_default_registry, TimedCommand};
+/// use r4w_core::timing::Timestamp;
+///
+/// let mut device = create_default_registry().create("sim://").unwrap();
+/// let timed = device.timed().unwrap();
+/// timed.at(Timestamp::at_sample(1000, 1e6)).set_frequency(2_402_000_000).unwrap();
+/// assert_eq!(timed.pending().len(), 1);
+/// ```
+pub trait TimedControl: Send {
+    /// Execute `command` when the device time reaches `time`.
+    ///
+    /// Commands whose time has already passed execute immediately and
+    /// are counted in [`late_count`](Self::late_count).
+    fn schedule(&mut self, time: Timestamp, command: TimedCommand) -> SdrResult<()>;
+
+    /// Commands not yet executed, in execution order.
+    fn pending(&self) -> Vec<(Timestamp, TimedCommand)>;
+
+    /// Drop all pending commands, returning how many there were.
+    fn cancel_all(&mut self) -> usize;
+
+    /// Number of commands scheduled after their time had passed.
+    fn late_count(&self) -> u64;
+}
+
+impl dyn TimedControl + '_ {
+    /// Schedule commands at `time`.
+    pub fn at(&mut self, time: Timestamp) -> TimedCommands<'_> {
+        TimedCommands { control: self, time }
+    }
+}
+
+/// Builder returned by `TimedControl::at`.
+pub struct TimedCommands<'a> {
+    control: &'a mut dyn TimedControl,
+    time: Timestamp,
+}
+
+impl TimedCommands<'_> {
+    /// Tune the center frequency at the scheduled time.
+    pub fn set_frequency(&mut self, freq_hz: u64) -> SdrResult<&mut Self> {
+        self.control.schedule(self.time.clone(), TimedCommand::SetFrequency(freq_hz))?;
+        Ok(self)
+    }
+
+    /// Set the receive gain at the scheduled time.
+    pub fn set_rx_gain(&mut self, gain_db: f64) -> SdrResult<&mut Self> {
+        self.control.schedule(self.time.clone(), TimedCommand::SetRxGain(gain_db))?;
+        Ok(self)
+    }
+
+    /// Set the transmit gain at the scheduled time.
+    pub fn set_tx_gain(&mut self, gain_db: f64) -> SdrResult<&mut Self> {
+        self.control.schedule(self.time.clone(), TimedCommand::SetTxGain(gain_db))?;
+        Ok(self)
+    }
+
+    /// Set the analog bandwidth at the scheduled time.
+    pub fn set_bandwidth(&mut self, bw_hz: f64) -> SdrResult<&mut Self> {
+        self.control.schedule(self.time.clone(), TimedCommand::SetBandwidth(bw_hz))?;
+        Ok(self)
+    }
+}
+
 /// Clock control interface for timing synchronization.
 pub trait ClockControl: Send {
     /// Set clock source.
@@ -314,6 +628,11 @@ pub trait SdrDeviceExt: Send {
     /// Get clock control interface (if supported).
     fn clock(&mut self) -> Option<&mut dyn ClockControl>;
 
+    /// Get the timed command queue (if supported).
+    fn timed(&mut self) -> Option<&mut dyn TimedControl> {
+        None
+    }
+
     /// Check if device supports GPS synchronization.
     fn supports_gps(&self) -> bool {
         false
@@ -328,6 +647,17 @@ pub trait SdrDeviceExt: Send {
     fn supports_external_clock(&self) -> bool {
         false
     }
+
+    /// Sample format the device moves over its transport.
+    fn native_format(&self) -> SampleFormat {
+        SampleFormat::ComplexFloat32
+    }
+
+    /// Check if the device has a DC spike / LO leakage at the center
+    /// frequency (zero-IF hardware), worth tuning around.
+    fn has_dc_offset(&self) -> bool {
+        false
+    }
 }
 
 /// Driver factory for creating devices.
@@ -335,6 +665,11 @@ pub trait DeviceDriver: Send + Sync {
     /// Driver name (e.g., "uhd", "soapysdr", "rtlsdr", "simulator").
     fn name(&self) -> &str;
 
+    /// Alternative names accepted in connection strings.
+    fn aliases(&self) -> &[&str] {
+        &[]
+    }
+
     /// Discover available devices.
     fn discover(&self) -> Vec<DeviceInfo>;
 
@@ -363,10 +698,10 @@ impl DriverRegistry {
         self.drivers.push(driver);
     }
 
-    /// Get a driver by name.
+    /// Get a driver by name or alias.
     pub fn get(&self, name: &str) -> Option<&dyn DeviceDriver> {
         self.drivers.iter()
-            .find(|d| d.name() == name)
+            .find(|d| d.name() == name || d.aliases().contains(&name))
             .map(|d| d.as_ref())
     }
 
@@ -418,14 +753,20 @@ impl Default for DriverRegistry {
 /// - UHD driver (USRP devices: B200, N210, X310, etc.)
 /// - RTL-SDR driver (cheap USB receivers)
 /// - SoapySDR driver (generic SDR wrapper)
+/// - File driver (SigMF recordings)
+/// - VRT driver (`vrt://`, VITA 49 over UDP)
+/// - Simulator driver (`sim://`, in-process device with a channel model)
 ///
 /// Note: Actual hardware access requires the real driver libraries to be installed.
 pub fn create_default_registry() -> DriverRegistry {
     let mut registry = DriverRegistry::new();
     registry.register(Box::new(uhd::UhdDriver::new()));
     registry.register(Box::new(rtlsdr::RtlSdrDriver::new()));
+    registry.register(Box::new(rtltcp::RtlTcpDriver::new()));
     registry.register(Box::new(soapysdr::SoapySdrDriver::new()));
     registry.register(Box::new(sigmf::FileDriver::new()));
+    registry.register(Box::new(vrt::VrtDriver::new()));
+    registry.register(Box::new(sim::SimDriver::new()));
     registry
 }
 
@@ -451,6 +792,26 @@ mod tests {
         assert_eq!(SampleFormat::default(), SampleFormat::ComplexFloat32);
     }
 
+    #[test]
+    fn test_sample_format_conversion() {
+        let samples = vec![IQSample::new(0.5, -0.25), IQSample::new(-1.0, 1.0), IQSample::new(2.0, 0.0)];
+        for (name, tolerance) in [("cf32", 1e-7), ("ci16", 1e-4), ("ci8", 1e-2), ("cu8", 1e-2)] {
+            let format = SampleFormat::parse(name).unwrap();
+            assert_eq!(format.name(), name);
+            let bytes = format.encode(&samples);
+            assert_eq!(bytes.len(), 3 * format.bytes_per_sample());
+            let decoded = format.decode(&bytes);
+            assert!((decoded[0] - samples[0]).norm() < tolerance, "{}", name);
+            assert!((decoded[1] - samples[1]).norm() < tolerance, "{}", name);
+            // Integer formats saturate at full scale
+            if format != SampleFormat::ComplexFloat32 {
+                assert!((decoded[2].re - 1.0).abs() < tolerance, "{}", name);
+            }
+        }
+        assert_eq!(SampleFormat::parse("ci16_le"), Some(SampleFormat::ComplexInt16));
+        assert_eq!(SampleFormat::parse("cf64"), None);
+    }
+
     #[test]
     fn test_driver_registry() {
         let registry = DriverRegistry::new();
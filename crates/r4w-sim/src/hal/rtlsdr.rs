@@ -58,7 +58,7 @@
 //! - R828D: 24-1766 MHz
 
 use super::{
-    ClockControl, DeviceDriver, SdrDeviceExt, SdrResult, StreamConfig,
+    ClockControl, DeviceDriver, SampleFormat, SdrDeviceExt, SdrResult, StreamConfig,
     StreamDirection, StreamHandle, StreamStatus, TunerControl,
 };
 use crate::device::{DeviceCapabilities, DeviceInfo, SdrConfig, SdrError};
@@ -452,6 +452,14 @@ impl SdrDeviceExt for RtlSdrDevice {
     fn clock(&mut self) -> Option<&mut dyn ClockControl> {
         None // RTL-SDR has no clock control
     }
+
+    fn native_format(&self) -> SampleFormat {
+        SampleFormat::ComplexUint8
+    }
+
+    fn has_dc_offset(&self) -> bool {
+        true // Zero-IF tuners (R820T, E4000) leave a spike at DC
+    }
 }
 
 impl TunerControl for RtlSdrDevice {
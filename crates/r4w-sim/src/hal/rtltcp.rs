@@ -0,0 +1,1342 @@
+//! # rtl_tcp Network Driver and Server
+//!
+//! Client and server for the `rtl_tcp` protocol, the de-facto way of
+//! sharing an RTL-SDR dongle over the network.
+//!
+//! ## Protocol
+//!
+//! ```text
+//!   server ──► client   12-byte header: "RTL0" | tuner type (u32 BE) | gain count (u32 BE)
+//!   server ──► client   continuous cu8 stream: I, Q, I, Q, ... (offset binary, 127.5 = 0)
+//!   client ──► server   5-byte commands: opcode (u8) | parameter (u32 BE)
+//! ```
+//!
+//! The protocol is one-way: the server never acknowledges a command, so
+//! the client reports the values it last requested. Streaming starts as
+//! soon as the connection is accepted.
+//!
+//! ## Client
+//!
+//! `rtltcp://host:port` (alias `rtl_tcp://`, default port 1234) connects to
+//! any rtl_tcp server — the stock `rtl_tcp` tool, SDR++ server mode or an
+//! [`RtlTcpServer`]. The remote dongle is RX only. Frequency, sample rate
+//! and gain go through [`TunerControl`]; AGC, PPM correction, bias tee and
+//! the other RTL-specific settings are methods on [`RtlTcpDevice`].
+//!
+//! ## Server
+//!
+//! [`RtlTcpServer`] exposes any HAL device — hardware, SigMF playback or
+//! the simulator — to rtl_tcp clients such as gqrx, SDR++ or another R4W
+//! instance. Frequency, sample rate and gain commands are forwarded to the
+//! device's tuner. Commands without a HAL equivalent (AGC, PPM, bias tee,
+//! direct sampling, ...) are recorded in [`RtlTcpState`]. Clients are
+//! served one at a time, like the original tool.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use r4w_sim::hal::{create_default_registry, RtlTcpServer, StreamConfig};
+//! use r4w_core::types::IQSample;
+//! use std::time::Duration;
+//!
+//! let registry = create_default_registry();
+//! let device = registry.create("sim://snr=20,seed=1").unwrap();
+//! let server = RtlTcpServer::bind("127.0.0.1:0", device).unwrap().spawn();
+//!
+//! let uri = format!("rtltcp://{}", server.local_addr());
+//! let mut client = registry.create(&uri).unwrap();
+//! client.tuner().set_sample_rate(1e6).unwrap();
+//!
+//! let mut rx = client.create_rx_stream(StreamConfig::default()).unwrap();
+//! rx.start().unwrap();
+//! let mut buffer = vec![IQSample::new(0.0, 0.0); 1024];
+//! let (n, _) = rx.read(&mut buffer, Duration::from_secs(2)).unwrap();
+//! assert!(n > 0);
+//!
+//! drop(client);
+//! server.stop();
+//! ```
+
+use super::{
+    ClockControl, DeviceDriver, SampleFormat, SdrDeviceExt, SdrResult, StreamConfig,
+    StreamDirection, StreamHandle, StreamStatus, TunerControl,
+};
+use super::rtlsdr::{RTLSDR_MAX_SAMPLE_RATE, RTLSDR_MIN_SAMPLE_RATE};
+use crate::device::{DeviceCapabilities, DeviceInfo, SdrConfig, SdrError};
+use r4w_core::timing::Timestamp;
+use r4w_core::types::IQSample;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Default rtl_tcp port.
+pub const RTLTCP_DEFAULT_PORT: u16 = 1234;
+
+/// Magic bytes opening the server header.
+const MAGIC: &[u8; 4] = b"RTL0";
+
+/// Connection timeout of the client.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often blocked server loops check for shutdown.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+fn io_error(context: &str, e: io::Error) -> SdrError {
+    SdrError::HardwareError(format!("{}: {}", context, e))
+}
+
+/// Convert an offset-binary 8-bit sample to ±1.0.
+fn cu8_to_f64(sample: u8) -> f64 {
+    (sample as f64 - 127.5) / 127.5
+}
+
+/// Convert ±1.0 to an offset-binary 8-bit sample (clipping).
+fn f64_to_cu8(value: f64) -> u8 {
+    (value * 127.5 + 127.5).round().clamp(0.0, 255.0) as u8
+}
+
+/// Tuner chip reported in the rtl_tcp header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RtlTunerType {
+    Unknown,
+    E4000,
+    Fc0012,
+    Fc0013,
+    Fc2580,
+    #[default]
+    R820T,
+    R828D,
+}
+
+impl RtlTunerType {
+    /// Decode the header value (unknown values map to `Unknown`).
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Self::E4000,
+            2 => Self::Fc0012,
+            3 => Self::Fc0013,
+            4 => Self::Fc2580,
+            5 => Self::R820T,
+            6 => Self::R828D,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Header value of the tuner.
+    pub fn as_u32(self) -> u32 {
+        match self {
+            Self::Unknown => 0,
+            Self::E4000 => 1,
+            Self::Fc0012 => 2,
+            Self::Fc0013 => 3,
+            Self::Fc2580 => 4,
+            Self::R820T => 5,
+            Self::R828D => 6,
+        }
+    }
+
+    /// Human-readable chip name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Unknown => "Unknown",
+            Self::E4000 => "E4000",
+            Self::Fc0012 => "FC0012",
+            Self::Fc0013 => "FC0013",
+            Self::Fc2580 => "FC2580",
+            Self::R820T => "R820T",
+            Self::R828D => "R828D",
+        }
+    }
+
+    /// Discrete gain steps in tenths of a dB (as in librtlsdr).
+    pub fn gains(self) -> &'static [i32] {
+        match self {
+            Self::E4000 => &[-10, 15, 40, 65, 90, 115, 140, 165, 190, 215, 240, 290, 340, 420],
+            Self::Fc0012 => &[-99, -40, 71, 179, 192],
+            Self::Fc0013 => &[
+                -99, -73, -65, -63, -60, -58, -54, 58, 61, 63, 65, 67, 68, 70, 71, 179, 181, 182,
+                184, 186, 188, 191, 197,
+            ],
+            Self::R820T | Self::R828D => &[
+                0, 9, 14, 27, 37, 77, 87, 125, 144, 157, 166, 197, 207, 229, 254, 280, 297, 328,
+                338, 364, 372, 386, 402, 421, 434, 439, 445, 480, 496,
+            ],
+            Self::Fc2580 | Self::Unknown => &[0],
+        }
+    }
+
+    /// Tunable range in Hz.
+    pub fn frequency_range(self) -> (u64, u64) {
+        match self {
+            Self::E4000 => (52_000_000, 2_200_000_000),
+            Self::Fc0012 => (22_000_000, 948_000_000),
+            Self::Fc0013 => (22_000_000, 1_100_000_000),
+            Self::Fc2580 => (146_000_000, 924_000_000),
+            Self::R820T | Self::R828D => (24_000_000, 1_766_000_000),
+            Self::Unknown => (0, u32::MAX as u64),
+        }
+    }
+
+    /// Gain step closest to `gain_db`, in tenths of a dB.
+    pub fn nearest_gain(self, gain_db: f64) -> i32 {
+        let tenths = (gain_db * 10.0).round() as i32;
+        self.gains()
+            .iter()
+            .copied()
+            .min_by_key(|g| (g - tenths).abs())
+            .unwrap_or(tenths)
+    }
+}
+
+/// An rtl_tcp command (client → server).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtlTcpCommand {
+    /// 0x01: center frequency (Hz)
+    SetFrequency(u32),
+    /// 0x02: sample rate (Hz)
+    SetSampleRate(u32),
+    /// 0x03: `true` for manual tuner gain, `false` for tuner AGC
+    SetGainMode(bool),
+    /// 0x04: tuner gain (tenths of a dB)
+    SetGain(i32),
+    /// 0x05: frequency correction (ppm)
+    SetFreqCorrection(i32),
+    /// 0x06: IF gain of one stage (tenths of a dB)
+    SetIfGain { stage: u16, gain: i16 },
+    /// 0x07: RTL2832 test mode (counter instead of samples)
+    SetTestMode(bool),
+    /// 0x08: RTL2832 digital AGC
+    SetAgcMode(bool),
+    /// 0x09: direct sampling (0 = off, 1 = I branch, 2 = Q branch)
+    SetDirectSampling(u32),
+    /// 0x0a: offset tuning (E4000 only)
+    SetOffsetTuning(bool),
+    /// 0x0b: RTL2832 crystal frequency (Hz)
+    SetRtlXtal(u32),
+    /// 0x0c: tuner crystal frequency (Hz)
+    SetTunerXtal(u32),
+    /// 0x0d: tuner gain by index into the gain table
+    SetGainByIndex(u32),
+    /// 0x0e: bias tee power
+    SetBiasTee(bool),
+    /// Any other opcode
+    Other { opcode: u8, param: u32 },
+}
+
+impl RtlTcpCommand {
+    /// Opcode and 32-bit parameter.
+    pub fn opcode_param(self) -> (u8, u32) {
+        match self {
+            Self::SetFrequency(f) => (0x01, f),
+            Self::SetSampleRate(r) => (0x02, r),
+            Self::SetGainMode(manual) => (0x03, manual as u32),
+            Self::SetGain(g) => (0x04, g as u32),
+            Self::SetFreqCorrection(ppm) => (0x05, ppm as u32),
+            Self::SetIfGain { stage, gain } => (0x06, ((stage as u32) << 16) | (gain as u16 as u32)),
+            Self::SetTestMode(on) => (0x07, on as u32),
+            Self::SetAgcMode(on) => (0x08, on as u32),
+            Self::SetDirectSampling(mode) => (0x09, mode),
+            Self::SetOffsetTuning(on) => (0x0a, on as u32),
+            Self::SetRtlXtal(f) => (0x0b, f),
+            Self::SetTunerXtal(f) => (0x0c, f),
+            Self::SetGainByIndex(i) => (0x0d, i),
+            Self::SetBiasTee(on) => (0x0e, on as u32),
+            Self::Other { opcode, param } => (opcode, param),
+        }
+    }
+
+    /// Encode as the 5-byte wire format.
+    pub fn encode(self) -> [u8; 5] {
+        let (opcode, param) = self.opcode_param();
+        let p = param.to_be_bytes();
+        [opcode, p[0], p[1], p[2], p[3]]
+    }
+
+    /// Decode the 5-byte wire format.
+    pub fn decode(bytes: [u8; 5]) -> Self {
+        let param = u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]);
+        match bytes[0] {
+            0x01 => Self::SetFrequency(param),
+            0x02 => Self::SetSampleRate(param),
+            0x03 => Self::SetGainMode(param != 0),
+            0x04 => Self::SetGain(param as i32),
+            0x05 => Self::SetFreqCorrection(param as i32),
+            0x06 => Self::SetIfGain {
+                stage: (param >> 16) as u16,
+                gain: param as u16 as i16,
+            },
+            0x07 => Self::SetTestMode(param != 0),
+            0x08 => Self::SetAgcMode(param != 0),
+            0x09 => Self::SetDirectSampling(param),
+            0x0a => Self::SetOffsetTuning(param != 0),
+            0x0b => Self::SetRtlXtal(param),
+            0x0c => Self::SetTunerXtal(param),
+            0x0d => Self::SetGainByIndex(param),
+            0x0e => Self::SetBiasTee(param != 0),
+            opcode => Self::Other { opcode, param },
+        }
+    }
+}
+
+/// Encode the 12-byte server header.
+fn encode_header(tuner: RtlTunerType) -> [u8; 12] {
+    let mut header = [0u8; 12];
+    header[..4].copy_from_slice(MAGIC);
+    header[4..8].copy_from_slice(&tuner.as_u32().to_be_bytes());
+    header[8..].copy_from_slice(&(tuner.gains().len() as u32).to_be_bytes());
+    header
+}
+
+// ============================================================================
+// Client
+// ============================================================================
+
+/// RX stream of an rtl_tcp connection.
+struct RtlTcpStream {
+    socket: TcpStream,
+    running: bool,
+    sample_rate: Arc<AtomicU64>,
+    samples_read: u64,
+    status: StreamStatus,
+    raw: Vec<u8>,
+    /// Odd byte left over from the previous read (keeps I/Q aligned)
+    pending: Option<u8>,
+}
+
+impl RtlTcpStream {
+    fn sample_rate(&self) -> f64 {
+        f64::from_bits(self.sample_rate.load(Ordering::Relaxed))
+    }
+}
+
+impl StreamHandle for RtlTcpStream {
+    fn direction(&self) -> StreamDirection {
+        StreamDirection::Rx
+    }
+
+    fn start(&mut self) -> SdrResult<()> {
+        if self.running {
+            return Err(SdrError::AlreadyRunning);
+        }
+        self.running = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> SdrResult<()> {
+        self.running = false;
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn read(&mut self, buffer: &mut [IQSample], timeout: Duration) -> SdrResult<(usize, Timestamp)> {
+        if !self.running {
+            return Err(SdrError::NotStarted);
+        }
+
+        let wanted = buffer.len() * 2;
+        self.raw.resize(wanted, 0);
+        let mut filled = 0;
+        if let Some(byte) = self.pending.take() {
+            self.raw[0] = byte;
+            filled = 1;
+        }
+
+        // Fill the buffer until it is full, the timeout expires or the server hangs up
+        let deadline = Instant::now() + timeout;
+        let mut closed = false;
+        while filled < wanted {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            self.socket
+                .set_read_timeout(Some(remaining))
+                .map_err(|e| io_error("rtl_tcp", e))?;
+            match self.socket.read(&mut self.raw[filled..wanted]) {
+                Ok(0) => {
+                    closed = true;
+                    break;
+                }
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => break,
+                Err(e) => return Err(io_error("rtl_tcp read", e)),
+            }
+        }
+
+        let n = filled / 2;
+        if n == 0 {
+            self.pending = (filled == 1).then(|| self.raw[0]);
+            return Err(if closed {
+                SdrError::HardwareError("rtl_tcp server closed the connection".to_string())
+            } else {
+                SdrError::Timeout("rtl_tcp samples".to_string())
+            });
+        }
+        if filled % 2 == 1 {
+            self.pending = Some(self.raw[filled - 1]);
+        }
+
+        for (sample, iq) in buffer.iter_mut().zip(self.raw[..n * 2].chunks_exact(2)) {
+            *sample = IQSample::new(cu8_to_f64(iq[0]), cu8_to_f64(iq[1]));
+        }
+
+        let timestamp = Timestamp::at_sample(self.samples_read, self.sample_rate());
+        self.samples_read += n as u64;
+        self.status.samples_processed = self.samples_read;
+        Ok((n, timestamp))
+    }
+
+    fn write(&mut self, _buffer: &[IQSample], _timestamp: Option<Timestamp>, _timeout: Duration) -> SdrResult<usize> {
+        Err(SdrError::ConfigError("rtl_tcp is RX only".to_string()))
+    }
+
+    fn status(&self) -> StreamStatus {
+        self.status.clone()
+    }
+
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::at_sample(self.samples_read, self.sample_rate())
+    }
+
+    fn available(&self) -> usize {
+        // The socket does not expose how much data is queued
+        0
+    }
+
+    fn free_space(&self) -> usize {
+        0
+    }
+}
+
+/// Remote RTL-SDR reached over rtl_tcp.
+///
+/// The server does not report its settings, so the configuration starts
+/// at the rtl_tcp defaults (100 MHz, 2.048 MS/s, automatic gain) until the
+/// first command is sent.
+pub struct RtlTcpDevice {
+    info: DeviceInfo,
+    socket: TcpStream,
+    config: SdrConfig,
+    tuner_type: RtlTunerType,
+    gain_count: u32,
+    sample_rate: Arc<AtomicU64>,
+    auto_gain: bool,
+    agc: bool,
+    ppm_correction: i32,
+    bias_tee: bool,
+}
+
+impl RtlTcpDevice {
+    /// Connect to an rtl_tcp server and read its header.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> SdrResult<Self> {
+        let addrs: Vec<SocketAddr> = addr
+            .to_socket_addrs()
+            .map_err(|e| SdrError::ConfigError(format!("Invalid rtl_tcp address: {}", e)))?
+            .collect();
+
+        let mut last_error = None;
+        let mut socket = None;
+        for addr in &addrs {
+            match TcpStream::connect_timeout(addr, CONNECT_TIMEOUT) {
+                Ok(s) => {
+                    socket = Some(s);
+                    break;
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let mut socket = socket.ok_or_else(|| {
+            SdrError::DeviceNotFound(match last_error {
+                Some(e) => format!("rtl_tcp server unreachable: {}", e),
+                None => "rtl_tcp address did not resolve".to_string(),
+            })
+        })?;
+        socket.set_nodelay(true).map_err(|e| io_error("rtl_tcp", e))?;
+
+        let mut header = [0u8; 12];
+        socket
+            .set_read_timeout(Some(CONNECT_TIMEOUT))
+            .map_err(|e| io_error("rtl_tcp", e))?;
+        socket
+            .read_exact(&mut header)
+            .map_err(|e| io_error("rtl_tcp header", e))?;
+        if &header[..4] != MAGIC {
+            return Err(SdrError::HardwareError(
+                "Not an rtl_tcp server (missing RTL0 header)".to_string(),
+            ));
+        }
+        let tuner_type = RtlTunerType::from_u32(u32::from_be_bytes([header[4], header[5], header[6], header[7]]));
+        let gain_count = u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+
+        let peer = socket.peer_addr().map_err(|e| io_error("rtl_tcp", e))?;
+        tracing::debug!("Connected to rtl_tcp server {} ({} tuner)", peer, tuner_type.name());
+
+        let config = SdrConfig {
+            frequency: 100e6,
+            sample_rate: 2.048e6,
+            bandwidth: 2.048e6,
+            rx_gain: 0.0,
+            tx_gain: 0.0,
+            antenna: "RX".to_string(),
+            ..Default::default()
+        };
+        let sample_rate = Arc::new(AtomicU64::new(config.sample_rate.to_bits()));
+
+        Ok(Self {
+            info: DeviceInfo {
+                driver: "rtltcp".to_string(),
+                serial: peer.to_string(),
+                label: format!("rtl_tcp {} ({})", peer, tuner_type.name()),
+                address: format!("rtltcp://{}", peer),
+            },
+            socket,
+            config,
+            tuner_type,
+            gain_count,
+            sample_rate,
+            auto_gain: true,
+            agc: false,
+            ppm_correction: 0,
+            bias_tee: false,
+        })
+    }
+
+    /// Send a raw command to the server.
+    pub fn send_command(&mut self, command: RtlTcpCommand) -> SdrResult<()> {
+        self.socket
+            .write_all(&command.encode())
+            .map_err(|e| io_error("rtl_tcp command", e))
+    }
+
+    /// Tuner chip reported by the server.
+    pub fn tuner_type(&self) -> RtlTunerType {
+        self.tuner_type
+    }
+
+    /// Number of gain steps reported by the server.
+    pub fn gain_count(&self) -> u32 {
+        self.gain_count
+    }
+
+    /// Available gain values in dB.
+    pub fn available_gains(&self) -> Vec<f64> {
+        self.tuner_type.gains().iter().map(|&g| g as f64 / 10.0).collect()
+    }
+
+    /// Switch between tuner AGC (`true`) and manual gain (`false`).
+    pub fn set_auto_gain(&mut self, enabled: bool) -> SdrResult<()> {
+        self.send_command(RtlTcpCommand::SetGainMode(!enabled))?;
+        self.auto_gain = enabled;
+        Ok(())
+    }
+
+    /// Whether the tuner gain is automatic.
+    pub fn auto_gain(&self) -> bool {
+        self.auto_gain
+    }
+
+    /// Enable or disable the RTL2832 digital AGC.
+    pub fn set_agc(&mut self, enabled: bool) -> SdrResult<()> {
+        self.send_command(RtlTcpCommand::SetAgcMode(enabled))?;
+        self.agc = enabled;
+        Ok(())
+    }
+
+    /// Whether the RTL2832 digital AGC is enabled.
+    pub fn agc(&self) -> bool {
+        self.agc
+    }
+
+    /// Set frequency correction in PPM.
+    pub fn set_ppm_correction(&mut self, ppm: i32) -> SdrResult<()> {
+        self.send_command(RtlTcpCommand::SetFreqCorrection(ppm))?;
+        self.ppm_correction = ppm;
+        Ok(())
+    }
+
+    /// Get frequency correction in PPM.
+    pub fn ppm_correction(&self) -> i32 {
+        self.ppm_correction
+    }
+
+    /// Power the antenna port bias tee.
+    pub fn set_bias_tee(&mut self, enabled: bool) -> SdrResult<()> {
+        self.send_command(RtlTcpCommand::SetBiasTee(enabled))?;
+        self.bias_tee = enabled;
+        Ok(())
+    }
+
+    /// Whether the bias tee is powered.
+    pub fn bias_tee(&self) -> bool {
+        self.bias_tee
+    }
+
+    /// Select direct sampling (0 = off, 1 = I branch, 2 = Q branch).
+    pub fn set_direct_sampling(&mut self, mode: u32) -> SdrResult<()> {
+        if mode > 2 {
+            return Err(SdrError::ConfigError(format!("Invalid direct sampling mode {}", mode)));
+        }
+        self.send_command(RtlTcpCommand::SetDirectSampling(mode))
+    }
+
+    /// Enable or disable offset tuning.
+    pub fn set_offset_tuning(&mut self, enabled: bool) -> SdrResult<()> {
+        self.send_command(RtlTcpCommand::SetOffsetTuning(enabled))
+    }
+}
+
+impl SdrDeviceExt for RtlTcpDevice {
+    fn name(&self) -> &str {
+        &self.info.label
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        let (min, max) = self.tuner_type.frequency_range();
+        DeviceCapabilities {
+            can_rx: true,
+            can_tx: false,
+            full_duplex: false,
+            rx_channels: 1,
+            tx_channels: 0,
+            min_frequency: min as f64,
+            max_frequency: max as f64,
+            max_sample_rate: self.sample_rate_range().1,
+        }
+    }
+
+    fn config(&self) -> &SdrConfig {
+        &self.config
+    }
+
+    fn configure(&mut self, config: &SdrConfig) -> SdrResult<()> {
+        self.set_sample_rate(config.sample_rate)?;
+        self.set_frequency(config.frequency as u64)?;
+        self.set_rx_gain(config.rx_gain)?;
+        self.config = SdrConfig {
+            frequency: self.config.frequency,
+            sample_rate: self.config.sample_rate,
+            rx_gain: self.config.rx_gain,
+            antenna: "RX".to_string(),
+            ..config.clone()
+        };
+        Ok(())
+    }
+
+    fn create_rx_stream(&mut self, config: StreamConfig) -> SdrResult<Box<dyn StreamHandle>> {
+        if config.channels.iter().any(|&c| c != 0) {
+            return Err(SdrError::ConfigError("rtl_tcp has a single RX channel".to_string()));
+        }
+        let socket = self.socket.try_clone().map_err(|e| io_error("rtl_tcp", e))?;
+
+        Ok(Box::new(RtlTcpStream {
+            socket,
+            running: false,
+            sample_rate: self.sample_rate.clone(),
+            samples_read: 0,
+            status: StreamStatus::default(),
+            raw: Vec::with_capacity(config.buffer_size * 2),
+            pending: None,
+        }))
+    }
+
+    fn create_tx_stream(&mut self, _config: StreamConfig) -> SdrResult<Box<dyn StreamHandle>> {
+        Err(SdrError::ConfigError("rtl_tcp does not support transmit".to_string()))
+    }
+
+    fn tuner(&mut self) -> &mut dyn TunerControl {
+        self
+    }
+
+    fn clock(&mut self) -> Option<&mut dyn ClockControl> {
+        None
+    }
+
+    fn native_format(&self) -> SampleFormat {
+        SampleFormat::ComplexUint8
+    }
+
+    fn has_dc_offset(&self) -> bool {
+        true
+    }
+}
+
+impl TunerControl for RtlTcpDevice {
+    fn set_frequency(&mut self, freq_hz: u64) -> SdrResult<u64> {
+        let (min, max) = self.frequency_range();
+        if freq_hz < min || freq_hz > max {
+            return Err(SdrError::ConfigError(format!(
+                "Frequency {} out of range [{}, {}]",
+                freq_hz, min, max
+            )));
+        }
+        self.send_command(RtlTcpCommand::SetFrequency(freq_hz as u32))?;
+        self.config.frequency = freq_hz as f64;
+        Ok(freq_hz)
+    }
+
+    fn frequency(&self) -> u64 {
+        self.config.frequency as u64
+    }
+
+    fn set_sample_rate(&mut self, rate: f64) -> SdrResult<f64> {
+        let (min, max) = self.sample_rate_range();
+        if !(min..=max).contains(&rate) {
+            return Err(SdrError::ConfigError(format!(
+                "Sample rate {} out of range [{}, {}]",
+                rate, min, max
+            )));
+        }
+        let rate = rate.round();
+        self.send_command(RtlTcpCommand::SetSampleRate(rate as u32))?;
+        self.config.sample_rate = rate;
+        self.sample_rate.store(rate.to_bits(), Ordering::Relaxed);
+        Ok(rate)
+    }
+
+    fn sample_rate(&self) -> f64 {
+        self.config.sample_rate
+    }
+
+    fn set_bandwidth(&mut self, bw_hz: f64) -> SdrResult<f64> {
+        // rtl_tcp has no bandwidth command; the tuner follows the sample rate
+        self.config.bandwidth = bw_hz;
+        Ok(bw_hz)
+    }
+
+    fn bandwidth(&self) -> f64 {
+        self.config.bandwidth
+    }
+
+    fn set_rx_gain(&mut self, gain_db: f64) -> SdrResult<f64> {
+        let tenths = self.tuner_type.nearest_gain(gain_db);
+        self.send_command(RtlTcpCommand::SetGainMode(true))?;
+        self.send_command(RtlTcpCommand::SetGain(tenths))?;
+        self.auto_gain = false;
+        self.config.rx_gain = tenths as f64 / 10.0;
+        Ok(self.config.rx_gain)
+    }
+
+    fn rx_gain(&self) -> f64 {
+        self.config.rx_gain
+    }
+
+    fn set_tx_gain(&mut self, _gain_db: f64) -> SdrResult<f64> {
+        Err(SdrError::ConfigError("rtl_tcp is RX only".to_string()))
+    }
+
+    fn tx_gain(&self) -> f64 {
+        0.0
+    }
+
+    fn set_antenna(&mut self, _antenna: &str) -> SdrResult<()> {
+        Ok(())
+    }
+
+    fn antenna(&self) -> &str {
+        "RX"
+    }
+
+    fn available_antennas(&self) -> Vec<String> {
+        vec!["RX".to_string()]
+    }
+
+    fn frequency_range(&self) -> (u64, u64) {
+        self.tuner_type.frequency_range()
+    }
+
+    fn sample_rate_range(&self) -> (f64, f64) {
+        match self.tuner_type {
+            RtlTunerType::Unknown => (1.0, u32::MAX as f64),
+            _ => (RTLSDR_MIN_SAMPLE_RATE, RTLSDR_MAX_SAMPLE_RATE),
+        }
+    }
+
+    fn gain_range(&self) -> (f64, f64) {
+        let gains = self.tuner_type.gains();
+        (gains[0] as f64 / 10.0, gains[gains.len() - 1] as f64 / 10.0)
+    }
+}
+
+/// rtl_tcp driver for the registry (`rtltcp://host:port`, alias `rtl_tcp://`)
+pub struct RtlTcpDriver;
+
+impl RtlTcpDriver {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for RtlTcpDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeviceDriver for RtlTcpDriver {
+    fn name(&self) -> &str {
+        "rtltcp"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["rtl_tcp"]
+    }
+
+    fn discover(&self) -> Vec<DeviceInfo> {
+        // Network servers cannot be enumerated
+        Vec::new()
+    }
+
+    fn create(&self, info: &DeviceInfo) -> SdrResult<Box<dyn SdrDeviceExt>> {
+        let args = info.address.split_once("://").map_or(info.address.as_str(), |(_, a)| a);
+        self.create_from_string(args)
+    }
+
+    fn create_from_string(&self, args: &str) -> SdrResult<Box<dyn SdrDeviceExt>> {
+        let args = args.trim().trim_end_matches('/');
+        let addr = if args.is_empty() {
+            format!("127.0.0.1:{}", RTLTCP_DEFAULT_PORT)
+        } else if args.parse::<SocketAddr>().is_ok() || args.rsplit_once(':').is_some_and(|(_, p)| p.parse::<u16>().is_ok()) {
+            args.to_string()
+        } else {
+            format!("{}:{}", args, RTLTCP_DEFAULT_PORT)
+        };
+        Ok(Box::new(RtlTcpDevice::connect(addr.as_str())?))
+    }
+}
+
+// ============================================================================
+// Server
+// ============================================================================
+
+/// Settings requested by rtl_tcp clients, as seen by an [`RtlTcpServer`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RtlTcpState {
+    /// Last requested center frequency (Hz)
+    pub frequency: u32,
+    /// Last requested sample rate (Hz)
+    pub sample_rate: u32,
+    /// Manual tuner gain (`false` = tuner AGC)
+    pub manual_gain: bool,
+    /// Last requested tuner gain (tenths of a dB)
+    pub gain_tenth_db: i32,
+    /// Frequency correction (ppm)
+    pub ppm_correction: i32,
+    /// RTL2832 digital AGC
+    pub agc: bool,
+    /// Direct sampling mode
+    pub direct_sampling: u32,
+    /// Offset tuning
+    pub offset_tuning: bool,
+    /// Bias tee power
+    pub bias_tee: bool,
+    /// Commands received
+    pub commands: u64,
+    /// Commands the device rejected
+    pub rejected: u64,
+    /// Clients served (including the current one)
+    pub clients: u64,
+    /// Samples sent to clients
+    pub samples_sent: u64,
+}
+
+fn lock_state(state: &Mutex<RtlTcpState>) -> MutexGuard<'_, RtlTcpState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Serves an r4w device over the rtl_tcp protocol.
+pub struct RtlTcpServer {
+    listener: TcpListener,
+    device: Box<dyn SdrDeviceExt>,
+    tuner_type: RtlTunerType,
+    block_size: usize,
+    realtime: bool,
+    state: Arc<Mutex<RtlTcpState>>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl RtlTcpServer {
+    /// Listen on `addr` (use port 0 for an ephemeral port).
+    pub fn bind<A: ToSocketAddrs>(addr: A, device: Box<dyn SdrDeviceExt>) -> SdrResult<Self> {
+        let listener = TcpListener::bind(addr).map_err(|e| io_error("rtl_tcp bind", e))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| io_error("rtl_tcp bind", e))?;
+
+        Ok(Self {
+            listener,
+            device,
+            tuner_type: RtlTunerType::default(),
+            block_size: 16384,
+            realtime: false,
+            state: Arc::new(Mutex::new(RtlTcpState::default())),
+            shutdown: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Tuner type advertised to clients (default R820T).
+    pub fn with_tuner_type(mut self, tuner_type: RtlTunerType) -> Self {
+        self.tuner_type = tuner_type;
+        self
+    }
+
+    /// Samples read from the device per network write.
+    pub fn with_block_size(mut self, samples: usize) -> Self {
+        self.block_size = samples.max(1);
+        self
+    }
+
+    /// Pace output to the device sample rate.
+    ///
+    /// Needed for sources that produce samples faster than real time,
+    /// such as SigMF playback or the simulator in virtual mode.
+    pub fn with_realtime(mut self, realtime: bool) -> Self {
+        self.realtime = realtime;
+        self
+    }
+
+    /// Address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.listener.local_addr().expect("bound listener has an address")
+    }
+
+    /// Snapshot of the client-requested settings.
+    pub fn state(&self) -> RtlTcpState {
+        lock_state(&self.state).clone()
+    }
+
+    /// Flag that makes [`run`](Self::run) return when set.
+    pub fn shutdown_flag(&self) -> Arc<AtomicBool> {
+        self.shutdown.clone()
+    }
+
+    /// Serve clients one after another until the shutdown flag is set.
+    pub fn run(&mut self) -> SdrResult<()> {
+        while !self.shutdown.load(Ordering::Relaxed) {
+            match self.listener.accept() {
+                Ok((socket, peer)) => {
+                    tracing::info!("rtl_tcp client connected: {}", peer);
+                    lock_state(&self.state).clients += 1;
+                    match self.serve_client(socket) {
+                        Ok(()) => tracing::info!("rtl_tcp client disconnected: {}", peer),
+                        Err(e) => tracing::warn!("rtl_tcp session with {} ended: {}", peer, e),
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(POLL_INTERVAL),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(io_error("rtl_tcp accept", e)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the server on a background thread.
+    pub fn spawn(mut self) -> RtlTcpServerHandle {
+        let addr = self.local_addr();
+        let state = self.state.clone();
+        let shutdown = self.shutdown.clone();
+        let thread = std::thread::spawn(move || {
+            if let Err(e) = self.run() {
+                tracing::error!("rtl_tcp server stopped: {}", e);
+            }
+            self
+        });
+
+        RtlTcpServerHandle {
+            addr,
+            state,
+            shutdown,
+            thread: Some(thread),
+        }
+    }
+
+    /// Give the device back.
+    pub fn into_device(self) -> Box<dyn SdrDeviceExt> {
+        self.device
+    }
+
+    fn serve_client(&mut self, mut socket: TcpStream) -> SdrResult<()> {
+        socket.set_nonblocking(false).map_err(|e| io_error("rtl_tcp", e))?;
+        socket.set_nodelay(true).map_err(|e| io_error("rtl_tcp", e))?;
+        socket
+            .write_all(&encode_header(self.tuner_type))
+            .map_err(|e| io_error("rtl_tcp header", e))?;
+        socket
+            .set_write_timeout(Some(POLL_INTERVAL))
+            .map_err(|e| io_error("rtl_tcp", e))?;
+
+        // Commands arrive on their own thread so a slow stream never delays them
+        let (commands_tx, commands) = mpsc::channel();
+        let mut reader = socket.try_clone().map_err(|e| io_error("rtl_tcp", e))?;
+        let reader_thread = std::thread::spawn(move || {
+            let mut bytes = [0u8; 5];
+            while reader.read_exact(&mut bytes).is_ok() {
+                if commands_tx.send(RtlTcpCommand::decode(bytes)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let result = self.stream_to(&mut socket, &commands);
+
+        let _ = socket.shutdown(Shutdown::Both);
+        let _ = reader_thread.join();
+        result
+    }
+
+    fn stream_to(&mut self, socket: &mut TcpStream, commands: &mpsc::Receiver<RtlTcpCommand>) -> SdrResult<()> {
+        let stream_config = StreamConfig {
+            buffer_size: self.block_size,
+            ..Default::default()
+        };
+        let mut stream = self.device.create_rx_stream(stream_config)?;
+        stream.start()?;
+
+        let mut samples = vec![IQSample::new(0.0, 0.0); self.block_size];
+        let mut bytes = vec![0u8; self.block_size * 2];
+        let mut paced_from = Instant::now();
+        let mut paced_samples = 0u64;
+
+        let result = 'stream: loop {
+            if self.shutdown.load(Ordering::Relaxed) {
+                break Ok(());
+            }
+
+            let mut rate_changed = false;
+            loop {
+                match commands.try_recv() {
+                    Ok(command) => rate_changed |= self.apply(command),
+                    Err(mpsc::TryRecvError::Empty) => break,
+                    // Reader thread gone: the client hung up
+                    Err(mpsc::TryRecvError::Disconnected) => break 'stream Ok(()),
+                }
+            }
+            if rate_changed {
+                paced_from = Instant::now();
+                paced_samples = 0;
+            }
+
+            let n = match stream.read(&mut samples, POLL_INTERVAL) {
+                // End of a finite source (e.g. SigMF playback)
+                Ok((0, _)) => break Ok(()),
+                Ok((n, _)) => n,
+                Err(SdrError::Timeout(_)) => continue,
+                Err(e) => break Err(e),
+            };
+
+            for (iq, sample) in bytes.chunks_exact_mut(2).zip(&samples[..n]) {
+                iq[0] = f64_to_cu8(sample.re);
+                iq[1] = f64_to_cu8(sample.im);
+            }
+            if let Err(e) = self.send_all(socket, &bytes[..n * 2]) {
+                break match e.kind() {
+                    ErrorKind::BrokenPipe | ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted => Ok(()),
+                    ErrorKind::Interrupted => Ok(()),
+                    _ => Err(io_error("rtl_tcp write", e)),
+                };
+            }
+            lock_state(&self.state).samples_sent += n as u64;
+
+            if self.realtime {
+                paced_samples += n as u64;
+                let rate = self.device.tuner().sample_rate();
+                let due = paced_from + Duration::from_secs_f64(paced_samples as f64 / rate);
+                let now = Instant::now();
+                if due > now {
+                    std::thread::sleep(due - now);
+                }
+            }
+        };
+
+        let _ = stream.stop();
+        result
+    }
+
+    /// Write everything, waking up periodically to honour shutdown.
+    fn send_all(&self, socket: &mut TcpStream, mut data: &[u8]) -> io::Result<()> {
+        while !data.is_empty() {
+            match socket.write(data) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(n) => data = &data[n..],
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted) => {
+                    if self.shutdown.load(Ordering::Relaxed) {
+                        return Err(ErrorKind::Interrupted.into());
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply a client command to the device; returns true if the sample rate changed.
+    fn apply(&mut self, command: RtlTcpCommand) -> bool {
+        tracing::debug!("rtl_tcp command: {:?}", command);
+        let mut state = lock_state(&self.state);
+        state.commands += 1;
+
+        let tuner = self.device.tuner();
+        let result = match command {
+            RtlTcpCommand::SetFrequency(f) => {
+                state.frequency = f;
+                tuner.set_frequency(f as u64).map(drop)
+            }
+            RtlTcpCommand::SetSampleRate(r) => {
+                state.sample_rate = r;
+                tuner.set_sample_rate(r as f64).map(drop)
+            }
+            RtlTcpCommand::SetGainMode(manual) => {
+                state.manual_gain = manual;
+                Ok(())
+            }
+            RtlTcpCommand::SetGain(tenths) => {
+                state.gain_tenth_db = tenths;
+                tuner.set_rx_gain(tenths as f64 / 10.0).map(drop)
+            }
+            RtlTcpCommand::SetGainByIndex(index) => match self.tuner_type.gains().get(index as usize) {
+                Some(&tenths) => {
+                    state.gain_tenth_db = tenths;
+                    tuner.set_rx_gain(tenths as f64 / 10.0).map(drop)
+                }
+                None => Err(SdrError::ConfigError(format!("Gain index {} out of range", index))),
+            },
+            RtlTcpCommand::SetFreqCorrection(ppm) => {
+                state.ppm_correction = ppm;
+                Ok(())
+            }
+            RtlTcpCommand::SetAgcMode(on) => {
+                state.agc = on;
+                Ok(())
+            }
+            RtlTcpCommand::SetDirectSampling(mode) => {
+                state.direct_sampling = mode;
+                Ok(())
+            }
+            RtlTcpCommand::SetOffsetTuning(on) => {
+                state.offset_tuning = on;
+                Ok(())
+            }
+            RtlTcpCommand::SetBiasTee(on) => {
+                state.bias_tee = on;
+                Ok(())
+            }
+            RtlTcpCommand::SetIfGain { .. }
+            | RtlTcpCommand::SetTestMode(_)
+            | RtlTcpCommand::SetRtlXtal(_)
+            | RtlTcpCommand::SetTunerXtal(_)
+            | RtlTcpCommand::Other { .. } => Ok(()),
+        };
+
+        if let Err(e) = &result {
+            state.rejected += 1;
+            tracing::warn!("rtl_tcp command {:?} rejected: {}", command, e);
+        }
+        result.is_ok() && matches!(command, RtlTcpCommand::SetSampleRate(_))
+    }
+}
+
+/// Handle to an [`RtlTcpServer`] running on a background thread.
+///
+/// Dropping the handle stops the server.
+pub struct RtlTcpServerHandle {
+    addr: SocketAddr,
+    state: Arc<Mutex<RtlTcpState>>,
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<RtlTcpServer>>,
+}
+
+impl RtlTcpServerHandle {
+    /// Address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Snapshot of the client-requested settings.
+    pub fn state(&self) -> RtlTcpState {
+        lock_state(&self.state).clone()
+    }
+
+    /// Stop the server and give the device back.
+    pub fn stop(mut self) -> Box<dyn SdrDeviceExt> {
+        self.join().into_device()
+    }
+
+    fn join(&mut self) -> RtlTcpServer {
+        self.shutdown.store(true, Ordering::Relaxed);
+        let thread = self.thread.take().expect("server thread joined once");
+        thread.join().unwrap_or_else(|e| std::panic::resume_unwind(e))
+    }
+}
+
+impl Drop for RtlTcpServerHandle {
+    fn drop(&mut self) {
+        if self.thread.is_some() {
+            self.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hal::{SimDevice, SimParams};
+
+    fn sim_server(args: &str) -> RtlTcpServerHandle {
+        let device = SimDevice::new(SimParams::parse(args).unwrap()).unwrap();
+        RtlTcpServer::bind("127.0.0.1:0", Box::new(device)).unwrap().spawn()
+    }
+
+    fn wait_for(server: &RtlTcpServerHandle, done: impl Fn(&RtlTcpState) -> bool) -> RtlTcpState {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let state = server.state();
+            if done(&state) || Instant::now() > deadline {
+                return state;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_command_roundtrip() {
+        let commands = [
+            RtlTcpCommand::SetFrequency(433_920_000),
+            RtlTcpCommand::SetSampleRate(2_048_000),
+            RtlTcpCommand::SetGainMode(true),
+            RtlTcpCommand::SetGain(-10),
+            RtlTcpCommand::SetFreqCorrection(-42),
+            RtlTcpCommand::SetIfGain { stage: 3, gain: -30 },
+            RtlTcpCommand::SetAgcMode(true),
+            RtlTcpCommand::SetGainByIndex(7),
+            RtlTcpCommand::SetBiasTee(true),
+            RtlTcpCommand::Other { opcode: 0x42, param: 7 },
+        ];
+        for command in commands {
+            assert_eq!(RtlTcpCommand::decode(command.encode()), command);
+        }
+        assert_eq!(RtlTcpCommand::SetFrequency(100_000_000).encode(), [0x01, 0x05, 0xf5, 0xe1, 0x00]);
+    }
+
+    #[test]
+    fn test_cu8_conversion() {
+        assert_eq!(f64_to_cu8(0.0), 128);
+        assert_eq!(f64_to_cu8(5.0), 255);
+        assert_eq!(f64_to_cu8(-5.0), 0);
+        for byte in [0u8, 1, 100, 200, 255] {
+            assert_eq!(f64_to_cu8(cu8_to_f64(byte)), byte);
+        }
+    }
+
+    #[test]
+    fn test_tuner_gains() {
+        let tuner = RtlTunerType::R820T;
+        assert_eq!(tuner.gains().len(), 29);
+        assert_eq!(tuner.nearest_gain(30.0), 297);
+        assert_eq!(tuner.nearest_gain(100.0), 496);
+        assert_eq!(RtlTunerType::from_u32(tuner.as_u32()), tuner);
+        assert_eq!(RtlTunerType::from_u32(99), RtlTunerType::Unknown);
+    }
+
+    #[test]
+    fn test_client_commands_reach_device() {
+        let server = sim_server("seed=1,snr=30");
+        let mut client = RtlTcpDevice::connect(server.local_addr()).unwrap();
+        assert_eq!(client.tuner_type(), RtlTunerType::R820T);
+        assert_eq!(client.gain_count(), 29);
+
+        client.set_frequency(433_920_000).unwrap();
+        client.set_sample_rate(1_024_000.0).unwrap();
+        assert!((client.set_rx_gain(30.0).unwrap() - 29.7).abs() < 1e-9);
+        client.set_agc(true).unwrap();
+        client.set_ppm_correction(-12).unwrap();
+        client.set_bias_tee(true).unwrap();
+        assert!(client.set_frequency(10_000).is_err());
+        assert!(client.set_tx_gain(10.0).is_err());
+
+        let state = wait_for(&server, |s| s.commands >= 7);
+        assert_eq!(state.frequency, 433_920_000);
+        assert_eq!(state.sample_rate, 1_024_000);
+        assert!(state.manual_gain);
+        assert_eq!(state.gain_tenth_db, 297);
+        assert!(state.agc);
+        assert_eq!(state.ppm_correction, -12);
+        assert!(state.bias_tee);
+        assert_eq!(state.rejected, 0);
+
+        drop(client);
+        let mut device = server.stop();
+        assert_eq!(device.tuner().frequency(), 433_920_000);
+        assert_eq!(device.tuner().sample_rate(), 1_024_000.0);
+        assert!((device.tuner().rx_gain() - 29.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stream_sim_over_rtltcp() {
+        // A transmitted tone on the simulator arrives at the rtl_tcp client
+        let mut device = SimDevice::new(SimParams::parse("seed=3,snr=40,rate=1e6").unwrap()).unwrap();
+        let tx_config = StreamConfig {
+            buffer_size: 65536,
+            ..Default::default()
+        };
+        let mut tx = device.create_tx_stream(tx_config).unwrap();
+        tx.start().unwrap();
+        let tone: Vec<IQSample> = (0..200_000)
+            .map(|i| IQSample::from_polar(0.5, 2.0 * std::f64::consts::PI * 0.05 * i as f64))
+            .collect();
+        tx.write(&tone, Some(Timestamp::at_sample(0, 1e6)), Duration::ZERO).unwrap();
+
+        let server = RtlTcpServer::bind("127.0.0.1:0", Box::new(device)).unwrap().spawn();
+        let registry = crate::hal::create_default_registry();
+        let mut client = registry.create(&format!("rtl_tcp://{}", server.local_addr())).unwrap();
+        assert!(client.name().starts_with("rtl_tcp"));
+        assert!(client.create_tx_stream(StreamConfig::default()).is_err());
+
+        let mut rx = client.create_rx_stream(StreamConfig::default()).unwrap();
+        rx.start().unwrap();
+        let mut received = Vec::new();
+        let mut buffer = vec![IQSample::new(0.0, 0.0); 4096];
+        while received.len() < 50_000 {
+            let (n, _) = rx.read(&mut buffer, Duration::from_secs(5)).unwrap();
+            received.extend_from_slice(&buffer[..n]);
+        }
+
+        // Quantised to 8 bits, the tone keeps its amplitude and frequency
+        let power = received.iter().map(|s| s.norm_sqr()).sum::<f64>() / received.len() as f64;
+        assert!((power - 0.25).abs() < 0.03, "power = {}", power);
+        let rotation: IQSample = received.windows(2).map(|w| w[1] * w[0].conj()).sum();
+        let freq = rotation.arg() / (2.0 * std::f64::consts::PI);
+        assert!((freq - 0.05).abs() < 0.002, "freq = {}", freq);
+        assert_eq!(rx.status().samples_processed, received.len() as u64);
+
+        drop(rx);
+        drop(client);
+        let state = wait_for(&server, |s| s.samples_sent >= received.len() as u64);
+        assert_eq!(state.clients, 1);
+        server.stop();
+    }
+
+    #[test]
+    fn test_connect_errors() {
+        // Nothing listens on a freshly released port
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let driver = RtlTcpDriver::new();
+        assert!(driver.create_from_string(&format!("127.0.0.1:{}", port)).is_err());
+
+        // A server that is not rtl_tcp is rejected by its header
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let fake = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            socket.write_all(b"HTTP/1.1 400").unwrap();
+        });
+        assert!(RtlTcpDevice::connect(addr).is_err());
+        fake.join().unwrap();
+    }
+}
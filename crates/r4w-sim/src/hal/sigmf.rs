@@ -10,12 +10,28 @@
 //! - **Data file** (`.sigmf-data`): Raw I/Q samples
 //! - **Metadata file** (`.sigmf-meta`): JSON description of the recording
 //!
+//! Recordings can also be packed into a `.sigmf` archive (a tar holding
+//! `<name>/<name>.sigmf-meta` and `<name>/<name>.sigmf-data` for each
+//! recording) and grouped by a `.sigmf-collection`. [`SigMfReader::open`]
+//! accepts archives directly; [`SigMfArchive`] lists and packs them.
+//!
+//! [`SigMfWriter`] records the SHA-512 of the dataset in `core:sha512`;
+//! [`SigMfReader::verify`] checks it.
+//!
 //! ## Supported Sample Formats
 //!
 //! - `cf32_le`: Complex float32, little-endian (native R4W format)
 //! - `ci16_le`: Complex int16, little-endian
 //! - `ci8`: Complex int8
 //!
+//! ## Multi-Channel Recordings
+//!
+//! With `core:num_channels` greater than one, the data file interleaves
+//! the channels sample by sample (`ch0[0], ch1[0], …, ch0[1], …`). Sample
+//! positions, counts and annotations are per channel.
+//! [`SigMfReader::read_channels`] and [`SigMfWriter::write_channels`] work
+//! with one buffer per channel.
+//!
 //! ## Example
 //!
 //! ```rust,ignore
@@ -34,9 +50,11 @@
 //! writer.close()?;
 //! ```
 
+use r4w_core::analysis::{extract, Detection};
 use r4w_core::timing::Timestamp;
 use r4w_core::types::IQSample;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
@@ -246,6 +264,38 @@ impl SigMfMeta {
             extensions: HashMap::new(),
         });
     }
+
+    /// Load metadata from a `.sigmf-meta` file (or its base name).
+    pub fn load<P: AsRef<Path>>(path: P) -> SdrResult<Self> {
+        let meta_path = meta_path(path.as_ref());
+        let meta_file = File::open(&meta_path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to open {}: {}", meta_path.display(), e))
+        })?;
+        serde_json::from_reader(BufReader::new(meta_file)).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to parse metadata: {}", e))
+        })
+    }
+
+    /// Write metadata to a `.sigmf-meta` file (or its base name).
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> SdrResult<()> {
+        let meta_path = meta_path(path.as_ref());
+        let meta_file = File::create(&meta_path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to create {}: {}", meta_path.display(), e))
+        })?;
+        serde_json::to_writer_pretty(BufWriter::new(meta_file), self).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to write metadata: {}", e))
+        })
+    }
+
+    /// Centre frequency of the first capture segment.
+    pub fn frequency(&self) -> f64 {
+        self.captures.first().and_then(|c| c.frequency).unwrap_or(0.0)
+    }
+}
+
+/// Metadata path for a `.sigmf-meta`, `.sigmf-data` or base name.
+fn meta_path(path: &Path) -> PathBuf {
+    path.with_extension("sigmf-meta")
 }
 
 /// Sample format information.
@@ -324,18 +374,28 @@ pub struct SigMfReader {
     /// Base path (without extension) - kept for future extensions
     #[allow(dead_code)]
     base_path: PathBuf,
-    /// Current sample position
+    /// Byte offset of the dataset (non-zero inside `.sigmf` archives)
+    data_offset: u64,
+    /// Dataset length in bytes
+    data_len: u64,
+    /// Current sample position (per channel)
     position: u64,
-    /// Total samples in file
+    /// Total samples in file (per channel)
     total_samples: u64,
+    /// Interleaved channels
+    num_channels: usize,
 }
 
 impl SigMfReader {
     /// Open a SigMF recording.
     ///
-    /// Pass either the `.sigmf-meta` file or the base name.
+    /// Pass either the `.sigmf-meta` file or the base name. A `.sigmf`
+    /// archive opens its first recording.
     pub fn open<P: AsRef<Path>>(path: P) -> SdrResult<Self> {
         let path = path.as_ref();
+        if path.extension().is_some_and(|e| e == "sigmf") {
+            return Self::open_archive(path, None);
+        }
 
         // Determine base path
         let base_path = if path.extension().map_or(false, |e| e == "sigmf-meta") {
@@ -357,29 +417,58 @@ impl SigMfReader {
             SdrError::ConfigError(format!("Failed to parse metadata: {}", e))
         })?;
 
-        // Parse sample format
-        let format = SampleFormat::from_datatype(&meta.global.datatype).ok_or_else(|| {
-            SdrError::ConfigError(format!("Unsupported datatype: {}", meta.global.datatype))
-        })?;
-
         // Open data file
         let data_file = File::open(&data_path).map_err(|e| {
             SdrError::ConfigError(format!("Failed to open {}: {}", data_path.display(), e))
         })?;
 
-        // Calculate total samples
         let file_size = data_file.metadata().map_err(|e| {
             SdrError::HardwareError(format!("Failed to get file size: {}", e))
         })?.len();
-        let total_samples = file_size / format.bytes_per_sample as u64;
+
+        Self::from_parts(meta, data_file, base_path, 0, file_size)
+    }
+
+    /// Open a recording inside a `.sigmf` archive.
+    ///
+    /// `name` selects a recording by base name (see
+    /// [`SigMfArchive::recordings`]); `None` opens the first one.
+    pub fn open_archive<P: AsRef<Path>>(path: P, name: Option<&str>) -> SdrResult<Self> {
+        SigMfArchive::open(path)?.reader(name)
+    }
+
+    /// Build a reader over `data_len` bytes of `data_file` starting at `data_offset`.
+    fn from_parts(
+        meta: SigMfMeta,
+        data_file: File,
+        base_path: PathBuf,
+        data_offset: u64,
+        data_len: u64,
+    ) -> SdrResult<Self> {
+        // Parse sample format
+        let format = SampleFormat::from_datatype(&meta.global.datatype).ok_or_else(|| {
+            SdrError::ConfigError(format!("Unsupported datatype: {}", meta.global.datatype))
+        })?;
+
+        // Calculate total samples
+        let num_channels = meta.global.num_channels.unwrap_or(1).max(1) as usize;
+        let total_samples = data_len / (format.bytes_per_sample * num_channels) as u64;
+
+        let mut data_file = BufReader::new(data_file);
+        data_file.seek(SeekFrom::Start(data_offset)).map_err(|e| {
+            SdrError::HardwareError(format!("Seek failed: {}", e))
+        })?;
 
         Ok(Self {
             meta,
             format,
-            data_file: BufReader::new(data_file),
+            data_file,
             base_path,
+            data_offset,
+            data_len,
             position: 0,
             total_samples,
+            num_channels,
         })
     }
 
@@ -400,7 +489,12 @@ impl SigMfReader {
             .unwrap_or(0.0)
     }
 
-    /// Get total number of samples.
+    /// Get the number of channels.
+    pub fn num_channels(&self) -> usize {
+        self.num_channels
+    }
+
+    /// Get total number of samples (per channel).
     pub fn total_samples(&self) -> u64 {
         self.total_samples
     }
@@ -417,7 +511,7 @@ impl SigMfReader {
 
     /// Seek to sample position.
     pub fn seek(&mut self, sample: u64) -> SdrResult<()> {
-        let byte_offset = sample * self.format.bytes_per_sample as u64;
+        let byte_offset = self.data_offset + sample * (self.format.bytes_per_sample * self.num_channels) as u64;
         self.data_file.seek(SeekFrom::Start(byte_offset)).map_err(|e| {
             SdrError::HardwareError(format!("Seek failed: {}", e))
         })?;
@@ -427,9 +521,12 @@ impl SigMfReader {
 
     /// Read samples into buffer.
     ///
-    /// Returns the number of samples actually read.
+    /// Multi-channel recordings fill the buffer with interleaved channels
+    /// (whole samples of every channel only). Returns the number of values
+    /// actually read.
     pub fn read_samples(&mut self, buffer: &mut [IQSample]) -> SdrResult<usize> {
-        let to_read = buffer.len().min(self.remaining() as usize);
+        let frames = (buffer.len() / self.num_channels).min(self.remaining() as usize);
+        let to_read = frames * self.num_channels;
         if to_read == 0 {
             return Ok(0);
         }
@@ -514,13 +611,35 @@ impl SigMfReader {
             }
         }
 
-        self.position += to_read as u64;
+        self.position += frames as u64;
         Ok(to_read)
     }
 
-    /// Read all remaining samples.
+    /// Read one buffer per channel.
+    ///
+    /// Returns the number of samples read into each buffer.
+    pub fn read_channels(&mut self, buffers: &mut [&mut [IQSample]]) -> SdrResult<usize> {
+        if buffers.len() != self.num_channels {
+            return Err(SdrError::ConfigError(format!(
+                "Recording has {} channel(s) but {} buffer(s) were given",
+                self.num_channels,
+                buffers.len()
+            )));
+        }
+        let frames = buffers.iter().map(|b| b.len()).min().unwrap_or(0);
+        let mut interleaved = vec![IQSample::new(0.0, 0.0); frames * self.num_channels];
+        let count = self.read_samples(&mut interleaved)? / self.num_channels;
+        for (i, frame) in interleaved.chunks_exact(self.num_channels).take(count).enumerate() {
+            for (buffer, &sample) in buffers.iter_mut().zip(frame) {
+                buffer[i] = sample;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Read all remaining samples (interleaved for multi-channel recordings).
     pub fn read_all(&mut self) -> SdrResult<Vec<IQSample>> {
-        let remaining = self.remaining() as usize;
+        let remaining = self.remaining() as usize * self.num_channels;
         let mut buffer = vec![IQSample::new(0.0, 0.0); remaining];
         self.read_samples(&mut buffer)?;
         Ok(buffer)
@@ -530,6 +649,30 @@ impl SigMfReader {
     pub fn timestamp(&self) -> Timestamp {
         Timestamp::at_sample(self.position, self.sample_rate())
     }
+
+    /// Check the dataset against `core:sha512`.
+    ///
+    /// Returns `false` when the recording carries no checksum and an error
+    /// when it does not match. The read position is preserved.
+    pub fn verify(&mut self) -> SdrResult<bool> {
+        let Some(expected) = self.meta.global.sha512.clone() else {
+            return Ok(false);
+        };
+        self.data_file.seek(SeekFrom::Start(self.data_offset)).map_err(|e| {
+            SdrError::HardwareError(format!("Seek failed: {}", e))
+        })?;
+        let actual = sha512_hex((&mut self.data_file).take(self.data_len))?;
+        self.seek(self.position)?;
+
+        if actual.eq_ignore_ascii_case(expected.trim()) {
+            Ok(true)
+        } else {
+            Err(SdrError::ConfigError(format!(
+                "SHA-512 mismatch: metadata has {}, dataset hashes to {}",
+                expected, actual
+            )))
+        }
+    }
 }
 
 /// SigMF file writer.
@@ -540,8 +683,12 @@ pub struct SigMfWriter {
     data_file: BufWriter<File>,
     /// Base path (without extension)
     base_path: PathBuf,
-    /// Samples written
+    /// Samples written (per channel)
     samples_written: u64,
+    /// Interleaved channels
+    num_channels: usize,
+    /// Running hash of the dataset for `core:sha512`
+    hasher: Sha512,
 }
 
 impl SigMfWriter {
@@ -590,9 +737,33 @@ impl SigMfWriter {
             data_file: BufWriter::new(data_file),
             base_path,
             samples_written: 0,
+            num_channels: 1,
+            hasher: Sha512::new(),
         })
     }
 
+    /// Create a multi-channel recording.
+    pub fn create_multichannel<P: AsRef<Path>>(
+        path: P,
+        sample_rate: f64,
+        frequency: f64,
+        datatype: &str,
+        num_channels: usize,
+    ) -> SdrResult<Self> {
+        if num_channels == 0 {
+            return Err(SdrError::ConfigError("Recording needs at least one channel".to_string()));
+        }
+        let mut writer = Self::create_with_format(path, sample_rate, frequency, datatype)?;
+        writer.meta.global.num_channels = Some(num_channels as u32);
+        writer.num_channels = num_channels;
+        Ok(writer)
+    }
+
+    /// Get the number of channels.
+    pub fn num_channels(&self) -> usize {
+        self.num_channels
+    }
+
     /// Set description.
     pub fn set_description(&mut self, desc: &str) {
         self.meta.global.description = Some(desc.to_string());
@@ -613,13 +784,23 @@ impl SigMfWriter {
         self.meta.add_annotation(start, count, label);
     }
 
-    /// Get samples written.
+    /// Get samples written (per channel).
     pub fn samples_written(&self) -> u64 {
         self.samples_written
     }
 
     /// Write samples.
+    ///
+    /// Multi-channel recordings take interleaved channels, whole samples
+    /// of every channel only.
     pub fn write_samples(&mut self, samples: &[IQSample]) -> SdrResult<usize> {
+        if !samples.len().is_multiple_of(self.num_channels) {
+            return Err(SdrError::ConfigError(format!(
+                "{} values do not interleave into {} channels",
+                samples.len(),
+                self.num_channels
+            )));
+        }
         match self.meta.global.datatype.as_str() {
             "cf32_le" | "cf32" => {
                 // Convert f64 to f32 and write (IQSample is Complex64)
@@ -634,9 +815,7 @@ impl SigMfWriter {
                         samples.len() * 8,
                     )
                 };
-                self.data_file.write_all(byte_buf).map_err(|e| {
-                    SdrError::HardwareError(format!("Write failed: {}", e))
-                })?;
+                self.write_bytes(byte_buf)?;
             }
             "ci16_le" | "ci16" => {
                 // Convert f64 to int16
@@ -651,9 +830,7 @@ impl SigMfWriter {
                         samples.len() * 4,
                     )
                 };
-                self.data_file.write_all(byte_buf).map_err(|e| {
-                    SdrError::HardwareError(format!("Write failed: {}", e))
-                })?;
+                self.write_bytes(byte_buf)?;
             }
             "ci8" => {
                 // Convert f64 to signed int8
@@ -668,9 +845,7 @@ impl SigMfWriter {
                         samples.len() * 2,
                     )
                 };
-                self.data_file.write_all(byte_buf).map_err(|e| {
-                    SdrError::HardwareError(format!("Write failed: {}", e))
-                })?;
+                self.write_bytes(byte_buf)?;
             }
             "cu8" => {
                 // Convert f64 to unsigned int8 (RTL-SDR format)
@@ -680,9 +855,7 @@ impl SigMfWriter {
                     u8_buf[i * 2] = ((sample.re + 1.0) * 127.5).clamp(0.0, 255.0) as u8;
                     u8_buf[i * 2 + 1] = ((sample.im + 1.0) * 127.5).clamp(0.0, 255.0) as u8;
                 }
-                self.data_file.write_all(&u8_buf).map_err(|e| {
-                    SdrError::HardwareError(format!("Write failed: {}", e))
-                })?;
+                self.write_bytes(&u8_buf)?;
             }
             _ => {
                 return Err(SdrError::Unsupported(format!(
@@ -692,10 +865,44 @@ impl SigMfWriter {
             }
         }
 
-        self.samples_written += samples.len() as u64;
+        self.samples_written += (samples.len() / self.num_channels) as u64;
         Ok(samples.len())
     }
 
+    /// Append encoded samples to the dataset and the running hash.
+    fn write_bytes(&mut self, bytes: &[u8]) -> SdrResult<()> {
+        self.hasher.update(bytes);
+        self.data_file.write_all(bytes).map_err(|e| {
+            SdrError::HardwareError(format!("Write failed: {}", e))
+        })
+    }
+
+    /// Mutable access to the metadata written on close.
+    ///
+    /// The datatype, channel count and checksum are managed by the writer.
+    pub fn metadata_mut(&mut self) -> &mut SigMfMeta {
+        &mut self.meta
+    }
+
+    /// Write one buffer per channel.
+    ///
+    /// Returns the number of samples written from each buffer.
+    pub fn write_channels(&mut self, buffers: &[&[IQSample]]) -> SdrResult<usize> {
+        if buffers.len() != self.num_channels {
+            return Err(SdrError::ConfigError(format!(
+                "Recording has {} channel(s) but {} buffer(s) were given",
+                self.num_channels,
+                buffers.len()
+            )));
+        }
+        let frames = buffers.iter().map(|b| b.len()).min().unwrap_or(0);
+        let interleaved: Vec<IQSample> = (0..frames)
+            .flat_map(|i| buffers.iter().map(move |b| b[i]))
+            .collect();
+        self.write_samples(&interleaved)?;
+        Ok(frames)
+    }
+
     /// Flush buffers.
     pub fn flush(&mut self) -> SdrResult<()> {
         self.data_file.flush().map_err(|e| {
@@ -707,6 +914,8 @@ impl SigMfWriter {
     pub fn close(mut self) -> SdrResult<SigMfMeta> {
         // Flush data
         self.flush()?;
+        let digest = std::mem::take(&mut self.hasher).finalize();
+        self.meta.global.sha512 = Some(to_hex(&digest));
 
         // Write metadata
         let meta_path = self.base_path.with_extension("sigmf-meta");
@@ -746,6 +955,508 @@ pub fn annotate_waveform(
     meta.annotations.push(ann);
 }
 
+/// Record energy detections as annotations on a recording.
+///
+/// Frequency edges are absolute (capture frequency plus the detection
+/// offset); the SNR is kept in the `r4w:snr_db` extension.
+pub fn annotate_detections(meta: &mut SigMfMeta, detections: &[Detection]) {
+    let frequency = meta.frequency();
+    for detection in detections {
+        let mut ann = SigMfAnnotation {
+            sample_start: detection.start_sample,
+            sample_count: detection.sample_count,
+            label: Some("detection".to_string()),
+            comment: Some(format!(
+                "{:.1} dB SNR, {:.0} Hz wide",
+                detection.snr_db, detection.bandwidth_hz
+            )),
+            freq_lower_edge: Some(frequency + detection.freq_lower_hz()),
+            freq_upper_edge: Some(frequency + detection.freq_upper_hz()),
+            extensions: HashMap::new(),
+        };
+        ann.extensions.insert("r4w:snr_db".to_string(), serde_json::json!(detection.snr_db));
+        meta.annotations.push(ann);
+    }
+}
+
+/// Extract each detection into its own baseband recording.
+///
+/// Recordings are named `<base>_<index>` (index from 0), centred on the
+/// detection and decimated to just above its bandwidth (`margin` as in
+/// [`r4w_core::analysis::extract`]). Returns the base paths written.
+pub fn extract_detections<P: AsRef<Path>>(
+    samples: &[IQSample],
+    meta: &SigMfMeta,
+    detections: &[Detection],
+    base: P,
+    margin: f64,
+) -> SdrResult<Vec<PathBuf>> {
+    let base = base.as_ref();
+    let stem = base.file_name().and_then(|n| n.to_str()).unwrap_or("detection");
+    let mut written = Vec::with_capacity(detections.len());
+
+    for (i, detection) in detections.iter().enumerate() {
+        let (baseband, rate) = extract(samples, meta.global.sample_rate, detection, margin);
+        let path = base.with_file_name(format!("{}_{:03}", stem, i));
+        let mut writer = SigMfWriter::create(&path, rate, meta.frequency() + detection.center_hz)?;
+        writer.set_description(&format!(
+            "Detection {} at sample {} ({:.1} dB SNR)",
+            i, detection.start_sample, detection.snr_db
+        ));
+        if let Some(hw) = &meta.global.hw {
+            writer.set_hardware(hw);
+        }
+        writer.write_samples(&baseband)?;
+        let mut out = writer.close()?;
+        out.global.extensions.insert(
+            "r4w:source_sample_start".to_string(),
+            serde_json::json!(detection.start_sample),
+        );
+        out.save(&path)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+// =============================================================================
+// Integrity, Archives and Collections
+// =============================================================================
+
+/// Lower-case hex encoding of a digest.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-512 of everything a reader yields, as lower-case hex.
+pub fn sha512_hex<R: Read>(mut reader: R) -> SdrResult<String> {
+    let mut hasher = Sha512::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf).map_err(|e| {
+            SdrError::HardwareError(format!("Read failed: {}", e))
+        })?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// SHA-512 of a file, as lower-case hex.
+pub fn sha512_file<P: AsRef<Path>>(path: P) -> SdrResult<String> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| {
+        SdrError::ConfigError(format!("Failed to open {}: {}", path.display(), e))
+    })?;
+    sha512_hex(BufReader::new(file))
+}
+
+/// Tar block size.
+const TAR_BLOCK: u64 = 512;
+
+/// A regular file inside a tar archive.
+#[derive(Debug, Clone)]
+struct TarMember {
+    /// Path inside the archive
+    name: String,
+    /// Byte offset of the file contents
+    offset: u64,
+    /// File size in bytes
+    size: u64,
+}
+
+/// Parse a NUL/space terminated octal field, or a GNU base-256 number.
+fn tar_number(field: &[u8]) -> u64 {
+    if field.first().is_some_and(|b| b & 0x80 != 0) {
+        return field[1..].iter().fold((field[0] & 0x7f) as u64, |acc, &b| (acc << 8) | b as u64);
+    }
+    field
+        .iter()
+        .skip_while(|&&b| b == b' ')
+        .take_while(|&&b| (b'0'..=b'7').contains(&b))
+        .fold(0, |acc, &b| acc * 8 + (b - b'0') as u64)
+}
+
+/// NUL-terminated string field.
+fn tar_string(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+/// List the regular files of a ustar/pax/GNU tar archive.
+fn tar_members<R: Read + Seek>(reader: &mut R) -> SdrResult<Vec<TarMember>> {
+    let mut members = Vec::new();
+    let mut long_name: Option<String> = None;
+    let mut pax_size: Option<u64> = None;
+    let mut offset = 0u64;
+    let mut header = [0u8; TAR_BLOCK as usize];
+
+    loop {
+        reader.seek(SeekFrom::Start(offset)).map_err(|e| {
+            SdrError::HardwareError(format!("Seek failed: {}", e))
+        })?;
+        if reader.read_exact(&mut header).is_err() || header.iter().all(|&b| b == 0) {
+            break;
+        }
+        let stored = tar_number(&header[148..156]);
+        let computed: u64 = header
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| if (148..156).contains(&i) { b' ' as u64 } else { b as u64 })
+            .sum();
+        if stored != computed {
+            return Err(SdrError::ConfigError(format!("Corrupt tar header at byte {}", offset)));
+        }
+
+        let size = pax_size.take().unwrap_or_else(|| tar_number(&header[124..136]));
+        let data_offset = offset + TAR_BLOCK;
+        match header[156] {
+            b'0' | 0 => {
+                let name = long_name.take().unwrap_or_else(|| {
+                    let prefix = tar_string(&header[345..500]);
+                    let name = tar_string(&header[0..100]);
+                    if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) }
+                });
+                members.push(TarMember { name, offset: data_offset, size });
+            }
+            // GNU long name and pax extended headers describe the next entry
+            b'L' | b'x' => {
+                let mut data = vec![0u8; size as usize];
+                reader.read_exact(&mut data).map_err(|e| {
+                    SdrError::HardwareError(format!("Read failed: {}", e))
+                })?;
+                if header[156] == b'L' {
+                    long_name = Some(tar_string(&data));
+                } else {
+                    // Records are "<len> <key>=<value>\n"
+                    for record in String::from_utf8_lossy(&data).lines() {
+                        let Some((_, kv)) = record.split_once(' ') else { continue };
+                        match kv.split_once('=') {
+                            Some(("path", v)) => long_name = Some(v.to_string()),
+                            Some(("size", v)) => pax_size = v.parse().ok(),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        offset = data_offset + size.div_ceil(TAR_BLOCK) * TAR_BLOCK;
+    }
+    Ok(members)
+}
+
+/// Build a ustar header for a regular file.
+fn tar_header(name: &str, size: u64, mtime: u64) -> SdrResult<[u8; TAR_BLOCK as usize]> {
+    let mut header = [0u8; TAR_BLOCK as usize];
+    let (prefix, name) = match name.len() {
+        0..=100 => ("", name),
+        _ => name
+            .rsplit_once('/')
+            .filter(|(p, n)| p.len() <= 155 && n.len() <= 100)
+            .ok_or_else(|| SdrError::ConfigError(format!("Archive member name too long: {}", name)))?,
+    };
+    let octal = |field: &mut [u8], value: u64| {
+        let text = format!("{:0width$o}", value, width = field.len() - 1);
+        field[..text.len()].copy_from_slice(text.as_bytes());
+    };
+
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    octal(&mut header[100..108], 0o644);
+    octal(&mut header[108..116], 0);
+    octal(&mut header[116..124], 0);
+    if size < 1 << 33 {
+        octal(&mut header[124..136], size);
+    } else {
+        // GNU base-256 for files of 8 GiB and more
+        header[124] = 0x80;
+        header[128..136].copy_from_slice(&size.to_be_bytes());
+    }
+    octal(&mut header[136..148], mtime);
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    header[148..156].fill(b' ');
+    let checksum: u64 = header.iter().map(|&b| b as u64).sum();
+    let text = format!("{:06o}\0 ", checksum);
+    header[148..156].copy_from_slice(text.as_bytes());
+    Ok(header)
+}
+
+/// Append a file to a tar stream, padded to the block size.
+fn tar_append<W: Write, R: Read>(out: &mut W, name: &str, size: u64, mut contents: R) -> SdrResult<()> {
+    let write_err = |e: std::io::Error| SdrError::HardwareError(format!("Write failed: {}", e));
+    let mtime = chrono::Utc::now().timestamp().max(0) as u64;
+    out.write_all(&tar_header(name, size, mtime)?).map_err(write_err)?;
+    let copied = std::io::copy(&mut contents, out).map_err(write_err)?;
+    if copied != size {
+        return Err(SdrError::HardwareError(format!("{} changed while archiving", name)));
+    }
+    let padding = (TAR_BLOCK - size % TAR_BLOCK) % TAR_BLOCK;
+    out.write_all(&vec![0u8; padding as usize]).map_err(write_err)
+}
+
+/// A SigMF archive (`.sigmf`): a tar of one or more recordings.
+///
+/// Recordings live under `<name>/<name>.sigmf-meta` and
+/// `<name>/<name>.sigmf-data`; an optional `.sigmf-collection` ties them
+/// together.
+pub struct SigMfArchive {
+    path: PathBuf,
+    members: Vec<TarMember>,
+}
+
+impl SigMfArchive {
+    /// Open an archive and index its members.
+    pub fn open<P: AsRef<Path>>(path: P) -> SdrResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to open {}: {}", path.display(), e))
+        })?;
+        let members = tar_members(&mut BufReader::new(file))?;
+        Ok(Self { path, members })
+    }
+
+    /// Base names (member paths without extension) of the recordings.
+    pub fn recordings(&self) -> Vec<String> {
+        self.members
+            .iter()
+            .filter_map(|m| m.name.strip_suffix(".sigmf-meta"))
+            .filter(|base| self.member(&format!("{}.sigmf-data", base)).is_some())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn member(&self, name: &str) -> Option<&TarMember> {
+        self.members.iter().find(|m| m.name == name)
+    }
+
+    fn read_member(&self, member: &TarMember) -> SdrResult<Vec<u8>> {
+        let mut file = File::open(&self.path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to open {}: {}", self.path.display(), e))
+        })?;
+        let mut data = vec![0u8; member.size as usize];
+        file.seek(SeekFrom::Start(member.offset))
+            .and_then(|_| file.read_exact(&mut data))
+            .map_err(|e| SdrError::HardwareError(format!("Read failed: {}", e)))?;
+        Ok(data)
+    }
+
+    /// Metadata of a recording.
+    ///
+    /// `name` is a base name or just its final component.
+    pub fn metadata(&self, name: &str) -> SdrResult<SigMfMeta> {
+        let base = self.resolve(Some(name))?;
+        let member = self.member(&format!("{}.sigmf-meta", base)).expect("resolved recording");
+        serde_json::from_slice(&self.read_member(member)?).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to parse metadata: {}", e))
+        })
+    }
+
+    fn resolve(&self, name: Option<&str>) -> SdrResult<String> {
+        let recordings = self.recordings();
+        let found = match name {
+            None => recordings.first(),
+            Some(name) => recordings
+                .iter()
+                .find(|r| r.as_str() == name || r.rsplit('/').next() == Some(name)),
+        };
+        found.cloned().ok_or_else(|| {
+            SdrError::ConfigError(format!(
+                "{}: no recording {} (contains: {})",
+                self.path.display(),
+                name.unwrap_or("found"),
+                recordings.join(", ")
+            ))
+        })
+    }
+
+    /// Open a recording for reading, the first one when `name` is `None`.
+    pub fn reader(&self, name: Option<&str>) -> SdrResult<SigMfReader> {
+        let base = self.resolve(name)?;
+        let meta = self.metadata(&base)?;
+        let data = self.member(&format!("{}.sigmf-data", base)).expect("resolved recording");
+        let file = File::open(&self.path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to open {}: {}", self.path.display(), e))
+        })?;
+        SigMfReader::from_parts(meta, file, self.path.join(&base), data.offset, data.size)
+    }
+
+    /// The archive's collection, if it has one.
+    pub fn collection(&self) -> SdrResult<Option<SigMfCollection>> {
+        let Some(member) = self.members.iter().find(|m| m.name.ends_with(".sigmf-collection")) else {
+            return Ok(None);
+        };
+        serde_json::from_slice(&self.read_member(member)?)
+            .map(Some)
+            .map_err(|e| SdrError::ConfigError(format!("Failed to parse collection: {}", e)))
+    }
+
+    /// Pack split recordings (base names) into a new archive.
+    ///
+    /// A collection, when given, is stored as `<archive>.sigmf-collection`
+    /// at the top level.
+    pub fn create<P: AsRef<Path>, Q: AsRef<Path>>(
+        path: P,
+        recordings: &[Q],
+        collection: Option<&SigMfCollection>,
+    ) -> SdrResult<()> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to create {}: {}", path.display(), e))
+        })?;
+        let mut out = BufWriter::new(file);
+
+        if let Some(collection) = collection {
+            let json = serde_json::to_vec_pretty(collection).map_err(|e| {
+                SdrError::ConfigError(format!("Failed to write collection: {}", e))
+            })?;
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("collection");
+            tar_append(&mut out, &format!("{}.sigmf-collection", stem), json.len() as u64, json.as_slice())?;
+        }
+
+        for recording in recordings {
+            let base = recording.as_ref().with_extension("");
+            let stem = base
+                .file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| SdrError::ConfigError(format!("Invalid recording {}", base.display())))?;
+            for ext in ["sigmf-meta", "sigmf-data"] {
+                let member_path = base.with_extension(ext);
+                let file = File::open(&member_path).map_err(|e| {
+                    SdrError::ConfigError(format!("Failed to open {}: {}", member_path.display(), e))
+                })?;
+                let size = file.metadata().map_err(|e| {
+                    SdrError::HardwareError(format!("Failed to get file size: {}", e))
+                })?.len();
+                tar_append(&mut out, &format!("{}/{}.{}", stem, stem, ext), size, BufReader::new(file))?;
+            }
+        }
+
+        // End-of-archive marker
+        out.write_all(&[0u8; 2 * TAR_BLOCK as usize])
+            .and_then(|_| out.flush())
+            .map_err(|e| SdrError::HardwareError(format!("Write failed: {}", e)))
+    }
+}
+
+/// Recording referenced by a collection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SigMfStreamRef {
+    /// Recording base name, relative to the collection
+    pub name: String,
+    /// SHA-512 of the recording's `.sigmf-meta` file
+    pub hash: String,
+}
+
+/// Collection-level metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigMfCollectionInfo {
+    /// Version of SigMF spec
+    #[serde(rename = "core:version")]
+    pub version: String,
+
+    /// Description (optional)
+    #[serde(rename = "core:description", skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Author (optional)
+    #[serde(rename = "core:author", skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Member recordings
+    #[serde(rename = "core:streams", default)]
+    pub streams: Vec<SigMfStreamRef>,
+
+    /// Additional extensions
+    #[serde(flatten)]
+    pub extensions: HashMap<String, serde_json::Value>,
+}
+
+/// A SigMF collection (`.sigmf-collection`) grouping related recordings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigMfCollection {
+    /// Collection metadata
+    pub collection: SigMfCollectionInfo,
+}
+
+impl Default for SigMfCollection {
+    fn default() -> Self {
+        Self {
+            collection: SigMfCollectionInfo {
+                version: "1.0.0".to_string(),
+                description: None,
+                author: None,
+                streams: Vec::new(),
+                extensions: HashMap::new(),
+            },
+        }
+    }
+}
+
+impl SigMfCollection {
+    /// Create an empty collection.
+    pub fn new(description: &str) -> Self {
+        let mut collection = Self::default();
+        collection.collection.description = Some(description.to_string());
+        collection
+    }
+
+    /// Load a `.sigmf-collection` file.
+    pub fn load<P: AsRef<Path>>(path: P) -> SdrResult<Self> {
+        let path = path.as_ref().with_extension("sigmf-collection");
+        let file = File::open(&path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to open {}: {}", path.display(), e))
+        })?;
+        serde_json::from_reader(BufReader::new(file)).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to parse collection: {}", e))
+        })
+    }
+
+    /// Write the collection to a `.sigmf-collection` file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> SdrResult<()> {
+        let path = path.as_ref().with_extension("sigmf-collection");
+        let file = File::create(&path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to create {}: {}", path.display(), e))
+        })?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to write collection: {}", e))
+        })
+    }
+
+    /// Add a split recording (base name), hashing its metadata file.
+    pub fn add_recording<P: AsRef<Path>>(&mut self, recording: P) -> SdrResult<()> {
+        let base = recording.as_ref().with_extension("");
+        let name = base
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| SdrError::ConfigError(format!("Invalid recording {}", base.display())))?;
+        let hash = sha512_file(meta_path(&base))?;
+        self.collection.streams.push(SigMfStreamRef { name: name.to_string(), hash });
+        Ok(())
+    }
+
+    /// Base paths of the member recordings, relative to `dir`.
+    pub fn recordings<P: AsRef<Path>>(&self, dir: P) -> Vec<PathBuf> {
+        self.collection.streams.iter().map(|s| dir.as_ref().join(&s.name)).collect()
+    }
+
+    /// Check every member's metadata hash and dataset `core:sha512`.
+    pub fn verify<P: AsRef<Path>>(&self, dir: P) -> SdrResult<()> {
+        for (stream, base) in self.collection.streams.iter().zip(self.recordings(dir)) {
+            let hash = sha512_file(meta_path(&base))?;
+            if !hash.eq_ignore_ascii_case(&stream.hash) {
+                return Err(SdrError::ConfigError(format!("{}: metadata hash mismatch", stream.name)));
+            }
+            SigMfReader::open(&base)?.verify()?;
+        }
+        Ok(())
+    }
+}
+
 // =============================================================================
 // SigMF Device Driver - File-based SDR Device
 // =============================================================================
@@ -777,6 +1488,8 @@ pub struct SigMfDevice {
     #[allow(dead_code)]
     base_path: PathBuf,
     mode: SigMfMode,
+    /// Channels of the recording (kept once the stream owns the file)
+    num_channels: usize,
 }
 
 /// File device mode
@@ -811,6 +1524,7 @@ impl SigMfDevice {
         Ok(Self {
             name: format!("SigMF: {}", path.as_ref().display()),
             config,
+            num_channels: reader.num_channels(),
             reader: Some(reader),
             writer: None,
             base_path: path.as_ref().to_path_buf(),
@@ -824,7 +1538,17 @@ impl SigMfDevice {
         sample_rate: f64,
         frequency: f64,
     ) -> SdrResult<Self> {
-        let writer = SigMfWriter::create(&path, sample_rate, frequency)?;
+        Self::create_write_multichannel(path, sample_rate, frequency, 1)
+    }
+
+    /// Create a new multi-channel SigMF file for writing.
+    pub fn create_write_multichannel<P: AsRef<Path>>(
+        path: P,
+        sample_rate: f64,
+        frequency: f64,
+        num_channels: usize,
+    ) -> SdrResult<Self> {
+        let writer = SigMfWriter::create_multichannel(&path, sample_rate, frequency, "cf32_le", num_channels)?;
 
         let config = SdrConfig {
             frequency,
@@ -843,6 +1567,7 @@ impl SigMfDevice {
             writer: Some(writer),
             base_path: path.as_ref().to_path_buf(),
             mode: SigMfMode::Write,
+            num_channels,
         })
     }
 
@@ -860,6 +1585,15 @@ impl SigMfDevice {
     pub fn samples_written(&self) -> u64 {
         self.writer.as_ref().map(|w| w.samples_written()).unwrap_or(0)
     }
+
+    /// Get the number of channels in the recording.
+    pub fn num_channels(&self) -> usize {
+        match (&self.reader, &self.writer) {
+            (Some(reader), _) => reader.num_channels(),
+            (None, Some(writer)) => writer.num_channels(),
+            (None, None) => self.num_channels,
+        }
+    }
 }
 
 /// Stream handle for SigMF file operations
@@ -870,6 +1604,7 @@ struct SigMfStream {
     running: bool,
     samples_processed: u64,
     sample_rate: f64,
+    num_channels: usize,
 }
 
 impl StreamHandle for SigMfStream {
@@ -906,11 +1641,55 @@ impl StreamHandle for SigMfStream {
 
         let timestamp = reader.timestamp();
         let count = reader.read_samples(buffer)?;
+        self.samples_processed += (count / self.num_channels) as u64;
+
+        Ok((count, timestamp))
+    }
+
+    fn num_channels(&self) -> usize {
+        self.num_channels
+    }
+
+    fn read_multi(
+        &mut self,
+        buffers: &mut [&mut [IQSample]],
+        _timeout: std::time::Duration,
+    ) -> SdrResult<(usize, Timestamp)> {
+        if !self.running {
+            return Err(SdrError::NotStarted);
+        }
+
+        let reader = self.reader.as_mut().ok_or_else(|| {
+            SdrError::ConfigError("No reader available".to_string())
+        })?;
+
+        let timestamp = reader.timestamp();
+        let count = reader.read_channels(buffers)?;
         self.samples_processed += count as u64;
 
         Ok((count, timestamp))
     }
 
+    fn write_multi(
+        &mut self,
+        buffers: &[&[IQSample]],
+        _timestamp: Option<Timestamp>,
+        _timeout: std::time::Duration,
+    ) -> SdrResult<usize> {
+        if !self.running {
+            return Err(SdrError::NotStarted);
+        }
+
+        let writer = self.writer.as_mut().ok_or_else(|| {
+            SdrError::ConfigError("No writer available".to_string())
+        })?;
+
+        let count = writer.write_channels(buffers)?;
+        self.samples_processed += count as u64;
+
+        Ok(count)
+    }
+
     fn write(
         &mut self,
         buffer: &[IQSample],
@@ -926,7 +1705,7 @@ impl StreamHandle for SigMfStream {
         })?;
 
         let count = writer.write_samples(buffer)?;
-        self.samples_processed += count as u64;
+        self.samples_processed += (count / self.num_channels) as u64;
 
         Ok(count)
     }
@@ -938,6 +1717,7 @@ impl StreamHandle for SigMfStream {
             late_count: 0,
             samples_processed: self.samples_processed,
             buffer_level: 0,
+            ..Default::default()
         }
     }
 
@@ -1046,8 +1826,8 @@ impl SdrDeviceExt for SigMfDevice {
             min_frequency: 0.0,
             max_frequency: 1e12,
             max_sample_rate: 1e12,
-            tx_channels: 1,
-            rx_channels: 1,
+            tx_channels: self.num_channels(),
+            rx_channels: self.num_channels(),
         }
     }
 
@@ -1079,6 +1859,7 @@ impl SdrDeviceExt for SigMfDevice {
             running: false,
             samples_processed: 0,
             sample_rate: self.config.sample_rate,
+            num_channels: self.num_channels,
         }))
     }
 
@@ -1099,6 +1880,7 @@ impl SdrDeviceExt for SigMfDevice {
             running: false,
             samples_processed: 0,
             sample_rate: self.config.sample_rate,
+            num_channels: self.num_channels,
         }))
     }
 
@@ -1112,6 +1894,12 @@ impl SdrDeviceExt for SigMfDevice {
     fn clock(&mut self) -> Option<&mut dyn ClockControl> {
         None // Files don't have clocks
     }
+
+    fn native_format(&self) -> super::SampleFormat {
+        self.metadata()
+            .and_then(|meta| super::SampleFormat::parse(&meta.global.datatype))
+            .unwrap_or_default()
+    }
 }
 
 /// File device driver for the registry.
@@ -1154,6 +1942,7 @@ impl DeviceDriver for FileDriver {
             let mut mode = "read";
             let mut rate = 1e6;
             let mut freq = 915e6;
+            let mut channels = 1;
 
             for part in &parts[1..] {
                 if let Some((key, value)) = part.split_once('=') {
@@ -1161,13 +1950,14 @@ impl DeviceDriver for FileDriver {
                         "mode" => mode = value.trim(),
                         "rate" => rate = value.trim().parse().unwrap_or(1e6),
                         "freq" => freq = value.trim().parse().unwrap_or(915e6),
+                        "channels" => channels = value.trim().parse().unwrap_or(1),
                         _ => {}
                     }
                 }
             }
 
             if mode == "write" {
-                SigMfDevice::create_write(path, rate, freq)
+                SigMfDevice::create_write_multichannel(path, rate, freq, channels)
                     .map(|d| Box::new(d) as Box<dyn SdrDeviceExt>)
             } else {
                 SigMfDevice::open_read(path)
@@ -1469,4 +2259,143 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_multichannel_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().join("array");
+        let ch0: Vec<IQSample> = (0..100).map(|i| IQSample::new(i as f64 / 100.0, 0.0)).collect();
+        let ch1: Vec<IQSample> = (0..100).map(|i| IQSample::new(0.0, -(i as f64) / 100.0)).collect();
+
+        let mut writer = SigMfWriter::create_multichannel(&base, 1e6, 2.4e9, "ci16_le", 2).unwrap();
+        assert_eq!(writer.write_channels(&[&ch0, &ch1]).unwrap(), 100);
+        // Interleaved writes must cover whole samples
+        assert!(writer.write_samples(&ch0[..3]).is_err());
+        assert_eq!(writer.samples_written(), 100);
+        let meta = writer.close().unwrap();
+        assert_eq!(meta.global.num_channels, Some(2));
+
+        let mut reader = SigMfReader::open(&base).unwrap();
+        assert_eq!(reader.num_channels(), 2);
+        assert_eq!(reader.total_samples(), 100);
+        reader.seek(40).unwrap();
+        let mut a = vec![IQSample::new(0.0, 0.0); 80];
+        let mut b = vec![IQSample::new(0.0, 0.0); 80];
+        assert_eq!(reader.read_channels(&mut [&mut a, &mut b]).unwrap(), 60);
+        assert!((a[0] - ch0[40]).norm() < 1e-4);
+        assert!((b[59] - ch1[99]).norm() < 1e-4);
+
+        // The file device exposes the channels as a multi-channel stream
+        let registry = super::super::create_default_registry();
+        let mut device = registry.create(&format!("file://{}", base.display())).unwrap();
+        assert_eq!(device.capabilities().rx_channels, 2);
+        let mut rx = device.create_rx_stream(StreamConfig::default()).unwrap();
+        rx.start().unwrap();
+        let (n, ts) = rx.read_multi(&mut [&mut a[..10], &mut b[..10]], std::time::Duration::ZERO).unwrap();
+        assert_eq!((n, ts.sample.samples()), (10, 0));
+        assert!((b[9] - ch1[9]).norm() < 1e-4);
+    }
+
+    #[test]
+    fn test_detections_annotated_and_extracted() {
+        use r4w_core::analysis::{EnergyDetector, WaterfallGenerator};
+
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().join("wideband");
+        let fs = 1e6;
+
+        // Two bursts at different times and frequencies, over a weak floor
+        let mut seed = 1u32;
+        let mut uniform = move || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            seed as f64 / u32::MAX as f64 - 0.5
+        };
+        let samples: Vec<IQSample> = (0..100_000usize)
+            .map(|n| {
+                let t = n as f64 / fs;
+                let mut s = IQSample::new(uniform(), uniform()) * 1e-3;
+                if (10_000..40_000).contains(&n) {
+                    s += IQSample::from_polar(0.3, 2.0 * std::f64::consts::PI * -100e3 * t);
+                }
+                if (60_000..90_000).contains(&n) {
+                    s += IQSample::from_polar(0.3, 2.0 * std::f64::consts::PI * 300e3 * t);
+                }
+                s
+            })
+            .collect();
+        let mut writer = SigMfWriter::create(&base, fs, 433e6).unwrap();
+        writer.write_samples(&samples).unwrap();
+        writer.close().unwrap();
+
+        let waterfall = WaterfallGenerator::new(512).compute(&samples, fs);
+        let detections = EnergyDetector::new().detect(&waterfall);
+        assert_eq!(detections.len(), 2, "{}", EnergyDetector::format_text(&detections));
+
+        let mut meta = SigMfMeta::load(&base).unwrap();
+        annotate_detections(&mut meta, &detections);
+        meta.save(&base).unwrap();
+
+        let reloaded = SigMfMeta::load(base.with_extension("sigmf-meta")).unwrap();
+        assert_eq!(reloaded.annotations.len(), 2);
+        let upper = reloaded.annotations[1].freq_upper_edge.unwrap();
+        let lower = reloaded.annotations[1].freq_lower_edge.unwrap();
+        assert!(lower < 433.3e6 && upper > 433.3e6, "{}..{}", lower, upper);
+
+        let paths = extract_detections(&samples, &meta, &detections, &base, 0.5).unwrap();
+        assert_eq!(paths.len(), 2);
+        let reader = SigMfReader::open(&paths[0]).unwrap();
+        assert!((reader.frequency() - 432.9e6).abs() < 2e3);
+        assert!(reader.sample_rate() < fs / 4.0);
+        assert!(reader.total_samples() > 0);
+        assert_eq!(
+            reader.metadata().global.extensions.get("r4w:source_sample_start"),
+            Some(&serde_json::json!(detections[0].start_sample))
+        );
+    }
+
+    #[test]
+    fn test_archive_collection_and_sha512() {
+        let temp_dir = TempDir::new().unwrap();
+        let samples: Vec<IQSample> = (0..1000).map(|i| IQSample::new(i as f64 / 1000.0, 0.25)).collect();
+        let mut bases = Vec::new();
+        for (name, freq) in [("ch1", 915e6), ("ch2", 916e6)] {
+            let base = temp_dir.path().join(name);
+            let mut writer = SigMfWriter::create(&base, 1e6, freq).unwrap();
+            writer.write_samples(&samples).unwrap();
+            writer.close().unwrap();
+            assert!(SigMfReader::open(&base).unwrap().verify().unwrap());
+            bases.push(base);
+        }
+
+        let mut collection = SigMfCollection::new("two channels");
+        for base in &bases {
+            collection.add_recording(base).unwrap();
+        }
+        collection.save(temp_dir.path().join("both")).unwrap();
+        let loaded = SigMfCollection::load(temp_dir.path().join("both.sigmf-collection")).unwrap();
+        assert_eq!(loaded.collection.streams.len(), 2);
+        loaded.verify(temp_dir.path()).unwrap();
+
+        let archive_path = temp_dir.path().join("both.sigmf");
+        SigMfArchive::create(&archive_path, &bases, Some(&loaded)).unwrap();
+        let archive = SigMfArchive::open(&archive_path).unwrap();
+        assert_eq!(archive.recordings(), vec!["ch1/ch1", "ch2/ch2"]);
+        assert_eq!(archive.collection().unwrap().unwrap().collection.streams[1].name, "ch2");
+
+        let mut reader = SigMfReader::open_archive(&archive_path, Some("ch2")).unwrap();
+        assert_eq!(reader.frequency(), 916e6);
+        assert!(reader.verify().unwrap());
+        let read = reader.read_all().unwrap();
+        assert_eq!(read.len(), 1000);
+        assert!((read[500].re - 0.5).abs() < 1e-6);
+        assert_eq!(SigMfReader::open(&archive_path).unwrap().frequency(), 915e6);
+
+        // Corrupt one sample: the dataset no longer matches core:sha512
+        let data_path = bases[0].with_extension("sigmf-data");
+        let mut data = std::fs::read(&data_path).unwrap();
+        data[100] ^= 0xff;
+        std::fs::write(&data_path, data).unwrap();
+        assert!(SigMfReader::open(&bases[0]).unwrap().verify().is_err());
+        assert!(loaded.verify(temp_dir.path()).is_err());
+    }
 }
@@ -0,0 +1,1899 @@
+//! Simulated SDR device for the HAL (`sim://` URIs)
+//!
+//! This driver exposes an in-process radio through the same
+//! [`SdrDeviceExt`]/[`StreamHandle`] interfaces as real hardware, so code
+//! written against the HAL runs unchanged in CI.
+//!
+//! ## Model
+//!
+//! ```text
+//!   TX stream ──► air (timestamped bursts) ──► Channel ──► array ──► CFO ──► + noise ──► RX stream
+//!                      ▲                                                               │
+//!                source file                                         device timeline (SampleClock)
+//! ```
+//!
+//! The device keeps a single sample-count timeline. By default it is
+//! *virtual*: reading RX samples and filling the TX buffer advance time,
+//! so runs are as fast as the CPU allows and fully reproducible. With
+//! `realtime=true` the timeline additionally follows the wall clock.
+//!
+//! - **Overflow**: an RX stream that falls more than its buffer capacity
+//!   (`buffer_size · num_buffers`) behind the timeline loses the oldest
+//!   samples and its `overflow_count` increments.
+//! - **Underflow**: a TX stream whose queued samples ran out before the
+//!   next write (without a timestamp) increments `underflow_count`.
+//! - **Late packets**: timed TX writes whose timestamp is already in the
+//!   past are dropped and counted in `late_count`.
+//! - **PPS**: a virtual pulse fires on every whole second of the device
+//!   timeline; `set_time_at_pps` takes effect on the next edge.
+//! - **Timed commands**: [`TimedControl`] commands execute on the exact
+//!   sample they were scheduled for. The `source` emitter sits at the
+//!   initial `freq`; tuning away shifts it within the passband and loses
+//!   it beyond half the sample rate. Transmitted bursts share the LO and
+//!   follow it. RX and TX gains scale the samples they are in effect for,
+//!   relative to the 30 dB default.
+//! - **Bursts**: [`TxMetadata`] start/end-of-burst flags frame TX bursts.
+//!   Only gaps inside a burst are underflows, and a late start-of-burst
+//!   drops the whole burst. RX streams can start at a future time with
+//!   [`start_at`](StreamHandle::start_at).
+//! - **Channels**: with `channels=N` the device has an N-element antenna
+//!   array ([`ArrayGeometry`]). Everything on the air arrives as a plane
+//!   wave from `aoa`, so each RX channel sees the same faded signal with
+//!   its own steering phase and inter-element delay, plus independent
+//!   noise. Multi-channel TX streams are combined towards the receiver
+//!   with the steering vector at `aod`. Use
+//!   [`read_multi`](StreamHandle::read_multi)/[`write_multi`](StreamHandle::write_multi)
+//!   with one buffer per channel.
+//!
+//! ## URI Parameters
+//!
+//! `sim://key=value,key=value,...`
+//!
+//! | Key        | Default | Meaning                                                  |
+//! |------------|---------|----------------------------------------------------------|
+//! | `snr`      | 30      | SNR (dB) of a unit-power signal over the noise floor     |
+//! | `cfo`      | 0       | Carrier frequency offset applied to received signals (Hz)|
+//! | `model`    | awgn    | ideal, awgn, multipath, rayleigh, rician, jakes, epa, eva, etu |
+//! | `doppler`  | 0       | Maximum Doppler (Hz) for `jakes` and TDL models          |
+//! | `delay`    | 0       | TX → RX propagation delay (samples)                      |
+//! | `seed`     | random  | Seed for noise and fading                                |
+//! | `rate`     | 1e6     | Initial sample rate (Hz)                                 |
+//! | `freq`     | 915e6   | Initial center frequency (Hz)                            |
+//! | `loopback` | true    | Route transmitted samples to the receiver                |
+//! | `source`   | —       | cf32 or SigMF file placed on the air at time zero        |
+//! | `realtime` | false   | Pace the timeline to the wall clock                      |
+//! | `channels` | 1       | Number of antenna elements (RX and TX channels)          |
+//! | `array`    | ula     | Element layout: ula, uca                                 |
+//! | `spacing`  | λ/2     | Distance between neighbouring elements (m)               |
+//! | `aoa`      | 0       | Azimuth of arrival of received signals (degrees)         |
+//! | `elevation`| 0       | Elevation of arrival (degrees)                           |
+//! | `aod`      | 0       | Azimuth of departure for multi-channel TX (degrees)      |
+//! | `skew`     | 0       | Extra cable delay per element index (ns)                 |
+//!
+//! ## Example
+//!
+//! ```rust
+//! use r4w_sim::hal::{create_default_registry, StreamConfig, StreamDirection};
+//! use r4w_core::types::IQSample;
+//! use std::time::Duration;
+//!
+//! let registry = create_default_registry();
+//! let mut device = registry.create("sim://snr=20,cfo=500,seed=1").unwrap();
+//! device.tuner().set_sample_rate(1e6).unwrap();
+//!
+//! let mut rx = device.create_rx_stream(StreamConfig::default()).unwrap();
+//! rx.start().unwrap();
+//! let mut buffer = vec![IQSample::new(0.0, 0.0); 1024];
+//! let (n, ts) = rx.read(&mut buffer, Duration::from_millis(10)).unwrap();
+//! assert_eq!(n, 1024);
+//! assert_eq!(ts.sample.samples(), 0);
+//! ```
+
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use r4w_core::timing::{HardwareClock, SampleClock, TimeSource, Timestamp};
+use r4w_core::types::IQSample;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+
+use super::sigmf::SigMfReader;
+use super::{
+    channel_mismatch, ClockControl, ClockSource, DeviceDriver, SdrDeviceExt, StreamConfig,
+    StreamDirection, StreamHandle, StreamStatus, TimedCommand, TimedControl, TunerControl,
+    TxMetadata,
+};
+use crate::array::{ArrayGeometry, ArrayLayout};
+use crate::channel::{Channel, ChannelConfig, ChannelModel, TdlProfile};
+use crate::device::{DeviceCapabilities, DeviceInfo, SdrConfig, SdrError, SdrResult};
+
+/// Simulated hardware tick rate (Hz)
+const TICK_RATE: f64 = 100_000_000.0;
+
+/// RX/TX gain (dB) at which samples pass unscaled (the [`SdrConfig`] default)
+const UNITY_GAIN_DB: f64 = 30.0;
+
+/// Simulator parameters parsed from a `sim://` URI
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimParams {
+    /// SNR (dB) of a unit-power signal over the noise floor
+    pub snr_db: f64,
+    /// Carrier frequency offset (Hz)
+    pub cfo_hz: f64,
+    /// Propagation channel model
+    pub model: ChannelModel,
+    /// TDL profile (for `epa`/`eva`/`etu`)
+    pub tdl_profile: TdlProfile,
+    /// Maximum Doppler frequency (Hz)
+    pub doppler_hz: f64,
+    /// TX → RX delay (samples)
+    pub delay_samples: u64,
+    /// RNG seed (None = from entropy)
+    pub seed: Option<u64>,
+    /// Initial sample rate (Hz)
+    pub sample_rate: f64,
+    /// Initial center frequency (Hz)
+    pub frequency: f64,
+    /// Route TX samples to RX
+    pub loopback: bool,
+    /// File placed on the air at time zero
+    pub source: Option<String>,
+    /// Pace the timeline to the wall clock
+    pub realtime: bool,
+    /// Number of antenna elements
+    pub channels: usize,
+    /// Element layout
+    pub array: ArrayLayout,
+    /// Element spacing (m; None = half a wavelength at `frequency`)
+    pub spacing_m: Option<f64>,
+    /// Azimuth of arrival (degrees)
+    pub aoa_deg: f64,
+    /// Elevation of arrival (degrees)
+    pub elevation_deg: f64,
+    /// Azimuth of departure for multi-channel TX (degrees)
+    pub aod_deg: f64,
+    /// Extra cable delay per element index (seconds)
+    pub skew_s: f64,
+}
+
+impl Default for SimParams {
+    fn default() -> Self {
+        Self {
+            snr_db: 30.0,
+            cfo_hz: 0.0,
+            model: ChannelModel::Awgn,
+            tdl_profile: TdlProfile::Epa,
+            doppler_hz: 0.0,
+            delay_samples: 0,
+            seed: None,
+            sample_rate: 1e6,
+            frequency: 915e6,
+            loopback: true,
+            source: None,
+            realtime: false,
+            channels: 1,
+            array: ArrayLayout::Ula,
+            spacing_m: None,
+            aoa_deg: 0.0,
+            elevation_deg: 0.0,
+            aod_deg: 0.0,
+            skew_s: 0.0,
+        }
+    }
+}
+
+impl SimParams {
+    /// Parse the argument part of a `sim://` URI
+    pub fn parse(args: &str) -> SdrResult<Self> {
+        let mut params = Self::default();
+
+        for part in args.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(|| {
+                SdrError::ConfigError(format!("Expected key=value, got '{}'", part))
+            })?;
+            let (key, value) = (key.trim(), value.trim());
+
+            match key {
+                "snr" => params.snr_db = parse_num(key, value)?,
+                "cfo" => params.cfo_hz = parse_num(key, value)?,
+                "doppler" => params.doppler_hz = parse_num(key, value)?,
+                "delay" => params.delay_samples = parse_num::<f64>(key, value)? as u64,
+                "seed" => params.seed = Some(parse_num(key, value)?),
+                "rate" => params.sample_rate = parse_num(key, value)?,
+                "freq" => params.frequency = parse_num(key, value)?,
+                "loopback" => params.loopback = parse_bool(key, value)?,
+                "realtime" => params.realtime = parse_bool(key, value)?,
+                "source" => params.source = Some(value.to_string()),
+                "channels" => params.channels = parse_num(key, value)?,
+                "spacing" => params.spacing_m = Some(parse_num(key, value)?),
+                "aoa" => params.aoa_deg = parse_num(key, value)?,
+                "elevation" => params.elevation_deg = parse_num(key, value)?,
+                "aod" => params.aod_deg = parse_num(key, value)?,
+                "skew" => params.skew_s = parse_num::<f64>(key, value)? * 1e-9,
+                "array" => {
+                    params.array = match value.to_lowercase().as_str() {
+                        "ula" | "linear" => ArrayLayout::Ula,
+                        "uca" | "circular" => ArrayLayout::Uca,
+                        other => {
+                            return Err(SdrError::ConfigError(format!(
+                                "Unknown array layout '{}'", other
+                            )))
+                        }
+                    }
+                }
+                "model" => {
+                    let (model, profile) = match value.to_lowercase().as_str() {
+                        "ideal" => (ChannelModel::Ideal, TdlProfile::Epa),
+                        "awgn" => (ChannelModel::Awgn, TdlProfile::Epa),
+                        "multipath" => (ChannelModel::Multipath, TdlProfile::Epa),
+                        "rayleigh" => (ChannelModel::Rayleigh, TdlProfile::Epa),
+                        "rician" => (ChannelModel::Rician, TdlProfile::Epa),
+                        "jakes" => (ChannelModel::JakesFading, TdlProfile::Epa),
+                        "epa" => (ChannelModel::TdlAwgn, TdlProfile::Epa),
+                        "eva" => (ChannelModel::TdlAwgn, TdlProfile::Eva),
+                        "etu" => (ChannelModel::TdlAwgn, TdlProfile::Etu),
+                        other => {
+                            return Err(SdrError::ConfigError(format!(
+                                "Unknown channel model '{}'", other
+                            )))
+                        }
+                    };
+                    params.model = model;
+                    params.tdl_profile = profile;
+                }
+                other => {
+                    return Err(SdrError::ConfigError(format!(
+                        "Unknown simulator parameter '{}'", other
+                    )))
+                }
+            }
+        }
+
+        if params.sample_rate <= 0.0 {
+            return Err(SdrError::ConfigError("Sample rate must be positive".to_string()));
+        }
+        if params.channels == 0 {
+            return Err(SdrError::ConfigError("channels must be at least 1".to_string()));
+        }
+
+        Ok(params)
+    }
+
+    /// Antenna array described by the parameters
+    pub fn array_geometry(&self) -> ArrayGeometry {
+        let spacing = self
+            .spacing_m
+            .unwrap_or_else(|| ArrayGeometry::half_wavelength(self.frequency));
+        let delays = (0..self.channels).map(|k| k as f64 * self.skew_s).collect();
+        self.array.build(self.channels, spacing).with_element_delays(delays)
+    }
+
+    /// Channel configuration for the fading part of the model
+    ///
+    /// Noise and CFO are applied by the device itself (against an absolute
+    /// noise floor), so the channel's own AWGN is disabled.
+    fn channel_config(&self, sample_rate: f64) -> ChannelConfig {
+        let mut config = ChannelConfig {
+            model: self.model,
+            snr_db: 300.0,
+            sample_rate,
+            ..Default::default()
+        };
+        match self.model {
+            ChannelModel::Multipath => {
+                config.multipath_delay = 3;
+                config.multipath_amplitude = 0.5;
+            }
+            ChannelModel::JakesFading => {
+                config.doppler_enabled = true;
+                config.max_doppler_hz = self.doppler_hz.max(1.0);
+            }
+            ChannelModel::TdlAwgn => {
+                config.tdl_enabled = true;
+                config.tdl_profile = self.tdl_profile;
+                if self.doppler_hz > 0.0 {
+                    config.model = ChannelModel::FrequencySelective;
+                    config.doppler_enabled = true;
+                    config.max_doppler_hz = self.doppler_hz;
+                }
+            }
+            _ => {}
+        }
+        config
+    }
+}
+
+pub(super) fn parse_num<T: std::str::FromStr>(key: &str, value: &str) -> SdrResult<T> {
+    value
+        .parse()
+        .map_err(|_| SdrError::ConfigError(format!("Invalid value '{}' for '{}'", value, key)))
+}
+
+pub(super) fn parse_bool(key: &str, value: &str) -> SdrResult<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(SdrError::ConfigError(format!("Invalid value '{}' for '{}'", value, key))),
+    }
+}
+
+/// Load a source file: SigMF (by `.sigmf-*` extension) or raw cf32 little-endian
+fn load_source(path: &str) -> SdrResult<Vec<IQSample>> {
+    if path.contains(".sigmf") {
+        let mut reader = SigMfReader::open(path)?;
+        let mut samples = reader.read_all()?;
+        // A multi-channel recording contributes its first channel
+        let channels = reader.num_channels();
+        if channels > 1 {
+            samples = samples.into_iter().step_by(channels).collect();
+        }
+        return Ok(samples);
+    }
+
+    let file = File::open(Path::new(path))
+        .map_err(|e| SdrError::ConfigError(format!("Cannot open source '{}': {}", path, e)))?;
+    let mut bytes = Vec::new();
+    BufReader::new(file)
+        .read_to_end(&mut bytes)
+        .map_err(|e| SdrError::HardwareError(format!("Cannot read source '{}': {}", path, e)))?;
+
+    Ok(bytes
+        .chunks_exact(8)
+        .map(|c| {
+            let re = f32::from_le_bytes([c[0], c[1], c[2], c[3]]);
+            let im = f32::from_le_bytes([c[4], c[5], c[6], c[7]]);
+            IQSample::new(re as f64, im as f64)
+        })
+        .collect())
+}
+
+/// A transmitted burst on the air
+#[derive(Debug)]
+struct Burst {
+    start: u64,
+    samples: Vec<IQSample>,
+}
+
+impl Burst {
+    fn end(&self) -> u64 {
+        self.start + self.samples.len() as u64
+    }
+}
+
+/// Shared device state
+struct SimCore {
+    params: SimParams,
+    sample_rate: f64,
+    /// Current position of the device timeline (samples)
+    now: u64,
+    /// Reported device time minus internal timeline (samples)
+    time_offset: i64,
+    /// Pending `set_time_at_pps`: (edge on internal timeline, new offset)
+    pending_pps: Option<(u64, i64)>,
+    clock_source: ClockSource,
+    time_source: TimeSource,
+    /// Transmitted bursts, sorted by start time
+    air: VecDeque<Burst>,
+    /// Oldest sample any RX stream can still read
+    history: u64,
+    channel: Channel,
+    rng: StdRng,
+    source: Vec<IQSample>,
+    array: ArrayGeometry,
+    /// LO frequency (Hz)
+    frequency: f64,
+    /// LO frequency over the timeline
+    lo_history: Schedule,
+    /// RX and TX gain (dB) over the timeline
+    rx_gain: Schedule,
+    tx_gain: Schedule,
+    bandwidth: f64,
+    /// Timed commands not yet executed (internal time), in order
+    commands: VecDeque<(u64, TimedCommand)>,
+    /// Executed timed commands (reported time)
+    command_log: Vec<(u64, TimedCommand)>,
+    late_commands: u64,
+    /// Last faded samples before `tail_end`, for inter-element delays
+    tail: Vec<IQSample>,
+    tail_end: u64,
+    /// Wall-clock anchor for realtime pacing: (instant, timeline position)
+    epoch: (Instant, u64),
+}
+
+impl SimCore {
+    fn new(params: SimParams) -> SdrResult<Self> {
+        let source = match &params.source {
+            Some(path) => load_source(path)?,
+            None => Vec::new(),
+        };
+        let sample_rate = params.sample_rate;
+        let channel = Self::make_channel(&params, sample_rate);
+        let array = params.array_geometry();
+        let frequency = params.frequency;
+        let rng = match params.seed {
+            Some(seed) => StdRng::seed_from_u64(seed ^ 0x5eed),
+            None => StdRng::from_entropy(),
+        };
+
+        Ok(Self {
+            params,
+            sample_rate,
+            now: 0,
+            time_offset: 0,
+            pending_pps: None,
+            clock_source: ClockSource::Internal,
+            time_source: TimeSource::Freerun,
+            air: VecDeque::new(),
+            history: 0,
+            channel,
+            rng,
+            source,
+            array,
+            frequency,
+            lo_history: Schedule::new(frequency),
+            rx_gain: Schedule::new(UNITY_GAIN_DB),
+            tx_gain: Schedule::new(UNITY_GAIN_DB),
+            bandwidth: sample_rate,
+            commands: VecDeque::new(),
+            command_log: Vec::new(),
+            late_commands: 0,
+            tail: Vec::new(),
+            tail_end: 0,
+            epoch: (Instant::now(), 0),
+        })
+    }
+
+    fn make_channel(params: &SimParams, sample_rate: f64) -> Channel {
+        let config = params.channel_config(sample_rate);
+        match params.seed {
+            Some(seed) => Channel::with_seed(config, seed),
+            None => Channel::new(config),
+        }
+    }
+
+    fn set_sample_rate(&mut self, rate: f64) {
+        self.sample_rate = rate;
+        self.channel = Self::make_channel(&self.params, rate);
+        self.epoch = (Instant::now(), self.now);
+    }
+
+    /// Advance the timeline (never backwards)
+    fn advance_to(&mut self, t: u64) {
+        if t > self.now {
+            self.now = t;
+        }
+        if let Some((edge, offset)) = self.pending_pps {
+            if self.now >= edge {
+                self.time_offset = offset;
+                self.pending_pps = None;
+            }
+        }
+        while self.commands.front().is_some_and(|(t, _)| *t <= self.now) {
+            let (t, command) = self.commands.pop_front().unwrap();
+            self.execute(t, command);
+        }
+        let horizon = self.now.saturating_sub(self.history);
+        while self.air.front().is_some_and(|b| b.end() < horizon) {
+            self.air.pop_front();
+        }
+        self.lo_history.prune(horizon);
+        self.rx_gain.prune(horizon);
+        self.tx_gain.prune(horizon);
+    }
+
+    /// Retune the LO from internal time `t` on
+    fn set_frequency(&mut self, t: u64, freq: f64) {
+        self.frequency = freq;
+        self.lo_history.set(t, freq);
+    }
+
+    /// LO frequency at internal time `t`
+    fn frequency_at(&self, t: u64) -> f64 {
+        self.lo_history.at(t)
+    }
+
+    /// Run a timed command at internal time `t`
+    fn execute(&mut self, t: u64, command: TimedCommand) {
+        match &command {
+            TimedCommand::SetFrequency(freq) => self.set_frequency(t, *freq as f64),
+            TimedCommand::SetRxGain(gain) => self.rx_gain.set(t, gain.clamp(0.0, 76.0)),
+            TimedCommand::SetTxGain(gain) => self.tx_gain.set(t, gain.clamp(0.0, 76.0)),
+            TimedCommand::SetBandwidth(bw) => self.bandwidth = *bw,
+        }
+        let reported = self.reported(t);
+        self.command_log.push((reported, command));
+    }
+
+    /// Follow the wall clock in realtime mode
+    fn sync_wall(&mut self) {
+        if self.params.realtime {
+            let (instant, base) = self.epoch;
+            let elapsed = instant.elapsed().as_secs_f64();
+            self.advance_to(base + (elapsed * self.sample_rate) as u64);
+        }
+    }
+
+    /// Wall-clock time at which the timeline reaches `t` (realtime mode)
+    fn wall_deadline(&self, t: u64) -> Instant {
+        let (instant, base) = self.epoch;
+        let secs = t.saturating_sub(base) as f64 / self.sample_rate;
+        instant + Duration::from_secs_f64(secs)
+    }
+
+    fn reported(&self, internal: u64) -> u64 {
+        (internal as i64 + self.time_offset).max(0) as u64
+    }
+
+    fn internal(&self, reported: u64) -> i64 {
+        reported as i64 - self.time_offset
+    }
+
+    fn timestamp_at(&self, internal: u64) -> Timestamp {
+        let samples = self.reported(internal);
+        let mut hw = HardwareClock::new(TICK_RATE);
+        hw.set_time_seconds(samples as f64 / self.sample_rate);
+        hw.set_pps_locked(self.pps_locked());
+        Timestamp::at_sample(samples, self.sample_rate).with_hardware(hw)
+    }
+
+    fn pps_locked(&self) -> bool {
+        matches!(self.time_source, TimeSource::Pps | TimeSource::Gps | TimeSource::External)
+    }
+
+    /// Next PPS edge strictly after the current time (internal timeline)
+    fn next_pps_edge(&self) -> u64 {
+        let per_second = self.sample_rate.round().max(1.0) as u64;
+        let reported = self.reported(self.now);
+        let next = (reported / per_second + 1) * per_second;
+        self.internal(next).max(self.now as i64 + 1) as u64
+    }
+
+    /// Validate the channel list of a new stream
+    fn check_channels(&self, channels: &[usize], direction: &str) -> SdrResult<()> {
+        let count = self.array.len();
+        let duplicate = channels.iter().enumerate().any(|(i, c)| channels[..i].contains(c));
+        if channels.is_empty() || duplicate || channels.iter().any(|&c| c >= count) {
+            return Err(SdrError::ConfigError(format!(
+                "Simulator has {} {} channel(s), got {:?}",
+                count, direction, channels
+            )));
+        }
+        Ok(())
+    }
+
+    /// Received samples for `[start, start + n)` at the given elements
+    fn render(&mut self, start: u64, n: usize, elements: &[usize]) -> Vec<Vec<IQSample>> {
+        let end = start + n as u64;
+        let mut signal = vec![IQSample::new(0.0, 0.0); n];
+
+        // The source emitter stays at the initial frequency while the LO moves
+        let nyquist = self.sample_rate / 2.0;
+        for (i, s) in self.source.iter().skip(start as usize).take(n).enumerate() {
+            let t = start + i as u64;
+            let offset = self.params.frequency - self.frequency_at(t);
+            if offset == 0.0 {
+                signal[i] += s;
+            } else if offset.abs() < nyquist {
+                let phase = (2.0 * PI * offset * t as f64 / self.sample_rate) % (2.0 * PI);
+                signal[i] += s * IQSample::from_polar(1.0, phase);
+            }
+        }
+
+        if self.params.loopback {
+            let delay = self.params.delay_samples;
+            for burst in &self.air {
+                let b_start = burst.start + delay;
+                let b_end = burst.end() + delay;
+                if b_end <= start || b_start >= end {
+                    continue;
+                }
+                let from = b_start.max(start);
+                let to = b_end.min(end);
+                for t in from..to {
+                    let gain = self.tx_gain.linear_at(t - delay);
+                    signal[(t - start) as usize] += burst.samples[(t - b_start) as usize] * gain;
+                }
+            }
+        }
+
+        let faded = self.channel.apply(&signal);
+
+        // Plane wave across the array; the faded tail carries delays across reads
+        let (aoa, elevation) = (self.params.aoa_deg, self.params.elevation_deg);
+        let mut history = std::mem::take(&mut self.tail);
+        if self.tail_end != start {
+            history.clear();
+        }
+        let mut rx = self.array.apply(
+            &faded, &history, elements, aoa, elevation, self.frequency, self.sample_rate,
+        );
+        history.extend_from_slice(&faded);
+        let keep = self.array.history_len(aoa, elevation, self.sample_rate);
+        history.drain(..history.len().saturating_sub(keep));
+        self.tail = history;
+        self.tail_end = end;
+
+        let noise_std = (10.0_f64.powf(-self.params.snr_db / 10.0) / 2.0).sqrt();
+        let noise = Normal::new(0.0, noise_std).unwrap();
+        let cfo_step = 2.0 * PI * self.params.cfo_hz / self.sample_rate;
+        for channel in rx.iter_mut() {
+            for (i, s) in channel.iter_mut().enumerate() {
+                if self.params.cfo_hz != 0.0 {
+                    let phase = (cfo_step * (start + i as u64) as f64) % (2.0 * PI);
+                    *s *= IQSample::new(phase.cos(), phase.sin());
+                }
+                *s += IQSample::new(noise.sample(&mut self.rng), noise.sample(&mut self.rng));
+                *s *= self.rx_gain.linear_at(start + i as u64);
+            }
+        }
+
+        rx
+    }
+}
+
+/// A device setting and its changes as (internal time, value), oldest first
+#[derive(Debug)]
+struct Schedule(VecDeque<(u64, f64)>);
+
+impl Schedule {
+    fn new(value: f64) -> Self {
+        Self(VecDeque::from([(0, value)]))
+    }
+
+    /// Latest value
+    fn current(&self) -> f64 {
+        self.0.back().map_or(0.0, |(_, v)| *v)
+    }
+
+    /// Change the value from internal time `t` on
+    fn set(&mut self, t: u64, value: f64) {
+        while self.0.back().is_some_and(|(from, _)| *from >= t) && self.0.len() > 1 {
+            self.0.pop_back();
+        }
+        self.0.push_back((t, value));
+    }
+
+    /// Value in effect at internal time `t`
+    fn at(&self, t: u64) -> f64 {
+        self.0
+            .iter()
+            .rev()
+            .find(|(from, _)| *from <= t)
+            .or(self.0.front())
+            .map_or(0.0, |(_, v)| *v)
+    }
+
+    /// Amplitude factor of a gain in dB at internal time `t`, relative to [`UNITY_GAIN_DB`]
+    fn linear_at(&self, t: u64) -> f64 {
+        10.0_f64.powf((self.at(t) - UNITY_GAIN_DB) / 20.0)
+    }
+
+    /// Forget changes superseded before `horizon`
+    fn prune(&mut self, horizon: u64) {
+        while self.0.len() > 1 && self.0[1].0 <= horizon {
+            self.0.pop_front();
+        }
+    }
+}
+
+fn lock(core: &Arc<Mutex<SimCore>>) -> MutexGuard<'_, SimCore> {
+    core.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// RX stream on the simulated device
+struct SimRxStream {
+    core: Arc<Mutex<SimCore>>,
+    /// Array elements read by the stream
+    channels: Vec<usize>,
+    running: bool,
+    cursor: u64,
+    capacity: u64,
+    status: StreamStatus,
+}
+
+impl StreamHandle for SimRxStream {
+    fn direction(&self) -> StreamDirection {
+        StreamDirection::Rx
+    }
+
+    fn start(&mut self) -> SdrResult<()> {
+        if self.running {
+            return Err(SdrError::AlreadyRunning);
+        }
+        let mut core = lock(&self.core);
+        core.sync_wall();
+        self.cursor = core.now;
+        self.running = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> SdrResult<()> {
+        self.running = false;
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn read(&mut self, buffer: &mut [IQSample], timeout: Duration) -> SdrResult<(usize, Timestamp)> {
+        self.read_multi(&mut [buffer], timeout)
+    }
+
+    fn start_at(&mut self, time: Timestamp) -> SdrResult<()> {
+        if self.running {
+            return Err(SdrError::AlreadyRunning);
+        }
+        let mut core = lock(&self.core);
+        core.sync_wall();
+        let internal = core.internal(time.sample.samples());
+        if internal < core.now as i64 {
+            self.status.late_count += 1;
+            self.status.last_late = Some(time);
+            self.cursor = core.now;
+        } else {
+            self.cursor = internal as u64;
+        }
+        self.running = true;
+        Ok(())
+    }
+
+    fn num_channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    fn read_multi(&mut self, buffers: &mut [&mut [IQSample]], timeout: Duration) -> SdrResult<(usize, Timestamp)> {
+        if buffers.len() != self.channels.len() {
+            return Err(channel_mismatch(self.channels.len(), buffers.len()));
+        }
+        if !self.running {
+            return Err(SdrError::NotStarted);
+        }
+
+        let wanted = buffers.iter().map(|b| b.len()).min().unwrap_or(0) as u64;
+        let realtime = lock(&self.core).params.realtime;
+        if realtime {
+            // Wait for the hardware to produce the samples (up to the timeout)
+            let deadline = lock(&self.core).wall_deadline(self.cursor + wanted);
+            let limit = Instant::now() + timeout;
+            let until = deadline.min(limit);
+            let now = Instant::now();
+            if until > now {
+                std::thread::sleep(until - now);
+            }
+        }
+
+        let mut core = lock(&self.core);
+        core.sync_wall();
+
+        // Samples older than the buffer capacity have been overwritten
+        if core.now > self.cursor + self.capacity {
+            self.status.overflow_count += 1;
+            self.cursor = core.now - self.capacity;
+        }
+
+        if !realtime {
+            // Reading drives the virtual timeline
+            let target = self.cursor + wanted;
+            core.advance_to(target);
+        }
+
+        let n = core.now.saturating_sub(self.cursor).min(wanted) as usize;
+        let timestamp = core.timestamp_at(self.cursor);
+        let samples = core.render(self.cursor, n, &self.channels);
+        for (buffer, channel) in buffers.iter_mut().zip(&samples) {
+            buffer[..n].copy_from_slice(channel);
+        }
+
+        self.cursor += n as u64;
+        self.status.samples_processed += n as u64;
+        self.status.buffer_level = core.now.saturating_sub(self.cursor) as usize;
+        Ok((n, timestamp))
+    }
+
+    fn write(&mut self, _buffer: &[IQSample], _timestamp: Option<Timestamp>, _timeout: Duration) -> SdrResult<usize> {
+        Err(SdrError::Unsupported("Cannot write to an RX stream".to_string()))
+    }
+
+    fn status(&self) -> StreamStatus {
+        self.status.clone()
+    }
+
+    fn timestamp(&self) -> Timestamp {
+        lock(&self.core).timestamp_at(self.cursor)
+    }
+
+    fn available(&self) -> usize {
+        let core = lock(&self.core);
+        (core.now.saturating_sub(self.cursor)).min(self.capacity) as usize
+    }
+
+    fn free_space(&self) -> usize {
+        0
+    }
+}
+
+/// TX stream on the simulated device
+struct SimTxStream {
+    core: Arc<Mutex<SimCore>>,
+    /// Array elements driven by the stream
+    channels: Vec<usize>,
+    running: bool,
+    /// End of the last queued sample (internal timeline)
+    cursor: Option<u64>,
+    capacity: u64,
+    /// Discarding the rest of a burst whose start was late
+    dropping: bool,
+    status: StreamStatus,
+}
+
+impl SimTxStream {
+    /// Combine per-channel buffers into the field radiated towards the receiver
+    fn combine(&self, buffers: &[&[IQSample]]) -> SdrResult<Vec<IQSample>> {
+        if buffers.len() != self.channels.len() {
+            return Err(channel_mismatch(self.channels.len(), buffers.len()));
+        }
+        let len = buffers.iter().map(|b| b.len()).min().unwrap_or(0);
+
+        // Narrowband: each element contributes with its steering phase only
+        let weights = {
+            let core = lock(&self.core);
+            let steering = core.array.steering_vector(core.params.aod_deg, core.params.elevation_deg, core.frequency);
+            self.channels.iter().map(|&c| steering[c]).collect::<Vec<_>>()
+        };
+        let mut combined = vec![IQSample::new(0.0, 0.0); len];
+        for (buffer, weight) in buffers.iter().zip(&weights) {
+            for (out, s) in combined.iter_mut().zip(buffer.iter()) {
+                *out += s * weight;
+            }
+        }
+        Ok(combined)
+    }
+
+    fn end_burst(&mut self) {
+        self.cursor = None;
+        self.status.bursts_completed += 1;
+    }
+
+    /// Put one combined signal on the air
+    fn queue(&mut self, buffer: &[IQSample], metadata: &TxMetadata, timeout: Duration) -> SdrResult<usize> {
+        if !self.running {
+            return Err(SdrError::NotStarted);
+        }
+        if metadata.start_of_burst {
+            // A new burst never underflows against the previous one
+            self.cursor = None;
+            self.dropping = false;
+        }
+        if self.dropping {
+            if metadata.end_of_burst {
+                self.dropping = false;
+                self.cursor = None;
+            }
+            return Ok(buffer.len());
+        }
+        if buffer.is_empty() {
+            if metadata.end_of_burst {
+                self.end_burst();
+            }
+            return Ok(0);
+        }
+
+        let mut core = lock(&self.core);
+        core.sync_wall();
+
+        let start = match &metadata.time {
+            Some(ts) => {
+                let internal = core.internal(ts.sample.samples());
+                if internal < core.now as i64 {
+                    // Late packet: the hardware drops it (and the rest of its burst)
+                    self.status.late_count += 1;
+                    self.status.last_late = Some(ts.clone());
+                    self.dropping = metadata.start_of_burst && !metadata.end_of_burst;
+                    return Ok(buffer.len());
+                }
+                internal as u64
+            }
+            None => match self.cursor {
+                Some(end) if end < core.now => {
+                    self.status.underflow_count += 1;
+                    core.now
+                }
+                Some(end) => end,
+                None => core.now,
+            },
+        };
+
+        // Buffer space: samples may be queued up to `capacity` ahead of now
+        let mut len = buffer.len() as u64;
+        let end = start + len;
+        if end > core.now + self.capacity {
+            if core.params.realtime {
+                let deadline = core.wall_deadline(end - self.capacity);
+                let limit = Instant::now() + timeout;
+                drop(core);
+                let now = Instant::now();
+                let until = deadline.min(limit);
+                if until > now {
+                    std::thread::sleep(until - now);
+                }
+                core = lock(&self.core);
+                core.sync_wall();
+                let room = (core.now + self.capacity).saturating_sub(start);
+                len = len.min(room);
+            } else {
+                // The virtual hardware drains the buffer as needed
+                core.advance_to(end - self.capacity);
+            }
+        }
+        if len == 0 {
+            return Ok(0);
+        }
+
+        // The TX gain is applied as the burst goes on the air
+        let samples = buffer[..len as usize].to_vec();
+        let burst = Burst { start, samples };
+        let pos = core.air.partition_point(|b| b.start <= start);
+        core.air.insert(pos, burst);
+
+        self.cursor = Some(start + len);
+        self.status.samples_processed += len;
+        self.status.buffer_level = (start + len).saturating_sub(core.now) as usize;
+        drop(core);
+        if metadata.end_of_burst && len as usize == buffer.len() {
+            self.end_burst();
+        }
+        Ok(len as usize)
+    }
+}
+
+impl StreamHandle for SimTxStream {
+    fn direction(&self) -> StreamDirection {
+        StreamDirection::Tx
+    }
+
+    fn start(&mut self) -> SdrResult<()> {
+        if self.running {
+            return Err(SdrError::AlreadyRunning);
+        }
+        self.running = true;
+        self.cursor = None;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> SdrResult<()> {
+        self.running = false;
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn read(&mut self, _buffer: &mut [IQSample], _timeout: Duration) -> SdrResult<(usize, Timestamp)> {
+        Err(SdrError::Unsupported("Cannot read from a TX stream".to_string()))
+    }
+
+    fn write(&mut self, buffer: &[IQSample], timestamp: Option<Timestamp>, timeout: Duration) -> SdrResult<usize> {
+        self.write_with_metadata(buffer, &TxMetadata::streaming(timestamp), timeout)
+    }
+
+    fn write_with_metadata(&mut self, buffer: &[IQSample], metadata: &TxMetadata, timeout: Duration) -> SdrResult<usize> {
+        let combined = self.combine(&[buffer])?;
+        self.queue(&combined, metadata, timeout)
+    }
+
+    fn num_channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    fn write_multi(&mut self, buffers: &[&[IQSample]], timestamp: Option<Timestamp>, timeout: Duration) -> SdrResult<usize> {
+        let combined = self.combine(buffers)?;
+        self.queue(&combined, &TxMetadata::streaming(timestamp), timeout)
+    }
+
+    fn status(&self) -> StreamStatus {
+        self.status.clone()
+    }
+
+    fn timestamp(&self) -> Timestamp {
+        let core = lock(&self.core);
+        core.timestamp_at(self.cursor.unwrap_or(core.now).max(core.now))
+    }
+
+    fn available(&self) -> usize {
+        0
+    }
+
+    fn free_space(&self) -> usize {
+        let core = lock(&self.core);
+        let queued = self.cursor.unwrap_or(0).saturating_sub(core.now);
+        self.capacity.saturating_sub(queued) as usize
+    }
+}
+
+/// Tuner of the simulated device
+///
+/// Frequency, gains and bandwidth live in the core, where timed commands
+/// change them; `config` mirrors them as of the last [`sync`](Self::sync).
+struct SimTuner {
+    core: Arc<Mutex<SimCore>>,
+    config: SdrConfig,
+}
+
+impl SimTuner {
+    /// Pick up settings changed by timed commands
+    fn sync(&mut self) {
+        let core = lock(&self.core);
+        self.config.frequency = core.frequency;
+        self.config.rx_gain = core.rx_gain.current();
+        self.config.tx_gain = core.tx_gain.current();
+        self.config.bandwidth = core.bandwidth;
+    }
+}
+
+impl TunerControl for SimTuner {
+    fn set_frequency(&mut self, freq_hz: u64) -> SdrResult<u64> {
+        self.config.frequency = freq_hz as f64;
+        let mut core = lock(&self.core);
+        core.sync_wall();
+        let now = core.now;
+        core.set_frequency(now, freq_hz as f64);
+        Ok(freq_hz)
+    }
+
+    fn frequency(&self) -> u64 {
+        lock(&self.core).frequency as u64
+    }
+
+    fn set_sample_rate(&mut self, rate: f64) -> SdrResult<f64> {
+        let (min, max) = self.sample_rate_range();
+        if !(min..=max).contains(&rate) {
+            return Err(SdrError::ConfigError(format!("Sample rate {} out of range", rate)));
+        }
+        self.config.sample_rate = rate;
+        lock(&self.core).set_sample_rate(rate);
+        Ok(rate)
+    }
+
+    fn sample_rate(&self) -> f64 {
+        self.config.sample_rate
+    }
+
+    fn set_bandwidth(&mut self, bw_hz: f64) -> SdrResult<f64> {
+        self.config.bandwidth = bw_hz;
+        lock(&self.core).bandwidth = bw_hz;
+        Ok(bw_hz)
+    }
+
+    fn bandwidth(&self) -> f64 {
+        lock(&self.core).bandwidth
+    }
+
+    fn set_rx_gain(&mut self, gain_db: f64) -> SdrResult<f64> {
+        let (min, max) = self.gain_range();
+        self.config.rx_gain = gain_db.clamp(min, max);
+        let mut core = lock(&self.core);
+        core.sync_wall();
+        let now = core.now;
+        core.rx_gain.set(now, self.config.rx_gain);
+        Ok(self.config.rx_gain)
+    }
+
+    fn rx_gain(&self) -> f64 {
+        lock(&self.core).rx_gain.current()
+    }
+
+    fn set_tx_gain(&mut self, gain_db: f64) -> SdrResult<f64> {
+        let (min, max) = self.gain_range();
+        self.config.tx_gain = gain_db.clamp(min, max);
+        let mut core = lock(&self.core);
+        core.sync_wall();
+        let now = core.now;
+        core.tx_gain.set(now, self.config.tx_gain);
+        Ok(self.config.tx_gain)
+    }
+
+    fn tx_gain(&self) -> f64 {
+        lock(&self.core).tx_gain.current()
+    }
+
+    fn set_antenna(&mut self, antenna: &str) -> SdrResult<()> {
+        if !self.available_antennas().iter().any(|a| a == antenna) {
+            return Err(SdrError::ConfigError(format!("Unknown antenna '{}'", antenna)));
+        }
+        self.config.antenna = antenna.to_string();
+        Ok(())
+    }
+
+    fn antenna(&self) -> &str {
+        &self.config.antenna
+    }
+
+    fn available_antennas(&self) -> Vec<String> {
+        vec!["TX/RX".to_string(), "RX2".to_string()]
+    }
+
+    fn frequency_range(&self) -> (u64, u64) {
+        (0, 10_000_000_000)
+    }
+
+    fn sample_rate_range(&self) -> (f64, f64) {
+        (1.0, 100e6)
+    }
+
+    fn gain_range(&self) -> (f64, f64) {
+        (0.0, 76.0)
+    }
+}
+
+/// Clock control of the simulated device
+struct SimClock {
+    core: Arc<Mutex<SimCore>>,
+}
+
+impl ClockControl for SimClock {
+    fn set_clock_source(&mut self, source: ClockSource) -> SdrResult<()> {
+        lock(&self.core).clock_source = source;
+        Ok(())
+    }
+
+    fn clock_source(&self) -> ClockSource {
+        lock(&self.core).clock_source
+    }
+
+    fn set_time_source(&mut self, source: TimeSource) -> SdrResult<()> {
+        lock(&self.core).time_source = source;
+        Ok(())
+    }
+
+    fn time_source(&self) -> TimeSource {
+        lock(&self.core).time_source
+    }
+
+    fn time(&self) -> Timestamp {
+        let mut core = lock(&self.core);
+        core.sync_wall();
+        let now = core.now;
+        core.timestamp_at(now)
+    }
+
+    fn set_time(&mut self, time: Timestamp) -> SdrResult<()> {
+        let mut core = lock(&self.core);
+        core.sync_wall();
+        core.time_offset = time.sample.samples() as i64 - core.now as i64;
+        core.pending_pps = None;
+        Ok(())
+    }
+
+    fn set_time_at_pps(&mut self, time: Timestamp) -> SdrResult<()> {
+        let mut core = lock(&self.core);
+        core.sync_wall();
+        let edge = core.next_pps_edge();
+        core.pending_pps = Some((edge, time.sample.samples() as i64 - edge as i64));
+        Ok(())
+    }
+
+    fn wait_for_pps(&mut self, timeout: Duration) -> SdrResult<()> {
+        let mut core = lock(&self.core);
+        core.sync_wall();
+        let edge = core.next_pps_edge();
+
+        if core.params.realtime {
+            let deadline = core.wall_deadline(edge);
+            drop(core);
+            let now = Instant::now();
+            if deadline > now + timeout {
+                std::thread::sleep(timeout);
+                return Err(SdrError::Timeout("PPS edge".to_string()));
+            }
+            if deadline > now {
+                std::thread::sleep(deadline - now);
+            }
+            lock(&self.core).sync_wall();
+        } else {
+            let wait = (edge - core.now) as f64 / core.sample_rate;
+            if wait > timeout.as_secs_f64() {
+                return Err(SdrError::Timeout("PPS edge".to_string()));
+            }
+            core.advance_to(edge);
+        }
+        Ok(())
+    }
+
+    fn is_locked(&self) -> bool {
+        true
+    }
+
+    fn hardware_clock(&self) -> Option<HardwareClock> {
+        let mut core = lock(&self.core);
+        core.sync_wall();
+        let now = core.now;
+        core.timestamp_at(now).hardware
+    }
+}
+
+/// Timed command queue of the simulated device
+struct SimTimed {
+    core: Arc<Mutex<SimCore>>,
+}
+
+impl TimedControl for SimTimed {
+    fn schedule(&mut self, time: Timestamp, command: TimedCommand) -> SdrResult<()> {
+        let mut core = lock(&self.core);
+        core.sync_wall();
+        let internal = core.internal(time.sample.samples());
+        let now = core.now;
+        if internal <= now as i64 {
+            if internal < now as i64 {
+                core.late_commands += 1;
+            }
+            core.execute(now, command);
+            return Ok(());
+        }
+        let t = internal as u64;
+        let pos = core.commands.partition_point(|(c, _)| *c <= t);
+        core.commands.insert(pos, (t, command));
+        Ok(())
+    }
+
+    fn pending(&self) -> Vec<(Timestamp, TimedCommand)> {
+        let core = lock(&self.core);
+        core.commands
+            .iter()
+            .map(|(t, command)| (core.timestamp_at(*t), command.clone()))
+            .collect()
+    }
+
+    fn cancel_all(&mut self) -> usize {
+        let mut core = lock(&self.core);
+        let count = core.commands.len();
+        core.commands.clear();
+        count
+    }
+
+    fn late_count(&self) -> u64 {
+        lock(&self.core).late_commands
+    }
+}
+
+/// Simulated SDR device
+pub struct SimDevice {
+    name: String,
+    tuner: SimTuner,
+    clock: SimClock,
+    timed: SimTimed,
+    core: Arc<Mutex<SimCore>>,
+}
+
+impl SimDevice {
+    /// Create a device from parameters
+    pub fn new(params: SimParams) -> SdrResult<Self> {
+        let config = SdrConfig {
+            frequency: params.frequency,
+            sample_rate: params.sample_rate,
+            bandwidth: params.sample_rate,
+            antenna: "TX/RX".to_string(),
+            ..Default::default()
+        };
+        let mut sim = SimCore::new(params)?;
+        sim.rx_gain = Schedule::new(config.rx_gain);
+        sim.tx_gain = Schedule::new(config.tx_gain);
+        sim.bandwidth = config.bandwidth;
+        let core = Arc::new(Mutex::new(sim));
+
+        Ok(Self {
+            name: "R4W HAL Simulator".to_string(),
+            tuner: SimTuner { core: core.clone(), config },
+            clock: SimClock { core: core.clone() },
+            timed: SimTimed { core: core.clone() },
+            core,
+        })
+    }
+
+    /// Parameters the device was created with
+    pub fn params(&self) -> SimParams {
+        lock(&self.core).params.clone()
+    }
+
+    /// Current position of the device timeline (samples)
+    pub fn sample_clock(&self) -> SampleClock {
+        let core = lock(&self.core);
+        SampleClock::at_sample(core.now, core.sample_rate)
+    }
+
+    /// Timed commands executed so far, with the device time they ran at
+    pub fn command_log(&self) -> Vec<(u64, TimedCommand)> {
+        lock(&self.core).command_log.clone()
+    }
+
+    /// Let `samples` of device time pass without any stream activity
+    ///
+    /// In virtual mode this is how a test models a slow consumer: RX
+    /// streams that are left behind overflow on their next read.
+    pub fn advance_time(&self, samples: u64) {
+        let mut core = lock(&self.core);
+        let target = core.now + samples;
+        core.advance_to(target);
+    }
+}
+
+impl SdrDeviceExt for SimDevice {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        let channels = lock(&self.core).array.len();
+        DeviceCapabilities {
+            can_tx: true,
+            can_rx: true,
+            full_duplex: true,
+            min_frequency: 0.0,
+            max_frequency: 10e9,
+            max_sample_rate: 100e6,
+            tx_channels: channels,
+            rx_channels: channels,
+        }
+    }
+
+    fn config(&self) -> &SdrConfig {
+        &self.tuner.config
+    }
+
+    fn configure(&mut self, config: &SdrConfig) -> SdrResult<()> {
+        self.tuner.set_sample_rate(config.sample_rate)?;
+        self.tuner.config = SdrConfig {
+            sample_rate: self.tuner.config.sample_rate,
+            ..config.clone()
+        };
+        Ok(())
+    }
+
+    fn create_rx_stream(&mut self, config: StreamConfig) -> SdrResult<Box<dyn StreamHandle>> {
+        let capacity = (config.buffer_size * config.num_buffers.max(1)) as u64;
+        let mut core = lock(&self.core);
+        core.check_channels(&config.channels, "RX")?;
+        core.history = core.history.max(capacity + core.params.delay_samples);
+
+        Ok(Box::new(SimRxStream {
+            core: self.core.clone(),
+            channels: config.channels,
+            running: false,
+            cursor: core.now,
+            capacity,
+            status: StreamStatus::default(),
+        }))
+    }
+
+    fn create_tx_stream(&mut self, config: StreamConfig) -> SdrResult<Box<dyn StreamHandle>> {
+        lock(&self.core).check_channels(&config.channels, "TX")?;
+        let capacity = (config.buffer_size * config.num_buffers.max(1)) as u64;
+
+        Ok(Box::new(SimTxStream {
+            core: self.core.clone(),
+            channels: config.channels,
+            running: false,
+            cursor: None,
+            capacity,
+            dropping: false,
+            status: StreamStatus::default(),
+        }))
+    }
+
+    fn tuner(&mut self) -> &mut dyn TunerControl {
+        self.tuner.sync();
+        &mut self.tuner
+    }
+
+    fn clock(&mut self) -> Option<&mut dyn ClockControl> {
+        Some(&mut self.clock)
+    }
+
+    fn timed(&mut self) -> Option<&mut dyn TimedControl> {
+        Some(&mut self.timed)
+    }
+
+    fn supports_pps(&self) -> bool {
+        true
+    }
+
+    fn supports_external_clock(&self) -> bool {
+        true
+    }
+}
+
+/// Simulator driver for the registry (`sim://`, alias `simulator://`)
+pub struct SimDriver;
+
+impl SimDriver {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SimDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeviceDriver for SimDriver {
+    fn name(&self) -> &str {
+        "sim"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["simulator"]
+    }
+
+    fn discover(&self) -> Vec<DeviceInfo> {
+        vec![DeviceInfo {
+            driver: "sim".to_string(),
+            serial: "SIM0".to_string(),
+            label: "R4W HAL Simulator".to_string(),
+            address: "sim://".to_string(),
+        }]
+    }
+
+    fn create(&self, info: &DeviceInfo) -> SdrResult<Box<dyn SdrDeviceExt>> {
+        let args = info.address.split_once("://").map_or(info.address.as_str(), |(_, a)| a);
+        self.create_from_string(args)
+    }
+
+    fn create_from_string(&self, args: &str) -> SdrResult<Box<dyn SdrDeviceExt>> {
+        let params = SimParams::parse(args)?;
+        Ok(Box::new(SimDevice::new(params)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(args: &str) -> SimDevice {
+        SimDevice::new(SimParams::parse(args).unwrap()).unwrap()
+    }
+
+    fn stream_config(buffer_size: usize, num_buffers: usize) -> StreamConfig {
+        StreamConfig {
+            buffer_size,
+            num_buffers,
+            ..Default::default()
+        }
+    }
+
+    fn zeros(n: usize) -> Vec<IQSample> {
+        vec![IQSample::new(0.0, 0.0); n]
+    }
+
+    #[test]
+    fn test_parse_params() {
+        let p = SimParams::parse("snr=10, cfo=500,model=rayleigh,seed=7,realtime=false").unwrap();
+        assert_eq!(p.snr_db, 10.0);
+        assert_eq!(p.cfo_hz, 500.0);
+        assert_eq!(p.model, ChannelModel::Rayleigh);
+        assert_eq!(p.seed, Some(7));
+        assert!(!p.realtime);
+
+        assert_eq!(SimParams::parse("").unwrap(), SimParams::default());
+        assert!(SimParams::parse("model=warp").is_err());
+        assert!(SimParams::parse("bogus=1").is_err());
+        assert!(SimParams::parse("snr").is_err());
+    }
+
+    #[test]
+    fn test_noise_floor_matches_snr() {
+        let mut dev = device("snr=10,seed=1");
+        let mut rx = dev.create_rx_stream(stream_config(8192, 4)).unwrap();
+        rx.start().unwrap();
+        let mut buf = zeros(8192);
+        let (n, _) = rx.read(&mut buf, Duration::from_millis(10)).unwrap();
+        assert_eq!(n, 8192);
+        let power: f64 = buf.iter().map(|s| s.norm_sqr()).sum::<f64>() / n as f64;
+        assert!((10.0 * power.log10() + 10.0).abs() < 0.3, "noise power {} dB", 10.0 * power.log10());
+    }
+
+    #[test]
+    fn test_loopback_with_cfo_and_timestamps() {
+        let mut dev = device("snr=60,cfo=1000,delay=5,seed=2");
+        let mut tx = dev.create_tx_stream(stream_config(1024, 4)).unwrap();
+        let mut rx = dev.create_rx_stream(stream_config(1024, 4)).unwrap();
+        tx.start().unwrap();
+        rx.start().unwrap();
+
+        // Timed burst at sample 100 on the device timeline
+        let ts = Timestamp::at_sample(100, 1e6);
+        let burst = vec![IQSample::new(1.0, 0.0); 200];
+        assert_eq!(tx.write(&burst, Some(ts), Duration::from_millis(10)).unwrap(), 200);
+
+        let mut buf = zeros(512);
+        let (n, ts) = rx.read(&mut buf, Duration::from_millis(10)).unwrap();
+        assert_eq!(n, 512);
+        assert_eq!(ts.sample.samples(), 0);
+        assert!(ts.hardware.is_some());
+
+        // Burst arrives at 100 + delay and carries the CFO rotation
+        assert!(buf[104].norm() < 0.05);
+        assert!((buf[105].norm() - 1.0).abs() < 0.05);
+        assert!((buf[304].norm() - 1.0).abs() < 0.05);
+        assert!(buf[305].norm() < 0.05);
+        let rot = buf[106] * buf[105].conj();
+        let expected = 2.0 * PI * 1000.0 / 1e6;
+        assert!((rot.arg() - expected).abs() < 1e-3);
+
+        // Timestamps continue across reads
+        let (_, ts2) = rx.read(&mut buf, Duration::from_millis(10)).unwrap();
+        assert_eq!(ts2.sample.samples(), 512);
+    }
+
+    #[test]
+    fn test_rx_overflow() {
+        let mut dev = device("seed=3");
+        let mut rx = dev.create_rx_stream(stream_config(1000, 2)).unwrap();
+        rx.start().unwrap();
+        let mut buf = zeros(500);
+        rx.read(&mut buf, Duration::from_millis(10)).unwrap();
+        assert_eq!(rx.status().overflow_count, 0);
+
+        // Consumer stalls for longer than the 2000-sample buffer
+        dev.advance_time(5000);
+        let (n, ts) = rx.read(&mut buf, Duration::from_millis(10)).unwrap();
+        assert_eq!(n, 500);
+        assert_eq!(rx.status().overflow_count, 1);
+        // Oldest retained sample: now (5500) - capacity (2000)
+        assert_eq!(ts.sample.samples(), 3500);
+
+        // Still 1500 behind; another 500 stays within capacity
+        dev.advance_time(500);
+        rx.read(&mut buf, Duration::from_millis(10)).unwrap();
+        assert_eq!(rx.status().overflow_count, 1);
+    }
+
+    #[test]
+    fn test_tx_underflow_and_late() {
+        let mut dev = device("seed=4");
+        let mut tx = dev.create_tx_stream(stream_config(1000, 1)).unwrap();
+        let mut rx = dev.create_rx_stream(stream_config(4096, 4)).unwrap();
+        tx.start().unwrap();
+        rx.start().unwrap();
+        let chunk = vec![IQSample::new(0.5, 0.0); 500];
+        let timeout = Duration::from_millis(10);
+
+        // Keeping the queue fed: no underflow
+        for _ in 0..4 {
+            tx.write(&chunk, None, timeout).unwrap();
+        }
+        assert_eq!(tx.status().underflow_count, 0);
+        // Full TX buffer drives the timeline forward
+        assert_eq!(dev.sample_clock().samples(), 1000);
+
+        // Receiver reads past the end of the queued samples
+        let mut buf = zeros(3000);
+        rx.read(&mut buf, timeout).unwrap();
+        tx.write(&chunk, None, timeout).unwrap();
+        assert_eq!(tx.status().underflow_count, 1);
+
+        // A timed packet in the past is dropped
+        let late = Timestamp::at_sample(10, 1e6);
+        tx.write(&chunk, Some(late), timeout).unwrap();
+        assert_eq!(tx.status().late_count, 1);
+    }
+
+    #[test]
+    fn test_clock_control_and_pps() {
+        let mut dev = device("rate=1000,seed=5");
+        let clock = dev.clock().unwrap();
+        clock.set_time_source(TimeSource::Pps).unwrap();
+        assert!(clock.hardware_clock().unwrap().is_pps_locked());
+
+        clock.wait_for_pps(Duration::from_secs(2)).unwrap();
+        assert_eq!(clock.time().sample.samples(), 1000);
+
+        // New time applies at the next edge
+        clock.set_time_at_pps(Timestamp::at_sample(50_000, 1000.0)).unwrap();
+        assert_eq!(clock.time().sample.samples(), 1000);
+        clock.wait_for_pps(Duration::from_secs(2)).unwrap();
+        assert_eq!(clock.time().sample.samples(), 50_000);
+
+        clock.set_time(Timestamp::at_sample(7, 1000.0)).unwrap();
+        assert_eq!(clock.time().sample.samples(), 7);
+
+        // Waiting longer than the timeout fails
+        assert!(clock.wait_for_pps(Duration::from_millis(10)).is_err());
+    }
+
+    #[test]
+    fn test_registry_uris() {
+        let registry = super::super::create_default_registry();
+        assert!(registry.list().contains(&"sim"));
+        let mut dev = registry.create("sim://snr=10,cfo=500,model=rayleigh").unwrap();
+        assert_eq!(dev.tuner().set_frequency(433_920_000).unwrap(), 433_920_000);
+        assert!(dev.clock().is_some());
+        assert!(registry.create("simulator://").is_ok());
+        assert!(registry.create("sim://model=nope").is_err());
+    }
+
+    #[test]
+    fn test_seeded_runs_are_reproducible() {
+        let capture = || {
+            let mut dev = device("snr=5,model=rician,seed=9");
+            let mut tx = dev.create_tx_stream(stream_config(1024, 4)).unwrap();
+            let mut rx = dev.create_rx_stream(stream_config(1024, 4)).unwrap();
+            tx.start().unwrap();
+            rx.start().unwrap();
+            tx.write(&[IQSample::new(1.0, 0.0); 256], None, Duration::ZERO).unwrap();
+            let mut buf = zeros(256);
+            rx.read(&mut buf, Duration::ZERO).unwrap();
+            buf
+        };
+        assert_eq!(capture(), capture());
+    }
+
+    #[test]
+    fn test_multichannel_steering() {
+        let mut dev = device("channels=4,aoa=30,freq=1e9,snr=80,seed=10");
+        assert_eq!(dev.capabilities().rx_channels, 4);
+        let mut tx = dev.create_tx_stream(stream_config(1024, 4)).unwrap();
+        let rx_config = StreamConfig {
+            channels: vec![0, 1, 2, 3],
+            ..stream_config(1024, 4)
+        };
+        let mut rx = dev.create_rx_stream(rx_config).unwrap();
+        assert_eq!(rx.num_channels(), 4);
+        tx.start().unwrap();
+        rx.start().unwrap();
+        tx.write(&[IQSample::new(1.0, 0.0); 256], None, Duration::ZERO).unwrap();
+
+        // A single buffer is not enough for a four-channel stream
+        let mut single = zeros(16);
+        assert!(rx.read(&mut single, Duration::ZERO).is_err());
+
+        let mut bufs = vec![zeros(256); 4];
+        let mut refs: Vec<&mut [IQSample]> = bufs.iter_mut().map(|b| b.as_mut_slice()).collect();
+        let (n, ts) = rx.read_multi(&mut refs, Duration::ZERO).unwrap();
+        assert_eq!((n, ts.sample.samples()), (256, 0));
+
+        // Relative phases follow the array's steering vector
+        let a = dev.params().array_geometry().steering_vector(30.0, 0.0, 1e9);
+        for k in 1..4 {
+            let measured = bufs[k][100] * bufs[0][100].conj();
+            let expected = a[k] * a[0].conj();
+            assert!((measured - expected).norm() < 1e-3, "element {}", k);
+        }
+
+        // Channel lists are validated
+        let bad = StreamConfig {
+            channels: vec![0, 4],
+            ..Default::default()
+        };
+        assert!(dev.create_rx_stream(bad).is_err());
+        let dup = StreamConfig {
+            channels: vec![1, 1],
+            ..Default::default()
+        };
+        assert!(dev.create_rx_stream(dup).is_err());
+    }
+
+    #[test]
+    fn test_multichannel_tx_beamforming_and_skew() {
+        // Conjugate steering towards `aod` adds coherently at the receiver
+        let mut dev = device("channels=4,aod=20,snr=80,seed=11");
+        let tx_config = StreamConfig {
+            channels: vec![0, 1, 2, 3],
+            ..stream_config(1024, 4)
+        };
+        let mut tx = dev.create_tx_stream(tx_config).unwrap();
+        let mut rx = dev.create_rx_stream(stream_config(1024, 4)).unwrap();
+        tx.start().unwrap();
+        rx.start().unwrap();
+        let a = dev.params().array_geometry().steering_vector(20.0, 0.0, 915e6);
+        let steered: Vec<Vec<IQSample>> = a.iter().map(|w| vec![w.conj(); 100]).collect();
+        let refs: Vec<&[IQSample]> = steered.iter().map(|b| b.as_slice()).collect();
+        assert_eq!(tx.write_multi(&refs, None, Duration::ZERO).unwrap(), 100);
+        let mut buf = zeros(100);
+        rx.read(&mut buf, Duration::ZERO).unwrap();
+        assert!((buf[50].norm() - 4.0).abs() < 1e-3);
+
+        // Two samples of cable skew per element delay an impulse accordingly
+        let mut dev = device("channels=3,skew=2000,snr=80,seed=12");
+        let mut tx = dev.create_tx_stream(stream_config(1024, 4)).unwrap();
+        let rx_config = StreamConfig {
+            channels: vec![0, 1, 2],
+            ..stream_config(1024, 4)
+        };
+        let mut rx = dev.create_rx_stream(rx_config).unwrap();
+        tx.start().unwrap();
+        rx.start().unwrap();
+        tx.write(&[IQSample::new(1.0, 0.0)], Some(Timestamp::at_sample(98, 1e6)), Duration::ZERO).unwrap();
+
+        // Read in two blocks so the delayed impulse crosses a read boundary
+        let mut bufs = vec![zeros(100); 3];
+        let mut peaks = Vec::new();
+        for block in 0..2 {
+            let mut refs: Vec<&mut [IQSample]> = bufs.iter_mut().map(|b| b.as_mut_slice()).collect();
+            rx.read_multi(&mut refs, Duration::ZERO).unwrap();
+            for (k, b) in bufs.iter().enumerate() {
+                if let Some(i) = b.iter().position(|s| s.norm() > 0.5) {
+                    peaks.push((k, block * 100 + i));
+                }
+            }
+        }
+        peaks.sort();
+        assert_eq!(peaks, vec![(0, 98), (1, 100), (2, 102)]);
+    }
+
+    #[test]
+    fn test_timed_frequency_hop_lands_on_sample() {
+        // Constant emitter at 915 MHz from a raw cf32 source file
+        let path = std::env::temp_dir().join(format!("r4w_sim_hop_{}.cf32", std::process::id()));
+        let bytes: Vec<u8> = (0..4000).flat_map(|_| [1.0f32.to_le_bytes(), 0.0f32.to_le_bytes()].concat()).collect();
+        std::fs::write(&path, bytes).unwrap();
+        let mut dev = device(&format!("source={},snr=80,seed=13", path.display()));
+        let mut rx = dev.create_rx_stream(stream_config(1024, 4)).unwrap();
+        rx.start().unwrap();
+
+        // 10 kHz up at sample 300, then out of band at sample 700
+        let timed = dev.timed().unwrap();
+        timed.at(Timestamp::at_sample(700, 1e6)).set_frequency(930_000_000).unwrap();
+        timed.at(Timestamp::at_sample(300, 1e6)).set_frequency(915_010_000).unwrap();
+        assert_eq!(timed.pending().len(), 2);
+        assert_eq!(timed.pending()[0].0.sample.samples(), 300);
+
+        let mut buf = zeros(1000);
+        rx.read(&mut buf, Duration::ZERO).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // Before the hop the emitter sits at DC
+        assert!((buf[299] - IQSample::new(1.0, 0.0)).norm() < 1e-3);
+        let rot = buf[301] * buf[300].conj();
+        assert!(buf[298].arg().abs() < 1e-3 && buf[299].arg().abs() < 1e-3);
+        assert!((rot.arg() + 2.0 * PI * 10_000.0 / 1e6).abs() < 1e-3);
+        assert!((buf[699].norm() - 1.0).abs() < 1e-3);
+        assert!(buf[700].norm() < 0.01);
+
+        assert!(dev.timed().unwrap().pending().is_empty());
+        let log = dev.command_log();
+        assert_eq!(log[0], (300, TimedCommand::SetFrequency(915_010_000)));
+        assert_eq!(log[1].0, 700);
+        assert_eq!(dev.tuner().frequency(), 930_000_000);
+    }
+
+    #[test]
+    fn test_timed_gain_steps_land_on_sample() {
+        let mut dev = device("snr=80,seed=17");
+        let mut tx = dev.create_tx_stream(stream_config(4096, 1)).unwrap();
+        let mut rx = dev.create_rx_stream(stream_config(4096, 4)).unwrap();
+        tx.start().unwrap();
+        rx.start().unwrap();
+        let burst = vec![IQSample::new(0.5, 0.0); 1000];
+        tx.write_with_metadata(&burst, &TxMetadata::burst(Timestamp::at_sample(0, 1e6)), Duration::ZERO)
+            .unwrap();
+
+        // TX +6 dB at sample 400 (after the burst was queued), RX -6 dB at sample 700
+        let timed = dev.timed().unwrap();
+        timed.at(Timestamp::at_sample(400, 1e6)).set_tx_gain(36.0).unwrap();
+        timed.at(Timestamp::at_sample(700, 1e6)).set_rx_gain(24.0).unwrap();
+
+        let mut buf = zeros(1000);
+        rx.read(&mut buf, Duration::ZERO).unwrap();
+        let step = 10.0_f64.powf(6.0 / 20.0);
+        assert!((buf[399].norm() - 0.5).abs() < 1e-3);
+        assert!((buf[400].norm() - 0.5 * step).abs() < 1e-3);
+        assert!((buf[699].norm() - 0.5 * step).abs() < 1e-3);
+        assert!((buf[700].norm() - 0.5).abs() < 1e-3);
+        assert_eq!((dev.tuner().tx_gain(), dev.tuner().rx_gain()), (36.0, 24.0));
+
+        // Immediate changes apply from the current sample on
+        dev.tuner().set_rx_gain(30.0).unwrap();
+        tx.write_with_metadata(&burst[..100], &TxMetadata::burst(Timestamp::at_sample(1000, 1e6)), Duration::ZERO)
+            .unwrap();
+        rx.read(&mut buf[..100], Duration::ZERO).unwrap();
+        assert!((buf[0].norm() - 0.5 * step).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_late_commands_and_cancel() {
+        let mut dev = device("seed=14");
+        dev.advance_time(1000);
+        let timed = dev.timed().unwrap();
+        timed.at(Timestamp::at_sample(10, 1e6)).set_rx_gain(30.0).unwrap().set_tx_gain(12.0).unwrap();
+        assert_eq!(timed.late_count(), 2);
+        timed.at(Timestamp::at_sample(5000, 1e6)).set_bandwidth(2e5).unwrap();
+        assert_eq!(timed.cancel_all(), 1);
+        assert_eq!(dev.tuner().rx_gain(), 30.0);
+        assert_eq!(dev.tuner().tx_gain(), 12.0);
+        assert_eq!(dev.config().rx_gain, 30.0);
+        dev.advance_time(5000);
+        assert_ne!(dev.tuner().bandwidth(), 2e5);
+        // Late commands run when they arrive
+        assert!(dev.command_log().iter().all(|(t, _)| *t == 1000));
+    }
+
+    #[test]
+    fn test_tx_bursts() {
+        let mut dev = device("snr=60,seed=15");
+        let mut tx = dev.create_tx_stream(stream_config(4096, 1)).unwrap();
+        let mut rx = dev.create_rx_stream(stream_config(4096, 4)).unwrap();
+        tx.start().unwrap();
+        rx.start().unwrap();
+        let chunk = vec![IQSample::new(0.5, 0.0); 100];
+        let timeout = Duration::ZERO;
+
+        // Burst in three packets; silence after end-of-burst is no underflow
+        let start = TxMetadata::start_of_burst(Some(Timestamp::at_sample(200, 1e6)));
+        tx.write_with_metadata(&chunk, &start, timeout).unwrap();
+        tx.write_with_metadata(&chunk, &TxMetadata::continuation(), timeout).unwrap();
+        tx.write_with_metadata(&chunk, &TxMetadata::end_of_burst(), timeout).unwrap();
+        let mut buf = zeros(1000);
+        rx.read(&mut buf, timeout).unwrap();
+        assert!(buf[199].norm() < 0.05 && buf[200].norm() > 0.4 && buf[499].norm() > 0.4);
+        assert!(buf[500].norm() < 0.05);
+        tx.write_with_metadata(&chunk, &TxMetadata::burst(Timestamp::at_sample(1200, 1e6)), timeout).unwrap();
+        let status = tx.status();
+        assert_eq!((status.underflow_count, status.bursts_completed), (0, 2));
+
+        // Running dry inside a burst underflows
+        tx.write_with_metadata(&chunk, &TxMetadata::start_of_burst(Some(Timestamp::at_sample(1500, 1e6))), timeout)
+            .unwrap();
+        rx.read(&mut buf, timeout).unwrap();
+        tx.write_with_metadata(&chunk, &TxMetadata::end_of_burst(), timeout).unwrap();
+        assert_eq!(tx.status().underflow_count, 1);
+    }
+
+    #[test]
+    fn test_late_burst_is_dropped() {
+        let mut dev = device("snr=60,seed=16");
+        let mut tx = dev.create_tx_stream(stream_config(4096, 1)).unwrap();
+        let mut rx = dev.create_rx_stream(stream_config(4096, 4)).unwrap();
+        tx.start().unwrap();
+        rx.start().unwrap();
+        dev.advance_time(1000);
+        let chunk = vec![IQSample::new(1.0, 0.0); 100];
+        let late = Timestamp::at_sample(500, 1e6);
+        tx.write_with_metadata(&chunk, &TxMetadata::start_of_burst(Some(late)), Duration::ZERO).unwrap();
+        tx.write_with_metadata(&chunk, &TxMetadata::continuation(), Duration::ZERO).unwrap();
+        tx.write_with_metadata(&chunk, &TxMetadata::end_of_burst(), Duration::ZERO).unwrap();
+        let status = tx.status();
+        assert_eq!(status.late_count, 1);
+        assert_eq!(status.last_late.unwrap().sample.samples(), 500);
+        assert_eq!(status.underflow_count, 0);
+
+        // Nothing of the burst reached the air
+        let mut buf = zeros(1000);
+        rx.read(&mut buf, Duration::ZERO).unwrap();
+        assert!(buf.iter().all(|s| s.norm() < 0.05));
+
+        // The next burst goes out normally
+        let next = TxMetadata::burst(Timestamp::at_sample(1100, 1e6));
+        tx.write_with_metadata(&chunk, &next, Duration::ZERO).unwrap();
+        let (_, ts) = rx.read(&mut buf[..200], Duration::ZERO).unwrap();
+        assert_eq!(ts.sample.samples(), 1000);
+        assert!(buf[100].norm() > 0.9);
+    }
+
+    #[test]
+    fn test_timed_rx_start() {
+        let mut dev = device("seed=17");
+        let mut rx = dev.create_rx_stream(stream_config(1024, 4)).unwrap();
+        rx.start_at(Timestamp::at_sample(5000, 1e6)).unwrap();
+        let mut buf = zeros(100);
+        let (_, ts) = rx.read(&mut buf, Duration::ZERO).unwrap();
+        assert_eq!(ts.sample.samples(), 5000);
+        assert_eq!(rx.status().late_count, 0);
+
+        // Starting in the past begins now and reports the late start
+        let mut late = dev.create_rx_stream(stream_config(1024, 4)).unwrap();
+        late.start_at(Timestamp::at_sample(10, 1e6)).unwrap();
+        assert_eq!(late.status().late_count, 1);
+        let (_, ts) = late.read(&mut buf, Duration::ZERO).unwrap();
+        assert_eq!(ts.sample.samples(), 5100);
+        assert!(matches!(late.start_at(Timestamp::at_sample(0, 1e6)), Err(SdrError::AlreadyRunning)));
+    }
+}
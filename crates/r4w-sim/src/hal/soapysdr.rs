@@ -473,6 +473,10 @@ impl SdrDeviceExt for SoapySdrDevice {
     fn supports_external_clock(&self) -> bool {
         true // Most SoapySDR devices support external clock
     }
+
+    fn has_dc_offset(&self) -> bool {
+        true // Most SoapySDR hardware is zero-IF
+    }
 }
 
 #[cfg(feature = "soapysdr")]
@@ -45,7 +45,7 @@
 //! trace:FR-0090 | ai:claude
 
 use super::{
-    ClockControl, ClockSource, DeviceDriver, SdrDeviceExt, StreamConfig,
+    ClockControl, ClockSource, DeviceDriver, SampleFormat, SdrDeviceExt, StreamConfig,
     StreamDirection, StreamHandle, StreamStatus, TunerControl,
 };
 use crate::device::{DeviceCapabilities, DeviceInfo, SdrConfig, SdrError, SdrResult};
@@ -482,6 +482,14 @@ impl SdrDeviceExt for UhdDevice {
     fn supports_external_clock(&self) -> bool {
         true
     }
+
+    fn native_format(&self) -> SampleFormat {
+        SampleFormat::ComplexInt16 // sc16 over the wire
+    }
+
+    fn has_dc_offset(&self) -> bool {
+        true
+    }
 }
 
 // =============================================================================
@@ -882,6 +890,7 @@ impl StreamHandle for UhdStream {
             late_count: 0,
             samples_processed: self.samples_processed.load(Ordering::SeqCst),
             buffer_level: 0,
+            ..Default::default()
         }
     }
 
@@ -0,0 +1,1216 @@
+//! VITA 49 (VRT) network device for the HAL (`vrt://` URIs)
+//!
+//! Streams I/Q over UDP as VITA 49 signal data packets, with context
+//! packets carrying frequency, bandwidth, gain and sample rate. This is
+//! the transport spoken by most commercial digitizers, so an r4w receiver
+//! can take samples from one directly, and an r4w transmitter can feed any
+//! VRT consumer. Packet encoding lives in [`r4w_core::vita49`].
+//!
+//! ## Model
+//!
+//! ```text
+//!   TX stream ──► VrtSender ──► UDP ──► VrtReceiver ──► RX stream
+//!       │        (data + context)          │  (context updates the tuner)
+//!   tuner settings                    packet-count / sample-count gaps
+//! ```
+//!
+//! - **Timing**: by default data packets carry a free-running sample
+//!   count, so RX timestamps reproduce the transmitter's sample timeline
+//!   exactly. With `timestamps=utc` they carry UTC seconds plus
+//!   picoseconds, which appear as [`Timestamp::synced`] on receive.
+//! - **Loss**: gaps in the modulo-16 packet counter, sample-count jumps and
+//!   trailers flagging sample loss increment `overflow_count`. A read never
+//!   spans a timing discontinuity, so the returned timestamp is always
+//!   valid for the whole buffer.
+//! - **Context**: received context packets update the device's tuner
+//!   settings and, if present, the payload format. VRT has no command
+//!   channel, so tuning an RX device only changes the local view; tuning a
+//!   TX device sends a fresh context packet with the next data packet.
+//!
+//! ## URI Parameters
+//!
+//! `vrt://[listen-addr],key=value,...`
+//!
+//! | Key          | Default      | Meaning                                              |
+//! |--------------|--------------|------------------------------------------------------|
+//! | `listen`     | 0.0.0.0:4991 | UDP address to receive on (also the bare first item) |
+//! | `dest`       | —            | UDP destination for transmitted packets              |
+//! | `format`     | ci16         | Payload format: ci8, ci16, cf32                      |
+//! | `stream`     | 1 / any      | Stream ID to send (and the only one accepted on RX)  |
+//! | `spp`        | 360          | Samples per data packet                              |
+//! | `context`    | 100          | Data packets between periodic context packets        |
+//! | `timestamps` | samples      | `samples` (free-running count) or `utc`              |
+//! | `rate`       | 1e6          | Initial sample rate (Hz)                             |
+//! | `freq`       | 915e6        | Initial center frequency (Hz)                        |
+//!
+//! A device with a `dest` but no `listen` binds an ephemeral port, so a
+//! transmit-only device never occupies the VRT port.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use r4w_sim::hal::{create_default_registry, SdrDeviceExt, StreamConfig, VrtDevice, VrtParams};
+//! use r4w_core::timing::Timestamp;
+//! use r4w_core::types::IQSample;
+//! use std::time::Duration;
+//!
+//! let mut rx_device = VrtDevice::new(VrtParams::parse("127.0.0.1:0").unwrap()).unwrap();
+//! let addr = rx_device.local_addr().unwrap();
+//!
+//! let registry = create_default_registry();
+//! let mut tx_device = registry.create(&format!("vrt://dest={},stream=7", addr)).unwrap();
+//!
+//! let mut rx = rx_device.create_rx_stream(StreamConfig::default()).unwrap();
+//! rx.start().unwrap();
+//! let mut tx = tx_device.create_tx_stream(StreamConfig::default()).unwrap();
+//! tx.start().unwrap();
+//! let samples = vec![IQSample::new(0.5, 0.0); 1000];
+//! tx.write(&samples, Some(Timestamp::at_sample(5000, 1e6)), Duration::ZERO).unwrap();
+//!
+//! let mut buffer = vec![IQSample::new(0.0, 0.0); 1000];
+//! let (n, ts) = rx.read(&mut buffer, Duration::from_secs(1)).unwrap();
+//! assert!(n > 0);
+//! assert_eq!(ts.sample.samples(), 5000);
+//! ```
+
+use super::sim::{parse_bool, parse_num};
+use super::{
+    ClockControl, DeviceDriver, SampleFormat, SdrDeviceExt, SdrResult, StreamConfig,
+    StreamDirection, StreamHandle, StreamStatus, TunerControl,
+};
+use crate::device::{DeviceCapabilities, DeviceInfo, SdrConfig, SdrError};
+use r4w_core::timing::{SyncedTime, TimeSource, Timestamp, WallClock};
+use r4w_core::types::IQSample;
+use r4w_core::vita49::{ContextPacket, DataPacket, PayloadFormat, Trailer, VrtPacket, VrtTimestamp};
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, ErrorKind};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// IANA-registered VRT port.
+pub const VRT_DEFAULT_PORT: u16 = 4991;
+
+/// Largest datagram the receiver accepts.
+const MAX_DATAGRAM: usize = 65536;
+
+fn io_error(context: &str, e: io::Error) -> SdrError {
+    SdrError::HardwareError(format!("{}: {}", context, e))
+}
+
+/// How transmitted data packets are timestamped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VrtTimestampMode {
+    /// Free-running sample count (TSI none, TSF sample count)
+    #[default]
+    SampleCount,
+    /// UTC seconds plus picoseconds (TSI UTC, TSF real time)
+    Utc,
+}
+
+/// Device parameters parsed from a `vrt://` URI
+#[derive(Debug, Clone, PartialEq)]
+pub struct VrtParams {
+    /// UDP address to receive on
+    pub listen: Option<String>,
+    /// UDP destination for transmitted packets
+    pub dest: Option<String>,
+    /// Payload format (until a context packet announces another)
+    pub format: PayloadFormat,
+    /// Stream ID to send and to accept (None = send 1, accept any)
+    pub stream_id: Option<u32>,
+    /// Samples per data packet
+    pub samples_per_packet: usize,
+    /// Data packets between periodic context packets
+    pub context_interval: usize,
+    /// Timestamp type of transmitted packets
+    pub timestamps: VrtTimestampMode,
+    /// Initial sample rate (Hz)
+    pub sample_rate: f64,
+    /// Initial center frequency (Hz)
+    pub frequency: f64,
+}
+
+impl Default for VrtParams {
+    fn default() -> Self {
+        Self {
+            listen: None,
+            dest: None,
+            format: PayloadFormat::Ci16,
+            stream_id: None,
+            samples_per_packet: 360,
+            context_interval: 100,
+            timestamps: VrtTimestampMode::SampleCount,
+            sample_rate: 1e6,
+            frequency: 915e6,
+        }
+    }
+}
+
+/// Accept `host:port`, `:port` or a bare port.
+fn normalize_addr(addr: &str) -> String {
+    if let Some(port) = addr.strip_prefix(':') {
+        format!("0.0.0.0:{}", port)
+    } else if addr.parse::<u16>().is_ok() {
+        format!("0.0.0.0:{}", addr)
+    } else if !addr.contains(':') {
+        format!("{}:{}", addr, VRT_DEFAULT_PORT)
+    } else {
+        addr.to_string()
+    }
+}
+
+impl VrtParams {
+    /// Parse the argument part of a `vrt://` URI
+    pub fn parse(args: &str) -> SdrResult<Self> {
+        let mut params = Self::default();
+
+        for part in args.trim_end_matches('/').split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let Some((key, value)) = part.split_once('=') else {
+                params.listen = Some(normalize_addr(part));
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+
+            match key {
+                "listen" => params.listen = Some(normalize_addr(value)),
+                "dest" => params.dest = Some(normalize_addr(value)),
+                "format" => {
+                    params.format = PayloadFormat::parse(value).ok_or_else(|| {
+                        SdrError::ConfigError(format!("Unknown VRT payload format '{}'", value))
+                    })?
+                }
+                "stream" => {
+                    let id = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => value.parse().ok(),
+                    };
+                    params.stream_id = Some(id.ok_or_else(|| {
+                        SdrError::ConfigError(format!("Invalid value '{}' for 'stream'", value))
+                    })?);
+                }
+                "spp" => params.samples_per_packet = parse_num(key, value)?,
+                "context" => params.context_interval = parse_num(key, value)?,
+                "timestamps" => {
+                    params.timestamps = match value.to_lowercase().as_str() {
+                        "samples" | "sample" | "count" => VrtTimestampMode::SampleCount,
+                        "utc" => VrtTimestampMode::Utc,
+                        other => {
+                            return Err(SdrError::ConfigError(format!(
+                                "Unknown timestamp mode '{}'", other
+                            )))
+                        }
+                    }
+                }
+                "utc" => {
+                    if parse_bool(key, value)? {
+                        params.timestamps = VrtTimestampMode::Utc;
+                    }
+                }
+                "rate" => params.sample_rate = parse_num(key, value)?,
+                "freq" => params.frequency = parse_num(key, value)?,
+                other => {
+                    return Err(SdrError::ConfigError(format!(
+                        "Unknown VRT parameter '{}'", other
+                    )))
+                }
+            }
+        }
+
+        if params.sample_rate <= 0.0 {
+            return Err(SdrError::ConfigError("Sample rate must be positive".to_string()));
+        }
+        if params.samples_per_packet == 0 {
+            return Err(SdrError::ConfigError("spp must be positive".to_string()));
+        }
+        let max_spp = (MAX_DATAGRAM - 64) / params.format.bytes_per_sample();
+        if params.samples_per_packet > max_spp {
+            return Err(SdrError::ConfigError(format!(
+                "spp {} exceeds the {} samples a {} datagram can hold",
+                params.samples_per_packet, max_spp, params.format.name()
+            )));
+        }
+
+        Ok(params)
+    }
+}
+
+/// Sends VRT data and context packets to one UDP destination
+pub struct VrtSender {
+    socket: UdpSocket,
+    dest: SocketAddr,
+    stream_id: u32,
+    format: PayloadFormat,
+    samples_per_packet: usize,
+    data_count: u8,
+    context_count: u8,
+    /// Data packets sent
+    pub packets_sent: u64,
+    /// Context packets sent
+    pub contexts_sent: u64,
+    /// Samples sent
+    pub samples_sent: u64,
+}
+
+impl VrtSender {
+    /// Sender from an ephemeral local port to `dest`.
+    pub fn new<A: ToSocketAddrs>(dest: A, stream_id: u32, format: PayloadFormat) -> io::Result<Self> {
+        let dest = dest
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "destination did not resolve"))?;
+        let bind = if dest.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        Self::with_socket(UdpSocket::bind(bind)?, dest, stream_id, format)
+    }
+
+    /// Sender on an existing socket.
+    pub fn with_socket(socket: UdpSocket, dest: SocketAddr, stream_id: u32, format: PayloadFormat) -> io::Result<Self> {
+        Ok(Self {
+            socket,
+            dest,
+            stream_id,
+            format,
+            samples_per_packet: 360,
+            data_count: 0,
+            context_count: 0,
+            packets_sent: 0,
+            contexts_sent: 0,
+            samples_sent: 0,
+        })
+    }
+
+    /// Samples per data packet.
+    pub fn with_samples_per_packet(mut self, spp: usize) -> Self {
+        self.samples_per_packet = spp.max(1);
+        self
+    }
+
+    /// Send `samples` as data packets; `timestamp` belongs to the first sample.
+    ///
+    /// Returns the number of packets sent.
+    pub fn send(&mut self, samples: &[IQSample], timestamp: VrtTimestamp, sample_rate: f64) -> io::Result<usize> {
+        let mut packets = 0;
+        for (i, chunk) in samples.chunks(self.samples_per_packet).enumerate() {
+            let mut packet = DataPacket::from_samples(chunk, self.format);
+            packet.stream_id = Some(self.stream_id);
+            packet.timestamp = timestamp.advance((i * self.samples_per_packet) as u64, sample_rate);
+            packet.packet_count = self.data_count;
+            packet.trailer = Some(Trailer {
+                valid_data: Some(true),
+                ..Default::default()
+            });
+            let bytes = packet
+                .encode()
+                .map_err(|e| io::Error::new(ErrorKind::InvalidInput, e.to_string()))?;
+            self.socket.send_to(&bytes, self.dest)?;
+
+            self.data_count = (self.data_count + 1) & 0xf;
+            self.packets_sent += 1;
+            self.samples_sent += chunk.len() as u64;
+            packets += 1;
+        }
+        Ok(packets)
+    }
+
+    /// Send a context packet (stream ID and packet count are filled in).
+    pub fn send_context(&mut self, context: &ContextPacket) -> io::Result<()> {
+        let mut context = context.clone();
+        context.stream_id = self.stream_id;
+        context.packet_count = self.context_count;
+        let bytes = context
+            .encode()
+            .map_err(|e| io::Error::new(ErrorKind::InvalidInput, e.to_string()))?;
+        self.socket.send_to(&bytes, self.dest)?;
+        self.context_count = (self.context_count + 1) & 0xf;
+        self.contexts_sent += 1;
+        Ok(())
+    }
+
+    /// Stream ID of sent packets.
+    pub fn stream_id(&self) -> u32 {
+        self.stream_id
+    }
+
+    /// Payload format of data packets.
+    pub fn format(&self) -> PayloadFormat {
+        self.format
+    }
+}
+
+/// Receives and decodes VRT packets from a UDP socket
+pub struct VrtReceiver {
+    socket: UdpSocket,
+    buffer: Vec<u8>,
+    stream_filter: Option<u32>,
+    /// Last packet count per (stream ID, is context)
+    counters: HashMap<(Option<u32>, bool), u8>,
+    /// Packets received and accepted
+    pub packets_received: u64,
+    /// Packets missing according to the packet counters
+    pub packets_lost: u64,
+    /// Datagrams that failed to decode
+    pub decode_errors: u64,
+}
+
+impl VrtReceiver {
+    /// Receiver bound to `addr`.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        Ok(Self::with_socket(UdpSocket::bind(addr)?))
+    }
+
+    /// Receiver on an existing socket.
+    pub fn with_socket(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            buffer: vec![0u8; MAX_DATAGRAM],
+            stream_filter: None,
+            counters: HashMap::new(),
+            packets_received: 0,
+            packets_lost: 0,
+            decode_errors: 0,
+        }
+    }
+
+    /// Only accept packets of this stream ID (None = all).
+    pub fn set_stream_filter(&mut self, stream_id: Option<u32>) {
+        self.stream_filter = stream_id;
+    }
+
+    /// Local address of the socket.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Wait up to `timeout` for the next accepted packet.
+    ///
+    /// Returns `Ok(None)` on timeout. Undecodable datagrams and packets of
+    /// other streams are skipped.
+    pub fn recv(&mut self, timeout: Duration) -> io::Result<Option<VrtPacket>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            self.socket.set_read_timeout(Some(remaining))?;
+            let len = match self.socket.recv(&mut self.buffer) {
+                Ok(len) => len,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => return Ok(None),
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            let packet = match VrtPacket::decode(&self.buffer[..len]) {
+                Ok(packet) => packet,
+                Err(e) => {
+                    tracing::debug!("Dropping undecodable VRT datagram: {}", e);
+                    self.decode_errors += 1;
+                    continue;
+                }
+            };
+            if let (Some(want), Some(id)) = (self.stream_filter, packet.stream_id()) {
+                if want != id {
+                    continue;
+                }
+            }
+
+            let (is_context, count) = match &packet {
+                VrtPacket::Data(p) => (false, p.packet_count),
+                VrtPacket::Context(p) => (true, p.packet_count),
+            };
+            if let Some(last) = self.counters.insert((packet.stream_id(), is_context), count) {
+                self.packets_lost += (count.wrapping_sub(last).wrapping_sub(1) & 0xf) as u64;
+            }
+            self.packets_received += 1;
+            return Ok(Some(packet));
+        }
+    }
+}
+
+/// State shared between the device, its tuner and its streams
+struct VrtShared {
+    /// Settings announced by transmitted context packets
+    local: SdrConfig,
+    /// Local settings changed since the last transmitted context
+    changed: bool,
+    /// Most recent received context and a counter of received contexts
+    remote: Option<ContextPacket>,
+    remote_seq: u64,
+    /// Payload format expected on receive
+    rx_format: PayloadFormat,
+}
+
+fn lock(shared: &Arc<Mutex<VrtShared>>) -> MutexGuard<'_, VrtShared> {
+    shared.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Received samples not yet handed to the reader
+struct Chunk {
+    /// Sample index of `samples[0]`
+    start: u64,
+    /// UTC time of `samples[0]` (ns), if the packet carried it
+    utc_ns: Option<u64>,
+    samples: Vec<IQSample>,
+}
+
+/// RX stream of a VRT device
+struct VrtRxStream {
+    receiver: VrtReceiver,
+    shared: Arc<Mutex<VrtShared>>,
+    running: bool,
+    pending: VecDeque<Chunk>,
+    /// Index of the next sample expected from the network
+    next: Option<u64>,
+    /// Index of the next sample handed to the reader
+    cursor: u64,
+    status: StreamStatus,
+}
+
+impl VrtRxStream {
+    fn sample_rate(&self) -> f64 {
+        lock(&self.shared).local.sample_rate
+    }
+
+    /// Turn a received packet into pending samples (or a tuner update).
+    fn accept(&mut self, packet: VrtPacket) {
+        match packet {
+            VrtPacket::Context(context) => {
+                let mut shared = lock(&self.shared);
+                if let Some(format) = context.data_format {
+                    shared.rx_format = format;
+                }
+                if let Some(rate) = context.sample_rate_hz {
+                    shared.local.sample_rate = rate;
+                }
+                shared.remote = Some(context);
+                shared.remote_seq += 1;
+            }
+            VrtPacket::Data(data) => {
+                let format = lock(&self.shared).rx_format;
+                let samples = data.samples(format);
+                if samples.is_empty() {
+                    return;
+                }
+
+                let expected = self.next.unwrap_or(0);
+                let start = data.timestamp.sample_index().unwrap_or(expected);
+                if self.next.is_some() && start != expected {
+                    self.status.overflow_count += 1;
+                }
+                if data.trailer.and_then(|t| t.sample_loss) == Some(true) {
+                    self.status.overflow_count += 1;
+                }
+                self.next = Some(start + samples.len() as u64);
+
+                let utc_ns = data.timestamp.utc_nanos();
+                self.pending.push_back(Chunk { start, utc_ns, samples });
+            }
+        }
+    }
+}
+
+impl StreamHandle for VrtRxStream {
+    fn direction(&self) -> StreamDirection {
+        StreamDirection::Rx
+    }
+
+    fn start(&mut self) -> SdrResult<()> {
+        if self.running {
+            return Err(SdrError::AlreadyRunning);
+        }
+        self.running = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> SdrResult<()> {
+        self.running = false;
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn read(&mut self, buffer: &mut [IQSample], timeout: Duration) -> SdrResult<(usize, Timestamp)> {
+        if !self.running {
+            return Err(SdrError::NotStarted);
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut filled = 0;
+        let mut first: Option<(u64, Option<u64>)> = None;
+
+        while filled < buffer.len() {
+            if let Some(chunk) = self.pending.front_mut() {
+                if first.is_some() && chunk.start != self.cursor {
+                    // Timing discontinuity: end this buffer here
+                    break;
+                }
+                if first.is_none() {
+                    first = Some((chunk.start, chunk.utc_ns));
+                    self.cursor = chunk.start;
+                }
+                let n = chunk.samples.len().min(buffer.len() - filled);
+                buffer[filled..filled + n].copy_from_slice(&chunk.samples[..n]);
+                filled += n;
+                self.cursor += n as u64;
+
+                if n == chunk.samples.len() {
+                    self.pending.pop_front();
+                } else {
+                    let rate = lock(&self.shared).local.sample_rate;
+                    chunk.samples.drain(..n);
+                    chunk.start += n as u64;
+                    chunk.utc_ns = chunk.utc_ns.map(|ns| ns + (n as f64 * 1e9 / rate).round() as u64);
+                }
+                continue;
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            match self.receiver.recv(remaining) {
+                Ok(Some(packet)) => self.accept(packet),
+                Ok(None) => break,
+                Err(e) => return Err(io_error("VRT receive", e)),
+            }
+        }
+
+        let Some((start, utc_ns)) = first else {
+            return Err(SdrError::Timeout("VRT samples".to_string()));
+        };
+
+        self.status.samples_processed += filled as u64;
+        self.status.buffer_level = self.pending.iter().map(|c| c.samples.len()).sum();
+        let mut timestamp = Timestamp::at_sample(start, self.sample_rate());
+        if let Some(ns) = utc_ns {
+            timestamp.wall = WallClock::from_nanos(ns);
+            timestamp = timestamp.with_synced(SyncedTime::from_utc_nanos(ns, TimeSource::External));
+        }
+        Ok((filled, timestamp))
+    }
+
+    fn write(&mut self, _buffer: &[IQSample], _timestamp: Option<Timestamp>, _timeout: Duration) -> SdrResult<usize> {
+        Err(SdrError::Unsupported("Cannot write to an RX stream".to_string()))
+    }
+
+    fn status(&self) -> StreamStatus {
+        let mut status = self.status.clone();
+        status.overflow_count = status.overflow_count.max(self.receiver.packets_lost);
+        status
+    }
+
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::at_sample(self.cursor, self.sample_rate())
+    }
+
+    fn available(&self) -> usize {
+        self.status.buffer_level
+    }
+
+    fn free_space(&self) -> usize {
+        0
+    }
+}
+
+/// TX stream of a VRT device
+struct VrtTxStream {
+    sender: VrtSender,
+    shared: Arc<Mutex<VrtShared>>,
+    timestamps: VrtTimestampMode,
+    context_interval: usize,
+    running: bool,
+    /// Index of the next sample
+    cursor: u64,
+    /// UTC time of sample 0 (ns), fixed by the first write
+    epoch_ns: Option<u64>,
+    /// Data packets since the last context packet
+    since_context: Option<usize>,
+    status: StreamStatus,
+}
+
+impl VrtTxStream {
+    fn context_packet(config: &SdrConfig, format: PayloadFormat, changed: bool) -> ContextPacket {
+        ContextPacket {
+            change_indicator: changed,
+            bandwidth_hz: Some(config.bandwidth),
+            rf_reference_hz: Some(config.frequency),
+            gain_db: Some(config.tx_gain),
+            sample_rate_hz: Some(config.sample_rate),
+            data_format: Some(format),
+            ..Default::default()
+        }
+    }
+}
+
+impl StreamHandle for VrtTxStream {
+    fn direction(&self) -> StreamDirection {
+        StreamDirection::Tx
+    }
+
+    fn start(&mut self) -> SdrResult<()> {
+        if self.running {
+            return Err(SdrError::AlreadyRunning);
+        }
+        self.running = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> SdrResult<()> {
+        self.running = false;
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn read(&mut self, _buffer: &mut [IQSample], _timeout: Duration) -> SdrResult<(usize, Timestamp)> {
+        Err(SdrError::Unsupported("Cannot read from a TX stream".to_string()))
+    }
+
+    fn write(&mut self, buffer: &[IQSample], timestamp: Option<Timestamp>, _timeout: Duration) -> SdrResult<usize> {
+        if !self.running {
+            return Err(SdrError::NotStarted);
+        }
+        if buffer.is_empty() {
+            return Ok(0);
+        }
+
+        let (config, changed) = {
+            let mut shared = lock(&self.shared);
+            let changed = std::mem::take(&mut shared.changed);
+            (shared.local.clone(), changed)
+        };
+        let rate = config.sample_rate;
+        let start = timestamp.as_ref().map_or(self.cursor, |ts| ts.sample.samples());
+
+        let vrt_timestamp = match self.timestamps {
+            VrtTimestampMode::SampleCount => VrtTimestamp::sample_count(start),
+            VrtTimestampMode::Utc => {
+                let epoch = *self.epoch_ns.get_or_insert_with(|| {
+                    let now = timestamp
+                        .as_ref()
+                        .and_then(|ts| ts.synced.map(|s| s.utc_nanos()))
+                        .unwrap_or_else(|| WallClock::now().as_nanos());
+                    now.saturating_sub((start as f64 * 1e9 / rate).round() as u64)
+                });
+                VrtTimestamp::from_utc_nanos(epoch + (start as f64 * 1e9 / rate).round() as u64)
+            }
+        };
+
+        // Context on the first packet, on every change and periodically
+        let packets = buffer.len().div_ceil(self.sender.samples_per_packet);
+        let due = match self.since_context {
+            None => true,
+            Some(n) => changed || n + packets > self.context_interval,
+        };
+        if due {
+            let mut context = Self::context_packet(&config, self.sender.format(), changed);
+            context.timestamp = vrt_timestamp;
+            self.sender
+                .send_context(&context)
+                .map_err(|e| io_error("VRT send", e))?;
+            self.since_context = Some(0);
+        }
+
+        self.sender
+            .send(buffer, vrt_timestamp, rate)
+            .map_err(|e| io_error("VRT send", e))?;
+        if let Some(n) = self.since_context.as_mut() {
+            *n += packets;
+        }
+
+        self.cursor = start + buffer.len() as u64;
+        self.status.samples_processed += buffer.len() as u64;
+        Ok(buffer.len())
+    }
+
+    fn status(&self) -> StreamStatus {
+        self.status.clone()
+    }
+
+    fn timestamp(&self) -> Timestamp {
+        Timestamp::at_sample(self.cursor, lock(&self.shared).local.sample_rate)
+    }
+
+    fn available(&self) -> usize {
+        0
+    }
+
+    fn free_space(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// Tuner of a VRT device
+///
+/// Values come from the most recent received context packet when there
+/// is one; local changes are announced to the TX destination.
+struct VrtTuner {
+    shared: Arc<Mutex<VrtShared>>,
+    config: SdrConfig,
+    /// Received context already applied to `config`
+    seen_seq: u64,
+}
+
+impl VrtTuner {
+    /// Apply a newly received context packet to the local view.
+    fn sync(&mut self) {
+        let shared = lock(&self.shared);
+        if shared.remote_seq == self.seen_seq {
+            return;
+        }
+        self.seen_seq = shared.remote_seq;
+        if let Some(context) = &shared.remote {
+            if let Some(freq) = context.rf_reference_hz {
+                self.config.frequency = freq + context.rf_offset_hz.unwrap_or(0.0);
+            }
+            if let Some(rate) = context.sample_rate_hz {
+                self.config.sample_rate = rate;
+            }
+            if let Some(bw) = context.bandwidth_hz {
+                self.config.bandwidth = bw;
+            }
+            if let Some(gain) = context.gain_db {
+                self.config.rx_gain = gain;
+            }
+        }
+    }
+
+    /// Publish local settings for the next transmitted context.
+    fn publish(&mut self) {
+        let mut shared = lock(&self.shared);
+        shared.local = self.config.clone();
+        shared.changed = true;
+    }
+}
+
+impl TunerControl for VrtTuner {
+    fn set_frequency(&mut self, freq_hz: u64) -> SdrResult<u64> {
+        self.config.frequency = freq_hz as f64;
+        self.publish();
+        Ok(freq_hz)
+    }
+
+    fn frequency(&self) -> u64 {
+        self.config.frequency as u64
+    }
+
+    fn set_sample_rate(&mut self, rate: f64) -> SdrResult<f64> {
+        if rate <= 0.0 {
+            return Err(SdrError::ConfigError(format!("Sample rate {} out of range", rate)));
+        }
+        self.config.sample_rate = rate;
+        self.publish();
+        Ok(rate)
+    }
+
+    fn sample_rate(&self) -> f64 {
+        self.config.sample_rate
+    }
+
+    fn set_bandwidth(&mut self, bw_hz: f64) -> SdrResult<f64> {
+        self.config.bandwidth = bw_hz;
+        self.publish();
+        Ok(bw_hz)
+    }
+
+    fn bandwidth(&self) -> f64 {
+        self.config.bandwidth
+    }
+
+    fn set_rx_gain(&mut self, gain_db: f64) -> SdrResult<f64> {
+        self.config.rx_gain = gain_db;
+        Ok(gain_db)
+    }
+
+    fn rx_gain(&self) -> f64 {
+        self.config.rx_gain
+    }
+
+    fn set_tx_gain(&mut self, gain_db: f64) -> SdrResult<f64> {
+        self.config.tx_gain = gain_db;
+        self.publish();
+        Ok(gain_db)
+    }
+
+    fn tx_gain(&self) -> f64 {
+        self.config.tx_gain
+    }
+
+    fn set_antenna(&mut self, _antenna: &str) -> SdrResult<()> {
+        Ok(())
+    }
+
+    fn antenna(&self) -> &str {
+        &self.config.antenna
+    }
+
+    fn available_antennas(&self) -> Vec<String> {
+        vec![self.config.antenna.clone()]
+    }
+
+    fn frequency_range(&self) -> (u64, u64) {
+        (0, u64::MAX)
+    }
+
+    fn sample_rate_range(&self) -> (f64, f64) {
+        (1.0, 1e12)
+    }
+
+    fn gain_range(&self) -> (f64, f64) {
+        (-256.0, 256.0)
+    }
+}
+
+/// VITA 49 network device
+pub struct VrtDevice {
+    name: String,
+    params: VrtParams,
+    tuner: VrtTuner,
+    socket: UdpSocket,
+    dest: Option<SocketAddr>,
+}
+
+impl VrtDevice {
+    /// Create a device from parameters
+    pub fn new(params: VrtParams) -> SdrResult<Self> {
+        let dest = match &params.dest {
+            Some(dest) => Some(
+                dest.to_socket_addrs()
+                    .map_err(|e| SdrError::ConfigError(format!("Invalid VRT destination '{}': {}", dest, e)))?
+                    .next()
+                    .ok_or_else(|| SdrError::ConfigError(format!("VRT destination '{}' did not resolve", dest)))?,
+            ),
+            None => None,
+        };
+        let listen = match (&params.listen, dest) {
+            (Some(listen), _) => listen.clone(),
+            (None, Some(_)) => "0.0.0.0:0".to_string(),
+            (None, None) => format!("0.0.0.0:{}", VRT_DEFAULT_PORT),
+        };
+        let socket = UdpSocket::bind(&listen).map_err(|e| io_error(&format!("VRT bind {}", listen), e))?;
+
+        let config = SdrConfig {
+            frequency: params.frequency,
+            sample_rate: params.sample_rate,
+            bandwidth: params.sample_rate,
+            antenna: "VRT".to_string(),
+            ..Default::default()
+        };
+        let shared = Arc::new(Mutex::new(VrtShared {
+            local: config.clone(),
+            changed: false,
+            remote: None,
+            remote_seq: 0,
+            rx_format: params.format,
+        }));
+
+        Ok(Self {
+            name: format!("VITA 49 ({})", socket.local_addr().map(|a| a.to_string()).unwrap_or(listen)),
+            params,
+            tuner: VrtTuner {
+                shared,
+                config,
+                seen_seq: 0,
+            },
+            socket,
+            dest,
+        })
+    }
+
+    /// Address the device receives on
+    pub fn local_addr(&self) -> SdrResult<SocketAddr> {
+        self.socket.local_addr().map_err(|e| io_error("VRT", e))
+    }
+
+    /// Parameters the device was created with
+    pub fn params(&self) -> &VrtParams {
+        &self.params
+    }
+
+    /// Most recent context packet received
+    pub fn last_context(&self) -> Option<ContextPacket> {
+        lock(&self.tuner.shared).remote.clone()
+    }
+}
+
+impl SdrDeviceExt for VrtDevice {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        DeviceCapabilities {
+            can_tx: self.dest.is_some(),
+            can_rx: true,
+            full_duplex: self.dest.is_some(),
+            min_frequency: 0.0,
+            max_frequency: f64::MAX,
+            max_sample_rate: 1e12,
+            tx_channels: self.dest.is_some() as usize,
+            rx_channels: 1,
+        }
+    }
+
+    fn config(&self) -> &SdrConfig {
+        &self.tuner.config
+    }
+
+    fn configure(&mut self, config: &SdrConfig) -> SdrResult<()> {
+        self.tuner.set_sample_rate(config.sample_rate)?;
+        self.tuner.config = config.clone();
+        self.tuner.publish();
+        Ok(())
+    }
+
+    fn create_rx_stream(&mut self, config: StreamConfig) -> SdrResult<Box<dyn StreamHandle>> {
+        if config.channels.iter().any(|&c| c != 0) {
+            return Err(SdrError::ConfigError("VRT device has a single RX channel".to_string()));
+        }
+        let socket = self.socket.try_clone().map_err(|e| io_error("VRT", e))?;
+        let mut receiver = VrtReceiver::with_socket(socket);
+        receiver.set_stream_filter(self.params.stream_id);
+
+        Ok(Box::new(VrtRxStream {
+            receiver,
+            shared: self.tuner.shared.clone(),
+            running: false,
+            pending: VecDeque::new(),
+            next: None,
+            cursor: 0,
+            status: StreamStatus::default(),
+        }))
+    }
+
+    fn create_tx_stream(&mut self, config: StreamConfig) -> SdrResult<Box<dyn StreamHandle>> {
+        let Some(dest) = self.dest else {
+            return Err(SdrError::ConfigError("VRT device has no 'dest' to transmit to".to_string()));
+        };
+        if config.channels.iter().any(|&c| c != 0) {
+            return Err(SdrError::ConfigError("VRT device has a single TX channel".to_string()));
+        }
+        let socket = self.socket.try_clone().map_err(|e| io_error("VRT", e))?;
+        let sender = VrtSender::with_socket(socket, dest, self.params.stream_id.unwrap_or(1), self.params.format)
+            .map_err(|e| io_error("VRT", e))?
+            .with_samples_per_packet(self.params.samples_per_packet);
+
+        Ok(Box::new(VrtTxStream {
+            sender,
+            shared: self.tuner.shared.clone(),
+            timestamps: self.params.timestamps,
+            context_interval: self.params.context_interval.max(1),
+            running: false,
+            cursor: 0,
+            epoch_ns: None,
+            since_context: None,
+            status: StreamStatus::default(),
+        }))
+    }
+
+    fn tuner(&mut self) -> &mut dyn TunerControl {
+        self.tuner.sync();
+        &mut self.tuner
+    }
+
+    fn clock(&mut self) -> Option<&mut dyn ClockControl> {
+        None
+    }
+
+    fn native_format(&self) -> SampleFormat {
+        match self.params.format {
+            PayloadFormat::Ci8 => SampleFormat::ComplexInt8,
+            PayloadFormat::Ci16 => SampleFormat::ComplexInt16,
+            PayloadFormat::Cf32 => SampleFormat::ComplexFloat32,
+        }
+    }
+}
+
+/// VRT driver for the registry (`vrt://`, alias `vita49://`)
+pub struct VrtDriver;
+
+impl VrtDriver {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for VrtDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeviceDriver for VrtDriver {
+    fn name(&self) -> &str {
+        "vrt"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["vita49"]
+    }
+
+    fn discover(&self) -> Vec<DeviceInfo> {
+        // VRT streams are pushed to us; there is nothing to enumerate
+        Vec::new()
+    }
+
+    fn create(&self, info: &DeviceInfo) -> SdrResult<Box<dyn SdrDeviceExt>> {
+        let args = info.address.split_once("://").map_or(info.address.as_str(), |(_, a)| a);
+        self.create_from_string(args)
+    }
+
+    fn create_from_string(&self, args: &str) -> SdrResult<Box<dyn SdrDeviceExt>> {
+        let params = VrtParams::parse(args)?;
+        Ok(Box::new(VrtDevice::new(params)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A receiving device on an ephemeral port and a transmitter aimed at it
+    fn pair(tx_args: &str) -> (VrtDevice, VrtDevice) {
+        let rx = VrtDevice::new(VrtParams::parse("127.0.0.1:0").unwrap()).unwrap();
+        let dest = rx.local_addr().unwrap();
+        let tx = VrtDevice::new(VrtParams::parse(&format!("dest={},{}", dest, tx_args)).unwrap()).unwrap();
+        (rx, tx)
+    }
+
+    fn tone(n: usize) -> Vec<IQSample> {
+        (0..n)
+            .map(|i| IQSample::from_polar(0.7, 2.0 * std::f64::consts::PI * 0.01 * i as f64))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_params() {
+        let p = VrtParams::parse(":5000,dest=10.0.0.2,format=cf32,stream=0x10,spp=1000,timestamps=utc").unwrap();
+        assert_eq!(p.listen.as_deref(), Some("0.0.0.0:5000"));
+        assert_eq!(p.dest.as_deref(), Some("10.0.0.2:4991"));
+        assert_eq!(p.format, PayloadFormat::Cf32);
+        assert_eq!(p.stream_id, Some(16));
+        assert_eq!(p.samples_per_packet, 1000);
+        assert_eq!(p.timestamps, VrtTimestampMode::Utc);
+
+        assert_eq!(VrtParams::parse("").unwrap(), VrtParams::default());
+        assert!(VrtParams::parse("format=ci4").is_err());
+        assert!(VrtParams::parse("spp=40000").is_err());
+        assert!(VrtParams::parse("bogus=1").is_err());
+    }
+
+    #[test]
+    fn test_stream_roundtrip_with_context() {
+        let (mut rx_device, mut tx_device) = pair("stream=42,format=cf32,spp=250");
+        tx_device.tuner().set_frequency(2_400_000_000).unwrap();
+        tx_device.tuner().set_sample_rate(2e6).unwrap();
+        tx_device.tuner().set_tx_gain(12.5).unwrap();
+
+        let mut rx = rx_device.create_rx_stream(StreamConfig::default()).unwrap();
+        rx.start().unwrap();
+        let mut tx = tx_device.create_tx_stream(StreamConfig::default()).unwrap();
+        tx.start().unwrap();
+
+        let sent = tone(2000);
+        tx.write(&sent[..1000], Some(Timestamp::at_sample(10_000, 2e6)), Duration::ZERO).unwrap();
+        tx.write(&sent[1000..], None, Duration::ZERO).unwrap();
+
+        let mut received = Vec::new();
+        let mut buffer = vec![IQSample::new(0.0, 0.0); 500];
+        let mut timestamps = Vec::new();
+        while received.len() < sent.len() {
+            let (n, ts) = rx.read(&mut buffer, Duration::from_secs(2)).unwrap();
+            timestamps.push(ts.sample.samples());
+            received.extend_from_slice(&buffer[..n]);
+        }
+
+        assert_eq!(timestamps[0], 10_000);
+        assert_eq!(timestamps[1], 10_500);
+        for (a, b) in received.iter().zip(&sent) {
+            assert!((a - b).norm() < 1e-6);
+        }
+        assert_eq!(rx.status().overflow_count, 0);
+
+        // The context packet retuned the receiving side
+        let context = rx_device.last_context().unwrap();
+        assert_eq!(context.stream_id, 42);
+        assert_eq!(context.data_format, Some(PayloadFormat::Cf32));
+        assert_eq!(rx_device.tuner().frequency(), 2_400_000_000);
+        assert_eq!(rx_device.tuner().sample_rate(), 2e6);
+        assert_eq!(rx_device.tuner().rx_gain(), 12.5);
+    }
+
+    #[test]
+    fn test_gap_detection_and_utc_timestamps() {
+        let (mut rx_device, mut tx_device) = pair("spp=100");
+        let mut rx = rx_device.create_rx_stream(StreamConfig::default()).unwrap();
+        rx.start().unwrap();
+        let mut tx = tx_device.create_tx_stream(StreamConfig::default()).unwrap();
+        tx.start().unwrap();
+
+        // 200 samples, then a jump of 1000 samples
+        tx.write(&tone(200), Some(Timestamp::at_sample(0, 1e6)), Duration::ZERO).unwrap();
+        tx.write(&tone(200), Some(Timestamp::at_sample(1200, 1e6)), Duration::ZERO).unwrap();
+
+        let mut buffer = vec![IQSample::new(0.0, 0.0); 400];
+        let (n, ts) = rx.read(&mut buffer, Duration::from_secs(2)).unwrap();
+        assert_eq!((n, ts.sample.samples()), (200, 0));
+        let (n, ts) = rx.read(&mut buffer[..200], Duration::from_secs(2)).unwrap();
+        assert_eq!((n, ts.sample.samples()), (200, 1200));
+        assert_eq!(rx.status().overflow_count, 1);
+
+        // UTC timestamps come back as synced time
+        let (mut rx_device, mut tx_device) = pair("timestamps=utc");
+        let mut rx = rx_device.create_rx_stream(StreamConfig::default()).unwrap();
+        rx.start().unwrap();
+        let mut tx = tx_device.create_tx_stream(StreamConfig::default()).unwrap();
+        tx.start().unwrap();
+        let t0 = SyncedTime::from_utc_nanos(1_700_000_000_000_000_000, TimeSource::Gps);
+        tx.write(&tone(500), Some(Timestamp::at_sample(0, 1e6).with_synced(t0)), Duration::ZERO).unwrap();
+
+        let (n, ts) = rx.read(&mut buffer, Duration::from_secs(2)).unwrap();
+        assert_eq!(n, 400);
+        assert_eq!(ts.synced.unwrap().utc_nanos(), 1_700_000_000_000_000_000);
+        let (_, ts) = rx.read(&mut buffer[..100], Duration::from_secs(2)).unwrap();
+        assert_eq!(ts.synced.unwrap().utc_nanos(), 1_700_000_000_000_400_000);
+    }
+
+    #[test]
+    fn test_receiver_filters_and_counts() {
+        let mut receiver = VrtReceiver::bind("127.0.0.1:0").unwrap();
+        receiver.set_stream_filter(Some(5));
+        let addr = receiver.local_addr().unwrap();
+
+        let mut other = VrtSender::new(addr, 6, PayloadFormat::Ci16).unwrap();
+        let mut wanted = VrtSender::new(addr, 5, PayloadFormat::Ci16).unwrap().with_samples_per_packet(10);
+        other.send(&tone(10), VrtTimestamp::sample_count(0), 1e6).unwrap();
+        wanted.send(&tone(10), VrtTimestamp::sample_count(0), 1e6).unwrap();
+        // Skip three packet counts as if they were lost
+        wanted.data_count = (wanted.data_count + 3) & 0xf;
+        wanted.send(&tone(10), VrtTimestamp::sample_count(40), 1e6).unwrap();
+
+        let first = receiver.recv(Duration::from_secs(2)).unwrap().unwrap();
+        assert_eq!(first.stream_id(), Some(5));
+        receiver.recv(Duration::from_secs(2)).unwrap().unwrap();
+        assert_eq!(receiver.packets_lost, 3);
+        assert!(receiver.recv(Duration::from_millis(20)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_registry_and_errors() {
+        let registry = crate::hal::create_default_registry();
+        let mut device = registry.create("vita49://127.0.0.1:0").unwrap();
+        assert!(device.name().starts_with("VITA 49"));
+        assert!(!device.capabilities().can_tx);
+        assert!(device.create_tx_stream(StreamConfig::default()).is_err());
+
+        let mut rx = device.create_rx_stream(StreamConfig::default()).unwrap();
+        let mut buffer = vec![IQSample::new(0.0, 0.0); 16];
+        assert!(matches!(rx.read(&mut buffer, Duration::ZERO), Err(SdrError::NotStarted)));
+        rx.start().unwrap();
+        assert!(matches!(rx.read(&mut buffer, Duration::from_millis(10)), Err(SdrError::Timeout(_))));
+    }
+}
@@ -0,0 +1,430 @@
+//! # WAV IQ Recordings
+//!
+//! Stereo WAV files with I in the left and Q in the right channel, as
+//! written by SDR#, SDRuno, HDSDR and SpectraVue.
+//!
+//! ```text
+//! RIFF/RF64 "WAVE"
+//!   ds64   64-bit sizes (RF64 only, for recordings over 4 GiB)
+//!   fmt    PCM 8-bit unsigned, 16-bit signed or 32-bit IEEE float, 2 channels
+//!   auxi   SYSTEMTIME start, SYSTEMTIME stop, centre frequency (u32 Hz), ...
+//!   data   interleaved I/Q
+//! ```
+//!
+//! The `auxi` chunk is optional; without it the centre frequency and start
+//! time fall back to the recorder's file name
+//! (`SDRSharp_20240101_120000Z_433920000Hz_IQ.wav`). SYSTEMTIME values are
+//! taken as UTC, which is what SDR# writes.
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike, Utc};
+use r4w_core::types::IQSample;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::iqfile::{sigmf_datatype, FilenameHints, IqSink, IqSource};
+use super::sigmf::SigMfMeta;
+use super::SampleFormat;
+use crate::device::{SdrError, SdrResult};
+
+/// `WAVE_FORMAT_PCM`
+const FORMAT_PCM: u16 = 1;
+/// `WAVE_FORMAT_IEEE_FLOAT`
+const FORMAT_FLOAT: u16 = 3;
+/// `WAVE_FORMAT_EXTENSIBLE` (actual format in the sub-format GUID)
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Size of the `auxi` chunk written by [`WavIqWriter`].
+const AUXI_LEN: usize = 64;
+
+fn read_err(e: std::io::Error) -> SdrError {
+    SdrError::HardwareError(format!("Read failed: {}", e))
+}
+
+fn write_err(e: std::io::Error) -> SdrError {
+    SdrError::HardwareError(format!("Write failed: {}", e))
+}
+
+fn u16_at(b: &[u8], i: usize) -> u16 {
+    u16::from_le_bytes([b[i], b[i + 1]])
+}
+
+fn u32_at(b: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]])
+}
+
+/// Decode a Windows SYSTEMTIME (year, month, weekday, day, h, m, s, ms).
+fn systemtime(b: &[u8]) -> Option<NaiveDateTime> {
+    let field = |i: usize| u16_at(b, i * 2) as u32;
+    NaiveDate::from_ymd_opt(field(0) as i32, field(1), field(3))?
+        .and_hms_milli_opt(field(4), field(5), field(6), field(7))
+}
+
+/// Encode a Windows SYSTEMTIME.
+fn to_systemtime(t: &NaiveDateTime) -> [u8; 16] {
+    let fields = [
+        t.year() as u16,
+        t.month() as u16,
+        t.weekday().num_days_from_sunday() as u16,
+        t.day() as u16,
+        t.hour() as u16,
+        t.minute() as u16,
+        t.second() as u16,
+        (t.nanosecond() / 1_000_000).min(999) as u16,
+    ];
+    let mut out = [0u8; 16];
+    for (chunk, v) in out.chunks_exact_mut(2).zip(fields) {
+        chunk.copy_from_slice(&v.to_le_bytes());
+    }
+    out
+}
+
+/// Reader for WAV IQ recordings.
+pub struct WavIqReader {
+    meta: SigMfMeta,
+    format: SampleFormat,
+    file: BufReader<File>,
+    position: u64,
+    total_samples: u64,
+}
+
+impl WavIqReader {
+    /// Open a WAV recording and map its header into SigMF metadata.
+    pub fn open<P: AsRef<Path>>(path: P) -> SdrResult<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to open {}: {}", path.display(), e))
+        })?;
+        let file_len = file.metadata().map_err(read_err)?.len();
+        let mut file = BufReader::new(file);
+
+        let mut riff = [0u8; 12];
+        file.read_exact(&mut riff).map_err(read_err)?;
+        let rf64 = &riff[0..4] == b"RF64";
+        if !(&riff[0..4] == b"RIFF" || rf64) || &riff[8..12] != b"WAVE" {
+            return Err(SdrError::ConfigError(format!("{} is not a WAV file", path.display())));
+        }
+
+        let mut fmt: Option<Vec<u8>> = None;
+        let mut auxi: Option<Vec<u8>> = None;
+        let mut ds64_data_len: Option<u64> = None;
+        let mut data: Option<(u64, u64)> = None;
+        let mut offset = 12u64;
+        while offset + 8 <= file_len {
+            let mut header = [0u8; 8];
+            file.seek(SeekFrom::Start(offset)).map_err(read_err)?;
+            file.read_exact(&mut header).map_err(read_err)?;
+            let id = &header[0..4];
+            let mut size = u32_at(&header, 4) as u64;
+            let body = offset + 8;
+
+            if id == b"data" {
+                if rf64 && size == u32::MAX as u64 {
+                    size = ds64_data_len.unwrap_or(size);
+                }
+                // Recorders that stopped abruptly leave a stale size
+                data = Some((body, size.min(file_len - body)));
+                break;
+            }
+            if matches!(id, b"fmt " | b"auxi" | b"ds64") {
+                let mut chunk = vec![0u8; size as usize];
+                file.read_exact(&mut chunk).map_err(read_err)?;
+                match id {
+                    b"fmt " => fmt = Some(chunk),
+                    b"auxi" => auxi = Some(chunk),
+                    _ if chunk.len() >= 16 => {
+                        ds64_data_len = Some(u64::from_le_bytes(chunk[8..16].try_into().expect("8 bytes")))
+                    }
+                    _ => {}
+                }
+            }
+            offset = body + size + (size & 1);
+        }
+
+        let fmt = fmt.filter(|f| f.len() >= 16).ok_or_else(|| {
+            SdrError::ConfigError(format!("{}: missing fmt chunk", path.display()))
+        })?;
+        let (data_offset, data_len) = data.ok_or_else(|| {
+            SdrError::ConfigError(format!("{}: missing data chunk", path.display()))
+        })?;
+
+        let mut tag = u16_at(&fmt, 0);
+        if tag == FORMAT_EXTENSIBLE && fmt.len() >= 26 {
+            tag = u16_at(&fmt, 24);
+        }
+        let channels = u16_at(&fmt, 2);
+        let sample_rate = u32_at(&fmt, 4) as f64;
+        let bits = u16_at(&fmt, 14);
+        let format = match (tag, bits) {
+            (FORMAT_PCM, 8) => SampleFormat::ComplexUint8,
+            (FORMAT_PCM, 16) => SampleFormat::ComplexInt16,
+            (FORMAT_FLOAT, 32) => SampleFormat::ComplexFloat32,
+            _ => {
+                return Err(SdrError::Unsupported(format!(
+                    "WAV format {} with {}-bit samples",
+                    tag, bits
+                )))
+            }
+        };
+        if channels != 2 {
+            return Err(SdrError::ConfigError(format!(
+                "{}: IQ WAV needs 2 channels, found {}",
+                path.display(),
+                channels
+            )));
+        }
+
+        let hints = FilenameHints::parse(path);
+        let mut frequency = hints.frequency;
+        let mut start = hints.datetime;
+        if let Some(auxi) = auxi.filter(|a| a.len() >= 36) {
+            if let Some(t) = systemtime(&auxi[0..16]) {
+                start = Some(t.and_utc().to_rfc3339());
+            }
+            frequency = Some(u32_at(&auxi, 32) as f64);
+        }
+
+        let mut meta = SigMfMeta::new(sample_rate, frequency.unwrap_or(0.0), sigmf_datatype(format));
+        meta.global.datetime = start.clone();
+        meta.captures[0].datetime = start;
+
+        file.seek(SeekFrom::Start(data_offset)).map_err(read_err)?;
+        Ok(Self {
+            meta,
+            format,
+            file,
+            position: 0,
+            total_samples: data_len / format.bytes_per_sample() as u64,
+        })
+    }
+}
+
+impl IqSource for WavIqReader {
+    fn metadata(&self) -> &SigMfMeta {
+        &self.meta
+    }
+
+    fn total_samples(&self) -> u64 {
+        self.total_samples
+    }
+
+    fn read_samples(&mut self, buffer: &mut [IQSample]) -> SdrResult<usize> {
+        let count = buffer.len().min((self.total_samples - self.position) as usize);
+        let mut bytes = vec![0u8; count * self.format.bytes_per_sample()];
+        self.file.read_exact(&mut bytes).map_err(read_err)?;
+        for (out, sample) in buffer.iter_mut().zip(self.format.decode(&bytes)) {
+            *out = sample;
+        }
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+/// Writer for WAV IQ recordings with an SDR#-compatible `auxi` chunk.
+///
+/// Recordings are limited to 4 GiB of samples (plain RIFF).
+pub struct WavIqWriter {
+    meta: SigMfMeta,
+    format: SampleFormat,
+    file: BufWriter<File>,
+    start: NaiveDateTime,
+    data_len: u64,
+}
+
+impl WavIqWriter {
+    /// Create a recording; `format` must be `cu8`, `ci16` or `cf32`.
+    ///
+    /// The sample rate, centre frequency and start time come from `meta`.
+    pub fn create<P: AsRef<Path>>(path: P, meta: &SigMfMeta, format: SampleFormat) -> SdrResult<Self> {
+        if matches!(format, SampleFormat::ComplexInt8) {
+            return Err(SdrError::Unsupported("WAV has no signed 8-bit PCM".to_string()));
+        }
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| {
+            SdrError::ConfigError(format!("Failed to create {}: {}", path.display(), e))
+        })?;
+        let start = meta
+            .global
+            .datetime
+            .as_deref()
+            .and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok())
+            .map(|d| d.naive_utc())
+            .unwrap_or_else(|| Utc::now().naive_utc());
+
+        let mut out_meta = SigMfMeta::new(meta.global.sample_rate, meta.frequency(), sigmf_datatype(format));
+        out_meta.global.datetime = Some(start.and_utc().to_rfc3339());
+        out_meta.captures[0].datetime = out_meta.global.datetime.clone();
+
+        let mut writer = Self {
+            meta: out_meta,
+            format,
+            file: BufWriter::new(file),
+            start,
+            data_len: 0,
+        };
+        writer.write_header()?;
+        Ok(writer)
+    }
+
+    /// Write (or rewrite) the headers for the current data length.
+    fn write_header(&mut self) -> SdrResult<()> {
+        let (tag, bits) = match self.format {
+            SampleFormat::ComplexFloat32 => (FORMAT_FLOAT, 32u16),
+            SampleFormat::ComplexInt16 => (FORMAT_PCM, 16),
+            _ => (FORMAT_PCM, 8),
+        };
+        let rate = self.meta.global.sample_rate.round() as u32;
+        let block_align = self.format.bytes_per_sample() as u16;
+        let data_len = u32::try_from(self.data_len).map_err(|_| {
+            SdrError::Unsupported("WAV recordings are limited to 4 GiB".to_string())
+        })?;
+        let stop = self.start + chrono::Duration::nanoseconds(
+            (self.data_len as f64 / block_align as f64 / self.meta.global.sample_rate * 1e9).round() as i64,
+        );
+
+        let mut header = Vec::with_capacity(12 + 24 + 8 + AUXI_LEN + 8);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&(4 + 24 + 8 + AUXI_LEN as u32 + 8 + data_len).to_le_bytes());
+        header.extend_from_slice(b"WAVE");
+
+        header.extend_from_slice(b"fmt ");
+        header.extend_from_slice(&16u32.to_le_bytes());
+        header.extend_from_slice(&tag.to_le_bytes());
+        header.extend_from_slice(&2u16.to_le_bytes());
+        header.extend_from_slice(&rate.to_le_bytes());
+        header.extend_from_slice(&(rate * block_align as u32).to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&bits.to_le_bytes());
+
+        header.extend_from_slice(b"auxi");
+        header.extend_from_slice(&(AUXI_LEN as u32).to_le_bytes());
+        header.extend_from_slice(&to_systemtime(&self.start));
+        header.extend_from_slice(&to_systemtime(&stop));
+        header.extend_from_slice(&(self.meta.frequency().clamp(0.0, u32::MAX as f64) as u32).to_le_bytes());
+        header.extend_from_slice(&rate.to_le_bytes());
+        header.resize(12 + 24 + 8 + AUXI_LEN, 0);
+
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&data_len.to_le_bytes());
+
+        self.file.seek(SeekFrom::Start(0)).map_err(write_err)?;
+        self.file.write_all(&header).map_err(write_err)?;
+        self.file.seek(SeekFrom::End(0)).map_err(write_err)?;
+        Ok(())
+    }
+}
+
+impl IqSink for WavIqWriter {
+    fn write_samples(&mut self, samples: &[IQSample]) -> SdrResult<usize> {
+        let bytes = self.format.encode(samples);
+        self.file.write_all(&bytes).map_err(write_err)?;
+        self.data_len += bytes.len() as u64;
+        Ok(samples.len())
+    }
+
+    fn samples_written(&self) -> u64 {
+        self.data_len / self.format.bytes_per_sample() as u64
+    }
+
+    fn finish(mut self: Box<Self>) -> SdrResult<SigMfMeta> {
+        self.write_header()?;
+        self.file.flush().map_err(write_err)?;
+        Ok(self.meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sdrsharp_style_file() {
+        // Hand-built SDR# recording: 8-bit PCM with an auxi chunk and an
+        // unrelated LIST chunk in between
+        let mut auxi = Vec::new();
+        for v in [2023u16, 11, 0, 5, 14, 30, 15, 250] {
+            auxi.extend_from_slice(&v.to_le_bytes());
+        }
+        auxi.extend_from_slice(&[0u8; 16]);
+        auxi.extend_from_slice(&145_500_000u32.to_le_bytes());
+        auxi.extend_from_slice(&[0u8; 28]);
+
+        let samples: [u8; 8] = [255, 128, 0, 128, 128, 255, 128, 0];
+        let mut chunks = Vec::new();
+        chunks.extend_from_slice(b"fmt ");
+        chunks.extend_from_slice(&16u32.to_le_bytes());
+        for v in [FORMAT_PCM, 2] {
+            chunks.extend_from_slice(&v.to_le_bytes());
+        }
+        chunks.extend_from_slice(&48_000u32.to_le_bytes());
+        chunks.extend_from_slice(&96_000u32.to_le_bytes());
+        for v in [2u16, 8] {
+            chunks.extend_from_slice(&v.to_le_bytes());
+        }
+        chunks.extend_from_slice(b"LIST");
+        chunks.extend_from_slice(&3u32.to_le_bytes());
+        chunks.extend_from_slice(b"abc\0");
+        chunks.extend_from_slice(b"auxi");
+        chunks.extend_from_slice(&(auxi.len() as u32).to_le_bytes());
+        chunks.extend_from_slice(&auxi);
+        chunks.extend_from_slice(b"data");
+        chunks.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+        chunks.extend_from_slice(&samples);
+
+        let mut file = b"RIFF".to_vec();
+        file.extend_from_slice(&(4 + chunks.len() as u32).to_le_bytes());
+        file.extend_from_slice(b"WAVE");
+        file.extend_from_slice(&chunks);
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("SDRSharp_20990101_000000Z_100000000Hz_IQ.wav");
+        std::fs::write(&path, &file).unwrap();
+
+        let mut reader = WavIqReader::open(&path).unwrap();
+        assert_eq!(reader.sample_rate(), 48_000.0);
+        // auxi wins over the file name
+        assert_eq!(reader.frequency(), 145_500_000.0);
+        assert_eq!(reader.metadata().global.datatype, "cu8");
+        assert_eq!(reader.metadata().global.datetime.as_deref(), Some("2023-11-05T14:30:15.250+00:00"));
+        assert_eq!(reader.total_samples(), 4);
+
+        let read = reader.read_all().unwrap();
+        assert!((read[0].re - 1.0).abs() < 1e-9 && read[0].im.abs() < 0.01);
+        assert!((read[1].re + 1.0).abs() < 1e-9);
+        assert!((read[2].im - 1.0).abs() < 1e-9);
+        assert!((read[3].im + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_float_roundtrip_and_auxi() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("out.wav");
+        let mut meta = SigMfMeta::new(2.4e6, 433.92e6, "cf32_le");
+        meta.global.datetime = Some("2024-02-29T23:59:59.500+00:00".to_string());
+
+        let samples: Vec<IQSample> = (0..2400).map(|i| IQSample::from_polar(0.8, i as f64 * 0.3)).collect();
+        let mut writer: Box<dyn IqSink> =
+            Box::new(WavIqWriter::create(&path, &meta, SampleFormat::ComplexFloat32).unwrap());
+        writer.write_samples(&samples[..1000]).unwrap();
+        writer.write_samples(&samples[1000..]).unwrap();
+        assert_eq!(writer.samples_written(), 2400);
+        writer.finish().unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(u32_at(&bytes, 4) as usize, bytes.len() - 8);
+        // Stop time is one millisecond (2400 samples) after the start
+        let stop = systemtime(&bytes[44 + 16..44 + 32]).unwrap();
+        assert_eq!(stop.and_utc().to_rfc3339(), "2024-02-29T23:59:59.501+00:00");
+
+        let mut reader = WavIqReader::open(&path).unwrap();
+        assert_eq!(reader.sample_rate(), 2.4e6);
+        assert_eq!(reader.frequency(), 433.92e6);
+        assert_eq!(reader.metadata().global.datetime, meta.global.datetime);
+        let read = reader.read_all().unwrap();
+        assert_eq!(read.len(), 2400);
+        for (a, b) in samples.iter().zip(read.iter()) {
+            assert!((a - b).norm() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,750 @@
+//! RF Front-End Impairments
+//!
+//! Transceiver hardware impairments that sit around the propagation
+//! channel:
+//!
+//! ```text
+//!   TX ──► PA (AM/AM, AM/PM) ──► [propagation + AWGN] ──► LO phase noise ──► IQ imbalance ──► DC offset ──► ADC ──► RX
+//! ```
+//!
+//! - **Phase noise**: oscillator phase noise shaped by a single-sideband
+//!   `L(f)` mask in dBc/Hz (log-log interpolated between points).
+//! - **IQ imbalance**: receive mixer gain and phase mismatch,
+//!   `y = μ·x + ν·x*`, which creates an image at `−f`.
+//! - **DC offset**: constant I/Q offset from LO leakage.
+//! - **PA nonlinearity**: Rapp (solid-state), Saleh (TWTA) and memory
+//!   polynomial models, driven at a configurable input back-off.
+//! - **ADC**: uniform quantisation with clipping at full scale.
+//!
+//! All settings live in [`ImpairmentConfig`], which is part of
+//! [`ChannelConfig`](crate::channel::ChannelConfig) and serialises with it.
+//!
+//! ```rust
+//! use r4w_sim::impairments::{AdcConfig, ImpairmentConfig, Impairments, IqImbalance};
+//! use r4w_core::types::IQSample;
+//!
+//! let config = ImpairmentConfig {
+//!     iq_imbalance: Some(IqImbalance { gain_db: 0.5, phase_deg: 3.0 }),
+//!     adc: Some(AdcConfig { bits: 8, full_scale: 2.0 }),
+//!     ..Default::default()
+//! };
+//! let mut impairments = Impairments::new(config, 1e6);
+//!
+//! let clean = vec![IQSample::new(0.7, 0.7); 256];
+//! let amplified = impairments.apply_tx(&clean);
+//! let rx = impairments.apply_rx(&amplified);
+//! assert_eq!(rx.len(), clean.len());
+//! ```
+
+use std::collections::VecDeque;
+
+use r4w_core::fft_utils::FftProcessor;
+use r4w_core::types::IQSample;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, StandardNormal};
+use serde::{Deserialize, Serialize};
+
+/// Oscillator phase noise mask
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseNoiseConfig {
+    /// Mask points: (offset from carrier in Hz, L(f) in dBc/Hz), ascending offsets
+    pub mask: Vec<(f64, f64)>,
+    /// Length of the shaping filter (frequency resolution `fs / filter_len`)
+    #[serde(default = "default_phase_noise_filter_len")]
+    pub filter_len: usize,
+}
+
+fn default_phase_noise_filter_len() -> usize {
+    4096
+}
+
+impl PhaseNoiseConfig {
+    /// Create from mask points (offset Hz, dBc/Hz)
+    pub fn new(mask: Vec<(f64, f64)>) -> Self {
+        Self {
+            mask,
+            filter_len: default_phase_noise_filter_len(),
+        }
+    }
+
+    /// Parse a mask written as `offset:dBc,offset:dBc,...` (e.g. `1e3:-80,1e5:-110`)
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut mask = Vec::new();
+        for point in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (f, l) = point
+                .split_once(':')
+                .ok_or_else(|| format!("Expected offset:dBc, got '{}'", point))?;
+            let f: f64 = f.trim().parse().map_err(|_| format!("Invalid offset '{}'", f))?;
+            let l: f64 = l.trim().parse().map_err(|_| format!("Invalid level '{}'", l))?;
+            mask.push((f, l));
+        }
+        mask.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let config = Self::new(mask);
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that the mask is non-empty with strictly increasing positive offsets
+    pub fn validate(&self) -> Result<(), String> {
+        if self.mask.is_empty() {
+            return Err("Phase noise mask is empty".to_string());
+        }
+        if let Some(&(f, _)) = self.mask.iter().find(|(f, _)| !(f.is_finite() && *f > 0.0)) {
+            return Err(format!("Offset must be positive, got {}", f));
+        }
+        if let Some(&(_, l)) = self.mask.iter().find(|(_, l)| !l.is_finite()) {
+            return Err(format!("Invalid level {}", l));
+        }
+        if let Some(w) = self.mask.windows(2).find(|w| w[1].0 <= w[0].0) {
+            return Err(format!("Mask offsets must be strictly increasing, got {} after {}", w[1].0, w[0].0));
+        }
+        Ok(())
+    }
+
+    /// L(f) in dBc/Hz at an offset, interpolated linearly in log frequency
+    ///
+    /// The mask is held flat below its first and above its last point.
+    pub fn level_dbc(&self, offset_hz: f64) -> f64 {
+        let first = self.mask[0];
+        let last = self.mask[self.mask.len() - 1];
+        if offset_hz <= first.0 {
+            return first.1;
+        }
+        if offset_hz >= last.0 {
+            return last.1;
+        }
+        let i = self.mask.partition_point(|p| p.0 <= offset_hz);
+        let (f0, l0) = self.mask[i - 1];
+        let (f1, l1) = self.mask[i];
+        let t = (offset_hz / f0).log10() / (f1 / f0).log10();
+        l0 + t * (l1 - l0)
+    }
+
+    /// RMS phase error (radians) integrated over the simulated band
+    ///
+    /// Integrates `2·L(f)` from `fs / filter_len` to `fs / 2`, matching what
+    /// the generator produces.
+    pub fn rms_phase_rad(&self, sample_rate: f64) -> f64 {
+        let n = self.filter_len.max(2);
+        let df = sample_rate / n as f64;
+        let variance: f64 = (1..n / 2)
+            .map(|k| 2.0 * 10.0_f64.powf(self.level_dbc(k as f64 * df) / 10.0) * df)
+            .sum();
+        variance.sqrt()
+    }
+}
+
+/// Receive mixer gain/phase imbalance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IqImbalance {
+    /// Q-branch gain relative to I (dB)
+    pub gain_db: f64,
+    /// Quadrature phase error (degrees)
+    pub phase_deg: f64,
+}
+
+impl IqImbalance {
+    /// Coefficients (μ, ν) of `y = μ·x + ν·x*`
+    pub fn coefficients(&self) -> (IQSample, IQSample) {
+        let g = 10.0_f64.powf(self.gain_db / 20.0);
+        let phi = self.phase_deg.to_radians();
+        let mu = (IQSample::new(1.0, 0.0) + IQSample::from_polar(g, phi)) / 2.0;
+        let nu = (IQSample::new(1.0, 0.0) - IQSample::from_polar(g, -phi)) / 2.0;
+        (mu, nu)
+    }
+
+    /// Image rejection ratio (dB)
+    pub fn image_rejection_db(&self) -> f64 {
+        let (mu, nu) = self.coefficients();
+        10.0 * (mu.norm_sqr() / nu.norm_sqr()).log10()
+    }
+
+    fn apply(&self, x: IQSample) -> IQSample {
+        let g = 10.0_f64.powf(self.gain_db / 20.0);
+        let phi = self.phase_deg.to_radians();
+        IQSample::new(x.re, g * (x.re * phi.sin() + x.im * phi.cos()))
+    }
+}
+
+/// Power amplifier behavioural model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PaModel {
+    /// Rapp solid-state PA: AM/AM only
+    ///
+    /// `A_out = G·A / (1 + (G·A / A_sat)^(2p))^(1/(2p))`
+    Rapp {
+        /// Small-signal gain (linear)
+        gain: f64,
+        /// Output saturation amplitude
+        saturation: f64,
+        /// Smoothness factor p (≈2–3 for SSPAs)
+        smoothness: f64,
+    },
+    /// Saleh travelling-wave tube model
+    ///
+    /// `A(r) = α_a·r / (1 + β_a·r²)`, `Φ(r) = α_p·r² / (1 + β_p·r²)`
+    Saleh {
+        alpha_a: f64,
+        beta_a: f64,
+        alpha_p: f64,
+        beta_p: f64,
+    },
+    /// Memory polynomial
+    ///
+    /// `y[n] = Σ_k Σ_m a[k][m] · x[n−m] · |x[n−m]|^k`, with coefficients
+    /// given as `[re, im]` for nonlinearity order `k+1` and memory tap `m`.
+    MemoryPolynomial {
+        coefficients: Vec<Vec<[f64; 2]>>,
+    },
+}
+
+impl PaModel {
+    /// Rapp model with unit gain
+    pub fn rapp(saturation: f64, smoothness: f64) -> Self {
+        PaModel::Rapp { gain: 1.0, saturation, smoothness }
+    }
+
+    /// Saleh model with the classic TWTA coefficients
+    pub fn saleh() -> Self {
+        PaModel::Saleh {
+            alpha_a: 2.1587,
+            beta_a: 1.1517,
+            alpha_p: 4.0033,
+            beta_p: 9.1040,
+        }
+    }
+
+    /// Linear (small-signal) gain, used to normalise the output level
+    pub fn small_signal_gain(&self) -> IQSample {
+        match self {
+            PaModel::Rapp { gain, .. } => IQSample::new(*gain, 0.0),
+            PaModel::Saleh { alpha_a, .. } => IQSample::new(*alpha_a, 0.0),
+            PaModel::MemoryPolynomial { coefficients } => coefficients
+                .first()
+                .map(|taps| taps.iter().map(|c| IQSample::new(c[0], c[1])).sum())
+                .unwrap_or(IQSample::new(1.0, 0.0)),
+        }
+    }
+
+    /// Memoryless AM/AM and AM/PM: output amplitude and phase shift for input amplitude `r`
+    fn memoryless(&self, r: f64) -> (f64, f64) {
+        match self {
+            PaModel::Rapp { gain, saturation, smoothness } => {
+                let a = gain * r;
+                let p2 = 2.0 * smoothness;
+                (a / (1.0 + (a / saturation).powf(p2)).powf(1.0 / p2), 0.0)
+            }
+            PaModel::Saleh { alpha_a, beta_a, alpha_p, beta_p } => {
+                let r2 = r * r;
+                (alpha_a * r / (1.0 + beta_a * r2), alpha_p * r2 / (1.0 + beta_p * r2))
+            }
+            PaModel::MemoryPolynomial { .. } => (r, 0.0),
+        }
+    }
+}
+
+/// PA model and operating point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaConfig {
+    /// Behavioural model
+    pub model: PaModel,
+    /// Input back-off (dB): a unit-power signal drives the PA at `−backoff` dB
+    /// relative to unit amplitude. Output is rescaled to the input level.
+    pub input_backoff_db: f64,
+}
+
+impl PaConfig {
+    /// Check that the PA has a usable small-signal gain at this drive level
+    ///
+    /// The output is normalised by `small_signal_gain() · drive`, so both
+    /// must be finite and non-zero.
+    pub fn validate(&self) -> Result<(), String> {
+        let drive = 10.0_f64.powf(-self.input_backoff_db / 20.0);
+        if !(drive.is_finite() && drive > 0.0) {
+            return Err(format!("Invalid PA input back-off {} dB", self.input_backoff_db));
+        }
+        let gain = self.model.small_signal_gain();
+        if !(gain.norm().is_finite() && gain.norm() > 0.0) {
+            return Err("PA small-signal gain (first-order memory taps) must be non-zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// ADC quantisation and clipping
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AdcConfig {
+    /// Resolution (bits per I/Q component)
+    pub bits: u32,
+    /// Full-scale amplitude per component; larger values are clipped
+    pub full_scale: f64,
+}
+
+impl AdcConfig {
+    /// Ideal SQNR (dB) for a full-scale sine: 6.02·N + 1.76
+    pub fn ideal_sqnr_db(&self) -> f64 {
+        6.02 * self.bits as f64 + 1.76
+    }
+
+    /// Check the resolution and full scale
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=32).contains(&self.bits) {
+            return Err(format!("ADC resolution must be 1-32 bits, got {}", self.bits));
+        }
+        if !(self.full_scale.is_finite() && self.full_scale > 0.0) {
+            return Err(format!("ADC full scale must be positive, got {}", self.full_scale));
+        }
+        Ok(())
+    }
+}
+
+/// Complete front-end impairment configuration (all stages optional)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImpairmentConfig {
+    /// LO phase noise
+    #[serde(default)]
+    pub phase_noise: Option<PhaseNoiseConfig>,
+    /// Receive IQ imbalance
+    #[serde(default)]
+    pub iq_imbalance: Option<IqImbalance>,
+    /// DC offset (I, Q)
+    #[serde(default)]
+    pub dc_offset: Option<(f64, f64)>,
+    /// Transmit power amplifier
+    #[serde(default)]
+    pub pa: Option<PaConfig>,
+    /// Receive ADC
+    #[serde(default)]
+    pub adc: Option<AdcConfig>,
+}
+
+impl ImpairmentConfig {
+    /// True if no impairment is configured
+    pub fn is_empty(&self) -> bool {
+        self.phase_noise.is_none()
+            && self.iq_imbalance.is_none()
+            && self.dc_offset.is_none()
+            && self.pa.is_none()
+            && self.adc.is_none()
+    }
+
+    /// Check every configured stage
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(pn) = &self.phase_noise {
+            pn.validate()?;
+        }
+        if let Some(pa) = &self.pa {
+            pa.validate()?;
+        }
+        if let Some(adc) = &self.adc {
+            adc.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// Streaming phase noise generator
+///
+/// White Gaussian noise is shaped by a mask-derived FIR filter using
+/// overlap-save FFT convolution.
+#[derive(Debug)]
+struct PhaseNoiseGenerator {
+    /// Filter length
+    len: usize,
+    /// FFT of the impulse response, zero-padded to `2 * len`
+    response: Vec<IQSample>,
+    fft: FftProcessor,
+    /// Last `len` white noise samples
+    history: Vec<f64>,
+    /// Generated phase samples not yet used
+    pending: VecDeque<f64>,
+    rng: StdRng,
+}
+
+impl PhaseNoiseGenerator {
+    fn new(config: &PhaseNoiseConfig, sample_rate: f64, rng: StdRng) -> Self {
+        let n = config.filter_len.max(16).next_power_of_two();
+        let df = sample_rate / n as f64;
+
+        // Two-sided PSD of φ equals L(f); white noise of unit variance has PSD 1/fs
+        let mut spectrum: Vec<IQSample> = (0..n)
+            .map(|k| {
+                let bin = if k <= n / 2 { k } else { n - k };
+                if bin == 0 {
+                    return IQSample::new(0.0, 0.0);
+                }
+                let level = 10.0_f64.powf(config.level_dbc(bin as f64 * df) / 10.0);
+                IQSample::new((level * sample_rate).sqrt(), 0.0)
+            })
+            .collect();
+        FftProcessor::new(n).ifft_inplace(&mut spectrum);
+
+        // Zero-phase response, rotated to a causal impulse response
+        let mut response: Vec<IQSample> = (0..2 * n)
+            .map(|i| if i < n { IQSample::new(spectrum[(i + n / 2) % n].re, 0.0) } else { IQSample::new(0.0, 0.0) })
+            .collect();
+        let mut fft = FftProcessor::new(2 * n);
+        fft.fft_inplace(&mut response);
+
+        Self {
+            len: n,
+            response,
+            fft,
+            history: vec![0.0; n],
+            pending: VecDeque::new(),
+            rng,
+        }
+    }
+
+    /// Filter the next `len` white noise samples
+    fn refill(&mut self) {
+        let n = self.len;
+        let fresh: Vec<f64> = (0..n).map(|_| StandardNormal.sample(&mut self.rng)).collect();
+        let mut segment: Vec<IQSample> = self
+            .history
+            .iter()
+            .chain(&fresh)
+            .map(|&w| IQSample::new(w, 0.0))
+            .collect();
+        self.fft.fft_inplace(&mut segment);
+        for (s, h) in segment.iter_mut().zip(&self.response) {
+            *s *= h;
+        }
+        self.fft.ifft_inplace(&mut segment);
+        self.pending.extend(segment[n..].iter().map(|s| s.re));
+        self.history = fresh;
+    }
+
+    fn generate(&mut self, len: usize) -> Vec<f64> {
+        while self.pending.len() < len {
+            self.refill();
+        }
+        self.pending.drain(..len).collect()
+    }
+}
+
+/// Front-end impairment pipeline with its running state
+#[derive(Debug)]
+pub struct Impairments {
+    config: ImpairmentConfig,
+    phase_noise: Option<PhaseNoiseGenerator>,
+    /// Past PA inputs for the memory polynomial (most recent last)
+    pa_memory: VecDeque<IQSample>,
+    clipped: u64,
+    quantized: u64,
+}
+
+impl Impairments {
+    /// Create a pipeline seeded from entropy
+    ///
+    /// # Panics
+    ///
+    /// If [`ImpairmentConfig::validate`] rejects `config`.
+    pub fn new(config: ImpairmentConfig, sample_rate: f64) -> Self {
+        Self::with_rng(config, sample_rate, StdRng::from_entropy())
+    }
+
+    /// Create a reproducible pipeline
+    ///
+    /// # Panics
+    ///
+    /// If [`ImpairmentConfig::validate`] rejects `config`.
+    pub fn with_seed(config: ImpairmentConfig, sample_rate: f64, seed: u64) -> Self {
+        Self::with_rng(config, sample_rate, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(config: ImpairmentConfig, sample_rate: f64, rng: StdRng) -> Self {
+        if let Err(e) = config.validate() {
+            panic!("Invalid impairment configuration: {}", e);
+        }
+        let phase_noise = config
+            .phase_noise
+            .as_ref()
+            .map(|pn| PhaseNoiseGenerator::new(pn, sample_rate, rng));
+        let memory = match &config.pa {
+            Some(PaConfig { model: PaModel::MemoryPolynomial { coefficients }, .. }) => {
+                coefficients.iter().map(Vec::len).max().unwrap_or(1)
+            }
+            _ => 1,
+        };
+        Self {
+            config,
+            phase_noise,
+            pa_memory: std::iter::repeat_n(IQSample::new(0.0, 0.0), memory).collect(),
+            clipped: 0,
+            quantized: 0,
+        }
+    }
+
+    /// Configuration
+    pub fn config(&self) -> &ImpairmentConfig {
+        &self.config
+    }
+
+    /// Fraction of ADC samples (I or Q) that were clipped so far
+    pub fn clip_rate(&self) -> f64 {
+        if self.quantized == 0 {
+            0.0
+        } else {
+            self.clipped as f64 / self.quantized as f64
+        }
+    }
+
+    /// Transmit-side impairments (PA)
+    pub fn apply_tx(&mut self, samples: &[IQSample]) -> Vec<IQSample> {
+        let Some(pa) = self.config.pa.clone() else {
+            return samples.to_vec();
+        };
+        let drive = 10.0_f64.powf(-pa.input_backoff_db / 20.0);
+        let norm = pa.model.small_signal_gain() * drive;
+
+        samples
+            .iter()
+            .map(|&s| {
+                let x = s * drive;
+                let y = match &pa.model {
+                    PaModel::MemoryPolynomial { coefficients } => {
+                        self.pa_memory.pop_front();
+                        self.pa_memory.push_back(x);
+                        let mut y = IQSample::new(0.0, 0.0);
+                        for (k, taps) in coefficients.iter().enumerate() {
+                            for (m, c) in taps.iter().enumerate() {
+                                let past = self.pa_memory[self.pa_memory.len() - 1 - m];
+                                y += IQSample::new(c[0], c[1]) * past * past.norm().powi(k as i32);
+                            }
+                        }
+                        y
+                    }
+                    model => {
+                        let (amplitude, phase) = model.memoryless(x.norm());
+                        IQSample::from_polar(amplitude, x.arg() + phase)
+                    }
+                };
+                y / norm
+            })
+            .collect()
+    }
+
+    /// Receive-side impairments (phase noise, IQ imbalance, DC offset, ADC)
+    pub fn apply_rx(&mut self, samples: &[IQSample]) -> Vec<IQSample> {
+        let mut out = samples.to_vec();
+
+        if let Some(generator) = self.phase_noise.as_mut() {
+            let phases = generator.generate(out.len());
+            for (s, phi) in out.iter_mut().zip(phases) {
+                *s *= IQSample::from_polar(1.0, phi);
+            }
+        }
+
+        if let Some(iq) = self.config.iq_imbalance {
+            for s in out.iter_mut() {
+                *s = iq.apply(*s);
+            }
+        }
+
+        if let Some((i, q)) = self.config.dc_offset {
+            for s in out.iter_mut() {
+                *s += IQSample::new(i, q);
+            }
+        }
+
+        if let Some(adc) = self.config.adc {
+            let levels = 2.0_f64.powi(adc.bits as i32);
+            let step = 2.0 * adc.full_scale / levels;
+            let max_code = levels / 2.0 - 1.0;
+            let mut quantize = |v: f64| {
+                let code = (v / step).floor();
+                let clamped = code.clamp(-levels / 2.0, max_code);
+                if clamped != code {
+                    self.clipped += 1;
+                }
+                (clamped + 0.5) * step
+            };
+            for s in out.iter_mut() {
+                *s = IQSample::new(quantize(s.re), quantize(s.im));
+            }
+            self.quantized += 2 * out.len() as u64;
+        }
+
+        out
+    }
+}
+
+/// Signal-to-distortion ratio (dB) of `impaired` against `reference`
+///
+/// The best complex gain is removed first, so a pure gain/phase change
+/// costs nothing. Useful for implementation-loss budgets.
+pub fn signal_to_distortion_db(reference: &[IQSample], impaired: &[IQSample]) -> f64 {
+    let n = reference.len().min(impaired.len());
+    let (reference, impaired) = (&reference[..n], &impaired[..n]);
+    let cross: IQSample = reference.iter().zip(impaired).map(|(r, y)| y * r.conj()).sum();
+    let ref_power: f64 = reference.iter().map(|r| r.norm_sqr()).sum();
+    if ref_power == 0.0 {
+        return f64::NAN;
+    }
+    let gain = cross / ref_power;
+    let error: f64 = reference
+        .iter()
+        .zip(impaired)
+        .map(|(r, y)| (y - r * gain).norm_sqr())
+        .sum();
+    10.0 * (gain.norm_sqr() * ref_power / error).log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn tone(freq: f64, fs: f64, n: usize) -> Vec<IQSample> {
+        (0..n)
+            .map(|i| IQSample::from_polar(1.0, 2.0 * PI * freq * i as f64 / fs))
+            .collect()
+    }
+
+    #[test]
+    fn test_phase_noise_matches_mask() {
+        let config = PhaseNoiseConfig::parse("1e3:-70, 1e4:-80, 1e5:-85, 4e5:-90").unwrap();
+        assert!((config.level_dbc(3162.2776) - -75.0).abs() < 1e-3);
+        assert!((config.level_dbc(2e5) - -87.5).abs() < 1e-9);
+        assert_eq!(config.level_dbc(10.0), -70.0);
+
+        let fs = 1e6;
+        let impairments = ImpairmentConfig {
+            phase_noise: Some(config.clone()),
+            ..Default::default()
+        };
+        let mut imp = Impairments::with_seed(impairments, fs, 3);
+        let rx = imp.apply_rx(&vec![IQSample::new(1.0, 0.0); 200_000]);
+
+        let phases: Vec<f64> = rx[8192..].iter().map(|s| s.arg()).collect();
+        let rms = (phases.iter().map(|p| p * p).sum::<f64>() / phases.len() as f64).sqrt();
+        let expected = config.rms_phase_rad(fs);
+        assert!((rms / expected - 1.0).abs() < 0.15, "rms {} vs {}", rms, expected);
+        // Amplitude is untouched
+        assert!(rx.iter().all(|s| (s.norm() - 1.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_iq_imbalance_image() {
+        let iq = IqImbalance { gain_db: 1.0, phase_deg: 5.0 };
+        let config = ImpairmentConfig { iq_imbalance: Some(iq), ..Default::default() };
+        let mut imp = Impairments::new(config, 1e6);
+
+        let n = 1024;
+        let rx = imp.apply_rx(&tone(64.0 * 1e6 / n as f64, 1e6, n));
+        let mut fft = FftProcessor::new(n);
+        let spectrum = fft.fft(&rx);
+        let wanted = spectrum[64].norm_sqr();
+        let image = spectrum[n - 64].norm_sqr();
+        let measured = 10.0 * (wanted / image).log10();
+        assert!((measured - iq.image_rejection_db()).abs() < 0.01);
+        assert!((iq.image_rejection_db() - 22.83).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_dc_offset_and_adc() {
+        let config = ImpairmentConfig {
+            dc_offset: Some((0.1, -0.05)),
+            adc: Some(AdcConfig { bits: 4, full_scale: 1.0 }),
+            ..Default::default()
+        };
+        let mut imp = Impairments::new(config, 1e6);
+        let rx = imp.apply_rx(&[IQSample::new(0.0, 0.0), IQSample::new(3.0, -3.0)]);
+
+        // 16 levels of 0.125: 0.1 → bin [0.0, 0.125) → 0.0625
+        assert!((rx[0].re - 0.0625).abs() < 1e-12);
+        assert!((rx[0].im - -0.0625).abs() < 1e-12);
+        // Clipped to the outermost codes
+        assert!((rx[1].re - 0.9375).abs() < 1e-12);
+        assert!((rx[1].im - -0.9375).abs() < 1e-12);
+        assert!((imp.clip_rate() - 0.5).abs() < 1e-12);
+
+        // Quantisation SQNR of a full-scale tone approaches 6.02N + 1.76
+        let adc = AdcConfig { bits: 10, full_scale: 1.0 };
+        let mut imp = Impairments::new(ImpairmentConfig { adc: Some(adc), ..Default::default() }, 1e6);
+        let clean: Vec<IQSample> = tone(1234.5, 1e6, 50_000).iter().map(|s| s * 0.999).collect();
+        let sdr = signal_to_distortion_db(&clean, &imp.apply_rx(&clean));
+        assert!((sdr - adc.ideal_sqnr_db()).abs() < 1.5, "SQNR {}", sdr);
+    }
+
+    #[test]
+    fn test_pa_models() {
+        // Rapp: linear well below saturation, compressed at it
+        let rapp = PaModel::rapp(1.0, 2.0);
+        let (small, _) = rapp.memoryless(0.01);
+        assert!((small - 0.01).abs() < 1e-9);
+        let (sat, phase) = rapp.memoryless(1.0);
+        assert!((sat - 0.5_f64.powf(0.25)).abs() < 1e-12);
+        assert_eq!(phase, 0.0);
+
+        // Saleh: AM/PM grows with drive
+        let saleh = PaModel::saleh();
+        assert!(saleh.memoryless(0.1).1 < saleh.memoryless(0.5).1);
+
+        // Distortion falls with back-off
+        let signal: Vec<IQSample> = tone(1e3, 1e6, 4096)
+            .iter()
+            .zip(tone(37e3, 1e6, 4096))
+            .map(|(a, b)| (a + b) / 2.0_f64.sqrt())
+            .collect();
+        let sdr_at = |backoff: f64| {
+            let config = ImpairmentConfig {
+                pa: Some(PaConfig { model: PaModel::saleh(), input_backoff_db: backoff }),
+                ..Default::default()
+            };
+            signal_to_distortion_db(&signal, &Impairments::new(config, 1e6).apply_tx(&signal))
+        };
+        assert!(sdr_at(12.0) > sdr_at(3.0) + 10.0);
+
+        // A linear memory polynomial is a pure (normalised) FIR
+        let config = ImpairmentConfig {
+            pa: Some(PaConfig {
+                model: PaModel::MemoryPolynomial { coefficients: vec![vec![[1.0, 0.0], [0.5, 0.0]]] },
+                input_backoff_db: 0.0,
+            }),
+            ..Default::default()
+        };
+        let out = Impairments::new(config, 1e6).apply_tx(&[IQSample::new(1.5, 0.0), IQSample::new(0.0, 0.0)]);
+        assert!((out[0].re - 1.0).abs() < 1e-12);
+        assert!((out[1].re - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let parse = |json: &str| serde_json::from_str::<ImpairmentConfig>(json).unwrap().validate();
+        assert!(parse(r#"{"phase_noise": {"mask": []}}"#).is_err());
+        assert!(parse(r#"{"phase_noise": {"mask": [[1e5, -110], [1e3, -80]]}}"#).is_err());
+        assert!(parse(r#"{"phase_noise": {"mask": [[0, -80]]}}"#).is_err());
+        assert!(parse(r#"{"phase_noise": {"mask": [[1e3, -80], [1e5, -110]]}}"#).is_ok());
+        assert!(PhaseNoiseConfig::parse("").is_err());
+
+        let pa = |coefficients: Vec<Vec<[f64; 2]>>| {
+            PaConfig { model: PaModel::MemoryPolynomial { coefficients }, input_backoff_db: 0.0 }.validate()
+        };
+        assert!(pa(vec![vec![[0.0, 0.0]], vec![[0.1, 0.0]]]).is_err());
+        assert!(pa(vec![vec![[1.0, 0.0], [-1.0, 0.0]]]).is_err());
+        assert!(pa(vec![vec![[1.0, 0.0]]]).is_ok());
+        let far = PaConfig { model: PaModel::saleh(), input_backoff_db: f64::INFINITY };
+        assert!(far.validate().is_err());
+
+        assert!(AdcConfig { bits: 0, full_scale: 1.0 }.validate().is_err());
+        assert!(AdcConfig { bits: 8, full_scale: 0.0 }.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_serde_roundtrip() {
+        let config = ImpairmentConfig {
+            phase_noise: Some(PhaseNoiseConfig::new(vec![(1e3, -80.0), (1e5, -110.0)])),
+            iq_imbalance: Some(IqImbalance { gain_db: 0.2, phase_deg: 1.0 }),
+            dc_offset: Some((0.01, 0.0)),
+            pa: Some(PaConfig { model: PaModel::rapp(1.0, 3.0), input_backoff_db: 6.0 }),
+            adc: Some(AdcConfig { bits: 12, full_scale: 1.0 }),
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        let back: ImpairmentConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(config, back);
+        assert!(serde_json::from_str::<ImpairmentConfig>("{}").unwrap().is_empty());
+    }
+}
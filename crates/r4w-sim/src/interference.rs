@@ -0,0 +1,752 @@
+//! Interference and Jamming Sources
+//!
+//! Reusable interference generators that are added to the received signal
+//! after propagation, independently of the desired signal's channel:
+//!
+//! - **CW**: a single unmodulated tone.
+//! - **Chirp**: a tone swept linearly between two frequencies, repeating
+//!   every sweep period.
+//! - **Partial-band noise**: Gaussian noise confined to a sub-band.
+//! - **Pulsed**: radar-like pulses (optionally linear-FM) at a fixed PRI.
+//! - **Follower**: listens to the transmitted signal, finds its dominant
+//!   frequency and jams a band around it after a reaction delay. This is
+//!   the classic threat against slow frequency hopping.
+//! - **Co-channel**: another waveform from
+//!   [`WaveformFactory`](r4w_core::waveform::WaveformFactory) carrying
+//!   random data, optionally frequency offset.
+//!
+//! Each interferer has its own jammer-to-signal ratio (JSR), referenced
+//! to the mean power of the transmitted block. For pulsed and follower
+//! jammers the JSR is the power while the jammer is on.
+//!
+//! Interferers live in [`ChannelConfig`](crate::channel::ChannelConfig)
+//! and can also be written as compact specs (see [`InterfererConfig::parse`]):
+//!
+//! ```rust
+//! use r4w_sim::interference::{Interference, InterfererConfig};
+//! use r4w_core::types::IQSample;
+//!
+//! let jammers = vec![
+//!     InterfererConfig::parse("cw:jsr=-3,freq=2e3").unwrap(),
+//!     InterfererConfig::parse("noise:jsr=0,center=-5e3,bw=4e3").unwrap(),
+//! ];
+//! let mut interference = Interference::with_seed(jammers, 48_000.0, 1);
+//!
+//! let signal = vec![IQSample::new(1.0, 0.0); 1024];
+//! let jam = interference.generate(&signal, signal.len());
+//! assert_eq!(jam.len(), signal.len());
+//! ```
+
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use r4w_core::fft_utils::FftProcessor;
+use r4w_core::types::IQSample;
+use r4w_core::waveform::{Waveform, WaveformFactory};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+use serde::{Deserialize, Serialize};
+
+/// Kind of interference and its waveform parameters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InterferenceSource {
+    /// Continuous-wave tone
+    Cw {
+        /// Tone frequency relative to the channel centre (Hz)
+        freq_hz: f64,
+    },
+    /// Linear frequency sweep, repeated every `period_s`
+    Chirp {
+        /// Sweep start frequency (Hz)
+        start_hz: f64,
+        /// Sweep stop frequency (Hz)
+        stop_hz: f64,
+        /// Duration of one sweep (seconds)
+        period_s: f64,
+    },
+    /// Band-limited Gaussian noise
+    PartialBandNoise {
+        /// Centre of the jammed band (Hz)
+        center_hz: f64,
+        /// Width of the jammed band (Hz)
+        bandwidth_hz: f64,
+    },
+    /// Pulsed carrier, optionally with linear FM inside each pulse
+    Pulsed {
+        /// Pulse centre frequency (Hz)
+        freq_hz: f64,
+        /// Pulse width (seconds)
+        pulse_width_s: f64,
+        /// Pulse repetition interval (seconds)
+        pri_s: f64,
+        /// Linear-FM sweep across each pulse (Hz, 0 for a plain carrier)
+        #[serde(default)]
+        chirp_bw_hz: f64,
+    },
+    /// Reactive jammer that follows the dominant signal frequency
+    Follower {
+        /// Bandwidth of the noise the jammer emits (Hz)
+        bandwidth_hz: f64,
+        /// Delay between observing the signal and jamming it (seconds)
+        reaction_time_s: f64,
+        /// Peak-to-average spectrum ratio needed to declare a detection (dB)
+        #[serde(default = "default_detect_db")]
+        detect_db: f64,
+        /// Observation window length in samples
+        #[serde(default = "default_look_len")]
+        look_len: usize,
+    },
+    /// Another waveform carrying random data
+    CoChannel {
+        /// Waveform name as accepted by `WaveformFactory::create`
+        waveform: String,
+        /// Frequency offset of the interferer (Hz)
+        #[serde(default)]
+        freq_offset_hz: f64,
+    },
+}
+
+fn default_detect_db() -> f64 {
+    6.0
+}
+
+fn default_look_len() -> usize {
+    256
+}
+
+impl InterferenceSource {
+    /// Short name used in specs and reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cw { .. } => "cw",
+            Self::Chirp { .. } => "chirp",
+            Self::PartialBandNoise { .. } => "noise",
+            Self::Pulsed { .. } => "pulsed",
+            Self::Follower { .. } => "follower",
+            Self::CoChannel { .. } => "cochannel",
+        }
+    }
+
+    /// Fraction of time the source is transmitting (1.0 for continuous sources)
+    pub fn duty_cycle(&self) -> f64 {
+        match self {
+            Self::Pulsed { pulse_width_s, pri_s, .. } if *pri_s > 0.0 => {
+                (pulse_width_s / pri_s).clamp(0.0, 1.0)
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+/// One interferer: a source and its power relative to the signal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterfererConfig {
+    /// Waveform of the interferer
+    #[serde(flatten)]
+    pub source: InterferenceSource,
+    /// Jammer-to-signal ratio in dB (power while the jammer is on)
+    #[serde(default)]
+    pub jsr_db: f64,
+}
+
+impl InterfererConfig {
+    /// Create an interferer at the given JSR
+    pub fn new(source: InterferenceSource, jsr_db: f64) -> Self {
+        Self { source, jsr_db }
+    }
+
+    /// Parse a spec written as `kind:key=value,...`
+    ///
+    /// | Kind | Keys (defaults) |
+    /// |------|-----------------|
+    /// | `cw` | `freq` (0) |
+    /// | `chirp` | `start`, `stop`, `period` (1e-3) |
+    /// | `noise` | `bw`, `center` (0) |
+    /// | `pulsed` | `width`, `pri`, `freq` (0), `chirp` (0) |
+    /// | `follower` | `bw`, `reaction` (1e-3), `detect` (6), `look` (256) |
+    /// | `cochannel` | `waveform`, `offset` (0) |
+    ///
+    /// Every kind also accepts `jsr` in dB (default 0).
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (kind, params) = spec.split_once(':').unwrap_or((spec, ""));
+        let mut values: Vec<(&str, &str)> = Vec::new();
+        for pair in params.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected key=value, got '{}'", pair))?;
+            values.push((key.trim(), value.trim()));
+        }
+
+        let lookup = |key: &str| values.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+        let number = |key: &str, default: Option<f64>| -> Result<f64, String> {
+            match lookup(key) {
+                Some(v) => v
+                    .parse()
+                    .map_err(|_| format!("Invalid value for '{}': '{}'", key, v)),
+                None => default.ok_or_else(|| format!("'{}' jammer requires '{}'", kind, key)),
+            }
+        };
+
+        let (source, allowed): (InterferenceSource, &[&str]) = match kind.trim().to_lowercase().as_str() {
+            "cw" | "tone" => (
+                InterferenceSource::Cw {
+                    freq_hz: number("freq", Some(0.0))?,
+                },
+                &["freq"],
+            ),
+            "chirp" | "sweep" => (
+                InterferenceSource::Chirp {
+                    start_hz: number("start", None)?,
+                    stop_hz: number("stop", None)?,
+                    period_s: number("period", Some(1e-3))?,
+                },
+                &["start", "stop", "period"],
+            ),
+            "noise" | "pbn" | "partial-band" => (
+                InterferenceSource::PartialBandNoise {
+                    center_hz: number("center", Some(0.0))?,
+                    bandwidth_hz: number("bw", None)?,
+                },
+                &["center", "bw"],
+            ),
+            "pulsed" | "pulse" | "radar" => (
+                InterferenceSource::Pulsed {
+                    freq_hz: number("freq", Some(0.0))?,
+                    pulse_width_s: number("width", None)?,
+                    pri_s: number("pri", None)?,
+                    chirp_bw_hz: number("chirp", Some(0.0))?,
+                },
+                &["freq", "width", "pri", "chirp"],
+            ),
+            "follower" => (
+                InterferenceSource::Follower {
+                    bandwidth_hz: number("bw", None)?,
+                    reaction_time_s: number("reaction", Some(1e-3))?,
+                    detect_db: number("detect", Some(default_detect_db()))?,
+                    look_len: number("look", Some(default_look_len() as f64))? as usize,
+                },
+                &["bw", "reaction", "detect", "look"],
+            ),
+            "cochannel" | "co-channel" => (
+                InterferenceSource::CoChannel {
+                    waveform: lookup("waveform")
+                        .ok_or_else(|| "'cochannel' jammer requires 'waveform'".to_string())?
+                        .to_string(),
+                    freq_offset_hz: number("offset", Some(0.0))?,
+                },
+                &["waveform", "offset"],
+            ),
+            other => return Err(format!("Unknown jammer type '{}'", other)),
+        };
+
+        if let Some((key, _)) = values.iter().find(|(k, _)| *k != "jsr" && !allowed.contains(k)) {
+            return Err(format!("Unknown parameter '{}' for '{}' jammer", key, source.name()));
+        }
+
+        let config = Self::new(source, number("jsr", Some(0.0))?);
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that the parameters describe a realisable interferer
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.source {
+            InterferenceSource::Chirp { period_s, .. } if *period_s <= 0.0 => {
+                Err("Chirp period must be positive".to_string())
+            }
+            InterferenceSource::PartialBandNoise { bandwidth_hz, .. }
+            | InterferenceSource::Follower { bandwidth_hz, .. }
+                if *bandwidth_hz <= 0.0 =>
+            {
+                Err("Jammer bandwidth must be positive".to_string())
+            }
+            InterferenceSource::Pulsed { pulse_width_s, pri_s, .. }
+                if *pulse_width_s <= 0.0 || *pri_s < *pulse_width_s =>
+            {
+                Err("Pulse width must be positive and no longer than the PRI".to_string())
+            }
+            InterferenceSource::Follower { reaction_time_s, look_len, .. }
+                if *reaction_time_s < 0.0 || *look_len < 8 =>
+            {
+                Err("Follower needs a non-negative reaction time and a look window of at least 8 samples".to_string())
+            }
+            InterferenceSource::CoChannel { waveform, .. } if WaveformFactory::create(waveform, 48_000.0).is_none() => {
+                Err(format!("Unknown co-channel waveform '{}'", waveform))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Windowed-sinc low-pass taps for a complex band of the given width,
+/// normalised so unit-variance white noise comes out at unit power
+fn noise_shaping_taps(bandwidth_hz: f64, sample_rate: f64) -> Vec<f64> {
+    let cutoff = (bandwidth_hz / 2.0 / sample_rate).min(0.5);
+    if cutoff >= 0.5 {
+        return vec![1.0];
+    }
+    let len = ((4.0 / cutoff) as usize).clamp(33, 1025) | 1;
+    let mid = (len / 2) as f64;
+    let mut taps: Vec<f64> = (0..len)
+        .map(|i| {
+            let x = i as f64 - mid;
+            let sinc = if x == 0.0 {
+                2.0 * cutoff
+            } else {
+                (2.0 * PI * cutoff * x).sin() / (PI * x)
+            };
+            let window = 0.54 - 0.46 * (2.0 * PI * i as f64 / (len - 1) as f64).cos();
+            sinc * window
+        })
+        .collect();
+    let energy: f64 = taps.iter().map(|t| t * t).sum();
+    let scale = 1.0 / energy.sqrt();
+    taps.iter_mut().for_each(|t| *t *= scale);
+    taps
+}
+
+/// Band-limited complex Gaussian noise at unit power
+#[derive(Debug)]
+struct ShapedNoise {
+    taps: Vec<f64>,
+    history: VecDeque<IQSample>,
+}
+
+impl ShapedNoise {
+    fn new(bandwidth_hz: f64, sample_rate: f64) -> Self {
+        let taps = noise_shaping_taps(bandwidth_hz, sample_rate);
+        let history = VecDeque::from(vec![IQSample::new(0.0, 0.0); taps.len()]);
+        Self { taps, history }
+    }
+
+    fn next(&mut self, rng: &mut StdRng) -> IQSample {
+        let re: f64 = StandardNormal.sample(rng);
+        let im: f64 = StandardNormal.sample(rng);
+        self.history.pop_back();
+        self.history
+            .push_front(IQSample::new(re, im) * std::f64::consts::FRAC_1_SQRT_2);
+        self.history
+            .iter()
+            .zip(&self.taps)
+            .map(|(x, h)| x * *h)
+            .sum()
+    }
+}
+
+/// Running state of one interferer
+#[derive(Debug)]
+enum Generator {
+    Tone {
+        phase: f64,
+        step: f64,
+    },
+    Chirp {
+        phase: f64,
+        start_hz: f64,
+        rate_hz_per_sample: f64,
+        period: usize,
+        n: usize,
+    },
+    Noise {
+        noise: ShapedNoise,
+        phase: f64,
+        step: f64,
+    },
+    Pulsed {
+        freq_hz: f64,
+        chirp_bw_hz: f64,
+        width: usize,
+        pri: usize,
+        n: usize,
+    },
+    Follower {
+        noise: ShapedNoise,
+        fft: FftProcessor,
+        look_len: usize,
+        reaction: usize,
+        detect_db: f64,
+        /// Samples observed but not yet analysed
+        observed: Vec<IQSample>,
+        /// (sample index at which to retune, frequency or None for silence)
+        decisions: VecDeque<(u64, Option<f64>)>,
+        target: Option<f64>,
+        phase: f64,
+        n: u64,
+    },
+    CoChannel {
+        waveform: Option<Box<dyn Waveform>>,
+        buffer: VecDeque<IQSample>,
+        phase: f64,
+        step: f64,
+    },
+}
+
+impl Generator {
+    fn new(source: &InterferenceSource, fs: f64) -> Self {
+        match source {
+            InterferenceSource::Cw { freq_hz } => Self::Tone {
+                phase: 0.0,
+                step: 2.0 * PI * freq_hz / fs,
+            },
+            InterferenceSource::Chirp { start_hz, stop_hz, period_s } => {
+                let period = ((period_s * fs).round() as usize).max(1);
+                Self::Chirp {
+                    phase: 0.0,
+                    start_hz: *start_hz,
+                    rate_hz_per_sample: (stop_hz - start_hz) / period as f64,
+                    period,
+                    n: 0,
+                }
+            }
+            InterferenceSource::PartialBandNoise { center_hz, bandwidth_hz } => Self::Noise {
+                noise: ShapedNoise::new(*bandwidth_hz, fs),
+                phase: 0.0,
+                step: 2.0 * PI * center_hz / fs,
+            },
+            InterferenceSource::Pulsed { freq_hz, pulse_width_s, pri_s, chirp_bw_hz } => {
+                let width = ((pulse_width_s * fs).round() as usize).max(1);
+                Self::Pulsed {
+                    freq_hz: *freq_hz,
+                    chirp_bw_hz: *chirp_bw_hz,
+                    width,
+                    pri: ((pri_s * fs).round() as usize).max(width),
+                    n: 0,
+                }
+            }
+            InterferenceSource::Follower { bandwidth_hz, reaction_time_s, detect_db, look_len } => {
+                Self::Follower {
+                    noise: ShapedNoise::new(*bandwidth_hz, fs),
+                    fft: FftProcessor::new(*look_len),
+                    look_len: *look_len,
+                    reaction: (reaction_time_s * fs).round() as usize,
+                    detect_db: *detect_db,
+                    observed: Vec::with_capacity(*look_len),
+                    decisions: VecDeque::new(),
+                    target: None,
+                    phase: 0.0,
+                    n: 0,
+                }
+            }
+            InterferenceSource::CoChannel { waveform, freq_offset_hz } => {
+                let waveform = WaveformFactory::create(waveform, fs);
+                if waveform.is_none() {
+                    tracing::warn!("Unknown co-channel waveform, interferer will be silent");
+                }
+                Self::CoChannel {
+                    waveform,
+                    buffer: VecDeque::new(),
+                    phase: 0.0,
+                    step: 2.0 * PI * freq_offset_hz / fs,
+                }
+            }
+        }
+    }
+
+    /// Produce `n` unit-power samples; `reference` is what the follower hears
+    fn generate(&mut self, reference: &[IQSample], n: usize, fs: f64, rng: &mut StdRng) -> Vec<IQSample> {
+        let mut out = Vec::with_capacity(n);
+        match self {
+            Self::Tone { phase, step } => {
+                for _ in 0..n {
+                    out.push(IQSample::from_polar(1.0, *phase));
+                    *phase = (*phase + *step).rem_euclid(2.0 * PI);
+                }
+            }
+            Self::Chirp { phase, start_hz, rate_hz_per_sample, period, n: count } => {
+                for _ in 0..n {
+                    out.push(IQSample::from_polar(1.0, *phase));
+                    let freq = *start_hz + *rate_hz_per_sample * *count as f64;
+                    *phase = (*phase + 2.0 * PI * freq / fs).rem_euclid(2.0 * PI);
+                    *count = (*count + 1) % *period;
+                }
+            }
+            Self::Noise { noise, phase, step } => {
+                for _ in 0..n {
+                    out.push(noise.next(rng) * IQSample::from_polar(1.0, *phase));
+                    *phase = (*phase + *step).rem_euclid(2.0 * PI);
+                }
+            }
+            Self::Pulsed { freq_hz, chirp_bw_hz, width, pri, n: count } => {
+                let rate = *chirp_bw_hz / (*width as f64 / fs);
+                for _ in 0..n {
+                    let k = *count % *pri;
+                    if k < *width {
+                        // Phase restarts at each pulse so pulses are identical
+                        let t = k as f64 / fs;
+                        let t_mid = t - *width as f64 / fs / 2.0;
+                        let phase = 2.0 * PI * (*freq_hz * t + 0.5 * rate * t_mid * t_mid);
+                        out.push(IQSample::from_polar(1.0, phase));
+                    } else {
+                        out.push(IQSample::new(0.0, 0.0));
+                    }
+                    *count = count.wrapping_add(1);
+                }
+            }
+            Self::Follower {
+                noise,
+                fft,
+                look_len,
+                reaction,
+                detect_db,
+                observed,
+                decisions,
+                target,
+                phase,
+                n: count,
+            } => {
+                let threshold = 10f64.powf(*detect_db / 10.0);
+                for i in 0..n {
+                    // Listen
+                    observed.push(reference.get(i).copied().unwrap_or_default());
+                    if observed.len() == *look_len {
+                        let mut spectrum = std::mem::take(observed);
+                        fft.fft_inplace(&mut spectrum);
+                        let powers: Vec<f64> = spectrum.iter().map(|s| s.norm_sqr()).collect();
+                        let mean = powers.iter().sum::<f64>() / powers.len() as f64;
+                        let (peak_bin, peak) = powers
+                            .iter()
+                            .enumerate()
+                            .fold((0, 0.0), |best, (k, &p)| if p > best.1 { (k, p) } else { best });
+                        let decision = if mean > 0.0 && peak > threshold * mean {
+                            let k = peak_bin as f64;
+                            let len = *look_len as f64;
+                            let bin = if k >= len / 2.0 { k - len } else { k };
+                            Some(bin * fs / len)
+                        } else {
+                            None
+                        };
+                        decisions.push_back((*count + 1 + *reaction as u64, decision));
+                        *observed = spectrum;
+                        observed.clear();
+                    }
+
+                    // React
+                    while decisions.front().is_some_and(|(at, _)| *at <= *count) {
+                        *target = decisions.pop_front().and_then(|(_, f)| f);
+                    }
+                    let jam = noise.next(rng);
+                    match *target {
+                        Some(freq) => {
+                            out.push(jam * IQSample::from_polar(1.0, *phase));
+                            *phase = (*phase + 2.0 * PI * freq / fs).rem_euclid(2.0 * PI);
+                        }
+                        None => out.push(IQSample::new(0.0, 0.0)),
+                    }
+                    *count += 1;
+                }
+            }
+            Self::CoChannel { waveform, buffer, phase, step } => {
+                let Some(waveform) = waveform else {
+                    return vec![IQSample::new(0.0, 0.0); n];
+                };
+                while buffer.len() < n {
+                    let data: Vec<u8> = (0..64).map(|_| rng.gen()).collect();
+                    let burst = waveform.modulate(&data);
+                    if burst.is_empty() {
+                        return vec![IQSample::new(0.0, 0.0); n];
+                    }
+                    let power = burst.iter().map(|s| s.norm_sqr()).sum::<f64>() / burst.len() as f64;
+                    let scale = if power > 0.0 { 1.0 / power.sqrt() } else { 0.0 };
+                    buffer.extend(burst.into_iter().map(|s| s * scale));
+                }
+                for s in buffer.drain(..n) {
+                    out.push(s * IQSample::from_polar(1.0, *phase));
+                    *phase = (*phase + *step).rem_euclid(2.0 * PI);
+                }
+            }
+        }
+        out
+    }
+}
+
+/// A set of interferers producing a combined interference signal
+#[derive(Debug)]
+pub struct Interference {
+    configs: Vec<InterfererConfig>,
+    generators: Vec<Generator>,
+    sample_rate: f64,
+    rng: StdRng,
+}
+
+impl Interference {
+    /// Create interference generators at the given sample rate
+    pub fn new(configs: Vec<InterfererConfig>, sample_rate: f64) -> Self {
+        Self::with_rng(configs, sample_rate, StdRng::from_entropy())
+    }
+
+    /// Create interference generators with a reproducible random state
+    pub fn with_seed(configs: Vec<InterfererConfig>, sample_rate: f64, seed: u64) -> Self {
+        Self::with_rng(configs, sample_rate, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(configs: Vec<InterfererConfig>, sample_rate: f64, rng: StdRng) -> Self {
+        let generators = configs
+            .iter()
+            .map(|c| Generator::new(&c.source, sample_rate))
+            .collect();
+        Self {
+            configs,
+            generators,
+            sample_rate,
+            rng,
+        }
+    }
+
+    /// The configured interferers
+    pub fn interferers(&self) -> &[InterfererConfig] {
+        &self.configs
+    }
+
+    /// Generate `n` samples of combined interference
+    ///
+    /// `reference` is the transmitted signal: its mean power sets the
+    /// jammer levels and the follower jammer listens to it.
+    pub fn generate(&mut self, reference: &[IQSample], n: usize) -> Vec<IQSample> {
+        let signal_power = if reference.is_empty() {
+            0.0
+        } else {
+            reference.iter().map(|s| s.norm_sqr()).sum::<f64>() / reference.len() as f64
+        };
+
+        let mut out = vec![IQSample::new(0.0, 0.0); n];
+        for (config, generator) in self.configs.iter().zip(self.generators.iter_mut()) {
+            let amplitude = (signal_power * 10f64.powf(config.jsr_db / 10.0)).sqrt();
+            let jam = generator.generate(reference, n, self.sample_rate, &mut self.rng);
+            for (o, j) in out.iter_mut().zip(jam) {
+                *o += j * amplitude;
+            }
+        }
+        out
+    }
+
+    /// Add interference to received samples in place
+    pub fn apply(&mut self, reference: &[IQSample], rx: &mut [IQSample]) {
+        let jam = self.generate(reference, rx.len());
+        for (r, j) in rx.iter_mut().zip(jam) {
+            *r += j;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FS: f64 = 48_000.0;
+
+    fn power(samples: &[IQSample]) -> f64 {
+        samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / samples.len() as f64
+    }
+
+    /// Fraction of power within `[lo, hi]` Hz
+    fn band_fraction(samples: &[IQSample], lo: f64, hi: f64) -> f64 {
+        let n = samples.len();
+        let mut spectrum = samples.to_vec();
+        FftProcessor::new(n).fft_inplace(&mut spectrum);
+        let mut inside = 0.0;
+        let mut total = 0.0;
+        for (k, s) in spectrum.iter().enumerate() {
+            let bin = if k >= n / 2 { k as f64 - n as f64 } else { k as f64 };
+            let f = bin * FS / n as f64;
+            let p = s.norm_sqr();
+            total += p;
+            if f >= lo && f <= hi {
+                inside += p;
+            }
+        }
+        inside / total
+    }
+
+    #[test]
+    fn test_parse_specs() {
+        let cw = InterfererConfig::parse("cw:jsr=-3,freq=2e3").unwrap();
+        assert_eq!(cw.source, InterferenceSource::Cw { freq_hz: 2e3 });
+        assert_eq!(cw.jsr_db, -3.0);
+
+        let pulsed = InterfererConfig::parse("pulsed:width=1e-4,pri=1e-3").unwrap();
+        assert!((pulsed.source.duty_cycle() - 0.1).abs() < 1e-12);
+
+        assert!(InterfererConfig::parse("noise:jsr=0").is_err());
+        assert!(InterfererConfig::parse("cw:bw=10").is_err());
+        assert!(InterfererConfig::parse("laser:jsr=0").is_err());
+        assert!(InterfererConfig::parse("cochannel:waveform=NOPE").is_err());
+        assert!(InterfererConfig::parse("cochannel:waveform=QPSK,offset=1e3").is_ok());
+
+        let json = serde_json::to_string(&cw).unwrap();
+        assert_eq!(serde_json::from_str::<InterfererConfig>(&json).unwrap(), cw);
+    }
+
+    #[test]
+    fn test_jsr_sets_power() {
+        let reference = vec![IQSample::new(0.5, 0.0); 8192];
+        for spec in [
+            "cw:jsr=10,freq=1e3",
+            "chirp:jsr=10,start=-1e4,stop=1e4,period=5e-3",
+            "noise:jsr=10,center=3e3,bw=6e3",
+            "cochannel:jsr=10,waveform=QPSK",
+        ] {
+            let mut interference =
+                Interference::with_seed(vec![InterfererConfig::parse(spec).unwrap()], FS, 3);
+            let jam = interference.generate(&reference, reference.len());
+            let jsr = 10.0 * (power(&jam) / power(&reference)).log10();
+            assert!((jsr - 10.0).abs() < 0.5, "{}: JSR {:.2} dB", spec, jsr);
+        }
+    }
+
+    #[test]
+    fn test_partial_band_noise_is_confined() {
+        let config = InterfererConfig::parse("noise:center=6e3,bw=4e3").unwrap();
+        let mut interference = Interference::with_seed(vec![config], FS, 5);
+        let reference = vec![IQSample::new(1.0, 0.0); 16384];
+        let jam = interference.generate(&reference, reference.len());
+        assert!(band_fraction(&jam, 3.5e3, 8.5e3) > 0.95);
+    }
+
+    #[test]
+    fn test_pulsed_duty_cycle() {
+        let config = InterfererConfig::parse("pulsed:jsr=0,width=1e-3,pri=4e-3").unwrap();
+        let mut interference = Interference::with_seed(vec![config], FS, 0);
+        let reference = vec![IQSample::new(1.0, 0.0); 19200];
+        let jam = interference.generate(&reference, reference.len());
+        let on = jam.iter().filter(|s| s.norm() > 0.5).count() as f64 / jam.len() as f64;
+        assert!((on - 0.25).abs() < 0.01);
+        assert!((power(&jam) - 0.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_follower_tracks_after_reaction() {
+        // Signal hops from -8 kHz to +8 kHz halfway through
+        let hop = 9600;
+        let reference: Vec<IQSample> = (0..2 * hop)
+            .map(|n| {
+                let f = if n < hop { -8e3 } else { 8e3 };
+                IQSample::from_polar(1.0, 2.0 * PI * f * n as f64 / FS)
+            })
+            .collect();
+        let config = InterfererConfig::parse("follower:bw=2e3,reaction=2e-3,look=256").unwrap();
+        let mut interference = Interference::with_seed(vec![config], FS, 9);
+        let jam = interference.generate(&reference, reference.len());
+
+        // Silent until the first look window plus the reaction time
+        let reaction = 256 + 96;
+        assert!(jam[..reaction - 1].iter().all(|s| s.norm() == 0.0));
+
+        // Late in each dwell the jammer sits on the signal
+        assert!(band_fraction(&jam[4096..hop], -9.5e3, -6.5e3) > 0.9);
+        assert!(band_fraction(&jam[hop + 4096..], 6.5e3, 9.5e3) > 0.9);
+    }
+
+    #[test]
+    fn test_follower_ignores_noise_like_signal() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let reference: Vec<IQSample> = (0..8192)
+            .map(|_| IQSample::new(StandardNormal.sample(&mut rng), StandardNormal.sample(&mut rng)))
+            .collect();
+        let config = InterfererConfig::parse("follower:bw=2e3,reaction=0,detect=15").unwrap();
+        let mut interference = Interference::with_seed(vec![config], FS, 2);
+        let jam = interference.generate(&reference, reference.len());
+        assert!(power(&jam) < 0.05 * power(&reference));
+    }
+}
@@ -6,9 +6,22 @@
 //! ## Supported Devices
 //!
 //! - **Simulator**: Pure software simulation for testing and learning
+//!   (also available through the HAL registry as `sim://snr=10,cfo=500,...`)
 //! - **USRP** (via UHD): Ettus Research radios (B200, B210, X310, etc.)
 //! - **SoapySDR**: Generic interface supporting HackRF, RTL-SDR, LimeSDR, etc.
 //! - **File I/O**: SigMF file reading and writing
+//! - **Interference**: reusable jammer and co-channel sources for
+//!   [`channel::Channel`] ([`interference`])
+//! - **Antenna arrays**: phase-coherent multi-channel reception for the
+//!   simulator ([`array::ArrayGeometry`])
+//! - **Virtual RF medium**: many simulated radios sharing one propagation
+//!   environment ([`medium::VirtualRfMedium`])
+//! - **Ground truth**: annotated packet recordings and decode scoring
+//!   against them ([`scoring`])
+//! - **Receive metrics**: Prometheus counters for receive paths
+//!   ([`monitor::StreamMonitor`])
+//! - **Datasets**: seeded synthetic training sets of labeled I/Q examples
+//!   ([`dataset`])
 //!
 //! ## Architecture
 //!
@@ -47,17 +60,33 @@
 //! let samples = sdr.read_samples(1024)?;
 //! ```
 
+pub mod array;
 pub mod channel;
+pub mod dataset;
 pub mod device;
 pub mod doppler;
 pub mod hal;
+pub mod impairments;
+pub mod interference;
+pub mod medium;
+pub mod monitor;
+pub mod ranging;
+pub mod scoring;
 pub mod simulator;
 
 // Re-exports
+pub use array::{ArrayGeometry, ArrayLayout};
 pub use channel::{Channel, ChannelConfig, ChannelModel, TappedDelayLine, TdlProfile, TdlTap, DopplerModelConfig};
+pub use dataset::{DatasetConfig, DatasetFormat, DatasetGenerator, DatasetManifest, Fading};
 pub use device::{SdrConfig, SdrDevice, SdrError, SdrResult};
 pub use doppler::{DopplerGenerator, DopplerModel, JakesDoppler, velocity_to_doppler};
-pub use hal::{ClockControl, ClockSource, DriverRegistry, SampleFormat, SdrDeviceExt, StreamConfig, StreamDirection, StreamHandle, StreamStatus, TunerControl};
+pub use hal::{ClockControl, ClockSource, DriverRegistry, SampleFormat, SdrDeviceExt, StreamConfig, StreamDirection, StreamHandle, StreamStatus, TimedCommand, TimedControl, TunerControl, TxMetadata};
+pub use impairments::{AdcConfig, ImpairmentConfig, Impairments, IqImbalance, PaConfig, PaModel, PhaseNoiseConfig};
+pub use interference::{Interference, InterferenceSource, InterfererConfig};
+pub use medium::{LinkConfig, MediumConfig, RadioConfig, RadioId, VirtualRfMedium};
+pub use monitor::StreamMonitor;
+pub use ranging::{RangingErrorStats, UwbRangingSim};
+pub use scoring::{BurstDecoder, Decode, PacketScenario, PacketTruth, ScoreReport};
 pub use simulator::Simulator;
 
 /// Prelude for convenient imports
@@ -0,0 +1,846 @@
+//! Shared Virtual RF Medium
+//!
+//! Connects many simulated radios through one propagation environment so
+//! multi-radio scenarios (interference, hidden nodes, frequency hopping
+//! nets, mesh) can be exercised at the I/Q level.
+//!
+//! Each radio has a position, velocity, antenna gain, center frequency and
+//! sample rate. Every receiver hears the sum of all active transmitters:
+//!
+//! ```text
+//!   TX j ──► path loss ──► delay τ(t) ──► band-limit + resample ──► Δf shift ──► Channel ──┐
+//!   TX k ──► ...                                                                           ├─► Σ + kTBF ──► RX i
+//!   TX l ──► ...                                                                           ┘
+//! ```
+//!
+//! - **Path loss**: any [`PathLossModel`] (free space by default, medium-wide
+//!   or per link), plus antenna gains and an optional per-link extra loss
+//!   (walls, terrain). Models with shadowing draw one seeded value per radio
+//!   pair.
+//! - **Delay and Doppler**: the propagation delay `τ = d(t)/c` is evaluated
+//!   per output sample from the radios' positions and velocities, so the
+//!   carrier phase `−2π·f·τ(t)` produces the Doppler shift naturally.
+//! - **Frequency translation**: a transmitter at `f_tx` appears at
+//!   `f_tx − f_rx` in the receiver's baseband. Only the part of its spectrum
+//!   inside the receiver passband is kept (windowed-sinc band-pass
+//!   interpolation), which also resamples between sample rates.
+//! - **Fading**: each link may carry any [`ChannelConfig`] fading model
+//!   (Rayleigh, Rician, TDL, Jakes). Link channels only add fading; noise
+//!   comes from each receiver's thermal noise floor.
+//!
+//! ## Units
+//!
+//! Transmitted samples of unit average power are radiated at the radio's
+//! `tx_power_dbm`. Received samples are in √mW, so `|x|²` is the power at
+//! the receiver's antenna port in milliwatts.
+//!
+//! ## Time
+//!
+//! The medium has no notion of wall-clock time: it advances only when
+//! [`VirtualRfMedium::advance_to`] is called, or on each tick when it is
+//! subscribed to a [`TickScheduler`](r4w_core::scheduler::TickScheduler).
+//! All random processes are seeded, so runs are reproducible.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use r4w_core::scheduler::TickScheduler;
+//! use r4w_core::types::IQSample;
+//! use r4w_sim::medium::{MediumConfig, RadioConfig, VirtualRfMedium};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let mut medium = VirtualRfMedium::new(MediumConfig::default());
+//! let tx = medium.add_radio(RadioConfig::new("tx", 915e6, 1e6).with_tx_power(20.0));
+//! let rx = medium.add_radio(RadioConfig::new("rx", 915e6, 1e6).with_position(1000.0, 0.0, 0.0));
+//!
+//! medium.transmit(tx, &vec![IQSample::new(1.0, 0.0); 1000]);
+//!
+//! // 1 ms per tick at 1 MS/s
+//! let medium = Arc::new(Mutex::new(medium));
+//! let mut scheduler = TickScheduler::with_sample_rate(1e6, 1000);
+//! scheduler.subscribe(medium.clone());
+//! scheduler.step(2);
+//!
+//! let samples = medium.lock().unwrap().receive(rx);
+//! assert_eq!(samples.len(), 2000);
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::f64::consts::PI;
+use std::time::Duration;
+
+use r4w_core::propagation::{PathLossModel, SPEED_OF_LIGHT, THERMAL_NOISE_DBM_HZ};
+use r4w_core::scheduler::{ComponentId, TickEvent, TickScheduler, TickSubscriber};
+use r4w_core::types::IQSample;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+
+use crate::channel::{Channel, ChannelConfig};
+use crate::device::{SdrError, SdrResult};
+
+/// Identifier of a radio attached to the medium
+pub type RadioId = usize;
+
+/// Radio attached to the medium
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadioConfig {
+    /// Display name
+    pub name: String,
+    /// Position (meters)
+    pub position: [f64; 3],
+    /// Velocity (m/s)
+    pub velocity: [f64; 3],
+    /// Center frequency (Hz)
+    pub center_freq_hz: f64,
+    /// Sample rate (Hz)
+    pub sample_rate: f64,
+    /// Antenna gain (dBi), used for both TX and RX
+    pub antenna_gain_dbi: f64,
+    /// Transmit power of a unit-power signal (dBm)
+    pub tx_power_dbm: f64,
+    /// Receiver noise figure (dB)
+    pub noise_figure_db: f64,
+}
+
+impl RadioConfig {
+    /// Create a radio at the origin
+    pub fn new(name: &str, center_freq_hz: f64, sample_rate: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            position: [0.0; 3],
+            velocity: [0.0; 3],
+            center_freq_hz,
+            sample_rate,
+            antenna_gain_dbi: 0.0,
+            tx_power_dbm: 0.0,
+            noise_figure_db: 6.0,
+        }
+    }
+
+    /// Set the position (meters)
+    pub fn with_position(mut self, x: f64, y: f64, z: f64) -> Self {
+        self.position = [x, y, z];
+        self
+    }
+
+    /// Set the velocity (m/s)
+    pub fn with_velocity(mut self, vx: f64, vy: f64, vz: f64) -> Self {
+        self.velocity = [vx, vy, vz];
+        self
+    }
+
+    /// Set the antenna gain (dBi)
+    pub fn with_antenna_gain(mut self, gain_dbi: f64) -> Self {
+        self.antenna_gain_dbi = gain_dbi;
+        self
+    }
+
+    /// Set the transmit power (dBm)
+    pub fn with_tx_power(mut self, power_dbm: f64) -> Self {
+        self.tx_power_dbm = power_dbm;
+        self
+    }
+
+    /// Set the receiver noise figure (dB)
+    pub fn with_noise_figure(mut self, nf_db: f64) -> Self {
+        self.noise_figure_db = nf_db;
+        self
+    }
+}
+
+/// Per-link propagation settings (directed, transmitter → receiver)
+#[derive(Debug, Clone)]
+pub struct LinkConfig {
+    /// Whether the receiver hears the transmitter at all
+    pub enabled: bool,
+    /// Loss on top of the path loss model (dB)
+    pub extra_loss_db: f64,
+    /// Path loss model for this link (None = the medium's model)
+    pub path_loss: Option<PathLossModel>,
+    /// Fading model (its AWGN/SNR settings are ignored)
+    pub channel: Option<ChannelConfig>,
+}
+
+impl Default for LinkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            extra_loss_db: 0.0,
+            path_loss: None,
+            channel: None,
+        }
+    }
+}
+
+/// Medium-wide settings
+#[derive(Debug, Clone)]
+pub struct MediumConfig {
+    /// Seed for thermal noise and link fading
+    pub seed: u64,
+    /// Add receiver thermal noise (kTBF)
+    pub thermal_noise: bool,
+    /// Interpolation kernel half-width (zero crossings of the narrower rate)
+    pub kernel_half_width: usize,
+    /// Fading model for links without their own [`LinkConfig`]
+    pub default_channel: Option<ChannelConfig>,
+    /// Path loss model for links without their own
+    pub path_loss: PathLossModel,
+    /// Component ID used when subscribed to a tick scheduler
+    pub component_id: ComponentId,
+}
+
+impl Default for MediumConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            thermal_noise: true,
+            kernel_half_width: 8,
+            default_channel: None,
+            path_loss: PathLossModel::FreeSpace,
+            component_id: 0x5246_4d45_4449_554d,
+        }
+    }
+}
+
+/// Samples radiated by one transmitter
+#[derive(Debug)]
+struct Transmission {
+    /// Start time (seconds)
+    start: f64,
+    sample_rate: f64,
+    center_freq_hz: f64,
+    /// Samples in √mW at the antenna port
+    samples: Vec<IQSample>,
+}
+
+impl Transmission {
+    fn end(&self) -> f64 {
+        self.start + self.samples.len() as f64 / self.sample_rate
+    }
+}
+
+#[derive(Debug)]
+struct Radio {
+    config: RadioConfig,
+    /// Time at which `config.position` was valid
+    position_time: f64,
+    /// End of the last queued transmission
+    tx_cursor: f64,
+    air: VecDeque<Transmission>,
+    /// Index of the next sample to be received
+    rx_next: u64,
+    rx_buffer: Vec<IQSample>,
+    rng: StdRng,
+}
+
+impl Radio {
+    fn position_at(&self, t: f64) -> [f64; 3] {
+        let dt = t - self.position_time;
+        let p = &self.config.position;
+        let v = &self.config.velocity;
+        [p[0] + v[0] * dt, p[1] + v[1] * dt, p[2] + v[2] * dt]
+    }
+}
+
+#[derive(Debug)]
+struct Link {
+    config: LinkConfig,
+    channel: Option<Channel>,
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+/// Normalised sinc
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// SplitMix64 step, used to derive independent seeds
+fn mix_seed(seed: u64, a: u64, b: u64) -> u64 {
+    let mut z = seed ^ a.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ b.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Shared propagation medium for many simulated radios
+pub struct VirtualRfMedium {
+    config: MediumConfig,
+    radios: Vec<Radio>,
+    links: HashMap<(RadioId, RadioId), Link>,
+    /// Current medium time (seconds)
+    now: f64,
+    /// Duration of one scheduler tick (set on registration)
+    tick_duration: Duration,
+}
+
+impl VirtualRfMedium {
+    /// Create an empty medium
+    pub fn new(config: MediumConfig) -> Self {
+        Self {
+            config,
+            radios: Vec::new(),
+            links: HashMap::new(),
+            now: 0.0,
+            tick_duration: Duration::from_millis(1),
+        }
+    }
+
+    /// Attach a radio; it starts receiving at the current time
+    pub fn add_radio(&mut self, config: RadioConfig) -> RadioId {
+        let id = self.radios.len();
+        let rx_next = (self.now * config.sample_rate).ceil() as u64;
+        self.radios.push(Radio {
+            config,
+            position_time: self.now,
+            tx_cursor: self.now,
+            air: VecDeque::new(),
+            rx_next,
+            rx_buffer: Vec::new(),
+            rng: StdRng::seed_from_u64(mix_seed(self.config.seed, id as u64, u64::MAX)),
+        });
+        id
+    }
+
+    /// Number of attached radios
+    pub fn num_radios(&self) -> usize {
+        self.radios.len()
+    }
+
+    /// Configuration of a radio (position as of its last update)
+    pub fn radio(&self, id: RadioId) -> &RadioConfig {
+        &self.radios[id].config
+    }
+
+    /// Current medium time (seconds)
+    pub fn now(&self) -> f64 {
+        self.now
+    }
+
+    /// Position of a radio at the current time
+    pub fn position(&self, id: RadioId) -> [f64; 3] {
+        self.radios[id].position_at(self.now)
+    }
+
+    /// Move a radio (takes effect at the current time)
+    pub fn set_position(&mut self, id: RadioId, position: [f64; 3]) {
+        let radio = &mut self.radios[id];
+        radio.config.position = position;
+        radio.position_time = self.now;
+    }
+
+    /// Change a radio's velocity (takes effect at the current time)
+    pub fn set_velocity(&mut self, id: RadioId, velocity: [f64; 3]) {
+        let now = self.now;
+        let radio = &mut self.radios[id];
+        radio.config.position = radio.position_at(now);
+        radio.position_time = now;
+        radio.config.velocity = velocity;
+    }
+
+    /// Retune a radio; affects later transmissions and all reception from now on
+    pub fn set_center_freq(&mut self, id: RadioId, freq_hz: f64) {
+        self.radios[id].config.center_freq_hz = freq_hz;
+    }
+
+    /// Change a radio's sample rate (reception restarts at the current time)
+    pub fn set_sample_rate(&mut self, id: RadioId, sample_rate: f64) {
+        let now = self.now;
+        let radio = &mut self.radios[id];
+        radio.config.sample_rate = sample_rate;
+        radio.rx_next = (now * sample_rate).ceil() as u64;
+    }
+
+    /// Configure the directed link from `tx` to `rx`
+    pub fn configure_link(&mut self, tx: RadioId, rx: RadioId, config: LinkConfig) {
+        self.links.insert((tx, rx), Link { config, channel: None });
+    }
+
+    /// Distance between two radios at the current time (meters)
+    pub fn distance(&self, a: RadioId, b: RadioId) -> f64 {
+        distance(self.position(a), self.position(b))
+    }
+
+    /// Path gain from `tx` to `rx` at the current time (dB, including antennas)
+    pub fn path_gain_db(&self, tx: RadioId, rx: RadioId) -> f64 {
+        let (t, r) = (&self.radios[tx].config, &self.radios[rx].config);
+        let link = self.links.get(&(tx, rx)).map(|l| &l.config);
+        t.antenna_gain_dbi + r.antenna_gain_dbi
+            - self.link_loss_db(tx, rx, link, self.distance(tx, rx), t.center_freq_hz)
+    }
+
+    /// Path loss, shadowing and extra loss of a link (dB)
+    fn link_loss_db(&self, tx: RadioId, rx: RadioId, link: Option<&LinkConfig>, distance_m: f64, freq_hz: f64) -> f64 {
+        let model = link
+            .and_then(|l| l.path_loss.as_ref())
+            .unwrap_or(&self.config.path_loss);
+        let sigma = model.shadowing_sigma_db();
+        let shadowing = if sigma > 0.0 {
+            // Same value in both directions, fixed for the life of the medium
+            let (a, b) = (tx.min(rx) as u64, tx.max(rx) as u64);
+            let mut rng = StdRng::seed_from_u64(mix_seed(!self.config.seed, a, b));
+            Normal::new(0.0, sigma).unwrap().sample(&mut rng)
+        } else {
+            0.0
+        };
+        model.path_loss_db(distance_m, freq_hz) + shadowing + link.map_or(0.0, |l| l.extra_loss_db)
+    }
+
+    /// Thermal noise power of a receiver (dBm)
+    pub fn noise_floor_dbm(&self, id: RadioId) -> f64 {
+        let r = &self.radios[id].config;
+        THERMAL_NOISE_DBM_HZ + 10.0 * r.sample_rate.log10() + r.noise_figure_db
+    }
+
+    /// Queue samples right after the radio's previous transmission (or now)
+    ///
+    /// Returns the start time of the transmission in seconds.
+    pub fn transmit(&mut self, id: RadioId, samples: &[IQSample]) -> f64 {
+        let start = self.radios[id].tx_cursor.max(self.now);
+        self.queue(id, start, samples);
+        start
+    }
+
+    /// Queue samples to start at `time` (seconds)
+    pub fn transmit_at(&mut self, id: RadioId, time: f64, samples: &[IQSample]) -> SdrResult<()> {
+        if time < self.now {
+            return Err(SdrError::ConfigError(format!(
+                "Transmission at {:.9} s is in the past (now {:.9} s)",
+                time, self.now
+            )));
+        }
+        self.queue(id, time, samples);
+        Ok(())
+    }
+
+    fn queue(&mut self, id: RadioId, start: f64, samples: &[IQSample]) {
+        let radio = &mut self.radios[id];
+        let scale = 10.0_f64.powf(radio.config.tx_power_dbm / 20.0);
+        let tx = Transmission {
+            start,
+            sample_rate: radio.config.sample_rate,
+            center_freq_hz: radio.config.center_freq_hz,
+            samples: samples.iter().map(|s| s * scale).collect(),
+        };
+        radio.tx_cursor = radio.tx_cursor.max(tx.end());
+        let pos = radio.air.partition_point(|t| t.start <= start);
+        radio.air.insert(pos, tx);
+    }
+
+    /// Samples received by a radio since the previous call
+    pub fn receive(&mut self, id: RadioId) -> Vec<IQSample> {
+        std::mem::take(&mut self.radios[id].rx_buffer)
+    }
+
+    /// Number of samples waiting in a radio's receive buffer
+    pub fn available(&self, id: RadioId) -> usize {
+        self.radios[id].rx_buffer.len()
+    }
+
+    /// Index of the next sample a radio will receive (at its sample rate)
+    pub fn rx_sample_index(&self, id: RadioId) -> u64 {
+        self.radios[id].rx_next - self.radios[id].rx_buffer.len() as u64
+    }
+
+    /// Advance the medium by `dt` seconds
+    pub fn advance(&mut self, dt: f64) {
+        self.advance_to(self.now + dt);
+    }
+
+    /// Advance the medium to time `t` (seconds), rendering every receiver
+    pub fn advance_to(&mut self, t: f64) {
+        if t <= self.now {
+            return;
+        }
+        for rx in 0..self.radios.len() {
+            self.render(rx, t);
+        }
+        self.now = t;
+        self.prune();
+    }
+
+    /// Render receiver `rx` up to (not including) time `t_end`
+    fn render(&mut self, rx: RadioId, t_end: f64) {
+        let fs_r = self.radios[rx].config.sample_rate;
+        let first = self.radios[rx].rx_next;
+        let end = (t_end * fs_r - 1e-6).ceil().max(first as f64) as u64;
+        let n = (end - first) as usize;
+        if n == 0 {
+            return;
+        }
+
+        let mut output = vec![IQSample::new(0.0, 0.0); n];
+        for tx in 0..self.radios.len() {
+            if tx == rx {
+                continue;
+            }
+            if let Some(contribution) = self.link_contribution(tx, rx, first, n) {
+                for (o, c) in output.iter_mut().zip(&contribution) {
+                    *o += c;
+                }
+            }
+        }
+
+        let radio = &mut self.radios[rx];
+        if self.config.thermal_noise {
+            let noise_dbm = THERMAL_NOISE_DBM_HZ + 10.0 * fs_r.log10() + radio.config.noise_figure_db;
+            let sigma = (10.0_f64.powf(noise_dbm / 10.0) / 2.0).sqrt();
+            let normal = Normal::new(0.0, sigma).unwrap();
+            for o in output.iter_mut() {
+                *o += IQSample::new(normal.sample(&mut radio.rng), normal.sample(&mut radio.rng));
+            }
+        }
+        radio.rx_buffer.extend(output);
+        radio.rx_next = end;
+    }
+
+    /// Signal from `tx` at receiver `rx` for samples `[first, first + n)`
+    fn link_contribution(&mut self, tx: RadioId, rx: RadioId, first: u64, n: usize) -> Option<Vec<IQSample>> {
+        let link_config = self
+            .links
+            .get(&(tx, rx))
+            .map(|l| l.config.clone())
+            .unwrap_or_else(|| LinkConfig {
+                channel: self.config.default_channel.clone(),
+                ..Default::default()
+            });
+        if !link_config.enabled {
+            return None;
+        }
+
+        let t_radio = &self.radios[tx];
+        let r_radio = &self.radios[rx];
+        let fs_r = r_radio.config.sample_rate;
+        let f_r = r_radio.config.center_freq_hz;
+        let t_first = first as f64 / fs_r;
+        let t_last = (first + n as u64 - 1) as f64 / fs_r;
+
+        let delay_at = |t: f64| distance(t_radio.position_at(t), r_radio.position_at(t)) / SPEED_OF_LIGHT;
+        let (d0, d1) = (delay_at(t_first), delay_at(t_last));
+        let (delay_min, delay_max) = (d0.min(d1), d0.max(d1));
+
+        let mut signal = vec![IQSample::new(0.0, 0.0); n];
+        let mut active = false;
+
+        for burst in &t_radio.air {
+            let fs_t = burst.sample_rate;
+            let df = burst.center_freq_hz - f_r;
+
+            // Part of the transmitted spectrum inside the receiver passband
+            let band_lo = (-fs_t / 2.0).max(-fs_r / 2.0 - df);
+            let band_hi = (fs_t / 2.0).min(fs_r / 2.0 - df);
+            if band_hi <= band_lo {
+                continue;
+            }
+            let width = band_hi - band_lo;
+            let band_center = (band_lo + band_hi) / 2.0;
+            let half_window = self.config.kernel_half_width as f64 / fs_t.min(fs_r);
+
+            // Skip bursts that cannot reach this block
+            let duration = burst.samples.len() as f64 / fs_t;
+            let u_max = t_last - delay_min - burst.start;
+            let u_min = t_first - delay_max - burst.start;
+            if u_max < -half_window || u_min > duration + half_window {
+                continue;
+            }
+            active = true;
+
+            // Path loss at the center of the block
+            let gain_db = t_radio.config.antenna_gain_dbi + r_radio.config.antenna_gain_dbi
+                - self.link_loss_db(
+                    tx,
+                    rx,
+                    Some(&link_config),
+                    (d0 + d1) / 2.0 * SPEED_OF_LIGHT,
+                    burst.center_freq_hz,
+                );
+            let amplitude = 10.0_f64.powf(gain_db / 20.0);
+            let len = burst.samples.len() as i64;
+
+            for (k, out) in signal.iter_mut().enumerate() {
+                let t = (first + k as u64) as f64 / fs_r;
+                let tau = delay_at(t);
+                let u = t - tau - burst.start;
+                if u < -half_window || u > duration + half_window {
+                    continue;
+                }
+
+                let n_lo = (((u - half_window) * fs_t).ceil() as i64).max(0);
+                let n_hi = (((u + half_window) * fs_t).floor() as i64).min(len - 1);
+                let mut acc = IQSample::new(0.0, 0.0);
+                for i in n_lo..=n_hi {
+                    let x = u - i as f64 / fs_t;
+                    let window = 0.5 * (1.0 + (PI * x / half_window).cos());
+                    let phase = 2.0 * PI * band_center * x;
+                    let h = width / fs_t * sinc(width * x) * window;
+                    acc += burst.samples[i as usize] * IQSample::new(h * phase.cos(), h * phase.sin());
+                }
+
+                // Frequency translation and propagation phase (Doppler)
+                let phase = 2.0 * PI * ((df * t) % 1.0 - (burst.center_freq_hz * tau) % 1.0);
+                *out += acc * IQSample::new(phase.cos(), phase.sin()) * amplitude;
+            }
+        }
+
+        if !active {
+            return None;
+        }
+
+        let seed = mix_seed(self.config.seed, tx as u64, rx as u64);
+        let link = self.links.entry((tx, rx)).or_insert_with(|| Link { config: link_config, channel: None });
+        if let Some(channel_config) = &link.config.channel {
+            let stale = link
+                .channel
+                .as_ref()
+                .is_none_or(|c| c.config().sample_rate != fs_r);
+            if stale {
+                let config = ChannelConfig {
+                    snr_db: f64::INFINITY,
+                    sample_rate: fs_r,
+                    ..channel_config.clone()
+                };
+                link.channel = Some(Channel::with_seed(config, seed));
+            }
+            if let Some(channel) = link.channel.as_mut() {
+                signal = channel.apply(&signal);
+            }
+        }
+
+        Some(signal)
+    }
+
+    /// Drop transmissions that can no longer reach any receiver
+    fn prune(&mut self) {
+        let mut max_distance: f64 = 0.0;
+        for a in 0..self.radios.len() {
+            for b in a + 1..self.radios.len() {
+                max_distance = max_distance.max(self.distance(a, b));
+            }
+        }
+        let min_rate = self
+            .radios
+            .iter()
+            .map(|r| r.config.sample_rate)
+            .fold(f64::INFINITY, f64::min);
+        let retention = 2.0 * max_distance / SPEED_OF_LIGHT
+            + 2.0 * self.config.kernel_half_width as f64 / min_rate;
+
+        let horizon = self.now - retention;
+        for radio in &mut self.radios {
+            radio.air.retain(|t| t.end() >= horizon);
+        }
+    }
+}
+
+impl TickSubscriber for VirtualRfMedium {
+    fn on_tick(&mut self, event: TickEvent) {
+        self.advance(event.elapsed as f64 * self.tick_duration.as_secs_f64());
+    }
+
+    fn id(&self) -> ComponentId {
+        self.config.component_id
+    }
+
+    fn on_register(&mut self, scheduler: &TickScheduler) {
+        self.tick_duration = scheduler.resolution().tick_duration();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::ChannelModel;
+    use std::sync::{Arc, Mutex};
+
+    fn quiet() -> MediumConfig {
+        MediumConfig {
+            thermal_noise: false,
+            ..Default::default()
+        }
+    }
+
+    fn power_db(samples: &[IQSample]) -> f64 {
+        10.0 * (samples.iter().map(|s| s.norm_sqr()).sum::<f64>() / samples.len() as f64).log10()
+    }
+
+    fn tone(freq: f64, fs: f64, n: usize) -> Vec<IQSample> {
+        (0..n)
+            .map(|i| {
+                let p = 2.0 * PI * freq * i as f64 / fs;
+                IQSample::new(p.cos(), p.sin())
+            })
+            .collect()
+    }
+
+    /// Mean phase step between consecutive samples, as a frequency (Hz)
+    fn frequency(samples: &[IQSample], fs: f64) -> f64 {
+        let sum: IQSample = samples.windows(2).map(|w| w[1] * w[0].conj()).sum();
+        sum.arg() * fs / (2.0 * PI)
+    }
+
+    #[test]
+    fn test_path_loss_and_delay() {
+        let mut medium = VirtualRfMedium::new(quiet());
+        let tx = medium.add_radio(RadioConfig::new("tx", 1e9, 1e6).with_tx_power(30.0).with_antenna_gain(3.0));
+        // 3 km: 10 µs = 10 samples of delay
+        let rx = medium.add_radio(
+            RadioConfig::new("rx", 1e9, 1e6)
+                .with_position(2997.92458, 0.0, 0.0)
+                .with_antenna_gain(3.0),
+        );
+
+        medium.transmit(tx, &vec![IQSample::new(1.0, 0.0); 500]);
+        medium.advance(1e-3);
+        let samples = medium.receive(rx);
+        assert_eq!(samples.len(), 1000);
+
+        assert!(samples[5].norm() < 1e-3 * samples[100].norm());
+        assert!((samples[10].norm() - samples[100].norm()).abs() < 0.1 * samples[100].norm());
+
+        let expected = 30.0 + medium.path_gain_db(tx, rx);
+        assert!((power_db(&samples[50..400]) - expected).abs() < 0.1);
+        // FSPL at 1 GHz, 3 km ≈ 102 dB
+        assert!((medium.path_gain_db(tx, rx) - (6.0 - 101.98)).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_path_loss_models() {
+        let mut config = quiet();
+        config.path_loss = PathLossModel::parse("log-distance:n=3.5,d0=10,sigma=6").unwrap();
+        let mut medium = VirtualRfMedium::new(config);
+        let a = medium.add_radio(RadioConfig::new("a", 915e6, 1e6).with_tx_power(20.0));
+        let b = medium.add_radio(RadioConfig::new("b", 915e6, 1e6).with_position(500.0, 0.0, 0.0));
+        let c = medium.add_radio(RadioConfig::new("c", 915e6, 1e6).with_position(0.0, 500.0, 0.0));
+
+        // Shadowing is reciprocal but differs between radio pairs
+        assert!((medium.path_gain_db(a, b) - medium.path_gain_db(b, a)).abs() < 1e-9);
+        assert!((medium.path_gain_db(a, b) - medium.path_gain_db(a, c)).abs() > 1e-6);
+
+        // A per-link model overrides the medium's
+        medium.configure_link(
+            a,
+            b,
+            LinkConfig { path_loss: Some(PathLossModel::FreeSpace), ..Default::default() },
+        );
+        let fspl = r4w_core::propagation::free_space_loss_db(500.0, 915e6);
+        assert!((medium.path_gain_db(a, b) + fspl).abs() < 1e-9);
+
+        medium.transmit(a, &vec![IQSample::new(1.0, 0.0); 500]);
+        medium.advance(1e-3);
+        let samples = medium.receive(b);
+        assert!((power_db(&samples[50..400]) - (20.0 - fspl)).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_frequency_offset_and_resampling() {
+        let mut medium = VirtualRfMedium::new(quiet());
+        // Narrowband transmitter 200 kHz above a wideband receiver
+        let tx = medium.add_radio(RadioConfig::new("tx", 915.2e6, 100e3));
+        let wide = medium.add_radio(RadioConfig::new("wide", 915e6, 1e6).with_position(10.0, 0.0, 0.0));
+        // Narrowband receiver that does not cover the transmitter
+        let narrow = medium.add_radio(RadioConfig::new("narrow", 915e6, 250e3).with_position(10.0, 0.0, 0.0));
+
+        medium.transmit(tx, &tone(10e3, 100e3, 400));
+        medium.advance(4e-3);
+
+        let wide_rx = medium.receive(wide);
+        assert_eq!(wide_rx.len(), 4000);
+        let f = frequency(&wide_rx[500..3500], 1e6);
+        assert!((f - 210e3).abs() < 100.0, "tone at {} Hz", f);
+        let expected = medium.path_gain_db(tx, wide);
+        assert!((power_db(&wide_rx[500..3500]) - expected).abs() < 0.2);
+
+        let narrow_rx = medium.receive(narrow);
+        assert_eq!(narrow_rx.len(), 1000);
+        assert!(narrow_rx.iter().all(|s| s.norm() == 0.0));
+    }
+
+    #[test]
+    fn test_doppler_from_motion() {
+        let mut medium = VirtualRfMedium::new(quiet());
+        let tx = medium.add_radio(RadioConfig::new("tx", 2.4e9, 100e3));
+        // Closing at 150 m/s
+        let rx = medium.add_radio(
+            RadioConfig::new("rx", 2.4e9, 100e3)
+                .with_position(5000.0, 0.0, 0.0)
+                .with_velocity(-150.0, 0.0, 0.0),
+        );
+        medium.transmit(tx, &vec![IQSample::new(1.0, 0.0); 20_000]);
+        medium.advance(0.2);
+        let samples = medium.receive(rx);
+
+        let expected = crate::doppler::velocity_to_doppler(150.0, 2.4e9);
+        let f = frequency(&samples[1000..19_000], 100e3);
+        assert!((f - expected).abs() < 1.0, "doppler {} Hz, expected {}", f, expected);
+        assert!(medium.distance(tx, rx) < 5000.0 - 29.0);
+    }
+
+    #[test]
+    fn test_hidden_node_collision() {
+        let mut medium = VirtualRfMedium::new(quiet());
+        let a = medium.add_radio(RadioConfig::new("a", 868e6, 125e3));
+        let b = medium.add_radio(RadioConfig::new("b", 868e6, 125e3).with_position(100.0, 0.0, 0.0));
+        let c = medium.add_radio(RadioConfig::new("c", 868e6, 125e3).with_position(200.0, 0.0, 0.0));
+        // A hill between A and C
+        medium.configure_link(a, c, LinkConfig { enabled: false, ..Default::default() });
+        medium.configure_link(c, a, LinkConfig { enabled: false, ..Default::default() });
+
+        medium.transmit(a, &tone(5e3, 125e3, 1000));
+        medium.transmit(c, &tone(-20e3, 125e3, 1000));
+        medium.advance(1000.0 / 125e3);
+
+        // A and C cannot sense each other, B receives both
+        assert!(medium.receive(a).iter().all(|s| s.norm() == 0.0));
+        assert!(medium.receive(c).iter().all(|s| s.norm() == 0.0));
+        let at_b = medium.receive(b);
+        let single = medium.path_gain_db(a, b);
+        assert!((power_db(&at_b[100..900]) - (single + 3.01)).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_noise_floor() {
+        let mut medium = VirtualRfMedium::new(MediumConfig::default());
+        let rx = medium.add_radio(RadioConfig::new("rx", 915e6, 1e6).with_noise_figure(5.0));
+        medium.advance(0.01);
+        let samples = medium.receive(rx);
+        assert!((power_db(&samples) - medium.noise_floor_dbm(rx)).abs() < 0.1);
+        assert!((medium.noise_floor_dbm(rx) - (-109.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_scheduler_driven_runs_are_reproducible() {
+        let run = || {
+            let config = MediumConfig {
+                seed: 42,
+                default_channel: Some(ChannelConfig {
+                    model: ChannelModel::Rayleigh,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            let mut medium = VirtualRfMedium::new(config);
+            let tx = medium.add_radio(RadioConfig::new("tx", 433e6, 50e3).with_tx_power(10.0));
+            let rx = medium.add_radio(RadioConfig::new("rx", 433e6, 50e3).with_position(500.0, 0.0, 0.0));
+            medium.transmit(tx, &tone(1e3, 50e3, 2000));
+
+            let medium = Arc::new(Mutex::new(medium));
+            let mut scheduler = TickScheduler::with_sample_rate(50e3, 500);
+            scheduler.subscribe(medium.clone());
+            scheduler.step(6);
+
+            let mut medium = medium.lock().unwrap();
+            assert!((medium.now() - 0.06).abs() < 1e-12);
+            medium.receive(rx)
+        };
+        let first = run();
+        assert_eq!(first.len(), 3000);
+        assert_eq!(first, run());
+    }
+}
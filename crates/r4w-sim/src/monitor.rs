@@ -0,0 +1,296 @@
+//! Receive Path Metrics
+//!
+//! [`StreamMonitor`] records what a receive path does into a shared
+//! [`Metrics`] instance: samples and signal level per block, processing
+//! latency, buffer levels, overruns (through [`RtStats`]) and decoded or
+//! failed packets. Serve the same `Arc<Metrics>` with
+//! [`r4w_core::observe::MetricsServer`] to scrape a running receiver.
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use r4w_core::observe::Metrics;
+//! use r4w_core::types::IQSample;
+//! use r4w_sim::monitor::StreamMonitor;
+//!
+//! let metrics = Arc::new(Metrics::new());
+//! metrics.set_device("file");
+//! let monitor = StreamMonitor::new(metrics.clone());
+//!
+//! monitor.record_block(&[IQSample::new(0.5, 0.0); 1024], Duration::from_micros(40), 0);
+//! assert_eq!(metrics.snapshot().rx_samples, 1024);
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use r4w_core::observe::Metrics;
+use r4w_core::rt::RtStats;
+use r4w_core::types::IQSample;
+
+use crate::device::SdrResult;
+use crate::hal::{IqSource, StreamStatus};
+use crate::scoring::{score, Decode, PacketTruth, ScoreReport};
+
+/// Records a receive path into shared metrics
+#[derive(Debug)]
+pub struct StreamMonitor {
+    metrics: Arc<Metrics>,
+    stats: RtStats,
+    /// Stream overflow and underflow totals already recorded
+    overflows_seen: AtomicU64,
+    underflows_seen: AtomicU64,
+    /// Overruns and underruns of `stats` already added to the metrics
+    overruns_recorded: AtomicU64,
+    underruns_recorded: AtomicU64,
+}
+
+impl StreamMonitor {
+    /// Monitor into `metrics`.
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        Self {
+            metrics,
+            stats: RtStats::new(),
+            overflows_seen: AtomicU64::new(0),
+            underflows_seen: AtomicU64::new(0),
+            overruns_recorded: AtomicU64::new(0),
+            underruns_recorded: AtomicU64::new(0),
+        }
+    }
+
+    /// The shared metrics
+    pub fn metrics(&self) -> &Arc<Metrics> {
+        &self.metrics
+    }
+
+    /// Real-time statistics of the monitored path
+    pub fn stats(&self) -> &RtStats {
+        &self.stats
+    }
+
+    /// Record a received block, the time spent on it and the samples still buffered.
+    pub fn record_block(&self, samples: &[IQSample], elapsed: Duration, buffer_level: usize) {
+        self.metrics.record_rx(samples);
+        self.metrics.rx_buffer_level.set(buffer_level as i64);
+        self.metrics.processing_latency_us.observe(elapsed.as_secs_f64() * 1e6);
+        self.stats.record_processing_time_ns(elapsed.as_nanos() as u64);
+        self.stats.record_samples(samples.len() as u64);
+        self.stats.touch();
+    }
+
+    /// Record a device stream's overflow and underflow totals.
+    pub fn record_stream_status(&self, status: &StreamStatus) {
+        let new_overflows = status.overflow_count.saturating_sub(self.overflows_seen.swap(status.overflow_count, Ordering::Relaxed));
+        let new_underflows = status.underflow_count.saturating_sub(self.underflows_seen.swap(status.underflow_count, Ordering::Relaxed));
+        (0..new_overflows).for_each(|_| self.stats.record_overrun());
+        (0..new_underflows).for_each(|_| self.stats.record_underrun());
+        self.metrics.rx_buffer_level.set(status.buffer_level as i64);
+        self.sync_rt_stats();
+    }
+
+    /// Add overruns and underruns not yet recorded to the shared counters.
+    ///
+    /// Only the difference is added, so other monitors and direct
+    /// increments on the same metrics keep counting.
+    pub fn sync_rt_stats(&self) {
+        let snapshot = self.stats.snapshot();
+        let new_overruns = snapshot.overruns.saturating_sub(self.overruns_recorded.swap(snapshot.overruns, Ordering::Relaxed));
+        let new_underruns = snapshot.underruns.saturating_sub(self.underruns_recorded.swap(snapshot.underruns, Ordering::Relaxed));
+        self.metrics.rx_overflows.inc_by(new_overruns);
+        self.metrics.tx_underflows.inc_by(new_underruns);
+    }
+
+    /// Read a source to the end in blocks, recording each one.
+    pub fn read_source(&self, source: &mut dyn IqSource, block_len: usize) -> SdrResult<Vec<IQSample>> {
+        let total = source.total_samples() as usize;
+        let mut samples = Vec::with_capacity(total);
+        let mut buffer = vec![IQSample::new(0.0, 0.0); block_len.max(1)];
+        loop {
+            let start = Instant::now();
+            let n = match source.read_samples(&mut buffer) {
+                Ok(n) => n,
+                Err(e) => {
+                    self.metrics.device_errors.inc();
+                    return Err(e);
+                }
+            };
+            if n == 0 {
+                break;
+            }
+            samples.extend_from_slice(&buffer[..n]);
+            self.record_block(&buffer[..n], start.elapsed(), total.saturating_sub(samples.len()));
+        }
+        self.sync_rt_stats();
+        Ok(samples)
+    }
+
+    /// Record packet decodes from `samples`, scored against ground truth when there is any.
+    ///
+    /// Without truth every decode counts as received and decoded. With
+    /// truth, detected packets with bit errors count as failed. The SNR
+    /// gauge is the power inside the decoded bursts against the power
+    /// outside them.
+    pub fn record_decodes(
+        &self,
+        samples: &[IQSample],
+        decodes: &[Decode],
+        truth: &[PacketTruth],
+        sample_rate: f64,
+    ) -> Option<ScoreReport> {
+        if let Some(snr_db) = burst_snr_db(samples, decodes) {
+            self.metrics.record_snr(snr_db);
+        }
+
+        if truth.is_empty() {
+            self.metrics.packets_rx.inc_by(decodes.len() as u64);
+            self.metrics.packets_decoded.inc_by(decodes.len() as u64);
+            return None;
+        }
+        let report = score(truth, decodes, sample_rate);
+        self.metrics.packets_rx.inc_by((report.detected + report.false_alarms) as u64);
+        self.metrics.packets_decoded.inc_by(report.correct as u64);
+        self.metrics
+            .packets_failed
+            .inc_by((report.detected - report.correct + report.false_alarms) as u64);
+        Some(report)
+    }
+}
+
+/// SNR from mean power inside and outside the decoded bursts.
+fn burst_snr_db(samples: &[IQSample], decodes: &[Decode]) -> Option<f64> {
+    let mut inside = vec![false; samples.len()];
+    for d in decodes {
+        let start = (d.sample_start as usize).min(samples.len());
+        let end = ((d.sample_start + d.sample_count) as usize).min(samples.len());
+        inside[start..end].iter_mut().for_each(|x| *x = true);
+    }
+    let (mut p_in, mut n_in, mut p_out, mut n_out) = (0.0, 0usize, 0.0, 0usize);
+    for (s, &is_in) in samples.iter().zip(&inside) {
+        if is_in {
+            p_in += s.norm_sqr();
+            n_in += 1;
+        } else {
+            p_out += s.norm_sqr();
+            n_out += 1;
+        }
+    }
+    if n_in == 0 || n_out == 0 || p_out <= 0.0 {
+        return None;
+    }
+    let (p_in, p_out) = (p_in / n_in as f64, p_out / n_out as f64);
+    Some(10.0 * ((p_in - p_out).max(p_out * 1e-3) / p_out).log10())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hal::sigmf::{SigMfReader, SigMfWriter};
+    use crate::scoring::{BurstDecoder, PacketScenario};
+    use r4w_core::observe::{parse_prometheus, scrape, MetricsServer};
+    use r4w_core::waveform::WaveformFactory;
+    use std::sync::atomic::AtomicBool;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_stream_status_overruns() {
+        let metrics = Arc::new(Metrics::new());
+        let monitor = StreamMonitor::new(metrics.clone());
+        let mut status = StreamStatus {
+            overflow_count: 3,
+            buffer_level: 512,
+            ..Default::default()
+        };
+        monitor.record_stream_status(&status);
+        status.overflow_count = 5;
+        monitor.record_stream_status(&status);
+        assert_eq!(monitor.stats().overruns(), 5);
+        assert_eq!(metrics.snapshot().rx_overflows, 5);
+        assert_eq!(metrics.snapshot().rx_buffer_level, 512);
+
+        // A second receiver and direct increments add to the same counter
+        let other = StreamMonitor::new(metrics.clone());
+        metrics.rx_overflows.inc();
+        other.record_stream_status(&StreamStatus {
+            overflow_count: 2,
+            ..Default::default()
+        });
+        monitor.record_stream_status(&status);
+        assert_eq!(metrics.snapshot().rx_overflows, 8);
+    }
+
+    /// Decode a SigMF recording block by block while another thread
+    /// scrapes the endpoint, then check the final counters.
+    #[test]
+    fn test_decode_sigmf_and_scrape() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().join("stress");
+        let scenario = PacketScenario::new("QPSK", 48_000.0)
+            .with_packets(120)
+            .with_payload_len(24)
+            .with_snr_db(Some(15.0))
+            .with_seed(11);
+        let (samples, truth) = scenario.generate().unwrap();
+        let mut writer = SigMfWriter::create(&base, 48_000.0, 915e6).unwrap();
+        writer.write_samples(&samples).unwrap();
+        for packet in &truth {
+            packet.annotate(writer.metadata_mut());
+        }
+        writer.close().unwrap();
+
+        let metrics = Arc::new(Metrics::new().with_label("device", "file"));
+        metrics.set_waveform("QPSK");
+        let server = MetricsServer::spawn("127.0.0.1:0", metrics.clone()).unwrap();
+        let addr = server.local_addr();
+
+        let done = Arc::new(AtomicBool::new(false));
+        let scraper = {
+            let done = done.clone();
+            std::thread::spawn(move || {
+                let mut last = 0.0;
+                let mut scrapes = 0;
+                while !done.load(Ordering::Relaxed) {
+                    let body = scrape(addr).unwrap();
+                    let rx = parse_prometheus(&body)
+                        .into_iter()
+                        .find(|(series, _)| series.starts_with("r4w_rx_samples_total"))
+                        .map(|(_, v)| v)
+                        .unwrap();
+                    assert!(rx >= last, "counter went backwards");
+                    last = rx;
+                    scrapes += 1;
+                }
+                scrapes
+            })
+        };
+
+        let monitor = StreamMonitor::new(metrics.clone());
+        let mut reader = SigMfReader::open(&base).unwrap();
+        let truth = PacketTruth::from_meta(reader.metadata());
+        let received = monitor.read_source(&mut reader, 256).unwrap();
+        let decoder = BurstDecoder::new(WaveformFactory::create("QPSK", 48_000.0).unwrap());
+        let decodes = decoder.decode(&received);
+        let report = monitor.record_decodes(&received, &decodes, &truth, 48_000.0).unwrap();
+        done.store(true, Ordering::Relaxed);
+        assert!(scraper.join().unwrap() > 0);
+
+        let values = parse_prometheus(&scrape(addr).unwrap());
+        let value = |name: &str| {
+            let series = format!(r#"{}{{device="file",waveform="QPSK"}}"#, name);
+            values.iter().find(|(s, _)| *s == series).map(|(_, v)| *v).unwrap()
+        };
+        assert_eq!(report.correct, 120);
+        assert_eq!(value("r4w_rx_samples_total"), samples.len() as f64);
+        assert_eq!(value("r4w_packets_rx_total"), 120.0);
+        assert_eq!(value("r4w_packets_decoded_total"), 120.0);
+        assert_eq!(value("r4w_packets_failed_total"), 0.0);
+        assert_eq!(value("r4w_rx_overflows_total"), 0.0);
+        assert_eq!(value("r4w_rx_buffer_level"), 0.0);
+        assert_eq!(
+            value("r4w_processing_latency_us_count"),
+            samples.len().div_ceil(256) as f64
+        );
+        let snr = value("r4w_snr_db");
+        assert!((snr - 15.0).abs() < 3.0, "SNR estimate {}", snr);
+    }
+}
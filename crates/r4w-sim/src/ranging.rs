@@ -0,0 +1,352 @@
+//! UWB Ranging Validation
+//!
+//! Runs the UWB first-path detector and two-way ranging exchanges from
+//! [`r4w_core::waveform::uwb`] through the channel models in
+//! [`crate::channel`] and reports ranging error distributions.
+//!
+//! Each trial transmits a preamble of identical pulse symbols, delays it by
+//! the (fractional-sample) time of flight, applies the channel and
+//! estimates the first-path arrival after coherent accumulation.
+//!
+//! ```rust
+//! use r4w_core::waveform::uwb::UwbIr;
+//! use r4w_sim::channel::{ChannelConfig, TdlProfile};
+//! use r4w_sim::ranging::UwbRangingSim;
+//!
+//! let sim = UwbRangingSim::new(UwbIr::ieee_802_15_4a(4e9), 1e-6, 8);
+//! let stats = sim.evaluate_toa(15.0, &ChannelConfig::tdl(10.0, TdlProfile::Epa, 4e9), 20);
+//! println!("{}", stats);
+//! ```
+
+use crate::channel::{Channel, ChannelConfig};
+use r4w_core::types::IQSample;
+use r4w_core::waveform::uwb::{
+    DeviceClock, DsTwrExchange, FirstPathDetector, SsTwrExchange, UwbIr, SPEED_OF_LIGHT,
+};
+use r4w_core::waveform::Waveform;
+use std::fmt;
+
+/// Ranging error distribution in meters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangingErrorStats {
+    /// Trials attempted
+    pub trials: usize,
+    /// Trials where a first path was detected
+    pub detected: usize,
+    /// Mean error (bias)
+    pub mean_m: f64,
+    /// Standard deviation
+    pub std_m: f64,
+    /// Root-mean-square error
+    pub rmse_m: f64,
+    /// Median absolute error
+    pub median_abs_m: f64,
+    /// 90th percentile absolute error
+    pub p90_abs_m: f64,
+    /// 95th percentile absolute error
+    pub p95_abs_m: f64,
+    /// Largest absolute error
+    pub max_abs_m: f64,
+}
+
+impl RangingErrorStats {
+    /// Statistics of `errors` (one per detected trial) out of `trials`
+    pub fn from_errors(errors: &[f64], trials: usize) -> Self {
+        let n = errors.len();
+        if n == 0 {
+            return Self {
+                trials,
+                detected: 0,
+                mean_m: f64::NAN,
+                std_m: f64::NAN,
+                rmse_m: f64::NAN,
+                median_abs_m: f64::NAN,
+                p90_abs_m: f64::NAN,
+                p95_abs_m: f64::NAN,
+                max_abs_m: f64::NAN,
+            };
+        }
+
+        let mean = errors.iter().sum::<f64>() / n as f64;
+        let variance = errors.iter().map(|e| (e - mean) * (e - mean)).sum::<f64>() / n as f64;
+        let rmse = (errors.iter().map(|e| e * e).sum::<f64>() / n as f64).sqrt();
+
+        let mut abs: Vec<f64> = errors.iter().map(|e| e.abs()).collect();
+        abs.sort_by(f64::total_cmp);
+        let percentile = |p: f64| abs[((p * n as f64).ceil() as usize).clamp(1, n) - 1];
+
+        Self {
+            trials,
+            detected: n,
+            mean_m: mean,
+            std_m: variance.sqrt(),
+            rmse_m: rmse,
+            median_abs_m: percentile(0.5),
+            p90_abs_m: percentile(0.9),
+            p95_abs_m: percentile(0.95),
+            max_abs_m: abs[n - 1],
+        }
+    }
+
+    /// Fraction of trials with a detection
+    pub fn detection_rate(&self) -> f64 {
+        if self.trials == 0 {
+            0.0
+        } else {
+            self.detected as f64 / self.trials as f64
+        }
+    }
+}
+
+impl fmt::Display for RangingErrorStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} detected, bias {:+.3} m, std {:.3} m, RMSE {:.3} m, |e| p50 {:.3} / p90 {:.3} / p95 {:.3} / max {:.3} m",
+            self.detected,
+            self.trials,
+            self.mean_m,
+            self.std_m,
+            self.rmse_m,
+            self.median_abs_m,
+            self.p90_abs_m,
+            self.p95_abs_m,
+            self.max_abs_m
+        )
+    }
+}
+
+/// UWB ranging simulation over a channel model
+#[derive(Debug, Clone)]
+pub struct UwbRangingSim {
+    uwb: UwbIr,
+    detector: FirstPathDetector,
+    /// Samples per preamble symbol
+    symbol_period: usize,
+    /// Preamble symbols accumulated per arrival
+    preamble_symbols: usize,
+    /// Pulse position within a symbol before the time of flight
+    guard: usize,
+}
+
+impl UwbRangingSim {
+    /// Simulation with preamble symbols of `symbol_period_s` (longer than
+    /// the channel delay spread) accumulated `preamble_symbols` times
+    pub fn new(uwb: UwbIr, symbol_period_s: f64, preamble_symbols: usize) -> Self {
+        let sample_rate = uwb.common_params().sample_rate;
+        let symbol_period = ((symbol_period_s * sample_rate) as usize).max(4 * uwb.pulse_template().len());
+        Self {
+            detector: uwb.first_path_detector(),
+            guard: 2 * uwb.pulse_template().len(),
+            uwb,
+            symbol_period,
+            preamble_symbols: preamble_symbols.max(1),
+        }
+    }
+
+    /// Sample rate in Hz
+    pub fn sample_rate(&self) -> f64 {
+        self.uwb.common_params().sample_rate
+    }
+
+    /// Longest distance whose arrival still fits in one symbol period
+    pub fn max_distance_m(&self) -> f64 {
+        let spare = self.symbol_period.saturating_sub(self.guard + 2 * self.uwb.pulse_template().len());
+        spare as f64 / self.sample_rate() * SPEED_OF_LIGHT
+    }
+
+    /// One reception: estimated time of flight in seconds
+    fn measure_tof(&self, distance_m: f64, channel: &mut Channel) -> Option<f64> {
+        let sample_rate = self.sample_rate();
+        let delay = self.guard as f64 + distance_m / SPEED_OF_LIGHT * sample_rate;
+        let symbol = self.uwb.pulse_at(self.symbol_period, delay);
+
+        let mut tx: Vec<IQSample> = Vec::with_capacity(self.symbol_period * self.preamble_symbols);
+        for _ in 0..self.preamble_symbols {
+            tx.extend_from_slice(&symbol);
+        }
+
+        channel.reset();
+        let rx = channel.apply(&tx);
+        let toa = self
+            .detector
+            .detect_accumulated(&rx, self.symbol_period, self.preamble_symbols)?;
+        Some((toa.first_path - self.guard as f64) / sample_rate)
+    }
+
+    fn channel(&self, config: &ChannelConfig) -> Channel {
+        Channel::new(ChannelConfig {
+            sample_rate: self.sample_rate(),
+            ..config.clone()
+        })
+    }
+
+    /// Time-of-arrival ranging errors in meters (one per detected trial).
+    ///
+    /// `config.sample_rate` is replaced with the UWB sample rate.
+    pub fn toa_errors(&self, distance_m: f64, config: &ChannelConfig, trials: usize) -> Vec<f64> {
+        let mut channel = self.channel(config);
+        (0..trials)
+            .filter_map(|_| self.measure_tof(distance_m, &mut channel))
+            .map(|tof| tof * SPEED_OF_LIGHT - distance_m)
+            .collect()
+    }
+
+    /// Time-of-arrival error distribution
+    pub fn evaluate_toa(&self, distance_m: f64, config: &ChannelConfig, trials: usize) -> RangingErrorStats {
+        RangingErrorStats::from_errors(&self.toa_errors(distance_m, config, trials), trials)
+    }
+
+    /// Timestamp error generator for TWR: each call simulates one
+    /// reception and returns its ToA error in seconds, flagging `missed`
+    /// if no first path was detected
+    fn rx_errors<'a>(
+        &'a self,
+        distance_m: f64,
+        channel: &'a mut Channel,
+        missed: &'a mut bool,
+    ) -> impl FnMut() -> f64 + 'a {
+        let true_tof = distance_m / SPEED_OF_LIGHT;
+        move || match self.measure_tof(distance_m, channel) {
+            Some(tof) => tof - true_tof,
+            None => {
+                *missed = true;
+                0.0
+            }
+        }
+    }
+
+    /// SS-TWR error distribution with a 1 ms reply delay; with
+    /// `compensate` the true relative clock drift is applied
+    pub fn evaluate_ss_twr(
+        &self,
+        distance_m: f64,
+        config: &ChannelConfig,
+        initiator: DeviceClock,
+        responder: DeviceClock,
+        compensate: bool,
+        trials: usize,
+    ) -> RangingErrorStats {
+        let relative_ppm = if compensate {
+            ((1.0 + responder.drift_ppm * 1e-6) / (1.0 + initiator.drift_ppm * 1e-6) - 1.0) * 1e6
+        } else {
+            0.0
+        };
+        let mut channel = self.channel(config);
+        let errors: Vec<f64> = (0..trials)
+            .filter_map(|_| {
+                let mut missed = false;
+                let exchange = SsTwrExchange::simulate(
+                    initiator,
+                    responder,
+                    distance_m,
+                    1e-3,
+                    self.rx_errors(distance_m, &mut channel, &mut missed),
+                );
+                (!missed).then(|| exchange.distance_m(relative_ppm) - distance_m)
+            })
+            .collect();
+        RangingErrorStats::from_errors(&errors, trials)
+    }
+
+    /// Asymmetric DS-TWR error distribution (500 µs / 800 µs reply delays)
+    pub fn evaluate_ds_twr(
+        &self,
+        distance_m: f64,
+        config: &ChannelConfig,
+        initiator: DeviceClock,
+        responder: DeviceClock,
+        trials: usize,
+    ) -> RangingErrorStats {
+        let mut channel = self.channel(config);
+        let errors: Vec<f64> = (0..trials)
+            .filter_map(|_| {
+                let mut missed = false;
+                let exchange = DsTwrExchange::simulate(
+                    initiator,
+                    responder,
+                    distance_m,
+                    500e-6,
+                    800e-6,
+                    self.rx_errors(distance_m, &mut channel, &mut missed),
+                );
+                (!missed).then(|| exchange.distance_m() - distance_m)
+            })
+            .collect();
+        RangingErrorStats::from_errors(&errors, trials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::{ChannelModel, TdlProfile};
+
+    fn sim() -> UwbRangingSim {
+        UwbRangingSim::new(UwbIr::ieee_802_15_4a(4e9), 1e-6, 8)
+    }
+
+    #[test]
+    fn test_ideal_channel_subsample_accuracy() {
+        let config = ChannelConfig {
+            model: ChannelModel::Ideal,
+            ..Default::default()
+        };
+        for distance in [3.0, 17.31, 42.07] {
+            let stats = sim().evaluate_toa(distance, &config, 1);
+            // 1 sample at 4 GHz is 7.5 cm
+            assert!(stats.max_abs_m < 0.02, "{} m: {}", distance, stats);
+        }
+    }
+
+    #[test]
+    fn test_awgn_error_distribution() {
+        let stats = sim().evaluate_toa(20.0, &ChannelConfig::with_snr(0.0), 50);
+        assert_eq!(stats.detected, 50);
+        assert!(stats.rmse_m < 0.1, "{}", stats);
+        assert!(stats.p95_abs_m >= stats.median_abs_m);
+    }
+
+    #[test]
+    fn test_two_ray_first_path() {
+        // Reflection 30 ns later and nearly as strong as the direct path
+        let config = ChannelConfig::multipath(20.0, 120, 0.9);
+        let stats = sim().evaluate_toa(10.0, &config, 20);
+        assert!(stats.mean_m.abs() < 0.05, "{}", stats);
+    }
+
+    #[test]
+    fn test_tdl_epa_error_distribution() {
+        let config = ChannelConfig::tdl(15.0, TdlProfile::Epa, 4e9);
+        let stats = sim().evaluate_toa(25.0, &config, 30);
+        assert_eq!(stats.detected, 30);
+        assert!(stats.p90_abs_m < 0.1, "{}", stats);
+    }
+
+    #[test]
+    fn test_twr_over_channel() {
+        let sim = sim();
+        let config = ChannelConfig::tdl(15.0, TdlProfile::Epa, 4e9);
+        let initiator = DeviceClock::with_drift(-15.0);
+        let responder = DeviceClock::with_drift(15.0);
+
+        let ds = sim.evaluate_ds_twr(30.0, &config, initiator, responder, 10);
+        assert!(ds.rmse_m < 0.1, "DS-TWR: {}", ds);
+
+        // 30 ppm × 1 ms / 2 ≈ 4.5 m of drift bias unless compensated
+        let ss = sim.evaluate_ss_twr(30.0, &config, initiator, responder, false, 10);
+        assert!(ss.mean_m.abs() > 3.0, "SS-TWR: {}", ss);
+        let ss = sim.evaluate_ss_twr(30.0, &config, initiator, responder, true, 10);
+        assert!(ss.rmse_m < 0.1, "SS-TWR compensated: {}", ss);
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = RangingErrorStats::from_errors(&[0.1, -0.1, 0.3, -0.2], 5);
+        assert_eq!(stats.detected, 4);
+        assert!((stats.detection_rate() - 0.8).abs() < 1e-12);
+        assert!((stats.mean_m - 0.025).abs() < 1e-12);
+        assert!((stats.max_abs_m - 0.3).abs() < 1e-12);
+        assert!(RangingErrorStats::from_errors(&[], 3).rmse_m.is_nan());
+    }
+}
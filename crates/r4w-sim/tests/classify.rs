@@ -0,0 +1,98 @@
+//! Blind modulation classification of the factory waveforms after a
+//! simulated channel.
+
+use r4w_core::analysis::{ModulationClassifier, ModulationFamily::*};
+use r4w_core::waveform::WaveformFactory;
+use r4w_sim::{Channel, ChannelConfig};
+
+/// Factory waveforms whose `Waveform::modulate` is a stub: SINCGARS
+/// needs mutable hopping state and modulates through `modulate_voice`/`modulate_data`
+const NO_STANDALONE_MODULATOR: &[&str] = &["SINCGARS"];
+
+/// Every factory waveform, through an AWGN + CFO channel at 25 dB, is
+/// recognised by the blind classifier (up to families that the
+/// implementation genuinely produces, e.g. PPM and UWB are on-off pulses)
+#[test]
+fn test_classifier_identifies_factory_waveforms() {
+    for name in WaveformFactory::list() {
+        let rate = match name {
+            "FM-Broadcast" | "LoRa" | "LoRa-SF7" | "LoRa-SF12" => 125e3,
+            "ADS-B" => 4e6,
+            "Zigbee" => 8e6,
+            "UWB" => 2e9,
+            "FMCW" => 400e6,
+            _ => 48e3,
+        };
+        let waveform = WaveformFactory::create(name, rate).unwrap();
+        if NO_STANDALONE_MODULATOR.contains(&name) {
+            assert!(
+                waveform.modulate(&[0x5a; 64]).is_empty(),
+                "{} now modulates; give it an expected class",
+                name
+            );
+            continue;
+        }
+
+        let expected: &[_] = match name {
+            "CW" => &[Tone],
+            "OOK" | "ASK" | "PPM" | "ADS-B" | "UWB" => &[Ook, Ask(2)],
+            "AM-Broadcast" | "HAVEQUICK" => &[Am],
+            "FM-Broadcast" | "NBFM" => &[Fm],
+            "4-ASK" => &[Ask(4)],
+            "BFSK" => &[Fsk(2)],
+            "4-FSK" | "P25" | "DMR" => &[Fsk(4)],
+            "ALE" | "3G-ALE" => &[Fsk(8)],
+            "BPSK" | "DSSS" | "Link-16" => &[Psk(2)],
+            "QPSK" | "DSSS-QPSK" | "Zigbee" | "STANAG-4285" => &[Psk(4)],
+            "8-PSK" | "MIL-STD-188-110" => &[Psk(8)],
+            // π/4-DQPSK alternates between two QPSK sets
+            "TETRA" => &[Psk(4), Psk(8)],
+            "16-QAM" => &[Qam(16)],
+            "64-QAM" => &[Qam(64)],
+            "256-QAM" => &[Qam(256)],
+            "OFDM" => &[Ofdm { fft_size: 64, cp_len: 16 }],
+            "FHSS" => &[Fhss],
+            "LoRa" | "LoRa-SF7" | "LoRa-SF12" | "FMCW" => &[Css],
+            other => panic!("{} has no expected class; add it to this table", other),
+        };
+
+        let mut seed = 1u32;
+        let mut audio = 0.0;
+        let mut samples = Vec::new();
+        while samples.len() < 32768 {
+            let data: Vec<u8> = (0..256)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                    let byte = (seed >> 24) as u8;
+                    if waveform.samples_per_symbol() == 1 {
+                        // Analogue waveforms take audio: low-pass filtered noise
+                        audio = 0.95 * audio + 0.05 * (byte as f64 - 128.0) * 2.0;
+                        audio.clamp(-127.0, 127.0) as i8 as u8
+                    } else {
+                        byte
+                    }
+                })
+                .collect();
+            let modulated = waveform.modulate(&data);
+            if modulated.is_empty() {
+                break;
+            }
+            samples.extend(modulated);
+        }
+        assert!(!samples.is_empty(), "{} produced no samples", name);
+
+        let config = ChannelConfig {
+            sample_rate: rate,
+            ..ChannelConfig::with_cfo(25.0, 150.0)
+        };
+        let received = Channel::with_seed(config, 7).apply(&samples);
+        let result = ModulationClassifier::new(rate).classify(&received);
+        assert!(
+            expected.contains(&result.best().family),
+            "{}: classified as {}\n{}",
+            name,
+            result.best().family,
+            result.to_text()
+        );
+    }
+}